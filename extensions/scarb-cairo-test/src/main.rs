@@ -6,7 +6,7 @@ use cairo_lang_test_runner::{CompiledTestRunner, TestRunConfig};
 use clap::Parser;
 
 use scarb_metadata::{Metadata, MetadataCommand, PackageMetadata, ScarbCommand, TargetMetadata};
-use scarb_ui::args::PackagesFilter;
+use scarb_ui::args::{FeaturesSpec, PackagesFilter};
 
 /// Execute all unit tests of a local package.
 #[derive(Parser, Clone, Debug)]
@@ -15,6 +15,9 @@ struct Args {
     #[command(flatten)]
     packages_filter: PackagesFilter,
 
+    #[command(flatten)]
+    features: FeaturesSpec,
+
     /// Run only tests whose name contain FILTER.
     #[arg(short, long, default_value = "")]
     filter: String,
@@ -31,7 +34,10 @@ struct Args {
 fn main() -> Result<()> {
     let args: Args = Args::parse();
 
-    let metadata = MetadataCommand::new().inherit_stderr().exec()?;
+    let metadata = MetadataCommand::new()
+        .envs(args.features.to_env())
+        .inherit_stderr()
+        .exec()?;
 
     check_scarb_version(&metadata);
 
@@ -41,6 +47,7 @@ fn main() -> Result<()> {
         .arg("build")
         .arg("--test")
         .env("SCARB_PACKAGES_FILTER", filter.to_env())
+        .envs(args.features.to_env())
         .run()?;
 
     let profile = env::var("SCARB_PROFILE").unwrap_or("dev".into());
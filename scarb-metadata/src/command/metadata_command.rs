@@ -304,6 +304,7 @@ mod tests {
                 manifest_path: Default::default(),
                 root: Default::default(),
                 members: Default::default(),
+                default_members: Default::default(),
                 extra: Default::default(),
             },
             packages: Default::default(),
@@ -187,6 +187,12 @@ pub struct WorkspaceMetadata {
     /// List of IDs of all packages that are members of this workspace.
     pub members: Vec<PackageId>,
 
+    /// List of IDs of members declared in the `default-members` field of the workspace manifest,
+    /// if present.
+    #[cfg_attr(feature = "builder", builder(default))]
+    #[serde(default)]
+    pub default_members: Option<Vec<PackageId>>,
+
     /// Additional data not captured by deserializer.
     #[cfg_attr(feature = "builder", builder(default))]
     #[serde(flatten)]
@@ -227,12 +233,36 @@ pub struct PackageMetadata {
     #[serde(flatten)]
     pub manifest_metadata: ManifestMetadata,
 
+    /// Information about the VCS checkout this package has been packaged from.
+    ///
+    /// Only available for packages downloaded from registries, which have been packaged
+    /// inside a Git repository.
+    #[cfg_attr(feature = "builder", builder(default))]
+    #[serde(default)]
+    pub vcs: Option<VcsMetadata>,
+
     /// Additional data not captured by deserializer.
     #[cfg_attr(feature = "builder", builder(default))]
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// Information about the VCS checkout a package has been packaged from.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "builder", derive(Builder))]
+#[cfg_attr(feature = "builder", builder(setter(into)))]
+#[non_exhaustive]
+pub struct VcsMetadata {
+    /// Git commit hash the package has been packaged from.
+    pub commit_hash: String,
+    /// Whether any of the packaged files had uncommitted changes.
+    pub dirty: bool,
+    /// Path of the package root relative to the repository root.
+    pub path_in_vcs: String,
+    /// URL of the source repository, if known.
+    pub repository: Option<String>,
+}
+
 /// Scarb package dependency specification.
 ///
 /// Only the `name` field is strictly sourced from `Scarb.toml`, the rest is processed by Scarb
@@ -388,6 +418,13 @@ pub struct ManifestMetadata {
     pub urls: Option<BTreeMap<String, String>>,
     /// Various unstructured metadata to be used by external tools.
     pub tool: Option<BTreeMap<String, serde_json::Value>>,
+    /// Contents of the `[package.metadata]` table, passed through verbatim.
+    ///
+    /// Scarb does not interpret this table in any way, external tools may store their settings
+    /// in it.
+    #[cfg_attr(feature = "builder", builder(default))]
+    #[serde(default)]
+    pub metadata: Option<BTreeMap<String, serde_json::Value>>,
 }
 
 /// Scarb's version.
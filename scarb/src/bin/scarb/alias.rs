@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use anyhow::{bail, Result};
+use smol_str::SmolStr;
+
+use scarb::core::AliasDefinition;
+
+/// Expand a user-defined alias invoked in place of an external subcommand.
+///
+/// `argv` is the full command line, ending with `external` arguments, the first of which is
+/// the subcommand name.
+/// Returns the command line to parse instead, or `None` if the name is not an alias.
+/// Aliases may expand to other aliases, while built-in commands always take precedence.
+pub fn expand_alias(
+    argv: &[OsString],
+    external: &[OsString],
+    aliases: &BTreeMap<SmolStr, AliasDefinition>,
+    builtins: &BTreeMap<String, Option<String>>,
+) -> Result<Option<Vec<OsString>>> {
+    let Some(name) = external.first().and_then(|name| name.to_str()) else {
+        return Ok(None);
+    };
+    if !aliases.contains_key(name) {
+        return Ok(None);
+    }
+
+    let mut expansion = vec![name.to_string()];
+    let mut expanded = Vec::new();
+    while let Some(definition) = aliases
+        .get(expansion[0].as_str())
+        .filter(|_| !builtins.contains_key(&expansion[0]))
+    {
+        let head = expansion[0].clone();
+        if expanded.contains(&head) {
+            bail!("alias `{name}` expands recursively through `{head}`");
+        }
+        let args = definition.args();
+        if args.is_empty() {
+            bail!("alias `{head}` has empty definition");
+        }
+        expansion.splice(0..1, args);
+        expanded.push(head);
+    }
+
+    let prefix = &argv[..argv.len() - external.len()];
+    Ok(Some(
+        prefix
+            .iter()
+            .cloned()
+            .chain(expansion.into_iter().map(OsString::from))
+            .chain(external[1..].iter().cloned())
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ffi::OsString;
+
+    use smol_str::SmolStr;
+    use test_case::test_case;
+
+    use scarb::core::AliasDefinition;
+
+    use super::expand_alias;
+
+    fn aliases() -> BTreeMap<SmolStr, AliasDefinition> {
+        BTreeMap::from([
+            ("b".into(), AliasDefinition::Line("build --release".into())),
+            (
+                "br".into(),
+                AliasDefinition::Line("b --target-names x".into()),
+            ),
+            (
+                "ct".into(),
+                AliasDefinition::Args(vec!["test".into(), "--filter".into(), "a b".into()]),
+            ),
+            ("build".into(), AliasDefinition::Line("fmt".into())),
+            ("loop".into(), AliasDefinition::Line("again --x".into())),
+            ("again".into(), AliasDefinition::Line("loop".into())),
+            ("empty".into(), AliasDefinition::Args(vec![])),
+        ])
+    }
+
+    fn expand(argv: &str) -> anyhow::Result<Option<String>> {
+        let argv = argv.split(' ').map(OsString::from).collect::<Vec<_>>();
+        // Like clap, take everything after global arguments as external subcommand arguments.
+        let position = argv
+            .iter()
+            .skip(1)
+            .position(|arg| !arg.to_str().unwrap().starts_with('-'))
+            .unwrap()
+            + 1;
+        let builtins = BTreeMap::from([
+            ("build".to_string(), None),
+            ("test".to_string(), None),
+            ("fmt".to_string(), None),
+        ]);
+        let expanded = expand_alias(&argv, &argv[position..], &aliases(), &builtins)?;
+        Ok(expanded.map(|argv| {
+            argv.iter()
+                .map(|arg| arg.to_str().unwrap())
+                .collect::<Vec<_>>()
+                .join("|")
+        }))
+    }
+
+    #[test_case("scarb b", Some("scarb|build|--release"))]
+    #[test_case("scarb --offline b -v", Some("scarb|--offline|build|--release|-v"))]
+    #[test_case("scarb br", Some("scarb|build|--release|--target-names|x"))]
+    #[test_case("scarb ct extra", Some("scarb|test|--filter|a b|extra"))]
+    #[test_case("scarb hello", None)]
+    fn expansion(argv: &str, expected: Option<&str>) {
+        assert_eq!(expand(argv).unwrap().as_deref(), expected);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            expand("scarb loop").unwrap_err().to_string(),
+            "alias `loop` expands recursively through `loop`"
+        );
+        assert_eq!(
+            expand("scarb empty").unwrap_err().to_string(),
+            "alias `empty` has empty definition"
+        );
+    }
+}
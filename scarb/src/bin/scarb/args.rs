@@ -4,20 +4,25 @@
 
 use std::collections::BTreeMap;
 use std::ffi::OsString;
+use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use camino::Utf8PathBuf;
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use semver::Version;
 use smol_str::SmolStr;
 use tracing::level_filters::LevelFilter;
 use tracing_log::AsTrace;
 use url::Url;
 
 use scarb::compiler::Profile;
-use scarb::core::PackageName;
+use scarb::core::{PackageName, Severity};
 use scarb::manifest_editor::DepId;
+use scarb::ops::{
+    self, ConfigScope, PackageKind, Skeleton, TemplateSource, TestRunner, VersionControl,
+};
 use scarb::version;
-use scarb_ui::args::PackagesFilter;
+use scarb_ui::args::{FeaturesSpec, PackagesFilter};
 use scarb_ui::OutputFormat;
 
 /// The Cairo package manager.
@@ -40,6 +45,26 @@ pub struct ScarbArgs {
     #[arg(long, env = "SCARB_OFFLINE", hide_short_help = true)]
     pub offline: bool,
 
+    /// Assert that `Scarb.lock` will remain unchanged.
+    #[arg(long, env = "SCARB_LOCKED", hide_short_help = true)]
+    pub locked: bool,
+
+    /// Equivalent to specifying both `--locked` and `--offline`.
+    #[arg(long, env = "SCARB_FROZEN", hide_short_help = true)]
+    pub frozen: bool,
+
+    /// Continue when the `cairo-version` requirement of given packages (all, if none are given)
+    /// does not match the bundled Cairo compiler.
+    #[arg(
+        long,
+        value_name = "PACKAGES",
+        num_args = 0..,
+        require_equals = true,
+        value_delimiter = ',',
+        hide_short_help = true
+    )]
+    pub ignore_cairo_version: Option<Vec<PackageName>>,
+
     /// Directory for all cache data stored by Scarb.
     #[arg(
         long,
@@ -58,6 +83,15 @@ pub struct ScarbArgs {
     )]
     pub global_config_dir: Option<Utf8PathBuf>,
 
+    /// Directory for extensions installed with `scarb install`.
+    #[arg(
+        long,
+        env = "SCARB_INSTALL_DIR",
+        value_name = "DIRECTORY",
+        hide_short_help = true
+    )]
+    pub global_install_dir: Option<Utf8PathBuf>,
+
     /// Directory for all generated artifacts.
     #[arg(
         long,
@@ -67,6 +101,10 @@ pub struct ScarbArgs {
     )]
     pub target_dir: Option<Utf8PathBuf>,
 
+    /// Override a configuration value, given as a dotted key and a TOML value, like `http.retry=5`.
+    #[arg(long = "config", value_name = "KEY=VALUE", hide_short_help = true)]
+    pub config_overrides: Vec<String>,
+
     /// Specify the profile to use.
     #[command(flatten)]
     pub profile_spec: ProfileSpec,
@@ -88,6 +126,10 @@ impl ScarbArgs {
 
     /// Get [`ui::Verbosity`] out of these arguments.
     pub fn ui_verbosity(&self) -> scarb_ui::Verbosity {
+        // Standard output of `scarb rpc` is reserved for JSON-RPC responses.
+        if matches!(self.command, Command::Rpc) {
+            return scarb_ui::Verbosity::Quiet;
+        }
         let filter = self.verbose.log_level_filter().as_trace();
         if filter >= LevelFilter::WARN {
             scarb_ui::Verbosity::Verbose
@@ -119,6 +161,35 @@ pub enum CacheSubcommand {
     Path,
 }
 
+/// Configuration subcommand and its arguments.
+#[derive(Subcommand, Clone, Debug)]
+pub enum ConfigSubcommand {
+    /// Print the value of a configuration key, or of all keys nested in it.
+    Get(ConfigGetArgs),
+    /// Print all configuration values.
+    List(ConfigListArgs),
+    /// Set a configuration value in a configuration file.
+    #[command(after_help = "\
+        The value is parsed as TOML, and used as a string if it is not valid TOML. The project \
+        configuration file is modified, unless `--global` is passed.
+    ")]
+    Set(ConfigSetArgs),
+}
+
+/// Self-management subcommand and its arguments.
+#[derive(Subcommand, Clone, Debug)]
+pub enum SelfSubcommand {
+    /// Update Scarb to the newest release.
+    #[command(after_help = "\
+        The newest release is looked up in the release channel configured in the \
+        `[self-update]` table of Scarb config, stable releases by default. The checksum of the \
+        downloaded archive is always verified, and so is the signature of release checksums, if \
+        trusted keys are configured. Executables of other tools bundled with Scarb, which are \
+        installed next to it, are updated as well.
+    ")]
+    Update(SelfUpdateArgs),
+}
+
 /// Subcommand and its arguments.
 #[derive(Subcommand, Clone, Debug)]
 pub enum Command {
@@ -129,28 +200,76 @@ pub enum Command {
     /// Remove dependencies from a manifest file.
     #[command(alias = "rm")]
     Remove(RemoveArgs),
+    /// Check locked dependencies against a security advisory database.
+    #[command(after_help = "\
+        The command fails if any locked package is affected by a vulnerability of severity at \
+        least `--severity-threshold`, or, with `--deny-unmaintained`, if it is unmaintained. \
+        Other affected packages are reported, but do not fail the audit.
+    ")]
+    Audit(AuditArgs),
     /// Compile current project.
     Build(BuildArgs),
     /// Manipulate packages cache.
     #[clap(subcommand)]
     Cache(CacheSubcommand),
     /// Remove generated artifacts.
-    Clean,
-    /// List installed commands.
+    Clean(CleanArgs),
+    /// List built-in commands and installed external subcommands.
+    #[command(long_flag = "list")]
     Commands,
-    /// Fetch dependencies of packages from the network.
-    Fetch,
+    /// Read and modify configuration files.
+    #[command(after_help = "\
+        Without `--global` or `--local`, effective values merged from all configuration files \
+        and `--config` overrides are printed. Use `--show-origin` to find out which file each \
+        value comes from.
+    ")]
+    #[clap(subcommand)]
+    Config(ConfigSubcommand),
+    /// Download and extract all dependencies of the workspace into the cache, without building.
+    Fetch(FetchArgs),
     /// Format project files.
     Fmt(FmtArgs),
     /// Create a new Scarb package in existing directory.
     Init(InitArgs),
+    /// Install Scarb extensions provided by a package.
+    #[command(after_help = "\
+        Prebuilt `scarb-*` executables are taken from the `bin` directory of the package. If there \
+        are none, the package has to contain a Cargo project building them, which requires `cargo` \
+        to be installed. Executables are put in the `bin` subdirectory of the Scarb install \
+        directory, which is always searched for Scarb subcommands.
+    ")]
+    Install(InstallArgs),
+    /// Save an authentication token for a registry.
+    #[command(after_help = "\
+        The token is stored in the `credentials.toml` file in Scarb config directory, and is sent \
+        with all requests to the registry, including package uploads.
+    ")]
+    Login(LoginArgs),
+    /// Remove an authentication token of a registry.
+    Logout(LogoutArgs),
     /// Print path to current Scarb.toml file to standard output.
     ManifestPath,
+    /// Print the JSON Schema of Scarb.toml files to standard output.
+    #[command(hide = true)]
+    ManifestSchema,
     /// Output the resolved dependencies of a package, the concrete used versions including
     /// overrides, in machine-readable format.
     Metadata(MetadataArgs),
     /// Create a new Scarb package at <PATH>.
     New(NewArgs),
+    /// List dependencies for which newer versions are available in their registries.
+    #[command(after_help = "\
+        For each registry dependency, shows the version locked in the lockfile, the newest version \
+        matching version requirements of its dependants, and the newest version available in the \
+        registry. Dependencies which are up to date are not listed.
+    ")]
+    Outdated,
+    /// Manage the owners of a package in the registry.
+    #[command(after_help = "\
+        Owners of a package can publish new versions of it, yank them, and invite or remove \
+        other owners. Managing owners requires a token saved with `scarb login`.
+    ")]
+    Owner(OwnerArgs),
     /// Assemble the local package into a distributable tarball.
     #[command(after_help = "\
         This command will create distributable, compressed `.tar.zst` archives containing source \
@@ -164,8 +283,23 @@ pub enum Command {
         to a registry.
     ")]
     Publish(PublishArgs),
+    /// Serve workspace information to extensions over JSON-RPC on standard input and output.
+    #[command(after_help = "\
+        This command is meant to be spawned by Scarb extensions, which can then query metadata, \
+        compilation units and configuration of the workspace without resolving it on each query. \
+        Requests and responses are newline-delimited JSON-RPC 2.0 messages. Nothing else is \
+        printed to standard output.
+    ")]
+    Rpc,
     /// Run arbitrary package scripts.
     Run(ScriptsRunnerArgs),
+    /// Search packages in a registry.
+    Search(SearchArgs),
+    /// Manage the Scarb installation itself.
+    #[command(name = "self", subcommand)]
+    SelfCommand(SelfSubcommand),
+    /// Remove extensions installed with `scarb install`.
+    Uninstall(UninstallArgs),
     /// Execute all unit and integration tests of a local package.
     #[command(after_help = "\
         By default, this command delegates to `scarb cairo-test`. This behaviour can be changed by \
@@ -174,22 +308,181 @@ pub enum Command {
         Run `scarb test -- --help` for test program options.
     ")]
     Test(TestArgs),
+    /// Display a tree visualization of the resolved dependency graph.
+    #[command(after_help = "\
+        Packages whose dependencies are already displayed elsewhere in the tree are marked with \
+        `(*)`. Dependencies used only by targets of some kinds are annotated with these kinds. \
+        With `--invert`, the tree shows dependants of the given package instead of dependencies.
+    ")]
+    Tree(TreeArgs),
     /// Update dependencies.
-    Update,
+    #[command(after_help = "\
+        Without `--package`, all locked versions are discarded and dependencies are resolved \
+        anew. With it, only the given packages are updated, and versions of other packages \
+        stay as they are in the lockfile.
+    ")]
+    Update(UpdateArgs),
+    /// Raise version requirements of dependencies in manifest files.
+    #[command(after_help = "\
+        By default, version requirements are raised to the newest versions matching them, which \
+        only changes the lowest accepted versions. With `--incompatible`, they are raised to the \
+        newest versions available, possibly across semver-incompatible releases. Unlike \
+        `scarb update`, which only changes the lockfile, this command edits `Scarb.toml` files.
+    ")]
+    Upgrade(UpgradeArgs),
+    /// Vendor all registry dependencies locally.
+    #[command(after_help = "\
+        This command copies sources of all registry dependencies of the workspace into the \
+        vendor directory, and prints the configuration to put in `.scarb/config.toml` in order \
+        to use the vendored sources instead of registries.
+    ")]
+    Vendor(VendorArgs),
+    /// Check that a published package matches its sources.
+    #[command(after_help = "\
+        This command downloads the published package, packages the sources from the current \
+        workspace or a git repository, and compares contents of both tarballs.
+    ")]
+    Verify(VerifyArgs),
+    /// Remove a published package version from dependency resolution.
+    #[command(after_help = "\
+        Yanked versions are still downloadable by packages which have them pinned in their \
+        lockfiles, but are never selected for new dependants. Use `--undo` to revert a yank.
+    ")]
+    Yank(YankArgs),
     /// External command (`scarb-*` executable).
     #[command(external_subcommand)]
     External(Vec<OsString>),
 }
 
+/// Arguments accepted by the `audit` command.
+#[derive(Parser, Clone, Debug)]
+pub struct AuditArgs {
+    /// Directory containing the advisory database.
+    #[arg(long, value_name = "PATH", env = "SCARB_ADVISORY_DB")]
+    pub db: Utf8PathBuf,
+
+    /// Lowest severity of vulnerabilities which fail the audit.
+    ///
+    /// One of `low`, `medium`, `high` or `critical`.
+    #[arg(long, value_name = "SEVERITY", default_value = "low")]
+    pub severity_threshold: Severity,
+
+    /// Fail the audit if any locked package is unmaintained.
+    #[arg(long)]
+    pub deny_unmaintained: bool,
+}
+
 /// Arguments accepted by the `build` command.
 #[derive(Parser, Clone, Debug)]
 pub struct BuildArgs {
     #[command(flatten)]
     pub packages_filter: PackagesFilter,
 
+    #[command(flatten)]
+    pub features: FeaturesSpec,
+
     /// Build tests.
     #[arg(short, long, default_value_t = false)]
     pub test: bool,
+
+    /// Report all compiler warnings as errors.
+    #[arg(long, default_value_t = false)]
+    pub deny_warnings: bool,
+}
+
+/// Arguments accepted by the `clean` command.
+#[derive(Parser, Clone, Debug)]
+pub struct CleanArgs {
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+}
+
+/// Arguments accepted by the `config get` command.
+#[derive(Parser, Clone, Debug)]
+pub struct ConfigGetArgs {
+    /// Dotted configuration key, like `http.retry`.
+    pub key: String,
+
+    #[command(flatten)]
+    pub scope: ConfigScopeSpec,
+
+    /// Print the path of the configuration file each value comes from.
+    #[arg(long)]
+    pub show_origin: bool,
+}
+
+/// Arguments accepted by the `config list` command.
+#[derive(Parser, Clone, Debug)]
+pub struct ConfigListArgs {
+    #[command(flatten)]
+    pub scope: ConfigScopeSpec,
+
+    /// Print the path of the configuration file each value comes from.
+    #[arg(long)]
+    pub show_origin: bool,
+}
+
+/// Arguments accepted by the `config set` command.
+#[derive(Parser, Clone, Debug)]
+pub struct ConfigSetArgs {
+    /// Dotted configuration key, like `http.retry`.
+    pub key: String,
+
+    /// New value of the key.
+    pub value: String,
+
+    #[command(flatten)]
+    pub scope: ConfigScopeSpec,
+}
+
+/// Arguments accepted by the `fetch` command.
+#[derive(Parser, Clone, Debug)]
+pub struct FetchArgs {
+    #[command(flatten)]
+    pub features: FeaturesSpec,
+}
+
+/// Arguments accepted by the `install` command.
+#[derive(Parser, Clone, Debug)]
+pub struct InstallArgs {
+    /// Package to install, optionally with a version requirement, like `foo@1.0`.
+    ///
+    /// Required for registry packages, otherwise inferred from `Scarb.toml` or `Cargo.toml`.
+    #[arg(value_name = "PACKAGE", required_unless_present_any = ["git", "path", "list"])]
+    pub package: Option<DepId>,
+
+    /// Git repository location.
+    ///
+    /// Without any other information, Scarb will use the latest commit on the default branch.
+    #[arg(long, value_name = "URI", conflicts_with = "path")]
+    pub git: Option<Url>,
+
+    /// Git reference args for `--git`.
+    #[command(flatten)]
+    pub git_ref: GitRefGroup,
+
+    /// Filesystem path to a local package.
+    #[arg(long)]
+    pub path: Option<Utf8PathBuf>,
+
+    /// Name of a registry defined in Scarb configuration to take the package from.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["git", "path"])]
+    pub registry: Option<SmolStr>,
+
+    /// Overwrite executables installed by other packages, or not installed by Scarb.
+    #[arg(long)]
+    pub force: bool,
+
+    /// List installed packages and their executables.
+    #[arg(long, conflicts_with_all = ["package", "git", "path", "registry", "force"])]
+    pub list: bool,
+}
+
+/// Arguments accepted by the `uninstall` command.
+#[derive(Parser, Clone, Debug)]
+pub struct UninstallArgs {
+    /// Name of the installed package.
+    pub package: String,
 }
 
 /// Arguments accepted by the `run` command.
@@ -217,6 +510,94 @@ pub struct InitArgs {
     /// Do not initialize a new Git repository.
     #[arg(long)]
     pub no_vcs: bool,
+
+    /// Version control system to initialize, along with its ignore file.
+    #[arg(long, value_enum, conflicts_with = "no_vcs")]
+    pub vcs: Option<VcsArg>,
+
+    /// Create a virtual workspace with the package as its first member.
+    #[arg(long)]
+    pub workspace: bool,
+
+    /// Kind of the package to create.
+    #[arg(long, value_enum, default_value_t)]
+    pub kind: PackageKindArg,
+
+    /// Test runner to set up in the package.
+    #[arg(long, value_enum, default_value_t)]
+    pub test_runner: TestRunnerArg,
+
+    /// SPDX license expression to put in the package manifest.
+    ///
+    /// Files with texts of MIT and Apache 2.0 licenses are created as well.
+    #[arg(long, value_name = "SPDX")]
+    pub license: Option<String>,
+
+    /// Ask for package kind, test runner, license and version control, offering values of other
+    /// arguments as defaults.
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+impl InitArgs {
+    /// Options of the `new` and `init` operations, creating a package at `path`.
+    pub fn into_options(self, path: Utf8PathBuf) -> ops::InitOptions {
+        ops::InitOptions {
+            name: self.name,
+            path,
+            vcs: if self.no_vcs || matches!(self.vcs, Some(VcsArg::None)) {
+                VersionControl::NoVcs
+            } else {
+                VersionControl::Git
+            },
+            workspace: self.workspace,
+            template: None,
+            skeleton: Skeleton {
+                kind: match self.kind {
+                    PackageKindArg::Lib => PackageKind::Lib,
+                    PackageKindArg::StarknetContract => PackageKind::StarknetContract,
+                    PackageKindArg::Executable => PackageKind::Executable,
+                },
+                test_runner: match self.test_runner {
+                    TestRunnerArg::CairoTest => TestRunner::CairoTest,
+                    TestRunnerArg::StarknetFoundry => TestRunner::StarknetFoundry,
+                },
+                license: self.license,
+            },
+            interactive: self.interactive,
+        }
+    }
+}
+
+/// Version control system of a new package.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum VcsArg {
+    /// Initialize a Git repository and write a `.gitignore` file.
+    Git,
+    /// Do not use any version control system.
+    None,
+}
+
+/// Kind of a new package.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum PackageKindArg {
+    /// A library.
+    #[default]
+    Lib,
+    /// A package of Starknet contracts.
+    StarknetContract,
+    /// A library with a `main` function, run with `scarb cairo-run`.
+    Executable,
+}
+
+/// Test runner of a new package.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum TestRunnerArg {
+    /// The `scarb cairo-test` extension, bundled with Scarb.
+    #[default]
+    CairoTest,
+    /// Starknet Foundry.
+    StarknetFoundry,
 }
 
 /// Arguments accepted by the `metadata` command.
@@ -228,6 +609,9 @@ pub struct MetadataArgs {
     /// Output information only about the workspace members and don't fetch dependencies.
     #[arg(long)]
     pub no_deps: bool,
+
+    #[command(flatten)]
+    pub features: FeaturesSpec,
 }
 
 /// Arguments accepted by the `new` command.
@@ -236,6 +620,40 @@ pub struct NewArgs {
     pub path: Utf8PathBuf,
     #[command(flatten)]
     pub init: InitArgs,
+
+    /// URL of a Git repository or path to a local directory, holding a template to generate
+    /// the package from.
+    #[arg(
+        long,
+        value_name = "URL|PATH",
+        value_parser = parse_template,
+        conflicts_with_all = ["workspace", "kind", "test_runner", "license", "interactive"]
+    )]
+    pub template: Option<TemplateSource>,
+
+    /// Set value of a template placeholder, instead of being asked for it.
+    #[arg(
+        long = "define",
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value,
+        requires = "template"
+    )]
+    pub defines: Vec<(String, String)>,
+}
+
+fn parse_template(s: &str) -> Result<TemplateSource> {
+    match Url::parse(s) {
+        // Windows paths, like `C:\template`, parse as URLs with single letter schemes.
+        Ok(url) if url.scheme().len() > 1 => Ok(TemplateSource::Git(url)),
+        _ => Ok(TemplateSource::Path(s.into())),
+    }
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("expected `KEY=VALUE`, got: {s}");
+    };
+    Ok((key.trim().to_string(), value.to_string()))
 }
 
 /// Arguments accepted by the `fmt` command.
@@ -267,6 +685,14 @@ pub struct AddArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Let prerelease versions satisfy version requirements of added dependencies.
+    #[arg(long)]
+    pub pre: bool,
+
+    /// Add as dependencies used only by test targets.
+    #[arg(long)]
+    pub dev: bool,
+
     #[command(flatten)]
     pub packages_filter: PackagesFilter,
 
@@ -291,6 +717,10 @@ pub struct AddSourceArgs {
     /// Git reference args for `--git`.
     #[command(flatten)]
     pub git_ref: GitRefGroup,
+
+    /// Name of a registry defined in Scarb configuration to take packages from.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["path", "git"])]
+    pub registry: Option<SmolStr>,
 }
 
 /// Arguments accepted by the `remove` command.
@@ -304,6 +734,11 @@ pub struct RemoveArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Also remove removed dependencies from `[workspace.dependencies]` if no workspace member
+    /// uses them anymore, without asking.
+    #[arg(long)]
+    pub prune: bool,
+
     #[command(flatten)]
     pub packages_filter: PackagesFilter,
 }
@@ -314,6 +749,9 @@ pub struct TestArgs {
     #[command(flatten)]
     pub packages_filter: PackagesFilter,
 
+    #[command(flatten)]
+    pub features: FeaturesSpec,
+
     /// Arguments for the test program.
     #[clap(allow_hyphen_values = true)]
     pub args: Vec<OsString>,
@@ -323,6 +761,8 @@ pub struct TestArgs {
 #[derive(Parser, Clone, Debug)]
 pub struct PackageArgs {
     /// Print files included in a package without making one.
+    ///
+    /// With `--json`, files of all packages are printed as a single object, keyed by package name.
     #[arg(short, long)]
     pub list: bool,
 
@@ -334,13 +774,288 @@ pub struct PackageArgs {
 #[derive(Parser, Clone, Debug)]
 pub struct PublishArgs {
     /// Registry index URL to upload the package to.
+    #[arg(long, value_name = "URL", required_unless_present = "registry")]
+    pub index: Option<Url>,
+
+    /// Name of a registry from Scarb configuration to upload the package to.
+    #[arg(long, value_name = "NAME", conflicts_with = "index")]
+    pub registry: Option<SmolStr>,
+
+    /// Wait until the published version is available in the registry index.
+    #[arg(long)]
+    pub wait: bool,
+
+    /// Maximum time to wait for a published version, in seconds.
+    ///
+    /// When publishing multiple packages, dependencies are always waited for before
+    /// publishing their dependants.
+    #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+    pub wait_timeout: u64,
+
+    /// Perform all checks and package the project, but do not upload it.
+    #[arg(long, conflicts_with = "wait")]
+    pub dry_run: bool,
+
+    /// Do not verify the package by compiling it before uploading.
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Upload a signed provenance attestation along with the package.
+    ///
+    /// Attestations can only be generated in GitHub Actions or GitLab CI, and are signed with
+    /// the unencrypted minisign secret key passed in `SCARB_PROVENANCE_KEY` environment variable.
+    #[arg(long)]
+    pub provenance: bool,
+
+    /// One-time password for registries enforcing two-factor authentication.
+    ///
+    /// If the registry requires it and this is not set, the password is asked for interactively.
+    #[arg(long, value_name = "CODE")]
+    pub otp: Option<String>,
+
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+}
+
+/// Arguments accepted by the `owner` command.
+#[derive(Parser, Clone, Debug)]
+pub struct OwnerArgs {
+    /// Name of the package to manage owners of, defaults to the current package.
+    pub package: Option<PackageName>,
+
+    #[command(flatten)]
+    pub action: OwnerAction,
+
+    /// Registry index URL the package is published in.
+    #[arg(long, value_name = "URL")]
+    pub index: Url,
+}
+
+/// Operation performed by the `owner` command.
+#[derive(Parser, Clone, Debug)]
+#[group(required = true, multiple = false)]
+pub struct OwnerAction {
+    /// Add users with given logins as owners.
+    #[arg(short, long, value_name = "LOGIN", value_delimiter = ',')]
+    pub add: Vec<String>,
+
+    /// Remove users with given logins from owners.
+    #[arg(short, long, value_name = "LOGIN", value_delimiter = ',')]
+    pub remove: Vec<String>,
+
+    /// List owners of the package.
+    #[arg(short, long, default_value_t = false)]
+    pub list: bool,
+}
+
+/// Arguments accepted by the `search` command.
+#[derive(Parser, Clone, Debug)]
+pub struct SearchArgs {
+    /// Text to look for in package names and descriptions.
+    pub query: String,
+
+    /// Registry index URL to search in.
     #[arg(long, value_name = "URL")]
     pub index: Url,
 
+    /// Maximum number of packages to display.
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+}
+
+/// Arguments accepted by the `self update` command.
+#[derive(Parser, Clone, Debug)]
+pub struct SelfUpdateArgs {
+    /// Install this version instead of the newest release.
+    #[arg(long)]
+    pub version: Option<Version>,
+}
+
+/// Arguments accepted by the `tree` command.
+#[derive(Parser, Clone, Debug)]
+pub struct TreeArgs {
+    /// Maximum display depth of the dependency tree.
+    #[arg(long, value_name = "DEPTH")]
+    pub depth: Option<usize>,
+
+    /// Invert the tree, showing all paths from workspace members to the given package.
+    ///
+    /// The package is specified as `<NAME>` or `<NAME>@<VERSION>`.
+    #[arg(short, long, value_name = "SPEC")]
+    pub invert: Option<PackageSpec>,
+
+    /// Show only packages present in multiple versions, with all paths pulling each of them in.
+    #[arg(short, long, conflicts_with = "invert")]
+    pub duplicates: bool,
+
     #[command(flatten)]
     pub packages_filter: PackagesFilter,
 }
 
+/// Arguments accepted by the `update` command.
+#[derive(Parser, Clone, Debug)]
+pub struct UpdateArgs {
+    /// Update only this package, may be given multiple times.
+    #[arg(short, long = "package", value_name = "NAME")]
+    pub packages: Vec<PackageName>,
+
+    /// Update the package to exactly this version.
+    #[arg(long, value_name = "VERSION", requires = "packages")]
+    pub precise: Option<Version>,
+
+    /// Resolve dependencies to the lowest versions matching their requirements.
+    ///
+    /// Useful for checking that declared lower bounds of version requirements actually work.
+    #[arg(long, conflicts_with = "precise")]
+    pub minimal_versions: bool,
+
+    /// Let prerelease versions satisfy version requirements of all dependencies.
+    ///
+    /// Prerelease versions selected this way stay locked in subsequent resolutions.
+    #[arg(long)]
+    pub pre: bool,
+}
+
+/// Arguments accepted by the `upgrade` command.
+#[derive(Parser, Clone, Debug)]
+pub struct UpgradeArgs {
+    /// Upgrade only requirements on these packages.
+    #[arg(value_name = "DEP")]
+    pub dependencies: Vec<PackageName>,
+
+    /// Upgrade to the newest versions, even if they do not match current version requirements.
+    #[arg(long)]
+    pub incompatible: bool,
+
+    /// Do not actually write the manifest.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+}
+
+/// Arguments accepted by the `vendor` command.
+#[derive(Parser, Clone, Debug)]
+pub struct VendorArgs {
+    /// Where to store vendored packages, relative to the workspace root.
+    #[arg(default_value = "vendor")]
+    pub path: Utf8PathBuf,
+}
+
+/// Arguments accepted by the `verify` command.
+#[derive(Parser, Clone, Debug)]
+pub struct VerifyArgs {
+    /// Package version to verify, in the `<NAME>@<VERSION>` form.
+    #[arg(value_name = "SPEC")]
+    pub package: PackageVersion,
+
+    /// Registry index URL the package is published in.
+    #[arg(long, value_name = "URL")]
+    pub index: Url,
+
+    /// Git repository to take package sources from, instead of the current workspace.
+    #[arg(long, value_name = "URL")]
+    pub git: Option<Url>,
+
+    /// Git tag to check out, `v<VERSION>` by default.
+    #[arg(long, requires = "git", conflicts_with = "rev")]
+    pub tag: Option<String>,
+
+    /// Git revision to check out.
+    #[arg(long, requires = "git")]
+    pub rev: Option<String>,
+}
+
+/// Arguments accepted by the `yank` command.
+#[derive(Parser, Clone, Debug)]
+pub struct YankArgs {
+    /// Package version to yank, in the `<NAME>@<VERSION>` form.
+    #[arg(value_name = "SPEC")]
+    pub package: PackageVersion,
+
+    /// Registry index URL the package is published in.
+    #[arg(long, value_name = "URL")]
+    pub index: Url,
+
+    /// Unyank the version instead, making it available for resolution again.
+    #[arg(long, default_value_t = false)]
+    pub undo: bool,
+}
+
+/// Exact version of a named package, written as `<NAME>@<VERSION>`.
+#[derive(Clone, Debug)]
+pub struct PackageVersion {
+    /// Package name.
+    pub name: PackageName,
+    /// Exact package version.
+    pub version: Version,
+}
+
+impl FromStr for PackageVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((name, version)) = s.split_once('@') else {
+            bail!("package version must be specified as `<NAME>@<VERSION>`, got: {s}");
+        };
+        Ok(Self {
+            name: name.parse()?,
+            version: version
+                .parse()
+                .with_context(|| format!("invalid package version: {version}"))?,
+        })
+    }
+}
+
+/// Package specification in the `<NAME>` or `<NAME>@<VERSION>` form.
+#[derive(Clone, Debug)]
+pub struct PackageSpec {
+    /// Package name.
+    pub name: PackageName,
+    /// Package version, if specified.
+    pub version: Option<Version>,
+}
+
+impl FromStr for PackageSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('@') {
+            Some(_) => {
+                let PackageVersion { name, version } = s.parse()?;
+                Ok(Self {
+                    name,
+                    version: Some(version),
+                })
+            }
+            None => Ok(Self {
+                name: s.parse()?,
+                version: None,
+            }),
+        }
+    }
+}
+
+/// Arguments accepted by the `login` command.
+#[derive(Parser, Clone, Debug)]
+pub struct LoginArgs {
+    /// Registry index URL to log in to.
+    #[arg(long, value_name = "URL")]
+    pub index: Url,
+
+    /// Authentication token, read from standard input if not provided.
+    pub token: Option<String>,
+}
+
+/// Arguments accepted by the `logout` command.
+#[derive(Parser, Clone, Debug)]
+pub struct LogoutArgs {
+    /// Registry index URL to log out from.
+    #[arg(long, value_name = "URL")]
+    pub index: Url,
+}
+
 /// Git reference specification arguments.
 #[derive(Parser, Clone, Debug)]
 #[group(requires = "git", multiple = false)]
@@ -360,6 +1075,31 @@ pub struct GitRefGroup {
     pub rev: Option<String>,
 }
 
+/// Configuration file specifier.
+#[derive(Parser, Clone, Debug)]
+#[group(multiple = false)]
+pub struct ConfigScopeSpec {
+    /// Use the global configuration file only.
+    #[arg(long)]
+    pub global: bool,
+
+    /// Use the configuration file of the current project only.
+    #[arg(long)]
+    pub local: bool,
+}
+
+impl ConfigScopeSpec {
+    pub fn determine(&self) -> Option<ConfigScope> {
+        if self.global {
+            Some(ConfigScope::Global)
+        } else if self.local {
+            Some(ConfigScope::Local)
+        } else {
+            None
+        }
+    }
+}
+
 /// Profile specifier.
 #[derive(Parser, Clone, Debug)]
 #[group(multiple = true)]
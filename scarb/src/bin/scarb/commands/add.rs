@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use url::Url;
 
 use scarb::core::Config;
 use scarb::manifest_editor::{AddDependency, DepId, EditManifestOptions, Op};
@@ -12,9 +13,11 @@ pub fn run(args: AddArgs, config: &mut Config) -> Result<()> {
 
     let package = args.packages_filter.match_one(&ws)?;
 
+    let registry = registry_url(&args.source, config)?;
+
     manifest_editor::edit(
         package.manifest_path(),
-        build_ops(args.packages, args.source),
+        build_ops(args.packages, args.source, registry, args.pre, args.dev),
         EditManifestOptions {
             config,
             dry_run: args.dry_run,
@@ -34,7 +37,28 @@ pub fn run(args: AddArgs, config: &mut Config) -> Result<()> {
     Ok(())
 }
 
-fn build_ops(packages: Vec<DepId>, source: AddSourceArgs) -> Vec<Box<dyn Op>> {
+fn registry_url(source: &AddSourceArgs, config: &Config) -> Result<Option<Url>> {
+    source
+        .registry
+        .as_ref()
+        .map(|name| {
+            config
+                .config_file()
+                .registries
+                .get(name)
+                .map(|registry| registry.index.clone())
+                .ok_or_else(|| anyhow!("registry `{name}` is not defined in Scarb configuration"))
+        })
+        .transpose()
+}
+
+fn build_ops(
+    packages: Vec<DepId>,
+    source: AddSourceArgs,
+    registry: Option<Url>,
+    pre: bool,
+    dev: bool,
+) -> Vec<Box<dyn Op>> {
     let template = AddDependency {
         dep: DepId::unspecified(),
         path: source.path,
@@ -42,6 +66,9 @@ fn build_ops(packages: Vec<DepId>, source: AddSourceArgs) -> Vec<Box<dyn Op>> {
         branch: source.git_ref.branch,
         tag: source.git_ref.tag,
         rev: source.git_ref.rev,
+        registry,
+        dev,
+        allow_prerelease: pre,
     };
 
     if packages.is_empty() {
@@ -0,0 +1,82 @@
+use anyhow::{bail, Result};
+use serde::{Serialize, Serializer};
+
+use scarb::core::{AdvisoryKind, Config};
+use scarb::ops;
+use scarb::ops::{AuditOpts, AuditReport};
+use scarb_ui::Message;
+
+use crate::args::AuditArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: AuditArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let opts = AuditOpts {
+        database: args.db,
+        severity_threshold: args.severity_threshold,
+        deny_unmaintained: args.deny_unmaintained,
+    };
+
+    let report = ops::audit(&opts, &ws)?;
+    let denied = report.denied();
+    let found = report.findings.len();
+    config.ui().print(AuditOutput(report));
+    if denied > 0 {
+        bail!("audit failed: {denied} of {found} advisories affecting locked packages are denied");
+    }
+    Ok(())
+}
+
+struct AuditOutput(AuditReport);
+
+impl Message for AuditOutput {
+    fn text(self) -> String {
+        if self.0.findings.is_empty() {
+            return "no advisories affect locked packages".to_string();
+        }
+
+        self.0
+            .findings
+            .iter()
+            .map(|finding| {
+                let advisory = &finding.advisory;
+                let mut lines = vec![format!(
+                    "{}: {}{}",
+                    advisory.id,
+                    advisory.title,
+                    if finding.denied { "" } else { " (allowed)" }
+                )];
+                lines.push(format!(
+                    "  package:  {} v{}",
+                    finding.package.name, finding.package.version
+                ));
+                match (advisory.kind, advisory.severity) {
+                    (AdvisoryKind::Vulnerability, Some(severity)) => {
+                        lines.push(format!("  severity: {severity}"))
+                    }
+                    (kind, _) => lines.push(format!("  kind:     {kind}")),
+                }
+                let patched = if advisory.patched.is_empty() {
+                    "none".to_string()
+                } else {
+                    advisory
+                        .patched
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                lines.push(format!("  patched:  {patched}"));
+                if let Some(url) = &advisory.url {
+                    lines.push(format!("  url:      {url}"));
+                }
+                lines.join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
@@ -3,7 +3,7 @@ use anyhow::Result;
 use crate::args::BuildArgs;
 use scarb::core::{Config, TargetKind};
 use scarb::ops;
-use scarb::ops::CompileOpts;
+use scarb::ops::{CompileOpts, FeaturesOpts};
 
 #[tracing::instrument(skip_all, level = "info")]
 pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
@@ -22,6 +22,8 @@ pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
     let opts = CompileOpts {
         include_targets,
         exclude_targets,
+        features: FeaturesOpts::try_from(args.features)?,
+        deny_warnings: args.deny_warnings,
     };
     ops::compile(packages, opts, &ws)
 }
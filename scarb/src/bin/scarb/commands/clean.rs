@@ -1,9 +1,21 @@
 use anyhow::Result;
 use scarb::core::Config;
 
+use crate::args::CleanArgs;
 use scarb::ops;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(config: &Config) -> Result<()> {
-    ops::clean(config)
+pub fn run(args: CleanArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    if packages.len() == ws.members_count() {
+        ops::clean(config)
+    } else {
+        ops::clean_packages(&packages, &ws)
+    }
 }
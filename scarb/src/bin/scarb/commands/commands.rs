@@ -1,11 +1,14 @@
 use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::path::PathBuf;
-use std::{env, fmt, fs};
+use std::{env, fs, thread};
 
 use anyhow::Result;
+use semver::Version;
 use serde::{Serialize, Serializer};
 
 use scarb::core::Config;
+use scarb::ops;
 use scarb::process::is_executable;
 use scarb::EXTERNAL_CMD_PREFIX;
 use scarb_ui::Message;
@@ -14,18 +17,16 @@ use crate::args::ScarbArgs;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
 enum CommandInfo {
-    BuiltIn { about: Option<String> },
-    External { path: PathBuf },
-}
-
-impl fmt::Display for CommandInfo {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            CommandInfo::BuiltIn { about: Some(about) } => write!(f, "{}", about),
-            CommandInfo::BuiltIn { about: None } => write!(f, "",),
-            CommandInfo::External { path } => write!(f, "{}", path.display()),
-        }
-    }
+    BuiltIn {
+        about: Option<String>,
+    },
+    External {
+        path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        version: Option<Version>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
 }
 
 #[derive(Serialize, Debug)]
@@ -35,11 +36,55 @@ struct CommandsList {
 
 impl Message for CommandsList {
     fn text(self) -> String {
-        let mut text = String::from("Installed Commands:\n");
+        let width = self
+            .commands
+            .keys()
+            .map(String::len)
+            .max()
+            .unwrap_or_default();
+        let version_width = self
+            .commands
+            .values()
+            .filter_map(|info| match info {
+                CommandInfo::External {
+                    version: Some(version),
+                    ..
+                } => Some(version.to_string().len() + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+
+        let mut builtin = String::from("Built-in commands:\n");
+        let mut external = String::from("External commands:\n");
         for (name, info) in self.commands {
-            text.push_str(&format!("{:<22}: {}\n", name, info));
+            let (section, line) = match info {
+                CommandInfo::BuiltIn { about } => (
+                    &mut builtin,
+                    format!("    {name:<width$}  {}", about.unwrap_or_default()),
+                ),
+                CommandInfo::External {
+                    path,
+                    version,
+                    description,
+                } => {
+                    let version = version.map(|v| format!("v{v}")).unwrap_or_default();
+                    // Point to the executable of extensions which do not describe themselves.
+                    let description = description.unwrap_or_else(|| path.display().to_string());
+                    (
+                        &mut external,
+                        format!("    {name:<width$}  {version:<version_width$}  {description}"),
+                    )
+                }
+            };
+            writeln!(section, "{}", line.trim_end()).unwrap();
+        }
+
+        if external.lines().count() > 1 {
+            format!("{builtin}\n{external}")
+        } else {
+            builtin
         }
-        text
     }
 
     fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
@@ -57,7 +102,7 @@ fn list_commands(config: &Config, builtins: &BTreeMap<String, Option<String>>) -
         .ok()
         .and_then(|p| p.parent())
         .map(PathBuf::from);
-    let mut commands = BTreeMap::new();
+    let mut externals = BTreeMap::new();
     for dir in config.dirs().path_dirs.iter().chain(scarb_exe_dir.iter()) {
         let Ok(entries) = fs::read_dir(dir) else {
             continue;
@@ -72,27 +117,50 @@ fn list_commands(config: &Config, builtins: &BTreeMap<String, Option<String>>) -
             }
             if is_executable(entry.path()) {
                 let end = filename.len() - suffix.len();
-                commands.insert(
-                    filename[prefix.len()..end].to_string(),
-                    CommandInfo::External { path: path.clone() },
-                );
+                // Like when running subcommands, the first executable found takes precedence.
+                externals
+                    .entry(filename[prefix.len()..end].to_string())
+                    .or_insert_with(|| path.clone());
             }
         }
     }
 
     // In case of name conflict, builtin commands take precedence.
-    let mut builtin_commands = builtins
-        .iter()
-        .map(|(name, about)| {
-            (
-                name.clone(),
-                CommandInfo::BuiltIn {
-                    about: about.clone(),
-                },
-            )
-        })
-        .collect();
-    commands.append(&mut builtin_commands);
+    externals.retain(|name, _| !builtins.contains_key(name));
+
+    // Extensions are asked to describe themselves in parallel, as each query spawns a process.
+    let mut commands: BTreeMap<String, CommandInfo> = thread::scope(|s| {
+        externals
+            .into_iter()
+            .map(|(name, path)| {
+                s.spawn(move || {
+                    let info = ops::describe_external_subcommand(&path);
+                    let external = CommandInfo::External {
+                        path,
+                        version: info.version,
+                        description: info.description,
+                    };
+                    (name, external)
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .expect("describing a subcommand must not panic")
+            })
+            .collect()
+    });
+
+    commands.extend(builtins.iter().map(|(name, about)| {
+        (
+            name.clone(),
+            CommandInfo::BuiltIn {
+                about: about.clone(),
+            },
+        )
+    }));
 
     CommandsList { commands }
 }
@@ -147,7 +215,9 @@ mod tests {
         assert_eq!(
             cmd.commands.remove("hello").unwrap(),
             CommandInfo::External {
-                path: sub_cmd.path().to_path_buf()
+                path: sub_cmd.path().to_path_buf(),
+                version: None,
+                description: None,
             }
         );
     }
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::{ConfigEntry, ConfigScope};
+use scarb_ui::Message;
+
+use crate::args::{ConfigGetArgs, ConfigListArgs, ConfigSetArgs, ConfigSubcommand};
+
+struct ConfigEntries {
+    entries: Vec<ConfigEntry>,
+    show_origin: bool,
+}
+
+impl Message for ConfigEntries {
+    fn text(self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| {
+                if self.show_origin {
+                    format!("{} = {} # {}", entry.key, entry.value, entry.origin)
+                } else {
+                    format!("{} = {}", entry.key, entry.value)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(ser)
+    }
+}
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(subcommand: ConfigSubcommand, config: &Config) -> Result<()> {
+    match subcommand {
+        ConfigSubcommand::Get(args) => get(args, config),
+        ConfigSubcommand::List(args) => list(args, config),
+        ConfigSubcommand::Set(args) => set(args, config),
+    }
+}
+
+fn get(args: ConfigGetArgs, config: &Config) -> Result<()> {
+    let entries = ops::get_config(&args.key, args.scope.determine(), config)?;
+    config.ui().print(ConfigEntries {
+        entries,
+        show_origin: args.show_origin,
+    });
+    Ok(())
+}
+
+fn list(args: ConfigListArgs, config: &Config) -> Result<()> {
+    let entries = ops::list_config(args.scope.determine(), config)?;
+    if !entries.is_empty() {
+        config.ui().print(ConfigEntries {
+            entries,
+            show_origin: args.show_origin,
+        });
+    }
+    Ok(())
+}
+
+fn set(args: ConfigSetArgs, config: &Config) -> Result<()> {
+    let scope = args.scope.determine().unwrap_or(ConfigScope::Local);
+    ops::set_config(&args.key, &args.value, scope, config)
+}
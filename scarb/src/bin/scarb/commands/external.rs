@@ -8,13 +8,6 @@ use scarb::ops::execute_external_subcommand;
 
 #[tracing::instrument(skip_all, level = "info")]
 pub fn run(args: Vec<OsString>, config: &Config) -> Result<()> {
-    let target_dir = if config.manifest_path().exists() {
-        let ws = ops::read_workspace(config.manifest_path(), config)?;
-        Some(ws.target_dir().path_unchecked().to_owned())
-    } else {
-        None
-    };
-
     let Some((cmd, args)) = args.split_first() else {
         panic!("`args` should never be empty.")
     };
@@ -23,6 +16,19 @@ pub fn run(args: Vec<OsString>, config: &Config) -> Result<()> {
         .to_str()
         .ok_or_else(|| anyhow!("command name must be valid UTF-8"))?;
 
+    let (target_dir, env, tools_dir) = if config.manifest_path().exists() {
+        let ws = ops::read_workspace(config.manifest_path(), config)?;
+        let tools_dir = ops::ensure_pinned_tools(&ws)?;
+        ops::check_required_tool_version(cmd, &ws, tools_dir.as_deref())?;
+        (
+            Some(ws.target_dir().path_unchecked().to_owned()),
+            Some(ws.env().resolve()),
+            tools_dir,
+        )
+    } else {
+        (None, None, None)
+    };
+
     // NOTE: This may replace the current process.
-    execute_external_subcommand(cmd, args, None, config, target_dir)
+    execute_external_subcommand(cmd, args, env, config, target_dir, tools_dir)
 }
@@ -2,13 +2,20 @@ use anyhow::Result;
 
 use scarb::core::Config;
 use scarb::ops;
+use scarb::ops::{FeaturesOpts, ResolveOpts};
+
+use crate::args::FetchArgs;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(config: &Config) -> Result<()> {
+pub fn run(args: FetchArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
-
-    match ops::resolve_workspace(&ws) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e),
-    }
+    let features = FeaturesOpts::try_from(args.features)?;
+    let members = ws.members().map(|p| p.id).collect::<Vec<_>>();
+    features.validate(&members, &ws)?;
+    let opts = ResolveOpts {
+        features,
+        ..Default::default()
+    };
+    ops::resolve_workspace_with_opts(&ws, &opts)?;
+    Ok(())
 }
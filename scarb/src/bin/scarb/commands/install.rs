@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Serializer};
+
+use scarb::core::{Config, GitReference};
+use scarb::ops;
+use scarb::ops::{InstallOpts, InstallSource, InstalledPackage};
+use scarb_ui::Message;
+
+use crate::args::InstallArgs;
+
+struct InstalledList(BTreeMap<String, InstalledPackage>);
+
+impl Message for InstalledList {
+    fn text(self) -> String {
+        if self.0.is_empty() {
+            return "no packages installed".to_string();
+        }
+        self.0
+            .iter()
+            .map(|(name, installed)| {
+                let version = installed
+                    .version
+                    .as_ref()
+                    .map(|version| format!(" v{version}"))
+                    .unwrap_or_default();
+                let executables = installed
+                    .executables
+                    .iter()
+                    .map(|file_name| format!("\n    {file_name}"))
+                    .collect::<String>();
+                format!("{name}{version} ({}):{executables}", installed.source)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: InstallArgs, config: &Config) -> Result<()> {
+    if args.list {
+        config
+            .ui()
+            .print(InstalledList(ops::list_installed(config)?));
+        return Ok(());
+    }
+
+    let source = match (args.git, args.path) {
+        (Some(url), _) => {
+            let reference = match (args.git_ref.branch, args.git_ref.tag, args.git_ref.rev) {
+                (Some(branch), _, _) => GitReference::Branch(branch.into()),
+                (_, Some(tag), _) => GitReference::Tag(tag.into()),
+                (_, _, Some(rev)) => GitReference::Rev(rev.into()),
+                (None, None, None) => GitReference::DefaultBranch,
+            };
+            InstallSource::Git { url, reference }
+        }
+        (None, Some(path)) => InstallSource::Path(path),
+        (None, None) => {
+            let registry = args
+                .registry
+                .map(|name| {
+                    config
+                        .config_file()
+                        .registries
+                        .get(&name)
+                        .map(|registry| registry.index.clone())
+                        .ok_or_else(|| {
+                            anyhow!("registry `{name}` is not defined in Scarb configuration")
+                        })
+                })
+                .transpose()?;
+            InstallSource::Registry(registry)
+        }
+    };
+
+    let package = args.package.unwrap_or_default();
+    ops::install(
+        InstallOpts {
+            name: package.name,
+            version_req: package.version_req,
+            source,
+            force: args.force,
+        },
+        config,
+    )
+}
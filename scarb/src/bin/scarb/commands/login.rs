@@ -0,0 +1,34 @@
+use std::io;
+
+use anyhow::{ensure, Context, Result};
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::LoginOpts;
+
+use crate::args::LoginArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: LoginArgs, config: &Config) -> Result<()> {
+    let token = match args.token {
+        Some(token) => token,
+        None => {
+            config.ui().print("please paste the token below");
+            let mut line = String::new();
+            io::stdin()
+                .read_line(&mut line)
+                .context("failed to read token from standard input")?;
+            line
+        }
+    };
+
+    let token = token.trim().to_string();
+    ensure!(!token.is_empty(), "token must not be empty");
+
+    let opts = LoginOpts {
+        index_url: args.index,
+        token,
+    };
+
+    ops::login(opts, config)
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::LogoutOpts;
+
+use crate::args::LogoutArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: LogoutArgs, config: &Config) -> Result<()> {
+    let opts = LogoutOpts {
+        index_url: args.index,
+    };
+
+    ops::logout(opts, config)
+}
@@ -0,0 +1,13 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb_ui::components::MachineMessage;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(config: &Config) -> Result<()> {
+    config
+        .ui()
+        .print(MachineMessage(ops::manifest_json_schema()));
+    Ok(())
+}
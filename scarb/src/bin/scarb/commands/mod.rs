@@ -4,27 +4,44 @@ use anyhow::Result;
 
 use scarb::core::Config;
 
-use crate::args::{CacheSubcommand, Command};
+use crate::args::{CacheSubcommand, Command, SelfSubcommand};
 
 pub mod add;
+pub mod audit;
 pub mod build;
 pub mod cache_clean;
 pub mod cache_path;
 pub mod clean;
 pub mod commands;
+pub mod config;
 pub mod external;
 pub mod fetch;
 pub mod fmt;
 pub mod init;
+pub mod install;
+pub mod login;
+pub mod logout;
 pub mod manifest_path;
+pub mod manifest_schema;
 pub mod metadata;
 pub mod new;
+pub mod outdated;
+pub mod owner;
 pub mod package;
 pub mod publish;
 pub mod remove;
+pub mod rpc;
 pub mod run;
+pub mod search;
+pub mod self_update;
 pub mod test;
+pub mod tree;
+pub mod uninstall;
 mod update;
+pub mod upgrade;
+pub mod vendor;
+pub mod verify;
+pub mod yank;
 
 pub fn run(command: Command, config: &mut Config) -> Result<()> {
     use Command::*;
@@ -32,23 +49,40 @@ pub fn run(command: Command, config: &mut Config) -> Result<()> {
     match command {
         // Keep these sorted alphabetically.
         Add(args) => add::run(args, config),
+        Audit(args) => audit::run(args, config),
         Build(args) => build::run(args, config),
         Cache(CacheSubcommand::Clean) => cache_clean::run(config),
         Cache(CacheSubcommand::Path) => cache_path::run(config),
-        Clean => clean::run(config),
+        Clean(args) => clean::run(args, config),
         Commands => commands::run(config),
+        Command::Config(subcommand) => config::run(subcommand, config),
         External(args) => external::run(args, config),
-        Fetch => fetch::run(config),
+        Fetch(args) => fetch::run(args, config),
         Fmt(args) => fmt::run(args, config),
         Init(args) => init::run(args, config),
+        Install(args) => install::run(args, config),
+        Login(args) => login::run(args, config),
+        Logout(args) => logout::run(args, config),
         ManifestPath => manifest_path::run(config),
+        ManifestSchema => manifest_schema::run(config),
         Metadata(args) => metadata::run(args, config),
         New(args) => new::run(args, config),
+        Outdated => outdated::run(config),
+        Owner(args) => owner::run(args, config),
         Package(args) => package::run(args, config),
         Publish(args) => publish::run(args, config),
         Remove(args) => remove::run(args, config),
+        Rpc => rpc::run(config),
         Run(args) => run::run(args, config),
+        Search(args) => search::run(args, config),
+        SelfCommand(SelfSubcommand::Update(args)) => self_update::run(args, config),
         Test(args) => test::run(args, config),
-        Update => update::run(config),
+        Tree(args) => tree::run(args, config),
+        Uninstall(args) => uninstall::run(args, config),
+        Update(args) => update::run(args, config),
+        Upgrade(args) => upgrade::run(args, config),
+        Vendor(args) => vendor::run(args, config),
+        Verify(args) => verify::run(args, config),
+        Yank(args) => yank::run(args, config),
     }
 }
@@ -1,29 +1,28 @@
 use anyhow::Result;
 
 use scarb::core::Config;
-use scarb::ops::{self, VersionControl};
+use scarb::ops;
 
 use crate::args::NewArgs;
 
 #[tracing::instrument(skip_all, level = "info")]
 pub fn run(args: NewArgs, config: &Config) -> Result<()> {
-    let result = ops::new_package(
-        ops::InitOptions {
-            name: args.init.name,
-            path: args.path,
-            // At the moment, we only support Git but ideally, we want to
-            // support more VCS and allow user to explicitly specify which VCS to use.
-            vcs: if args.init.no_vcs {
-                VersionControl::NoVcs
-            } else {
-                VersionControl::Git
-            },
-        },
-        config,
-    )?;
+    let workspace = args.init.workspace;
+    let mut opts = args.init.into_options(args.path);
+    opts.template = args.template.map(|source| ops::TemplateOpts {
+        source,
+        defines: args.defines.into_iter().collect(),
+    });
+    let result = ops::new_package(opts, config)?;
 
-    config
-        .ui()
-        .print(format!("Created `{}` package.", result.name));
+    if workspace {
+        config
+            .ui()
+            .print(format!("Created `{}` workspace.", result.name));
+    } else {
+        config
+            .ui()
+            .print(format!("Created `{}` package.", result.name));
+    }
     Ok(())
 }
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::{OutdatedDependency, OutdatedDependencyKind};
+use scarb_ui::Message;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let outdated = ops::outdated(&ws)?;
+    config.ui().print(OutdatedOutput(outdated));
+    Ok(())
+}
+
+struct OutdatedOutput(Vec<OutdatedDependency>);
+
+impl Message for OutdatedOutput {
+    fn text(self) -> String {
+        if self.0.is_empty() {
+            return "all dependencies are up to date".to_string();
+        }
+
+        // Versions equal to the current one are shown as `-`, to make available upgrades stand out.
+        let display = |version: &semver::Version, current: &semver::Version| {
+            if version == current {
+                "-".to_string()
+            } else {
+                version.to_string()
+            }
+        };
+
+        let mut rows = vec![[
+            "Name".to_string(),
+            "Current".to_string(),
+            "Compatible".to_string(),
+            "Latest".to_string(),
+            "Kind".to_string(),
+        ]];
+        rows.extend(self.0.iter().map(|dep| {
+            [
+                dep.name.to_string(),
+                dep.current.to_string(),
+                display(&dep.compatible, &dep.current),
+                display(&dep.latest, &dep.current),
+                match dep.kind {
+                    OutdatedDependencyKind::Direct => "direct".to_string(),
+                    OutdatedDependencyKind::Transitive => "transitive".to_string(),
+                },
+            ]
+        }));
+
+        let widths: Vec<usize> = (0..5)
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap())
+            .collect();
+
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:<width$}"))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
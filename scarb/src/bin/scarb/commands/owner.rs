@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::OwnersOpts;
+
+use crate::args::OwnerArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: OwnerArgs, config: &Config) -> Result<()> {
+    let package = match args.package {
+        Some(package) => package,
+        None => {
+            let ws = ops::read_workspace(config.manifest_path(), config)?;
+            ws.current_package()?.id.name.clone()
+        }
+    };
+
+    let opts = OwnersOpts {
+        index_url: args.index,
+        package,
+    };
+
+    if args.action.list {
+        for owner in ops::list_owners(&opts, config)? {
+            config.ui().print(owner);
+        }
+        Ok(())
+    } else if !args.action.add.is_empty() {
+        ops::add_owners(&args.action.add, &opts, config)
+    } else {
+        ops::remove_owners(&args.action.remove, &opts, config)
+    }
+}
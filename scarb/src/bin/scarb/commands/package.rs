@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 
 use anyhow::Result;
 use camino::Utf8PathBuf;
-use serde::Serializer;
+use serde::{Serialize, Serializer};
 
 use scarb::core::{Config, PackageName};
 use scarb::ops;
@@ -21,7 +21,7 @@ pub fn run(args: PackageArgs, config: &Config) -> Result<()> {
         .map(|p| p.id)
         .collect::<Vec<_>>();
 
-    let opts = PackageOpts;
+    let opts = PackageOpts { run_hooks: true };
 
     if args.list {
         let result = ops::package_list(&packages, &opts, &ws)?;
@@ -58,10 +58,26 @@ impl Message for ListMessage {
         }
     }
 
-    fn structured<S: Serializer>(self, _ser: S) -> Result<S::Ok, S::Error>
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error>
     where
         Self: Sized,
     {
-        todo!("JSON output is not implemented yet.")
+        // Paths are separated with `/` on all platforms, like they are stored in the archive.
+        self.0
+            .into_iter()
+            .map(|(package, files)| {
+                let files = files
+                    .iter()
+                    .map(|file| {
+                        file.components()
+                            .map(|c| c.as_str())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    })
+                    .collect::<Vec<_>>();
+                (package, files)
+            })
+            .collect::<BTreeMap<_, _>>()
+            .serialize(ser)
     }
 }
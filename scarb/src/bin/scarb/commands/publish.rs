@@ -1,4 +1,7 @@
-use anyhow::Result;
+use std::env;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
 
 use scarb::core::Config;
 use scarb::ops;
@@ -6,14 +9,49 @@ use scarb::ops::PublishOpts;
 
 use crate::args::PublishArgs;
 
+/// Environment variable with the secret key used to sign provenance attestations.
+const PROVENANCE_KEY_ENV: &str = "SCARB_PROVENANCE_KEY";
+
 #[tracing::instrument(skip_all, level = "info")]
 pub fn run(args: PublishArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
-    let package = args.packages_filter.match_one(&ws)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+
+    let provenance_key = args
+        .provenance
+        .then(|| {
+            env::var(PROVENANCE_KEY_ENV).with_context(|| {
+                format!("`--provenance` requires a minisign secret key in `{PROVENANCE_KEY_ENV}`")
+            })
+        })
+        .transpose()?;
+
+    let index_url = match (&args.registry, args.index) {
+        (Some(name), _) => config
+            .config_file()
+            .registries
+            .get(name)
+            .map(|registry| registry.index.clone())
+            .ok_or_else(|| anyhow!("registry `{name}` is not defined in Scarb configuration"))?,
+        (None, Some(index)) => index,
+        (None, None) => unreachable!("clap requires either `--index` or `--registry`"),
+    };
 
     let ops = PublishOpts {
-        index_url: args.index,
+        index_url,
+        registry_name: args.registry,
+        wait: args.wait,
+        wait_timeout: Duration::from_secs(args.wait_timeout),
+        verify: !args.no_verify,
+        dry_run: args.dry_run,
+        provenance_key,
+        otp: args.otp,
     };
 
-    ops::publish(package.id, &ops, &ws)
+    ops::publish(&packages, &ops, &ws)
 }
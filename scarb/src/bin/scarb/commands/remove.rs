@@ -1,7 +1,10 @@
-use anyhow::Result;
+use std::io;
+use std::io::IsTerminal;
 
-use scarb::core::{Config, PackageName};
-use scarb::manifest_editor::{EditManifestOptions, Op, RemoveDependency};
+use anyhow::{Context, Result};
+
+use scarb::core::{Config, Package, PackageName, Workspace};
+use scarb::manifest_editor::{tables_inheriting, EditManifestOptions, Op, RemoveDependency};
 use scarb::{manifest_editor, ops};
 
 use crate::args::RemoveArgs;
@@ -12,6 +15,8 @@ pub fn run(args: RemoveArgs, config: &mut Config) -> Result<()> {
 
     let package = args.packages_filter.match_one(&ws)?;
 
+    let unused = unused_workspace_dependencies(&args.packages, &package, &ws)?;
+
     manifest_editor::edit(
         package.manifest_path(),
         build_ops(args.packages),
@@ -21,6 +26,37 @@ pub fn run(args: RemoveArgs, config: &mut Config) -> Result<()> {
         },
     )?;
 
+    let mut prune = Vec::new();
+    for dep in unused {
+        if args.prune || confirm_prune(&dep, config)? {
+            prune.push(dep);
+        } else {
+            config.ui().warn(format!(
+                "dependency `{dep}` in `[workspace.dependencies]` is no longer used by any \
+                workspace member\n\
+                help: pass `--prune` to remove such dependencies from the workspace manifest"
+            ));
+        }
+    }
+    if !prune.is_empty() {
+        manifest_editor::edit(
+            ws.manifest_path(),
+            prune
+                .into_iter()
+                .map(|dep| -> Box<dyn Op> {
+                    Box::new(RemoveDependency {
+                        dep,
+                        table: vec!["workspace".to_string(), "dependencies".to_string()],
+                    })
+                })
+                .collect(),
+            EditManifestOptions {
+                config,
+                dry_run: args.dry_run,
+            },
+        )?;
+    }
+
     if !args.dry_run {
         // Reload the workspace since we have changed dependencies
         let ws = ops::read_workspace(config.manifest_path(), config)?;
@@ -37,6 +73,55 @@ pub fn run(args: RemoveArgs, config: &mut Config) -> Result<()> {
 fn build_ops(packages: Vec<PackageName>) -> Vec<Box<dyn Op>> {
     packages
         .into_iter()
-        .map(|dep| -> Box<dyn Op> { Box::new(RemoveDependency { dep }) })
+        .map(|dep| -> Box<dyn Op> { Box::new(RemoveDependency::new(dep)) })
         .collect()
 }
+
+/// Dependencies among `deps` which `package` inherits from `[workspace.dependencies]`, and
+/// which no workspace member is going to inherit once they are removed from `package`.
+fn unused_workspace_dependencies(
+    deps: &[PackageName],
+    package: &Package,
+    ws: &Workspace<'_>,
+) -> Result<Vec<PackageName>> {
+    let mut unused = Vec::new();
+    for dep in deps {
+        let inherited = tables_inheriting(package.manifest_path(), dep)?
+            .iter()
+            .any(|table| table == &["dependencies"]);
+        if !inherited {
+            continue;
+        }
+        let mut used = false;
+        for member in ws.members() {
+            let tables = tables_inheriting(member.manifest_path(), dep)?;
+            used |= if member.id == package.id {
+                tables.iter().any(|table| table != &["dependencies"])
+            } else {
+                !tables.is_empty()
+            };
+        }
+        if !used {
+            unused.push(dep.clone());
+        }
+    }
+    Ok(unused)
+}
+
+/// Ask the user whether an unused workspace dependency should be removed.
+///
+/// Never asks, and answers no, if standard input is not a terminal.
+fn confirm_prune(dep: &PackageName, config: &Config) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    config.ui().print(format!(
+        "dependency `{dep}` is no longer used by any workspace member, \
+        remove it from `[workspace.dependencies]`? [y/N]"
+    ));
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .context("failed to read answer from standard input")?;
+    Ok(matches!(line.trim(), "y" | "Y" | "yes"))
+}
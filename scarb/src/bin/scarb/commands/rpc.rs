@@ -0,0 +1,11 @@
+use std::io;
+
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(config: &Config) -> Result<()> {
+    ops::serve_rpc(io::stdin().lock(), io::stdout().lock(), config)
+}
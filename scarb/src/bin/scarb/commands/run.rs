@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fmt::Write;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use indoc::formatdoc;
 use serde::{Serialize, Serializer};
 use smol_str::SmolStr;
@@ -19,14 +19,29 @@ use crate::errors::ErrorWithExitCode;
 pub fn run(args: ScriptsRunnerArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
     let packages = args.packages_filter.match_many(&ws)?;
-    let errors = packages
+    let results = match args.script.clone() {
+        Some(script) if runs_in_workspace_root(&script, &args, &ws) => {
+            vec![ops::execute_script_with_needs(
+                &script,
+                &args.args,
+                |name| ws.scripts().get(name),
+                &ws,
+                ws.root(),
+                None,
+            )]
+        }
+        Some(script) => packages
+            .into_iter()
+            .map(|package| run_script(script.clone(), &args.args, package, &ws))
+            .collect(),
+        None => packages
+            .into_iter()
+            .map(|package| list_scripts(package, &ws))
+            .chain((!ws.scripts().is_empty()).then(|| list_workspace_scripts(&ws)))
+            .collect(),
+    };
+    let errors = results
         .into_iter()
-        .map(|package| {
-            args.script
-                .clone()
-                .map(|script| run_script(script, &args.args, package.clone(), &ws))
-                .unwrap_or_else(|| list_scripts(package, &ws))
-        })
         .filter_map(|res| res.err())
         .map(|res| anyhow!(res))
         .collect::<Vec<anyhow::Error>>();
@@ -50,22 +65,43 @@ pub fn run(args: ScriptsRunnerArgs, config: &Config) -> Result<()> {
     }
 }
 
+/// Whether `script` is a workspace script to be run once, in the workspace root.
+///
+/// This is the case if no packages are selected explicitly and the current package, if any,
+/// does not define a script with the same name.
+fn runs_in_workspace_root(script: &SmolStr, args: &ScriptsRunnerArgs, ws: &Workspace) -> bool {
+    args.packages_filter.is_default()
+        && ws.scripts().contains_key(script)
+        && !ws
+            .current_package()
+            .is_ok_and(|package| package.manifest.scripts.contains_key(script))
+}
+
+/// Run `script` in the root of `package`, falling back to the workspace script if the package
+/// does not define it.
 fn run_script(script: SmolStr, args: &[OsString], package: Package, ws: &Workspace) -> Result<()> {
-    let script_definition = package.manifest.scripts.get(&script).ok_or_else(|| {
+    let lookup = |name: &str| {
+        package
+            .manifest
+            .scripts
+            .get(name)
+            .or_else(|| ws.scripts().get(name))
+    };
+    if lookup(script.as_str()).is_none() {
         let package_name = package.id.name.to_string();
         let package_selector = if !ws.is_single_package() {
             format!(" -p {package_name}")
         } else {
             String::new()
         };
-        anyhow!(formatdoc! {r#"
+        bail!(formatdoc! {r#"
             missing script `{script}` for package: {package_name}
 
             To see a list of scripts, run:
                 scarb run{package_selector}
-            "#})
-    })?;
-    ops::execute_script(script_definition, args, ws, package.root(), None)
+            "#});
+    }
+    ops::execute_script_with_needs(&script, args, lookup, ws, package.root(), None)
 }
 
 fn list_scripts(package: Package, ws: &Workspace) -> Result<()> {
@@ -81,6 +117,22 @@ fn list_scripts(package: Package, ws: &Workspace) -> Result<()> {
         scripts,
         package,
         single_package,
+        workspace: false,
+    });
+    Ok(())
+}
+
+fn list_workspace_scripts(ws: &Workspace) -> Result<()> {
+    let scripts = ws
+        .scripts()
+        .iter()
+        .map(|(name, definition)| (name.to_string(), definition.to_string()))
+        .collect();
+    ws.config().ui().print(ScriptsList {
+        scripts,
+        package: String::new(),
+        single_package: false,
+        workspace: true,
     });
     Ok(())
 }
@@ -90,13 +142,16 @@ struct ScriptsList {
     package: String,
     scripts: BTreeMap<String, String>,
     single_package: bool,
+    workspace: bool,
 }
 
 impl Message for ScriptsList {
     fn text(self) -> String {
         let mut text = String::new();
         write!(text, "Scripts available via `scarb run`",).unwrap();
-        if !self.single_package {
+        if self.workspace {
+            write!(text, " for workspace").unwrap();
+        } else if !self.single_package {
             write!(text, " for package `{}`", self.package).unwrap();
         }
         writeln!(text, ":",).unwrap();
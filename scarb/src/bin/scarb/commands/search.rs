@@ -0,0 +1,61 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+
+use scarb::core::{Config, SearchResults};
+use scarb::ops;
+use scarb::ops::SearchOpts;
+use scarb_ui::Message;
+
+use crate::args::SearchArgs;
+
+struct SearchOutput(SearchResults);
+
+impl Message for SearchOutput {
+    fn text(self) -> String {
+        let SearchResults { packages, total } = self.0;
+        if packages.is_empty() {
+            return "no packages found".to_string();
+        }
+
+        let width = packages
+            .iter()
+            .map(|p| p.name.as_str().len() + p.version.to_string().len())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = packages
+            .iter()
+            .map(|p| {
+                let spec = format!("{} = \"{}\"", p.name, p.version);
+                match &p.description {
+                    // Account for ` = ""` between name and version.
+                    Some(description) => format!("{spec:<w$}    # {description}", w = width + 5),
+                    None => spec,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if total > packages.len() {
+            lines.push(format!("... and {} more packages", total - packages.len()));
+        }
+
+        lines.join("\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: SearchArgs, config: &Config) -> Result<()> {
+    let opts = SearchOpts {
+        index_url: args.index,
+        query: args.query,
+        limit: args.limit,
+    };
+
+    let results = ops::search(opts, config)?;
+    config.ui().print(SearchOutput(results));
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::SelfUpdateOpts;
+
+use crate::args::SelfUpdateArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: SelfUpdateArgs, config: &Config) -> Result<()> {
+    let opts = SelfUpdateOpts {
+        version: args.version,
+    };
+    ops::self_update(&opts, config)
+}
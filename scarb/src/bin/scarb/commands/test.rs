@@ -11,5 +11,7 @@ pub fn run(args: TestArgs, config: &Config) -> Result<()> {
     args.packages_filter
         .match_many(&ws)?
         .iter()
-        .try_for_each(|package| ops::execute_test_subcommand(package, &args.args, &ws).map(|_| ()))
+        .try_for_each(|package| {
+            ops::execute_test_subcommand(package, &args.args, &args.features, &ws).map(|_| ())
+        })
 }
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::{DependencyTree, TreeInvertSpec, TreeOpts};
+use scarb_ui::Message;
+
+use crate::args::TreeArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: TreeArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+
+    let opts = TreeOpts {
+        packages,
+        depth: args.depth,
+        invert: args.invert.map(|spec| TreeInvertSpec {
+            name: spec.name,
+            version: spec.version,
+        }),
+        duplicates: args.duplicates,
+    };
+
+    let trees = ops::tree(&opts, &ws)?;
+    if trees.is_empty() {
+        config.ui().print("no duplicate packages found");
+        return Ok(());
+    }
+    config.ui().print(TreeOutput(trees));
+    Ok(())
+}
+
+struct TreeOutput(Vec<DependencyTree>);
+
+impl Message for TreeOutput {
+    fn text(self) -> String {
+        let mut lines = Vec::new();
+        for (i, tree) in self.0.iter().enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.push(node_label(tree));
+            render_dependencies(tree, "", &mut lines);
+        }
+        lines.join("\n")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+fn render_dependencies(tree: &DependencyTree, prefix: &str, lines: &mut Vec<String>) {
+    let count = tree.dependencies.len();
+    for (i, dep) in tree.dependencies.iter().enumerate() {
+        let is_last = i + 1 == count;
+        let (branch, continuation) = if is_last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!("{prefix}{branch}{}", node_label(dep)));
+        render_dependencies(dep, &format!("{prefix}{continuation}"), lines);
+    }
+}
+
+fn node_label(tree: &DependencyTree) -> String {
+    let mut label = tree.package.to_string();
+    if !tree.target_kinds.is_empty() {
+        let kinds = tree
+            .target_kinds
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        label.push_str(&format!(" [{kinds}]"));
+    }
+    if tree.deduplicated {
+        label.push_str(" (*)");
+    }
+    label
+}
@@ -0,0 +1,11 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+
+use crate::args::UninstallArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: UninstallArgs, config: &Config) -> Result<()> {
+    ops::uninstall(&args.package, config)
+}
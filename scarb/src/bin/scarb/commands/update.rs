@@ -4,10 +4,19 @@ use scarb::core::Config;
 use scarb::ops;
 use scarb::ops::ResolveOpts;
 
+use crate::args::UpdateArgs;
+
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(config: &Config) -> Result<()> {
+pub fn run(args: UpdateArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
-    let opts = ResolveOpts { update: true };
+    let opts = ResolveOpts {
+        update: args.packages.is_empty(),
+        update_packages: args.packages,
+        precise: args.precise,
+        minimal_versions: args.minimal_versions,
+        allow_prerelease: args.pre,
+        ..Default::default()
+    };
     ops::resolve_workspace_with_opts(&ws, &opts)?;
     Ok(())
 }
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::UpgradeOpts;
+
+use crate::args::UpgradeArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: UpgradeArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+
+    let opts = UpgradeOpts {
+        packages,
+        dependencies: args.dependencies,
+        incompatible: args.incompatible,
+        dry_run: args.dry_run,
+    };
+
+    let upgraded = ops::upgrade(&opts, &ws)?;
+    if upgraded == 0 {
+        config.ui().print("all version requirements are up to date");
+    } else if !args.dry_run {
+        // Reload the workspace, so that the lockfile follows upgraded requirements.
+        let ws = ops::read_workspace(config.manifest_path(), config)?;
+        ops::resolve_workspace(&ws)?;
+    }
+    Ok(())
+}
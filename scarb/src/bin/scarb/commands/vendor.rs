@@ -0,0 +1,22 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::VendorOpts;
+
+use crate::args::VendorArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: VendorArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let opts = VendorOpts {
+        destination: args.path,
+    };
+
+    let source_config = ops::vendor(&opts, &ws)?;
+    config.ui().print(format!(
+        "To use vendored sources, add this to your .scarb/config.toml for this project:\n\n\
+        {source_config}"
+    ));
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+
+use scarb::core::{Config, GitReference};
+use scarb::ops;
+use scarb::ops::VerifyOpts;
+
+use crate::args::VerifyArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: VerifyArgs, config: &Config) -> Result<()> {
+    let version = args.package.version;
+    let git = args.git.map(|url| {
+        let reference = match (args.tag, args.rev) {
+            (_, Some(rev)) => GitReference::Rev(rev.into()),
+            (Some(tag), None) => GitReference::Tag(tag.into()),
+            (None, None) => GitReference::Tag(format!("v{version}").into()),
+        };
+        (url, reference)
+    });
+
+    let opts = VerifyOpts {
+        index_url: args.index,
+        name: args.package.name,
+        version,
+        git,
+    };
+
+    ops::verify(opts, config)
+}
@@ -0,0 +1,19 @@
+use anyhow::Result;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::YankOpts;
+
+use crate::args::YankArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: YankArgs, config: &Config) -> Result<()> {
+    let opts = YankOpts {
+        index_url: args.index,
+        name: args.package.name,
+        version: args.package.version,
+        undo: args.undo,
+    };
+
+    ops::yank(opts, config)
+}
@@ -6,7 +6,7 @@ use tracing::debug;
 use tracing_log::AsTrace;
 use tracing_subscriber::EnvFilter;
 
-use args::ScarbArgs;
+use args::{Command, ScarbArgs};
 use scarb::core::errors::ScriptExecutionError;
 use scarb::core::Config;
 use scarb::ops;
@@ -14,6 +14,7 @@ use scarb_ui::Ui;
 
 use crate::errors::ErrorWithExitCode;
 
+mod alias;
 mod args;
 mod commands;
 mod errors;
@@ -66,13 +67,35 @@ fn cli_main(args: ScarbArgs) -> Result<()> {
     let mut config = Config::builder(manifest_path)
         .global_cache_dir_override(args.global_cache_dir)
         .global_config_dir_override(args.global_config_dir)
+        .global_install_dir_override(args.global_install_dir)
         .target_dir_override(args.target_dir)
         .ui_verbosity(ui_verbosity)
         .ui_output_format(ui_output_format)
         .offline(args.offline)
+        .locked(args.locked)
+        .frozen(args.frozen)
+        .ignore_cairo_version(args.ignore_cairo_version)
         .log_filter_directive(env::var_os("SCARB_LOG"))
         .profile(args.profile_spec.determine()?)
+        .config_overrides(args.config_overrides)
         .build()?;
 
+    if let Command::External(external) = &args.command {
+        let argv = env::args_os().collect::<Vec<_>>();
+        let builtins = ScarbArgs::get_builtin_subcommands();
+        if let Some(expanded) =
+            alias::expand_alias(&argv, external, &config.config_file().alias, &builtins)?
+        {
+            debug!("expanded alias to: {expanded:?}");
+            return cli_main(ScarbArgs::parse_from(expanded));
+        }
+    }
+
+    // Self-management commands always operate on the running Scarb.
+    if !matches!(args.command, Command::SelfCommand(_)) {
+        // NOTE: This may replace the current process.
+        ops::switch_to_pinned_toolchain(&config)?;
+    }
+
     commands::run(args.command, &mut config)
 }
@@ -46,6 +46,10 @@ pub struct CompilationUnit {
 pub struct CompilationUnitComponent {
     /// The Scarb [`Package`] to be build.
     pub package: Package,
+    /// Name of the Cairo crate of this component, under which other crates can refer to it.
+    ///
+    /// This is the package name, unless dependants import the package under a different name.
+    pub crate_name: SmolStr,
     /// Information about the specific target to build, out of the possible targets in `package`.
     pub target: Target,
 }
@@ -133,11 +137,12 @@ impl CompilationUnit {
 
 impl CompilationUnitComponent {
     pub fn cairo_package_name(&self) -> SmolStr {
-        self.package.id.name.to_smol_str()
+        self.crate_name.clone()
     }
 
     fn hash(&self, hasher: &mut impl Hasher) {
         self.package.id.hash(hasher);
+        self.crate_name.hash(hasher);
         self.target.hash(hasher);
     }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use tracing::trace_span;
 
 use crate::compiler::helpers::{
-    build_compiler_config, collect_main_crate_ids, write_json, write_string,
+    build_compiler_config, collect_main_crate_ids, write_json, write_string, DeniedLints,
 };
 use crate::compiler::{CompilationUnit, Compiler};
 use crate::core::{TargetKind, Workspace};
@@ -51,7 +51,8 @@ impl Compiler for LibCompiler {
 
         let target_dir = unit.target_dir(ws);
 
-        let compiler_config = build_compiler_config(&unit, ws);
+        let denied_lints = DeniedLints::default();
+        let compiler_config = build_compiler_config(&unit, ws, &denied_lints);
 
         let main_crate_ids = collect_main_crate_ids(&unit, db);
 
@@ -60,6 +61,7 @@ impl Compiler for LibCompiler {
             cairo_lang_compiler::compile_prepared_db(db, main_crate_ids, compiler_config)?
                 .into_artifact()
         };
+        denied_lints.ensure_none()?;
 
         if props.sierra {
             write_json(
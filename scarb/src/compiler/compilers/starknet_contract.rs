@@ -20,7 +20,9 @@ use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 use tracing::{debug, trace, trace_span};
 
-use crate::compiler::helpers::{build_compiler_config, collect_main_crate_ids, write_json};
+use crate::compiler::helpers::{
+    build_compiler_config, collect_main_crate_ids, write_json, DeniedLints,
+};
 use crate::compiler::{CompilationUnit, Compiler};
 use crate::core::{PackageName, TargetKind, Utf8PathWorkspaceExt, Workspace};
 use crate::internal::serdex::RelativeUtf8PathBuf;
@@ -205,7 +207,8 @@ impl Compiler for StarknetContractCompiler {
 
         let target_dir = unit.target_dir(ws);
 
-        let compiler_config = build_compiler_config(&unit, ws);
+        let denied_lints = DeniedLints::default();
+        let compiler_config = build_compiler_config(&unit, ws, &denied_lints);
 
         let main_crate_ids = collect_main_crate_ids(&unit, db);
 
@@ -233,6 +236,7 @@ impl Compiler for StarknetContractCompiler {
             let _ = trace_span!("compile_starknet").enter();
             compile_prepared_db(db, &contracts, compiler_config)?
         };
+        denied_lints.ensure_none()?;
 
         check_allowed_libfuncs(&props, &contracts, &classes, db, &unit, ws)?;
 
@@ -1,12 +1,12 @@
 use anyhow::Result;
 use cairo_lang_compiler::db::RootDatabase;
-use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_test_plugin::compile_test_prepared_db;
 use tracing::trace_span;
 
-use scarb_ui::components::TypedMessage;
-
-use crate::compiler::helpers::{collect_all_crate_ids, collect_main_crate_ids, write_json};
+use crate::compiler::helpers::{
+    build_diagnostics_reporter, collect_all_crate_ids, collect_main_crate_ids, write_json,
+    DeniedLints,
+};
 use crate::compiler::{CompilationUnit, Compiler};
 use crate::core::{PackageName, SourceId, TargetKind, Workspace};
 
@@ -32,18 +32,13 @@ impl Compiler for TestCompiler {
                 && plugin.package.id.source_id == SourceId::for_std()
         });
 
-        let diagnostics_reporter = DiagnosticsReporter::callback({
-            let config = ws.config();
-            |diagnostic: String| {
-                config
-                    .ui()
-                    .print(TypedMessage::naked_text("diagnostic", &diagnostic));
-            }
-        });
+        let denied_lints = DeniedLints::default();
+        let diagnostics_reporter = build_diagnostics_reporter(&unit, ws, &denied_lints);
 
         diagnostics_reporter
             .with_extra_crates(&main_crate_ids)
             .ensure(db)?;
+        denied_lints.ensure_none()?;
 
         let test_compilation = {
             let _ = trace_span!("compile_test").enter();
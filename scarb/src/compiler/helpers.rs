@@ -2,34 +2,86 @@
 
 use anyhow::{Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
-use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
+use cairo_lang_compiler::diagnostics::{DiagnosticsError, DiagnosticsReporter};
 use cairo_lang_compiler::CompilerConfig;
 use cairo_lang_filesystem::db::FilesGroup;
 use cairo_lang_filesystem::ids::{CrateId, CrateLongId};
 use serde::Serialize;
+use std::cell::Cell;
 use std::io::{BufWriter, Write};
+use std::rc::Rc;
 
 use scarb_ui::components::TypedMessage;
 
 use crate::compiler::CompilationUnit;
-use crate::core::Workspace;
+use crate::core::{split_diagnostics, LintLevel, Workspace};
 use crate::flock::Filesystem;
 
-pub fn build_compiler_config<'c>(unit: &CompilationUnit, ws: &Workspace<'c>) -> CompilerConfig<'c> {
+pub fn build_compiler_config<'c>(
+    unit: &CompilationUnit,
+    ws: &Workspace<'c>,
+    denied_lints: &DeniedLints,
+) -> CompilerConfig<'c> {
     CompilerConfig {
-        diagnostics_reporter: DiagnosticsReporter::callback({
-            let config = ws.config();
-            |diagnostic: String| {
-                config
-                    .ui()
-                    .print(TypedMessage::naked_text("diagnostic", &diagnostic));
-            }
-        }),
+        diagnostics_reporter: build_diagnostics_reporter(unit, ws, denied_lints),
         replace_ids: unit.compiler_config.sierra_replace_ids,
         ..CompilerConfig::default()
     }
 }
 
+/// Build a diagnostics reporter applying lint levels declared by the main package of the unit.
+///
+/// Warnings of allowed lints are not printed, while warnings of denied lints are printed as errors
+/// and counted in `denied_lints`. If the unit denies warnings, all warnings which are not allowed
+/// are treated as denied.
+pub fn build_diagnostics_reporter<'c>(
+    unit: &CompilationUnit,
+    ws: &Workspace<'c>,
+    denied_lints: &DeniedLints,
+) -> DiagnosticsReporter<'c> {
+    let config = ws.config();
+    let lints = unit.main_component().package.manifest.lints.clone();
+    let deny_warnings = unit.compiler_config.deny_warnings;
+    let denied_lints = denied_lints.clone();
+    DiagnosticsReporter::callback(move |diagnostics: String| {
+        let mut reported = String::with_capacity(diagnostics.len());
+        for diagnostic in split_diagnostics(&diagnostics) {
+            let level = match lints.level_of(diagnostic) {
+                Some(LintLevel::Warn) if deny_warnings => Some(LintLevel::Deny),
+                level => level,
+            };
+            match level {
+                Some(LintLevel::Allow) => {}
+                Some(LintLevel::Deny) => {
+                    denied_lints.0.set(denied_lints.0.get() + 1);
+                    reported.push_str("error");
+                    reported.push_str(diagnostic.strip_prefix("warning").unwrap());
+                }
+                Some(LintLevel::Warn) | None => reported.push_str(diagnostic),
+            }
+        }
+        if !reported.is_empty() {
+            config
+                .ui()
+                .print(TypedMessage::naked_text("diagnostic", &reported));
+        }
+    })
+}
+
+/// Number of warnings reported as errors because of lints denied in the package manifest.
+#[derive(Clone, Debug, Default)]
+pub struct DeniedLints(Rc<Cell<usize>>);
+
+impl DeniedLints {
+    /// Fail compilation if any warning of a denied lint has been reported.
+    pub fn ensure_none(&self) -> Result<()> {
+        if self.0.get() > 0 {
+            return Err(DiagnosticsError.into());
+        }
+        Ok(())
+    }
+}
+
 pub fn collect_main_crate_ids(unit: &CompilationUnit, db: &RootDatabase) -> Vec<CrateId> {
     vec![db.intern_crate(CrateLongId::Real(
         unit.main_component().cairo_package_name(),
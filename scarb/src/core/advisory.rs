@@ -0,0 +1,295 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Result};
+use camino::Utf8Path;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use walkdir::WalkDir;
+
+use crate::core::PackageName;
+use crate::internal::fsx;
+use crate::internal::fsx::PathUtf8Ext;
+
+/// A collection of security advisories, loaded from a directory of advisory files.
+///
+/// Each advisory is described by a separate TOML file, placed anywhere within the database
+/// directory, for example `<db>/<package>/<advisory-id>.toml`:
+///
+/// ```toml
+/// [advisory]
+/// id = "SCARB-2024-0001"
+/// package = "foo"
+/// title = "Overflow in token transfers"
+/// severity = "high"
+///
+/// [versions]
+/// patched = [">=1.2.0"]
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: Vec<Advisory>,
+}
+
+/// A known vulnerability, or other issue, affecting some versions of a package.
+#[derive(Clone, Debug, Serialize)]
+pub struct Advisory {
+    pub id: String,
+    pub package: PackageName,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    pub kind: AdvisoryKind,
+    /// Severity of the vulnerability, `None` for informational advisories.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Versions in which the issue has been fixed.
+    pub patched: Vec<VersionReq>,
+    /// Versions which have never been affected by the issue.
+    pub unaffected: Vec<VersionReq>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdvisoryKind {
+    /// A security vulnerability.
+    #[default]
+    Vulnerability,
+    /// The package is no longer maintained, and will not receive fixes.
+    Unmaintained,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlAdvisoryFile {
+    advisory: TomlAdvisory,
+    #[serde(default)]
+    versions: TomlAdvisoryVersions,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlAdvisory {
+    id: String,
+    package: PackageName,
+    title: String,
+    description: Option<String>,
+    url: Option<Url>,
+    #[serde(default)]
+    kind: AdvisoryKind,
+    severity: Option<Severity>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlAdvisoryVersions {
+    #[serde(default)]
+    patched: Vec<VersionReq>,
+    #[serde(default)]
+    unaffected: Vec<VersionReq>,
+}
+
+impl AdvisoryDatabase {
+    pub fn new(advisories: impl IntoIterator<Item = Advisory>) -> Self {
+        Self {
+            advisories: advisories.into_iter().collect(),
+        }
+    }
+
+    /// Load all advisory files (`*.toml`) found within the `path` directory.
+    pub fn load(path: &Utf8Path) -> Result<Self> {
+        ensure!(
+            path.is_dir(),
+            "advisory database directory does not exist: {path}"
+        );
+        let mut advisories = Vec::new();
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            let entry = entry?;
+            let file = entry.path().try_as_utf8()?;
+            if !entry.file_type().is_file() || file.extension() != Some("toml") {
+                continue;
+            }
+            let advisory = fsx::read_to_string(file)
+                .and_then(|content| content.parse::<Advisory>())
+                .with_context(|| format!("failed to load advisory: {file}"))?;
+            advisories.push(advisory);
+        }
+        Ok(Self::new(advisories))
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories affecting this version of the package named `name`.
+    pub fn affecting<'a>(
+        &'a self,
+        name: &'a PackageName,
+        version: &'a Version,
+    ) -> impl Iterator<Item = &'a Advisory> + 'a {
+        self.advisories
+            .iter()
+            .filter(move |advisory| advisory.package == *name && advisory.affects(version))
+    }
+}
+
+impl Advisory {
+    /// Whether `version` of the package is affected, that is, neither patched nor unaffected.
+    pub fn affects(&self, version: &Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .any(|req| req.matches(version))
+    }
+}
+
+impl FromStr for Advisory {
+    type Err = anyhow::Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        let TomlAdvisoryFile { advisory, versions } = toml::from_str(content)?;
+        if advisory.kind == AdvisoryKind::Vulnerability && advisory.severity.is_none() {
+            bail!(
+                "advisory `{}` describes a vulnerability, but does not specify its severity",
+                advisory.id
+            );
+        }
+        Ok(Self {
+            id: advisory.id,
+            package: advisory.package,
+            title: advisory.title,
+            description: advisory.description,
+            url: advisory.url,
+            kind: advisory.kind,
+            severity: advisory.severity,
+            patched: versions.patched,
+            unaffected: versions.unaffected,
+        })
+    }
+}
+
+impl fmt::Display for AdvisoryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdvisoryKind::Vulnerability => f.write_str("vulnerability"),
+            AdvisoryKind::Unmaintained => f.write_str("unmaintained"),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Low => f.write_str("low"),
+            Severity::Medium => f.write_str("medium"),
+            Severity::High => f.write_str("high"),
+            Severity::Critical => f.write_str("critical"),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            _ => bail!("unknown severity `{s}`, expected one of: low, medium, high, critical"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use semver::Version;
+
+    use super::{Advisory, AdvisoryDatabase, AdvisoryKind, Severity};
+    use crate::core::PackageName;
+
+    #[test]
+    fn parse_and_match_versions() {
+        let advisory: Advisory = indoc! {r#"
+            [advisory]
+            id = "SCARB-2024-0001"
+            package = "foo"
+            title = "Overflow in token transfers"
+            severity = "high"
+
+            [versions]
+            patched = [">=1.2.0"]
+            unaffected = ["<1.0.0"]
+        "#}
+        .parse()
+        .unwrap();
+
+        assert_eq!(advisory.kind, AdvisoryKind::Vulnerability);
+        assert_eq!(advisory.severity, Some(Severity::High));
+        assert!(!advisory.affects(&Version::new(0, 9, 0)));
+        assert!(advisory.affects(&Version::new(1, 0, 0)));
+        assert!(advisory.affects(&Version::new(1, 1, 5)));
+        assert!(!advisory.affects(&Version::new(1, 2, 0)));
+
+        let db = AdvisoryDatabase::new([advisory]);
+        let foo = PackageName::new("foo");
+        let bar = PackageName::new("bar");
+        assert_eq!(db.affecting(&foo, &Version::new(1, 0, 0)).count(), 1);
+        assert_eq!(db.affecting(&bar, &Version::new(1, 0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn vulnerability_requires_severity() {
+        let err = indoc! {r#"
+            [advisory]
+            id = "SCARB-2024-0002"
+            package = "foo"
+            title = "Overflow in token transfers"
+        "#}
+        .parse::<Advisory>()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "advisory `SCARB-2024-0002` describes a vulnerability, but does not specify its severity"
+        );
+
+        let advisory = indoc! {r#"
+            [advisory]
+            id = "SCARB-2024-0003"
+            package = "foo"
+            title = "foo is unmaintained"
+            kind = "unmaintained"
+        "#}
+        .parse::<Advisory>()
+        .unwrap();
+        assert_eq!(advisory.severity, None);
+        assert!(advisory.affects(&Version::new(1, 0, 0)));
+    }
+
+    #[test]
+    fn severity_order() {
+        assert!(Severity::Low < Severity::Medium);
+        assert!(Severity::High < Severity::Critical);
+        assert_eq!("medium".parse::<Severity>().unwrap(), Severity::Medium);
+        assert!("severe".parse::<Severity>().is_err());
+    }
+}
@@ -1,12 +1,14 @@
+use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::{env, mem};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use once_cell::sync::OnceCell;
+use smol_str::SmolStr;
 use tokio::runtime::{Builder, Handle, Runtime};
 use tracing::trace;
 use which::which_in;
@@ -15,9 +17,12 @@ use scarb_ui::{OutputFormat, Ui, Verbosity};
 
 use crate::compiler::plugin::CairoPluginRepository;
 use crate::compiler::{CompilerRepository, Profile};
-use crate::core::AppDirs;
 #[cfg(doc)]
 use crate::core::Workspace;
+use crate::core::{
+    AppDirs, ConfigFile, CredentialProvider, CredentialTarget, Credentials, PackageName,
+    RegistryConfig, SourceId,
+};
 use crate::flock::AdvisoryLock;
 use crate::internal::fsx;
 use crate::SCARB_ENV;
@@ -29,6 +34,8 @@ const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VE
 pub struct Config {
     manifest_path: Utf8PathBuf,
     dirs: Arc<AppDirs>,
+    config_file: ConfigFile,
+    config_overrides: Vec<String>,
     target_dir_override: Option<Utf8PathBuf>,
     app_exe: OnceCell<PathBuf>,
     ui: Ui,
@@ -38,6 +45,9 @@ pub struct Config {
     package_cache_lock: OnceCell<AdvisoryLock<'static>>,
     log_filter_directive: OsString,
     offline: bool,
+    locked: bool,
+    frozen: bool,
+    ignore_cairo_version: Option<Vec<PackageName>>,
     compilers: CompilerRepository,
     cairo_plugins: CairoPluginRepository,
     // This is a Dojo-specific feature that will be removed once Dojo is decoupled from Scarb as a library.
@@ -46,6 +56,11 @@ pub struct Config {
     tokio_handle: OnceCell<Handle>,
     profile: Profile,
     http_client: OnceCell<reqwest::Client>,
+    /// HTTP clients with custom TLS settings, keyed by registry name.
+    registry_http_clients: Mutex<HashMap<SmolStr, reqwest::Client>>,
+    credentials: OnceCell<Credentials>,
+    /// Tokens obtained from credential providers, keyed by registry index.
+    registry_tokens: Mutex<HashMap<SourceId, Arc<OnceCell<Option<String>>>>>,
 }
 
 impl Config {
@@ -61,6 +76,7 @@ impl Config {
         let dirs = Arc::new(AppDirs::init(
             b.global_cache_dir_override,
             b.global_config_dir_override,
+            b.global_install_dir_override,
             b.path_env_override,
         )?);
 
@@ -70,6 +86,19 @@ impl Config {
             }
         }
 
+        let project_root = b
+            .manifest_path
+            .parent()
+            .expect("parent of manifest path must always exist");
+        let mut config_file = ConfigFile::load_with_project(&dirs.config_dir, project_root)?;
+        if !b.config_overrides.is_empty() {
+            let cwd = fsx::canonicalize_utf8(env::current_dir()?)?;
+            for arg in &b.config_overrides {
+                config_file = config_file.apply_override(arg, &cwd)?;
+            }
+        }
+        let offline = b.offline || config_file.network.offline();
+
         let compilers = b.compilers.unwrap_or_else(CompilerRepository::std);
         let compiler_plugins = b.cairo_plugins.unwrap_or_else(CairoPluginRepository::std);
         let profile: Profile = b.profile.unwrap_or_default();
@@ -81,13 +110,18 @@ impl Config {
         Ok(Self {
             manifest_path: b.manifest_path,
             dirs,
+            config_file,
+            config_overrides: b.config_overrides,
             target_dir_override: b.target_dir_override,
             app_exe: OnceCell::new(),
             ui,
             creation_time,
             package_cache_lock: OnceCell::new(),
             log_filter_directive: b.log_filter_directive.unwrap_or_default(),
-            offline: b.offline,
+            offline,
+            locked: b.locked,
+            frozen: b.frozen,
+            ignore_cairo_version: b.ignore_cairo_version,
             compilers,
             cairo_plugins: compiler_plugins,
             custom_source_patches: b.custom_source_patches,
@@ -95,6 +129,9 @@ impl Config {
             tokio_handle,
             profile,
             http_client: OnceCell::new(),
+            registry_http_clients: Default::default(),
+            credentials: OnceCell::new(),
+            registry_tokens: Default::default(),
         })
     }
 
@@ -116,6 +153,18 @@ impl Config {
         &self.dirs
     }
 
+    /// Settings loaded from the `config.toml` file in global config directory,
+    /// merged with project-local `.scarb/config.toml`.
+    pub fn config_file(&self) -> &ConfigFile {
+        &self.config_file
+    }
+
+    /// `KEY=VALUE` configuration overrides passed with the `--config` argument, which have been
+    /// applied to [`Config::config_file`].
+    pub fn config_overrides(&self) -> &[String] {
+        &self.config_overrides
+    }
+
     pub fn target_dir_override(&self) -> Option<&Utf8PathBuf> {
         self.target_dir_override.as_ref()
     }
@@ -219,7 +268,25 @@ impl Config {
     /// If `false`, Scarb should never access the network, but otherwise it should continue operating
     /// if possible.
     pub const fn network_allowed(&self) -> bool {
-        !self.offline()
+        !self.offline() && !self.frozen()
+    }
+
+    /// States whether the lockfile must be kept as it is, failing dependency resolution which
+    /// would change it.
+    pub const fn locked(&self) -> bool {
+        self.locked || self.frozen
+    }
+
+    /// States whether the lockfile must be kept as it is, and the network must not be accessed.
+    pub const fn frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// States whether a mismatching `cairo-version` requirement of the package should be ignored.
+    pub fn ignores_cairo_version(&self, package: &PackageName) -> bool {
+        self.ignore_cairo_version
+            .as_ref()
+            .is_some_and(|packages| packages.is_empty() || packages.contains(package))
     }
 
     pub fn compilers(&self) -> &CompilerRepository {
@@ -244,16 +311,152 @@ impl Config {
     /// user agent etc.
     ///
     /// It is fine to clone the returned instance, because it contains [`Arc`] inside.
+    ///
+    /// Proxies are taken from `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables,
+    /// unless a proxy is set in the `[http]` section of the config file.
     pub fn http(&self) -> Result<reqwest::Client> {
         self.http_client
             .get_or_try_init(|| {
-                reqwest::Client::builder()
-                    .user_agent(USER_AGENT)
+                self.http_client_builder()?
                     .build()
                     .context("failed to create HTTP client")
             })
             .cloned()
     }
+
+    /// Returns handle to HTTP client which should be used to talk to registry `source_id`.
+    ///
+    /// If the registry has custom TLS settings (CA certificates or client certificate)
+    /// configured, a dedicated client is created for it. Otherwise, this is the same as
+    /// [`Config::http`].
+    pub fn http_for(&self, source_id: SourceId) -> Result<reqwest::Client> {
+        let Some((name, registry)) = self
+            .config_file
+            .registry_serving(source_id)
+            .filter(|(_, registry)| registry.has_custom_tls())
+        else {
+            return self.http();
+        };
+
+        let mut clients = self.registry_http_clients.lock().unwrap();
+        if let Some(client) = clients.get(name) {
+            return Ok(client.clone());
+        }
+
+        let client = configure_tls(self.http_client_builder()?, registry)
+            .and_then(|builder| Ok(builder.build()?))
+            .with_context(|| format!("failed to create HTTP client for registry `{name}`"))?;
+        clients.insert(name.clone(), client.clone());
+        Ok(client)
+    }
+
+    /// Credentials stored by `scarb login`, loaded lazily from the global config directory.
+    pub fn credentials(&self) -> Result<&Credentials> {
+        self.credentials
+            .get_or_try_init(|| Credentials::load(&self.dirs.config_dir))
+    }
+
+    /// Find the registry whose credentials should be used for `source_id`, and the provider
+    /// storing them.
+    ///
+    /// Mirrors use credentials of the registry they mirror. Registries which are not defined
+    /// in the config file use the default provider.
+    pub fn credential_provider_for(
+        &self,
+        source_id: SourceId,
+    ) -> Result<(CredentialTarget<'_>, CredentialProvider)> {
+        let Some((name, registry)) = self.config_file.registry_serving(source_id) else {
+            let target = CredentialTarget {
+                source_id,
+                name: None,
+            };
+            return Ok((target, CredentialProvider::default()));
+        };
+        let target = CredentialTarget {
+            source_id: SourceId::for_registry(&registry.index)?,
+            name: Some(name.as_str()),
+        };
+        Ok((target, registry.credential_provider.clone()))
+    }
+
+    /// Find authentication token for registry `source_id`.
+    ///
+    /// Tokens are asked for only once per Scarb invocation, so that credential providers
+    /// which mint short-lived tokens are not spawned for each request.
+    pub fn registry_token(&self, source_id: SourceId) -> Result<Option<String>> {
+        let (target, provider) = self.credential_provider_for(source_id)?;
+
+        // The provider is queried outside the lock, so that lookups of other registries do not
+        // wait for it, while concurrent lookups of the same registry wait for the first one.
+        let cell = self
+            .registry_tokens
+            .lock()
+            .unwrap()
+            .entry(target.source_id)
+            .or_default()
+            .clone();
+        let token = cell.get_or_try_init(|| {
+            provider
+                .get(target, self)
+                .with_context(|| format!("failed to get token for registry: {}", target.source_id))
+        })?;
+        Ok(token.clone())
+    }
+
+    fn http_client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder().user_agent(USER_AGENT);
+        if let Some(proxy) = &self.config_file.http.proxy {
+            trace!(
+                "using http proxy from config: {}",
+                proxy.host_str().unwrap_or_default()
+            );
+            let proxy = reqwest::Proxy::all(proxy.as_str())
+                .context("invalid http proxy url")?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder)
+    }
+}
+
+fn configure_tls(
+    mut builder: reqwest::ClientBuilder,
+    registry: &RegistryConfig,
+) -> Result<reqwest::ClientBuilder> {
+    if let Some(path) = &registry.ca_cert {
+        let pem = fsx::read(path).context("failed to read CA certificate")?;
+        let cert = reqwest::Certificate::from_pem(&pem)
+            .with_context(|| format!("failed to parse CA certificate: {path}"))?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    match (&registry.client_cert, &registry.client_key) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = fsx::read(cert_path).context("failed to read client certificate")?;
+            let key = fsx::read(key_path).context("failed to read client certificate key")?;
+            let identity = client_identity(&cert, &key)
+                .with_context(|| format!("failed to load client certificate: {cert_path}"))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => bail!("`client-cert` and `client-key` must be specified together"),
+    }
+
+    Ok(builder)
+}
+
+#[cfg(unix)]
+fn client_identity(cert: &[u8], key: &[u8]) -> Result<reqwest::Identity> {
+    // Rustls accepts a single PEM buffer containing both the key and the certificate chain.
+    let mut pem = key.to_vec();
+    pem.push(b'\n');
+    pem.extend_from_slice(cert);
+    Ok(reqwest::Identity::from_pem(&pem)?)
+}
+
+#[cfg(not(unix))]
+fn client_identity(cert: &[u8], key: &[u8]) -> Result<reqwest::Identity> {
+    Ok(reqwest::Identity::from_pkcs8_pem(cert, key)?)
 }
 
 #[derive(Debug)]
@@ -261,17 +464,22 @@ pub struct ConfigBuilder {
     manifest_path: Utf8PathBuf,
     global_config_dir_override: Option<Utf8PathBuf>,
     global_cache_dir_override: Option<Utf8PathBuf>,
+    global_install_dir_override: Option<Utf8PathBuf>,
     path_env_override: Option<Vec<PathBuf>>,
     target_dir_override: Option<Utf8PathBuf>,
     ui_verbosity: Verbosity,
     ui_output_format: OutputFormat,
     offline: bool,
+    locked: bool,
+    frozen: bool,
+    ignore_cairo_version: Option<Vec<PackageName>>,
     log_filter_directive: Option<OsString>,
     compilers: Option<CompilerRepository>,
     cairo_plugins: Option<CairoPluginRepository>,
     custom_source_patches: Option<Vec<ManifestDependency>>,
     tokio_handle: Option<Handle>,
     profile: Option<Profile>,
+    config_overrides: Vec<String>,
 }
 
 impl ConfigBuilder {
@@ -280,17 +488,22 @@ impl ConfigBuilder {
             manifest_path,
             global_config_dir_override: None,
             global_cache_dir_override: None,
+            global_install_dir_override: None,
             path_env_override: None,
             target_dir_override: None,
             ui_verbosity: Verbosity::Normal,
             ui_output_format: OutputFormat::Text,
             offline: false,
+            locked: false,
+            frozen: false,
+            ignore_cairo_version: None,
             log_filter_directive: None,
             compilers: None,
             cairo_plugins: None,
             custom_source_patches: None,
             tokio_handle: None,
             profile: None,
+            config_overrides: Vec::new(),
         }
     }
 
@@ -314,6 +527,14 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn global_install_dir_override(
+        mut self,
+        global_install_dir_override: Option<impl Into<Utf8PathBuf>>,
+    ) -> Self {
+        self.global_install_dir_override = global_install_dir_override.map(Into::into);
+        self
+    }
+
     pub fn path_env_override(
         mut self,
         path_env_override: Option<impl IntoIterator<Item = impl Into<PathBuf>>>,
@@ -342,6 +563,23 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    pub fn frozen(mut self, frozen: bool) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// Ignore mismatching `cairo-version` requirements of given packages, or of all packages
+    /// if the list is empty.
+    pub fn ignore_cairo_version(mut self, packages: Option<Vec<PackageName>>) -> Self {
+        self.ignore_cairo_version = packages;
+        self
+    }
+
     pub fn log_filter_directive(
         mut self,
         log_filter_directive: Option<impl Into<OsString>>,
@@ -374,4 +612,10 @@ impl ConfigBuilder {
         self.profile = Some(profile);
         self
     }
+
+    /// Configuration values in `KEY=VALUE` form, taking precedence over configuration files.
+    pub fn config_overrides(mut self, config_overrides: Vec<String>) -> Self {
+        self.config_overrides = config_overrides;
+        self
+    }
 }
@@ -0,0 +1,138 @@
+//! Storage of registry tokens in the operating system keyring.
+//!
+//! Instead of linking platform libraries, the keyring is accessed through command line tools
+//! shipped with the system: `security` on macOS and `secret-tool` (libsecret) on other Unix
+//! systems. Tokens are stored under the `scarb` service, keyed by registry index URL.
+
+use anyhow::Result;
+
+use crate::core::SourceId;
+
+#[cfg(unix)]
+const SERVICE: &str = "scarb";
+
+pub fn get(source_id: SourceId) -> Result<Option<String>> {
+    imp::get(source_id.url.as_str())
+}
+
+pub fn store(source_id: SourceId, token: &str) -> Result<()> {
+    imp::store(source_id.url.as_str(), token)
+}
+
+/// Remove the token from the keyring, returning `false` if there was none.
+pub fn erase(source_id: SourceId) -> Result<bool> {
+    imp::erase(source_id.url.as_str())
+}
+
+#[cfg(unix)]
+fn parse_token(stdout: Vec<u8>) -> Option<String> {
+    let token = String::from_utf8(stdout).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::process::Command;
+
+    use anyhow::Result;
+
+    use super::{parse_token, SERVICE};
+    use crate::core::credentials::provider::{ensure_success, run_helper, spawn_helper};
+
+    /// Exit code of `security` when the requested item does not exist.
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = 44;
+
+    pub fn get(url: &str) -> Result<Option<String>> {
+        let mut cmd = Command::new("security");
+        cmd.args(["find-generic-password", "-s", SERVICE, "-a", url, "-w"]);
+        let output = spawn_helper(cmd, None)?;
+        if output.status.code() == Some(ERR_SEC_ITEM_NOT_FOUND) {
+            return Ok(None);
+        }
+        Ok(parse_token(ensure_success("security", output)?.stdout))
+    }
+
+    pub fn store(url: &str, token: &str) -> Result<()> {
+        // With `-w` given last and without a value, `security` prompts for the password and its
+        // confirmation, so the token is not exposed in the process list.
+        let mut cmd = Command::new("security");
+        cmd.args(["add-generic-password", "-U", "-s", SERVICE, "-a", url, "-w"]);
+        run_helper(cmd, Some(&format!("{token}\n{token}\n")))?;
+        Ok(())
+    }
+
+    pub fn erase(url: &str) -> Result<bool> {
+        let mut cmd = Command::new("security");
+        cmd.args(["delete-generic-password", "-s", SERVICE, "-a", url]);
+        let output = spawn_helper(cmd, None)?;
+        if output.status.code() == Some(ERR_SEC_ITEM_NOT_FOUND) {
+            return Ok(false);
+        }
+        ensure_success("security", output)?;
+        Ok(true)
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+    use std::process::Command;
+
+    use anyhow::Result;
+
+    use super::{parse_token, SERVICE};
+    use crate::core::credentials::provider::{ensure_success, run_helper, spawn_helper};
+
+    fn secret_tool(action: &str, url: &str) -> Command {
+        let mut cmd = Command::new("secret-tool");
+        cmd.arg(action);
+        if action == "store" {
+            cmd.args(["--label", &format!("Scarb registry token for {url}")]);
+        }
+        cmd.args(["service", SERVICE, "registry", url]);
+        cmd
+    }
+
+    pub fn get(url: &str) -> Result<Option<String>> {
+        // `secret-tool lookup` exits with non-zero code and prints nothing if there is no item.
+        let output = spawn_helper(secret_tool("lookup", url), None)?;
+        if !output.status.success() && output.stderr.is_empty() {
+            return Ok(None);
+        }
+        Ok(parse_token(ensure_success("secret-tool", output)?.stdout))
+    }
+
+    pub fn store(url: &str, token: &str) -> Result<()> {
+        run_helper(secret_tool("store", url), Some(token))?;
+        Ok(())
+    }
+
+    pub fn erase(url: &str) -> Result<bool> {
+        // `secret-tool clear` succeeds even if there is nothing to remove.
+        if get(url)?.is_none() {
+            return Ok(false);
+        }
+        run_helper(secret_tool("clear", url), None)?;
+        Ok(true)
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use anyhow::{bail, Result};
+
+    const UNSUPPORTED: &str = "the `scarb:keyring` credential provider is not supported on this \
+        platform, use an external credential provider instead";
+
+    pub fn get(_url: &str) -> Result<Option<String>> {
+        bail!(UNSUPPORTED)
+    }
+
+    pub fn store(_url: &str, _token: &str) -> Result<()> {
+        bail!(UNSUPPORTED)
+    }
+
+    pub fn erase(_url: &str) -> Result<bool> {
+        bail!(UNSUPPORTED)
+    }
+}
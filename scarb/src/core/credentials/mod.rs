@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use crate::core::SourceId;
+use crate::flock::Filesystem;
+use crate::internal::fsx;
+
+pub use provider::{CredentialProvider, CredentialTarget};
+
+mod keyring;
+mod provider;
+
+/// Contents of the `credentials.toml` file stored in Scarb's global config directory.
+///
+/// This file is managed by `scarb login` and `scarb logout` commands, for registries which use
+/// the default [`CredentialProvider::Token`] provider.
+/// Registries are identified by their index URL.
+///
+/// ```toml
+/// [registries."https://example.com/"]
+/// token = "secret"
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Credentials {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, RegistryCredentials>,
+}
+
+/// Credentials of a single registry.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RegistryCredentials {
+    /// Token sent in the `Authorization` header of requests to the registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl Credentials {
+    pub const FILE_NAME: &'static str = "credentials.toml";
+
+    /// Load `credentials.toml` from the config directory, returning empty credentials
+    /// if the file is missing.
+    pub fn load(config_dir: &Filesystem<'_>) -> Result<Self> {
+        let path = config_dir.path_unchecked().join(Self::FILE_NAME);
+        if !path.is_file() {
+            trace!("credentials file does not exist: {path}");
+            return Ok(Self::default());
+        }
+
+        let contents = fsx::read_to_string(&path)?;
+        toml::from_str(&contents).with_context(|| format!("failed to parse credentials: {path}"))
+    }
+
+    /// Write `credentials.toml` to the config directory, readable only by the current user.
+    pub fn save(&self, config_dir: &Filesystem<'_>) -> Result<()> {
+        let path = config_dir.path_existent()?.join(Self::FILE_NAME);
+        let contents = toml::to_string_pretty(self)?;
+        write_private(&path, contents)
+    }
+
+    pub fn token_for(&self, source_id: SourceId) -> Option<&str> {
+        self.registries
+            .get(source_id.url.as_str())
+            .and_then(|registry| registry.token.as_deref())
+    }
+
+    pub fn set_token(&mut self, source_id: SourceId, token: String) {
+        self.registries
+            .entry(source_id.url.to_string())
+            .or_default()
+            .token = Some(token);
+    }
+
+    /// Remove token of registry `source_id`, returning `false` if there was none.
+    pub fn remove_token(&mut self, source_id: SourceId) -> bool {
+        let Some(registry) = self.registries.get_mut(source_id.url.as_str()) else {
+            return false;
+        };
+        let removed = registry.token.take().is_some();
+        if registry == &RegistryCredentials::default() {
+            self.registries.remove(source_id.url.as_str());
+        }
+        removed
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Utf8Path, contents: String) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("failed to create `{path}`"))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("failed to write `{path}`"))
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Utf8Path, contents: String) -> Result<()> {
+    fsx::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::core::SourceId;
+
+    use super::Credentials;
+
+    #[test]
+    fn set_and_remove_token() {
+        let source_id =
+            SourceId::for_registry(&Url::parse("https://example.com/").unwrap()).unwrap();
+
+        let mut credentials = Credentials::default();
+        assert_eq!(credentials.token_for(source_id), None);
+
+        credentials.set_token(source_id, "secret".into());
+        assert_eq!(credentials.token_for(source_id), Some("secret"));
+        assert_eq!(
+            toml::to_string(&credentials).unwrap(),
+            "[registries.\"https://example.com/\"]\ntoken = \"secret\"\n"
+        );
+
+        assert!(credentials.remove_token(source_id));
+        assert!(!credentials.remove_token(source_id));
+        assert_eq!(credentials, Credentials::default());
+    }
+}
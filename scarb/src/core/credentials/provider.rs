@@ -0,0 +1,267 @@
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::core::credentials::keyring;
+use crate::core::{Config, Credentials, SourceId};
+
+/// Source of authentication tokens of a registry, selected with the `credential-provider`
+/// field of registry configuration.
+///
+/// ```toml
+/// [registries.internal]
+/// index = "https://registry.example.com/"
+/// credential-provider = "scarb:keyring"
+///
+/// [registries.corporate]
+/// index = "https://scarb.corp.example.com/"
+/// credential-provider = ["/usr/local/bin/mint-token", "--audience", "scarb"]
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ProviderSpec", into = "ProviderSpec")]
+pub enum CredentialProvider {
+    /// Tokens are stored in plaintext in the `credentials.toml` file (`scarb:token`).
+    #[default]
+    Token,
+    /// Tokens are stored in the operating system keyring (`scarb:keyring`).
+    Keyring,
+    /// Tokens are managed by an external process, implementing the credential provider
+    /// protocol.
+    ///
+    /// The command is invoked with one extra argument, the action to perform:
+    /// - `get` - print the token to standard output, or print nothing if there is none,
+    /// - `store` - save the token read from standard input,
+    /// - `erase` - forget the token.
+    ///
+    /// The registry is identified by the `SCARB_REGISTRY_INDEX_URL` environment variable,
+    /// and by `SCARB_REGISTRY_NAME` if it is defined in the config file.
+    Process(Vec<String>),
+}
+
+/// How credential providers are spelled in the config file: either a built-in provider name,
+/// a command line split on whitespace, or an array of program and its arguments.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ProviderSpec {
+    String(String),
+    Args(Vec<String>),
+}
+
+/// Registry for which credentials are requested.
+#[derive(Clone, Copy, Debug)]
+pub struct CredentialTarget<'a> {
+    /// Source ID of the registry index.
+    pub source_id: SourceId,
+    /// Name of the registry in the config file, if it is defined there.
+    pub name: Option<&'a str>,
+}
+
+impl CredentialProvider {
+    const TOKEN: &'static str = "scarb:token";
+    const KEYRING: &'static str = "scarb:keyring";
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Get the token of registry `target`, if there is one.
+    pub fn get(&self, target: CredentialTarget<'_>, config: &Config) -> Result<Option<String>> {
+        debug!("getting token for {} from: {self}", target.source_id);
+        match self {
+            Self::Token => Ok(config
+                .credentials()?
+                .token_for(target.source_id)
+                .map(ToString::to_string)),
+            Self::Keyring => keyring::get(target.source_id),
+            Self::Process(args) => {
+                let output = run_helper(process_command(args, target, "get"), None)?;
+                let token = String::from_utf8(output.stdout).with_context(|| {
+                    format!("credential provider `{self}` returned non UTF-8 token")
+                })?;
+                let token = token.trim();
+                Ok((!token.is_empty()).then(|| token.to_string()))
+            }
+        }
+    }
+
+    /// Save `token` of registry `target`.
+    pub fn store(&self, target: CredentialTarget<'_>, token: &str, config: &Config) -> Result<()> {
+        debug!("storing token for {} in: {self}", target.source_id);
+        match self {
+            Self::Token => {
+                let config_dir = &config.dirs().config_dir;
+                let mut credentials = Credentials::load(config_dir)?;
+                credentials.set_token(target.source_id, token.to_string());
+                credentials.save(config_dir)
+            }
+            Self::Keyring => keyring::store(target.source_id, token),
+            Self::Process(args) => {
+                run_helper(process_command(args, target, "store"), Some(token))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Forget the token of registry `target`, returning `false` if it is known there was none.
+    pub fn erase(&self, target: CredentialTarget<'_>, config: &Config) -> Result<bool> {
+        debug!("erasing token for {} from: {self}", target.source_id);
+        match self {
+            Self::Token => {
+                let config_dir = &config.dirs().config_dir;
+                let mut credentials = Credentials::load(config_dir)?;
+                if !credentials.remove_token(target.source_id) {
+                    return Ok(false);
+                }
+                credentials.save(config_dir)?;
+                Ok(true)
+            }
+            Self::Keyring => keyring::erase(target.source_id),
+            Self::Process(args) => {
+                run_helper(process_command(args, target, "erase"), None)?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CredentialProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token => f.write_str(Self::TOKEN),
+            Self::Keyring => f.write_str(Self::KEYRING),
+            Self::Process(args) => f.write_str(&args.join(" ")),
+        }
+    }
+}
+
+impl TryFrom<ProviderSpec> for CredentialProvider {
+    type Error = anyhow::Error;
+
+    fn try_from(spec: ProviderSpec) -> Result<Self> {
+        let args = match spec {
+            ProviderSpec::String(name) if name == Self::TOKEN => return Ok(Self::Token),
+            ProviderSpec::String(name) if name == Self::KEYRING => return Ok(Self::Keyring),
+            ProviderSpec::String(name) if name.starts_with("scarb:") => {
+                bail!("unknown built-in credential provider: {name}")
+            }
+            ProviderSpec::String(command) => command
+                .split_whitespace()
+                .map(ToString::to_string)
+                .collect(),
+            ProviderSpec::Args(args) => args,
+        };
+        ensure!(
+            !args.is_empty(),
+            "credential provider command must not be empty"
+        );
+        Ok(Self::Process(args))
+    }
+}
+
+impl From<CredentialProvider> for ProviderSpec {
+    fn from(provider: CredentialProvider) -> Self {
+        match provider {
+            CredentialProvider::Process(args) => Self::Args(args),
+            provider => Self::String(provider.to_string()),
+        }
+    }
+}
+
+/// Build a credential provider protocol invocation of `action` for registry `target`.
+fn process_command(args: &[String], target: CredentialTarget<'_>, action: &str) -> Command {
+    let (program, args) = args
+        .split_first()
+        .expect("credential provider command is never empty");
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .arg(action)
+        .env("SCARB_REGISTRY_INDEX_URL", target.source_id.url.as_str());
+    if let Some(name) = target.name {
+        cmd.env("SCARB_REGISTRY_NAME", name);
+    }
+    cmd
+}
+
+/// Run a credential helper process, writing `input` to its standard input,
+/// and fail if it does not exit successfully.
+pub(super) fn run_helper(cmd: Command, input: Option<&str>) -> Result<Output> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    ensure_success(&program, spawn_helper(cmd, input)?)
+}
+
+/// Turn unsuccessful `output` of credential helper `program` into an error.
+pub(super) fn ensure_success(program: &str, output: Output) -> Result<Output> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "credential provider `{program}` failed ({}): {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    Ok(output)
+}
+
+/// Run a credential helper process, writing `input` to its standard input,
+/// and collect its output regardless of exit status.
+pub(super) fn spawn_helper(mut cmd: Command, input: Option<&str>) -> Result<Output> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn credential provider: {program}"))?;
+
+    let mut stdin = child.stdin.take().expect("we asked Rust to pipe stdin");
+    if let Some(input) = input {
+        stdin
+            .write_all(input.as_bytes())
+            .with_context(|| format!("failed to write to credential provider: {program}"))?;
+    }
+    drop(stdin);
+
+    child
+        .wait_with_output()
+        .with_context(|| format!("failed to wait for credential provider: {program}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::CredentialProvider;
+
+    #[test_case(r#""scarb:token""# => CredentialProvider::Token)]
+    #[test_case(r#""scarb:keyring""# => CredentialProvider::Keyring)]
+    #[test_case(r#""mint-token --audience scarb""# => CredentialProvider::Process(vec!["mint-token".into(), "--audience".into(), "scarb".into()]))]
+    #[test_case(r#"["/opt/my tools/mint-token", "get token"]"# => CredentialProvider::Process(vec!["/opt/my tools/mint-token".into(), "get token".into()]))]
+    fn parse(value: &str) -> CredentialProvider {
+        let provider: CredentialProvider = serde_json::from_str(value).unwrap();
+        let roundtrip = serde_json::to_string(&provider).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CredentialProvider>(&roundtrip).unwrap(),
+            provider
+        );
+        provider
+    }
+
+    #[test_case(
+        r#""scarb:unknown""#,
+        "unknown built-in credential provider: scarb:unknown"
+    )]
+    #[test_case(r#""  ""#, "credential provider command must not be empty")]
+    #[test_case("[]", "credential provider command must not be empty")]
+    fn parse_error(value: &str, expected: &str) {
+        let err = serde_json::from_str::<CredentialProvider>(value)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(expected), "{err}");
+    }
+}
@@ -14,6 +14,8 @@ use crate::internal::fsx::PathUtf8Ext;
 pub struct AppDirs {
     pub cache_dir: RootFilesystem,
     pub config_dir: RootFilesystem,
+    /// Directory holding extensions installed with `scarb install`.
+    pub install_dir: RootFilesystem,
     pub path_dirs: Vec<PathBuf>,
 }
 
@@ -21,12 +23,18 @@ impl AppDirs {
     pub(crate) fn init(
         cache_dir_override: Option<Utf8PathBuf>,
         config_dir_override: Option<Utf8PathBuf>,
+        install_dir_override: Option<Utf8PathBuf>,
         path_dirs_override: Option<Vec<PathBuf>>,
     ) -> Result<Self> {
         let pd = ProjectDirs::from("com", "swmansion", "scarb").ok_or_else(|| {
             anyhow!("no valid home directory path could be retrieved from the operating system")
         })?;
 
+        let install_dir = match install_dir_override {
+            Some(p) => p,
+            None => pd.data_local_dir().try_to_utf8()?,
+        };
+
         let path_dirs = match path_dirs_override {
             Some(p) => p,
             None => {
@@ -36,7 +44,7 @@ impl AppDirs {
                     vec![]
                 };
 
-                let home_bin = pd.data_local_dir().join("bin");
+                let home_bin = install_dir.join("bin").into_std_path_buf();
 
                 if !path_dirs.iter().any(|p| p == &home_bin) {
                     path_dirs.push(home_bin);
@@ -59,6 +67,7 @@ impl AppDirs {
         Ok(Self {
             cache_dir: RootFilesystem::new_output_dir(cache_dir),
             config_dir: RootFilesystem::new(config_dir),
+            install_dir: RootFilesystem::new(install_dir),
             path_dirs,
         })
     }
@@ -70,13 +79,25 @@ impl AppDirs {
     pub fn registry_dir(&self) -> Filesystem<'_> {
         self.cache_dir.child("registry")
     }
+
+    pub fn toolchains_dir(&self) -> Filesystem<'_> {
+        self.cache_dir.child("toolchains")
+    }
+
+    /// Directory with executables of extensions installed with `scarb install`.
+    ///
+    /// It is always searched for external subcommands.
+    pub fn install_bin_dir(&self) -> Filesystem<'_> {
+        self.install_dir.child("bin")
+    }
 }
 
 impl fmt::Display for AppDirs {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "cache dir:  {}", self.cache_dir)?;
-        writeln!(f, "config dir: {}", self.config_dir)?;
-        writeln!(f, "PATH:       {}", self.path_env().to_string_lossy())?;
+        writeln!(f, "cache dir:   {}", self.cache_dir)?;
+        writeln!(f, "config dir:  {}", self.config_dir)?;
+        writeln!(f, "install dir: {}", self.install_dir)?;
+        writeln!(f, "PATH:        {}", self.path_env().to_string_lossy())?;
         Ok(())
     }
 }
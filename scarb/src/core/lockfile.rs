@@ -1,12 +1,13 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
 use std::str::FromStr;
 use toml_edit::Document;
 
-use crate::core::{ManifestDependency, PackageId, PackageName, Resolve, SourceId};
+use crate::core::{Checksum, ManifestDependency, PackageId, PackageName, Resolve, SourceId};
 
 const HEADER: &str = "# Code generated by scarb DO NOT EDIT.";
 
@@ -27,6 +28,11 @@ pub struct Lockfile {
     #[serde(default = "BTreeSet::new")]
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     pub packages: BTreeSet<PackageLock>,
+    /// Packages required by build dependencies, which are resolved separately.
+    #[serde(rename = "build-package")]
+    #[serde(default = "BTreeSet::new")]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub build_packages: BTreeSet<PackageLock>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
@@ -36,6 +42,8 @@ pub struct PackageLock {
     pub version: Version,
     #[serde(skip_serializing_if = "skip_path_source_id")]
     pub source: Option<SourceId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<Checksum>,
     #[serde(default = "BTreeSet::new")]
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     pub dependencies: BTreeSet<PackageName>,
@@ -50,6 +58,20 @@ impl Lockfile {
         Self {
             version: Default::default(),
             packages: packages.into_iter().collect(),
+            build_packages: BTreeSet::new(),
+        }
+    }
+
+    /// Lockfile made of the locked packages of build dependencies only.
+    pub fn build_lockfile(&self) -> Self {
+        Self::new(self.build_packages.iter().cloned())
+    }
+
+    /// Record packages of `build` lockfile as locked build dependencies.
+    pub fn with_build_lockfile(self, build: Lockfile) -> Self {
+        Self {
+            build_packages: build.packages,
+            ..self
         }
     }
 
@@ -63,43 +85,137 @@ impl Lockfile {
                     .package_dependencies(package)
                     .filter(include_package)
                     .map(|dep| dep.name.clone());
-                PackageLock::new(&package, deps)
+                let checksum = resolve
+                    .summaries
+                    .get(&package)
+                    .and_then(|summary| summary.checksum.clone());
+                PackageLock::new(&package, deps, checksum)
             });
         Self::new(packages)
     }
 
+    /// Remove lock entries of packages named `name`, so that they are resolved anew.
+    ///
+    /// Returns `false` if no package of this name is locked.
+    pub fn unlock(&mut self, name: &PackageName) -> bool {
+        let count = self.packages.len() + self.build_packages.len();
+        self.packages.retain(|package| package.name != *name);
+        self.build_packages.retain(|package| package.name != *name);
+        count != self.packages.len() + self.build_packages.len()
+    }
+
+    /// Lock packages named `name` to exactly `version`, keeping their sources.
+    ///
+    /// Returns `false` if no package of this name is locked.
+    pub fn lock_precise(&mut self, name: &PackageName, version: &Version) -> bool {
+        let relock = |packages: &mut BTreeSet<PackageLock>| {
+            let (matching, rest): (Vec<_>, Vec<_>) = mem::take(packages)
+                .into_iter()
+                .partition(|package| package.name == *name);
+            let found = !matching.is_empty();
+            packages.extend(rest);
+            packages.extend(matching.into_iter().map(|package| PackageLock {
+                version: version.clone(),
+                // The checksum of the previously locked version does not apply anymore.
+                checksum: None,
+                ..package
+            }));
+            found
+        };
+        let found = relock(&mut self.packages);
+        relock(&mut self.build_packages) || found
+    }
+
+    /// Describe how versions of packages locked in `new` differ from these locked in `self`,
+    /// one change per line, like `Updating foo v1.0.0 -> v1.1.0`.
+    ///
+    /// Changes to dependencies or checksums of locked packages are not described.
+    pub fn diff(&self, new: &Lockfile) -> Vec<String> {
+        let old = self.locked_versions();
+        let new = new.locked_versions();
+
+        let empty = BTreeSet::new();
+        let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+        let mut changes = Vec::new();
+        for key in keys {
+            let name = key.0;
+            let old = old.get(key).unwrap_or(&empty);
+            let new = new.get(key).unwrap_or(&empty);
+            if old.len() == 1 && new.len() == 1 {
+                let (from, to) = (old.first().unwrap(), new.first().unwrap());
+                if from != to {
+                    changes.push(format!("Updating {name} v{from} -> v{to}"));
+                }
+                continue;
+            }
+            for version in old.difference(new) {
+                changes.push(format!("Removing {name} v{version}"));
+            }
+            for version in new.difference(old) {
+                changes.push(format!("Adding {name} v{version}"));
+            }
+        }
+        changes
+    }
+
+    /// Versions of locked packages, including build dependencies, by package name and source.
+    fn locked_versions(&self) -> BTreeMap<(&PackageName, Option<SourceId>), BTreeSet<&Version>> {
+        let mut versions: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for package in self.packages.iter().chain(&self.build_packages) {
+            versions
+                .entry((&package.name, package.source))
+                .or_default()
+                .insert(&package.version);
+        }
+        versions
+    }
+
     pub fn packages(&self) -> impl Iterator<Item = &PackageLock> {
         self.packages.iter()
     }
 
+    /// Find the lock entry matching `dependency`, preferring the newest version if there are
+    /// multiple, like in lockfiles merged from both sides of a merge conflict.
     pub fn packages_matching(&self, dependency: ManifestDependency) -> Option<Result<PackageId>> {
         self.packages()
             .filter(|p| dependency.matches_name_and_version(&p.name, &p.version))
-            .find(|p| {
+            .filter(|p| {
                 p.source
                     .map(|sid| sid.can_lock_source_id(dependency.source_id))
                     // No locking occurs on path sources.
                     .unwrap_or(false)
             })
+            .max_by_key(|p| &p.version)
             .cloned()
             .map(|p| p.try_into())
     }
 
+    /// Find the lock entry of exactly this package.
+    pub fn package(&self, package_id: PackageId) -> Option<&PackageLock> {
+        self.packages().find(|p| {
+            p.name == package_id.name
+                && p.version == package_id.version
+                && p.source == Some(package_id.source_id)
+        })
+    }
+
     fn body(&self) -> Result<Document> {
         let doc = toml_edit::ser::to_string_pretty(self)?;
         let mut doc = doc.parse::<Document>()?;
 
-        for packages in doc["package"].as_array_of_tables_mut().iter_mut() {
-            for pkg in packages.iter_mut() {
-                if let Some(deps) = pkg.get_mut("dependencies") {
-                    if let Some(deps) = deps.as_array_mut() {
-                        deps.iter_mut().for_each(|dep| {
-                            dep.decor_mut().set_prefix("\n ");
-                        });
-                        if deps.len() > 1 {
-                            deps.set_trailing("\n");
-                        } else {
-                            deps.set_trailing(",\n");
+        for key in ["package", "build-package"] {
+            for packages in doc[key].as_array_of_tables_mut().iter_mut() {
+                for pkg in packages.iter_mut() {
+                    if let Some(deps) = pkg.get_mut("dependencies") {
+                        if let Some(deps) = deps.as_array_mut() {
+                            deps.iter_mut().for_each(|dep| {
+                                dep.decor_mut().set_prefix("\n ");
+                            });
+                            if deps.len() > 1 {
+                                deps.set_trailing("\n");
+                            } else {
+                                deps.set_trailing(",\n");
+                            }
                         }
                     }
                 }
@@ -112,6 +228,72 @@ impl Lockfile {
     pub fn render(&self) -> Result<String> {
         Ok(format!("{HEADER}\n{}", self.body()?))
     }
+
+    /// Union of lock entries of both lockfiles.
+    ///
+    /// If both lockfiles lock the same package in different versions, both entries are kept, and
+    /// the resolver uses the newest one matching dependency requirements.
+    /// Entries which turn out to be unused are dropped when the lockfile is written anew.
+    pub fn merge(self, other: Lockfile) -> Self {
+        Self {
+            version: self.version.max(other.version),
+            packages: self.packages.into_iter().chain(other.packages).collect(),
+            build_packages: self
+                .build_packages
+                .into_iter()
+                .chain(other.build_packages)
+                .collect(),
+        }
+    }
+}
+
+/// Split content containing Git merge conflict markers into both sides of the conflict.
+///
+/// Returns `None` if there are no conflict markers. Lines outside conflicts are kept on both sides,
+/// while merge base sections of `diff3` style conflicts are dropped.
+fn split_merge_conflicts(content: &str) -> Result<Option<(String, String)>> {
+    enum Section {
+        Common,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    if !content.lines().any(|line| line.starts_with("<<<<<<<")) {
+        return Ok(None);
+    }
+
+    let mut ours = String::new();
+    let mut theirs = String::new();
+    let mut section = Section::Common;
+    for (no, line) in content.lines().enumerate() {
+        let marker = line.get(..7).unwrap_or_default();
+        section = match (section, marker) {
+            (Section::Common, "<<<<<<<") => Section::Ours,
+            (Section::Ours, "|||||||") => Section::Base,
+            (Section::Ours | Section::Base, "=======") => Section::Theirs,
+            (Section::Theirs, ">>>>>>>") => Section::Common,
+            (_, "<<<<<<<" | "|||||||" | "=======" | ">>>>>>>") => {
+                bail!("unexpected merge conflict marker at line {}", no + 1)
+            }
+            (section, _) => {
+                if matches!(section, Section::Common | Section::Ours) {
+                    ours.push_str(line);
+                    ours.push('\n');
+                }
+                if matches!(section, Section::Common | Section::Theirs) {
+                    theirs.push_str(line);
+                    theirs.push('\n');
+                }
+                section
+            }
+        };
+    }
+    ensure!(
+        matches!(section, Section::Common),
+        "unterminated merge conflict"
+    );
+    Ok(Some((ours, theirs)))
 }
 
 impl FromStr for Lockfile {
@@ -119,19 +301,36 @@ impl FromStr for Lockfile {
 
     fn from_str(content: &str) -> Result<Self> {
         if content.is_empty() {
-            Ok(Self::default())
-        } else {
-            toml::from_str(content).context("failed to parse lockfile content")
+            return Ok(Self::default());
         }
+
+        // Resolve merge conflicts by taking lock entries from both sides, so that the resolver
+        // can pick from them and write a consistent lockfile.
+        let conflicts =
+            split_merge_conflicts(content).context("failed to resolve lockfile merge conflicts")?;
+        if let Some((ours, theirs)) = conflicts {
+            let ours = Self::from_str(&ours)
+                .context("failed to parse our side of lockfile merge conflicts")?;
+            let theirs = Self::from_str(&theirs)
+                .context("failed to parse their side of lockfile merge conflicts")?;
+            return Ok(ours.merge(theirs));
+        }
+
+        toml::from_str(content).context("failed to parse lockfile content")
     }
 }
 
 impl PackageLock {
-    pub fn new(package: &PackageId, dependencies: impl Iterator<Item = PackageName>) -> Self {
+    pub fn new(
+        package: &PackageId,
+        dependencies: impl Iterator<Item = PackageName>,
+        checksum: Option<Checksum>,
+    ) -> Self {
         Self {
             name: package.name.clone(),
             version: package.version.clone(),
             source: Some(package.source_id),
+            checksum,
             dependencies: dependencies.collect(),
         }
     }
@@ -154,12 +353,13 @@ impl TryFrom<PackageLock> for PackageId {
 #[cfg(test)]
 mod tests {
     use crate::core::lockfile::{Lockfile, PackageLock};
-    use crate::core::{PackageId, PackageName, SourceId};
+    use crate::core::{ManifestDependency, PackageId, PackageName, SourceId};
 
     use indoc::indoc;
-    use semver::Version;
+    use semver::{Version, VersionReq};
     use snapbox::assert_eq;
     use std::str::FromStr;
+    use test_case::test_case;
 
     #[test]
     fn simple() {
@@ -170,12 +370,18 @@ mod tests {
                 SourceId::default_registry(),
             ),
             vec![PackageName::new("fourth")].into_iter(),
+            Some(
+                "sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
+                    .parse()
+                    .unwrap(),
+            ),
         );
 
         let pkg2 = PackageLock {
             name: "second".try_into().unwrap(),
             version: Version::parse("1.0.0").unwrap(),
             source: None,
+            checksum: None,
             dependencies: vec![PackageName::new("fourth")].into_iter().collect(),
         };
 
@@ -186,6 +392,7 @@ mod tests {
                 SourceId::mock_git(),
             ),
             vec![].into_iter(),
+            None,
         );
 
         let pkg4 = PackageLock::new(
@@ -195,6 +402,7 @@ mod tests {
                 SourceId::default_registry(),
             ),
             vec![PackageName::new("third")].into_iter(),
+            None,
         );
 
         let lock = Lockfile::new(vec![pkg1, pkg2, pkg3, pkg4]);
@@ -207,6 +415,7 @@ mod tests {
             name = "first"
             version = "1.0.0"
             source = "registry+https://there-is-no-default-registry-yet.com/"
+            checksum = "sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
             dependencies = [
              "fourth",
             ]
@@ -247,4 +456,157 @@ mod tests {
         let deserialized = Lockfile::from_str(serialized).unwrap();
         assert_eq!(lock, deserialized);
     }
+
+    #[test]
+    fn build_packages() {
+        let pkg = PackageLock::new(
+            &PackageId::new(
+                PackageName::new("first"),
+                Version::parse("1.0.0").unwrap(),
+                SourceId::default_registry(),
+            ),
+            vec![].into_iter(),
+            None,
+        );
+        let build_pkg = PackageLock::new(
+            &PackageId::new(
+                PackageName::new("codegen"),
+                Version::parse("0.2.0").unwrap(),
+                SourceId::default_registry(),
+            ),
+            vec![PackageName::new("first")].into_iter(),
+            None,
+        );
+
+        let lock = Lockfile::new(vec![pkg.clone()])
+            .with_build_lockfile(Lockfile::new(vec![build_pkg.clone(), pkg]));
+
+        let serialized = indoc! {r#"
+            # Code generated by scarb DO NOT EDIT.
+            version = 1
+
+            [[package]]
+            name = "first"
+            version = "1.0.0"
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+
+            [[build-package]]
+            name = "codegen"
+            version = "0.2.0"
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+            dependencies = [
+             "first",
+            ]
+
+            [[build-package]]
+            name = "first"
+            version = "1.0.0"
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+        "#};
+
+        assert_eq(serialized, lock.render().unwrap());
+        let deserialized = Lockfile::from_str(serialized).unwrap();
+        assert_eq!(lock, deserialized);
+        assert_eq!(
+            deserialized.build_lockfile().packages().collect::<Vec<_>>(),
+            vec![&build_pkg, lock.packages().next().unwrap()]
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let lock = |packages: &[(&str, &str)]| {
+            Lockfile::new(packages.iter().map(|(name, version)| {
+                PackageLock::new(
+                    &PackageId::new(
+                        PackageName::new(name),
+                        Version::parse(version).unwrap(),
+                        SourceId::default_registry(),
+                    ),
+                    Vec::new().into_iter(),
+                    None,
+                )
+            }))
+        };
+        let old = lock(&[
+            ("bar", "1.0.0"),
+            ("baz", "1.0.0"),
+            ("foo", "1.0.0"),
+            ("qux", "1.0.0"),
+        ]);
+        let new = lock(&[
+            ("bar", "1.1.0"),
+            ("baz", "1.0.0"),
+            ("foo", "1.0.0"),
+            ("foo", "2.0.0"),
+            ("quux", "0.1.0"),
+        ]);
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                "Updating bar v1.0.0 -> v1.1.0",
+                "Adding foo v2.0.0",
+                "Adding quux v0.1.0",
+                "Removing qux v1.0.0",
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        let conflicted = indoc! {r#"
+            # Code generated by scarb DO NOT EDIT.
+            version = 1
+
+            [[package]]
+            name = "bar"
+            <<<<<<< HEAD
+            version = "1.0.0"
+            ||||||| base
+            version = "0.9.0"
+            =======
+            version = "1.1.0"
+            >>>>>>> feature
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+
+            [[package]]
+            <<<<<<< HEAD
+            name = "baz"
+            version = "1.0.0"
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+            =======
+            name = "qux"
+            version = "2.0.0"
+            source = "registry+https://there-is-no-default-registry-yet.com/"
+            >>>>>>> feature
+        "#};
+        let lock = Lockfile::from_str(conflicted).unwrap();
+        let packages = lock
+            .packages()
+            .map(|package| format!("{} {}", package.name, package.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            packages,
+            vec!["bar 1.0.0", "bar 1.1.0", "baz 1.0.0", "qux 2.0.0"]
+        );
+
+        let bar = ManifestDependency::builder()
+            .name(PackageName::new("bar"))
+            .version_req(VersionReq::parse("1").unwrap().into())
+            .source_id(SourceId::default_registry())
+            .build();
+        let locked = lock.packages_matching(bar).unwrap().unwrap();
+        assert_eq!(locked.version, Version::parse("1.1.0").unwrap());
+    }
+
+    #[test_case("<<<<<<< HEAD\nversion = 1\n", "unterminated merge conflict")]
+    #[test_case(
+        "<<<<<<< HEAD\nversion = 1\n>>>>>>> feature\n",
+        "unexpected merge conflict marker at line 3"
+    )]
+    fn invalid_merge_conflicts(content: &str, error: &str) {
+        let err = Lockfile::from_str(content).unwrap_err();
+        assert_eq!(err.root_cause().to_string(), error);
+    }
 }
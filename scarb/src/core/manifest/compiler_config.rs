@@ -15,12 +15,18 @@ pub struct ManifestCompilerConfig {
     /// - For types: `felt252` or `Box<Box<felt252>>`.
     /// - For user functions: `test::foo`.
     pub sierra_replace_ids: bool,
+    /// Report all compiler warnings as errors, failing the compilation.
+    ///
+    /// Warnings of lints allowed in the `[lints]` table are still not reported.
+    #[serde(default)]
+    pub deny_warnings: bool,
 }
 
 impl DefaultForProfile for ManifestCompilerConfig {
     fn default_for_profile(profile: &Profile) -> Self {
         Self {
             sierra_replace_ids: profile.is_dev(),
+            deny_warnings: false,
         }
     }
 }
@@ -29,6 +35,7 @@ impl From<ManifestCompilerConfig> for TomlCairo {
     fn from(config: ManifestCompilerConfig) -> Self {
         Self {
             sierra_replace_ids: Some(config.sierra_replace_ids),
+            deny_warnings: Some(config.deny_warnings),
         }
     }
 }
@@ -4,7 +4,9 @@ use std::ops::Deref;
 use std::sync::Arc;
 use typed_builder::TypedBuilder;
 
-use crate::core::{DependencyVersionReq, PackageId, PackageName, SourceId, Summary, TargetKind};
+use crate::core::{
+    DependencyVersionReq, FeatureName, PackageId, PackageName, SourceId, Summary, TargetKind,
+};
 
 /// See [`ManifestDependencyInner`] for public fields reference.
 #[derive(Clone, Eq, PartialEq, Hash)]
@@ -21,6 +23,21 @@ pub struct ManifestDependencyInner {
     pub source_id: SourceId,
     #[builder(default)]
     pub kind: DepKind,
+    /// Features of the dependency to enable.
+    #[builder(default)]
+    pub features: Vec<FeatureName>,
+    /// Whether to enable the `default` feature of the dependency.
+    #[builder(default = true)]
+    pub default_features: bool,
+    /// Whether this dependency is only used when enabled by a feature.
+    #[builder(default)]
+    pub optional: bool,
+    /// Name under which the dependency is imported, if it differs from the package name.
+    #[builder(default)]
+    pub rename: Option<PackageName>,
+    /// Whether prerelease versions satisfy the version requirement.
+    #[builder(default)]
+    pub allow_prerelease: bool,
 }
 
 #[derive(Clone, Default, Eq, PartialEq, Hash)]
@@ -50,6 +67,30 @@ impl ManifestDependency {
         ManifestDependencyInner::builder()
     }
 
+    /// Returns a copy of this dependency, only used by targets of given `kind`.
+    pub fn with_kind(&self, kind: DepKind) -> Self {
+        ManifestDependencyInner {
+            kind,
+            ..self.0.as_ref().clone()
+        }
+        .into()
+    }
+
+    /// Returns a copy of this dependency, which version requirement is satisfied by prerelease
+    /// versions as well.
+    pub fn allowing_prerelease(&self) -> Self {
+        ManifestDependencyInner {
+            allow_prerelease: true,
+            ..self.0.as_ref().clone()
+        }
+        .into()
+    }
+
+    /// Name under which the dependency is visible to the dependant package.
+    pub fn import_name(&self) -> &PackageName {
+        self.rename.as_ref().unwrap_or(&self.name)
+    }
+
     pub fn matches_summary(&self, summary: &Summary) -> bool {
         self.matches_package_id(summary.package_id)
     }
@@ -59,7 +100,15 @@ impl ManifestDependency {
     }
 
     pub fn matches_name_and_version(&self, name: &PackageName, version: &Version) -> bool {
-        *name == self.name && self.version_req.matches(version)
+        *name == self.name && self.matches_version(version)
+    }
+
+    pub fn matches_version(&self, version: &Version) -> bool {
+        if self.allow_prerelease {
+            self.version_req.matches_prerelease(version)
+        } else {
+            self.version_req.matches(version)
+        }
     }
 }
 
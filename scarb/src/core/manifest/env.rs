@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::ffi::OsString;
+
+use anyhow::{bail, Context, Result};
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::internal::fsx;
+
+/// Name of the dotenv file loaded from the workspace root, unless the profile sets another one.
+pub const DEFAULT_ENV_FILE_NAME: &str = ".env";
+
+/// A value of the `[env]` table, either a plain string or a detailed definition.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum TomlEnvVar {
+    Value(String),
+    Detailed(DetailedTomlEnvVar),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DetailedTomlEnvVar {
+    pub value: String,
+    /// Override the variable even if it is already set in the environment of Scarb.
+    #[serde(default)]
+    pub force: bool,
+    /// Treat the value as a path relative to the directory of the workspace root manifest.
+    #[serde(default)]
+    pub relative: bool,
+}
+
+pub type TomlEnv = BTreeMap<SmolStr, TomlEnvVar>;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct EnvVar {
+    value: String,
+    force: bool,
+}
+
+/// Environment variables declared in the `[env]` table of the workspace root manifest.
+///
+/// These are set for compiler invocations, scripts and external subcommands.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EnvVars(BTreeMap<SmolStr, EnvVar>);
+
+impl EnvVars {
+    /// Resolve variables of the `[env]` table, with relative paths anchored at `root`.
+    pub fn new(toml: TomlEnv, root: &Utf8Path) -> Self {
+        Self(
+            toml.into_iter()
+                .map(|(name, var)| {
+                    let var = match var {
+                        TomlEnvVar::Value(value) => EnvVar {
+                            value,
+                            force: false,
+                        },
+                        TomlEnvVar::Detailed(DetailedTomlEnvVar {
+                            value,
+                            force,
+                            relative,
+                        }) => EnvVar {
+                            value: if relative {
+                                root.join(value).into_string()
+                            } else {
+                                value
+                            },
+                            force,
+                        },
+                    };
+                    (name, var)
+                })
+                .collect(),
+        )
+    }
+
+    /// Add variables defined in a dotenv file, unless already declared.
+    pub fn load_env_file(&mut self, path: &Utf8Path) -> Result<()> {
+        let contents = fsx::read_to_string(path)?;
+        let vars = parse_env_file(&contents)
+            .with_context(|| format!("failed to parse env file: {path}"))?;
+        for (name, value) in vars {
+            self.0.entry(name.into()).or_insert(EnvVar {
+                value,
+                force: false,
+            });
+        }
+        Ok(())
+    }
+
+    /// Values of all variables, as seen by processes spawned by Scarb.
+    ///
+    /// Variables already set in the environment of Scarb keep their value, unless forced.
+    pub fn resolve(&self) -> HashMap<OsString, OsString> {
+        self.resolve_with(|name| env::var_os(name))
+    }
+
+    fn resolve_with(
+        &self,
+        current: impl Fn(&str) -> Option<OsString>,
+    ) -> HashMap<OsString, OsString> {
+        self.0
+            .iter()
+            .map(|(name, var)| {
+                let value = current(name)
+                    .filter(|_| !var.force)
+                    .unwrap_or_else(|| var.value.clone().into());
+                (name.as_str().into(), value)
+            })
+            .collect()
+    }
+
+    /// Set all variables in the environment of the current process, so that they are visible to
+    /// the compiler and its plugins.
+    pub fn apply_to_process(&self) {
+        for (name, value) in self.resolve() {
+            env::set_var(name, value);
+        }
+    }
+}
+
+/// Parse contents of a dotenv file.
+///
+/// Each line holds a `KEY=value` pair, optionally prefixed with `export`.
+/// Values can be wrapped in single quotes, taken literally, or in double quotes, which support
+/// `\n`, `\"` and `\\` escapes.
+/// Empty lines and lines starting with `#` are ignored, as are comments following unquoted values.
+fn parse_env_file(contents: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            bail!("expected `KEY=value` at line {}", index + 1);
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            bail!("invalid variable name `{name}` at line {}", index + 1);
+        }
+        let value = parse_env_value(value.trim())
+            .with_context(|| format!("invalid value of `{name}` at line {}", index + 1))?;
+        vars.push((name.to_string(), value));
+    }
+    Ok(vars)
+}
+
+fn parse_env_value(value: &str) -> Result<String> {
+    if let Some(quoted) = value.strip_prefix('\'') {
+        let Some((literal, _)) = quoted.split_once('\'') else {
+            bail!("unterminated single quote");
+        };
+        return Ok(literal.to_string());
+    }
+    if let Some(quoted) = value.strip_prefix('"') {
+        let mut result = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Ok(result),
+                '\\' => match chars.next() {
+                    Some('n') => result.push('\n'),
+                    Some(c @ ('"' | '\\')) => result.push(c),
+                    Some(c) => {
+                        result.push('\\');
+                        result.push(c);
+                    }
+                    None => break,
+                },
+                c => result.push(c),
+            }
+        }
+        bail!("unterminated double quote");
+    }
+    let value = match value.find(" #") {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+    Ok(value.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+    use std::fs;
+
+    use assert_fs::TempDir;
+    use camino::Utf8Path;
+    use test_case::test_case;
+
+    use super::{parse_env_file, EnvVars, TomlEnv};
+
+    fn env_vars(toml: &str) -> EnvVars {
+        let toml: TomlEnv = toml::from_str(toml).unwrap();
+        EnvVars::new(toml, Utf8Path::new("/ws"))
+    }
+
+    fn resolve(vars: &EnvVars, current: &[(&str, &str)]) -> Vec<(String, String)> {
+        let current: HashMap<&str, &str> = current.iter().copied().collect();
+        let mut resolved = vars
+            .resolve_with(|name| current.get(name).map(OsString::from))
+            .into_iter()
+            .map(|(k, v)| (k.into_string().unwrap(), v.into_string().unwrap()))
+            .collect::<Vec<_>>();
+        resolved.sort();
+        resolved
+    }
+
+    #[test]
+    fn plain_and_detailed_values() {
+        let vars = env_vars(
+            r#"
+            PLAIN = "a"
+            DETAILED = { value = "b" }
+            PATHLIKE = { value = "assets/data", relative = true }
+            "#,
+        );
+        assert_eq!(
+            resolve(&vars, &[]),
+            vec![
+                ("DETAILED".into(), "b".into()),
+                ("PATHLIKE".into(), "/ws/assets/data".into()),
+                ("PLAIN".into(), "a".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_variables_are_kept_unless_forced() {
+        let vars = env_vars(
+            r#"
+            KEPT = "manifest"
+            FORCED = { value = "manifest", force = true }
+            "#,
+        );
+        assert_eq!(
+            resolve(&vars, &[("KEPT", "process"), ("FORCED", "process")]),
+            vec![
+                ("FORCED".into(), "manifest".into()),
+                ("KEPT".into(), "process".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_field_is_rejected() {
+        assert!(toml::from_str::<TomlEnv>(r#"A = { value = "a", forced = true }"#).is_err());
+    }
+
+    #[test]
+    fn parse_dotenv() {
+        let contents = r#"
+            # RPC endpoint
+            RPC_URL=https://example.com/rpc # trailing comment
+            export ACCOUNT = alice
+            SINGLE='literal # $value'
+            DOUBLE="line\nbreak \"quoted\""
+            EMPTY=
+        "#;
+        assert_eq!(
+            parse_env_file(contents).unwrap(),
+            vec![
+                ("RPC_URL".into(), "https://example.com/rpc".into()),
+                ("ACCOUNT".into(), "alice".into()),
+                ("SINGLE".into(), "literal # $value".into()),
+                ("DOUBLE".into(), "line\nbreak \"quoted\"".into()),
+                ("EMPTY".into(), "".into()),
+            ]
+        );
+    }
+
+    #[test_case("NO_VALUE", "expected `KEY=value` at line 1")]
+    #[test_case("BAD NAME=1", "invalid variable name `BAD NAME` at line 1")]
+    #[test_case("A=\"open", "invalid value of `A` at line 1")]
+    #[test_case("A='open", "invalid value of `A` at line 1")]
+    fn parse_invalid_dotenv(contents: &str, expected: &str) {
+        assert_eq!(parse_env_file(contents).unwrap_err().to_string(), expected);
+    }
+
+    #[test]
+    fn env_table_takes_precedence_over_env_file() {
+        let t = TempDir::new().unwrap();
+        let path = Utf8Path::from_path(t.path()).unwrap().join(".env");
+        fs::write(&path, "GREETING=from-file\nTOKEN=secret\n").unwrap();
+        let mut vars = env_vars(r#"GREETING = "from-manifest""#);
+        vars.load_env_file(&path).unwrap();
+        assert_eq!(
+            resolve(&vars, &[]),
+            vec![
+                ("GREETING".into(), "from-manifest".into()),
+                ("TOKEN".into(), "secret".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::core::PackageName;
+
+/// Name of a feature declared in the `[features]` table of a package.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "SmolStr", try_from = "SmolStr")]
+pub struct FeatureName(SmolStr);
+
+impl FeatureName {
+    /// Feature enabled by default, unless dependants opt out with `default-features = false`.
+    pub const DEFAULT: Self = FeatureName(SmolStr::new_inline("default"));
+
+    /// Constructs and validates new [`FeatureName`].
+    ///
+    /// Panics if name does not conform to feature naming rules.
+    pub fn new(name: impl AsRef<str>) -> Self {
+        Self::try_new(name).unwrap()
+    }
+
+    /// Constructs and validates new [`FeatureName`].
+    pub fn try_new(name: impl AsRef<str>) -> Result<Self> {
+        Self::try_new_impl(name.as_ref().into())
+    }
+
+    fn try_new_impl(name: SmolStr) -> Result<Self> {
+        let mut chars = name.chars();
+
+        let Some(first) = chars.next() else {
+            bail!("empty string cannot be used as feature name");
+        };
+
+        if !(first.is_ascii_alphanumeric() || first == '_') {
+            bail!(
+                "invalid character `{first}` in feature name: `{name}`, \
+                the first character must be an ASCII letter, ASCII number or underscore"
+            )
+        }
+
+        for ch in chars {
+            if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-') {
+                bail!(
+                    "invalid character `{ch}` in feature name: `{name}`, \
+                    characters must be ASCII letters, ASCII numbers, underscore or dash"
+                )
+            }
+        }
+
+        Ok(Self(name))
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    #[inline(always)]
+    pub fn to_smol_str(&self) -> SmolStr {
+        self.0.clone()
+    }
+}
+
+impl AsRef<str> for FeatureName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for FeatureName {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<FeatureName> for SmolStr {
+    fn from(value: FeatureName) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<SmolStr> for FeatureName {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SmolStr) -> Result<Self> {
+        FeatureName::try_new_impl(value)
+    }
+}
+
+impl FromStr for FeatureName {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        FeatureName::try_new(name)
+    }
+}
+
+impl fmt::Display for FeatureName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for FeatureName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FeatureName({self})")
+    }
+}
+
+/// A single item of a feature definition, describing what enabling the feature enables.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum FeatureValue {
+    /// Another feature of the same package, written as `<feature>`.
+    Feature(FeatureName),
+    /// An optional dependency, written as `dep:<dependency>`.
+    Dep { dep_name: PackageName },
+    /// A feature of a dependency, written as `<dependency>/<feature>`.
+    ///
+    /// If the dependency is optional, it gets enabled as well.
+    DepFeature {
+        dep_name: PackageName,
+        dep_feature: FeatureName,
+    },
+}
+
+impl FeatureValue {
+    pub fn parse(value: &str) -> Result<Self> {
+        if let Some(dep_name) = value.strip_prefix("dep:") {
+            return Ok(FeatureValue::Dep {
+                dep_name: PackageName::try_new(dep_name)?,
+            });
+        }
+
+        match value.split_once('/') {
+            Some((dep_name, dep_feature)) => Ok(FeatureValue::DepFeature {
+                dep_name: PackageName::try_new(dep_name)?,
+                dep_feature: FeatureName::try_new(dep_feature)?,
+            }),
+            None => Ok(FeatureValue::Feature(FeatureName::try_new(value)?)),
+        }
+    }
+}
+
+impl FromStr for FeatureValue {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        FeatureValue::parse(value)
+    }
+}
+
+impl TryFrom<String> for FeatureValue {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        FeatureValue::parse(&value)
+    }
+}
+
+impl From<FeatureValue> for String {
+    fn from(value: FeatureValue) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for FeatureValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureValue::Feature(name) => write!(f, "{name}"),
+            FeatureValue::Dep { dep_name } => write!(f, "dep:{dep_name}"),
+            FeatureValue::DepFeature {
+                dep_name,
+                dep_feature,
+            } => write!(f, "{dep_name}/{dep_feature}"),
+        }
+    }
+}
+
+/// Determines which packages of the workspace share enabled features.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeatureUnification {
+    /// Features are computed for each compilation unit, from its main package alone.
+    #[default]
+    Unit,
+    /// Features enabled by any workspace member are enabled in all compilation units.
+    Workspace,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{FeatureName, FeatureValue};
+    use crate::core::PackageName;
+
+    #[test_case("foo")]
+    #[test_case("foo-bar")]
+    #[test_case("foo_bar")]
+    #[test_case("_foo")]
+    #[test_case("2d")]
+    #[test_case("Json")]
+    fn validate_correct_feature_name(name: &str) {
+        assert!(FeatureName::try_new(name).is_ok())
+    }
+
+    #[test_case("" => "empty string cannot be used as feature name"; "empty string")]
+    #[test_case("-foo" => "invalid character `-` in feature name: `-foo`, the first character must be an ASCII letter, ASCII number or underscore")]
+    #[test_case("foo.bar" => "invalid character `.` in feature name: `foo.bar`, characters must be ASCII letters, ASCII numbers, underscore or dash")]
+    #[test_case("foo/bar/baz" => "invalid character `/` in feature name: `bar/baz`, characters must be ASCII letters, ASCII numbers, underscore or dash")]
+    fn validate_incorrect_feature_name(name: &str) -> String {
+        FeatureValue::parse(name).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parse_feature_value() {
+        assert_eq!(
+            FeatureValue::parse("json").unwrap(),
+            FeatureValue::Feature(FeatureName::new("json"))
+        );
+        assert_eq!(
+            FeatureValue::parse("serde/std").unwrap(),
+            FeatureValue::DepFeature {
+                dep_name: PackageName::new("serde"),
+                dep_feature: FeatureName::new("std"),
+            }
+        );
+        assert_eq!(
+            FeatureValue::parse("dep:serde").unwrap(),
+            FeatureValue::Dep {
+                dep_name: PackageName::new("serde"),
+            }
+        );
+    }
+}
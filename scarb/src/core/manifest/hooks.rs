@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::manifest::ScriptDefinition;
+
+/// Point of the build process at which a hook script of a package is run.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookKind {
+    /// Before any compilation unit of the package is compiled.
+    PreBuild,
+    /// After all compilation units of the package have been compiled.
+    PostBuild,
+    /// Before files of the package are collected into an archive.
+    PrePackage,
+    /// After the package archive has been written.
+    PostPackage,
+}
+
+impl HookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PreBuild => "pre-build",
+            Self::PostBuild => "post-build",
+            Self::PrePackage => "pre-package",
+            Self::PostPackage => "post-package",
+        }
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub type Hooks = BTreeMap<HookKind, ScriptDefinition>;
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::core::manifest::maybe_workspace::{MaybeWorkspace, WorkspaceInherit};
+
+/// Level at which diagnostics of a lint are reported.
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintLevel {
+    /// Diagnostics are not reported at all.
+    Allow,
+    /// Diagnostics are reported as warnings.
+    #[default]
+    Warn,
+    /// Diagnostics are reported as errors, failing the compilation.
+    Deny,
+}
+
+/// Name of the lint group covering all compiler warnings.
+pub const WARNINGS_LINT: &str = "warnings";
+
+/// Lints which can be configured individually, along with beginnings of the compiler warning
+/// messages they cover.
+const NAMED_LINTS: &[(&str, &str)] = &[
+    ("unused_variables", "Unused variable."),
+    ("unused_imports", "Unused import"),
+    ("unhandled_must_use", "Unhandled `#[must_use]`"),
+];
+
+pub type TomlLints = BTreeMap<SmolStr, LintLevel>;
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlWorkspaceLints {
+    pub workspace: bool,
+}
+
+impl WorkspaceInherit for TomlWorkspaceLints {
+    fn inherit_toml_table(&self) -> &str {
+        "lints"
+    }
+
+    fn workspace(&self) -> bool {
+        self.workspace
+    }
+}
+
+pub type MaybeWorkspaceTomlLints = MaybeWorkspace<TomlLints, TomlWorkspaceLints>;
+
+/// Lint levels declared in the `[lints]` table of a package manifest.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[serde(transparent)]
+pub struct Lints(BTreeMap<SmolStr, LintLevel>);
+
+impl Lints {
+    pub fn new(levels: TomlLints) -> Result<Self> {
+        for name in levels.keys() {
+            if !Self::known().contains(&name.as_str()) {
+                bail!(
+                    "unknown lint `{name}`, expected one of: {}",
+                    Self::known().join(", ")
+                );
+            }
+        }
+        Ok(Self(levels))
+    }
+
+    /// Names of all lints which can be configured.
+    pub fn known() -> Vec<&'static str> {
+        [WARNINGS_LINT]
+            .into_iter()
+            .chain(NAMED_LINTS.iter().map(|(name, _)| *name))
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<LintLevel> {
+        self.0.get(name).copied()
+    }
+
+    /// Level at which a single, formatted compiler diagnostic should be reported.
+    ///
+    /// Returns `None` for diagnostics which are not warnings, as errors cannot be allowed.
+    /// Warnings not covered by any named lint follow the level of the `warnings` group,
+    /// which also applies to named lints that are not configured explicitly.
+    pub fn level_of(&self, diagnostic: &str) -> Option<LintLevel> {
+        let message = diagnostic.strip_prefix("warning: ")?;
+        let named = NAMED_LINTS
+            .iter()
+            .find(|(_, prefix)| message.starts_with(prefix))
+            .and_then(|(name, _)| self.get(name));
+        Some(
+            named
+                .or_else(|| self.get(WARNINGS_LINT))
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// Split a string holding several formatted compiler diagnostics into individual ones.
+pub fn split_diagnostics(diagnostics: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in diagnostics.split_inclusive('\n') {
+        if offset > start && (line.starts_with("error: ") || line.starts_with("warning: ")) {
+            result.push(&diagnostics[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        result.push(&diagnostics[start..offset]);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{split_diagnostics, LintLevel, Lints};
+
+    #[test_case("error: Missing semicolon.\n", &[], None)]
+    #[test_case("warning: Unused variable. Consider ignoring by prefixing with `_`.\n", &[], Some(LintLevel::Warn))]
+    #[test_case("warning: Unused variable. Consider ignoring by prefixing with `_`.\n", &[("warnings", LintLevel::Deny)], Some(LintLevel::Deny))]
+    #[test_case("warning: Unused variable. Consider ignoring by prefixing with `_`.\n", &[("warnings", LintLevel::Deny), ("unused_variables", LintLevel::Allow)], Some(LintLevel::Allow))]
+    #[test_case("warning: Unhandled `#[must_use]` function.\n", &[("unused_variables", LintLevel::Allow)], Some(LintLevel::Warn))]
+    #[test_case("error: Missing semicolon.\n", &[("warnings", LintLevel::Allow)], None)]
+    fn lint_level_of_diagnostic(
+        diagnostic: &str,
+        levels: &[(&str, LintLevel)],
+        expected: Option<LintLevel>,
+    ) {
+        let lints = Lints::new(
+            levels
+                .iter()
+                .map(|(name, level)| ((*name).into(), *level))
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(lints.level_of(diagnostic), expected);
+    }
+
+    #[test]
+    fn unknown_lint() {
+        let err = Lints::new([("unused_things".into(), LintLevel::Allow)].into())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "unknown lint `unused_things`, expected one of: warnings, unused_variables, \
+            unused_imports, unhandled_must_use"
+        );
+    }
+
+    #[test]
+    fn split_multiple_diagnostics() {
+        let diagnostics = "warning: Unused variable.\n --> lib.cairo:1:5\nlet a = 1;\n    ^\n\n\
+            error: Missing semicolon.\n --> lib.cairo:2:1\n";
+        assert_eq!(
+            split_diagnostics(diagnostics),
+            vec![
+                "warning: Unused variable.\n --> lib.cairo:1:5\nlet a = 1;\n    ^\n\n",
+                "error: Missing semicolon.\n --> lib.cairo:2:1\n",
+            ]
+        );
+    }
+}
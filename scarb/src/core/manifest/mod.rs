@@ -10,6 +10,10 @@ use toml::Value;
 
 pub use compiler_config::*;
 pub use dependency::*;
+pub use env::*;
+pub use feature::*;
+pub use hooks::*;
+pub use lints::*;
 pub use maybe_workspace::*;
 pub use scripts::*;
 pub use summary::*;
@@ -23,7 +27,12 @@ use crate::compiler::Profile;
 
 mod compiler_config;
 mod dependency;
+mod env;
+mod feature;
+mod hooks;
+mod lints;
 mod maybe_workspace;
+pub mod schema;
 mod scripts;
 mod summary;
 mod target;
@@ -39,6 +48,9 @@ mod version_req;
 #[non_exhaustive]
 pub struct Manifest {
     pub summary: Summary,
+    /// Dependencies available to build tooling, never compiled together with the package.
+    #[builder(default)]
+    pub build_dependencies: Vec<ManifestDependency>,
     pub targets: Vec<Target>,
     #[builder(default)]
     pub metadata: ManifestMetadata,
@@ -46,6 +58,11 @@ pub struct Manifest {
     pub compiler_config: ManifestCompilerConfig,
     #[builder(default)]
     pub scripts: BTreeMap<SmolStr, ScriptDefinition>,
+    #[builder(default)]
+    pub lints: Lints,
+    /// Scripts run at well-defined points of building and packaging this package.
+    #[builder(default)]
+    pub hooks: Hooks,
 }
 
 /// Subset of a [`Manifest`] that contains package metadata.
@@ -61,8 +78,14 @@ pub struct ManifestMetadata {
     pub license_file: Option<String>,
     pub readme: Option<Utf8PathBuf>,
     pub repository: Option<String>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    /// Names of registries the package can be published to, `None` if unrestricted.
+    pub publish: Option<Vec<SmolStr>>,
     #[serde(rename = "tool")]
     pub tool_metadata: Option<BTreeMap<SmolStr, Value>>,
+    #[serde(rename = "metadata")]
+    pub package_metadata: Option<BTreeMap<SmolStr, Value>>,
     pub cairo_version: Option<VersionReq>,
 }
 
@@ -70,6 +93,7 @@ impl ManifestBuilder {
     fn check(&self) -> Result<()> {
         self.check_cairo_plugin_target_is_exclusive()?;
         self.check_unique_targets()?;
+        self.check_features()?;
         Ok(())
     }
 
@@ -117,4 +141,39 @@ impl ManifestBuilder {
         }
         Ok(())
     }
+
+    fn check_features(&self) -> Result<()> {
+        let Some(summary) = &self.summary else {
+            return Ok(());
+        };
+        let features = &summary.features;
+        for (feature, values) in features {
+            for value in values {
+                match value {
+                    FeatureValue::Feature(name) => ensure!(
+                        features.contains_key(name),
+                        "feature `{feature}` includes `{value}`, \
+                        but `{name}` is not a declared feature"
+                    ),
+                    FeatureValue::Dep { dep_name } => ensure!(
+                        summary
+                            .dependencies
+                            .iter()
+                            .any(|dep| dep.import_name() == dep_name && dep.optional),
+                        "feature `{feature}` includes `{value}`, \
+                        but `{dep_name}` is not an optional dependency"
+                    ),
+                    FeatureValue::DepFeature { dep_name, .. } => ensure!(
+                        summary
+                            .dependencies
+                            .iter()
+                            .any(|dep| dep.import_name() == dep_name),
+                        "feature `{feature}` includes `{value}`, \
+                        but `{dep_name}` is not a dependency"
+                    ),
+                }
+            }
+        }
+        Ok(())
+    }
 }
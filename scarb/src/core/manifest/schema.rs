@@ -0,0 +1,478 @@
+//! Structure of the `Scarb.toml` manifest file, describing which keys each table accepts.
+//!
+//! It is used to warn about keys which are not used by Scarb, which are most often typos,
+//! and to generate the JSON Schema of the manifest, consumed by editors.
+
+use serde_json::{json, Map, Value};
+
+/// Shape of a value in the manifest.
+#[derive(Clone, Copy, Debug)]
+pub enum Shape {
+    /// Any value, not interpreted by Scarb.
+    Any,
+    Bool,
+    String,
+    Array(&'static Shape),
+    /// A table with a fixed set of keys.
+    Table(&'static [(&'static str, Shape)]),
+    /// A table with arbitrary keys, all holding values of the same shape.
+    Map(&'static Shape),
+    /// A value matching any of these shapes.
+    OneOf(&'static [Shape]),
+}
+
+const STRINGS: Shape = Shape::Array(&Shape::String);
+const INHERITED: Shape = Shape::Table(&[("workspace", Shape::Bool)]);
+const INHERITABLE_STRING: Shape = Shape::OneOf(&[Shape::String, INHERITED]);
+const INHERITABLE_STRINGS: Shape = Shape::OneOf(&[STRINGS, INHERITED]);
+
+const SCRIPT: Shape = Shape::OneOf(&[
+    Shape::String,
+    Shape::Table(&[
+        ("script", Shape::String),
+        ("windows", Shape::String),
+        ("unix", Shape::String),
+        ("shell", Shape::String),
+        ("needs", STRINGS),
+    ]),
+]);
+
+const DEPENDENCY: Shape = Shape::OneOf(&[
+    Shape::String,
+    Shape::Table(&[
+        ("version", Shape::String),
+        ("package", Shape::String),
+        ("path", Shape::String),
+        ("git", Shape::String),
+        ("branch", Shape::String),
+        ("tag", Shape::String),
+        ("rev", Shape::String),
+        ("registry", Shape::String),
+        ("features", STRINGS),
+        ("default-features", Shape::Bool),
+        ("optional", Shape::Bool),
+        ("allow-prerelease", Shape::Bool),
+        ("workspace", Shape::Bool),
+    ]),
+]);
+const DEPENDENCIES: Shape = Shape::Map(&DEPENDENCY);
+
+const TOOL_PIN: Shape = Shape::OneOf(&[
+    Shape::String,
+    Shape::Table(&[
+        ("version", Shape::String),
+        ("registry", Shape::String),
+        ("git", Shape::String),
+        ("branch", Shape::String),
+        ("tag", Shape::String),
+        ("rev", Shape::String),
+        ("path", Shape::String),
+    ]),
+]);
+
+const PACKAGE: Shape = Shape::Table(&[
+    ("name", Shape::String),
+    ("version", INHERITABLE_STRING),
+    ("authors", INHERITABLE_STRINGS),
+    (
+        "urls",
+        Shape::OneOf(&[Shape::Map(&Shape::String), INHERITED]),
+    ),
+    ("description", INHERITABLE_STRING),
+    ("documentation", INHERITABLE_STRING),
+    ("homepage", INHERITABLE_STRING),
+    ("keywords", INHERITABLE_STRINGS),
+    ("license", INHERITABLE_STRING),
+    ("license-file", INHERITABLE_STRING),
+    (
+        "readme",
+        Shape::OneOf(&[Shape::String, Shape::Bool, INHERITED]),
+    ),
+    ("repository", INHERITABLE_STRING),
+    ("include", INHERITABLE_STRINGS),
+    ("exclude", INHERITABLE_STRINGS),
+    ("publish", Shape::OneOf(&[Shape::Bool, STRINGS, INHERITED])),
+    ("no-core", Shape::Bool),
+    ("cairo-version", INHERITABLE_STRING),
+    ("metadata", Shape::Map(&Shape::Any)),
+]);
+
+const WORKSPACE: Shape = Shape::Table(&[
+    ("members", STRINGS),
+    ("exclude", STRINGS),
+    ("default-members", STRINGS),
+    (
+        "package",
+        Shape::Table(&[
+            ("version", Shape::String),
+            ("authors", STRINGS),
+            ("urls", Shape::Map(&Shape::String)),
+            ("description", Shape::String),
+            ("documentation", Shape::String),
+            ("homepage", Shape::String),
+            ("keywords", STRINGS),
+            ("license", Shape::String),
+            ("license-file", Shape::String),
+            ("readme", Shape::OneOf(&[Shape::String, Shape::Bool])),
+            ("repository", Shape::String),
+            ("include", STRINGS),
+            ("exclude", STRINGS),
+            ("publish", Shape::OneOf(&[Shape::Bool, STRINGS])),
+            ("cairo-version", Shape::String),
+        ]),
+    ),
+    ("dependencies", DEPENDENCIES),
+    ("scripts", Shape::Map(&SCRIPT)),
+    ("tool", Shape::Map(&Shape::Any)),
+    ("lints", Shape::Map(&Shape::String)),
+    ("feature-unification", Shape::String),
+    ("allow-prerelease", Shape::Bool),
+]);
+
+const LIB_TARGET: Shape = Shape::Table(&[
+    ("name", Shape::String),
+    ("source-path", Shape::String),
+    ("sierra", Shape::Bool),
+    ("casm", Shape::Bool),
+    ("sierra-text", Shape::Bool),
+]);
+
+/// Parameters of targets other than `lib` are interpreted by the tools building them.
+const EXTERNAL_TARGET: Shape = Shape::Map(&Shape::Any);
+
+const CAIRO: Shape = Shape::Table(&[
+    ("sierra-replace-ids", Shape::Bool),
+    ("deny-warnings", Shape::Bool),
+]);
+
+const PROFILE: Shape = Shape::Table(&[
+    ("inherits", Shape::String),
+    ("env-file", Shape::String),
+    ("cairo", CAIRO),
+    ("tool", Shape::Map(&Shape::Any)),
+    (
+        "package",
+        Shape::Map(&Shape::Table(&[
+            ("cairo", CAIRO),
+            ("tool", Shape::Map(&Shape::Any)),
+        ])),
+    ),
+]);
+
+/// Shape of the whole manifest file.
+pub const MANIFEST: Shape = Shape::Table(&[
+    ("package", PACKAGE),
+    ("workspace", WORKSPACE),
+    ("dependencies", DEPENDENCIES),
+    ("target-dependencies", Shape::Map(&DEPENDENCIES)),
+    ("build-dependencies", DEPENDENCIES),
+    ("features", Shape::Map(&STRINGS)),
+    ("lib", LIB_TARGET),
+    ("cairo-plugin", EXTERNAL_TARGET),
+    ("test", Shape::Array(&EXTERNAL_TARGET)),
+    ("target", Shape::Map(&Shape::Array(&EXTERNAL_TARGET))),
+    ("cairo", CAIRO),
+    ("profile", Shape::Map(&PROFILE)),
+    ("scripts", Shape::Map(&Shape::OneOf(&[SCRIPT, INHERITED]))),
+    ("tool", Shape::Map(&Shape::Any)),
+    ("lints", Shape::Map(&Shape::String)),
+    (
+        "hooks",
+        Shape::Table(&[
+            ("pre-build", Shape::String),
+            ("post-build", Shape::String),
+            ("pre-package", Shape::String),
+            ("post-package", Shape::String),
+        ]),
+    ),
+    ("required-tools", Shape::Map(&Shape::String)),
+    ("tools", Shape::Map(&TOOL_PIN)),
+    (
+        "env",
+        Shape::Map(&Shape::OneOf(&[
+            Shape::String,
+            Shape::Table(&[
+                ("value", Shape::String),
+                ("force", Shape::Bool),
+                ("relative", Shape::Bool),
+            ]),
+        ])),
+    ),
+    ("patch", Shape::Map(&DEPENDENCIES)),
+]);
+
+/// A key present in the manifest, which is not used by Scarb.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnusedKey {
+    /// Dotted path to the key, like `package.nmae`.
+    pub path: String,
+    /// A known key with a similar name, if there is one.
+    pub suggestion: Option<&'static str>,
+}
+
+/// Find all keys of `manifest` which are not used by Scarb.
+pub fn find_unused_keys(manifest: &toml::Table) -> Vec<UnusedKey> {
+    let mut unused = Vec::new();
+    walk_table(manifest, &MANIFEST, &mut Vec::new(), &mut unused);
+    unused
+}
+
+fn walk(value: &toml::Value, shape: &Shape, path: &mut Vec<String>, unused: &mut Vec<UnusedKey>) {
+    match value {
+        toml::Value::Table(table) => walk_table(table, shape, path, unused),
+        toml::Value::Array(items) => {
+            let item_shape = alternatives(shape)
+                .into_iter()
+                .find_map(|shape| match shape {
+                    Shape::Array(item) => Some(*item),
+                    _ => None,
+                });
+            if let Some(item_shape) = item_shape {
+                for item in items {
+                    walk(item, item_shape, path, unused);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_table(
+    table: &toml::Table,
+    shape: &Shape,
+    path: &mut Vec<String>,
+    unused: &mut Vec<UnusedKey>,
+) {
+    let mut fields: Vec<&(&'static str, Shape)> = Vec::new();
+    let mut values: Option<&Shape> = None;
+    for shape in alternatives(shape) {
+        match shape {
+            Shape::Any => return,
+            Shape::Table(table_fields) => fields.extend(table_fields.iter()),
+            Shape::Map(value_shape) => values = values.or(Some(*value_shape)),
+            _ => {}
+        }
+    }
+    if fields.is_empty() && values.is_none() {
+        // The value is not expected to be a table at all, deserialization reports this.
+        return;
+    }
+
+    for (key, value) in table {
+        let field_shape = fields
+            .iter()
+            .find(|(name, _)| *name == key.as_str())
+            .map(|(_, shape)| shape)
+            .or(values);
+        path.push(key.clone());
+        match field_shape {
+            Some(field_shape) => walk(value, field_shape, path, unused),
+            None => unused.push(UnusedKey {
+                path: path.join("."),
+                suggestion: suggest(key, fields.iter().map(|(name, _)| *name)),
+            }),
+        }
+        path.pop();
+    }
+}
+
+/// Flatten nested [`Shape::OneOf`] alternatives.
+fn alternatives(shape: &Shape) -> Vec<&Shape> {
+    match shape {
+        Shape::OneOf(shapes) => shapes.iter().flat_map(alternatives).collect(),
+        shape => vec![shape],
+    }
+}
+
+/// Find the known key most similar to `key`, if it is similar enough to be a likely typo.
+fn suggest(key: &str, known: impl Iterator<Item = &'static str>) -> Option<&'static str> {
+    let max_distance = (key.chars().count() / 3).max(2);
+    known
+        .map(|name| (edit_distance(key, name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Generate the JSON Schema of the manifest file.
+pub fn json_schema() -> Value {
+    let mut schema = shape_schema(&MANIFEST);
+    let object = schema
+        .as_object_mut()
+        .expect("manifest schema must be an object");
+    object.insert(
+        "$schema".into(),
+        "https://json-schema.org/draft/2020-12/schema".into(),
+    );
+    object.insert("title".into(), "Scarb.toml".into());
+    schema
+}
+
+fn shape_schema(shape: &Shape) -> Value {
+    match shape {
+        Shape::Any => json!({}),
+        Shape::Bool => json!({ "type": "boolean" }),
+        Shape::String => json!({ "type": "string" }),
+        Shape::Array(item) => json!({ "type": "array", "items": shape_schema(item) }),
+        Shape::Table(fields) => {
+            let properties = fields
+                .iter()
+                .map(|(name, shape)| (name.to_string(), shape_schema(shape)))
+                .collect::<Map<_, _>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            })
+        }
+        Shape::Map(value) => json!({
+            "type": "object",
+            "additionalProperties": shape_schema(value),
+        }),
+        Shape::OneOf(shapes) => json!({
+            "anyOf": shapes.iter().map(shape_schema).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::{edit_distance, find_unused_keys, json_schema, UnusedKey};
+
+    fn unused_keys(manifest: &str) -> Vec<UnusedKey> {
+        let mut unused = find_unused_keys(&toml::from_str(manifest).unwrap());
+        unused.sort_by(|a, b| a.path.cmp(&b.path));
+        unused
+    }
+
+    #[test]
+    fn no_unused_keys() {
+        let manifest = indoc! {r#"
+            [package]
+            name = "hello"
+            version.workspace = true
+            readme = false
+
+            [package.metadata.anything]
+            goes = "here"
+
+            [dependencies]
+            foo = "1.0.0"
+            bar = { path = "../bar", features = ["x"] }
+            baz.workspace = true
+
+            [[target.starknet-contract]]
+            sierra = true
+            allowed-libfuncs-list.name = "experimental"
+
+            [cairo]
+            sierra-replace-ids = true
+
+            [profile.custom]
+            inherits = "release"
+
+            [profile.custom.package.hello.cairo]
+            deny-warnings = true
+
+            [scripts]
+            test.workspace = true
+            build = "echo"
+
+            [tool.fmt]
+            sort-module-level-items = true
+
+            [workspace.lints]
+            warnings = "deny"
+        "#};
+        assert_eq!(unused_keys(manifest), vec![]);
+    }
+
+    #[test]
+    fn reports_unused_keys_with_suggestions() {
+        let manifest = indoc! {r#"
+            [package]
+            nmae = "hello"
+            version = "0.1.0"
+            colour = "blue"
+
+            [dependencies]
+            foo = { verison = "1.0.0" }
+
+            [lib]
+            sierra_text = true
+
+            [profile.dev.cairo]
+            sierra-replace-id = true
+
+            [build]
+            jobs = 2
+        "#};
+        assert_eq!(
+            unused_keys(manifest),
+            vec![
+                UnusedKey {
+                    path: "build".into(),
+                    suggestion: None,
+                },
+                UnusedKey {
+                    path: "dependencies.foo.verison".into(),
+                    suggestion: Some("version"),
+                },
+                UnusedKey {
+                    path: "lib.sierra_text".into(),
+                    suggestion: Some("sierra-text"),
+                },
+                UnusedKey {
+                    path: "package.colour".into(),
+                    suggestion: None,
+                },
+                UnusedKey {
+                    path: "package.nmae".into(),
+                    suggestion: Some("name"),
+                },
+                UnusedKey {
+                    path: "profile.dev.cairo.sierra-replace-id".into(),
+                    suggestion: Some("sierra-replace-ids"),
+                },
+            ]
+        );
+    }
+
+    #[test_case("name", "name", 0)]
+    #[test_case("nmae", "name", 2)]
+    #[test_case("verison", "version", 2)]
+    #[test_case("", "abc", 3)]
+    #[test_case("sierra_text", "sierra-text", 1)]
+    fn distance(a: &str, b: &str, expected: usize) {
+        assert_eq!(edit_distance(a, b), expected);
+    }
+
+    #[test]
+    fn schema_describes_package_table() {
+        let schema = json_schema();
+        assert_eq!(schema["title"], "Scarb.toml");
+        assert_eq!(
+            schema["properties"]["package"]["properties"]["name"],
+            serde_json::json!({ "type": "string" })
+        );
+        assert_eq!(schema["additionalProperties"], false);
+    }
+}
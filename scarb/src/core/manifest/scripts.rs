@@ -1,24 +1,148 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use deno_task_shell::parser::SequentialList;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Serialize, Serializer};
+use serde_untagged::UntaggedEnumVisitor;
+use smol_str::SmolStr;
 use std::ffi::OsString;
 use std::fmt;
 
+/// A script, defined either as a plain command or a table declaring platform-specific commands,
+/// the shell running them, or other scripts it needs to be run first.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptDefinition(DetailedScriptDefinition);
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct ScriptDefinition(String);
+#[serde(deny_unknown_fields)]
+struct DetailedScriptDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    script: Option<String>,
+    /// Command run on Windows instead of `script`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    windows: Option<String>,
+    /// Command run on Unix-like systems instead of `script`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unix: Option<String>,
+    #[serde(default, skip_serializing_if = "ScriptShell::is_builtin")]
+    shell: ScriptShell,
+    /// Scripts which have to finish successfully before this one is run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    needs: Vec<SmolStr>,
+}
+
+/// Shell used to run a script.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScriptShell {
+    /// The cross-platform shell built into Scarb.
+    #[default]
+    Builtin,
+    Sh,
+    Bash,
+    Cmd,
+    Powershell,
+    Pwsh,
+}
+
+impl ScriptShell {
+    fn is_builtin(&self) -> bool {
+        *self == Self::Builtin
+    }
+
+    /// Program running this system shell, along with arguments preceding the command to run.
+    ///
+    /// Returns `None` for the built-in shell.
+    pub fn program(&self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Self::Builtin => None,
+            Self::Sh => Some(("sh", &["-c"])),
+            Self::Bash => Some(("bash", &["-c"])),
+            Self::Cmd => Some(("cmd", &["/C"])),
+            Self::Powershell => Some(("powershell", &["-NoProfile", "-Command"])),
+            Self::Pwsh => Some(("pwsh", &["-NoProfile", "-Command"])),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ScriptDefinition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        UntaggedEnumVisitor::new()
+            .string(|s| Ok(ScriptDefinition::new(s.to_string())))
+            .map(|map| {
+                let detailed: DetailedScriptDefinition = map.deserialize()?;
+                if detailed.script.is_none()
+                    && detailed.windows.is_none()
+                    && detailed.unix.is_none()
+                {
+                    return Err(de::Error::custom(
+                        "script must define at least one of `script`, `windows` or `unix` commands",
+                    ));
+                }
+                Ok(ScriptDefinition(detailed))
+            })
+            .deserialize(deserializer)
+    }
+}
+
+impl Serialize for ScriptDefinition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let detailed = &self.0;
+        match &detailed.script {
+            Some(script)
+                if detailed.windows.is_none()
+                    && detailed.unix.is_none()
+                    && detailed.shell.is_builtin()
+                    && detailed.needs.is_empty() =>
+            {
+                script.serialize(serializer)
+            }
+            _ => detailed.serialize(serializer),
+        }
+    }
+}
 
 impl ScriptDefinition {
     pub fn new(script: String) -> Self {
-        Self(script)
+        Self(DetailedScriptDefinition {
+            script: Some(script),
+            ..Default::default()
+        })
     }
 
-    pub fn parse(&self, args: &[OsString]) -> Result<SequentialList> {
+    /// Command run by this script on the current platform, if any.
+    pub fn command(&self) -> Option<&str> {
+        let platform = if cfg!(windows) {
+            &self.0.windows
+        } else {
+            &self.0.unix
+        };
+        platform.as_deref().or(self.0.script.as_deref())
+    }
+
+    pub fn shell(&self) -> ScriptShell {
+        self.0.shell
+    }
+
+    /// Names of scripts which have to be run before this one.
+    pub fn needs(&self) -> &[SmolStr] {
+        &self.0.needs
+    }
+
+    /// Full command line of this script on the current platform, with `args` appended.
+    pub fn command_line(&self, args: &[OsString]) -> Result<String> {
         // The following implementation has been copied from the `deno_task_shell` crate
         // with slight modifications only.
         // The original implementation can be found here:
         // https://github.com/denoland/deno/blob/c34e26a9d56596645ee63b19f99c09cf4aea4b37/cli/tools/task.rs#L111-L123
 
-        let manifest_script = &self.0;
+        let manifest_script = self.command().ok_or_else(|| {
+            anyhow!(
+                "script does not define a command for this platform, \
+                nor a platform-independent `script` command"
+            )
+        })?;
         let additional_args = args
             .iter()
             // surround all the additional arguments in double quotes
@@ -35,13 +159,78 @@ impl ScriptDefinition {
             .collect::<Vec<_>>()
             .join(" ");
         let full_script = format!("{manifest_script} {additional_args}");
-        let script = full_script.trim().to_owned();
-        deno_task_shell::parser::parse(&script)
+        Ok(full_script.trim().to_owned())
+    }
+
+    pub fn parse(&self, args: &[OsString]) -> Result<SequentialList> {
+        deno_task_shell::parser::parse(&self.command_line(args)?)
     }
 }
 
 impl fmt::Display for ScriptDefinition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(&self.0, f)
+        fmt::Display::fmt(self.command().unwrap_or_default(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::{ScriptDefinition, ScriptShell};
+
+    #[derive(Deserialize)]
+    struct Scripts {
+        script: ScriptDefinition,
+    }
+
+    fn parse(toml: &str) -> Result<ScriptDefinition, toml::de::Error> {
+        toml::from_str::<Scripts>(toml).map(|scripts| scripts.script)
+    }
+
+    #[test_case(r#"script = "echo hi""#, Some("echo hi"))]
+    #[test_case(r#"script = { script = "echo hi" }"#, Some("echo hi"))]
+    #[test_case(r#"script = { unix = "ls", windows = "dir" }"#, Some(if cfg!(windows) { "dir" } else { "ls" }))]
+    #[test_case(r#"script = { script = "echo hi", windows = "echo win" }"#, Some(if cfg!(windows) { "echo win" } else { "echo hi" }))]
+    #[test_case(r#"script = { unix = "ls", shell = "bash" }"#, if cfg!(windows) { None } else { Some("ls") })]
+    fn platform_command(toml: &str, expected: Option<&str>) {
+        assert_eq!(parse(toml).unwrap().command(), expected);
+    }
+
+    #[test]
+    fn shell() {
+        let definition = parse(r#"script = { script = "ls", shell = "pwsh" }"#).unwrap();
+        assert_eq!(definition.shell(), ScriptShell::Pwsh);
+        assert_eq!(
+            definition.shell().program(),
+            Some(("pwsh", &["-NoProfile", "-Command"][..]))
+        );
+    }
+
+    #[test]
+    fn missing_command() {
+        let err = parse(r#"script = { shell = "bash" }"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "script must define at least one of `script`, `windows` or `unix` commands"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let plain = parse(r#"script = { script = "echo hi" }"#).unwrap();
+        assert_eq!(serde_json::to_value(plain).unwrap(), json!("echo hi"));
+        let detailed =
+            parse(r#"script = { unix = "ls", shell = "bash", needs = ["build"] }"#).unwrap();
+        assert_eq!(
+            serde_json::to_value(detailed).unwrap(),
+            json!({"unix": "ls", "shell": "bash", "needs": ["build"]})
+        );
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -8,7 +8,8 @@ use typed_builder::TypedBuilder;
 #[cfg(doc)]
 use crate::core::Manifest;
 use crate::core::{
-    DepKind, DependencyVersionReq, ManifestDependency, PackageId, PackageName, SourceId, TargetKind,
+    Checksum, DepKind, DependencyVersionReq, FeatureName, FeatureValue, ManifestDependency,
+    PackageId, PackageName, SourceId, TargetKind,
 };
 
 /// Subset of a [`Manifest`] that contains only the most important information about a package.
@@ -29,6 +30,12 @@ pub struct SummaryInner {
     pub target_kinds: HashSet<TargetKind>,
     #[builder(default = false)]
     pub no_core: bool,
+    /// Checksum of the package tarball, known for packages coming from registries.
+    #[builder(default)]
+    pub checksum: Option<Checksum>,
+    /// Features declared by this package, along with what each of them enables.
+    #[builder(default)]
+    pub features: BTreeMap<FeatureName, Vec<FeatureValue>>,
 }
 
 impl Deref for Summary {
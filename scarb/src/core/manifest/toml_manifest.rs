@@ -15,19 +15,23 @@ use tracing::trace;
 use url::Url;
 
 use crate::compiler::{DefaultForProfile, Profile};
+use crate::core::config_file::ConfigFile;
 use crate::core::manifest::maybe_workspace::{MaybeWorkspace, WorkspaceInherit};
 use crate::core::manifest::scripts::ScriptDefinition;
 use crate::core::manifest::{ManifestDependency, ManifestMetadata, Summary, Target};
 use crate::core::package::PackageId;
+use crate::core::registry::patch_map::PatchMap;
 use crate::core::source::{GitReference, SourceId};
 use crate::core::{
-    DependencyVersionReq, ManifestBuilder, ManifestCompilerConfig, PackageName, TargetKind,
-    TestTargetProps, TestTargetType,
+    Config, DepKind, DependencyVersionReq, EnvVars, FeatureName, FeatureUnification, FeatureValue,
+    Hooks, Lints, ManifestBuilder, ManifestCompilerConfig, MaybeWorkspaceTomlLints, PackageName,
+    TargetKind, TestTargetProps, TestTargetType, TomlEnv, TomlLints, DEFAULT_ENV_FILE_NAME,
 };
 use crate::internal::fsx;
 use crate::internal::fsx::PathBufUtf8Ext;
 use crate::internal::serdex::{toml_merge, RelativeUtf8PathBuf};
 use crate::internal::to_version::ToVersion;
+use crate::sources::canonical_url::CanonicalUrl;
 use crate::{
     DEFAULT_MODULE_MAIN_FILE, DEFAULT_SOURCE_PATH, DEFAULT_TESTS_PATH, MANIFEST_FILE_NAME,
 };
@@ -41,6 +45,10 @@ pub struct TomlManifest {
     pub package: Option<Box<TomlPackage>>,
     pub workspace: Option<TomlWorkspace>,
     pub dependencies: Option<BTreeMap<PackageName, MaybeTomlWorkspaceDependency>>,
+    pub target_dependencies:
+        Option<BTreeMap<TargetKind, BTreeMap<PackageName, MaybeTomlWorkspaceDependency>>>,
+    pub build_dependencies: Option<BTreeMap<PackageName, MaybeTomlWorkspaceDependency>>,
+    pub features: Option<BTreeMap<FeatureName, Vec<FeatureValue>>>,
     pub lib: Option<TomlTarget<TomlLibTargetParams>>,
     pub cairo_plugin: Option<TomlTarget<TomlExternalTargetParams>>,
     pub test: Option<Vec<TomlTarget<TomlExternalTargetParams>>>,
@@ -49,6 +57,15 @@ pub struct TomlManifest {
     pub profile: Option<TomlProfilesDefinition>,
     pub scripts: Option<BTreeMap<SmolStr, MaybeWorkspaceScriptDefinition>>,
     pub tool: Option<BTreeMap<SmolStr, MaybeWorkspaceTomlTool>>,
+    pub lints: Option<MaybeWorkspaceTomlLints>,
+    pub hooks: Option<Hooks>,
+    /// Version requirements of external subcommands, checked before running them.
+    pub required_tools: Option<BTreeMap<SmolStr, VersionReq>>,
+    /// Pinned packages providing external subcommands, installed into the target directory.
+    pub tools: Option<BTreeMap<PackageName, TomlToolPin>>,
+    /// Environment variables set for compiler invocations, scripts and external subcommands.
+    pub env: Option<TomlEnv>,
+    pub patch: Option<BTreeMap<SmolStr, BTreeMap<PackageName, TomlDependency>>>,
 }
 
 type MaybeWorkspaceScriptDefinition = MaybeWorkspace<ScriptDefinition, WorkspaceScriptDefinition>;
@@ -93,10 +110,15 @@ type TomlToolsDefinition = BTreeMap<SmolStr, toml::Value>;
 #[serde(rename_all = "kebab-case")]
 pub struct TomlWorkspace {
     pub members: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub default_members: Option<Vec<String>>,
     pub package: Option<PackageInheritableFields>,
     pub dependencies: Option<BTreeMap<PackageName, TomlDependency>>,
     pub scripts: Option<BTreeMap<SmolStr, ScriptDefinition>>,
     pub tool: Option<TomlToolsDefinition>,
+    pub lints: Option<TomlLints>,
+    pub feature_unification: Option<FeatureUnification>,
+    pub allow_prerelease: Option<bool>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -104,6 +126,7 @@ pub struct TomlWorkspace {
 pub struct PackageInheritableFields {
     pub version: Option<Version>,
     pub authors: Option<Vec<String>>,
+    pub urls: Option<BTreeMap<String, String>>,
     pub description: Option<String>,
     pub documentation: Option<String>,
     pub homepage: Option<String>,
@@ -112,6 +135,9 @@ pub struct PackageInheritableFields {
     pub license_file: Option<String>,
     pub readme: Option<PathOrBool>,
     pub repository: Option<String>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub publish: Option<TomlPublish>,
     pub cairo_version: Option<VersionReq>,
 }
 
@@ -128,10 +154,12 @@ macro_rules! get_field {
     };
 }
 type VecOfStrings = Vec<String>;
+type Urls = BTreeMap<String, String>;
 
 impl PackageInheritableFields {
     get_field!(version, Version);
     get_field!(authors, VecOfStrings);
+    get_field!(urls, Urls);
     get_field!(keywords, VecOfStrings);
     get_field!(cairo_version, VersionReq);
     get_field!(description, String);
@@ -140,6 +168,9 @@ impl PackageInheritableFields {
     get_field!(license, String);
     get_field!(license_file, String);
     get_field!(repository, String);
+    get_field!(include, VecOfStrings);
+    get_field!(exclude, VecOfStrings);
+    get_field!(publish, TomlPublish);
 
     pub fn readme(&self, workspace_root: &Utf8Path, package_root: &Utf8Path) -> Result<PathOrBool> {
         let Ok(Some(readme)) = readme_for_package(workspace_root, self.readme.as_ref()) else {
@@ -178,7 +209,7 @@ pub struct TomlPackage {
     pub name: PackageName,
     pub version: MaybeWorkspaceField<Version>,
     pub authors: Option<MaybeWorkspaceField<Vec<String>>>,
-    pub urls: Option<BTreeMap<String, String>>,
+    pub urls: Option<MaybeWorkspaceField<BTreeMap<String, String>>>,
     pub description: Option<MaybeWorkspaceField<String>>,
     pub documentation: Option<MaybeWorkspaceField<String>>,
     pub homepage: Option<MaybeWorkspaceField<String>>,
@@ -187,9 +218,17 @@ pub struct TomlPackage {
     pub license_file: Option<MaybeWorkspaceField<String>>,
     pub readme: Option<MaybeWorkspaceField<PathOrBool>>,
     pub repository: Option<MaybeWorkspaceField<String>>,
+    /// Globs of files to put in the package tarball, ignoring VCS ignore files.
+    pub include: Option<MaybeWorkspaceField<Vec<String>>>,
+    /// Globs of files to leave out of the package tarball.
+    pub exclude: Option<MaybeWorkspaceField<Vec<String>>>,
+    /// Whether the package can be published, or names of registries it can be published to.
+    pub publish: Option<MaybeWorkspaceField<TomlPublish>>,
     /// **UNSTABLE** This package does not depend on Cairo's `core`.
     pub no_core: Option<bool>,
     pub cairo_version: Option<MaybeWorkspaceField<VersionReq>>,
+    /// Arbitrary settings of external tools, not interpreted by Scarb.
+    pub metadata: Option<BTreeMap<SmolStr, toml::Value>>,
 }
 
 #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
@@ -223,9 +262,75 @@ impl From<bool> for PathOrBool {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum TomlPublish {
+    Bool(bool),
+    Registries(Vec<SmolStr>),
+}
+
+impl<'de> Deserialize<'de> for TomlPublish {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        UntaggedEnumVisitor::new()
+            .bool(|b| Ok(TomlPublish::Bool(b)))
+            .seq(|seq| seq.deserialize().map(TomlPublish::Registries))
+            .deserialize(deserializer)
+    }
+}
+
+impl TomlPublish {
+    /// Names of registries the package can be published to, `None` if unrestricted.
+    pub fn allowed_registries(&self) -> Option<Vec<SmolStr>> {
+        match self {
+            TomlPublish::Bool(true) => None,
+            TomlPublish::Bool(false) => Some(Vec::new()),
+            TomlPublish::Registries(registries) => Some(registries.clone()),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct TomlWorkspaceDependency {
     pub workspace: bool,
+    /// Features to enable in addition to these enabled by the workspace dependency.
+    pub features: Option<Vec<FeatureName>>,
+    /// Whether the dependency is only used when enabled by a feature.
+    pub optional: Option<bool>,
+    /// Other keys, which are not allowed for inherited dependencies.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, toml::Value>,
+}
+
+impl TomlWorkspaceDependency {
+    /// Apply keys specified next to `workspace = true` to the inherited workspace dependency.
+    fn apply_to(&self, dep: TomlDependency) -> Result<TomlDependency> {
+        if let Some(key) = self.other.keys().next() {
+            bail!(
+                "`{key}` cannot be specified for a dependency inherited from workspace, \
+                only `features` and `optional` can"
+            );
+        }
+        if self.features.is_none() && self.optional.is_none() {
+            return Ok(dep);
+        }
+
+        let mut detailed = dep.resolve().into_owned();
+        if let Some(features) = &self.features {
+            let all_features = detailed.features.get_or_insert_with(Vec::new);
+            for feature in features {
+                if !all_features.contains(feature) {
+                    all_features.push(feature.clone());
+                }
+            }
+        }
+        if let Some(optional) = self.optional {
+            detailed.optional = Some(optional);
+        }
+        Ok(TomlDependency::Detailed(Box::new(detailed)))
+    }
 }
 
 impl WorkspaceInherit for TomlWorkspaceDependency {
@@ -240,7 +345,7 @@ impl WorkspaceInherit for TomlWorkspaceDependency {
 
 type MaybeTomlWorkspaceDependency = MaybeWorkspace<TomlDependency, TomlWorkspaceDependency>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum TomlDependency {
     /// [`VersionReq`] specified as a string, e.g. `package = "<version>"`.
@@ -249,11 +354,14 @@ pub enum TomlDependency {
     Detailed(Box<DetailedTomlDependency>),
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct DetailedTomlDependency {
     pub version: Option<VersionReq>,
 
+    /// Name of the depended upon package, if it differs from the name it is imported as.
+    pub package: Option<PackageName>,
+
     /// Relative to the file it appears in.
     pub path: Option<RelativeUtf8PathBuf>,
 
@@ -263,6 +371,51 @@ pub struct DetailedTomlDependency {
     pub rev: Option<String>,
 
     pub registry: Option<Url>,
+
+    /// Features of the dependency to enable.
+    pub features: Option<Vec<FeatureName>>,
+    /// Whether to enable the `default` feature of the dependency, defaults to `true`.
+    pub default_features: Option<bool>,
+    /// Whether the dependency is only used when enabled by a feature, defaults to `false`.
+    pub optional: Option<bool>,
+    /// Whether prerelease versions satisfy the version requirement, defaults to `false`.
+    pub allow_prerelease: Option<bool>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TomlToolPin {
+    /// Exact version from the default registry, e.g. `package = "<version>"`.
+    Simple(Version),
+    /// Detailed specification as a table, e.g. `package = { git = "<url>", tag = "<tag>" }`.
+    Detailed(Box<DetailedTomlToolPin>),
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DetailedTomlToolPin {
+    pub version: Option<Version>,
+    pub registry: Option<Url>,
+
+    pub git: Option<Url>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub rev: Option<String>,
+
+    /// Relative to the file it appears in.
+    pub path: Option<RelativeUtf8PathBuf>,
+}
+
+impl TomlToolPin {
+    pub fn to_detailed(&self) -> DetailedTomlToolPin {
+        match self {
+            Self::Simple(version) => DetailedTomlToolPin {
+                version: Some(version.clone()),
+                ..Default::default()
+            },
+            Self::Detailed(detailed) => (**detailed).clone(),
+        }
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -300,12 +453,28 @@ pub struct TomlCairo {
     ///
     /// Defaults to `false`.
     pub sierra_replace_ids: Option<bool>,
+    /// Report all compiler warnings as errors, failing the compilation.
+    ///
+    /// Defaults to `false`.
+    pub deny_warnings: Option<bool>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlProfile {
     pub inherits: Option<SmolStr>,
+    /// Path to a dotenv file, relative to the workspace root, loaded into the environment of
+    /// scripts, builds and external subcommands.
+    pub env_file: Option<Utf8PathBuf>,
+    pub cairo: Option<TomlCairo>,
+    pub tool: Option<TomlToolsDefinition>,
+    pub package: Option<BTreeMap<PackageName, TomlProfilePackage>>,
+}
+
+/// Profile settings overridden for a single package, defined in `[profile.<name>.package.<package>]`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlProfilePackage {
     pub cairo: Option<TomlCairo>,
     pub tool: Option<TomlToolsDefinition>,
 }
@@ -319,6 +488,51 @@ impl DefaultForProfile for TomlProfile {
     }
 }
 
+impl TomlProfilePackage {
+    /// Apply these overrides to the manifest of a package built as a dependency.
+    pub fn apply_to(&self, manifest: &mut Manifest) -> Result<()> {
+        if let Some(cairo) = &self.cairo {
+            let base: TomlCairo = manifest.compiler_config.clone().into();
+            let cairo: TomlCairo = toml_merge(&base, cairo)?;
+            if let Some(sierra_replace_ids) = cairo.sierra_replace_ids {
+                manifest.compiler_config.sierra_replace_ids = sierra_replace_ids;
+            }
+            if let Some(deny_warnings) = cairo.deny_warnings {
+                manifest.compiler_config.deny_warnings = deny_warnings;
+            }
+        }
+        if let Some(tool) = &self.tool {
+            manifest.metadata.tool_metadata = Some(match &manifest.metadata.tool_metadata {
+                Some(base) => toml_merge(base, tool)?,
+                None => tool.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TomlProfile {
+    /// Apply settings overridden for the package `name` on top of this profile.
+    fn for_package(mut self, name: &PackageName) -> Result<Self> {
+        let Some(overrides) = self.package.take().and_then(|mut p| p.remove(name)) else {
+            return Ok(self);
+        };
+        if let Some(cairo) = overrides.cairo {
+            self.cairo = Some(match self.cairo {
+                Some(base) => toml_merge(&base, &cairo)?,
+                None => cairo,
+            });
+        }
+        if let Some(tool) = overrides.tool {
+            self.tool = Some(match self.tool {
+                Some(base) => toml_merge(&base, &tool)?,
+                None => tool,
+            });
+        }
+        Ok(self)
+    }
+}
+
 impl TomlManifest {
     pub fn read_from_path(path: &Utf8Path) -> Result<Self> {
         let contents = fs::read_to_string(path)
@@ -405,33 +619,67 @@ impl TomlManifest {
             PackageId::new(name, version, source_id)
         };
 
-        let mut dependencies = Vec::new();
-        for (name, toml_dep) in self.dependencies.iter().flatten() {
+        let target_dependencies = self
+            .target_dependencies
+            .iter()
+            .flatten()
+            .flat_map(|(kind, deps)| deps.iter().map(|dep| (DepKind::Target(kind.clone()), dep)));
+        let all_dependencies = self
+            .dependencies
+            .iter()
+            .flatten()
+            .map(|dep| (DepKind::Normal, dep))
+            .chain(target_dependencies);
+
+        let to_dependency = |name: &PackageName, toml_dep: &MaybeTomlWorkspaceDependency| {
             let inherit_ws = || {
-                workspace
+                let ws_dep = workspace
                     .dependencies
                     .as_ref()
                     .and_then(|deps| deps.get(name.as_str()))
                     .cloned()
-                    .ok_or_else(|| anyhow!("dependency `{}` not found in workspace", name.clone()))?
-                    .to_dependency(name.clone(), workspace_manifest_path)
+                    .ok_or_else(|| {
+                        anyhow!("dependency `{}` not found in workspace", name.clone())
+                    })?;
+                let ws_dep = match toml_dep {
+                    MaybeWorkspace::Workspace(inherit) => inherit.apply_to(ws_dep)?,
+                    MaybeWorkspace::Defined(_) => ws_dep,
+                };
+                ws_dep.to_dependency(name.clone(), workspace_manifest_path)
             };
-            let toml_dep = toml_dep
+            toml_dep
                 .clone()
                 .map(|dep| dep.to_dependency(name.clone(), manifest_path))?
-                .resolve(name.as_str(), inherit_ws)?;
-            dependencies.push(toml_dep);
+                .resolve(name.as_str(), inherit_ws)
+        };
+
+        let mut dependencies = Vec::new();
+        for (kind, (name, toml_dep)) in all_dependencies {
+            dependencies.push(to_dependency(name, toml_dep)?.with_kind(kind));
+        }
+
+        let mut build_dependencies = Vec::new();
+        for (name, toml_dep) in self.build_dependencies.iter().flatten() {
+            let dep = to_dependency(name, toml_dep)?;
+            ensure!(
+                !dep.optional,
+                "build dependency `{name}` cannot be optional"
+            );
+            build_dependencies.push(dep);
         }
 
         let no_core = package.no_core.unwrap_or(false);
 
         let targets = self.collect_targets(package.name.to_smol_str(), root)?;
 
+        let features = self.collect_features(&dependencies)?;
+
         let summary = Summary::builder()
             .target_kinds(targets.iter().map(|t| t.kind.clone()).collect())
             .package_id(package_id)
             .dependencies(dependencies)
             .no_core(no_core)
+            .features(features)
             .build();
 
         let scripts = self.scripts.clone().unwrap_or_default();
@@ -450,17 +698,52 @@ impl TomlManifest {
             })
             .try_collect()?;
 
+        let lints = match self.lints.clone() {
+            None => Lints::default(),
+            Some(MaybeWorkspace::Defined(lints)) => Lints::new(lints)?,
+            Some(MaybeWorkspace::Workspace(_)) => Lints::new(
+                workspace
+                    .lints
+                    .clone()
+                    .context("no `lints` table found in workspace root manifest")?,
+            )?,
+        };
+
         // Following Cargo convention, pull profile config from workspace root only.
         let profile_source = workspace_manifest.unwrap_or(self);
-        let profile_definition = profile_source.collect_profile_definition(profile.clone())?;
+        let profile_definition = profile_source
+            .collect_profile_definition(profile.clone())?
+            .for_package(&package.name)?;
 
         let compiler_config = self.collect_compiler_config(&profile, profile_definition.clone())?;
         let workspace_tool = workspace.tool.clone();
         let tool = self.collect_tool(profile_definition, workspace_tool)?;
 
         let metadata = ManifestMetadata {
-            urls: package.urls.clone(),
+            urls: package
+                .urls
+                .clone()
+                .map(|mw| mw.resolve("urls", || inheritable_package.urls()))
+                .transpose()?,
+            include: package
+                .include
+                .clone()
+                .map(|mw| mw.resolve("include", || inheritable_package.include()))
+                .transpose()?,
+            exclude: package
+                .exclude
+                .clone()
+                .map(|mw| mw.resolve("exclude", || inheritable_package.exclude()))
+                .transpose()?,
+            publish: package
+                .publish
+                .clone()
+                .map(|mw| mw.resolve("publish", || inheritable_package.publish()))
+                .transpose()?
+                .as_ref()
+                .and_then(TomlPublish::allowed_registries),
             tool_metadata: tool,
+            package_metadata: package.metadata.clone(),
             authors: package
                 .authors
                 .clone()
@@ -523,15 +806,44 @@ impl TomlManifest {
 
         let manifest = ManifestBuilder::default()
             .summary(summary)
+            .build_dependencies(build_dependencies)
             .targets(targets)
             .metadata(metadata)
             .compiler_config(compiler_config)
             .scripts(scripts)
+            .hooks(self.hooks.clone().unwrap_or_default())
+            .lints(lints)
             .build()?;
 
         Ok(manifest)
     }
 
+    /// Collect declared features, adding an implicit feature for each optional dependency
+    /// which is not explicitly enabled with `dep:<dependency>` by any of them.
+    fn collect_features(
+        &self,
+        dependencies: &[ManifestDependency],
+    ) -> Result<BTreeMap<FeatureName, Vec<FeatureValue>>> {
+        let mut features = self.features.clone().unwrap_or_default();
+        for dep in dependencies.iter().filter(|dep| dep.optional) {
+            let name = dep.import_name();
+            let is_explicit = features
+                .values()
+                .flatten()
+                .any(|value| matches!(value, FeatureValue::Dep { dep_name } if dep_name == name));
+            if !is_explicit {
+                features
+                    .entry(FeatureName::try_new(name)?)
+                    .or_insert_with(|| {
+                        vec![FeatureValue::Dep {
+                            dep_name: name.clone(),
+                        }]
+                    });
+            }
+        }
+        Ok(features)
+    }
+
     fn collect_targets(&self, package_name: SmolStr, root: &Utf8Path) -> Result<Vec<Target>> {
         let mut targets = Vec::new();
 
@@ -702,15 +1014,78 @@ impl TomlManifest {
             .unwrap_or(Ok(vec![]))
     }
 
+    /// Collect variables of the `[env]` table, resolving relative paths against the directory
+    /// of this manifest.
+    ///
+    /// Variables from the dotenv file of `profile` are added as well, unless declared in the
+    /// `[env]` table.
+    /// Without an `env-file` set in the profile, the `.env` file placed next to this manifest is
+    /// loaded, if it exists.
+    pub fn collect_env(&self, manifest_path: &Utf8Path, profile: Profile) -> Result<EnvVars> {
+        let root = manifest_path
+            .parent()
+            .expect("manifest path parent must always exist");
+        let mut env = EnvVars::new(self.env.clone().unwrap_or_default(), root);
+        match self.collect_profile_definition(profile)?.env_file {
+            Some(env_file) => env.load_env_file(&root.join(env_file))?,
+            None => {
+                let default_env_file = root.join(DEFAULT_ENV_FILE_NAME);
+                if default_env_file.is_file() {
+                    env.load_env_file(&default_env_file)?;
+                }
+            }
+        }
+        Ok(env)
+    }
+
+    /// Collect settings overridden for individual packages in the `[profile]` table.
+    pub fn collect_profile_package_overrides(
+        &self,
+        profile: Profile,
+    ) -> Result<BTreeMap<PackageName, TomlProfilePackage>> {
+        Ok(self
+            .collect_profile_definition(profile)?
+            .package
+            .unwrap_or_default())
+    }
+
+    /// Collect dependencies replacing packages of other sources from the `[patch]` table.
+    pub fn collect_patches(&self, manifest_path: &Utf8Path, config: &Config) -> Result<PatchMap> {
+        let mut patch_map = PatchMap::new();
+        insert_patches(
+            &mut patch_map,
+            self.patch.iter().flatten(),
+            manifest_path,
+            config,
+        )?;
+        Ok(patch_map)
+    }
+
     fn collect_profile_definition(&self, profile: Profile) -> Result<TomlProfile> {
-        let toml_cairo = self.cairo.clone().unwrap_or_default();
-        let toml_profiles = self.profile.clone();
+        self.collect_profile_definition_chain(profile, &mut Vec::new())
+    }
 
-        let profile_definition = toml_profiles
-            .clone()
-            .unwrap_or_default()
-            .get(profile.as_str())
-            .cloned();
+    /// Collect definition of `profile`, resolving its parent profiles recursively.
+    ///
+    /// The `chain` holds profiles inheriting from `profile`, used to detect inheritance cycles.
+    fn collect_profile_definition_chain(
+        &self,
+        profile: Profile,
+        chain: &mut Vec<Profile>,
+    ) -> Result<TomlProfile> {
+        let toml_profiles = self.profile.clone().unwrap_or_default();
+
+        if chain.contains(&profile) {
+            let cycle = chain
+                .iter()
+                .chain([&profile])
+                .map(|p| format!("`{}`", p.as_str()))
+                .join(" -> ");
+            bail!("profile inheritance cycle detected: {cycle}");
+        }
+        chain.push(profile.clone());
+
+        let profile_definition = toml_profiles.get(profile.as_str()).cloned();
 
         let parent_profile = profile_definition
             .clone()
@@ -725,27 +1100,31 @@ impl TomlManifest {
                 }
             })?;
 
-        if parent_profile.is_custom() {
-            bail!(
-                "profile can inherit from `dev` or `release` only, found `{}`",
+        let parent_definition = if parent_profile == profile && !profile.is_custom() {
+            // Built-in profiles are based on their defaults, overridden by the `[cairo]` section.
+            let toml_cairo = self.cairo.clone().unwrap_or_default();
+            let parent_default = TomlProfile::default_for_profile(&parent_profile);
+            let parent_definition = profile_definition.clone().unwrap_or(parent_default.clone());
+            let mut parent_definition = toml_merge(&parent_default, &parent_definition)?;
+            parent_definition.cairo = toml_merge(&parent_definition.cairo, &toml_cairo)?;
+            parent_definition
+        } else {
+            ensure!(
+                !parent_profile.is_custom() || toml_profiles.contains_key(parent_profile.as_str()),
+                "profile `{}` inherits from `{}`, which is not defined",
+                profile.as_str(),
                 parent_profile.as_str()
             );
-        }
-
-        let parent_default = TomlProfile::default_for_profile(&parent_profile);
-        let parent_definition = toml_profiles
-            .unwrap_or_default()
-            .get(parent_profile.as_str())
-            .cloned()
-            .unwrap_or(parent_default.clone());
-
-        let mut parent_definition = toml_merge(&parent_default, &parent_definition)?;
-
-        let parent_cairo = toml_merge(&parent_definition.cairo, &toml_cairo)?;
-        parent_definition.cairo = parent_cairo;
+            self.collect_profile_definition_chain(parent_profile, chain)?
+        };
 
         let profile = if let Some(profile_definition) = profile_definition {
-            toml_merge(&parent_definition, &profile_definition)?
+            // Package overrides are merged per package, instead of being replaced as a whole.
+            let mut packages = parent_definition.package.clone().unwrap_or_default();
+            packages.extend(profile_definition.package.clone().unwrap_or_default());
+            let mut profile: TomlProfile = toml_merge(&parent_definition, &profile_definition)?;
+            profile.package = (!packages.is_empty()).then_some(packages);
+            profile
         } else {
             parent_definition
         };
@@ -763,6 +1142,9 @@ impl TomlManifest {
             if let Some(sierra_replace_ids) = cairo.sierra_replace_ids {
                 compiler_config.sierra_replace_ids = sierra_replace_ids;
             }
+            if let Some(deny_warnings) = cairo.deny_warnings {
+                compiler_config.deny_warnings = deny_warnings;
+            }
         }
         Ok(compiler_config)
     }
@@ -846,6 +1228,43 @@ fn default_readme_from_package_root(package_root: &Utf8Path) -> Option<&Utf8Path
     None
 }
 
+/// Add patches from a `[patch]` table to `patch_map`, overriding existing ones.
+///
+/// Sources are identified either by URL, by the name of a registry defined in Scarb
+/// configuration, or by `default` for the default registry.
+/// Relative paths of patch dependencies are resolved against the parent directory of `path`.
+pub fn insert_patches<'a>(
+    patch_map: &mut PatchMap,
+    patches: impl IntoIterator<Item = (&'a SmolStr, &'a BTreeMap<PackageName, TomlDependency>)>,
+    path: &Utf8Path,
+    config: &Config,
+) -> Result<()> {
+    for (source, patches) in patches {
+        let url = if source == ConfigFile::DEFAULT_SOURCE_NAME {
+            SourceId::default_registry().url.clone()
+        } else if let Some(registry) = config.config_file().registries.get(source) {
+            registry.index.clone()
+        } else {
+            Url::parse(source).map_err(|_| {
+                anyhow!(
+                    "`[patch.{source}]` must refer to a URL or a registry \
+                    defined in Scarb configuration"
+                )
+            })?
+        };
+
+        let dependencies = patches
+            .iter()
+            .map(|(name, dep)| {
+                dep.to_dependency(name.clone(), path)
+                    .with_context(|| format!("invalid patch for `{name}` in `[patch.{source}]`"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        patch_map.insert(CanonicalUrl::new(&url)?, dependencies);
+    }
+    Ok(())
+}
+
 impl TomlDependency {
     fn to_dependency(
         &self,
@@ -930,10 +1349,20 @@ impl DetailedTomlDependency {
             (Some(_), None, None, None) => SourceId::default(),
         };
 
+        let (name, rename) = match &self.package {
+            Some(package) if *package != name => (package.clone(), Some(name)),
+            _ => (name, None),
+        };
+
         Ok(ManifestDependency::builder()
             .name(name)
+            .rename(rename)
             .source_id(source_id)
             .version_req(version_req)
+            .features(self.features.clone().unwrap_or_default())
+            .default_features(self.default_features.unwrap_or(true))
+            .optional(self.optional.unwrap_or(false))
+            .allow_prerelease(self.allow_prerelease.unwrap_or(false))
             .build())
     }
 }
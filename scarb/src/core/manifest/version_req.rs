@@ -1,6 +1,6 @@
 use std::fmt;
 
-use semver::{Comparator, Op, Version, VersionReq};
+use semver::{Comparator, Op, Prerelease, Version, VersionReq};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
 pub enum DependencyVersionReq {
@@ -40,6 +40,32 @@ impl DependencyVersionReq {
             }
         }
     }
+
+    /// Like [`DependencyVersionReq::matches`], but prerelease versions satisfy the requirement
+    /// whenever they fall within the required range, even if the requirement itself does not
+    /// mention any prerelease.
+    ///
+    /// For example, `^1.0` matches `1.3.0-rc.1`, but `^1.3.0` does not, because `1.3.0-rc.1`
+    /// precedes `1.3.0`.
+    pub fn matches_prerelease(&self, version: &Version) -> bool {
+        match self {
+            DependencyVersionReq::Req(req) if !version.pre.is_empty() => {
+                // Semver only lets prerelease versions match comparators on the same
+                // `major.minor.patch` with a prerelease part. Such a comparator is added here,
+                // which lowest possible prerelease accepts all prereleases of the version.
+                let mut req = req.clone();
+                req.comparators.push(Comparator {
+                    op: Op::GreaterEq,
+                    major: version.major,
+                    minor: Some(version.minor),
+                    patch: Some(version.patch),
+                    pre: Prerelease::new("0").unwrap(),
+                });
+                req.matches(version)
+            }
+            _ => self.matches(version),
+        }
+    }
 }
 
 impl From<VersionReq> for DependencyVersionReq {
@@ -67,3 +93,26 @@ impl fmt::Display for DependencyVersionReq {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use semver::{Version, VersionReq};
+    use test_case::test_case;
+
+    use super::DependencyVersionReq;
+
+    #[test_case("1", "1.3.0-rc.1", true)]
+    #[test_case("1.0", "1.0.1-beta", true)]
+    #[test_case("1.3.0", "1.3.0-rc.1", false)]
+    #[test_case("1", "2.0.0-rc.1", false)]
+    #[test_case("~1.2", "1.2.5-alpha", true)]
+    #[test_case(">=1.0, <2", "1.5.0-rc.1", true)]
+    #[test_case("=1.0.0", "1.0.0-rc.1", false)]
+    #[test_case("1.3.0-rc.1", "1.3.0-rc.2", true)]
+    #[test_case("1", "1.2.0", true)]
+    fn matches_prerelease(req: &str, version: &str, expected: bool) {
+        let req = DependencyVersionReq::from(VersionReq::parse(req).unwrap());
+        let version = Version::parse(version).unwrap();
+        assert_eq!(req.matches_prerelease(&version), expected);
+    }
+}
@@ -2,17 +2,27 @@
 //!
 //! For read operations and workspace mutations, see [`crate::ops`] module.
 
+pub use advisory::{Advisory, AdvisoryDatabase, AdvisoryKind, Severity};
 pub use checksum::*;
 pub use config::Config;
+pub use config_file::{
+    AliasDefinition, ConfigFile, DownloadsConfig, HttpConfig, RegistryConfig, ReleaseChannel,
+    SelfUpdateConfig, SourceConfig,
+};
+pub use credentials::{CredentialProvider, CredentialTarget, Credentials, RegistryCredentials};
 pub use dirs::AppDirs;
 pub use manifest::*;
 pub use package::{Package, PackageId, PackageIdInner, PackageInner, PackageName};
+pub use registry::search::{SearchResult, SearchResults};
 pub use resolver::Resolve;
 pub use source::{GitReference, SourceId, SourceIdInner, SourceKind};
 pub use workspace::{Utf8PathWorkspaceExt, Workspace};
 
+pub(crate) mod advisory;
 mod checksum;
 pub(crate) mod config;
+pub(crate) mod config_file;
+pub(crate) mod credentials;
 mod dirs;
 pub mod errors;
 pub(crate) mod lockfile;
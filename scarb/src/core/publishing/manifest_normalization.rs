@@ -6,7 +6,7 @@ use indoc::formatdoc;
 
 use crate::core::{
     DepKind, DependencyVersionReq, DetailedTomlDependency, ManifestDependency, MaybeWorkspace,
-    Package, PackageName, TargetKind, TomlDependency, TomlManifest, TomlPackage,
+    Package, PackageName, TargetKind, TomlDependency, TomlManifest, TomlPackage, TomlPublish,
     TomlWorkspaceDependency, TomlWorkspaceField,
 };
 
@@ -35,6 +35,10 @@ pub fn prepare_manifest_for_publish(pkg: &Package) -> Result<TomlManifest> {
         package,
         workspace: None,
         dependencies,
+        target_dependencies: None,
+        build_dependencies: None,
+        features: (!pkg.manifest.summary.features.is_empty())
+            .then(|| pkg.manifest.summary.features.clone()),
         lib: None,
         cairo_plugin,
         test: None,
@@ -43,6 +47,12 @@ pub fn prepare_manifest_for_publish(pkg: &Package) -> Result<TomlManifest> {
         profile: None,
         scripts: None,
         tool,
+        lints: None,
+        hooks: None,
+        required_tools: None,
+        tools: None,
+        env: None,
+        patch: None,
     })
 }
 
@@ -53,7 +63,7 @@ fn generate_package(pkg: &Package) -> Box<TomlPackage> {
         name: summary.package_id.name.clone(),
         version: MaybeWorkspace::Defined(summary.package_id.version.clone()),
         authors: metadata.authors.clone().map(MaybeWorkspace::Defined),
-        urls: metadata.urls.clone(),
+        urls: metadata.urls.clone().map(MaybeWorkspace::Defined),
         description: metadata.description.clone().map(MaybeWorkspace::Defined),
         documentation: metadata.documentation.clone().map(MaybeWorkspace::Defined),
         homepage: metadata.homepage.clone().map(MaybeWorkspace::Defined),
@@ -68,8 +78,19 @@ fn generate_package(pkg: &Package) -> Box<TomlPackage> {
             .as_ref()
             .map(|p| map_metadata_file_path(p, pkg)),
         repository: metadata.repository.clone().map(MaybeWorkspace::Defined),
+        // Package contents have already been selected.
+        include: None,
+        exclude: None,
+        publish: metadata.publish.clone().map(|registries| {
+            MaybeWorkspace::Defined(if registries.is_empty() {
+                TomlPublish::Bool(false)
+            } else {
+                TomlPublish::Registries(registries)
+            })
+        }),
         no_core: summary.no_core.then_some(true),
         cairo_version: metadata.cairo_version.clone().map(MaybeWorkspace::Defined),
+        metadata: metadata.package_metadata.clone(),
     })
 }
 
@@ -80,7 +101,7 @@ fn generate_dependencies(
     deps.iter()
         .filter(|dep| dep.kind == kind)
         .map(|dep| {
-            let name = dep.name.clone();
+            let name = dep.import_name().clone();
             let toml_dep = generate_dependency(dep)?;
             Ok((name, MaybeWorkspace::Defined(toml_dep)))
         })
@@ -118,6 +139,7 @@ fn generate_dependency(dep: &ManifestDependency) -> Result<TomlDependency> {
 
     Ok(TomlDependency::Detailed(Box::new(DetailedTomlDependency {
         version,
+        package: dep.rename.as_ref().map(|_| dep.name.clone()),
 
         // Erase path information, effectively making the dependency default registry-based.
         path: None,
@@ -136,6 +158,11 @@ fn generate_dependency(dep: &ManifestDependency) -> Result<TomlDependency> {
         } else {
             None
         },
+
+        features: (!dep.features.is_empty()).then(|| dep.features.clone()),
+        default_features: (!dep.default_features).then_some(false),
+        optional: dep.optional.then_some(true),
+        allow_prerelease: dep.allow_prerelease.then_some(true),
     })))
 }
 
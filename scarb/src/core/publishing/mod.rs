@@ -1,2 +1,3 @@
 pub mod manifest_normalization;
 pub mod source;
+pub mod vcs;
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use camino::Utf8PathBuf;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::{DirEntry, WalkBuilder};
 
 use crate::core::Package;
@@ -12,6 +13,9 @@ use crate::{DEFAULT_TARGET_DIR_NAME, LOCK_FILE_NAME, MANIFEST_FILE_NAME, SCARB_I
 /// package, provided that they potentially can be committed to the source directory. The following
 /// rules hold:
 /// * Look for any `.scarbignore`, `.gitignore` or `.ignore`-like files, using the [`ignore`] crate.
+/// * If `package.include` globs are specified, take only files matching them instead, so that
+///   files ignored by VCS (like generated sources) can be packaged.
+/// * Skip files matching `package.exclude` globs, even if they are included.
 /// * Skip `.git` directory.
 /// * Skip any subdirectories containing `Scarb.toml`.
 /// * Skip `<root>/target` directory.
@@ -27,6 +31,19 @@ pub fn list_source_files(pkg: &Package) -> Result<Vec<Utf8PathBuf>> {
 }
 
 fn push_worktree_files(pkg: &Package, ret: &mut Vec<Utf8PathBuf>) -> Result<()> {
+    let metadata = &pkg.manifest.metadata;
+    let include = metadata
+        .include
+        .as_ref()
+        .map(|globs| build_globs(pkg, globs, "include"))
+        .transpose()?;
+    let exclude = build_globs(
+        pkg,
+        metadata.exclude.as_deref().unwrap_or_default(),
+        "exclude",
+    )?;
+    let use_ignore_files = include.is_none();
+
     let filter = {
         let pkg = pkg.clone();
         move |entry: &DirEntry| -> bool {
@@ -53,26 +70,57 @@ fn push_worktree_files(pkg: &Package, ret: &mut Vec<Utf8PathBuf>) -> Result<()>
                 return false;
             }
 
-            true
+            if is_root {
+                return true;
+            }
+
+            let is_dir = is_dir(entry);
+            if exclude
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+            {
+                return false;
+            }
+
+            match &include {
+                // Directories are traversed, as their contents may be included.
+                Some(_) if is_dir => entry.file_name() != ".git",
+                Some(include) => include.matched_path_or_any_parents(path, false).is_ignore(),
+                None => true,
+            }
         }
     };
 
-    WalkBuilder::new(pkg.root())
+    let mut builder = WalkBuilder::new(pkg.root());
+    builder
         .follow_links(true)
-        .standard_filters(true)
+        .standard_filters(use_ignore_files)
         .parents(false)
         .require_git(true)
         .same_file_system(true)
-        .add_custom_ignore_filename(SCARB_IGNORE_FILE_NAME)
-        .filter_entry(filter)
-        .build()
-        .try_for_each(|entry| {
-            let entry = entry?;
-            if !is_dir(&entry) {
-                ret.push(entry.into_path().try_into_utf8()?);
-            }
-            Ok(())
-        })
+        .filter_entry(filter);
+    if use_ignore_files {
+        builder.add_custom_ignore_filename(SCARB_IGNORE_FILE_NAME);
+    }
+    builder.build().try_for_each(|entry| {
+        let entry = entry?;
+        if !is_dir(&entry) {
+            ret.push(entry.into_path().try_into_utf8()?);
+        }
+        Ok(())
+    })
+}
+
+/// Build a matcher of `package.{field}` globs, which are matched like `.gitignore` patterns,
+/// relative to the package root.
+fn build_globs(pkg: &Package, globs: &[String], field: &str) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(pkg.root());
+    for glob in globs {
+        builder
+            .add_line(None, glob)
+            .with_context(|| format!("invalid `package.{field}` glob: {glob}"))?;
+    }
+    Ok(builder.build()?)
 }
 
 fn is_dir(entry: &DirEntry) -> bool {
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use anyhow::{ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::internal::fsx;
+use crate::sources::git::client::git_command;
+
+/// Name of the file describing the VCS checkout a package has been packaged from.
+pub const VCS_INFO_FILE_NAME: &str = "VCS_INFO.json";
+
+/// Contents of the [`VCS_INFO_FILE_NAME`] file.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VcsInfo {
+    pub git: GitVcsInfo,
+    /// Path of the package root relative to the repository root, with `/` as separator.
+    pub path_in_vcs: String,
+    /// URL of the `origin` remote of the repository, if set.
+    pub repository: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GitVcsInfo {
+    pub commit_hash: String,
+    /// Whether any of the packaged files differ from their committed version.
+    pub dirty: bool,
+}
+
+impl VcsInfo {
+    /// Read the VCS info file from the root of an unpacked package, if present.
+    pub fn read(package_root: &Utf8Path) -> Result<Option<Self>> {
+        let path = package_root.join(VCS_INFO_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = fsx::read_to_string(&path)?;
+        let info =
+            serde_json::from_str(&contents).with_context(|| format!("failed to parse: {path}"))?;
+        Ok(Some(info))
+    }
+}
+
+/// A Git repository containing a package that is about to be packaged.
+pub struct GitPackageRepo {
+    work_dir: Utf8PathBuf,
+    path_in_vcs: String,
+    commit_hash: String,
+    repository: Option<String>,
+}
+
+impl GitPackageRepo {
+    /// Find the Git repository containing the package at `root`.
+    ///
+    /// Returns `None` if the package is not inside a Git work tree, or the repository has no
+    /// commits yet.
+    pub fn discover(root: &Utf8Path) -> Result<Option<Self>> {
+        let root = fsx::canonicalize_utf8(root)?;
+        let repo = match gix::discover(&root) {
+            Ok(repo) => repo,
+            Err(err) => {
+                debug!("package at {root} is not in a git repository: {err}");
+                return Ok(None);
+            }
+        };
+        let Some(work_dir) = repo.work_dir() else {
+            return Ok(None);
+        };
+        let work_dir = fsx::canonicalize_utf8(work_dir)?;
+        let Ok(commit) = repo.head_id() else {
+            debug!("git repository at {work_dir} has no commits");
+            return Ok(None);
+        };
+        let path_in_vcs = to_slash(root.strip_prefix(&work_dir)?);
+        let repository = repo
+            .find_default_remote(gix::remote::Direction::Fetch)
+            .and_then(Result::ok)
+            .and_then(|remote| {
+                remote
+                    .url(gix::remote::Direction::Fetch)
+                    .map(|url| url.to_bstring().to_string())
+            });
+        Ok(Some(Self {
+            work_dir,
+            path_in_vcs,
+            commit_hash: commit.to_string(),
+            repository,
+        }))
+    }
+
+    /// Describe the current state of the repository, checking whether any of `files` (relative to
+    /// the package root) are modified or not tracked.
+    pub fn vcs_info(&self, files: &[Utf8PathBuf]) -> Result<VcsInfo> {
+        let changed = self
+            .changed_files()
+            .context("failed to check git repository status")?;
+        let dirty = files.iter().any(|file| {
+            let file = to_slash(file);
+            let path = if self.path_in_vcs.is_empty() {
+                file
+            } else {
+                format!("{}/{file}", self.path_in_vcs)
+            };
+            changed.contains(&path)
+        });
+        Ok(VcsInfo {
+            git: GitVcsInfo {
+                commit_hash: self.commit_hash.clone(),
+                dirty,
+            },
+            path_in_vcs: self.path_in_vcs.clone(),
+            repository: self.repository.clone(),
+        })
+    }
+
+    /// List paths, relative to the repository root, of all modified and untracked files.
+    fn changed_files(&self) -> Result<HashSet<String>> {
+        let output = git_command()
+            .args(["status", "--porcelain", "-z", "--untracked-files=all"])
+            .current_dir(&self.work_dir)
+            .output()?;
+        ensure!(
+            output.status.success(),
+            "git status failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        let stdout = String::from_utf8(output.stdout)?;
+
+        let mut changed = HashSet::new();
+        let mut entries = stdout.split_terminator('\0');
+        while let Some(entry) = entries.next() {
+            // Each entry has the form of `XY <path>`.
+            if entry.len() < 4 {
+                continue;
+            }
+            let (status, path) = entry.split_at(3);
+            changed.insert(path.to_string());
+            // Renames and copies are followed by an entry with the original path.
+            if status.starts_with(['R', 'C']) {
+                if let Some(original) = entries.next() {
+                    changed.insert(original.to_string());
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+fn to_slash(path: &Utf8Path) -> String {
+    path.components()
+        .map(|c| c.as_str())
+        .collect::<Vec<_>>()
+        .join("/")
+}
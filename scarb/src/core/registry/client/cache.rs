@@ -0,0 +1,256 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, ensure, Result};
+use async_trait::async_trait;
+use camino::Utf8PathBuf;
+use tracing::{debug, trace};
+
+use crate::core::registry::client::http::{
+    cached_records_path, read_cached_records, CachedRecords,
+};
+use crate::core::registry::client::{DownloadProgress, RegistryClient, TarballStream};
+use crate::core::registry::index::IndexRecords;
+use crate::core::registry::provenance::SignedProvenance;
+use crate::core::registry::search::SearchResults;
+use crate::core::{Config, ManifestMetadata, Package, PackageId, PackageName, SourceId};
+use crate::flock::{FileLockGuard, Filesystem};
+
+/// Time for which the answer of a registry that a package does not exist is trusted.
+const NOT_FOUND_TTL: Duration = Duration::from_secs(60);
+
+/// A caching wrapper over remote registry clients, which remembers lookups of packages
+/// that are not present in the registry.
+///
+/// Resolution often asks registries for packages which do not exist in them, for example
+/// when probing multiple registries for the same name. Such `NotFound` answers are stored
+/// on disk for [`NOT_FOUND_TTL`], so that repeated resolution attempts do not issue
+/// network requests again. Publishing a package through this client drops its entry.
+///
+/// Index records of found packages are stored on disk as well, in the same files which
+/// [`HttpRegistryClient`](super::http::HttpRegistryClient) keeps for revalidating them.
+/// In offline mode, the wrapped client is not asked at all, and lookups are answered from
+/// these records only. All other operations which would access the network fail, apart from
+/// downloads of packages which the wrapped client has already stored on disk.
+pub struct CachingRegistryClient<'c> {
+    client: Box<dyn RegistryClient + 'c>,
+    config: &'c Config,
+    fs: Filesystem<'c>,
+}
+
+impl<'c> CachingRegistryClient<'c> {
+    pub fn new(
+        source_id: SourceId,
+        config: &'c Config,
+        client: Box<dyn RegistryClient + 'c>,
+    ) -> Self {
+        let fs = config
+            .dirs()
+            .registry_dir()
+            .into_child("index")
+            .into_child(source_id.ident());
+        Self { client, config, fs }
+    }
+
+    /// Store `records` for offline lookups, unless the wrapped client has stored them already.
+    async fn remember_records(&self, package: &PackageName, records: &IndexRecords) -> Result<()> {
+        let path = cached_records_path(self.fs.path_existent()?, package);
+        let is_cached = read_cached_records(&path)
+            .await
+            .map_or(false, |cached| &cached.records == records);
+        if !is_cached {
+            let cached = CachedRecords {
+                cache_key: None,
+                records: records.clone(),
+            };
+            tokio::fs::write(path, serde_json::to_vec(&cached)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Fail with an error saying that `action` cannot be done, if network access is not allowed.
+    fn ensure_network_allowed(&self, action: impl FnOnce() -> String) -> Result<()> {
+        ensure!(
+            self.config.network_allowed(),
+            "cannot {} in offline mode (--offline)",
+            action()
+        );
+        Ok(())
+    }
+
+    /// Answer a lookup from records stored by previous lookups, without asking the registry.
+    async fn get_cached_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        if self.is_known_not_found(&package).await {
+            trace!("package {package} is known to be missing in the registry");
+            return Ok(None);
+        }
+        let path = cached_records_path(self.fs.path_unchecked(), &package);
+        match read_cached_records(&path).await {
+            Some(cached) => Ok(Some(Arc::new(cached.records))),
+            None => bail!(
+                "index records of package `{package}` are missing from the cache, \
+                cannot look them up in offline mode"
+            ),
+        }
+    }
+
+    fn not_found_path(&self, package: &PackageName) -> Utf8PathBuf {
+        self.fs
+            .path_unchecked()
+            .join(format!("{package}.not-found"))
+    }
+
+    async fn is_known_not_found(&self, package: &PackageName) -> bool {
+        let Ok(metadata) = tokio::fs::metadata(self.not_found_path(package)).await else {
+            return false;
+        };
+        metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map_or(false, |age| age < NOT_FOUND_TTL)
+    }
+
+    async fn remember_not_found(&self, package: &PackageName) -> Result<()> {
+        self.fs.path_existent()?;
+        tokio::fs::write(self.not_found_path(package), "").await?;
+        Ok(())
+    }
+
+    async fn forget_not_found(&self, package: &PackageName) {
+        let _ = tokio::fs::remove_file(self.not_found_path(package)).await;
+    }
+}
+
+#[async_trait]
+impl<'c> RegistryClient for CachingRegistryClient<'c> {
+    fn is_offline(&self) -> bool {
+        self.client.is_offline()
+    }
+
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        if !self.config.network_allowed() {
+            return self.get_cached_records(package).await;
+        }
+
+        if self.is_known_not_found(&package).await {
+            trace!("package {package} is known to be missing in the registry");
+            return Ok(None);
+        }
+
+        let records = self.client.get_records(package.clone()).await?;
+        match &records {
+            Some(records) => {
+                self.forget_not_found(&package).await;
+                // Failing to cache records is not fatal, only offline mode cannot use them.
+                if let Err(err) = self.remember_records(&package, records).await {
+                    debug!("failed to cache index records of {package}: {err:#}");
+                }
+            }
+            None => {
+                // Failing to cache the answer is not fatal, the registry will be asked again.
+                if let Err(err) = self.remember_not_found(&package).await {
+                    debug!("failed to cache missing package {package}: {err:#}");
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    async fn is_downloaded(&self, package: PackageId) -> bool {
+        self.client.is_downloaded(package).await
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        if !self.client.is_downloaded(package).await {
+            self.ensure_network_allowed(|| format!("download `{package}`"))?;
+        }
+        self.client.download(package, progress).await
+    }
+
+    async fn download_stream(&self, package: PackageId) -> Result<Option<TarballStream>> {
+        self.ensure_network_allowed(|| format!("download `{package}`"))?;
+        self.client.download_stream(package).await
+    }
+
+    fn unpacked_path(&self, package: PackageId) -> Option<PathBuf> {
+        self.client.unpacked_path(package)
+    }
+
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        self.ensure_network_allowed(|| format!("fetch signature of `{package}`"))?;
+        self.client.get_signature(package).await
+    }
+
+    async fn get_provenance(&self, package: PackageId) -> Result<Option<SignedProvenance>> {
+        self.ensure_network_allowed(|| format!("fetch provenance attestation of `{package}`"))?;
+        self.client.get_provenance(package).await
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        self.ensure_network_allowed(|| "search packages".to_string())?;
+        self.client.search(query, limit).await
+    }
+
+    async fn supports_publish(&self) -> Result<bool> {
+        self.ensure_network_allowed(|| "check registry capabilities".to_string())?;
+        self.client.supports_publish().await
+    }
+
+    async fn check_publish(&self, package: PackageId, metadata: &ManifestMetadata) -> Result<()> {
+        self.ensure_network_allowed(|| format!("publish `{package}`"))?;
+        self.client.check_publish(package, metadata).await
+    }
+
+    async fn publish(
+        &self,
+        package: Package,
+        tarball: &FileLockGuard,
+        otp: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_network_allowed(|| format!("publish `{}`", package.id))?;
+        let name = package.id.name.clone();
+        self.client.publish(package, tarball, otp).await?;
+        self.forget_not_found(&name).await;
+        Ok(())
+    }
+
+    async fn publish_provenance(
+        &self,
+        package: PackageId,
+        provenance: SignedProvenance,
+    ) -> Result<()> {
+        self.ensure_network_allowed(|| format!("publish provenance attestation of `{package}`"))?;
+        self.client.publish_provenance(package, provenance).await
+    }
+
+    async fn yank(&self, package: PackageId) -> Result<()> {
+        self.ensure_network_allowed(|| format!("yank `{package}`"))?;
+        self.client.yank(package).await
+    }
+
+    async fn unyank(&self, package: PackageId) -> Result<()> {
+        self.ensure_network_allowed(|| format!("unyank `{package}`"))?;
+        self.client.unyank(package).await
+    }
+
+    async fn list_owners(&self, package: PackageName) -> Result<Vec<String>> {
+        self.ensure_network_allowed(|| format!("list owners of `{package}`"))?;
+        self.client.list_owners(package).await
+    }
+
+    async fn add_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        self.ensure_network_allowed(|| format!("add owners of `{package}`"))?;
+        self.client.add_owners(package, logins).await
+    }
+
+    async fn remove_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        self.ensure_network_allowed(|| format!("remove owners of `{package}`"))?;
+        self.client.remove_owners(package, logins).await
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use async_trait::async_trait;
+use tokio::task::spawn_blocking;
+use url::Url;
+
+use crate::core::registry::client::{DownloadProgress, RegistryClient};
+use crate::core::registry::index::{IndexRecord, IndexRecords};
+use crate::core::{PackageId, PackageName, SourceId};
+use crate::internal::fsx;
+
+/// URL scheme prefix marking registries of unpacked packages, created by `scarb vendor`.
+pub const DIRECTORY_SCHEME_PREFIX: &str = "dir+";
+
+/// Name of the file with index record of a vendored package, stored in its root directory.
+pub const VENDOR_RECORD_FILE: &str = ".scarb-vendor.json";
+
+/// Registry serving already unpacked packages from a directory, as created by `scarb vendor`.
+///
+/// Registry URLs have the form `registry+dir+file:///path/to/vendor/`.
+/// Each package lives in its own directory, next to the index record it has been vendored with:
+///
+/// ```text
+/// [vendor root]/
+/// ├── alexandria_math-0.1.0/
+/// │  ├── .scarb-vendor.json
+/// │  ├── Scarb.toml
+/// │  └── src/
+/// └── open_zeppelin-0.7.0/
+///    ├── .scarb-vendor.json
+///    ├── Scarb.toml
+///    └── src/
+/// ```
+pub struct DirectoryRegistryClient {
+    root: PathBuf,
+}
+
+impl DirectoryRegistryClient {
+    pub fn new(source_id: SourceId) -> Result<Self> {
+        let url = source_id
+            .url
+            .as_str()
+            .strip_prefix(DIRECTORY_SCHEME_PREFIX)
+            .ok_or_else(|| anyhow!("not a directory registry url: {source_id}"))?;
+        let root = Url::parse(url)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| anyhow!("url is not a valid path: {url}"))?;
+
+        ensure!(
+            root.is_dir(),
+            "vendored sources directory does not exist: {}",
+            root.display()
+        );
+
+        Ok(Self { root })
+    }
+
+    /// Build URL of the directory registry rooted at `path`.
+    pub fn url_for(path: &Path) -> Result<Url> {
+        let url = Url::from_directory_path(path)
+            .map_err(|_| anyhow!("directory path is not absolute: {}", path.display()))?;
+        Ok(Url::parse(&format!("{DIRECTORY_SCHEME_PREFIX}{url}"))?)
+    }
+
+    fn package_path(&self, package: PackageId) -> PathBuf {
+        self.root.join(package.tarball_basename())
+    }
+}
+
+#[async_trait]
+impl RegistryClient for DirectoryRegistryClient {
+    fn is_offline(&self) -> bool {
+        true
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        let root = self.root.clone();
+        spawn_blocking(move || {
+            let prefix = format!("{package}-");
+            let mut records = IndexRecords::new();
+            for entry in fsx::read_dir(&root)? {
+                let path = entry?.path();
+                let is_package_dir = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| name.starts_with(&prefix));
+                if !is_package_dir || !path.is_dir() {
+                    continue;
+                }
+
+                let record_path = path.join(VENDOR_RECORD_FILE);
+                let record: IndexRecord = serde_json::from_slice(&fsx::read(&record_path)?)
+                    .with_context(|| {
+                        format!("failed to read vendored record: {}", record_path.display())
+                    })?;
+                records.push(record);
+            }
+
+            if records.is_empty() {
+                return Ok(None);
+            }
+            records.sort_by_cached_key(|r| r.version.clone());
+            Ok(Some(Arc::new(records)))
+        })
+        .await?
+    }
+
+    async fn is_downloaded(&self, _package: PackageId) -> bool {
+        true
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        _progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        bail!("vendored package `{package}` is not stored as a tarball")
+    }
+
+    fn unpacked_path(&self, package: PackageId) -> Option<PathBuf> {
+        Some(self.package_path(package))
+    }
+}
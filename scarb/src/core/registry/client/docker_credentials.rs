@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, ensure, Context, Result};
+use data_encoding::BASE64;
+use directories::BaseDirs;
+use serde::Deserialize;
+use tracing::{debug, trace};
+
+use crate::internal::fsx;
+
+/// Credentials used to authenticate with a container registry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DockerCredentials {
+    pub username: String,
+    pub secret: String,
+}
+
+/// Look up credentials for the container registry `host` the same way Docker CLI does.
+///
+/// The Docker config file is read from `$DOCKER_CONFIG/config.json`, falling back to
+/// `~/.docker/config.json`. Per-registry credential helpers (`credHelpers`) take precedence over
+/// static `auths` entries, which take precedence over the default credential store
+/// (`credsStore`).
+///
+/// Returns `None` if no credentials are configured for this host.
+pub fn lookup(host: &str) -> Result<Option<DockerCredentials>> {
+    let Some(path) = config_path() else {
+        return Ok(None);
+    };
+
+    if !path.is_file() {
+        trace!("docker config file does not exist: {}", path.display());
+        return Ok(None);
+    }
+
+    let docker_config: DockerConfig = serde_json::from_str(&fsx::read_to_string(&path)?)
+        .with_context(|| format!("failed to parse docker config: {}", path.display()))?;
+
+    docker_config.lookup(host)
+}
+
+fn config_path() -> Option<PathBuf> {
+    env::var_os("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| BaseDirs::new().map(|dirs| dirs.home_dir().join(".docker")))
+        .map(|dir| dir.join("config.json"))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+    creds_store: Option<String>,
+    #[serde(default)]
+    cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DockerAuth {
+    auth: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(rename = "identitytoken")]
+    identity_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperOutput {
+    username: String,
+    secret: String,
+}
+
+impl DockerConfig {
+    fn lookup(&self, host: &str) -> Result<Option<DockerCredentials>> {
+        if let Some(helper) = self.cred_helpers.get(host) {
+            return run_helper(helper, host);
+        }
+
+        if let Some(auth) = self
+            .auths
+            .iter()
+            .find(|(key, _)| normalize_host(key) == host)
+            .map(|(_, auth)| auth)
+        {
+            return auth.credentials().map(Some);
+        }
+
+        if let Some(helper) = &self.creds_store {
+            return run_helper(helper, host);
+        }
+
+        Ok(None)
+    }
+}
+
+impl DockerAuth {
+    fn credentials(&self) -> Result<DockerCredentials> {
+        if let Some(auth) = &self.auth {
+            let decoded = BASE64
+                .decode(auth.as_bytes())
+                .context("failed to decode docker auth entry")?;
+            let decoded =
+                String::from_utf8(decoded).context("docker auth entry is not valid UTF-8")?;
+            let Some((username, secret)) = decoded.split_once(':') else {
+                bail!("docker auth entry is not in `username:password` format");
+            };
+            return Ok(DockerCredentials {
+                username: username.to_string(),
+                secret: secret.to_string(),
+            });
+        }
+
+        if let Some(identity_token) = &self.identity_token {
+            return Ok(DockerCredentials {
+                username: self.username.clone().unwrap_or_default(),
+                secret: identity_token.clone(),
+            });
+        }
+
+        match (&self.username, &self.password) {
+            (Some(username), Some(password)) => Ok(DockerCredentials {
+                username: username.clone(),
+                secret: password.clone(),
+            }),
+            _ => bail!("docker auth entry does not contain credentials"),
+        }
+    }
+}
+
+/// Strip scheme and path from legacy `auths` keys like `https://index.docker.io/v1/`.
+fn normalize_host(key: &str) -> &str {
+    let key = key
+        .strip_prefix("https://")
+        .or_else(|| key.strip_prefix("http://"))
+        .unwrap_or(key);
+    key.split('/').next().unwrap_or(key)
+}
+
+/// Run `docker-credential-<helper> get` as described by the
+/// [docker-credential-helpers](https://github.com/docker/docker-credential-helpers) protocol.
+fn run_helper(helper: &str, host: &str) -> Result<Option<DockerCredentials>> {
+    let program = format!("docker-credential-{helper}");
+    debug!("querying credential helper `{program}` for: {host}");
+
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn docker credential helper: {program}"))?;
+
+    child
+        .stdin
+        .take()
+        .expect("we asked Rust to pipe stdin")
+        .write_all(host.as_bytes())
+        .with_context(|| format!("failed to write to docker credential helper: {program}"))?;
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("failed to wait for docker credential helper: {program}"))?;
+
+    if !output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("credentials not found") {
+            return Ok(None);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "docker credential helper `{program}` failed: {}",
+            stderr.trim()
+        );
+    }
+
+    let output: HelperOutput = serde_json::from_slice(&output.stdout).with_context(|| {
+        format!("failed to parse output of docker credential helper: {program}")
+    })?;
+    ensure!(
+        !output.secret.is_empty(),
+        "docker credential helper `{program}` returned empty secret"
+    );
+
+    Ok(Some(DockerCredentials {
+        username: output.username,
+        secret: output.secret,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{DockerConfig, DockerCredentials};
+
+    #[test]
+    fn lookup_static_auth() {
+        let config: DockerConfig = serde_json::from_str(
+            r#"{
+              "auths": {
+                "ghcr.io": { "auth": "dXNlcjpzZWNyZXQ=" },
+                "https://index.docker.io/v1/": { "username": "john", "password": "doe" }
+              }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.lookup("ghcr.io").unwrap(),
+            Some(DockerCredentials {
+                username: "user".to_string(),
+                secret: "secret".to_string(),
+            })
+        );
+        assert_eq!(
+            config.lookup("index.docker.io").unwrap(),
+            Some(DockerCredentials {
+                username: "john".to_string(),
+                secret: "doe".to_string(),
+            })
+        );
+        assert_eq!(config.lookup("example.com").unwrap(), None);
+    }
+
+    #[test_case("ghcr.io" => "ghcr.io")]
+    #[test_case("localhost:5000" => "localhost:5000")]
+    #[test_case("https://index.docker.io/v1/" => "index.docker.io")]
+    #[test_case("http://registry.local/" => "registry.local")]
+    fn normalize_host(key: &str) -> &str {
+        super::normalize_host(key)
+    }
+}
@@ -0,0 +1,308 @@
+use std::io;
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use async_trait::async_trait;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs4::tokio::AsyncFileExt;
+use futures::StreamExt;
+use reqwest::StatusCode;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::OnceCell;
+use tokio::task::spawn_blocking;
+use tracing::{debug, trace};
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::client::{DownloadProgress, RegistryClient};
+use crate::core::registry::index::{IndexConfig, IndexRecords, TemplateUrl};
+use crate::core::registry::signature::signature_url;
+use crate::core::{Config, GitReference, PackageId, PackageName, SourceId};
+use crate::flock::Filesystem;
+use crate::internal::fsx;
+use crate::sources::canonical_url::CanonicalUrl;
+use crate::sources::git::client::{GitDatabase, GitRemote};
+
+/// URL scheme prefix marking registries whose index is stored in a Git repository.
+pub const GIT_INDEX_SCHEME_PREFIX: &str = "git+";
+
+/// Time after which checkouts of previous index revisions are removed.
+const STALE_CHECKOUT_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Remote registry with an index hosted in a Git repository.
+///
+/// Registry URLs have the form `registry+git+https://example.com/index.git`. The repository
+/// is expected to follow the same layout as [`LocalRegistryClient`][super::local::LocalRegistryClient]
+/// uses, with the `config.json` file at the repository root, just like HTTP registries serve:
+///
+/// ```text
+/// [repository root]/
+/// ├── config.json
+/// └── index/
+///    └── al/
+///       └── ex/
+///          └── alexandria_math.json
+/// ```
+///
+/// The repository is cloned into Scarb cache once and then incrementally updated with
+/// `git fetch` on subsequent runs. Index records are read directly from the working tree,
+/// while package tarballs are downloaded over HTTP, using the `dl` template from `config.json`.
+pub struct GitIndexRegistryClient<'c> {
+    source_id: SourceId,
+    config: &'c Config,
+    remote: GitRemote,
+    checkout: OnceCell<Utf8PathBuf>,
+    cached_index_config: OnceCell<IndexConfig>,
+    dl_fs: Filesystem<'c>,
+}
+
+impl<'c> GitIndexRegistryClient<'c> {
+    pub fn new(source_id: SourceId, config: &'c Config) -> Result<Self> {
+        let repo_url = source_id
+            .url
+            .as_str()
+            .strip_prefix(GIT_INDEX_SCHEME_PREFIX)
+            .ok_or_else(|| anyhow!("not a git index registry url: {source_id}"))?;
+        let repo_url = Url::parse(repo_url)
+            .with_context(|| format!("invalid git index registry url: {source_id}"))?;
+        let remote = GitRemote::new(CanonicalUrl::new(&repo_url)?);
+
+        let dl_fs = config
+            .dirs()
+            .registry_dir()
+            .into_child("dl")
+            .into_child(source_id.ident());
+
+        Ok(Self {
+            source_id,
+            config,
+            remote,
+            checkout: OnceCell::new(),
+            cached_index_config: OnceCell::new(),
+            dl_fs,
+        })
+    }
+
+    /// Get path to an up-to-date checkout of the index repository.
+    ///
+    /// The repository is fetched at most once per client lifetime.
+    async fn checkout(&self) -> Result<&Utf8Path> {
+        self.checkout
+            .get_or_try_init(|| async {
+                let _lock = self.config.package_cache_lock().acquire_async().await?;
+
+                let remote = self.remote.clone();
+
+                // HACK: We know that we will not use &Config outside scope of this function,
+                //   but `spawn_blocking` lifetime bounds force us to think so.
+                let config: &'static Config = unsafe { mem::transmute(self.config) };
+
+                spawn_blocking(move || update_index(remote, config)).await?
+            })
+            .await
+            .map(|path| path.as_path())
+            .with_context(|| format!("failed to update git index: {}", self.remote))
+    }
+
+    async fn index_config(&self) -> Result<&IndexConfig> {
+        self.cached_index_config
+            .get_or_try_init(|| async {
+                let path = self.checkout().await?.join(IndexConfig::WELL_KNOWN_PATH);
+                let index_config: IndexConfig = serde_json::from_str(&fsx::read_to_string(path)?)?;
+                trace!(index_config = %serde_json::to_string(&index_config).unwrap());
+                Ok::<_, anyhow::Error>(index_config)
+            })
+            .await
+            .context("failed to read registry config")
+    }
+
+    async fn records_path(&self, package: &PackageName) -> Result<PathBuf> {
+        let root_url = Url::from_directory_path(self.checkout().await?)
+            .expect("Checkout path should always be convertible to URL.");
+        let index_template_url =
+            TemplateUrl::new(&format!("{root_url}index/{{prefix}}/{{package}}.json"));
+        Ok(index_template_url
+            .expand(package.into())?
+            .to_file_path()
+            .expect("Expanded checkout URL should always be a file path."))
+    }
+}
+
+fn update_index(remote: GitRemote, config: &Config) -> Result<Utf8PathBuf> {
+    let remote_ident = remote.ident();
+    let reference = GitReference::DefaultBranch;
+
+    let index_fs = config.dirs().registry_dir().into_child("index");
+    let db_fs = index_fs
+        .child("db")
+        .into_child(&format!("{remote_ident}.git"));
+
+    let db = GitDatabase::open(&remote, &db_fs).ok();
+    let (db, rev) = match db {
+        // If Scarb is in offline mode and there is a functional database, then use whatever
+        // version of the index has been fetched previously.
+        Some(db) if !config.network_allowed() => {
+            let rev = db.resolve(&reference).context(
+                "failed to lookup index in preexisting repository, and \
+                cannot check for updates in offline mode (--offline)",
+            )?;
+            (db, rev)
+        }
+
+        // Otherwise, fetch into the existing database, or clone from scratch.
+        db => {
+            if config.network_allowed() {
+                config
+                    .ui()
+                    .print(Status::new("Updating", &format!("git index {remote}")));
+            }
+
+            remote.checkout(&db_fs, db, &reference, None, config)?
+        }
+    };
+    debug!("using git index revision: {rev}");
+
+    let checkouts_fs = index_fs.child("checkouts").into_child(&remote_ident);
+    let short_id = db.short_id_of(rev)?;
+    let checkout_fs = checkouts_fs.child(&short_id);
+
+    let checkout = db.copy_to(&checkout_fs, rev, config)?;
+    prune_checkouts(checkouts_fs.path_unchecked(), &short_id)?;
+    Ok(checkout.location)
+}
+
+/// Remove checkouts of index revisions other than `current`, which have not been used for
+/// [`STALE_CHECKOUT_AGE`].
+///
+/// Checkouts are recreated each time they are used, so their modification time tells when
+/// a Scarb process has started reading from them. Such a process reads records after
+/// releasing the package cache lock, so recently used checkouts are kept around.
+fn prune_checkouts(checkouts_dir: &Utf8Path, current: &str) -> Result<()> {
+    for entry in fsx::read_dir(checkouts_dir)? {
+        let entry = entry?;
+        let is_stale = entry
+            .metadata()?
+            .modified()?
+            .elapsed()
+            .map_or(false, |age| age >= STALE_CHECKOUT_AGE);
+        if entry.file_name() != current && entry.file_type()?.is_dir() && is_stale {
+            debug!(
+                "removing stale git index checkout: {}",
+                entry.path().display()
+            );
+            fsx::remove_dir_all(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl<'c> RegistryClient for GitIndexRegistryClient<'c> {
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        let checkout = self.checkout().await?.to_path_buf();
+        let records_path = self.records_path(&package).await?;
+
+        spawn_blocking(move || {
+            let records = match fsx::read(records_path) {
+                Err(e)
+                    if e.downcast_ref::<io::Error>()
+                        .map_or(false, |ioe| ioe.kind() == io::ErrorKind::NotFound) =>
+                {
+                    // A missing checkout does not mean that the package does not exist.
+                    ensure!(
+                        checkout.is_dir(),
+                        "git index checkout has been removed while in use: {checkout}"
+                    );
+                    return Ok(None);
+                }
+                r => r?,
+            };
+            let records = serde_json::from_slice(&records)?;
+            Ok(Some(Arc::new(records)))
+        })
+        .await?
+    }
+
+    async fn is_downloaded(&self, _package: PackageId) -> bool {
+        false
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        let dl_url = self.index_config().await?.dl.expand(package.into())?;
+
+        let response = self
+            .config
+            .http_for(self.source_id)?
+            .get(dl_url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let output_path = self.dl_fs.path_existent()?.join(package.tarball_name());
+        let output_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&output_path)
+            .await
+            .with_context(|| format!("failed to open: {output_path}"))?;
+
+        output_file
+            .lock_exclusive()
+            .with_context(|| format!("failed to lock file: {output_path}"))?;
+
+        let total = response.content_length();
+        let mut downloaded = 0;
+        let mut stream = response.bytes_stream();
+        let mut writer = BufWriter::new(output_file);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("failed to read response chunk")?;
+            downloaded += chunk.len() as u64;
+            progress.update(downloaded, total)?;
+            tokio::io::copy_buf(&mut &*chunk, &mut writer)
+                .await
+                .context("failed to save response chunk on disk")?;
+        }
+        writer.flush().await?;
+
+        Ok(output_path.into_std_path_buf())
+    }
+
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        let dl_url = self.index_config().await?.dl.expand(package.into())?;
+        let signature_url = signature_url(&dl_url);
+        debug!("fetching package signature: {signature_url}");
+
+        let response = self
+            .config
+            .http_for(self.source_id)?
+            .get(signature_url)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let signature = response
+            .error_for_status()?
+            .text()
+            .await
+            .context("failed to read package signature")?;
+        Ok(Some(signature))
+    }
+}
@@ -1,32 +1,58 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use async_trait::async_trait;
+use camino::{Utf8Path, Utf8PathBuf};
 use fs4::tokio::AsyncFileExt;
-use futures::StreamExt;
-use reqwest::StatusCode;
+use futures::{StreamExt, TryStreamExt};
+use reqwest::header::{
+    HeaderName, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+    LAST_MODIFIED, RANGE,
+};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use tokio::fs::OpenOptions;
 use tokio::io;
-use tokio::io::BufWriter;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter, SeekFrom};
 use tokio::sync::OnceCell;
 use tracing::{debug, trace};
+use url::Url;
 
-use crate::core::registry::client::RegistryClient;
-use crate::core::registry::index::{IndexConfig, IndexRecords};
-use crate::core::{Config, Package, PackageId, PackageName, SourceId};
+use crate::core::registry::client::retry::{check_transient_status, RetryPolicy};
+use crate::core::registry::client::{DownloadProgress, OtpRequired, RegistryClient, TarballStream};
+use crate::core::registry::index::{IndexConfig, IndexRecord, IndexRecords};
+use crate::core::registry::provenance::{provenance_url, SignedProvenance};
+use crate::core::registry::search::SearchResults;
+use crate::core::registry::signature::signature_url;
+use crate::core::{Config, ManifestMetadata, Package, PackageId, PackageName, SourceId};
 use crate::flock::{FileLockGuard, Filesystem};
 
-// TODO(mkaput): Honour ETag and Last-Modified headers.
-// TODO(mkaput): Progressbar.
 // TODO(mkaput): Request timeout.
 
+/// Instance manipulation (RFC 3229) asking the registry to respond only with index records
+/// which changed since the version identified by `If-None-Match` header.
+const RECORDS_DELTA: &str = "records-delta";
+const A_IM: HeaderName = HeaderName::from_static("a-im");
+const IM: HeaderName = HeaderName::from_static("im");
+
+/// Header carrying the one-time password of the user in upload requests, and, with the value
+/// of `required`, marking responses of registries which refuse uploads without a valid one.
+const OTP: HeaderName = HeaderName::from_static("x-scarb-otp");
+
 /// Remote registry served by the HTTP-based registry API.
+///
+/// Index records are cached on disk along with their `cache_key`, which is sent back
+/// to the registry in conditional requests. Registries can respond with `304 Not Modified`
+/// if records have not changed, or with `226 IM Used` and a JSON array of only added
+/// or changed records, if they support the `records-delta` instance manipulation.
 pub struct HttpRegistryClient<'c> {
     source_id: SourceId,
     config: &'c Config,
     cached_index_config: OnceCell<IndexConfig>,
     dl_fs: Filesystem<'c>,
+    index_fs: Filesystem<'c>,
+    retry: RetryPolicy<'c>,
 }
 
 impl<'c> HttpRegistryClient<'c> {
@@ -36,12 +62,19 @@ impl<'c> HttpRegistryClient<'c> {
             .registry_dir()
             .into_child("dl")
             .into_child(source_id.ident());
+        let index_fs = config
+            .dirs()
+            .registry_dir()
+            .into_child("index")
+            .into_child(source_id.ident());
 
         Ok(Self {
             source_id,
             config,
             cached_index_config: Default::default(),
             dl_fs,
+            index_fs,
+            retry: RetryPolicy::new(config),
         })
     }
 
@@ -49,18 +82,19 @@ impl<'c> HttpRegistryClient<'c> {
         // TODO(mkaput): Cache config locally, honouring ETag and Last-Modified headers.
 
         async fn load(source_id: SourceId, config: &Config) -> Result<IndexConfig> {
+            ensure!(
+                config.network_allowed(),
+                "cannot fetch registry config in offline mode (--offline)"
+            );
             let index_config_url = source_id
                 .url
                 .join(IndexConfig::WELL_KNOWN_PATH)
                 .expect("Registry config URL should always be valid.");
             debug!("fetching registry config: {index_config_url}");
 
-            let index_config = config
-                .http()?
-                .get(index_config_url)
-                .send()
+            let index_config = RetryPolicy::new(config)
+                .send(|| get(config, source_id, index_config_url.clone(), true))
                 .await?
-                .error_for_status()?
                 .json::<IndexConfig>()
                 .await?;
 
@@ -74,6 +108,336 @@ impl<'c> HttpRegistryClient<'c> {
             .await
             .context("failed to fetch registry config")
     }
+
+    /// Send a tarball download request, asking for bytes from `offset` onwards if resuming.
+    async fn request_tarball(&self, dl_url: Url, resume: Option<(u64, &str)>) -> Result<Response> {
+        let authenticate = self.sends_token_to(&dl_url).await?;
+        let mut request = get(self.config, self.source_id, dl_url, authenticate)?;
+        if let Some((offset, validator)) = resume {
+            request = request
+                .header(RANGE, format!("bytes={offset}-"))
+                .header(IF_RANGE, validator);
+        }
+        Ok(check_transient_status(request.send().await?)?)
+    }
+
+    async fn download_attempt(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        let dl_url = self.index_config().await?.dl.expand(package.into())?;
+
+        // Tarballs are downloaded into a scratch file first and moved into place once complete.
+        // If a previous download has been interrupted, it is resumed with an HTTP Range request.
+        // The `.validator` file stores entity tag or modification date of the tarball
+        // being downloaded, so that the server can tell whether the partial content is still valid.
+        let dl_dir = self.dl_fs.path_existent()?;
+        let tarball_name = package.tarball_name();
+        let output_path = dl_dir.join(&tarball_name);
+        let scratch_path = dl_dir.join(format!("{tarball_name}.part"));
+        let validator_path = dl_dir.join(format!("{tarball_name}.part.validator"));
+
+        let scratch_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&scratch_path)
+            .await
+            .with_context(|| format!("failed to open: {scratch_path}"))?;
+
+        scratch_file
+            .lock_exclusive()
+            .with_context(|| format!("failed to lock file: {scratch_path}"))?;
+
+        let scratch_len = scratch_file.metadata().await?.len();
+        let validator = match scratch_len {
+            0 => None,
+            _ => read_validator(&validator_path).await,
+        };
+        let resume = validator
+            .as_deref()
+            .map(|validator| (scratch_len, validator));
+
+        let mut response = self.request_tarball(dl_url.clone(), resume).await?;
+        let mut offset = 0;
+        if resume.is_some() {
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT
+                    if content_range_start(&response) == Some(scratch_len) =>
+                {
+                    debug!("resuming download of {package} from byte {scratch_len}");
+                    offset = scratch_len;
+                }
+                StatusCode::PARTIAL_CONTENT | StatusCode::RANGE_NOT_SATISFIABLE => {
+                    debug!("cannot resume download of {package}, restarting");
+                    response = self.request_tarball(dl_url, None).await?;
+                }
+                _ => {
+                    // The server either ignored the Range header, or the tarball has changed,
+                    // and the whole content is sent.
+                }
+            }
+        }
+        let response = response.error_for_status()?;
+
+        if offset == 0 {
+            scratch_file.set_len(0).await?;
+            match range_validator(&response) {
+                Some(validator) => tokio::fs::write(&validator_path, validator).await?,
+                None => {
+                    let _ = tokio::fs::remove_file(&validator_path).await;
+                }
+            }
+        }
+
+        let mut writer = BufWriter::new(scratch_file);
+        writer.seek(SeekFrom::Start(offset)).await?;
+
+        // When resuming, the response only contains the remaining part of the tarball.
+        let total = response.content_length().map(|length| offset + length);
+        let mut downloaded = offset;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    // Keep whatever has been received so far, so that the download can be resumed.
+                    writer.flush().await?;
+                    return Err(err).context("failed to read response chunk");
+                }
+            };
+            downloaded += chunk.len() as u64;
+            if let Err(err) = progress.update(downloaded, total) {
+                writer.flush().await?;
+                return Err(err);
+            }
+            io::copy_buf(&mut &*chunk, &mut writer)
+                .await
+                .context("failed to save response chunk on disk")?;
+        }
+        writer.flush().await?;
+        drop(writer);
+
+        tokio::fs::rename(&scratch_path, &output_path)
+            .await
+            .with_context(|| format!("failed to move downloaded tarball to: {output_path}"))?;
+        let _ = tokio::fs::remove_file(&validator_path).await;
+
+        Ok(output_path.into_std_path_buf())
+    }
+
+    /// Resolve `path` against the API endpoint of this registry.
+    async fn api_url(&self, path: &str) -> Result<Url> {
+        let api =
+            self.index_config().await?.api.as_ref().ok_or_else(|| {
+                anyhow!("registry does not provide api endpoint: {}", self.source_id)
+            })?;
+        Ok(api.join(path)?)
+    }
+
+    /// Fail early if the registry requires authentication, but there is no token to send.
+    async fn check_auth(&self) -> Result<()> {
+        if self.index_config().await?.auth_required {
+            self.required_token()
+                .context("registry requires authentication")?;
+        }
+        Ok(())
+    }
+
+    /// Whether to send the token stored by `scarb login` with requests to `url`.
+    ///
+    /// The token is only sent to origins of the registry index and API, unless the registry
+    /// requires all requests to be authenticated, so that it does not leak to other hosts,
+    /// like ones serving package downloads.
+    async fn sends_token_to(&self, url: &Url) -> Result<bool> {
+        let index_config = self.index_config().await?;
+        let origin = url.origin();
+        Ok(index_config.auth_required
+            || origin == self.source_id.url.origin()
+            || index_config
+                .api
+                .as_ref()
+                .map_or(false, |api| origin == api.origin()))
+    }
+
+    /// Fetch a text file, returning `None` if the registry responds with `404 Not Found`.
+    async fn get_optional_text(&self, url: Url) -> Result<Option<String>> {
+        let authenticate = self.sends_token_to(&url).await?;
+        let response = self
+            .retry
+            .send(|| get(self.config, self.source_id, url.clone(), authenticate))
+            .await;
+
+        if let Err(err) = &response {
+            if let Some(status) = err
+                .downcast_ref::<reqwest::Error>()
+                .and_then(|e| e.status())
+            {
+                if status == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+            }
+        }
+
+        Ok(Some(response?.text().await?))
+    }
+
+    async fn owners_url(&self, package: &PackageName) -> Result<Url> {
+        self.api_url(&format!("v1/packages/{package}/owners")).await
+    }
+
+    /// Get the token stored by `scarb login`, which is required by all API calls
+    /// modifying the registry.
+    fn required_token(&self) -> Result<String> {
+        self.config.registry_token(self.source_id)?.ok_or_else(|| {
+            anyhow!(
+                "no token found for registry: {source_id}\n\
+                help: run `scarb login --index {url}` first",
+                source_id = self.source_id,
+                url = self.source_id.url
+            )
+        })
+    }
+}
+
+/// Index records of a package stored on disk, along with the validator of the response
+/// they have been read from, if there was one.
+///
+/// These records are also used by [`CachingRegistryClient`](super::cache::CachingRegistryClient)
+/// to answer lookups in offline mode.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) struct CachedRecords {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<CacheKey>,
+    pub records: IndexRecords,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum CacheKey {
+    Etag(String),
+    LastModified(String),
+}
+
+impl CacheKey {
+    fn of(response: &Response) -> Option<Self> {
+        let headers = response.headers();
+        let header = |name: HeaderName| Some(headers.get(name)?.to_str().ok()?.to_string());
+        header(ETAG)
+            .map(Self::Etag)
+            .or_else(|| header(LAST_MODIFIED).map(Self::LastModified))
+    }
+
+    /// Make `request` conditional on the records having changed since this key was obtained.
+    ///
+    /// Delta updates can only be requested for entity tags.
+    fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Etag(etag) => request
+                .header(IF_NONE_MATCH, etag)
+                .header(A_IM, RECORDS_DELTA),
+            Self::LastModified(date) => request.header(IF_MODIFIED_SINCE, date),
+        }
+    }
+}
+
+/// Path of the file caching index records of `package` in the index cache directory
+/// of a registry.
+pub(super) fn cached_records_path(index_dir: &Utf8Path, package: &PackageName) -> Utf8PathBuf {
+    index_dir.join(format!("{package}.json"))
+}
+
+pub(super) async fn read_cached_records(path: &Utf8Path) -> Option<CachedRecords> {
+    let contents = tokio::fs::read(path).await.ok()?;
+    // Corrupted cache entries are ignored, and overwritten by the next full response.
+    serde_json::from_slice(&contents).ok()
+}
+
+/// Update `records` with changed or added records from a delta response.
+fn apply_delta(records: &mut IndexRecords, delta: Vec<IndexRecord>) {
+    for record in delta {
+        match records.iter_mut().find(|r| r.version == record.version) {
+            Some(existing) => *existing = record,
+            None => records.push(record),
+        }
+    }
+}
+
+/// Check that the manifest sets fields which HTTP registries require of published packages.
+fn check_required_metadata(metadata: &ManifestMetadata) -> Result<()> {
+    let is_set = |field: &Option<String>| field.as_ref().is_some_and(|v| !v.trim().is_empty());
+    let mut missing = Vec::new();
+    if !is_set(&metadata.description) {
+        missing.push("`description`");
+    }
+    if !is_set(&metadata.license) && !is_set(&metadata.license_file) {
+        missing.push("`license` or `license-file`");
+    }
+    ensure!(
+        missing.is_empty(),
+        "manifest is missing fields required by registry: {}\n\
+        help: set them in the `[package]` section of Scarb.toml",
+        missing.join(", ")
+    );
+    Ok(())
+}
+
+/// Body of requests and responses of the owners API.
+#[derive(Serialize, Deserialize)]
+struct OwnersList {
+    owners: Vec<String>,
+}
+
+/// Build a `GET` request to registry `source_id`, authenticated with the token stored
+/// by `scarb login`, if there is one and `authenticate` is set.
+fn get(
+    config: &Config,
+    source_id: SourceId,
+    url: Url,
+    authenticate: bool,
+) -> Result<RequestBuilder> {
+    let request = config.http_for(source_id)?.get(url);
+    if !authenticate {
+        return Ok(request);
+    }
+    Ok(match config.registry_token(source_id)? {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    })
+}
+
+/// Extract the first byte position from `Content-Range: bytes <start>-<end>/<size>` header.
+fn content_range_start(response: &Response) -> Option<u64> {
+    parse_content_range_start(response.headers().get(CONTENT_RANGE)?.to_str().ok()?)
+}
+
+fn parse_content_range_start(value: &str) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Pick a value for `If-Range` header, which ensures that a resumed download continues
+/// the same content.
+///
+/// Weak entity tags are not allowed in `If-Range`, in such case `Last-Modified` is used.
+fn range_validator(response: &Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED)?.to_str().ok())
+        .map(ToString::to_string)
+}
+
+async fn read_validator(path: &Utf8Path) -> Option<String> {
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .filter(|validator| !validator.is_empty())
 }
 
 #[async_trait]
@@ -83,31 +447,89 @@ impl<'c> RegistryClient for HttpRegistryClient<'c> {
     }
 
     async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        self.check_auth().await?;
         let index_config = self.index_config().await?;
         let records_url = index_config.index.expand(package.into())?;
 
+        let cache_path = cached_records_path(self.index_fs.path_existent()?, &package);
+        // Records cached without a key cannot be revalidated, so they are fetched again.
+        let cached = read_cached_records(&cache_path)
+            .await
+            .and_then(|cached| Some((cached.cache_key?, cached.records)));
+
+        let authenticate = self.sends_token_to(&records_url).await?;
         let response = self
-            .config
-            .http()?
-            .get(records_url)
-            .send()
-            .await?
-            .error_for_status();
+            .retry
+            .send(|| {
+                let request = get(
+                    self.config,
+                    self.source_id,
+                    records_url.clone(),
+                    authenticate,
+                )?;
+                Ok(match &cached {
+                    Some((cache_key, _)) => cache_key.apply(request),
+                    None => request,
+                })
+            })
+            .await;
 
         if let Err(err) = &response {
-            if let Some(status) = err.status() {
+            if let Some(status) = err
+                .downcast_ref::<reqwest::Error>()
+                .and_then(|e| e.status())
+            {
                 if status == StatusCode::NOT_FOUND {
+                    let _ = tokio::fs::remove_file(&cache_path).await;
                     return Ok(None);
                 }
             }
         }
 
-        let records = response?
-            .json()
-            .await
-            .context("failed to deserialize index records")?;
+        let response = response?;
+        let cache_key = CacheKey::of(&response);
+        let records = match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some((_, records))) => {
+                trace!("index records of {package} are up to date");
+                return Ok(Some(Arc::new(records)));
+            }
+            (StatusCode::IM_USED, Some((_, mut records))) => {
+                let im = response.headers().get(IM).and_then(|v| v.to_str().ok());
+                ensure!(
+                    im == Some(RECORDS_DELTA),
+                    "registry responded with unsupported instance manipulation: {}",
+                    im.unwrap_or_default()
+                );
+                let delta: Vec<IndexRecord> = response
+                    .json()
+                    .await
+                    .context("failed to deserialize index records delta")?;
+                trace!(
+                    "received {} changed index records of {package}",
+                    delta.len()
+                );
+                apply_delta(&mut records, delta);
+                records
+            }
+            (StatusCode::NOT_MODIFIED | StatusCode::IM_USED, None) => {
+                bail!(
+                    "registry responded with status {} to an unconditional request",
+                    response.status()
+                )
+            }
+            _ => response
+                .json()
+                .await
+                .context("failed to deserialize index records")?,
+        };
 
-        Ok(Some(Arc::new(records)))
+        // Records are cached even without a key, so that offline mode can use them.
+        let cached = CachedRecords { cache_key, records };
+        // Failing to cache records is not fatal, they will be fetched again next time.
+        if let Err(err) = tokio::fs::write(&cache_path, serde_json::to_vec(&cached)?).await {
+            debug!("failed to cache index records of {package}: {err}");
+        }
+        Ok(Some(Arc::new(cached.records)))
     }
 
     async fn is_downloaded(&self, _package: PackageId) -> bool {
@@ -115,49 +537,380 @@ impl<'c> RegistryClient for HttpRegistryClient<'c> {
         false
     }
 
-    async fn download(&self, package: PackageId) -> Result<PathBuf> {
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        self.check_auth().await?;
+        // Retried attempts resume the download from where the previous one stopped.
+        self.retry
+            .run(|| self.download_attempt(package, progress))
+            .await
+    }
+
+    async fn download_stream(&self, package: PackageId) -> Result<Option<TarballStream>> {
+        self.check_auth().await?;
+
+        // Interrupted downloads are resumed by the regular download path.
+        let scratch_path = self
+            .dl_fs
+            .path_existent()?
+            .join(format!("{}.part", package.tarball_name()));
+        if tokio::fs::metadata(&scratch_path)
+            .await
+            .map_or(false, |metadata| metadata.len() > 0)
+        {
+            return Ok(None);
+        }
+
+        let dl_url = self.index_config().await?.dl.expand(package.into())?;
+        debug!("streaming download of {package} from: {dl_url}");
+        let authenticate = self.sends_token_to(&dl_url).await?;
+        let response = self
+            .retry
+            .send(|| get(self.config, self.source_id, dl_url.clone(), authenticate))
+            .await?;
+
+        Ok(Some(TarballStream {
+            total: response.content_length(),
+            chunks: response.bytes_stream().map_err(Into::into).boxed(),
+        }))
+    }
+
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        self.check_auth().await?;
+        let dl_url = self.index_config().await?.dl.expand(package.into())?;
+        let signature_url = signature_url(&dl_url);
+        debug!("fetching package signature: {signature_url}");
+        self.get_optional_text(signature_url)
+            .await
+            .context("failed to read package signature")
+    }
+
+    async fn get_provenance(&self, package: PackageId) -> Result<Option<SignedProvenance>> {
+        self.check_auth().await?;
         let dl_url = self.index_config().await?.dl.expand(package.into())?;
+        let attestation_url = provenance_url(&dl_url);
+        debug!("fetching package provenance attestation: {attestation_url}");
+
+        let Some(attestation) = self
+            .get_optional_text(attestation_url.clone())
+            .await
+            .context("failed to read provenance attestation")?
+        else {
+            return Ok(None);
+        };
+        let signature = self
+            .get_optional_text(signature_url(&attestation_url))
+            .await?
+            .context("registry does not provide a signature of provenance attestation")?;
+        Ok(Some(SignedProvenance {
+            attestation,
+            signature,
+        }))
+    }
+
+    /// Query the `{api}v1/packages?q={query}&per_page={limit}` endpoint.
+    async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        self.check_auth().await?;
+        let mut search_url = self.api_url("v1/packages").await?;
+        search_url
+            .query_pairs_mut()
+            .append_pair("q", query)
+            .append_pair("per_page", &limit.to_string());
+        debug!("searching packages: {search_url}");
+
+        let authenticate = self.sends_token_to(&search_url).await?;
+        self.retry
+            .send(|| {
+                get(
+                    self.config,
+                    self.source_id,
+                    search_url.clone(),
+                    authenticate,
+                )
+            })
+            .await?
+            .json()
+            .await
+            .context("failed to deserialize search results")
+    }
+
+    async fn supports_publish(&self) -> Result<bool> {
+        Ok(self.index_config().await?.api.is_some())
+    }
+
+    /// Ensure that a token is available and that the package version is not in the index yet,
+    /// as the registry would reject the upload otherwise.
+    async fn check_publish(&self, package: PackageId, metadata: &ManifestMetadata) -> Result<()> {
+        self.required_token()?;
+        check_required_metadata(metadata)?;
+        let records = self.get_records(package.name.clone()).await?;
+        let is_published = records.map_or(false, |records| {
+            records
+                .iter()
+                .any(|record| record.version == package.version)
+        });
+        ensure!(
+            !is_published,
+            "package `{package}` is already published in registry"
+        );
+        Ok(())
+    }
+
+    /// Upload the tarball with a `PUT` request to `{api}v1/packages/new` endpoint.
+    ///
+    /// Publishing always requires authentication with a token stored by `scarb login`.
+    /// If the registry responds with `429 Too Many Requests`, the upload is retried later.
+    /// Registries enforcing two-factor authentication respond with `401 Unauthorized` and
+    /// `X-Scarb-OTP: required` header if the one-time password is missing or invalid.
+    async fn publish(
+        &self,
+        package: Package,
+        tarball: &FileLockGuard,
+        otp: Option<&str>,
+    ) -> Result<()> {
+        let upload_url = self.api_url("v1/packages/new").await?;
+        let token = self.required_token()?;
 
+        let body = tokio::fs::read(tarball.path())
+            .await
+            .with_context(|| format!("failed to read tarball: {}", tarball.path()))?;
+
+        debug!("uploading {package} to: {upload_url}");
+        let http = self.config.http_for(self.source_id)?;
         let response = self
-            .config
-            .http()?
-            .get(dl_url)
+            .retry
+            .send_rate_limited_unchecked(|| {
+                let mut request = http
+                    .put(upload_url.clone())
+                    .bearer_auth(&token)
+                    .header(CONTENT_TYPE, "application/octet-stream")
+                    .body(body.clone());
+                if let Some(otp) = otp {
+                    request = request.header(OTP, otp);
+                }
+                Ok(request)
+            })
+            .await
+            .context("registry rejected the package")?;
+
+        if response.status() == StatusCode::UNAUTHORIZED
+            && response
+                .headers()
+                .get(OTP)
+                .is_some_and(|value| value == "required")
+        {
+            bail!(OtpRequired);
+        }
+        response
+            .error_for_status()
+            .context("registry rejected the package")?;
+
+        Ok(())
+    }
+
+    /// Upload the attestation with a `PUT` request to `{api}v1/packages/{name}/{version}/provenance`
+    /// endpoint.
+    async fn publish_provenance(
+        &self,
+        package: PackageId,
+        provenance: SignedProvenance,
+    ) -> Result<()> {
+        let url = self
+            .api_url(&format!(
+                "v1/packages/{}/{}/provenance",
+                package.name, package.version
+            ))
+            .await?;
+        let token = self.required_token()?;
+
+        debug!("uploading provenance attestation of {package} to: {url}");
+        let http = self.config.http_for(self.source_id)?;
+        self.retry
+            .send_rate_limited(|| Ok(http.put(url.clone()).bearer_auth(&token).json(&provenance)))
+            .await
+            .context("registry rejected the provenance attestation")?;
+
+        Ok(())
+    }
+
+    /// Send a `DELETE` request to `{api}v1/packages/{name}/{version}/yank` endpoint.
+    async fn yank(&self, package: PackageId) -> Result<()> {
+        let url = self
+            .api_url(&format!(
+                "v1/packages/{}/{}/yank",
+                package.name, package.version
+            ))
+            .await?;
+        let token = self.required_token()?;
+
+        debug!("yanking {package} at: {url}");
+        self.config
+            .http_for(self.source_id)?
+            .delete(url)
+            .bearer_auth(token)
             .send()
             .await?
-            .error_for_status()?;
+            .error_for_status()
+            .context("registry rejected the yank request")?;
 
-        let output_path = self.dl_fs.path_existent()?.join(package.tarball_name());
-        let output_file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(&output_path)
+        Ok(())
+    }
+
+    /// Send a `PUT` request to `{api}v1/packages/{name}/{version}/unyank` endpoint.
+    async fn unyank(&self, package: PackageId) -> Result<()> {
+        let url = self
+            .api_url(&format!(
+                "v1/packages/{}/{}/unyank",
+                package.name, package.version
+            ))
+            .await?;
+        let token = self.required_token()?;
+
+        debug!("unyanking {package} at: {url}");
+        self.config
+            .http_for(self.source_id)?
+            .put(url)
+            .bearer_auth(token)
+            .send()
+            .await?
+            .error_for_status()
+            .context("registry rejected the unyank request")?;
+
+        Ok(())
+    }
+
+    /// Send a `GET` request to `{api}v1/packages/{name}/owners` endpoint.
+    async fn list_owners(&self, package: PackageName) -> Result<Vec<String>> {
+        let url = self.owners_url(&package).await?;
+        let token = self.required_token()?;
+
+        let owners: OwnersList = self
+            .config
+            .http_for(self.source_id)?
+            .get(url)
+            .bearer_auth(token)
+            .send()
+            .await?
+            .error_for_status()
+            .context("registry rejected the request")?
+            .json()
             .await
-            .with_context(|| format!("failed to open: {output_path}"))?;
+            .context("failed to deserialize owners list")?;
 
-        output_file
-            .lock_exclusive()
-            .with_context(|| format!("failed to lock file: {output_path}"))?;
+        Ok(owners.owners)
+    }
 
-        let mut stream = response.bytes_stream();
-        let mut writer = BufWriter::new(output_file);
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("failed to read response chunk")?;
-            io::copy_buf(&mut &*chunk, &mut writer)
-                .await
-                .context("failed to save response chunk on disk")?;
-        }
+    /// Send a `PUT` request with new owners to `{api}v1/packages/{name}/owners` endpoint.
+    async fn add_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let url = self.owners_url(&package).await?;
+        let token = self.required_token()?;
 
-        Ok(output_path.into_std_path_buf())
+        debug!("adding owners of {package} at: {url}");
+        self.config
+            .http_for(self.source_id)?
+            .put(url)
+            .bearer_auth(token)
+            .json(&OwnersList {
+                owners: logins.to_vec(),
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .context("registry rejected the request")?;
+
+        Ok(())
     }
 
-    async fn supports_publish(&self) -> Result<bool> {
-        // TODO(mkaput): Publishing to HTTP registries is not implemented yet.
-        Ok(false)
+    /// Send a `DELETE` request with removed owners to `{api}v1/packages/{name}/owners` endpoint.
+    async fn remove_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let url = self.owners_url(&package).await?;
+        let token = self.required_token()?;
+
+        debug!("removing owners of {package} at: {url}");
+        self.config
+            .http_for(self.source_id)?
+            .delete(url)
+            .bearer_auth(token)
+            .json(&OwnersList {
+                owners: logins.to_vec(),
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .context("registry rejected the request")?;
+
+        Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+    use test_case::test_case;
+
+    use super::{apply_delta, check_required_metadata, parse_content_range_start};
+    use crate::core::registry::index::IndexRecord;
+    use crate::core::{Checksum, ManifestMetadata};
+
+    #[test_case("bytes 100-199/200" => Some(100))]
+    #[test_case("bytes 0-0/*" => Some(0))]
+    #[test_case("bytes */200" => None)]
+    #[test_case("items 100-199/200" => None)]
+    #[test_case("bytes abc-199/200" => None)]
+    fn content_range_start(value: &str) -> Option<u64> {
+        parse_content_range_start(value)
+    }
+
+    fn record(version: &str, yanked: bool) -> IndexRecord {
+        IndexRecord {
+            version: Version::parse(version).unwrap(),
+            dependencies: Vec::new(),
+            checksum: Checksum::parse(
+                "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+            )
+            .unwrap(),
+            no_core: false,
+            yanked,
+            size: None,
+            features: Default::default(),
+        }
+    }
+
+    #[test]
+    fn delta_updates_records() {
+        let mut records = vec![record("1.0.0", false), record("1.1.0", false)];
+        apply_delta(
+            &mut records,
+            vec![record("1.0.0", true), record("1.2.0", false)],
+        );
+        assert_eq!(
+            records,
+            vec![
+                record("1.0.0", true),
+                record("1.1.0", false),
+                record("1.2.0", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn required_metadata() {
+        let mut metadata = ManifestMetadata::default();
+        assert_eq!(
+            check_required_metadata(&metadata).unwrap_err().to_string(),
+            "manifest is missing fields required by registry: `description`, \
+            `license` or `license-file`\n\
+            help: set them in the `[package]` section of Scarb.toml"
+        );
+
+        metadata.description = Some("A package.".to_string());
+        metadata.license_file = Some("LICENSE".to_string());
+        assert!(check_required_metadata(&metadata).is_ok());
 
-    async fn publish(&self, _package: Package, _tarball: FileLockGuard) -> Result<()> {
-        todo!("Publishing to HTTP registries is not implemented yet.")
+        metadata.description = Some(" ".to_string());
+        assert!(check_required_metadata(&metadata).is_err());
     }
 }
@@ -1,19 +1,22 @@
 use std::fs::OpenOptions;
 use std::io;
 use std::io::{BufReader, BufWriter, Seek, SeekFrom};
-use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::{ensure, Context, Error, Result};
+use anyhow::{bail, ensure, Context, Error, Result};
 use async_trait::async_trait;
 use fs4::FileExt;
 use tokio::task::spawn_blocking;
 use url::Url;
-
-use crate::core::registry::client::RegistryClient;
-use crate::core::registry::index::{IndexDependency, IndexRecord, IndexRecords, TemplateUrl};
-use crate::core::{Checksum, Digest, Package, PackageId, PackageName, Summary};
+use walkdir::WalkDir;
+
+use crate::core::registry::client::{DownloadProgress, RegistryClient};
+use crate::core::registry::index::{IndexRecord, IndexRecords, TemplateUrl};
+use crate::core::registry::provenance::{SignedProvenance, PROVENANCE_SUFFIX};
+use crate::core::registry::search::{SearchResult, SearchResults};
+use crate::core::registry::signature::SIGNATURE_SUFFIX;
+use crate::core::{Digest, Package, PackageId, PackageName, Summary};
 use crate::flock::FileLockGuard;
 use crate::internal::fsx;
 
@@ -37,12 +40,18 @@ use crate::internal::fsx;
 /// │  └── op/
 /// │     └── en/
 /// │        └── open_zeppelin.json
+/// ├── owners/                             # owners of packages, managed with `scarb owner`
+/// │  └── alexandria_math.json
 /// ├── alexandria_ascii-0.1.0.tar.zst      # pre-downloaded package tarballs
 /// ├── alexandria_math-0.1.0.tar.zst
+/// ├── alexandria_math-0.1.0.tar.zst.minisig  # optional package signature
+/// ├── alexandria_math-0.1.0.tar.zst.provenance.json  # optional provenance attestation
+/// ├── alexandria_math-0.1.0.tar.zst.provenance.json.minisig
 /// ├── cairo_lib-0.2.0.tar.zst
 /// └── open_zeppelin-0.7.0.tar.zst
 /// ```
 pub struct LocalRegistryClient {
+    root: PathBuf,
     index_template_url: TemplateUrl,
     dl_template_url: TemplateUrl,
 }
@@ -59,7 +68,7 @@ impl LocalRegistryClient {
 
         let root = fsx::canonicalize(root)?;
 
-        let root_url = Url::from_directory_path(root)
+        let root_url = Url::from_directory_path(&root)
             .expect("Canonical path should always be convertible to URL.");
 
         let index_template_url =
@@ -69,6 +78,7 @@ impl LocalRegistryClient {
             TemplateUrl::new(&format!("{root_url}{{package}}-{{version}}.tar.zst"));
 
         Ok(Self {
+            root,
             index_template_url,
             dl_template_url,
         })
@@ -89,6 +99,59 @@ impl LocalRegistryClient {
             .to_file_path()
             .unwrap()
     }
+
+    /// Path to the file next to the package tarball, with `suffix` appended to its name.
+    fn dl_sibling_path(&self, package: PackageId, suffix: &str) -> PathBuf {
+        let mut path = self.dl_path(package).into_os_string();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+
+    fn owners_path(&self, package: &PackageName) -> PathBuf {
+        self.root.join("owners").join(format!("{package}.json"))
+    }
+
+    /// Edit the list of owners of the package, which must be published in this registry.
+    async fn edit_owners(
+        &self,
+        package: PackageName,
+        func: impl FnOnce(&mut Vec<String>) -> Result<()> + Send + 'static,
+    ) -> Result<()> {
+        let records_path = self.records_path(&package);
+        let owners_path = self.owners_path(&package);
+        spawn_blocking(move || {
+            ensure!(
+                records_path.is_file(),
+                "package `{package}` is not published in this registry"
+            );
+            let mut owners = read_owners(&owners_path)?;
+            func(&mut owners)?;
+            fsx::create_dir_all(owners_path.parent().unwrap())?;
+            fsx::write(&owners_path, serde_json::to_string(&owners)?)
+        })
+        .await?
+    }
+
+    async fn set_yanked(&self, package: PackageId, yanked: bool) -> Result<()> {
+        let records_path = self.records_path(&package.name);
+        spawn_blocking(move || {
+            ensure!(
+                records_path.is_file(),
+                "package version is not published in this registry"
+            );
+            let mut found = false;
+            edit_records(&records_path, |records| {
+                if let Some(record) = records.iter_mut().find(|r| r.version == package.version) {
+                    record.yanked = yanked;
+                    found = true;
+                }
+            })
+            .with_context(|| format!("failed to edit records file: {}", records_path.display()))?;
+            ensure!(found, "package version is not published in this registry");
+            Ok(())
+        })
+        .await?
+    }
 }
 
 #[async_trait]
@@ -121,36 +184,208 @@ impl RegistryClient for LocalRegistryClient {
         true
     }
 
-    async fn download(&self, package: PackageId) -> Result<PathBuf> {
+    async fn download(
+        &self,
+        package: PackageId,
+        _progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
         Ok(self.dl_path(package))
     }
 
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        let signature_path = self.dl_sibling_path(package, SIGNATURE_SUFFIX);
+
+        spawn_blocking(move || {
+            if !signature_path.is_file() {
+                return Ok(None);
+            }
+            fsx::read_to_string(signature_path).map(Some)
+        })
+        .await?
+    }
+
+    async fn get_provenance(&self, package: PackageId) -> Result<Option<SignedProvenance>> {
+        let attestation_path = self.dl_sibling_path(package, PROVENANCE_SUFFIX);
+        let signature_path =
+            self.dl_sibling_path(package, &format!("{PROVENANCE_SUFFIX}{SIGNATURE_SUFFIX}"));
+
+        spawn_blocking(move || {
+            if !attestation_path.is_file() {
+                return Ok(None);
+            }
+            Ok(Some(SignedProvenance {
+                attestation: fsx::read_to_string(attestation_path)?,
+                signature: fsx::read_to_string(signature_path)?,
+            }))
+        })
+        .await?
+    }
+
+    /// Match `query` against names of all packages in the index.
+    ///
+    /// Local registries do not store package descriptions, so these are never returned.
+    async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        let index_dir = self.root.join("index");
+        let query = query.to_lowercase();
+        spawn_blocking(move || search_impl(&index_dir, &query, limit)).await?
+    }
+
     async fn supports_publish(&self) -> Result<bool> {
         Ok(true)
     }
 
-    async fn publish(&self, package: Package, tarball: FileLockGuard) -> Result<()> {
+    /// Local registries do not authenticate users, so `otp` is ignored.
+    async fn publish(
+        &self,
+        package: Package,
+        tarball: &FileLockGuard,
+        otp: Option<&str>,
+    ) -> Result<()> {
+        let _ = otp;
         let summary = package.manifest.summary.clone();
         let records_path = self.records_path(&summary.package_id.name);
         let dl_path = self.dl_path(summary.package_id);
+        let tarball_path = tarball.path().as_std_path().to_owned();
 
-        spawn_blocking(move || publish_impl(summary, tarball, records_path, dl_path))
+        spawn_blocking(move || publish_impl(summary, &tarball_path, records_path, dl_path))
             .await
             .with_context(|| format!("failed to publish package: {package}"))?
     }
+
+    async fn publish_provenance(
+        &self,
+        package: PackageId,
+        provenance: SignedProvenance,
+    ) -> Result<()> {
+        let attestation_path = self.dl_sibling_path(package, PROVENANCE_SUFFIX);
+        let signature_path =
+            self.dl_sibling_path(package, &format!("{PROVENANCE_SUFFIX}{SIGNATURE_SUFFIX}"));
+
+        spawn_blocking(move || {
+            fsx::write(attestation_path, provenance.attestation)?;
+            fsx::write(signature_path, provenance.signature)
+        })
+        .await?
+        .with_context(|| format!("failed to publish provenance attestation of: {package}"))
+    }
+
+    async fn yank(&self, package: PackageId) -> Result<()> {
+        self.set_yanked(package, true).await
+    }
+
+    async fn unyank(&self, package: PackageId) -> Result<()> {
+        self.set_yanked(package, false).await
+    }
+
+    /// Owners are stored in `owners/{package}.json` files next to the index.
+    ///
+    /// Local registries do not authenticate users, so owners are only informative here.
+    async fn list_owners(&self, package: PackageName) -> Result<Vec<String>> {
+        let records_path = self.records_path(&package);
+        let owners_path = self.owners_path(&package);
+        spawn_blocking(move || {
+            ensure!(
+                records_path.is_file(),
+                "package `{package}` is not published in this registry"
+            );
+            read_owners(&owners_path)
+        })
+        .await?
+    }
+
+    async fn add_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let logins = logins.to_vec();
+        self.edit_owners(package, move |owners| {
+            for login in logins {
+                if !owners.contains(&login) {
+                    owners.push(login);
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let logins = logins.to_vec();
+        let package_name = package.clone();
+        self.edit_owners(package, move |owners| {
+            for login in logins {
+                let Some(idx) = owners.iter().position(|o| *o == login) else {
+                    bail!("`{login}` is not an owner of package `{package_name}`");
+                };
+                owners.remove(idx);
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+fn search_impl(index_dir: &Path, query: &str, limit: usize) -> Result<SearchResults> {
+    let mut packages = Vec::new();
+    if index_dir.is_dir() {
+        for entry in WalkDir::new(index_dir).sort_by_file_name() {
+            let entry = entry?;
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension() != Some("json".as_ref()) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !name.to_lowercase().contains(query) {
+                continue;
+            }
+
+            let records: IndexRecords = serde_json::from_slice(&fsx::read(path)?)
+                .with_context(|| format!("failed to read records file: {}", path.display()))?;
+            let Some(version) = records
+                .into_iter()
+                .filter(|r| !r.yanked)
+                .map(|r| r.version)
+                .max()
+            else {
+                continue;
+            };
+
+            packages.push(SearchResult {
+                name: PackageName::try_new(name)?,
+                version,
+                description: None,
+            });
+        }
+    }
+
+    let total = packages.len();
+    packages.truncate(limit);
+    Ok(SearchResults { packages, total })
+}
+
+fn read_owners(owners_path: &Path) -> Result<Vec<String>> {
+    if !owners_path.is_file() {
+        return Ok(Vec::new());
+    }
+    let owners = fsx::read(owners_path)?;
+    serde_json::from_slice(&owners)
+        .with_context(|| format!("failed to read owners file: {}", owners_path.display()))
 }
 
 fn publish_impl(
     summary: Summary,
-    tarball: FileLockGuard,
+    tarball: &Path,
     records_path: PathBuf,
     dl_path: PathBuf,
 ) -> Result<(), Error> {
-    fsx::copy(tarball.path(), dl_path)?;
+    fsx::copy(tarball, dl_path)?;
 
-    let checksum = Digest::recommended().update_read(tarball.deref())?.finish();
+    let tarball = fsx::open(tarball)?;
+    let checksum = Digest::recommended().update_read(&tarball)?.finish();
 
-    let record = build_record(summary, checksum);
+    let record = IndexRecord {
+        size: Some(tarball.metadata()?.len()),
+        ..IndexRecord::from_summary(&summary, checksum)
+    };
 
     edit_records(&records_path, move |records| {
         // Remove existing record if exists (note: version is the key).
@@ -167,21 +402,6 @@ fn publish_impl(
     Ok(())
 }
 
-fn build_record(summary: Summary, checksum: Checksum) -> IndexRecord {
-    IndexRecord {
-        version: summary.package_id.version.clone(),
-        dependencies: summary
-            .publish_dependencies()
-            .map(|dep| IndexDependency {
-                name: dep.name.clone(),
-                req: dep.version_req.clone().into(),
-            })
-            .collect(),
-        checksum,
-        no_core: summary.no_core,
-    }
-}
-
 fn edit_records(records_path: &Path, func: impl FnOnce(&mut IndexRecords)) -> Result<()> {
     fsx::create_dir_all(records_path.parent().unwrap())?;
     let mut file = OpenOptions::new()
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Error, Result};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use tracing::debug;
+
+use crate::core::registry::client::{DownloadProgress, RegistryClient, TarballStream};
+use crate::core::registry::index::IndexRecords;
+use crate::core::registry::provenance::SignedProvenance;
+use crate::core::registry::search::SearchResults;
+use crate::core::{Config, ManifestMetadata, Package, PackageId, PackageName, SourceId};
+use crate::flock::FileLockGuard;
+
+/// A registry client which fails over to mirrors when the primary registry does not respond.
+///
+/// Clients are tried in order, starting with the one which responded successfully most recently.
+/// Only errors trigger a failover: if a registry states that a package does not exist,
+/// this answer is trusted and mirrors are not asked.
+/// Publishing, yanking and owner management always go to the primary registry.
+pub struct MirroredRegistryClient<'c> {
+    config: &'c Config,
+    /// Primary registry client goes first, then mirrors in configured order.
+    clients: Vec<(SourceId, Box<dyn RegistryClient + 'c>)>,
+    /// Index of the client that most recently succeeded.
+    preferred: AtomicUsize,
+}
+
+impl<'c> MirroredRegistryClient<'c> {
+    pub fn new(config: &'c Config, clients: Vec<(SourceId, Box<dyn RegistryClient + 'c>)>) -> Self {
+        assert!(!clients.is_empty());
+        Self {
+            config,
+            clients,
+            preferred: AtomicUsize::new(0),
+        }
+    }
+
+    fn primary(&self) -> &dyn RegistryClient {
+        self.clients[0].1.as_ref()
+    }
+
+    async fn with_failover<'a, T>(
+        &'a self,
+        op: impl Fn(&'a dyn RegistryClient) -> BoxFuture<'a, Result<T>>,
+    ) -> Result<T> {
+        let preferred = self.preferred.load(Ordering::Relaxed);
+        let order = (0..self.clients.len()).map(|i| (preferred + i) % self.clients.len());
+
+        let mut last_err: Option<Error> = None;
+        for idx in order {
+            let (source_id, client) = &self.clients[idx];
+            match op(client.as_ref()).await {
+                Ok(value) => {
+                    if idx != preferred {
+                        debug!("switching preferred registry mirror to: {source_id}");
+                        self.preferred.store(idx, Ordering::Relaxed);
+                    }
+                    return Ok(value);
+                }
+                Err(err) => {
+                    self.config.ui().warn(format!(
+                        "registry `{source_id}` failed, trying next mirror: {err:#}"
+                    ));
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err
+            .expect("at least one registry client must be present")
+            .context("all registry mirrors failed"))
+    }
+}
+
+#[async_trait]
+impl<'c> RegistryClient for MirroredRegistryClient<'c> {
+    fn is_offline(&self) -> bool {
+        self.primary().is_offline()
+    }
+
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        self.with_failover(|client| client.get_records(package.clone()))
+            .await
+    }
+
+    async fn is_downloaded(&self, package: PackageId) -> bool {
+        for (_, client) in &self.clients {
+            if client.is_downloaded(package).await {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        self.with_failover(|client| client.download(package, progress))
+            .await
+    }
+
+    async fn download_stream(&self, package: PackageId) -> Result<Option<TarballStream>> {
+        self.with_failover(|client| client.download_stream(package))
+            .await
+    }
+
+    fn unpacked_path(&self, package: PackageId) -> Option<PathBuf> {
+        self.primary().unpacked_path(package)
+    }
+
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        self.with_failover(|client| client.get_signature(package))
+            .await
+    }
+
+    async fn get_provenance(&self, package: PackageId) -> Result<Option<SignedProvenance>> {
+        self.with_failover(|client| client.get_provenance(package))
+            .await
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        self.with_failover(|client| client.search(query, limit))
+            .await
+    }
+
+    async fn supports_publish(&self) -> Result<bool> {
+        self.primary().supports_publish().await
+    }
+
+    async fn check_publish(&self, package: PackageId, metadata: &ManifestMetadata) -> Result<()> {
+        self.primary().check_publish(package, metadata).await
+    }
+
+    async fn publish(
+        &self,
+        package: Package,
+        tarball: &FileLockGuard,
+        otp: Option<&str>,
+    ) -> Result<()> {
+        self.primary().publish(package, tarball, otp).await
+    }
+
+    async fn publish_provenance(
+        &self,
+        package: PackageId,
+        provenance: SignedProvenance,
+    ) -> Result<()> {
+        self.primary().publish_provenance(package, provenance).await
+    }
+
+    async fn yank(&self, package: PackageId) -> Result<()> {
+        self.primary().yank(package).await
+    }
+
+    async fn unyank(&self, package: PackageId) -> Result<()> {
+        self.primary().unyank(package).await
+    }
+
+    async fn list_owners(&self, package: PackageName) -> Result<Vec<String>> {
+        self.primary().list_owners(package).await
+    }
+
+    async fn add_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        self.primary().add_owners(package, logins).await
+    }
+
+    async fn remove_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        self.primary().remove_owners(package, logins).await
+    }
+}
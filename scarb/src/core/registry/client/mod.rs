@@ -1,15 +1,77 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use thiserror::Error;
+
+use scarb_ui::components::ProgressBarHandle;
 
 use crate::core::registry::index::IndexRecords;
-use crate::core::{Package, PackageId, PackageName};
+use crate::core::registry::provenance::SignedProvenance;
+#[cfg(doc)]
+use crate::core::registry::provenance::PROVENANCE_SUFFIX;
+use crate::core::registry::search::SearchResults;
+#[cfg(doc)]
+use crate::core::registry::signature::SIGNATURE_SUFFIX;
+use crate::core::{ManifestMetadata, Package, PackageId, PackageName};
 use crate::flock::FileLockGuard;
 
+pub mod cache;
+pub mod directory;
+pub mod docker_credentials;
+pub mod git;
 pub mod http;
 pub mod local;
+pub mod mirrored;
+pub mod object_storage;
+pub mod object_storage_credentials;
+pub mod oci;
+pub mod retry;
+
+/// Error returned by [`RegistryClient::publish`] when the registry requires a one-time password
+/// to accept the upload, and none or an invalid one has been provided.
+#[derive(Debug, Error)]
+#[error("registry requires a one-time password to publish packages")]
+pub struct OtpRequired;
+
+/// Receives progress updates of a package tarball download.
+pub trait DownloadProgress: Send + Sync {
+    /// Called whenever more bytes of the tarball have been received.
+    ///
+    /// The `downloaded` counter includes bytes received in all previous updates.
+    /// The `total` size is `None` if it is not known upfront.
+    /// Returning an error aborts the download, before the newly received bytes are stored.
+    fn update(&self, downloaded: u64, total: Option<u64>) -> Result<()>;
+}
+
+/// A [`DownloadProgress`] which ignores all updates.
+pub struct NoProgress;
+
+impl DownloadProgress for NoProgress {
+    fn update(&self, _downloaded: u64, _total: Option<u64>) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl DownloadProgress for ProgressBarHandle {
+    fn update(&self, downloaded: u64, total: Option<u64>) -> Result<()> {
+        if let Some(total) = total {
+            self.set_length(total);
+        }
+        self.set_position(downloaded);
+        Ok(())
+    }
+}
+
+/// Contents of a package `.tar.zst` file, as they are being received from the registry.
+pub struct TarballStream {
+    /// Size of the whole tarball, if it is known upfront.
+    pub total: Option<u64>,
+    pub chunks: BoxStream<'static, Result<Bytes>>,
+}
 
 #[async_trait]
 pub trait RegistryClient: Send + Sync {
@@ -38,13 +100,71 @@ pub trait RegistryClient: Send + Sync {
     /// Download the package `.tar.zst` file.
     ///
     /// Returns a [`PathBuf`] to the downloaded `.tar.zst` file.
+    /// Clients which transfer files over the network should report download progress
+    /// to the `progress` callback.
     ///
     /// ## Caching
     ///
     /// If the registry is remote, i.e. actually downloads files and writes them to disk,
     /// it should write downloaded files to Scarb cache directory. If the file has already been
     /// downloaded, it should avoid downloading it again, and read it from this cache instead.
-    async fn download(&self, package: PackageId) -> Result<PathBuf>;
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf>;
+
+    /// Start downloading the package `.tar.zst` file, without storing it on disk.
+    ///
+    /// This allows unpacking the package while it is still being downloaded.
+    /// Returns `None` if the download should not be streamed, for example because
+    /// a previously interrupted download can be resumed, and [`RegistryClient::download`]
+    /// should be used instead.
+    /// Default implementation states that the registry does not support streaming downloads.
+    async fn download_stream(&self, package: PackageId) -> Result<Option<TarballStream>> {
+        let _ = package;
+        Ok(None)
+    }
+
+    /// Get the path to unpacked sources of the package, if this registry stores packages
+    /// unpacked instead of as `.tar.zst` files.
+    ///
+    /// Such packages are loaded directly from this path, skipping [`RegistryClient::download`]
+    /// and tarball verification. This method must not perform any IO operations.
+    /// Default implementation states that packages are stored as tarballs.
+    fn unpacked_path(&self, package: PackageId) -> Option<PathBuf> {
+        let _ = package;
+        None
+    }
+
+    /// Fetch the detached signature of the package `.tar.zst` file, published next to it
+    /// with the [`SIGNATURE_SUFFIX`] suffix.
+    ///
+    /// Returns `None` if the registry does not provide a signature for this package.
+    /// Default implementation states that the registry does not support signatures at all.
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        let _ = package;
+        Ok(None)
+    }
+
+    /// Fetch the provenance attestation of the package, published next to its `.tar.zst` file
+    /// with the [`PROVENANCE_SUFFIX`] suffix, along with the detached signature of the attestation.
+    ///
+    /// Returns `None` if the registry does not provide an attestation for this package.
+    /// Default implementation states that the registry does not support attestations at all.
+    async fn get_provenance(&self, package: PackageId) -> Result<Option<SignedProvenance>> {
+        let _ = package;
+        Ok(None)
+    }
+
+    /// Find packages whose names or descriptions match `query`, returning at most `limit` results.
+    ///
+    /// Default implementation states that the registry does not support searching.
+    async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        let _ = query;
+        let _ = limit;
+        bail!("searching packages is not supported by this registry")
+    }
 
     /// State whether packages can be published to this registry.
     ///
@@ -53,6 +173,18 @@ pub trait RegistryClient: Send + Sync {
         Ok(false)
     }
 
+    /// Check whether `package` can be published to this registry, without uploading anything.
+    ///
+    /// This is called before the package is built, so that problems like missing credentials
+    /// are reported early, and by `scarb publish --dry-run`.
+    /// The `metadata` comes from the manifest of the package, for registries which require
+    /// certain fields to be set.
+    /// Default implementation does not do any checks.
+    async fn check_publish(&self, package: PackageId, metadata: &ManifestMetadata) -> Result<()> {
+        let _ = (package, metadata);
+        Ok(())
+    }
+
     /// Publish a package to this registry.
     ///
     /// This function can only be called if [`RegistryClient::supports_publish`] returns `true`.
@@ -61,10 +193,77 @@ pub trait RegistryClient: Send + Sync {
     /// The `package` argument must correspond to just packaged `tarball` file.
     /// The client is free to use information within `package` to send to the registry.
     /// Package source is not required to match the registry the package is published to.
-    async fn publish(&self, package: Package, tarball: FileLockGuard) -> Result<()> {
+    ///
+    /// The `otp` is a one-time password of the user, for registries enforcing two-factor
+    /// authentication. Such registries should fail with [`OtpRequired`] if it is missing.
+    async fn publish(
+        &self,
+        package: Package,
+        tarball: &FileLockGuard,
+        otp: Option<&str>,
+    ) -> Result<()> {
         // Silence clippy warnings without using _ in argument names.
         let _ = package;
         let _ = tarball;
+        let _ = otp;
         unreachable!("This registry does not support publishing.")
     }
+
+    /// Upload a signed provenance attestation of an already published package.
+    ///
+    /// This function can only be called if [`RegistryClient::supports_publish`] returns `true`.
+    /// Default implementation states that the registry does not support attestations.
+    async fn publish_provenance(
+        &self,
+        package: PackageId,
+        provenance: SignedProvenance,
+    ) -> Result<()> {
+        let _ = package;
+        let _ = provenance;
+        bail!("provenance attestations are not supported by this registry")
+    }
+
+    /// Mark a published package version as yanked, so that it is no longer selected
+    /// by the resolver for new dependants.
+    ///
+    /// Yanking is part of the publishing API, so this function can only be called if
+    /// [`RegistryClient::supports_publish`] returns `true`.
+    /// Default implementation panics with [`unreachable!`].
+    async fn yank(&self, package: PackageId) -> Result<()> {
+        let _ = package;
+        unreachable!("This registry does not support yanking.")
+    }
+
+    /// Revert the effect of [`RegistryClient::yank`].
+    ///
+    /// This function can only be called if [`RegistryClient::supports_publish`] returns `true`.
+    /// Default implementation panics with [`unreachable!`].
+    async fn unyank(&self, package: PackageId) -> Result<()> {
+        let _ = package;
+        unreachable!("This registry does not support yanking.")
+    }
+
+    /// Get logins of users allowed to publish new versions of the package.
+    ///
+    /// Owners are managed through the publishing API, so this function and the other ones
+    /// operating on owners can only be called if [`RegistryClient::supports_publish`]
+    /// returns `true`. Default implementation panics with [`unreachable!`].
+    async fn list_owners(&self, package: PackageName) -> Result<Vec<String>> {
+        let _ = package;
+        unreachable!("This registry does not support managing owners.")
+    }
+
+    /// Grant users with given logins ownership of the package.
+    async fn add_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let _ = package;
+        let _ = logins;
+        unreachable!("This registry does not support managing owners.")
+    }
+
+    /// Revoke ownership of the package from users with given logins.
+    async fn remove_owners(&self, package: PackageName, logins: &[String]) -> Result<()> {
+        let _ = package;
+        let _ = logins;
+        unreachable!("This registry does not support managing owners.")
+    }
 }
@@ -0,0 +1,325 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::{bail, ensure, Context, Result};
+use async_trait::async_trait;
+use fs4::tokio::AsyncFileExt;
+use futures::StreamExt;
+use reqwest::{Response, StatusCode};
+use tokio::fs::OpenOptions;
+use tokio::io;
+use tokio::io::BufWriter;
+use tokio::sync::OnceCell;
+use tracing::{debug, trace};
+use url::Url;
+
+use crate::core::registry::client::object_storage_credentials::{
+    gcs_token, AwsCredentials, ObjectStorageCredentials,
+};
+use crate::core::registry::client::{DownloadProgress, RegistryClient};
+use crate::core::registry::index::{IndexRecords, TemplateUrl};
+use crate::core::registry::signature::signature_url;
+use crate::core::{Config, PackageId, PackageName, SourceId};
+use crate::flock::Filesystem;
+
+/// Object storage service hosting the registry bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectStorageKind {
+    /// Amazon S3 or any S3-compatible service (MinIO, Cloudflare R2, etc.).
+    S3,
+    /// Google Cloud Storage.
+    Gcs,
+}
+
+impl ObjectStorageKind {
+    pub fn from_scheme(scheme: &str) -> Option<Self> {
+        match scheme {
+            "s3" => Some(Self::S3),
+            "gs" | "gcs" => Some(Self::Gcs),
+            _ => None,
+        }
+    }
+
+    /// Look up credentials configured for this service, see [`AwsCredentials::from_env`]
+    /// and [`gcs_token`].
+    async fn credentials(
+        self,
+        source_id: SourceId,
+        config: &Config,
+    ) -> Result<Option<ObjectStorageCredentials>> {
+        Ok(match self {
+            Self::S3 => AwsCredentials::from_env()?.map(ObjectStorageCredentials::Aws),
+            Self::Gcs => gcs_token(source_id, config)
+                .await?
+                .map(ObjectStorageCredentials::GcsToken),
+        })
+    }
+
+    /// Help message on how to configure credentials for this service.
+    fn credentials_help(self) -> &'static str {
+        match self {
+            Self::S3 => {
+                "help: set `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY` environment variables \
+                to access private buckets"
+            }
+            Self::Gcs => {
+                "help: set `GOOGLE_APPLICATION_CREDENTIALS` environment variable, or log in \
+                with `scarb login`, to access private buckets"
+            }
+        }
+    }
+
+    /// Compute base HTTP(S) URL of the bucket, pointing to the `prefix` directory.
+    ///
+    /// Custom endpoints can be provided using the same environment variables as official
+    /// cloud SDKs use: `AWS_ENDPOINT_URL` for S3 and `STORAGE_EMULATOR_HOST` for GCS.
+    /// If a custom endpoint is set, path-style addressing is used.
+    fn bucket_url(self, bucket: &str, prefix: &str) -> Result<Url> {
+        let prefix = prefix.trim_matches('/');
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}/")
+        };
+
+        let url = match self {
+            Self::S3 => match env::var("AWS_ENDPOINT_URL") {
+                Ok(endpoint) => format!("{}/{bucket}/{prefix}", endpoint.trim_end_matches('/')),
+                Err(_) => {
+                    match env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")) {
+                        Ok(region) => {
+                            format!("https://{bucket}.s3.{region}.amazonaws.com/{prefix}")
+                        }
+                        Err(_) => format!("https://{bucket}.s3.amazonaws.com/{prefix}"),
+                    }
+                }
+            },
+            Self::Gcs => {
+                let endpoint = env::var("STORAGE_EMULATOR_HOST")
+                    .unwrap_or_else(|_| "https://storage.googleapis.com".to_string());
+                format!("{}/{bucket}/{prefix}", endpoint.trim_end_matches('/'))
+            }
+        };
+
+        Url::parse(&url).with_context(|| format!("invalid object storage url: {url}"))
+    }
+}
+
+/// Remote registry stored as plain objects in an S3-compatible or GCS bucket.
+///
+/// The bucket (under an optional prefix) is expected to follow exactly the same layout as
+/// [`LocalRegistryClient`][super::local::LocalRegistryClient] uses on the filesystem,
+/// so a local registry directory can be synced to a bucket as-is:
+///
+/// ```text
+/// s3://[bucket]/[prefix]/
+/// ├── index/
+/// │  └── al/
+/// │     └── ex/
+/// │        └── alexandria_math.json
+/// ├── alexandria_math-0.1.0.tar.zst
+/// └── alexandria_math-0.1.0.tar.zst.minisig
+/// ```
+///
+/// Requests to S3 are signed with the AWS access key from environment variables, while
+/// requests to GCS carry an OAuth access token (see [`gcs_token`]). If no credentials are
+/// configured, objects are fetched anonymously, hence the bucket must allow public reads.
+pub struct ObjectStorageRegistryClient<'c> {
+    source_id: SourceId,
+    config: &'c Config,
+    kind: ObjectStorageKind,
+    credentials: OnceCell<Option<ObjectStorageCredentials>>,
+    index_template_url: TemplateUrl,
+    dl_template_url: TemplateUrl,
+    dl_fs: Filesystem<'c>,
+}
+
+impl<'c> ObjectStorageRegistryClient<'c> {
+    pub fn new(source_id: SourceId, config: &'c Config) -> Result<Self> {
+        let Some(kind) = ObjectStorageKind::from_scheme(source_id.url.scheme()) else {
+            bail!("unsupported object storage protocol: {source_id}");
+        };
+
+        let bucket = source_id.url.host_str().unwrap_or_default();
+        ensure!(
+            !bucket.is_empty(),
+            "object storage registry url must specify bucket name: {source_id}"
+        );
+
+        let bucket_url = kind.bucket_url(bucket, source_id.url.path())?;
+        trace!("object storage bucket url: {bucket_url}");
+
+        let index_template_url =
+            TemplateUrl::new(&format!("{bucket_url}index/{{prefix}}/{{package}}.json"));
+        let dl_template_url =
+            TemplateUrl::new(&format!("{bucket_url}{{package}}-{{version}}.tar.zst"));
+
+        let dl_fs = config
+            .dirs()
+            .registry_dir()
+            .into_child("dl")
+            .into_child(source_id.ident());
+
+        Ok(Self {
+            source_id,
+            config,
+            kind,
+            credentials: OnceCell::new(),
+            index_template_url,
+            dl_template_url,
+            dl_fs,
+        })
+    }
+
+    /// Send a `GET` request to `url`, authenticated with credentials configured for the bucket.
+    async fn get(&self, url: Url) -> Result<Response> {
+        let credentials = self
+            .credentials
+            .get_or_try_init(|| self.kind.credentials(self.source_id, self.config))
+            .await
+            .context("failed to look up object storage credentials")?;
+
+        let mut request = self.config.http_for(self.source_id)?.get(url.clone());
+        match credentials {
+            Some(ObjectStorageCredentials::Aws(credentials)) => {
+                for (name, value) in credentials.sign(&url, &[], SystemTime::now()) {
+                    request = request.header(name, value);
+                }
+            }
+            Some(ObjectStorageCredentials::GcsToken(token)) => {
+                request = request.bearer_auth(token);
+            }
+            None => {}
+        }
+        let response = request.send().await?;
+
+        // Private buckets deny anonymous access to all keys, tell users why it happens.
+        if credentials.is_none()
+            && matches!(
+                response.status(),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+            )
+        {
+            bail!(
+                "access to `{url}` has been denied, and no credentials are configured \
+                for object storage registry: {}\n{}",
+                self.source_id,
+                self.kind.credentials_help()
+            );
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl<'c> RegistryClient for ObjectStorageRegistryClient<'c> {
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        let records_url = self.index_template_url.expand(package.into())?;
+        debug!("fetching index records: {records_url}");
+
+        let response = self.get(records_url).await?;
+
+        // Buckets which do not allow listing respond with 403 instead of 404 for missing keys.
+        // Anonymous requests denied by private buckets have already failed in `get`.
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::FORBIDDEN
+        ) {
+            return Ok(None);
+        }
+
+        let records = response
+            .error_for_status()?
+            .json()
+            .await
+            .context("failed to deserialize index records")?;
+
+        Ok(Some(Arc::new(records)))
+    }
+
+    async fn is_downloaded(&self, _package: PackageId) -> bool {
+        false
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        let dl_url = self.dl_template_url.expand(package.into())?;
+
+        let response = self.get(dl_url).await?.error_for_status()?;
+
+        let output_path = self.dl_fs.path_existent()?.join(package.tarball_name());
+        let output_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&output_path)
+            .await
+            .with_context(|| format!("failed to open: {output_path}"))?;
+
+        output_file
+            .lock_exclusive()
+            .with_context(|| format!("failed to lock file: {output_path}"))?;
+
+        let total = response.content_length();
+        let mut downloaded = 0;
+        let mut stream = response.bytes_stream();
+        let mut writer = BufWriter::new(output_file);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("failed to read response chunk")?;
+            downloaded += chunk.len() as u64;
+            progress.update(downloaded, total)?;
+            io::copy_buf(&mut &*chunk, &mut writer)
+                .await
+                .context("failed to save response chunk on disk")?;
+        }
+
+        Ok(output_path.into_std_path_buf())
+    }
+
+    async fn get_signature(&self, package: PackageId) -> Result<Option<String>> {
+        let dl_url = self.dl_template_url.expand(package.into())?;
+        let signature_url = signature_url(&dl_url);
+        debug!("fetching package signature: {signature_url}");
+
+        let response = self.get(signature_url).await?;
+
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::FORBIDDEN
+        ) {
+            return Ok(None);
+        }
+
+        let signature = response
+            .error_for_status()?
+            .text()
+            .await
+            .context("failed to read package signature")?;
+        Ok(Some(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::ObjectStorageKind;
+
+    #[test_case("/", "https://storage.googleapis.com/bucket/"; "root")]
+    #[test_case("/a/b", "https://storage.googleapis.com/bucket/a/b/"; "prefix")]
+    #[test_case("/a/b/", "https://storage.googleapis.com/bucket/a/b/"; "prefix with trailing slash")]
+    fn gcs_bucket_url(prefix: &str, expected: &str) {
+        let url = ObjectStorageKind::Gcs.bucket_url("bucket", prefix).unwrap();
+        assert_eq!(url.as_str(), expected);
+    }
+}
@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use async_trait::async_trait;
+use fs4::tokio::AsyncFileExt;
+use futures::StreamExt;
+use reqwest::header::{ACCEPT, AUTHORIZATION, WWW_AUTHENTICATE};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use tokio::fs::OpenOptions;
+use tokio::io;
+use tokio::io::BufWriter;
+use tokio::task::spawn_blocking;
+use tracing::{debug, trace};
+use url::Url;
+
+use crate::core::registry::client::docker_credentials;
+use crate::core::registry::client::{DownloadProgress, RegistryClient};
+use crate::core::registry::index::IndexRecords;
+use crate::core::{Checksum, Config, Digest, PackageId, PackageName, SourceId};
+use crate::flock::Filesystem;
+
+/// Media types used by OCI artifacts storing Scarb packages.
+pub mod media_types {
+    /// OCI image manifest, used as the envelope of all Scarb artifacts.
+    pub const IMAGE_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+    /// Artifact type of manifests describing Scarb packages.
+    pub const PACKAGE_ARTIFACT: &str = "application/vnd.scarb.package.v1";
+    /// Layer containing JSON-serialized [`IndexRecords`][super::IndexRecords] of a package.
+    pub const INDEX_RECORDS: &str = "application/vnd.scarb.index.records.v1+json";
+    /// Layer containing `.tar.zst` package tarball.
+    pub const PACKAGE_TARBALL: &str = "application/vnd.scarb.package.tarball.v1.tar+zstd";
+}
+
+/// Tag under which the index records artifact of each package is stored.
+const INDEX_TAG: &str = "index";
+
+/// Remote registry stored as OCI artifacts in a container registry (GHCR, Harbor, Artifactory).
+///
+/// Registry URLs have the form `registry+oci://[host]/[namespace]`. Each package is stored in
+/// a separate repository named `[namespace]/[package]`, with the following tags:
+///
+/// ```text
+/// [host]/[namespace]/alexandria_math:index   # artifact with IndexRecords JSON layer
+/// [host]/[namespace]/alexandria_math:0.1.0   # artifact with .tar.zst package tarball layer
+/// ```
+///
+/// Semver build metadata separator `+` is not allowed in OCI tags, so it is replaced with `_`.
+///
+/// Credentials are resolved the same way Docker CLI does, from `~/.docker/config.json`
+/// and Docker credential helpers. Registries on `localhost` are accessed over plain HTTP.
+pub struct OciRegistryClient<'c> {
+    source_id: SourceId,
+    config: &'c Config,
+    base_url: Url,
+    host: String,
+    namespace: String,
+    dl_fs: Filesystem<'c>,
+    /// Cached `Authorization` header values, keyed by repository name.
+    authorizations: Mutex<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImageManifest {
+    schema_version: u32,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: Checksum,
+    size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+impl<'c> OciRegistryClient<'c> {
+    pub fn new(source_id: SourceId, config: &'c Config) -> Result<Self> {
+        let url = &source_id.url;
+        let Some(host_str) = url.host_str() else {
+            bail!("oci registry url must specify registry host: {source_id}");
+        };
+
+        let host = match url.port() {
+            Some(port) => format!("{host_str}:{port}"),
+            None => host_str.to_string(),
+        };
+
+        let namespace = url.path().trim_matches('/').to_string();
+        ensure!(
+            !namespace.is_empty(),
+            "oci registry url must specify repository namespace: {source_id}"
+        );
+
+        let scheme = if is_localhost(host_str) {
+            "http"
+        } else {
+            "https"
+        };
+        let base_url = Url::parse(&format!("{scheme}://{host}/v2/"))
+            .with_context(|| format!("invalid oci registry url: {source_id}"))?;
+        trace!("oci registry base url: {base_url}");
+
+        let dl_fs = config
+            .dirs()
+            .registry_dir()
+            .into_child("dl")
+            .into_child(source_id.ident());
+
+        Ok(Self {
+            source_id,
+            config,
+            base_url,
+            host,
+            namespace,
+            dl_fs,
+            authorizations: Default::default(),
+        })
+    }
+
+    fn repository(&self, package: &PackageName) -> String {
+        format!("{}/{package}", self.namespace)
+    }
+
+    /// Fetch image manifest under `reference` from `repository`.
+    ///
+    /// Returns `None` if either the repository or the tag does not exist.
+    async fn get_manifest(
+        &self,
+        repository: &str,
+        reference: &str,
+    ) -> Result<Option<ImageManifest>> {
+        let url = self
+            .base_url
+            .join(&format!("{repository}/manifests/{reference}"))?;
+        debug!("fetching oci manifest: {url}");
+
+        let response = self
+            .send(repository, |http| {
+                http.get(url.clone())
+                    .header(ACCEPT, media_types::IMAGE_MANIFEST)
+            })
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let manifest: ImageManifest = response
+            .error_for_status()?
+            .json()
+            .await
+            .context("failed to deserialize oci manifest")?;
+
+        ensure!(
+            manifest.schema_version == 2,
+            "unsupported oci manifest schema version: {}",
+            manifest.schema_version
+        );
+
+        Ok(Some(manifest))
+    }
+
+    async fn get_blob(&self, repository: &str, descriptor: &Descriptor) -> Result<Response> {
+        let url = self
+            .base_url
+            .join(&format!("{repository}/blobs/{}", descriptor.digest))?;
+        debug!("fetching oci blob: {url}");
+
+        let response = self
+            .send(repository, |http| http.get(url.clone()))
+            .await?
+            .error_for_status()?;
+        Ok(response)
+    }
+
+    /// Send a request built by `build`, authenticating using the registry's challenge if asked to.
+    async fn send(
+        &self,
+        repository: &str,
+        build: impl Fn(&reqwest::Client) -> RequestBuilder,
+    ) -> Result<Response> {
+        let http = self.config.http_for(self.source_id)?;
+
+        let authorization = self.authorizations.lock().unwrap().get(repository).cloned();
+        let response = with_authorization(build(&http), authorization.as_deref())
+            .send()
+            .await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| anyhow!("oci registry requires authentication: {}", self.host))?;
+        let challenge = AuthChallenge::parse(challenge)?;
+
+        let authorization = self.authorize(repository, challenge).await?;
+        self.authorizations
+            .lock()
+            .unwrap()
+            .insert(repository.to_string(), authorization.clone());
+
+        let response = with_authorization(build(&http), Some(&authorization))
+            .send()
+            .await?;
+        Ok(response)
+    }
+
+    async fn authorize(&self, repository: &str, challenge: AuthChallenge) -> Result<String> {
+        // Credential helpers are external processes, which must not block the async runtime.
+        let host = self.host.clone();
+        let credentials = spawn_blocking(move || docker_credentials::lookup(&host))
+            .await?
+            .with_context(|| format!("failed to look up credentials for: {}", self.host))?;
+
+        match challenge {
+            AuthChallenge::Basic => {
+                let Some(credentials) = credentials else {
+                    bail!("no credentials configured for oci registry: {}", self.host);
+                };
+                let encoded = data_encoding::BASE64
+                    .encode(format!("{}:{}", credentials.username, credentials.secret).as_bytes());
+                Ok(format!("Basic {encoded}"))
+            }
+
+            AuthChallenge::Bearer {
+                realm,
+                service,
+                scope,
+            } => {
+                let mut token_url = Url::parse(&realm)
+                    .with_context(|| format!("invalid authentication realm: {realm}"))?;
+                {
+                    let mut query = token_url.query_pairs_mut();
+                    if let Some(service) = &service {
+                        query.append_pair("service", service);
+                    }
+                    let scope = scope.unwrap_or_else(|| format!("repository:{repository}:pull"));
+                    query.append_pair("scope", &scope);
+                }
+                debug!("requesting oci registry token: {token_url}");
+
+                let mut request = self.config.http_for(self.source_id)?.get(token_url);
+                if let Some(credentials) = credentials {
+                    request = request.basic_auth(credentials.username, Some(credentials.secret));
+                }
+
+                let token: TokenResponse = request
+                    .send()
+                    .await?
+                    .error_for_status()
+                    .context("failed to obtain oci registry token")?
+                    .json()
+                    .await
+                    .context("failed to deserialize oci registry token")?;
+
+                let token = token
+                    .token
+                    .or(token.access_token)
+                    .ok_or_else(|| anyhow!("oci registry token response contains no token"))?;
+                Ok(format!("Bearer {token}"))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<'c> RegistryClient for OciRegistryClient<'c> {
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    async fn get_records(&self, package: PackageName) -> Result<Option<Arc<IndexRecords>>> {
+        let repository = self.repository(&package);
+        let Some(manifest) = self.get_manifest(&repository, INDEX_TAG).await? else {
+            return Ok(None);
+        };
+
+        let layer = find_layer(&manifest, media_types::INDEX_RECORDS)?;
+        let bytes = self.get_blob(&repository, layer).await?.bytes().await?;
+
+        let actual = Digest::recommended().update(&bytes).finish();
+        ensure!(
+            actual == layer.digest,
+            "index records digest mismatch for package `{package}`: \
+            expected {}, got {actual}",
+            layer.digest
+        );
+
+        let records =
+            serde_json::from_slice(&bytes).context("failed to deserialize index records")?;
+        Ok(Some(Arc::new(records)))
+    }
+
+    async fn is_downloaded(&self, _package: PackageId) -> bool {
+        false
+    }
+
+    async fn download(
+        &self,
+        package: PackageId,
+        progress: &dyn DownloadProgress,
+    ) -> Result<PathBuf> {
+        let repository = self.repository(&package.name);
+        let tag = version_tag(&package.version);
+        let manifest = self
+            .get_manifest(&repository, &tag)
+            .await?
+            .ok_or_else(|| anyhow!("package not found in oci registry: {package}"))?;
+
+        let layer = find_layer(&manifest, media_types::PACKAGE_TARBALL)?;
+        let response = self.get_blob(&repository, layer).await?;
+
+        let output_path = self.dl_fs.path_existent()?.join(package.tarball_name());
+        let output_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&output_path)
+            .await
+            .with_context(|| format!("failed to open: {output_path}"))?;
+
+        output_file
+            .lock_exclusive()
+            .with_context(|| format!("failed to lock file: {output_path}"))?;
+
+        let mut digest = Digest::recommended();
+        let total = Some(layer.size);
+        let mut downloaded = 0;
+        let mut stream = response.bytes_stream();
+        let mut writer = BufWriter::new(output_file);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("failed to read response chunk")?;
+            downloaded += chunk.len() as u64;
+            progress.update(downloaded, total)?;
+            digest.update(&chunk);
+            io::copy_buf(&mut &*chunk, &mut writer)
+                .await
+                .context("failed to save response chunk on disk")?;
+        }
+        io::AsyncWriteExt::flush(&mut writer).await?;
+
+        let actual = digest.finish();
+        ensure!(
+            actual == layer.digest,
+            "package tarball digest mismatch for `{package}`: expected {}, got {actual}",
+            layer.digest
+        );
+
+        Ok(output_path.into_std_path_buf())
+    }
+}
+
+fn find_layer<'a>(manifest: &'a ImageManifest, media_type: &str) -> Result<&'a Descriptor> {
+    let layer = manifest
+        .layers
+        .iter()
+        .find(|layer| layer.media_type == media_type)
+        .ok_or_else(|| anyhow!("oci manifest does not contain a `{media_type}` layer"))?;
+    trace!(digest = %layer.digest, size = layer.size, "found oci layer");
+    Ok(layer)
+}
+
+fn with_authorization(request: RequestBuilder, authorization: Option<&str>) -> RequestBuilder {
+    match authorization {
+        Some(authorization) => request.header(AUTHORIZATION, authorization),
+        None => request,
+    }
+}
+
+/// OCI tags cannot contain `+`, so replace build metadata separator with `_`, like Helm does.
+fn version_tag(version: &semver::Version) -> String {
+    version.to_string().replace('+', "_")
+}
+
+fn is_localhost(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .map_or(false, |ip| ip.is_loopback())
+}
+
+/// Parsed `WWW-Authenticate` header returned by container registries.
+#[derive(Debug, PartialEq, Eq)]
+enum AuthChallenge {
+    Basic,
+    Bearer {
+        realm: String,
+        service: Option<String>,
+        scope: Option<String>,
+    },
+}
+
+impl AuthChallenge {
+    fn parse(header: &str) -> Result<Self> {
+        let header = header.trim();
+        let (scheme, params) = header.split_once(' ').unwrap_or((header, ""));
+
+        if scheme.eq_ignore_ascii_case("basic") {
+            return Ok(Self::Basic);
+        }
+
+        ensure!(
+            scheme.eq_ignore_ascii_case("bearer"),
+            "unsupported oci registry authentication scheme: {scheme}"
+        );
+
+        let mut params = parse_auth_params(params);
+        let realm = params
+            .remove("realm")
+            .ok_or_else(|| anyhow!("bearer authentication challenge is missing realm"))?;
+        Ok(Self::Bearer {
+            realm,
+            service: params.remove("service"),
+            scope: params.remove("scope"),
+        })
+    }
+}
+
+/// Parse comma-separated `key=value` or `key="value"` pairs.
+fn parse_auth_params(input: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = input.trim_start();
+
+    while let Some((key, tail)) = rest.split_once('=') {
+        let key = key.trim().to_ascii_lowercase();
+        let (value, tail) = match tail.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, tail)) => (value, tail),
+                None => (quoted, ""),
+            },
+            None => tail.split_once(',').unwrap_or((tail, "")),
+        };
+        params.insert(key, value.trim().to_string());
+        rest = tail.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+    use test_case::test_case;
+
+    use super::AuthChallenge;
+
+    #[test]
+    fn parse_bearer_challenge() {
+        let challenge = AuthChallenge::parse(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/foo:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(
+            challenge,
+            AuthChallenge::Bearer {
+                realm: "https://ghcr.io/token".to_string(),
+                service: Some("ghcr.io".to_string()),
+                scope: Some("repository:org/foo:pull,push".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_basic_challenge() {
+        let challenge = AuthChallenge::parse(r#"Basic realm="Harbor""#).unwrap();
+        assert_eq!(challenge, AuthChallenge::Basic);
+    }
+
+    #[test]
+    fn parse_unsupported_challenge() {
+        assert_eq!(
+            AuthChallenge::parse("Digest realm=x")
+                .unwrap_err()
+                .to_string(),
+            "unsupported oci registry authentication scheme: Digest"
+        );
+    }
+
+    #[test_case("1.0.0" => "1.0.0")]
+    #[test_case("1.0.0-rc.1" => "1.0.0-rc.1")]
+    #[test_case("1.0.0+build.5" => "1.0.0_build.5")]
+    fn version_tag(version: &str) -> String {
+        super::version_tag(&Version::parse(version).unwrap())
+    }
+
+    #[test_case("localhost" => true)]
+    #[test_case("127.0.0.1" => true)]
+    #[test_case("[::1]" => true)]
+    #[test_case("ghcr.io" => false)]
+    fn is_localhost(host: &str) -> bool {
+        super::is_localhost(host)
+    }
+}
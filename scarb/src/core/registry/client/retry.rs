@@ -0,0 +1,228 @@
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use thiserror::Error;
+use tracing::debug;
+
+use crate::core::Config;
+
+/// Retries HTTP requests which failed due to transient network or server problems.
+///
+/// Connection errors, timeouts, interrupted response bodies and responses with `408`, `429`
+/// or `5xx` status codes are considered transient. Subsequent attempts are delayed
+/// with jittered exponential backoff, unless the server asks for a specific delay
+/// with a `Retry-After` header.
+pub struct RetryPolicy<'c> {
+    config: &'c Config,
+    retries: u32,
+}
+
+/// Response status code indicating a temporary server problem.
+#[derive(Debug, Error)]
+#[error("HTTP status {status}")]
+pub struct TransientStatusError {
+    status: StatusCode,
+    retry_after: Option<Duration>,
+}
+
+impl<'c> RetryPolicy<'c> {
+    const BASE_DELAY: Duration = Duration::from_millis(500);
+    const MAX_DELAY: Duration = Duration::from_secs(10);
+    const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+    pub fn new(config: &'c Config) -> Self {
+        Self {
+            config,
+            retries: config.config_file().http.retry(),
+        }
+    }
+
+    /// Send a request built by `request`, retrying transient failures.
+    ///
+    /// Responses with non-transient error status codes are turned into errors.
+    pub async fn send(&self, request: impl Fn() -> Result<RequestBuilder>) -> Result<Response> {
+        let request = &request;
+        self.run(|| async move {
+            let response = request()?.send().await?;
+            Ok(check_transient_status(response)?.error_for_status()?)
+        })
+        .await
+    }
+
+    /// Send a request built by `request`, retrying only if the server responds with
+    /// `429 Too Many Requests`.
+    ///
+    /// Unlike [`RetryPolicy::send`], this is suitable for requests which must not be repeated
+    /// if the server might have processed them, like uploads.
+    /// Responses with error status codes are turned into errors.
+    pub async fn send_rate_limited(
+        &self,
+        request: impl Fn() -> Result<RequestBuilder>,
+    ) -> Result<Response> {
+        Ok(self
+            .send_rate_limited_unchecked(request)
+            .await?
+            .error_for_status()?)
+    }
+
+    /// Like [`RetryPolicy::send_rate_limited`], but responses with error status codes
+    /// are returned as they are, for callers which need to inspect them.
+    pub async fn send_rate_limited_unchecked(
+        &self,
+        request: impl Fn() -> Result<RequestBuilder>,
+    ) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let response = request()?.send().await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || attempt >= self.retries {
+                return Ok(response);
+            }
+
+            attempt += 1;
+            let delay = retry_after(&response)
+                .map(|retry_after| retry_after.min(Self::MAX_RETRY_AFTER))
+                .unwrap_or_else(|| backoff(attempt));
+            self.config.ui().warn(format!(
+                "registry is rate limiting requests, retrying in {}s ({} tries remaining)",
+                delay.as_secs(),
+                self.retries - attempt + 1
+            ));
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Run `op` until it succeeds, fails with a non-transient error, or retries are exhausted.
+    ///
+    /// Use [`check_transient_status`] inside `op` to retry requests which received a response
+    /// with transient error status code.
+    pub async fn run<T, F>(&self, mut op: impl FnMut() -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let err = match op().await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            let Some(retry_after) = transient_retry_after(&err) else {
+                return Err(err);
+            };
+            if attempt >= self.retries {
+                return Err(err);
+            }
+            attempt += 1;
+
+            let delay = retry_after
+                .map(|retry_after| retry_after.min(Self::MAX_RETRY_AFTER))
+                .unwrap_or_else(|| backoff(attempt));
+            self.config.ui().warn(format!(
+                "spurious network error ({} tries remaining): {err:#}",
+                self.retries - attempt + 1
+            ));
+            debug!("retrying in {delay:?}");
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Turn a response with a transient error status code into an error recognized
+/// by [`RetryPolicy`], passing other responses through.
+pub fn check_transient_status(response: Response) -> Result<Response, TransientStatusError> {
+    let status = response.status();
+    if is_transient_status(status) {
+        Err(TransientStatusError {
+            status,
+            retry_after: retry_after(&response),
+        })
+    } else {
+        Ok(response)
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Check whether `err` is transient, and if so, return server-requested delay, if any.
+fn transient_retry_after(err: &Error) -> Option<Option<Duration>> {
+    err.chain().find_map(|cause| {
+        if let Some(err) = cause.downcast_ref::<TransientStatusError>() {
+            return Some(err.retry_after);
+        }
+        let err = cause.downcast_ref::<reqwest::Error>()?;
+        let is_transient = !err.is_status()
+            && (err.is_timeout() || err.is_connect() || err.is_request() || err.is_body());
+        is_transient.then_some(None)
+    })
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after)
+}
+
+/// Parse `Retry-After` header value.
+///
+/// Only the delay in seconds form is supported, HTTP dates fall back to regular backoff.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Compute delay before `attempt`-th retry: exponential backoff with random jitter,
+/// uniformly distributed between half and full backoff.
+fn backoff(attempt: u32) -> Duration {
+    let delay = RetryPolicy::BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RetryPolicy::MAX_DELAY);
+    let half = delay / 2;
+    let jitter = random_u64() % (half.as_millis() as u64 + 1);
+    half + Duration::from_millis(jitter)
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_case::test_case;
+
+    use super::{backoff, parse_retry_after, RetryPolicy};
+
+    #[test_case("120" => Some(Duration::from_secs(120)))]
+    #[test_case(" 0 " => Some(Duration::ZERO))]
+    #[test_case("Wed, 21 Oct 2015 07:28:00 GMT" => None)]
+    #[test_case("-1" => None)]
+    fn retry_after(value: &str) -> Option<Duration> {
+        parse_retry_after(value)
+    }
+
+    #[test]
+    fn backoff_bounds() {
+        for attempt in 1..=32 {
+            let full = RetryPolicy::BASE_DELAY
+                .saturating_mul(2u32.saturating_pow(attempt - 1))
+                .min(RetryPolicy::MAX_DELAY);
+            let delay = backoff(attempt);
+            assert!(
+                delay >= full / 2,
+                "attempt {attempt}: {delay:?} < {full:?} / 2"
+            );
+            assert!(delay <= full, "attempt {attempt}: {delay:?} > {full:?}");
+        }
+    }
+}
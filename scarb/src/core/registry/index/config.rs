@@ -12,10 +12,14 @@ use crate::core::registry::index::{BaseUrl, TemplateUrl};
 ///   "version": 1,
 ///   "api": "https://example.com/api/v1",
 ///   "dl": "https://example.com/api/v1/download/{package}/{version}",
-///   "index": "https://example.com/index/{prefix}/{package}.json"
+///   "index": "https://example.com/index/{prefix}/{package}.json",
+///   "auth-required": false
 /// }
 /// ```
 ///
+/// Scarb discovers the layout of HTTP registries from this file, so self-hosted registries
+/// are free to serve index files and tarballs from arbitrary locations.
+///
 /// ## URL Templates
 ///
 /// The values for the `"dl"` and `"index"` fields are URL templates.
@@ -41,12 +45,24 @@ pub struct IndexConfig {
     /// Usually, this is a location where `config.json` lies, as the rest of index files resides
     /// alongside config.
     pub index: TemplateUrl,
+
+    /// Whether all requests to the registry, including index and tarball downloads,
+    /// must be authenticated with a token.
+    ///
+    /// If this is `true`, Scarb refuses to access the registry before `scarb login`
+    /// instead of failing with opaque HTTP errors.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub auth_required: bool,
 }
 
 impl IndexConfig {
     pub const WELL_KNOWN_PATH: &'_ str = "config.json";
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(into = "u8", try_from = "u8")]
 pub struct IndexVersion;
@@ -79,6 +95,7 @@ mod tests {
             api: Some("https://example.com/api/v1/".parse().unwrap()),
             dl: TemplateUrl::new("https://example.com/api/v1/download/{package}/{version}"),
             index: TemplateUrl::new("https://example.com/index/{prefix}/{package}.json"),
+            auth_required: false,
         };
 
         let actual: IndexConfig = serde_json::from_str(
@@ -93,4 +110,20 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn deserialize_auth_required() {
+        let actual: IndexConfig = serde_json::from_str(
+            r#"{
+              "version": 1,
+              "dl": "https://example.com/dl/{package}/{version}",
+              "index": "https://example.com/index/{prefix}/{package}.json",
+              "auth-required": true
+            }"#,
+        )
+        .unwrap();
+
+        assert!(actual.auth_required);
+        assert_eq!(actual.api, None);
+    }
 }
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
+
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Checksum, PackageName};
+use crate::core::{Checksum, FeatureName, FeatureValue, PackageName, Summary};
 
 pub type IndexRecords = Vec<IndexRecord>;
 
@@ -15,6 +17,40 @@ pub struct IndexRecord {
     pub checksum: Checksum,
     #[serde(default = "default_false", skip_serializing_if = "is_false")]
     pub no_core: bool,
+    /// Yanked versions are skipped by the resolver, unless they are pinned in the lockfile.
+    #[serde(default = "default_false", skip_serializing_if = "is_false")]
+    pub yanked: bool,
+    /// Size of the package tarball in bytes, checked before downloading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Features declared by the package, needed to determine which optional dependencies to use.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<FeatureName, Vec<FeatureValue>>,
+}
+
+impl IndexRecord {
+    /// Build an index record describing the package with given tarball `checksum`.
+    pub fn from_summary(summary: &Summary, checksum: Checksum) -> Self {
+        Self {
+            version: summary.package_id.version.clone(),
+            dependencies: summary
+                .publish_dependencies()
+                .map(|dep| IndexDependency {
+                    name: dep.name.clone(),
+                    req: dep.version_req.clone().into(),
+                    features: dep.features.clone(),
+                    default_features: dep.default_features,
+                    optional: dep.optional,
+                    rename: dep.rename.clone(),
+                })
+                .collect(),
+            checksum,
+            no_core: summary.no_core,
+            yanked: false,
+            size: None,
+            features: summary.features.clone(),
+        }
+    }
 }
 
 pub type IndexDependencies = Vec<IndexDependency>;
@@ -23,12 +59,29 @@ pub type IndexDependencies = Vec<IndexDependency>;
 pub struct IndexDependency {
     pub name: PackageName,
     pub req: VersionReq,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<FeatureName>,
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub default_features: bool,
+    #[serde(default = "default_false", skip_serializing_if = "is_false")]
+    pub optional: bool,
+    /// Name under which the dependency is imported, if it differs from the package name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<PackageName>,
 }
 
 fn default_false() -> bool {
     false
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
 fn is_false(value: &bool) -> bool {
     !*value
 }
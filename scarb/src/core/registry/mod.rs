@@ -1,7 +1,9 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::core::{ManifestDependency, Package, PackageId, Summary};
+use crate::core::{
+    DependencyVersionReq, ManifestDependency, Package, PackageId, PackageName, SourceId, Summary,
+};
 
 pub mod cache;
 pub mod client;
@@ -9,6 +11,9 @@ pub mod index;
 pub mod package_source_store;
 pub mod patch_map;
 pub mod patcher;
+pub mod provenance;
+pub mod search;
+pub mod signature;
 pub mod source_map;
 
 pub const DEFAULT_REGISTRY_INDEX: &str = "https://there-is-no-default-registry-yet.com";
@@ -20,6 +25,16 @@ pub trait Registry {
 
     /// Fetch full package by its ID.
     async fn download(&self, package_id: PackageId) -> Result<Package>;
+
+    /// Find all versions of a package available in a source, regardless of version requirements.
+    async fn query_versions(&self, name: PackageName, source_id: SourceId) -> Result<Vec<Summary>> {
+        let dependency = ManifestDependency::builder()
+            .name(name)
+            .version_req(DependencyVersionReq::Any)
+            .source_id(source_id)
+            .build();
+        self.query(&dependency).await
+    }
 }
 
 #[cfg(test)]
@@ -138,7 +153,7 @@ pub(crate) mod mock {
                 .ok_or_else(|| anyhow!("MockRegistry/query: cannot find {dependency}"))?
                 .iter()
                 .copied()
-                .filter(|id| dependency.version_req.matches(&id.version))
+                .filter(|id| dependency.matches_version(&id.version))
                 .sorted_unstable_by(|a, b| b.version.cmp(&a.version))
                 .map(|id| self.get_package(id).unwrap().manifest.summary.clone())
                 .collect())
@@ -251,7 +266,7 @@ pub(crate) mod mock {
             use $crate::core::registry::mock;
             let package_id = $crate::core::PackageId::from_display_str($p).unwrap();
             let dependencies: Vec<$crate::core::PackageName> = mock::pkg_names![$($d),*].iter().cloned().collect();
-            $crate::core::lockfile::PackageLock::new(&package_id, dependencies.into_iter())
+            $crate::core::lockfile::PackageLock::new(&package_id, dependencies.into_iter(), None)
         }};
     }
 
@@ -1,6 +1,9 @@
-use anyhow::{ensure, Context, Result};
+use anyhow::{bail, Context, Result};
 use camino::Utf8PathBuf;
+use std::io;
+use std::io::Read;
 use std::path::PathBuf;
+use tar::EntryType;
 use tokio::task::spawn_blocking;
 use tracing::{debug, trace};
 
@@ -9,6 +12,7 @@ use crate::flock::{protected_run_if_not_ok, Filesystem, OK_FILE};
 use crate::internal::fsx;
 use crate::internal::fsx::PathUtf8Ext;
 use crate::internal::restricted_names::is_windows_restricted_path;
+use crate::internal::safe_unpack::{check_entry_path, check_link_target, LimitedReader};
 
 pub struct PackageSourceStore<'a> {
     fs: Filesystem<'a>,
@@ -25,6 +29,12 @@ impl<'a> PackageSourceStore<'a> {
         Self { fs, config }
     }
 
+    /// Path to the package, if it has already been completely unpacked.
+    pub fn unpacked_path(&self, pkg: PackageId) -> Option<Utf8PathBuf> {
+        let fs = self.fs.child(pkg.tarball_basename());
+        fs.is_ok().then(|| fs.path_unchecked().to_owned())
+    }
+
     /// Extract a downloaded package archive into a location where it is ready to be compiled.
     ///
     /// No action is taken if the source looks like it's already unpacked.
@@ -32,12 +42,32 @@ impl<'a> PackageSourceStore<'a> {
     pub async fn extract(&self, pkg: PackageId, archive: PathBuf) -> Result<Utf8PathBuf> {
         trace!("attempting to extract `{pkg}`");
         trace!(archive = ?archive.display());
-        self.extract_impl(pkg, archive)
+        self.extract_impl(pkg, move || fsx::open(archive))
+            .await
+            .with_context(|| format!("failed to extract: {pkg}"))
+    }
+
+    /// Extract a package archive read from `reader`, for example while it is being downloaded.
+    ///
+    /// The reader is always read to the end, so that it can verify all data it has provided.
+    /// No action is taken if the source looks like it's already unpacked.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn extract_reader(
+        &self,
+        pkg: PackageId,
+        reader: impl Read + Send + 'static,
+    ) -> Result<Utf8PathBuf> {
+        trace!("attempting to extract `{pkg}` from stream");
+        self.extract_impl(pkg, move || Ok(reader))
             .await
             .with_context(|| format!("failed to extract: {pkg}"))
     }
 
-    async fn extract_impl(&self, pkg: PackageId, archive: PathBuf) -> Result<Utf8PathBuf> {
+    async fn extract_impl<R: Read>(
+        &self,
+        pkg: PackageId,
+        open: impl FnOnce() -> Result<R> + Send + 'static,
+    ) -> Result<Utf8PathBuf> {
         let prefix = pkg.tarball_basename();
         let fs = self.fs.child(&prefix);
         let parent_path = self.fs.path_existent()?.to_owned();
@@ -46,6 +76,8 @@ impl<'a> PackageSourceStore<'a> {
 
         assert_eq!(parent_path.join(&prefix), output_path);
 
+        let max_unpack_size = self.config.config_file().downloads.max_unpack_size();
+
         protected_run_if_not_ok!(&fs, &self.config.package_cache_lock(), {
             debug!("starting extraction");
 
@@ -57,10 +89,11 @@ impl<'a> PackageSourceStore<'a> {
             spawn_blocking(move || -> Result<()> {
                 // FIXME(mkaput): Verify VERSION is 1.
 
+                let mut reader = open()?;
                 let mut tar = {
-                    let file = fsx::open(archive)?;
-                    let zst = zstd::Decoder::new(file)?;
-                    // FIXME(mkaput): Protect against zip bomb attacks (https://github.com/rust-lang/cargo/pull/11337).
+                    let zst = zstd::Decoder::new(&mut reader)?;
+                    // Protect against decompression bombs.
+                    let zst = LimitedReader::new(zst, max_unpack_size);
                     // FIXME(mkaput): Protect against CVE-2023-38497 (https://github.com/rust-lang/cargo/pull/12443).
                     tar::Archive::new(zst)
                 };
@@ -74,11 +107,22 @@ impl<'a> PackageSourceStore<'a> {
 
                     // Ensure extracting will not accidentally or maliciously overwrite files
                     // outside extraction directory.
-                    ensure!(
-                        entry_path.starts_with(&prefix),
-                        "invalid package tarball, contains a file {entry_path} \
-                        which is not under {prefix}"
-                    );
+                    check_entry_path(&entry_path, &prefix)?;
+                    match entry.header().entry_type() {
+                        EntryType::Regular | EntryType::Continuous | EntryType::Directory => {}
+                        EntryType::Symlink => {
+                            let target = entry
+                                .link_name()
+                                .with_context(|| "failed to read link target")?
+                                .with_context(|| format!("link {entry_path} has no target"))?
+                                .try_to_utf8()?;
+                            check_link_target(&entry_path, &target)?;
+                        }
+                        entry_type => bail!(
+                            "invalid package tarball, contains an entry {entry_path} \
+                            of unsupported type: {entry_type:?}"
+                        ),
+                    }
 
                     // Prevent unpacking OK-file.
                     if entry_path.file_name().unwrap_or_default() == OK_FILE {
@@ -93,6 +137,10 @@ impl<'a> PackageSourceStore<'a> {
 
                     r.with_context(|| format!("failed to extract: {entry_path}"))?;
                 }
+                drop(tar);
+
+                // Consume anything left after the end of archive.
+                io::copy(&mut reader, &mut io::sink())?;
 
                 Ok(())
             })
@@ -0,0 +1,459 @@
+//! SLSA-style provenance attestations of published packages.
+//!
+//! When publishing from CI, Scarb can describe where the package tarball comes from
+//! in an [in-toto statement](https://in-toto.io/) with a [SLSA provenance](https://slsa.dev/provenance/v1)
+//! predicate, stating the CI builder identity, source repository and commit.
+//! The attestation is signed with a [minisign](https://jedisct1.github.io/minisign/) key
+//! and published next to package tarball, for example
+//! `alexandria_math-0.1.0.tar.zst.provenance.json`, with its detached signature in
+//! `alexandria_math-0.1.0.tar.zst.provenance.json.minisig`.
+
+use std::collections::BTreeMap;
+use std::env;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use data_encoding::BASE64;
+use ring::signature::Ed25519KeyPair;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::core::registry::signature::verify_signature;
+use crate::core::{Checksum, PackageId};
+
+/// File name suffix of provenance attestations, appended to the tarball name.
+pub const PROVENANCE_SUFFIX: &str = ".provenance.json";
+
+const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+const BUILD_TYPE: &str = "https://docs.swmansion.com/scarb/provenance/v1";
+
+/// Compute URL of the provenance attestation of the tarball downloadable from `dl_url`.
+///
+/// The signature of the attestation is available at [`signature_url`] of the returned URL.
+///
+/// [`signature_url`]: crate::core::registry::signature::signature_url
+pub fn provenance_url(dl_url: &Url) -> Url {
+    let mut url = dl_url.clone();
+    url.set_path(&format!("{}{PROVENANCE_SUFFIX}", dl_url.path()));
+    url
+}
+
+/// A provenance attestation along with its detached minisign signature.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedProvenance {
+    /// Serialized [`Statement`].
+    pub attestation: String,
+    pub signature: String,
+}
+
+/// An in-toto statement about package tarballs.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<Subject>,
+    pub predicate_type: String,
+    pub predicate: Predicate,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Subject {
+    /// Tarball file name.
+    pub name: String,
+    /// Tarball digests, keyed by hash function name.
+    pub digest: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Predicate {
+    pub build_definition: BuildDefinition,
+    pub run_details: RunDetails,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    pub build_type: String,
+    pub external_parameters: ExternalParameters,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExternalParameters {
+    pub source: SourceDescriptor,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SourceDescriptor {
+    pub repository: String,
+    pub commit: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RunDetails {
+    pub builder: Builder,
+    pub metadata: RunMetadata,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Builder {
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMetadata {
+    pub invocation_id: String,
+}
+
+/// Description of the CI environment the package is being published from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuildEnvironment {
+    /// Identity of the CI workflow building the package.
+    pub builder_id: String,
+    /// URL of this particular CI run.
+    pub invocation_id: String,
+    pub repository: String,
+    pub commit: String,
+}
+
+impl BuildEnvironment {
+    /// Detect supported CI environment from environment variables of the current process.
+    pub fn detect() -> Option<Self> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if var("GITHUB_ACTIONS").as_deref() == Some("true") {
+            let server = var("GITHUB_SERVER_URL")?;
+            let repository = var("GITHUB_REPOSITORY")?;
+            let run_id = var("GITHUB_RUN_ID")?;
+            let attempt = var("GITHUB_RUN_ATTEMPT").unwrap_or_else(|| "1".into());
+            return Some(Self {
+                builder_id: format!("{server}/{}", var("GITHUB_WORKFLOW_REF")?),
+                invocation_id: format!(
+                    "{server}/{repository}/actions/runs/{run_id}/attempts/{attempt}"
+                ),
+                repository: format!("{server}/{repository}"),
+                commit: var("GITHUB_SHA")?,
+            });
+        }
+
+        if var("GITLAB_CI").as_deref() == Some("true") {
+            let server = var("CI_SERVER_URL")?;
+            return Some(Self {
+                builder_id: format!(
+                    "{server}/{}/-/runners/{}",
+                    var("CI_PROJECT_PATH")?,
+                    var("CI_RUNNER_ID")?
+                ),
+                invocation_id: var("CI_JOB_URL")?,
+                repository: var("CI_PROJECT_URL")?,
+                commit: var("CI_COMMIT_SHA")?,
+            });
+        }
+
+        None
+    }
+}
+
+impl Statement {
+    /// Describe the tarball of `package` with `checksum`, built in `environment`.
+    pub fn new(package: PackageId, checksum: &Checksum, environment: &BuildEnvironment) -> Self {
+        let checksum = checksum.to_string();
+        let (algorithm, digest) = checksum
+            .split_once(':')
+            .expect("checksums are always prefixed with hash function type");
+        Self {
+            statement_type: STATEMENT_TYPE.into(),
+            subject: vec![Subject {
+                name: package.tarball_name(),
+                digest: BTreeMap::from([(algorithm.to_string(), digest.to_string())]),
+            }],
+            predicate_type: PREDICATE_TYPE.into(),
+            predicate: Predicate {
+                build_definition: BuildDefinition {
+                    build_type: BUILD_TYPE.into(),
+                    external_parameters: ExternalParameters {
+                        source: SourceDescriptor {
+                            repository: environment.repository.clone(),
+                            commit: environment.commit.clone(),
+                        },
+                    },
+                },
+                run_details: RunDetails {
+                    builder: Builder {
+                        id: environment.builder_id.clone(),
+                    },
+                    metadata: RunMetadata {
+                        invocation_id: environment.invocation_id.clone(),
+                    },
+                },
+            },
+        }
+    }
+
+    /// Check whether this statement describes the tarball of `package` with `checksum`.
+    fn describes(&self, package: PackageId, checksum: &Checksum) -> bool {
+        let checksum = checksum.to_string();
+        let Some((algorithm, digest)) = checksum.split_once(':') else {
+            return false;
+        };
+        self.subject.iter().any(|subject| {
+            subject.name == package.tarball_name()
+                && subject.digest.get(algorithm).map(String::as_str) == Some(digest)
+        })
+    }
+}
+
+/// A minisign secret key used to sign provenance attestations.
+pub struct ProvenanceSigningKey {
+    key_pair: Ed25519KeyPair,
+    key_id: [u8; 8],
+}
+
+impl ProvenanceSigningKey {
+    /// Parse the contents of an unencrypted minisign secret key file,
+    /// as created by `minisign -G -W`.
+    pub fn parse(secret_key: &str) -> Result<Self> {
+        Self::parse_impl(secret_key).context("failed to parse provenance signing key")
+    }
+
+    fn parse_impl(secret_key: &str) -> Result<Self> {
+        // Secret key files consist of an untrusted comment line and the base64 encoded key.
+        let encoded = secret_key
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))
+            .context("secret key is empty")?;
+        let bytes = BASE64.decode(encoded.as_bytes())?;
+
+        // Layout: signature algorithm (2), KDF algorithm (2), checksum algorithm (2),
+        // KDF salt (32), KDF limits (16), key ID (8), secret key (64), checksum (32).
+        ensure!(bytes.len() == 158, "invalid secret key length");
+        ensure!(&bytes[..2] == b"Ed", "unsupported signature algorithm");
+        if bytes[2..4] != [0, 0] {
+            bail!(
+                "encrypted secret keys are not supported\n\
+                help: create a key without password with `minisign -G -W`"
+            );
+        }
+
+        let key_id = bytes[54..62].try_into().unwrap();
+        let (seed, public_key) = bytes[62..126].split_at(32);
+        let key_pair = Ed25519KeyPair::from_seed_and_public_key(seed, public_key)
+            .map_err(|err| anyhow!("invalid secret key: {err}"))?;
+        Ok(Self { key_pair, key_id })
+    }
+
+    /// Produce contents of a `.minisig` file with the legacy signature of `contents`.
+    pub fn sign(&self, contents: &[u8]) -> String {
+        let signature = self.key_pair.sign(contents);
+
+        let mut signature_bytes = b"Ed".to_vec();
+        signature_bytes.extend_from_slice(&self.key_id);
+        signature_bytes.extend_from_slice(signature.as_ref());
+
+        let trusted_comment = "file:provenance.json";
+        let mut global_message = signature.as_ref().to_vec();
+        global_message.extend_from_slice(trusted_comment.as_bytes());
+        let global_signature = self.key_pair.sign(&global_message);
+
+        format!(
+            "untrusted comment: scarb provenance attestation signature\n{}\n\
+            trusted comment: {trusted_comment}\n{}\n",
+            BASE64.encode(&signature_bytes),
+            BASE64.encode(global_signature.as_ref()),
+        )
+    }
+}
+
+/// Create an attestation of the tarball of `package` with `checksum`, signed with `key`.
+pub fn attest(
+    package: PackageId,
+    checksum: &Checksum,
+    environment: &BuildEnvironment,
+    key: &ProvenanceSigningKey,
+) -> Result<SignedProvenance> {
+    let statement = Statement::new(package, checksum, environment);
+    let attestation = serde_json::to_string_pretty(&statement)?;
+    let signature = key.sign(attestation.as_bytes());
+    Ok(SignedProvenance {
+        attestation,
+        signature,
+    })
+}
+
+/// Check that `provenance` is signed with any of `trusted_keys` and that it describes
+/// the tarball of `package` with `checksum`.
+///
+/// Returns the verified statement.
+pub fn verify_provenance(
+    provenance: &SignedProvenance,
+    package: PackageId,
+    checksum: &Checksum,
+    trusted_keys: &[String],
+) -> Result<Statement> {
+    verify_signature(
+        provenance.attestation.as_bytes(),
+        &provenance.signature,
+        trusted_keys,
+    )
+    .context("provenance attestation signature is invalid")?;
+    let statement: Statement = serde_json::from_str(&provenance.attestation)
+        .context("failed to parse provenance attestation")?;
+    ensure!(
+        statement.predicate_type == PREDICATE_TYPE,
+        "unsupported provenance predicate type: {}",
+        statement.predicate_type
+    );
+    ensure!(
+        statement.describes(package, checksum),
+        "provenance attestation does not describe `{}` with checksum {checksum}",
+        package.tarball_name()
+    );
+    Ok(statement)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use test_case::test_case;
+
+    use crate::core::{Checksum, PackageId};
+
+    use url::Url;
+
+    use super::{
+        attest, provenance_url, verify_provenance, BuildEnvironment, ProvenanceSigningKey,
+    };
+
+    // Unencrypted minisign secret key and its public key, derived from the `[1; 32]` seed.
+    const SECRET_KEY: &str = "untrusted comment: minisign secret key
+RWQAAEIyAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAYqI4910CfGV/VLbLTy6XXLKZwm/HZQSG/N0iAG0D29cAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=
+";
+    const PUBLIC_KEY: &str = "RWQBAQEBAQEBAYqI4910CfGV/VLbLTy6XXLKZwm/HZQSG/N0iAG0D29c";
+    const OTHER_PUBLIC_KEY: &str = "RWQJCQkJCQkJCepKbGPinFIKvvVQexMuxfmVR3auvr57kkIe6mkURtIs";
+
+    fn checksum(byte: char) -> Checksum {
+        Checksum::parse(&format!("sha256:{}", byte.to_string().repeat(64))).unwrap()
+    }
+
+    fn environment() -> BuildEnvironment {
+        BuildEnvironment {
+            builder_id: "https://github.com/foo/bar/.github/workflows/release.yml@refs/tags/v1.0.0"
+                .into(),
+            invocation_id: "https://github.com/foo/bar/actions/runs/1/attempts/1".into(),
+            repository: "https://github.com/foo/bar".into(),
+            commit: "0123456789abcdef0123456789abcdef01234567".into(),
+        }
+    }
+
+    #[test]
+    fn attest_and_verify() {
+        let key = ProvenanceSigningKey::parse(SECRET_KEY).unwrap();
+        let package = PackageId::from_display_str("foo v1.0.0").unwrap();
+        let provenance = attest(package, &checksum('a'), &environment(), &key).unwrap();
+
+        let statement =
+            verify_provenance(&provenance, package, &checksum('a'), &[PUBLIC_KEY.into()]).unwrap();
+        assert_eq!(statement.subject[0].name, "foo-1.0.0.tar.zst");
+        assert_eq!(
+            statement
+                .predicate
+                .build_definition
+                .external_parameters
+                .source
+                .commit,
+            "0123456789abcdef0123456789abcdef01234567"
+        );
+
+        let err = verify_provenance(&provenance, package, &checksum('b'), &[PUBLIC_KEY.into()])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "provenance attestation does not describe `foo-1.0.0.tar.zst` with checksum {}",
+                checksum('b')
+            )
+        );
+
+        let other = PackageId::from_display_str("bar v1.0.0").unwrap();
+        assert!(
+            verify_provenance(&provenance, other, &checksum('a'), &[PUBLIC_KEY.into()]).is_err()
+        );
+
+        let err = verify_provenance(
+            &provenance,
+            package,
+            &checksum('a'),
+            &[OTHER_PUBLIC_KEY.into()],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "provenance attestation signature is invalid"
+        );
+    }
+
+    #[test]
+    fn encrypted_key() {
+        let encrypted = SECRET_KEY.replace("RWQAAEIy", "RWRTY0Iy");
+        let err = ProvenanceSigningKey::parse(&encrypted).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "failed to parse provenance signing key: encrypted secret keys are not supported\n\
+            help: create a key without password with `minisign -G -W`"
+        );
+    }
+
+    #[test_case(&[] => None; "no ci")]
+    #[test_case(&[
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_SERVER_URL", "https://github.com"),
+        ("GITHUB_REPOSITORY", "foo/bar"),
+        ("GITHUB_RUN_ID", "42"),
+        ("GITHUB_RUN_ATTEMPT", "2"),
+        ("GITHUB_WORKFLOW_REF", "foo/bar/.github/workflows/release.yml@refs/tags/v1.0.0"),
+        ("GITHUB_SHA", "abc"),
+    ] => Some(BuildEnvironment {
+        builder_id: "https://github.com/foo/bar/.github/workflows/release.yml@refs/tags/v1.0.0".into(),
+        invocation_id: "https://github.com/foo/bar/actions/runs/42/attempts/2".into(),
+        repository: "https://github.com/foo/bar".into(),
+        commit: "abc".into(),
+    }); "github actions")]
+    #[test_case(&[
+        ("GITLAB_CI", "true"),
+        ("CI_SERVER_URL", "https://gitlab.com"),
+        ("CI_PROJECT_PATH", "foo/bar"),
+        ("CI_RUNNER_ID", "7"),
+        ("CI_JOB_URL", "https://gitlab.com/foo/bar/-/jobs/42"),
+        ("CI_PROJECT_URL", "https://gitlab.com/foo/bar"),
+        ("CI_COMMIT_SHA", "abc"),
+    ] => Some(BuildEnvironment {
+        builder_id: "https://gitlab.com/foo/bar/-/runners/7".into(),
+        invocation_id: "https://gitlab.com/foo/bar/-/jobs/42".into(),
+        repository: "https://gitlab.com/foo/bar".into(),
+        commit: "abc".into(),
+    }); "gitlab ci")]
+    #[test_case(&[("GITHUB_ACTIONS", "true")] => None; "incomplete github actions")]
+    fn build_environment(vars: &[(&str, &str)]) -> Option<BuildEnvironment> {
+        let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+        BuildEnvironment::from_vars(|name| vars.get(name).map(ToString::to_string))
+    }
+
+    #[test_case(
+        "https://example.com/bar-1.0.0.tar.zst",
+        "https://example.com/bar-1.0.0.tar.zst.provenance.json"
+    )]
+    #[test_case(
+        "https://example.com/dl/bar/1.0.0?token=x",
+        "https://example.com/dl/bar/1.0.0.provenance.json?token=x"
+    )]
+    fn provenance_urls(dl_url: &str, expected: &str) {
+        let dl_url = Url::parse(dl_url).unwrap();
+        assert_eq!(provenance_url(&dl_url).as_str(), expected);
+    }
+}
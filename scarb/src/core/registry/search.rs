@@ -0,0 +1,31 @@
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageName;
+
+/// Response of registry search API.
+///
+/// ```json
+/// {
+///   "packages": [
+///     { "name": "alexandria_math", "version": "0.2.0", "description": "Math library" }
+///   ],
+///   "total": 1
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SearchResults {
+    /// Matching packages, ordered by relevance.
+    pub packages: Vec<SearchResult>,
+    /// Total number of matching packages, which may be greater than the number of returned ones.
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub name: PackageName,
+    /// Latest non-yanked version of the package.
+    pub version: Version,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
@@ -0,0 +1,89 @@
+//! Verification of [minisign](https://jedisct1.github.io/minisign/) signatures
+//! of package tarballs.
+//!
+//! Registries which sign their packages publish detached signatures next to package tarballs,
+//! for example `alexandria_math-0.1.0.tar.zst.minisig`.
+
+use anyhow::{bail, Context, Result};
+use minisign_verify::{PublicKey, Signature};
+use url::Url;
+
+/// File name suffix of detached signatures, appended to the tarball name.
+pub const SIGNATURE_SUFFIX: &str = ".minisig";
+
+/// Compute URL of the detached signature of the tarball downloadable from `dl_url`.
+pub fn signature_url(dl_url: &Url) -> Url {
+    let mut url = dl_url.clone();
+    url.set_path(&format!("{}{SIGNATURE_SUFFIX}", dl_url.path()));
+    url
+}
+
+/// Check that `signature` of `tarball` contents has been made with any of `trusted_keys`.
+///
+/// Trusted keys are base64 encoded minisign public keys.
+/// Both prehashed and legacy signatures are accepted.
+pub fn verify_signature(tarball: &[u8], signature: &str, trusted_keys: &[String]) -> Result<()> {
+    let signature = Signature::decode(signature).context("failed to parse package signature")?;
+    for key in trusted_keys {
+        let public_key =
+            PublicKey::from_base64(key).with_context(|| format!("invalid trusted key: {key}"))?;
+        if public_key.verify(tarball, &signature, true).is_ok() {
+            return Ok(());
+        }
+    }
+    bail!("package signature is invalid or has not been made with any of the trusted keys")
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use url::Url;
+
+    use super::{signature_url, verify_signature};
+
+    const KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    const OTHER_KEY: &str = "RWQJCQkJCQkJCepKbGPinFIKvvVQexMuxfmVR3auvr57kkIe6mkURtIs";
+    const CONTENTS: &[u8] = b"Hello, Scarb!";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCI7qcMUQ6NrVU1imVXp7bbBWfvrtb1FZkgCejkij5h59HAj2AwiJHgX/IO7au2KPOZtSS96nPLZQ9vdH90C26AU=
+trusted comment: timestamp:0\tfile:hello.txt
+nEgzKtcNEfAMA1uFJYZhZNo2QFaUZqjeC8Smo+QYrY/6X+dibo+Zcpx2GmnuO/PaHrdX99eHmq2gbTFfpIyqDw==
+";
+
+    #[test]
+    fn valid_signature() {
+        verify_signature(CONTENTS, SIGNATURE, &[KEY.into()]).unwrap();
+        verify_signature(CONTENTS, SIGNATURE, &[OTHER_KEY.into(), KEY.into()]).unwrap();
+    }
+
+    #[test_case(CONTENTS, &[OTHER_KEY]; "untrusted key")]
+    #[test_case(b"Hello, Cairo!", &[KEY]; "tampered contents")]
+    #[test_case(CONTENTS, &[]; "no trusted keys")]
+    fn invalid_signature(contents: &[u8], keys: &[&str]) {
+        let keys = keys.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let err = verify_signature(contents, SIGNATURE, &keys).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "package signature is invalid or has not been made with any of the trusted keys"
+        );
+    }
+
+    #[test]
+    fn invalid_key() {
+        let err = verify_signature(CONTENTS, SIGNATURE, &["not a key".into()]).unwrap_err();
+        assert_eq!(err.to_string(), "invalid trusted key: not a key");
+    }
+
+    #[test_case(
+        "https://example.com/bar-1.0.0.tar.zst",
+        "https://example.com/bar-1.0.0.tar.zst.minisig"
+    )]
+    #[test_case(
+        "https://example.com/dl/bar/1.0.0?token=x",
+        "https://example.com/dl/bar/1.0.0.minisig?token=x"
+    )]
+    fn signature_urls(dl_url: &str, expected: &str) {
+        let dl_url = Url::parse(dl_url).unwrap();
+        assert_eq!(signature_url(&dl_url).as_str(), expected);
+    }
+}
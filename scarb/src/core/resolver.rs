@@ -1,9 +1,11 @@
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use petgraph::graphmap::DiGraphMap;
 use petgraph::visit::{Dfs, EdgeFiltered, Walker};
 use smallvec::SmallVec;
 
-use crate::core::{PackageId, TargetKind};
+use crate::core::{PackageId, PackageName, Summary, TargetKind};
 
 /// Represents a fully-resolved package dependency graph.
 ///
@@ -14,6 +16,13 @@ pub struct Resolve {
     ///
     /// If package `a` depends on package `b`, then this graph will contain an edge from `a` to `b`.
     pub graph: DiGraphMap<PackageId, DependencyEdge>,
+
+    /// Summaries of all packages present in the graph.
+    pub summaries: HashMap<PackageId, Summary>,
+
+    /// Names under which packages are imported by their dependants, for packages renamed with
+    /// the `package` key of dependency specifications.
+    pub renames: HashMap<PackageId, PackageName>,
 }
 
 impl Resolve {
@@ -24,18 +33,33 @@ impl Resolve {
         self.graph.nodes()
     }
 
+    /// Name under which the package is visible to other packages, as a Cairo crate.
+    pub fn import_name(&self, package_id: PackageId) -> PackageName {
+        self.renames
+            .get(&package_id)
+            .cloned()
+            .unwrap_or_else(|| package_id.name.clone())
+    }
+
     /// Collect all [`PackageId`]s needed to compile a root package.
     ///
     /// Returns a collection of all [`PackageId`]s of packages needed to provide as _crate roots_
     /// to the Cairo compiler, or to load as _cairo plugins_, in order to build a particular
     /// package (named _root package_).
     ///
+    /// Dependencies specific to a target kind are only followed from the root package,
+    /// dependencies of other packages are always built as libraries.
+    ///
     /// # Safety
     /// * Asserts that `root_package` is a node in this graph.
     pub fn solution_of(&self, root_package: PackageId, target_kind: &TargetKind) -> Vec<PackageId> {
         assert!(&self.graph.contains_node(root_package));
-        let filtered_graph = EdgeFiltered::from_fn(&self.graph, move |(_node_a, _node_b, edge)| {
-            edge.accepts_target(target_kind.clone())
+        let filtered_graph = EdgeFiltered::from_fn(&self.graph, move |(node_a, _node_b, edge)| {
+            if node_a == root_package {
+                edge.accepts_target(target_kind.clone())
+            } else {
+                edge.is_normal()
+            }
         });
         Dfs::new(&filtered_graph, root_package)
             .iter(&filtered_graph)
@@ -67,6 +91,16 @@ impl DependencyEdge {
         self.0.is_empty() || self.0.iter().any(|name| target_kind == *name)
     }
 
+    /// Whether this edge represents a dependency used by all targets.
+    pub fn is_normal(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Kinds of targets using this dependency, empty if it is used by all targets.
+    pub fn target_kinds(&self) -> &[TargetKind] {
+        &self.0
+    }
+
     pub fn extend(self, target_kind: Option<TargetKind>) -> Self {
         if let Some(target_kind) = target_kind {
             let mut edge = self.0;
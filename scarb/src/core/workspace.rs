@@ -4,11 +4,14 @@ use std::fmt;
 use anyhow::{anyhow, bail, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use scarb_ui::args::PackagesSource;
+use smol_str::SmolStr;
 
 use crate::compiler::Profile;
 use crate::core::config::Config;
+use crate::core::manifest::{EnvVars, ScriptDefinition};
 use crate::core::package::Package;
-use crate::core::PackageId;
+use crate::core::registry::patch_map::PatchMap;
+use crate::core::{FeatureUnification, PackageId};
 use crate::flock::RootFilesystem;
 use crate::{DEFAULT_TARGET_DIR_NAME, LOCK_FILE_NAME, MANIFEST_FILE_NAME};
 
@@ -22,16 +25,29 @@ pub struct Workspace<'c> {
     manifest_path: Utf8PathBuf,
     profiles: Vec<Profile>,
     root_package: Option<PackageId>,
+    default_members: Option<Vec<PackageId>>,
+    scripts: BTreeMap<SmolStr, ScriptDefinition>,
     target_dir: RootFilesystem,
+    feature_unification: FeatureUnification,
+    allow_prerelease: bool,
+    patch_map: PatchMap,
+    env: EnvVars,
 }
 
 impl<'c> Workspace<'c> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         manifest_path: Utf8PathBuf,
         packages: &[Package],
         root_package: Option<PackageId>,
+        default_members: Option<Vec<PackageId>>,
+        scripts: BTreeMap<SmolStr, ScriptDefinition>,
         config: &'c Config,
         profiles: Vec<Profile>,
+        feature_unification: FeatureUnification,
+        allow_prerelease: bool,
+        patch_map: PatchMap,
+        env: EnvVars,
     ) -> Result<Self> {
         let packages = packages
             .iter()
@@ -49,8 +65,14 @@ impl<'c> Workspace<'c> {
             manifest_path,
             profiles,
             root_package,
+            default_members,
+            scripts,
             target_dir,
             members: packages,
+            feature_unification,
+            allow_prerelease,
+            patch_map,
+            env,
         })
     }
 
@@ -58,6 +80,8 @@ impl<'c> Workspace<'c> {
         package: Package,
         config: &'c Config,
         profiles: Vec<Profile>,
+        patch_map: PatchMap,
+        env: EnvVars,
     ) -> Result<Self> {
         let manifest_path = package.manifest_path().to_path_buf();
         let root_package = Some(package.id);
@@ -66,8 +90,14 @@ impl<'c> Workspace<'c> {
             manifest_path,
             vec![package].as_ref(),
             root_package,
+            None,
+            BTreeMap::new(),
             config,
             profiles,
+            FeatureUnification::default(),
+            false,
+            patch_map,
+            env,
         )
     }
 
@@ -94,6 +124,27 @@ impl<'c> Workspace<'c> {
         &self.target_dir
     }
 
+    /// Returns how features are shared between compilation units of this workspace.
+    pub fn feature_unification(&self) -> FeatureUnification {
+        self.feature_unification
+    }
+
+    /// Returns whether prerelease versions satisfy version requirements of all dependencies
+    /// resolved in this workspace.
+    pub fn allow_prerelease(&self) -> bool {
+        self.allow_prerelease
+    }
+
+    /// Returns dependency overrides declared in the `[patch]` table of the workspace manifest.
+    pub fn patch_map(&self) -> &PatchMap {
+        &self.patch_map
+    }
+
+    /// Environment variables declared in the `[env]` table of the workspace root manifest.
+    pub fn env(&self) -> &EnvVars {
+        &self.env
+    }
+
     /// Returns the current package of this workspace.
     ///
     /// Note that this can return an error in the future,
@@ -119,6 +170,24 @@ impl<'c> Workspace<'c> {
         self.root_package.and_then(|id| self.package(&id)).cloned()
     }
 
+    /// Returns members declared in the `default-members` field of the workspace manifest.
+    ///
+    /// These are the packages to operate on when a command is run from the workspace root
+    /// without any package selection flags. Returns `None` if the field is not present.
+    pub fn default_members(&self) -> Option<Vec<Package>> {
+        self.default_members.as_ref().map(|ids| {
+            ids.iter()
+                .filter_map(|id| self.package(id))
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Returns scripts declared in the `[workspace.scripts]` table of the workspace manifest.
+    pub fn scripts(&self) -> &BTreeMap<SmolStr, ScriptDefinition> {
+        &self.scripts
+    }
+
     pub fn package(&self, id: &PackageId) -> Option<&Package> {
         self.members.get(id)
     }
@@ -209,7 +278,15 @@ impl<'c> PackagesSource for Workspace<'c> {
         Workspace::members(self).collect()
     }
 
+    fn default_members(&self) -> Option<Vec<Self::Package>> {
+        Workspace::default_members(self)
+    }
+
     fn runtime_manifest(&self) -> Utf8PathBuf {
         self.config.manifest_path().to_path_buf()
     }
+
+    fn workspace_manifest(&self) -> Utf8PathBuf {
+        self.manifest_path.clone()
+    }
 }
@@ -0,0 +1,80 @@
+use std::io;
+use std::io::Read;
+
+use anyhow::Result;
+use bytes::{Buf, Bytes};
+use tokio::sync::mpsc;
+
+/// A blocking [`Read`] implementation over chunks of bytes received from an async channel.
+///
+/// This allows feeding synchronous decoders with data as it arrives from the network.
+/// Errors sent through the channel are returned from [`Read::read`], and the reader reaches
+/// end of file when all senders are dropped.
+/// Must be used outside of async context, for example in [`tokio::task::spawn_blocking`].
+pub struct ChannelReader {
+    rx: mpsc::Receiver<Result<Bytes>>,
+    chunk: Bytes,
+}
+
+impl ChannelReader {
+    pub fn new(rx: mpsc::Receiver<Result<Bytes>>) -> Self {
+        Self {
+            rx,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.rx.blocking_recv() {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(err)) => return Err(io::Error::new(io::ErrorKind::Other, err)),
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len());
+        self.chunk.copy_to_slice(&mut buf[..len]);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use anyhow::anyhow;
+    use bytes::Bytes;
+    use tokio::sync::mpsc;
+
+    use super::ChannelReader;
+
+    #[test]
+    fn reads_all_chunks() {
+        let (tx, rx) = mpsc::channel(4);
+        tx.try_send(Ok(Bytes::from_static(b"Hello, "))).unwrap();
+        tx.try_send(Ok(Bytes::new())).unwrap();
+        tx.try_send(Ok(Bytes::from_static(b"Scarb!"))).unwrap();
+        drop(tx);
+
+        let mut contents = String::new();
+        ChannelReader::new(rx)
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "Hello, Scarb!");
+    }
+
+    #[test]
+    fn forwards_errors() {
+        let (tx, rx) = mpsc::channel(4);
+        tx.try_send(Ok(Bytes::from_static(b"Hello"))).unwrap();
+        tx.try_send(Err(anyhow!("connection reset"))).unwrap();
+
+        let mut contents = Vec::new();
+        let err = ChannelReader::new(rx)
+            .read_to_end(&mut contents)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "connection reset");
+    }
+}
@@ -46,6 +46,15 @@ pub fn remove_dir_all(p: impl AsRef<Path>) -> Result<()> {
     }
 }
 
+/// Equivalent to [`fs::remove_file`] with better error messages.
+pub fn remove_file(p: impl AsRef<Path>) -> Result<()> {
+    return inner(p.as_ref());
+
+    fn inner(p: &Path) -> Result<()> {
+        fs::remove_file(p).with_context(|| format!("failed to remove file `{}`", p.display()))
+    }
+}
+
 /// Equivalent to [`fs::write`] with better error messages.
 pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
     return inner(path.as_ref(), contents.as_ref());
@@ -91,6 +100,15 @@ pub fn read_to_string(path: impl AsRef<Path>) -> Result<String> {
     }
 }
 
+/// Equivalent to [`fs::read_dir`] with better error messages.
+pub fn read_dir(path: impl AsRef<Path>) -> Result<fs::ReadDir> {
+    return inner(path.as_ref());
+
+    fn inner(path: &Path) -> Result<fs::ReadDir> {
+        fs::read_dir(path).with_context(|| format!("failed to read directory `{}`", path.display()))
+    }
+}
+
 /// Equivalent to [`fs::rename`] with better error messages.
 pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
     return inner(from.as_ref(), to.as_ref());
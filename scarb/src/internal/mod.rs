@@ -1,8 +1,10 @@
 pub mod async_cache;
+pub mod channel_reader;
 pub mod cloneable_error;
 pub mod fsx;
 pub mod lazy_directory_creator;
 pub mod restricted_names;
+pub mod safe_unpack;
 pub mod serdex;
 pub mod stable_hash;
 pub mod static_hash_cache;
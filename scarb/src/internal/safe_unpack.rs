@@ -0,0 +1,143 @@
+//! Checks protecting package tarball extraction from malicious or corrupted archives.
+
+use std::io;
+use std::io::Read;
+
+use anyhow::{bail, ensure, Result};
+use camino::{Utf8Component, Utf8Path};
+
+/// A reader which fails once more than `limit` bytes have been read from the inner reader.
+///
+/// Wrapping a decompressor with it protects against decompression bombs.
+pub struct LimitedReader<R> {
+    inner: R,
+    limit: u64,
+    remaining: u64,
+}
+
+impl<R> LimitedReader<R> {
+    pub fn new(inner: R, limit: u64) -> Self {
+        Self {
+            inner,
+            limit,
+            remaining: limit,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        match self.remaining.checked_sub(read as u64) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                Ok(read)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("maximum unpack size of {} bytes exceeded", self.limit),
+            )),
+        }
+    }
+}
+
+/// Ensure that archive entry at `path` is extracted into the `prefix` directory.
+///
+/// Absolute paths and `..` components are rejected, even if the path would resolve
+/// to a location inside `prefix`.
+pub fn check_entry_path(path: &Utf8Path, prefix: &str) -> Result<()> {
+    for component in path.components() {
+        match component {
+            Utf8Component::Prefix(_) | Utf8Component::RootDir => {
+                bail!("invalid package tarball, contains a file with absolute path: {path}")
+            }
+            Utf8Component::ParentDir => {
+                bail!("invalid package tarball, contains a file with `..` in path: {path}")
+            }
+            Utf8Component::CurDir | Utf8Component::Normal(_) => {}
+        }
+    }
+    ensure!(
+        path.starts_with(prefix),
+        "invalid package tarball, contains a file {path} which is not under {prefix}"
+    );
+    Ok(())
+}
+
+/// Ensure that the link at archive entry `path`, pointing to `target`, does not escape
+/// the top-level directory of the archive entry.
+pub fn check_link_target(path: &Utf8Path, target: &Utf8Path) -> Result<()> {
+    // Depth of the directory containing the link, relative to the package directory.
+    let mut depth = path.components().count().saturating_sub(2);
+    for component in target.components() {
+        let escapes = match component {
+            Utf8Component::Prefix(_) | Utf8Component::RootDir => true,
+            Utf8Component::ParentDir => match depth.checked_sub(1) {
+                Some(parent_depth) => {
+                    depth = parent_depth;
+                    false
+                }
+                None => true,
+            },
+            Utf8Component::CurDir => false,
+            Utf8Component::Normal(_) => {
+                depth += 1;
+                false
+            }
+        };
+        ensure!(
+            !escapes,
+            "invalid package tarball, link {path} points outside of package: {target}"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use camino::Utf8Path;
+    use test_case::test_case;
+
+    use super::{check_entry_path, check_link_target, LimitedReader};
+
+    #[test_case("foo-1.0.0/src/lib.cairo")]
+    #[test_case("foo-1.0.0/./Scarb.toml")]
+    fn valid_entry_path(path: &str) {
+        check_entry_path(Utf8Path::new(path), "foo-1.0.0").unwrap();
+    }
+
+    #[test_case("/etc/passwd" => "invalid package tarball, contains a file with absolute path: /etc/passwd")]
+    #[test_case("foo-1.0.0/../bar" => "invalid package tarball, contains a file with `..` in path: foo-1.0.0/../bar")]
+    #[test_case("bar-1.0.0/Scarb.toml" => "invalid package tarball, contains a file bar-1.0.0/Scarb.toml which is not under foo-1.0.0")]
+    fn invalid_entry_path(path: &str) -> String {
+        check_entry_path(Utf8Path::new(path), "foo-1.0.0")
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test_case("foo-1.0.0/link", "Scarb.toml" => true)]
+    #[test_case("foo-1.0.0/src/link", "../Scarb.toml" => true)]
+    #[test_case("foo-1.0.0/src/link", "./nested/../lib.cairo" => true)]
+    #[test_case("foo-1.0.0/link", "../bar-1.0.0/Scarb.toml" => false)]
+    #[test_case("foo-1.0.0/src/link", "../../../.ssh/id_rsa" => false)]
+    #[test_case("foo-1.0.0/link", "/etc/passwd" => false)]
+    fn link_target(path: &str, target: &str) -> bool {
+        check_link_target(Utf8Path::new(path), Utf8Path::new(target)).is_ok()
+    }
+
+    #[test]
+    fn limited_reader() {
+        let mut buf = Vec::new();
+        LimitedReader::new(&[0u8; 16][..], 16)
+            .read_to_end(&mut buf)
+            .unwrap();
+        assert_eq!(buf.len(), 16);
+
+        let err = LimitedReader::new(&[0u8; 17][..], 16)
+            .read_to_end(&mut buf)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "maximum unpack size of 16 bytes exceeded");
+    }
+}
@@ -38,4 +38,11 @@ impl RelativeUtf8PathBuf {
         let root = file.parent().expect("Expected file path to not be `/`.");
         self.relative_to_directory(root)
     }
+
+    /// Prefix this path with `root` if it is relative, without touching the file system.
+    pub fn rebase(&mut self, root: &Utf8Path) {
+        if self.0.is_relative() {
+            self.0 = root.join(&self.0);
+        }
+    }
 }
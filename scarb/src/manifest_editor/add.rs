@@ -1,11 +1,13 @@
-use std::mem;
+use std::{iter, mem};
 
-use anyhow::{anyhow, ensure, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use toml_edit::{value, Document, Entry, InlineTable, Item};
 use url::Url;
 
-use crate::core::{GitReference, PackageName};
+use crate::core::registry::source_map::SourceMap;
+use crate::core::registry::Registry;
+use crate::core::{Config, GitReference, PackageName, SourceId};
 use crate::internal::fsx;
 use crate::sources::canonical_url::CanonicalUrl;
 
@@ -20,11 +22,18 @@ pub struct AddDependency {
     pub branch: Option<String>,
     pub tag: Option<String>,
     pub rev: Option<String>,
+    /// Registry to take the dependency from, instead of the default one.
+    pub registry: Option<Url>,
+    /// Add the dependency to `[target-dependencies.test]`, so that only test targets use it.
+    pub dev: bool,
+    /// Let prerelease versions satisfy the version requirement.
+    pub allow_prerelease: bool,
 }
 
 struct Dep {
     name: PackageName,
     source: Box<dyn Source>,
+    allow_prerelease: bool,
 }
 
 trait Source {
@@ -33,6 +42,7 @@ trait Source {
 
 struct RegistrySource {
     version: String,
+    registry: Option<Url>,
 }
 
 struct PathSource {
@@ -49,7 +59,12 @@ struct GitSource {
 impl Op for AddDependency {
     #[tracing::instrument(level = "trace", skip(doc, ctx))]
     fn apply_to(self: Box<Self>, doc: &mut Document, ctx: OpCtx<'_>) -> Result<()> {
-        let tab = get_table_mut(doc, &["dependencies"])?;
+        let table_path: &[&str] = if self.dev {
+            &["target-dependencies", "test"]
+        } else {
+            &["dependencies"]
+        };
+        let tab = get_table_mut(doc, table_path)?;
 
         let dep = Dep::resolve(*self, ctx)?;
 
@@ -90,6 +105,12 @@ impl Dep {
             only one of `git` or `path` is allowed"
         );
 
+        ensure!(
+            op.registry.is_none() || (op.path.is_none() && op.git.is_none()),
+            "dependency ({name}) specification is ambiguous, \
+            only one of `git`, `path` or `registry` is allowed"
+        );
+
         if op.branch.is_some() || op.tag.is_some() || op.rev.is_some() {
             ensure!(
                 op.git.is_some(),
@@ -142,14 +163,27 @@ impl Dep {
                 reference,
             })
         } else {
+            let version = match version {
+                Some(version) => version,
+                None => {
+                    let source_id = match &op.registry {
+                        Some(url) => SourceId::for_registry(url)?,
+                        None => SourceId::default(),
+                    };
+                    latest_version(&name, source_id, op.allow_prerelease, ctx.opts.config)?
+                }
+            };
             Box::new(RegistrySource {
-                version: version.ok_or_else(|| {
-                    anyhow!("please specify package version requirement, for example: {name}@1.0.0")
-                })?,
+                version,
+                registry: op.registry,
             })
         };
 
-        Ok(Dep { name, source })
+        Ok(Dep {
+            name,
+            source,
+            allow_prerelease: op.allow_prerelease,
+        })
     }
 
     // TODO(#13): With namespaced packages, this should produce a path.
@@ -162,6 +196,11 @@ impl Dep {
         expand_version_shortcut(item);
         purge_source(item.as_inline_table_mut().unwrap());
         self.source.insert(item.as_inline_table_mut().unwrap());
+        if self.allow_prerelease {
+            item.as_inline_table_mut()
+                .unwrap()
+                .insert("allow-prerelease", true.into());
+        }
         simplify_to_version_shortcut_if_possible(item);
     }
 }
@@ -169,6 +208,10 @@ impl Dep {
 impl Source for RegistrySource {
     fn insert(self: Box<Self>, tab: &mut InlineTable) {
         tab.insert("version", self.version.into());
+
+        if let Some(registry) = self.registry {
+            tab.insert("registry", registry.to_string().into());
+        }
     }
 }
 
@@ -230,6 +273,36 @@ fn purge_source(tab: &mut InlineTable) {
     tab.remove("branch");
     tab.remove("tag");
     tab.remove("rev");
+    tab.remove("registry");
+}
+
+/// Look up the newest version of a package available in a registry.
+///
+/// Prerelease versions are only considered if `allow_prerelease` is set.
+/// Remote registries are not queried in offline mode, so the version has to be specified then.
+fn latest_version(
+    name: &PackageName,
+    source_id: SourceId,
+    allow_prerelease: bool,
+    config: &Config,
+) -> Result<String> {
+    ensure!(
+        config.network_allowed() || source_id.url.scheme() == "file",
+        "please specify package version requirement, for example: {name}@1.0.0"
+    );
+
+    let source_map = SourceMap::preloaded(iter::empty(), config);
+    let summaries = config
+        .tokio_handle()
+        .block_on(source_map.query_versions(name.clone(), source_id))
+        .with_context(|| format!("failed to look up versions of package `{name}`"))?;
+    summaries
+        .iter()
+        .map(|summary| &summary.package_id.version)
+        .filter(|version| allow_prerelease || version.pre.is_empty())
+        .max()
+        .map(|version| version.to_string())
+        .ok_or_else(|| anyhow!("cannot find package `{name}` in registry: {source_id}"))
 }
 
 fn path_value(manifest_path: &Utf8Path, abs_path: &Utf8Path) -> String {
@@ -8,7 +8,8 @@ use toml_edit::Document;
 
 pub use add::AddDependency;
 pub use dep_id::DepId;
-pub use remove::RemoveDependency;
+pub use remove::{tables_inheriting, RemoveDependency};
+pub use upgrade::SetDependencyVersion;
 
 use crate::core::Config;
 use crate::internal::fsx;
@@ -17,6 +18,7 @@ mod add;
 mod dep_id;
 mod remove;
 mod tomlx;
+mod upgrade;
 
 pub trait Op {
     fn apply_to(self: Box<Self>, doc: &mut Document, ctx: OpCtx<'_>) -> Result<()>;
@@ -1,9 +1,13 @@
-use anyhow::{anyhow, Result};
-use toml_edit::Document;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use camino::Utf8Path;
+use toml_edit::{Document, Item};
 
 use scarb_ui::components::Status;
 
 use crate::core::PackageName;
+use crate::internal::fsx;
 
 use super::tomlx::get_table_mut;
 use super::{Op, OpCtx};
@@ -11,17 +15,30 @@ use super::{Op, OpCtx};
 #[derive(Debug)]
 pub struct RemoveDependency {
     pub dep: PackageName,
+    /// Path to the table declaring the dependency, for example `["workspace", "dependencies"]`.
+    pub table: Vec<String>,
+}
+
+impl RemoveDependency {
+    /// Remove `dep` from the `[dependencies]` table.
+    pub fn new(dep: PackageName) -> Self {
+        Self {
+            dep,
+            table: vec!["dependencies".to_string()],
+        }
+    }
 }
 
 impl Op for RemoveDependency {
     #[tracing::instrument(level = "trace", skip(doc, ctx))]
     fn apply_to(self: Box<Self>, doc: &mut Document, ctx: OpCtx<'_>) -> Result<()> {
-        let tab = get_table_mut(doc, &["dependencies"])?;
+        let table = self.table.iter().map(String::as_str).collect::<Vec<_>>();
+        let tab = get_table_mut(doc, &table)?;
+        let table = table.join(".");
 
-        // section is hardcoded as there's no support for other section types yet
         ctx.opts.config.ui().print(Status::new(
             "Removing",
-            &format!("{} from dependencies", self.dep),
+            &format!("{} from {table}", self.dep),
         ));
 
         tab.as_table_like_mut()
@@ -29,7 +46,7 @@ impl Op for RemoveDependency {
             .remove(self.dep.as_str())
             .ok_or_else(|| {
                 anyhow!(
-                    "the dependency `{}` could not be found in `dependencies`",
+                    "the dependency `{}` could not be found in `{table}`",
                     self.dep
                 )
             })?;
@@ -37,3 +54,33 @@ impl Op for RemoveDependency {
         Ok(())
     }
 }
+
+/// Paths to dependency tables of the manifest at `manifest_path` which inherit `dep` from
+/// `[workspace.dependencies]`.
+pub fn tables_inheriting(manifest_path: &Utf8Path, dep: &PackageName) -> Result<Vec<Vec<String>>> {
+    let doc = Document::from_str(&fsx::read_to_string(manifest_path)?)
+        .with_context(|| format!("failed to read manifest at: {manifest_path}"))?;
+
+    let inherits = |table: &Item| {
+        table
+            .get(dep.as_str())
+            .and_then(|dep| dep.get("workspace"))
+            .and_then(|workspace| workspace.as_bool())
+            .unwrap_or(false)
+    };
+
+    let mut tables = Vec::new();
+    for name in ["dependencies", "build-dependencies"] {
+        if doc.get(name).is_some_and(inherits) {
+            tables.push(vec![name.to_string()]);
+        }
+    }
+    if let Some(target_deps) = doc.get("target-dependencies").and_then(Item::as_table_like) {
+        for (kind, table) in target_deps.iter() {
+            if inherits(table) {
+                tables.push(vec!["target-dependencies".to_string(), kind.to_string()]);
+            }
+        }
+    }
+    Ok(tables)
+}
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Result};
+use toml_edit::{Document, Value};
+
+use super::tomlx::get_table_mut;
+use super::{Op, OpCtx};
+
+/// Replace version requirement of an existing dependency, keeping the rest of its specification
+/// and formatting intact.
+#[derive(Debug)]
+pub struct SetDependencyVersion {
+    /// Path to the table declaring the dependency, for example `["target-dependencies", "test"]`.
+    pub table: Vec<String>,
+    /// Key of the dependency in the table.
+    pub key: String,
+    pub version: String,
+}
+
+impl Op for SetDependencyVersion {
+    #[tracing::instrument(level = "trace", skip(doc, _ctx))]
+    fn apply_to(self: Box<Self>, doc: &mut Document, _ctx: OpCtx<'_>) -> Result<()> {
+        let path = self.table.iter().map(String::as_str).collect::<Vec<_>>();
+        let tab = get_table_mut(doc, &path)?;
+
+        let not_found = || {
+            anyhow!(
+                "the dependency `{}` could not be found in `{}`",
+                self.key,
+                self.table.join(".")
+            )
+        };
+        let item = tab
+            .as_table_like_mut()
+            .unwrap()
+            .get_mut(&self.key)
+            .ok_or_else(not_found)?;
+
+        if item.is_str() {
+            replace_keeping_decor(item.as_value_mut().unwrap(), &self.version);
+        } else if let Some(tab) = item.as_table_like_mut() {
+            match tab.get_mut("version").and_then(|item| item.as_value_mut()) {
+                Some(value) => replace_keeping_decor(value, &self.version),
+                None => {
+                    tab.insert("version", toml_edit::value(self.version.as_str()));
+                }
+            }
+        } else {
+            return Err(not_found());
+        }
+
+        Ok(())
+    }
+}
+
+fn replace_keeping_decor(value: &mut Value, new: &str) {
+    let decor = value.decor().clone();
+    *value = new.into();
+    *value.decor_mut() = decor;
+}
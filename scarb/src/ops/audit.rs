@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use crate::core::{Advisory, AdvisoryDatabase, AdvisoryKind, PackageId, Severity, Workspace};
+use crate::ops;
+use crate::ops::lockfile::read_lockfile;
+
+pub struct AuditOpts {
+    /// Directory containing the advisory database.
+    pub database: Utf8PathBuf,
+    /// Lowest severity of vulnerabilities which fail the audit.
+    pub severity_threshold: Severity,
+    /// Fail the audit on unmaintained packages as well.
+    pub deny_unmaintained: bool,
+}
+
+/// Advisories affecting packages locked in the workspace lockfile.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditReport {
+    pub findings: Vec<AuditFinding>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditFinding {
+    pub package: PackageId,
+    pub advisory: Advisory,
+    /// Whether this finding fails the audit, according to [`AuditOpts`].
+    pub denied: bool,
+}
+
+impl AuditReport {
+    /// Number of findings which fail the audit.
+    pub fn denied(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.denied)
+            .count()
+    }
+}
+
+/// Check packages locked in the workspace lockfile against the advisory database.
+///
+/// The lockfile is brought up to date first. Only packages coming from registries are checked,
+/// as advisories refer to packages by their names.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn audit(opts: &AuditOpts, ws: &Workspace<'_>) -> Result<AuditReport> {
+    let db = AdvisoryDatabase::load(&opts.database).context("failed to load advisory database")?;
+
+    ops::resolve_workspace(ws)?;
+    let lockfile = read_lockfile(ws)?;
+
+    let mut findings = Vec::new();
+    for package in lockfile.packages().chain(&lockfile.build_packages) {
+        if !package.source.is_some_and(|source| source.is_registry()) {
+            continue;
+        }
+        for advisory in db.affecting(&package.name, &package.version) {
+            let denied = match advisory.kind {
+                AdvisoryKind::Vulnerability => advisory
+                    .severity
+                    .is_some_and(|severity| severity >= opts.severity_threshold),
+                AdvisoryKind::Unmaintained => opts.deny_unmaintained,
+            };
+            findings.push(AuditFinding {
+                package: package.clone().try_into()?,
+                advisory: advisory.clone(),
+                denied,
+            });
+        }
+    }
+    Ok(AuditReport { findings })
+}
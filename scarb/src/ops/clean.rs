@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
+use camino::Utf8Path;
+use serde_json::Value;
 
-use crate::core::Config;
+use crate::core::{Config, Package, PackageId, Workspace};
 use crate::internal::fsx;
+use crate::internal::fsx::PathBufUtf8Ext;
 use crate::ops;
 
 #[tracing::instrument(skip_all, level = "debug")]
@@ -13,3 +16,78 @@ pub fn clean(config: &Config) -> Result<()> {
     }
     Ok(())
 }
+
+/// Remove artifacts generated for selected workspace members, leaving artifacts of other members
+/// intact.
+///
+/// Removes compilation artifacts of package targets in all profile directories, contract class
+/// files listed in Starknet artifacts files, and package archives.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn clean_packages(packages: &[PackageId], ws: &Workspace<'_>) -> Result<()> {
+    let target_dir = ws.target_dir().path_unchecked();
+    if !target_dir.exists() {
+        return Ok(());
+    }
+    let packages = packages
+        .iter()
+        .map(|id| ws.fetch_package(id))
+        .collect::<Result<Vec<_>>>()?;
+    for entry in fsx::read_dir(target_dir)? {
+        let path = entry?.path().try_into_utf8()?;
+        if !path.is_dir() {
+            continue;
+        }
+        for package in &packages {
+            if path.file_name() == Some("package") {
+                let prefix = format!("{}-{}", package.id.name, package.id.version);
+                remove_matching(&path, |name| name.starts_with(&prefix))?;
+            } else {
+                clean_package_artifacts(package, &path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn clean_package_artifacts(package: &Package, profile_dir: &Utf8Path) -> Result<()> {
+    for target in &package.manifest.targets {
+        let artifacts_path = profile_dir.join(format!("{}.starknet_artifacts.json", target.name));
+        if artifacts_path.exists() {
+            let artifacts: Value = serde_json::from_str(&fsx::read_to_string(&artifacts_path)?)
+                .with_context(|| format!("failed to parse `{artifacts_path}`"))?;
+            let contracts = artifacts["contracts"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            for contract in contracts {
+                for file in ["sierra", "casm"] {
+                    if let Some(file) = contract["artifacts"][file].as_str() {
+                        let path = profile_dir.join(file);
+                        if path.exists() {
+                            fsx::remove_file(path)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        let prefix = format!("{}.", target.name);
+        remove_matching(profile_dir, |name| name.starts_with(&prefix))?;
+    }
+    Ok(())
+}
+
+fn remove_matching(dir: &Utf8Path, matches: impl Fn(&str) -> bool) -> Result<()> {
+    for entry in fsx::read_dir(dir)? {
+        let path = entry?.path().try_into_utf8()?;
+        if !path.file_name().is_some_and(&matches) {
+            continue;
+        }
+        if path.is_dir() {
+            fsx::remove_dir_all(path)?;
+        } else {
+            fsx::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
 use cairo_lang_compiler::diagnostics::DiagnosticsError;
 use indoc::formatdoc;
@@ -7,18 +9,51 @@ use scarb_ui::HumanDuration;
 
 use crate::compiler::db::{build_scarb_root_database, has_starknet_plugin};
 use crate::compiler::CompilationUnit;
-use crate::core::{PackageId, TargetKind, Utf8PathWorkspaceExt, Workspace};
+use crate::core::{HookKind, PackageId, TargetKind, Utf8PathWorkspaceExt, Workspace};
 use crate::ops;
+use crate::ops::{FeaturesOpts, ResolveOpts};
 
 #[derive(Debug)]
 pub struct CompileOpts {
     pub include_targets: Vec<TargetKind>,
     pub exclude_targets: Vec<TargetKind>,
+    pub features: FeaturesOpts,
+    /// Report all compiler warnings as errors, regardless of profile settings.
+    pub deny_warnings: bool,
+}
+
+impl CompileOpts {
+    fn includes_target_kind(&self, kind: &TargetKind) -> bool {
+        !self.exclude_targets.contains(kind)
+            && (self.include_targets.is_empty() || self.include_targets.contains(kind))
+    }
 }
 
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn compile(packages: Vec<PackageId>, opts: CompileOpts, ws: &Workspace<'_>) -> Result<()> {
-    let resolve = ops::resolve_workspace(ws)?;
+    opts.features.validate(&packages, ws)?;
+    let resolve = ops::resolve_workspace_with_opts(
+        ws,
+        &ResolveOpts {
+            features: opts.features.clone(),
+            ..Default::default()
+        },
+    )?;
+
+    // Hooks run only for packages explicitly requested to be built, not for their dependencies.
+    let hooked = packages
+        .iter()
+        .map(|package_id| {
+            let package = ws.fetch_package(package_id)?;
+            let targets = package
+                .manifest
+                .targets
+                .iter()
+                .filter(|t| opts.includes_target_kind(&t.kind))
+                .collect::<Vec<_>>();
+            Ok((package, targets))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     // Add test compilation units to build
     let packages = packages
@@ -36,19 +71,31 @@ pub fn compile(packages: Vec<PackageId>, opts: CompileOpts, ws: &Workspace<'_>)
         })
         .collect::<Vec<PackageId>>();
 
-    let compilation_units = ops::generate_compilation_units(&resolve, ws)?
+    let compilation_units = ops::generate_compilation_units(&resolve, &opts.features, ws)?
         .into_iter()
-        .filter(|cu| !opts.exclude_targets.contains(&cu.target().kind))
-        .filter(|cu| {
-            opts.include_targets.is_empty() || opts.include_targets.contains(&cu.target().kind)
-        })
+        .filter(|cu| opts.includes_target_kind(&cu.target().kind))
         .filter(|cu| packages.contains(&cu.main_package_id))
         .collect::<Vec<_>>();
 
-    for unit in compilation_units {
+    // Compiler plugins run within this process, so they see variables of the `[env]` table
+    // only if these are set in its own environment.
+    ws.env().apply_to_process();
+
+    for (package, targets) in &hooked {
+        ops::run_hook(HookKind::PreBuild, package, targets, HashMap::new(), ws)?;
+    }
+
+    for mut unit in compilation_units {
+        if opts.deny_warnings {
+            unit.compiler_config.deny_warnings = true;
+        }
         compile_unit(unit, ws)?;
     }
 
+    for (package, targets) in &hooked {
+        ops::run_hook(HookKind::PostBuild, package, targets, HashMap::new(), ws)?;
+    }
+
     let elapsed_time = HumanDuration(ws.config().elapsed_time());
     ws.config().ui().print(Status::new(
         "Finished",
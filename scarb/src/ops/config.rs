@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use toml_edit::{Document, Item, Table};
+
+use scarb_ui::components::Status;
+
+use crate::core::config_file::override_key;
+use crate::core::{Config, ConfigFile};
+use crate::internal::fsx;
+
+/// Configuration files to read or modify.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigScope {
+    /// The global configuration file, in the Scarb config directory.
+    Global,
+    /// The `.scarb/config.toml` file of the current project.
+    Local,
+}
+
+/// A single configuration value, along with the place it has been set in.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConfigEntry {
+    /// Dotted key of the value, like `http.retry`.
+    pub key: String,
+    pub value: toml::Value,
+    /// Path of the configuration file the value comes from, or `--config` for values overridden
+    /// on the command line.
+    pub origin: String,
+}
+
+/// List effective configuration values.
+///
+/// Without `scope`, values merged from all configuration files and command-line overrides are
+/// listed. Otherwise, only values set in the configuration file of the given scope are listed.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn list_config(scope: Option<ConfigScope>, config: &Config) -> Result<Vec<ConfigEntry>> {
+    let paths = match scope {
+        None => ConfigFile::paths(&config.dirs().config_dir, project_root(config)),
+        Some(scope) => vec![scope_path(scope, config)],
+    };
+    let layers = paths
+        .into_iter()
+        .map(|(path, base_dir)| {
+            let file = ConfigFile::load_from(&path, &base_dir)?;
+            Ok((path, flatten(&file)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (effective, overridden) = match scope {
+        None => {
+            let overridden = config
+                .config_overrides()
+                .iter()
+                .map(|arg| Ok(flatten_value(toml::from_str(arg)?)))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .flat_map(BTreeMap::into_keys)
+                .collect();
+            (flatten(config.config_file())?, overridden)
+        }
+        Some(_) => {
+            let values = layers
+                .last()
+                .map(|(_, values)| values.clone())
+                .unwrap_or_default();
+            (values, BTreeSet::new())
+        }
+    };
+    let entries = effective
+        .into_iter()
+        .map(|(key, value)| {
+            // Overrides take precedence over all configuration files.
+            let origin = layers
+                .iter()
+                .rev()
+                .filter(|_| !overridden.contains(&key))
+                .find(|(_, values)| values.contains_key(&key))
+                .map(|(path, _)| path.to_string())
+                .unwrap_or_else(|| "--config".to_string());
+            ConfigEntry { key, value, origin }
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Get configuration values of `key`, or of all keys nested in it if it refers to a table.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn get_config(
+    key: &str,
+    scope: Option<ConfigScope>,
+    config: &Config,
+) -> Result<Vec<ConfigEntry>> {
+    let prefix = format!("{key}.");
+    let entries = list_config(scope, config)?
+        .into_iter()
+        .filter(|entry| entry.key == key || entry.key.starts_with(&prefix))
+        .collect::<Vec<_>>();
+    ensure!(!entries.is_empty(), "configuration key `{key}` is not set");
+    Ok(entries)
+}
+
+/// Set configuration `key` to `value` in the configuration file of the given scope.
+///
+/// The value is parsed as TOML, falling back to a string if it is not valid TOML.
+/// Other contents and formatting of the configuration file are preserved.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn set_config(key: &str, value: &str, scope: ConfigScope, config: &Config) -> Result<()> {
+    let mut value = toml_edit::Value::from_str(value).unwrap_or_else(|_| value.into());
+    value.decor_mut().clear();
+
+    let (path, base_dir) = scope_path(scope, config);
+    let arg = format!("{key}={value}");
+    ConfigFile::load_from(&path, &base_dir)?.apply_override(&arg, &base_dir)?;
+    let table: toml::Table = toml::from_str(&arg)?;
+    let segments = override_key(&table);
+    let Some((last, tables)) = segments.split_last() else {
+        bail!("invalid configuration key `{key}`");
+    };
+
+    let contents = if path.is_file() {
+        fsx::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let mut doc = Document::from_str(&contents)
+        .with_context(|| format!("failed to parse config file: {path}"))?;
+    let mut item = doc.as_item_mut();
+    for segment in tables {
+        item = item[segment].or_insert({
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        ensure!(
+            item.is_table_like(),
+            "cannot set `{key}`, because `{segment}` is not a table in config file: {path}"
+        );
+    }
+    item[last] = toml_edit::value(value);
+
+    let contents = doc.to_string();
+    ConfigFile::parse(&contents)
+        .with_context(|| format!("cannot set `{key}` in config file: {path}"))?;
+    fsx::create_dir_all(path.parent().expect("config file path must have a parent"))?;
+    fsx::write(&path, contents)?;
+
+    config
+        .ui()
+        .print(Status::new("Updated", &format!("`{key}` in {path}")));
+    Ok(())
+}
+
+fn project_root(config: &Config) -> &Utf8Path {
+    config
+        .manifest_path()
+        .parent()
+        .expect("parent of manifest path must always exist")
+}
+
+/// Path of the configuration file of `scope`, along with the directory against which relative
+/// paths in it are resolved.
+fn scope_path(scope: ConfigScope, config: &Config) -> (Utf8PathBuf, Utf8PathBuf) {
+    match scope {
+        ConfigScope::Global => {
+            let dir = config.dirs().config_dir.path_unchecked();
+            (dir.join(ConfigFile::FILE_NAME), dir.to_path_buf())
+        }
+        ConfigScope::Local => {
+            let root = project_root(config);
+            (ConfigFile::project_path(root), root.to_path_buf())
+        }
+    }
+}
+
+/// Flatten configuration into a map from dotted keys to values, which are not tables.
+fn flatten(config: &ConfigFile) -> Result<BTreeMap<String, toml::Value>> {
+    Ok(flatten_value(toml::Value::try_from(config)?))
+}
+
+fn flatten_value(value: toml::Value) -> BTreeMap<String, toml::Value> {
+    fn visit(prefix: Option<&str>, value: toml::Value, values: &mut BTreeMap<String, toml::Value>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (segment, value) in table {
+                    let segment = key_segment(&segment);
+                    let key = match prefix {
+                        Some(prefix) => format!("{prefix}.{segment}"),
+                        None => segment,
+                    };
+                    visit(Some(&key), value, values);
+                }
+            }
+            value => {
+                let key = prefix.expect("configuration must be a table").to_string();
+                values.insert(key, value);
+            }
+        }
+    }
+
+    let mut values = BTreeMap::new();
+    visit(None, value, &mut values);
+    values
+}
+
+/// Format a key segment, quoting it unless it is a bare TOML key.
+fn key_segment(segment: &str) -> String {
+    let is_bare = !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if is_bare {
+        segment.to_string()
+    } else {
+        toml::Value::String(segment.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use crate::core::ConfigFile;
+
+    use super::{flatten, key_segment};
+
+    #[test_case("retry", "retry")]
+    #[test_case("my-registry_2", "my-registry_2")]
+    #[test_case("https://example.com", r#""https://example.com""#)]
+    #[test_case("", r#""""#)]
+    fn key_segments(segment: &str, expected: &str) {
+        assert_eq!(key_segment(segment), expected);
+    }
+
+    #[test]
+    fn flatten_config() {
+        let config = ConfigFile::parse(indoc! {r#"
+            [http]
+            retry = 3
+
+            [alias]
+            b = ["build", "-p"]
+
+            [registries.my-registry]
+            index = "https://example.com/"
+        "#})
+        .unwrap();
+        let values = flatten(&config)
+            .unwrap()
+            .into_iter()
+            .map(|(key, value)| format!("{key} = {value}"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                r#"alias.b = ["build", "-p"]"#,
+                "http.retry = 3",
+                r#"registries.my-registry.index = "https://example.com/""#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+use anyhow::{anyhow, Result};
+use serde_json::json;
+use tracing::debug;
+
+use scarb_ui::components::Status;
+
+use crate::core::errors::ScriptExecutionError;
+use crate::core::{HookKind, Package, Target, Workspace};
+use crate::ops;
+use crate::subcommands::SCARB_MANIFEST_PATH_ENV;
+
+/// Name of the hook being run.
+pub const SCARB_HOOK_ENV: &str = "SCARB_HOOK";
+
+/// Run the `hook` script of `package`, if the package defines one.
+///
+/// Apart from the environment passed to all scripts, the hook receives the name and version of the
+/// package, along with `targets` being built, both as a JSON array of objects holding the `kind`
+/// and `name` of each target in `SCARB_TARGETS`, and as a comma-separated list of distinct target
+/// kinds in `SCARB_TARGET_KINDS`.
+/// Variables in `extra_env` are passed as well.
+#[tracing::instrument(level = "debug", skip_all, fields(package = %package.id, %hook))]
+pub fn run_hook(
+    hook: HookKind,
+    package: &Package,
+    targets: &[&Target],
+    extra_env: HashMap<OsString, OsString>,
+    ws: &Workspace<'_>,
+) -> Result<()> {
+    let Some(script) = package.manifest.hooks.get(&hook) else {
+        return Ok(());
+    };
+    debug!("running `{hook}` hook: {script}");
+    let package_name = &package.id.name;
+    ws.config().ui().print(Status::new(
+        "Running",
+        &format!("{hook} {package_name} ({script})"),
+    ));
+
+    let mut target_kinds = Vec::new();
+    for target in targets {
+        if !target_kinds.contains(&target.kind.as_str()) {
+            target_kinds.push(target.kind.as_str());
+        }
+    }
+    let targets_json = json!(targets
+        .iter()
+        .map(|target| json!({"kind": target.kind.as_str(), "name": target.name}))
+        .collect::<Vec<_>>());
+
+    let mut env: HashMap<OsString, OsString> = HashMap::from_iter([
+        (SCARB_HOOK_ENV.into(), hook.as_str().into()),
+        (
+            SCARB_MANIFEST_PATH_ENV.into(),
+            package.manifest_path().into(),
+        ),
+        ("SCARB_PACKAGE_NAME".into(), package_name.as_str().into()),
+        (
+            "SCARB_PACKAGE_VERSION".into(),
+            package.id.version.to_string().into(),
+        ),
+        ("SCARB_TARGETS".into(), targets_json.to_string().into()),
+        ("SCARB_TARGET_KINDS".into(), target_kinds.join(",").into()),
+    ]);
+    env.extend(extra_env);
+
+    ops::execute_script(script, &[], ws, package.root(), Some(env)).map_err(|err| {
+        match err.downcast_ref::<ScriptExecutionError>() {
+            Some(ScriptExecutionError { exit_code }) => anyhow!(
+                "`{hook}` hook of package `{package_name}` failed with exit code: {exit_code}"
+            ),
+            None => err,
+        }
+    })
+}
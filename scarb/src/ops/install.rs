@@ -0,0 +1,430 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::{env, fmt, fs, iter};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::source_map::SourceMap;
+use crate::core::registry::Registry;
+use crate::core::{Config, GitReference, PackageName, SourceId};
+use crate::flock::Filesystem;
+use crate::internal::fsx;
+use crate::process::{exec, is_executable};
+use crate::sources::canonical_url::CanonicalUrl;
+use crate::sources::git::{self, GitRemote};
+use crate::MANIFEST_FILE_NAME;
+
+/// Name of the file in the install directory, tracking installed extensions.
+const INSTALLS_FILE_NAME: &str = "installs.toml";
+
+/// Name of the file locked while the install directory is being modified.
+const INSTALL_LOCK_FILE_NAME: &str = ".install-lock";
+
+/// Prefix of file names of Scarb extension executables.
+const EXTENSION_PREFIX: &str = "scarb-";
+
+/// Where to take a package providing extensions from.
+#[derive(Clone, Debug)]
+pub enum InstallSource {
+    /// A registry, or the default registry if `None`.
+    Registry(Option<Url>),
+    Git {
+        url: Url,
+        reference: GitReference,
+    },
+    Path(Utf8PathBuf),
+}
+
+#[derive(Clone, Debug)]
+pub struct InstallOpts {
+    /// Name of the package, required for registry sources.
+    ///
+    /// For other sources, it is inferred from `Scarb.toml` or `Cargo.toml` manifest if `None`.
+    pub name: Option<PackageName>,
+    /// Requirement of the installed version, only allowed for registry sources.
+    pub version_req: Option<VersionReq>,
+    pub source: InstallSource,
+    /// Overwrite executables installed by other packages, or not installed by Scarb at all.
+    pub force: bool,
+}
+
+/// A package installed with `scarb install`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InstalledPackage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    pub source: SourceId,
+    /// File names of installed executables.
+    pub executables: Vec<String>,
+}
+
+/// Contents of [`INSTALLS_FILE_NAME`], keyed by package name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Installs {
+    #[serde(default)]
+    packages: BTreeMap<String, InstalledPackage>,
+}
+
+impl Installs {
+    fn read(install_dir: &Utf8Path) -> Result<Self> {
+        let path = install_dir.join(INSTALLS_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&fsx::read_to_string(&path)?)
+            .with_context(|| format!("failed to parse installed packages list: {path}"))
+    }
+
+    fn write(&self, install_dir: &Utf8Path) -> Result<()> {
+        fsx::write(
+            install_dir.join(INSTALLS_FILE_NAME),
+            toml::to_string_pretty(self)?,
+        )
+    }
+}
+
+/// Sources of a package to install, available on disk.
+struct FetchedPackage {
+    name: String,
+    version: Option<Version>,
+    source: SourceId,
+    root: Utf8PathBuf,
+}
+
+impl fmt::Display for FetchedPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, " v{version}")?;
+        }
+        write!(f, " ({})", self.source)
+    }
+}
+
+/// Install Scarb extensions provided by a package into the install directory of Scarb.
+///
+/// Prebuilt `scarb-*` executables are taken from the `bin` directory of the package.
+/// If there are none, the package must contain a Cargo project, which is built with `cargo` to
+/// produce them.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn install(opts: InstallOpts, config: &Config) -> Result<()> {
+    install_into(&opts, &config.dirs().install_dir, config)
+}
+
+/// Install Scarb extensions provided by a package into the `bin` subdirectory of `install_dir`,
+/// tracking them in the same way as [`install`] does.
+pub(crate) fn install_into(
+    opts: &InstallOpts,
+    install_dir: &Filesystem<'_>,
+    config: &Config,
+) -> Result<()> {
+    let package = fetch(opts, config)?;
+    config
+        .ui()
+        .print(Status::new("Installing", &package.to_string()));
+
+    let built = build_executables(&package, config)
+        .with_context(|| format!("failed to build `{}`", package.name))?;
+    let file_names = built
+        .iter()
+        .map(|path| path.file_name().unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+
+    let _lock = install_dir.open_rw(INSTALL_LOCK_FILE_NAME, "install directory", config)?;
+    let mut installs = Installs::read(install_dir.path_existent()?)?;
+    let bin_dir = install_dir.child("bin");
+    let bin_dir = bin_dir.path_existent()?;
+
+    for file_name in &file_names {
+        let owner = installs
+            .packages
+            .iter()
+            .find(|(_, installed)| installed.executables.contains(file_name))
+            .map(|(name, _)| name);
+        match owner {
+            Some(owner) if *owner == package.name || opts.force => {}
+            Some(owner) => bail!(
+                "executable `{file_name}` is already installed by package `{owner}`\n\
+                help: use --force to overwrite it"
+            ),
+            None if bin_dir.join(file_name).exists() && !opts.force => bail!(
+                "executable `{file_name}` already exists in: {bin_dir}\n\
+                help: use --force to overwrite it"
+            ),
+            None => {}
+        }
+    }
+
+    // Remove executables which are no longer provided by the package.
+    if let Some(previous) = installs.packages.remove(&package.name) {
+        for file_name in previous.executables {
+            let path = bin_dir.join(&file_name);
+            if !file_names.contains(&file_name) && path.exists() {
+                fsx::remove_file(&path)?;
+            }
+        }
+    }
+    // Forget executables overwritten by this package.
+    for installed in installs.packages.values_mut() {
+        installed
+            .executables
+            .retain(|file_name| !file_names.contains(file_name));
+    }
+    installs
+        .packages
+        .retain(|_, installed| !installed.executables.is_empty());
+
+    for (path, file_name) in built.iter().zip(&file_names) {
+        fsx::copy(path, bin_dir.join(file_name))?;
+    }
+    installs.packages.insert(
+        package.name.clone(),
+        InstalledPackage {
+            version: package.version.clone(),
+            source: package.source,
+            executables: file_names.clone(),
+        },
+    );
+    installs.write(install_dir.path_existent()?)?;
+
+    config.ui().print(Status::new(
+        "Installed",
+        &format!("{} to: {bin_dir}", file_names.join(", ")),
+    ));
+    Ok(())
+}
+
+/// Remove executables of a package installed with `scarb install`.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn uninstall(name: &str, config: &Config) -> Result<()> {
+    let install_dir = &config.dirs().install_dir;
+    let _lock = install_dir.open_rw(INSTALL_LOCK_FILE_NAME, "install directory", config)?;
+    let mut installs = Installs::read(install_dir.path_existent()?)?;
+    let Some(installed) = installs.packages.remove(name) else {
+        bail!("package `{name}` is not installed");
+    };
+
+    let bin_dir = config.dirs().install_bin_dir();
+    for file_name in &installed.executables {
+        let path = bin_dir.path_unchecked().join(file_name);
+        if path.exists() {
+            fsx::remove_file(&path)?;
+        }
+        config.ui().print(Status::new("Removing", path.as_str()));
+    }
+    installs.write(install_dir.path_existent()?)
+}
+
+/// Packages installed with `scarb install`, keyed by name.
+pub fn list_installed(config: &Config) -> Result<BTreeMap<String, InstalledPackage>> {
+    installed_in(config.dirs().install_dir.path_unchecked())
+}
+
+/// Packages installed into `install_dir` with [`install_into`], keyed by name.
+pub(crate) fn installed_in(install_dir: &Utf8Path) -> Result<BTreeMap<String, InstalledPackage>> {
+    Installs::read(install_dir).map(|installs| installs.packages)
+}
+
+fn fetch(opts: &InstallOpts, config: &Config) -> Result<FetchedPackage> {
+    ensure!(
+        opts.version_req.is_none() || matches!(opts.source, InstallSource::Registry(_)),
+        "version requirement can only be used when installing from a registry"
+    );
+    let (root, source) = match &opts.source {
+        InstallSource::Registry(registry) => {
+            let Some(name) = &opts.name else {
+                bail!("package name is required to install from a registry");
+            };
+            return fetch_from_registry(name, opts.version_req.as_ref(), registry.as_ref(), config);
+        }
+        InstallSource::Git { url, reference } => {
+            let remote = GitRemote::new(CanonicalUrl::new(url)?);
+            let (root, rev) = config
+                .tokio_handle()
+                .block_on(async {
+                    let _lock = config.package_cache_lock().acquire_async().await?;
+                    git::checkout(&remote, reference, None, config)
+                })
+                .with_context(|| format!("failed to fetch package from: {url}"))?;
+            let source = SourceId::for_git(url, reference)?.with_precise(rev.to_string())?;
+            (root, source)
+        }
+        InstallSource::Path(path) => {
+            let root = fsx::canonicalize_utf8(path)
+                .with_context(|| format!("failed to find package directory: {path}"))?;
+            let source = SourceId::for_path(&root)?;
+            (root, source)
+        }
+    };
+
+    let (declared_name, version) = read_package_metadata(&root)?;
+    let name = opts
+        .name
+        .as_ref()
+        .map(|name| name.to_string())
+        .or(declared_name)
+        .ok_or_else(|| {
+            anyhow!(
+                "cannot infer package name from manifest in: {root}\n\
+                help: pass the package name as an argument"
+            )
+        })?;
+    Ok(FetchedPackage {
+        name,
+        version,
+        source,
+        root,
+    })
+}
+
+fn fetch_from_registry(
+    name: &PackageName,
+    version_req: Option<&VersionReq>,
+    registry: Option<&Url>,
+    config: &Config,
+) -> Result<FetchedPackage> {
+    let source_id = match registry {
+        Some(url) => SourceId::for_registry(url)?,
+        None => SourceId::default_registry(),
+    };
+    let source_map = SourceMap::preloaded(iter::empty(), config);
+    let package = config
+        .tokio_handle()
+        .block_on(async {
+            let summaries = source_map.query_versions(name.clone(), source_id).await?;
+            let Some(package_id) = summaries
+                .iter()
+                .map(|summary| summary.package_id)
+                .filter(|id| match version_req {
+                    Some(req) => req.matches(&id.version),
+                    None => id.version.pre.is_empty(),
+                })
+                .max_by(|a, b| a.version.cmp(&b.version))
+            else {
+                match version_req {
+                    Some(req) => bail!(
+                        "cannot find package `{name}` matching `{req}` in registry: {source_id}"
+                    ),
+                    None => bail!("cannot find package `{name}` in registry: {source_id}"),
+                }
+            };
+            source_map.download(package_id).await
+        })
+        .with_context(|| format!("failed to download package `{name}`"))?;
+    Ok(FetchedPackage {
+        name: name.to_string(),
+        version: Some(package.id.version.clone()),
+        source: package.id.source_id,
+        root: package.root().to_path_buf(),
+    })
+}
+
+/// Name and version of the package in `root`, as declared in its `Scarb.toml` or `Cargo.toml`.
+fn read_package_metadata(root: &Utf8Path) -> Result<(Option<String>, Option<Version>)> {
+    for file_name in [MANIFEST_FILE_NAME, "Cargo.toml"] {
+        let path = root.join(file_name);
+        if !path.is_file() {
+            continue;
+        }
+        let manifest: toml::Table = toml::from_str(&fsx::read_to_string(&path)?)
+            .with_context(|| format!("failed to parse manifest at: {path}"))?;
+        let field = |key: &str| {
+            manifest
+                .get("package")
+                .and_then(|package| package.get(key))
+                .and_then(|value| value.as_str())
+        };
+        let name = field("name").map(str::to_string);
+        // Versions inherited from workspaces are not tracked.
+        let version = field("version").and_then(|version| version.parse().ok());
+        return Ok((name, version));
+    }
+    Ok((None, None))
+}
+
+/// Paths to Scarb extension executables provided by a package.
+fn build_executables(package: &FetchedPackage, config: &Config) -> Result<Vec<Utf8PathBuf>> {
+    let prebuilt = find_executables(&package.root.join("bin"))?;
+    if !prebuilt.is_empty() {
+        return Ok(prebuilt);
+    }
+
+    let cargo_manifest = package.root.join("Cargo.toml");
+    ensure!(
+        cargo_manifest.is_file(),
+        "package contains neither `{EXTENSION_PREFIX}*` executables in its `bin` directory, \
+        nor a Cargo project building them"
+    );
+
+    // Build artifacts are kept in cache, to speed up reinstalling the package.
+    let target_dir = config.dirs().cache_dir.child("install");
+    let target_dir = target_dir.child(&package.name);
+    let target_dir = target_dir.path_existent()?;
+    let mut cmd = Command::new("cargo");
+    cmd.args(["build", "--release", "--bins"])
+        .arg("--manifest-path")
+        .arg(&cargo_manifest)
+        .arg("--target-dir")
+        .arg(target_dir);
+    if !config.network_allowed() {
+        cmd.arg("--offline");
+    }
+    exec(&mut cmd, config)?;
+
+    let built = find_executables(&target_dir.join("release"))?;
+    ensure!(
+        !built.is_empty(),
+        "Cargo project does not build any `{EXTENSION_PREFIX}*` executables"
+    );
+    Ok(built)
+}
+
+/// Scarb extension executables directly in `dir`, sorted by file name.
+fn find_executables(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read directory: {dir}"))? {
+        let path = Utf8PathBuf::try_from(entry?.path())?;
+        if is_extension_file_name(path.file_name().unwrap_or_default()) && is_executable(&path) {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn is_extension_file_name(file_name: &str) -> bool {
+    file_name
+        .strip_suffix(env::consts::EXE_SUFFIX)
+        .and_then(|stem| stem.strip_prefix(EXTENSION_PREFIX))
+        .is_some_and(|name| !name.is_empty() && !name.contains('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use test_case::test_case;
+
+    use super::is_extension_file_name;
+
+    #[test_case("scarb-hello", true)]
+    #[test_case("scarb-cairo-run", true)]
+    #[test_case("scarb-", false)]
+    #[test_case("scarb-hello.d", false)]
+    #[test_case("hello", false)]
+    fn extension_file_names(stem: &str, expected: bool) {
+        let file_name = format!("{stem}{}", env::consts::EXE_SUFFIX);
+        assert_eq!(is_extension_file_name(&file_name), expected);
+    }
+}
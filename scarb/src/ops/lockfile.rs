@@ -1,13 +1,19 @@
 use crate::core::lockfile::Lockfile;
 use crate::core::Workspace;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use fs4::FileExt;
+use itertools::Itertools;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::str::FromStr;
 
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn read_lockfile(ws: &Workspace<'_>) -> Result<Lockfile> {
+    // Do not create a missing lockfile when it must not change.
+    if ws.config().locked() && !ws.lockfile_path().exists() {
+        return Ok(Lockfile::default());
+    }
+
     let mut file = OpenOptions::new()
         .read(true)
         .write(true)
@@ -26,6 +32,10 @@ pub fn read_lockfile(ws: &Workspace<'_>) -> Result<Lockfile> {
 
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn write_lockfile(lockfile: Lockfile, ws: &Workspace<'_>) -> Result<()> {
+    if ws.config().locked() {
+        return check_lockfile_unchanged(&lockfile, ws);
+    }
+
     let mut file = File::create(ws.lockfile_path()).context("failed to create lockfile")?;
 
     file.lock_exclusive()
@@ -36,3 +46,34 @@ pub fn write_lockfile(lockfile: Lockfile, ws: &Workspace<'_>) -> Result<()> {
 
     Ok(())
 }
+
+/// Fail if writing `lockfile` would change the lockfile on disk, describing what would change.
+fn check_lockfile_unchanged(lockfile: &Lockfile, ws: &Workspace<'_>) -> Result<()> {
+    let path = ws.lockfile_path();
+    let flag = if ws.config().frozen() {
+        "--frozen"
+    } else {
+        "--locked"
+    };
+    if !path.exists() {
+        bail!("the lockfile does not exist, but `{flag}` was passed to prevent creating it");
+    }
+
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .context("failed to read lockfile")?;
+    if content == lockfile.render()? {
+        return Ok(());
+    }
+
+    let mut changes = Lockfile::from_str(&content)?.diff(lockfile);
+    if changes.is_empty() {
+        changes.push("Updating dependencies or checksums of locked packages".to_string());
+    }
+    let changes = changes
+        .iter()
+        .map(|change| format!("  {change}"))
+        .join("\n");
+    bail!("the lockfile needs to be updated, but `{flag}` was passed to prevent this\n{changes}")
+}
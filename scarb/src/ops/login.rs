@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::{Config, SourceId};
+
+pub struct LoginOpts {
+    pub index_url: Url,
+    pub token: String,
+}
+
+pub struct LogoutOpts {
+    pub index_url: Url,
+}
+
+/// Save registry authentication token using the credential provider configured for the registry.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn login(opts: LoginOpts, config: &Config) -> Result<()> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let (target, provider) = config.credential_provider_for(source_id)?;
+
+    provider
+        .store(target, &opts.token, config)
+        .with_context(|| format!("failed to save token using credential provider: {provider}"))?;
+
+    config.ui().print(Status::new(
+        "Login",
+        &format!("token for `{}` saved", target.source_id),
+    ));
+    Ok(())
+}
+
+/// Remove registry authentication token using the credential provider configured
+/// for the registry.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn logout(opts: LogoutOpts, config: &Config) -> Result<()> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let (target, provider) = config.credential_provider_for(source_id)?;
+
+    let removed = provider
+        .erase(target, config)
+        .with_context(|| format!("failed to remove token using credential provider: {provider}"))?;
+
+    if removed {
+        config.ui().print(Status::new(
+            "Logout",
+            &format!("token for `{}` has been removed", target.source_id),
+        ));
+    } else {
+        config.ui().warn(format!(
+            "no token found for `{}`, nothing to do",
+            target.source_id
+        ));
+    }
+    Ok(())
+}
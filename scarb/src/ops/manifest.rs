@@ -3,7 +3,7 @@ use std::env;
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 
-use crate::core::manifest::TomlManifest;
+use crate::core::manifest::{schema, TomlManifest};
 use crate::internal::fsx;
 use crate::internal::fsx::PathBufUtf8Ext;
 use crate::MANIFEST_FILE_NAME;
@@ -24,6 +24,11 @@ pub fn find_manifest_path(user_override: Option<&Utf8Path>) -> Result<Utf8PathBu
     }
 }
 
+/// JSON Schema of the `Scarb.toml` manifest file, for use by editors and other tools.
+pub fn manifest_json_schema() -> serde_json::Value {
+    schema::json_schema()
+}
+
 #[tracing::instrument(level = "debug")]
 pub fn find_workspace_manifest_path(pkg_manifest_path: Utf8PathBuf) -> Result<Option<Utf8PathBuf>> {
     let is_workspace: fn(Utf8PathBuf) -> Result<bool> = |manifest_path| {
@@ -4,11 +4,13 @@ use anyhow::{bail, Result};
 use itertools::Itertools;
 use semver::{Version, VersionReq};
 use smol_str::SmolStr;
+use tracing::debug;
 
 use scarb_metadata as m;
 use scarb_ui::args::PackagesSource;
 
 use crate::compiler::CompilationUnit;
+use crate::core::publishing::vcs::VcsInfo;
 use crate::core::{
     DependencyVersionReq, ManifestDependency, Package, PackageId, SourceId, Target, Workspace,
 };
@@ -18,6 +20,7 @@ use crate::version::CommitInfo;
 pub struct MetadataOptions {
     pub version: u64,
     pub no_deps: bool,
+    pub features: ops::FeaturesOpts,
 }
 
 #[tracing::instrument(skip_all, level = "debug")]
@@ -31,15 +34,29 @@ pub fn collect_metadata(opts: &MetadataOptions, ws: &Workspace<'_>) -> Result<m:
     }
 
     let (mut packages, mut compilation_units) = if !opts.no_deps {
-        let resolve = ops::resolve_workspace(ws)?;
+        let members: Vec<PackageId> = ws.members().map(|member| member.id).collect();
+        opts.features.validate(&members, ws)?;
+        let resolve = ops::resolve_workspace_with_opts(
+            ws,
+            &ops::ResolveOpts {
+                features: opts.features.clone(),
+                ..Default::default()
+            },
+        )?;
         let packages: Vec<m::PackageMetadata> = resolve
             .packages
             .values()
+            .chain(
+                resolve
+                    .build_packages
+                    .values()
+                    .filter(|package| !resolve.packages.contains_key(&package.id)),
+            )
             .map(collect_package_metadata)
             .collect();
 
         let compilation_units: Vec<m::CompilationUnitMetadata> =
-            ops::generate_compilation_units(&resolve, ws)?
+            ops::generate_compilation_units(&resolve, &opts.features, ws)?
                 .iter()
                 .map(collect_compilation_unit_metadata)
                 .collect();
@@ -70,11 +87,17 @@ pub fn collect_metadata(opts: &MetadataOptions, ws: &Workspace<'_>) -> Result<m:
 fn collect_workspace_metadata(ws: &Workspace<'_>) -> Result<m::WorkspaceMetadata> {
     let mut members: Vec<m::PackageId> = ws.members().map(|it| wrap_package_id(it.id)).collect();
     members.sort();
+    let default_members = ws.default_members().map(|packages| {
+        let mut ids: Vec<m::PackageId> = packages.iter().map(|it| wrap_package_id(it.id)).collect();
+        ids.sort();
+        ids
+    });
 
     Ok(m::WorkspaceMetadataBuilder::default()
         .manifest_path(ws.manifest_path())
         .root(ws.root())
         .members(members)
+        .default_members(default_members)
         .build()
         .unwrap())
 }
@@ -122,6 +145,14 @@ fn collect_package_metadata(package: &Package) -> m::PackageMetadata {
                 .as_ref()
                 .map(btree_toml_to_json),
         )
+        .metadata(
+            package
+                .manifest
+                .metadata
+                .package_metadata
+                .as_ref()
+                .map(btree_toml_to_json),
+        )
         .build()
         .unwrap();
 
@@ -135,10 +166,33 @@ fn collect_package_metadata(package: &Package) -> m::PackageMetadata {
         .dependencies(dependencies)
         .targets(targets)
         .manifest_metadata(manifest_metadata)
+        .vcs(collect_vcs_metadata(package))
         .build()
         .unwrap()
 }
 
+fn collect_vcs_metadata(package: &Package) -> Option<m::VcsMetadata> {
+    if !package.id.source_id.is_registry() {
+        return None;
+    }
+    let vcs_info = match VcsInfo::read(package.root()) {
+        Ok(vcs_info) => vcs_info?,
+        Err(err) => {
+            debug!("failed to read VCS info of {}: {err:#}", package.id);
+            return None;
+        }
+    };
+    Some(
+        m::VcsMetadataBuilder::default()
+            .commit_hash(vcs_info.git.commit_hash)
+            .dirty(vcs_info.git.dirty)
+            .path_in_vcs(vcs_info.path_in_vcs)
+            .repository(vcs_info.repository)
+            .build()
+            .unwrap(),
+    )
+}
+
 fn collect_dependency_metadata(dependency: &ManifestDependency) -> m::DependencyMetadata {
     let version_req = match &dependency.version_req {
         DependencyVersionReq::Any => VersionReq::STAR,
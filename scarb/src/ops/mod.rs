@@ -2,31 +2,68 @@
 //!
 //! For datastructures describing the state, see [`crate::core`] module.
 
+pub use audit::*;
 pub use cache::*;
 pub use clean::*;
 pub use compile::*;
+pub use config::*;
 pub use fmt::*;
+pub use hooks::*;
+pub use install::*;
+pub use login::*;
 pub use manifest::*;
 pub use metadata::*;
 pub use new::*;
+pub use outdated::*;
+pub use owner::*;
 pub use package::*;
 pub use publish::*;
 pub use resolve::*;
+pub use rpc::*;
 pub use scripts::*;
+pub use search::*;
+pub use self_update::*;
 pub use subcommands::*;
+pub use templates::*;
+pub use toolchain::*;
+pub use tools::*;
+pub use tree::*;
+pub use upgrade::*;
+pub use vendor::*;
+pub use verify::*;
 pub use workspace::*;
+pub use yank::*;
 
+mod audit;
 mod cache;
 mod clean;
 mod compile;
+mod config;
 mod fmt;
+mod hooks;
+mod install;
+mod licenses;
 mod lockfile;
+mod login;
 mod manifest;
 mod metadata;
 mod new;
+mod outdated;
+mod owner;
 mod package;
 mod publish;
 mod resolve;
+mod rpc;
 mod scripts;
+mod search;
+mod self_update;
 mod subcommands;
+mod templates;
+mod toolchain;
+mod tools;
+mod tree;
+mod upgrade;
+mod vendor;
+mod verify;
 mod workspace;
+mod yank;
@@ -1,11 +1,16 @@
+use std::io;
+use std::io::BufRead;
+
 use anyhow::{bail, ensure, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use indoc::{formatdoc, indoc};
 use itertools::Itertools;
 
-use crate::core::{Config, PackageName};
+use crate::core::{Config, PackageName, DEFAULT_ENV_FILE_NAME};
 use crate::internal::fsx;
 use crate::internal::restricted_names;
+use crate::ops::licenses::license_files;
+use crate::ops::{default_author, generate_from_template, TemplateOpts};
 use crate::{ops, DEFAULT_SOURCE_PATH, DEFAULT_TARGET_DIR_NAME, MANIFEST_FILE_NAME};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -14,19 +19,59 @@ pub enum VersionControl {
     NoVcs,
 }
 
+/// Kind of the main target of a new package.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PackageKind {
+    #[default]
+    Lib,
+    StarknetContract,
+    /// A library with a `main` function, run with `scarb cairo-run`.
+    Executable,
+}
+
+/// Test runner set up in a new package.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TestRunner {
+    /// The `scarb cairo-test` extension, bundled with Scarb.
+    #[default]
+    CairoTest,
+    StarknetFoundry,
+}
+
+/// Contents of a new package, generated unless a template is used.
+#[derive(Clone, Debug, Default)]
+pub struct Skeleton {
+    pub kind: PackageKind,
+    pub test_runner: TestRunner,
+    /// SPDX license expression put in the package manifest.
+    ///
+    /// For well-known licenses, files with their texts are created as well.
+    pub license: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct InitOptions {
     pub path: Utf8PathBuf,
     pub name: Option<PackageName>,
     pub vcs: VersionControl,
+    /// Create a virtual workspace with the package as its only member.
+    pub workspace: bool,
+    /// Generate the package from a template instead of the default hello world package.
+    pub template: Option<TemplateOpts>,
+    pub skeleton: Skeleton,
+    /// Ask the user for skeleton and VCS options, offering the ones given as defaults.
+    pub interactive: bool,
 }
 
+/// Tag of `snforge_std` package added to packages tested with Starknet Foundry.
+const SNFORGE_STD_TAG: &str = "v0.11.0";
+
 #[derive(Debug)]
 pub struct NewResult {
     pub name: PackageName,
 }
 
-pub fn new_package(opts: InitOptions, config: &Config) -> Result<NewResult> {
+pub fn new_package(mut opts: InitOptions, config: &Config) -> Result<NewResult> {
     ensure!(
         !opts.path.exists(),
         formatdoc!(
@@ -38,13 +83,20 @@ pub fn new_package(opts: InitOptions, config: &Config) -> Result<NewResult> {
         )
     );
 
-    let name = infer_name(opts.name, &opts.path, config)?;
+    let name = infer_name(opts.name.clone(), &opts.path, config)?;
+
+    if opts.interactive {
+        ask_options(&mut opts, &mut io::stdin().lock(), config)?;
+    }
 
     mk(
         MkOpts {
             path: opts.path.clone(),
             name: name.clone(),
             version_control: opts.vcs,
+            workspace: opts.workspace,
+            template: opts.template,
+            skeleton: opts.skeleton,
         },
         config,
     )
@@ -53,19 +105,26 @@ pub fn new_package(opts: InitOptions, config: &Config) -> Result<NewResult> {
     Ok(NewResult { name })
 }
 
-pub fn init_package(opts: InitOptions, config: &Config) -> Result<NewResult> {
+pub fn init_package(mut opts: InitOptions, config: &Config) -> Result<NewResult> {
     ensure!(
         !opts.path.join(MANIFEST_FILE_NAME).exists(),
         "`scarb init` cannot be run on existing Scarb packages"
     );
 
-    let name = infer_name(opts.name, &opts.path, config)?;
+    let name = infer_name(opts.name.clone(), &opts.path, config)?;
+
+    if opts.interactive {
+        ask_options(&mut opts, &mut io::stdin().lock(), config)?;
+    }
 
     mk(
         MkOpts {
             path: opts.path,
             name: name.clone(),
             version_control: opts.vcs,
+            workspace: opts.workspace,
+            template: opts.template,
+            skeleton: opts.skeleton,
         },
         config,
     )
@@ -108,10 +167,108 @@ fn infer_name(name: Option<PackageName>, path: &Utf8Path, config: &Config) -> Re
     Ok(name)
 }
 
+/// Ask the user about skeleton and VCS options of a new package, reading answers from `input`.
+///
+/// Options already set are offered as default answers.
+fn ask_options(opts: &mut InitOptions, input: &mut impl BufRead, config: &Config) -> Result<()> {
+    let skeleton = &mut opts.skeleton;
+    skeleton.kind = choose(
+        "Package kind",
+        &[
+            ("lib", PackageKind::Lib),
+            ("starknet-contract", PackageKind::StarknetContract),
+            ("executable", PackageKind::Executable),
+        ],
+        skeleton.kind,
+        input,
+        config,
+    )?;
+    skeleton.test_runner = choose(
+        "Test runner",
+        &[
+            ("cairo-test", TestRunner::CairoTest),
+            ("starknet-foundry", TestRunner::StarknetFoundry),
+        ],
+        skeleton.test_runner,
+        input,
+        config,
+    )?;
+    let license = ask(
+        &format!(
+            "License (SPDX expression) [{}]:",
+            skeleton.license.as_deref().unwrap_or("none")
+        ),
+        input,
+        config,
+    )?;
+    if !license.is_empty() {
+        skeleton.license = (license != "none").then_some(license);
+    }
+    opts.vcs = choose(
+        "Version control",
+        &[
+            ("git", VersionControl::Git),
+            ("none", VersionControl::NoVcs),
+        ],
+        opts.vcs,
+        input,
+        config,
+    )?;
+    Ok(())
+}
+
+/// Ask the user to pick one of `choices`, until a valid answer is given.
+fn choose<T: Copy + PartialEq>(
+    question: &str,
+    choices: &[(&str, T)],
+    default: T,
+    input: &mut impl BufRead,
+    config: &Config,
+) -> Result<T> {
+    let names = choices.iter().map(|(name, _)| *name).join(", ");
+    let default_name = choices
+        .iter()
+        .find(|(_, value)| *value == default)
+        .map(|(name, _)| *name)
+        .unwrap_or_default();
+    loop {
+        let answer = ask(
+            &format!("{question} ({names}) [{default_name}]:"),
+            input,
+            config,
+        )?;
+        if answer.is_empty() {
+            return Ok(default);
+        }
+        match choices.iter().find(|(name, _)| *name == answer) {
+            Some((_, value)) => return Ok(*value),
+            None => config.ui().warn(format!(
+                "invalid answer `{answer}`, expected one of: {names}"
+            )),
+        }
+    }
+}
+
+fn ask(question: &str, input: &mut impl BufRead, config: &Config) -> Result<String> {
+    config.ui().print(question);
+    let mut line = String::new();
+    let read = input
+        .read_line(&mut line)
+        .context("failed to read answer from standard input")?;
+    ensure!(
+        read > 0,
+        "standard input closed while waiting for an answer"
+    );
+    Ok(line.trim().to_string())
+}
+
 struct MkOpts {
     path: Utf8PathBuf,
     name: PackageName,
     version_control: VersionControl,
+    workspace: bool,
+    template: Option<TemplateOpts>,
+    skeleton: Skeleton,
 }
 
 fn mk(
@@ -119,6 +276,9 @@ fn mk(
         path,
         name,
         version_control,
+        workspace,
+        template,
+        skeleton,
     }: MkOpts,
     config: &Config,
 ) -> Result<()> {
@@ -128,60 +288,161 @@ fn mk(
     let canonical_path = fsx::canonicalize_utf8(&path).unwrap_or(path);
 
     init_vcs(&canonical_path, version_control)?;
-    write_vcs_ignore(&canonical_path, config, version_control)?;
 
-    // Create the `Scarb.toml` file.
-    let manifest_path = canonical_path.join(MANIFEST_FILE_NAME);
+    if let Some(template) = template {
+        generate_from_template(&template, &canonical_path, &name, config)?;
+        // Templates may ship their own ignore file.
+        if !canonical_path.join(".gitignore").exists() {
+            write_vcs_ignore(&canonical_path, &skeleton, config, version_control)?;
+        }
+        let manifest_path = canonical_path.join(MANIFEST_FILE_NAME);
+        if let Err(err) = ops::read_workspace(&manifest_path, config) {
+            config.ui().warn(formatdoc! {r#"
+                compiling this new package may not work due to invalid workspace configuration
+
+                {err:?}
+            "#})
+        }
+        return Ok(());
+    }
+
+    write_vcs_ignore(&canonical_path, &skeleton, config, version_control)?;
+
+    if workspace {
+        mk_workspace(&canonical_path, &name, &skeleton, config)
+    } else {
+        mk_package(
+            &canonical_path,
+            &name,
+            package_manifest(&name, r#"version = "0.1.0""#, &skeleton),
+            &skeleton,
+            config,
+        )
+    }
+}
+
+/// Create a virtual workspace in `path`, with a single member package in the `name` subdirectory.
+fn mk_workspace(
+    path: &Utf8Path,
+    name: &PackageName,
+    skeleton: &Skeleton,
+    config: &Config,
+) -> Result<()> {
+    let manifest_path = path.join(MANIFEST_FILE_NAME);
+    let member_path = path.join(name.as_str());
+    ensure!(
+        !member_path.exists(),
+        "destination `{member_path}` already exists"
+    );
+
     fsx::write(
         &manifest_path,
         formatdoc! {r#"
-            [package]
-            name = "{name}"
-            version = "0.1.0"
+            [workspace]
+            members = ["{name}"]
 
-            # See more keys and their definitions at https://docs.swmansion.com/scarb/docs/reference/manifest.html
+            # See more keys and their definitions at https://docs.swmansion.com/scarb/docs/reference/workspaces.html
 
-            [dependencies]
+            [workspace.package]
+            version = "0.1.0"
+
+            [workspace.dependencies]
         "#},
     )?;
 
+    mk_package(
+        &member_path,
+        name,
+        package_manifest(name, "version.workspace = true", skeleton),
+        skeleton,
+        config,
+    )?;
+
+    // Generate the lockfile, so that it can be committed right away.
+    if let Ok(ws) = ops::read_workspace(&manifest_path, config) {
+        ops::resolve_workspace(&ws)?;
+    }
+
+    Ok(())
+}
+
+/// Manifest of a new package, with `version` being the line declaring its version.
+fn package_manifest(name: &PackageName, version: &str, skeleton: &Skeleton) -> String {
+    let mut manifest = formatdoc! {r#"
+        [package]
+        name = "{name}"
+        {version}
+    "#};
+    if let Some(license) = &skeleton.license {
+        manifest.push_str(&format!("license = \"{license}\"\n"));
+    }
+    manifest.push_str(indoc! {r#"
+
+        # See more keys and their definitions at https://docs.swmansion.com/scarb/docs/reference/manifest.html
+
+        [dependencies]
+    "#});
+    if skeleton.kind == PackageKind::StarknetContract {
+        let cairo_version = crate::version::get().cairo.version;
+        manifest.push_str(&format!("starknet = \">={cairo_version}\"\n"));
+    }
+    if skeleton.test_runner == TestRunner::StarknetFoundry {
+        manifest.push_str(&format!(
+            "snforge_std = {{ git = \"https://github.com/foundry-rs/starknet-foundry.git\", \
+            tag = \"{SNFORGE_STD_TAG}\" }}\n"
+        ));
+    }
+
+    if skeleton.kind == PackageKind::StarknetContract {
+        manifest.push_str("\n[[target.starknet-contract]]\n");
+    }
+
+    let mut scripts = Vec::new();
+    if skeleton.kind == PackageKind::Executable {
+        scripts.push(r#"run = "scarb build && scarb cairo-run""#);
+    }
+    if skeleton.test_runner == TestRunner::StarknetFoundry {
+        scripts.push(r#"test = "snforge test""#);
+    }
+    if !scripts.is_empty() {
+        manifest.push_str("\n[scripts]\n");
+        for script in scripts {
+            manifest.push_str(script);
+            manifest.push('\n');
+        }
+    }
+
+    manifest
+}
+
+/// Write the package manifest, hello world source files and license files into `path`.
+fn mk_package(
+    path: &Utf8Path,
+    name: &PackageName,
+    manifest: String,
+    skeleton: &Skeleton,
+    config: &Config,
+) -> Result<()> {
+    fsx::create_dir_all(path)?;
+
+    // Create the `Scarb.toml` file.
+    let manifest_path = path.join(MANIFEST_FILE_NAME);
+    fsx::write(&manifest_path, manifest)?;
+
     // Create hello world source files (with respective parent directories) if none exist.
-    let source_path = canonical_path.join(DEFAULT_SOURCE_PATH);
+    let source_path = path.join(DEFAULT_SOURCE_PATH);
     if !source_path.exists() {
         fsx::create_dir_all(source_path.parent().unwrap())?;
 
-        fsx::write(
-            source_path,
-            indoc! {r#"
-                fn main() -> felt252 {
-                    fib(16)
-                }
-
-                fn fib(mut n: felt252) -> felt252 {
-                    let mut a: felt252 = 0;
-                    let mut b: felt252 = 1;
-                    loop {
-                        if n == 0 {
-                            break a;
-                        }
-                        n = n - 1;
-                        let temp = b;
-                        b = a + b;
-                        a = temp;
-                    }
-                }
-
-                #[cfg(test)]
-                mod tests {
-                    use super::fib;
-
-                    #[test]
-                    fn it_works() {
-                        assert(fib(16) == 987, 'it works!');
-                    }
-                }
-            "#},
-        )?;
+        let source = match skeleton.kind {
+            PackageKind::Lib | PackageKind::Executable => HELLO_WORLD_SOURCE,
+            PackageKind::StarknetContract => HELLO_STARKNET_SOURCE,
+        };
+        fsx::write(source_path, source)?;
+    }
+
+    if let Some(license) = &skeleton.license {
+        write_license_files(path, name, license, config)?;
     }
 
     if let Err(err) = ops::read_workspace(&manifest_path, config) {
@@ -195,6 +456,90 @@ fn mk(
     Ok(())
 }
 
+/// Create files with texts of licenses in the SPDX `license` expression, unless they exist.
+///
+/// Copyright is attributed to the Git author, if known.
+fn write_license_files(
+    path: &Utf8Path,
+    name: &PackageName,
+    license: &str,
+    config: &Config,
+) -> Result<()> {
+    let holder = default_author().unwrap_or_else(|| format!("The {name} Authors"));
+    let Some(files) = license_files(license, &holder) else {
+        config.ui().warn(formatdoc! {r#"
+            Scarb does not know the text of `{license}` license
+            help: add the license file to the package manually
+        "#});
+        return Ok(());
+    };
+    for (file_name, contents) in files {
+        let file_path = path.join(file_name);
+        if !file_path.exists() {
+            fsx::write(&file_path, contents)?;
+        }
+    }
+    Ok(())
+}
+
+const HELLO_WORLD_SOURCE: &str = indoc! {r#"
+    fn main() -> felt252 {
+        fib(16)
+    }
+
+    fn fib(mut n: felt252) -> felt252 {
+        let mut a: felt252 = 0;
+        let mut b: felt252 = 1;
+        loop {
+            if n == 0 {
+                break a;
+            }
+            n = n - 1;
+            let temp = b;
+            b = a + b;
+            a = temp;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::fib;
+
+        #[test]
+        fn it_works() {
+            assert(fib(16) == 987, 'it works!');
+        }
+    }
+"#};
+
+const HELLO_STARKNET_SOURCE: &str = indoc! {r#"
+    #[starknet::interface]
+    trait IHelloStarknet<TContractState> {
+        fn increase_balance(ref self: TContractState, amount: felt252);
+        fn get_balance(self: @TContractState) -> felt252;
+    }
+
+    #[starknet::contract]
+    mod HelloStarknet {
+        #[storage]
+        struct Storage {
+            balance: felt252,
+        }
+
+        #[external(v0)]
+        impl HelloStarknetImpl of super::IHelloStarknet<ContractState> {
+            fn increase_balance(ref self: ContractState, amount: felt252) {
+                assert(amount != 0, 'Amount cannot be 0');
+                self.balance.write(self.balance.read() + amount);
+            }
+
+            fn get_balance(self: @ContractState) -> felt252 {
+                self.balance.read()
+            }
+        }
+    }
+"#};
+
 fn init_vcs(path: &Utf8Path, vcs: VersionControl) -> Result<()> {
     match vcs {
         VersionControl::Git => {
@@ -208,9 +553,17 @@ fn init_vcs(path: &Utf8Path, vcs: VersionControl) -> Result<()> {
     Ok(())
 }
 
-/// Write VCS ignore file.
-fn write_vcs_ignore(path: &Utf8Path, config: &Config, vcs: VersionControl) -> Result<()> {
-    let patterns = vec![DEFAULT_TARGET_DIR_NAME];
+/// Write VCS ignore file, covering build artifacts, dotenv files and test runner caches.
+fn write_vcs_ignore(
+    path: &Utf8Path,
+    skeleton: &Skeleton,
+    config: &Config,
+    vcs: VersionControl,
+) -> Result<()> {
+    let mut patterns = vec![DEFAULT_TARGET_DIR_NAME, DEFAULT_ENV_FILE_NAME];
+    if skeleton.test_runner == TestRunner::StarknetFoundry {
+        patterns.push(".snfoundry_cache/");
+    }
 
     let fp_ignore = match vcs {
         VersionControl::Git => path.join(".gitignore"),
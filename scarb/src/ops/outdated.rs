@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use petgraph::Direction;
+use semver::Version;
+use serde::Serialize;
+
+use crate::core::registry::source_map::SourceMap;
+use crate::core::registry::Registry;
+use crate::core::{ManifestDependency, PackageId, PackageName, SourceId, Workspace};
+use crate::ops;
+
+/// A locked dependency for which newer versions are available in its registry.
+#[derive(Clone, Debug, Serialize)]
+pub struct OutdatedDependency {
+    pub name: PackageName,
+    pub source: SourceId,
+    /// Version locked in the lockfile.
+    pub current: Version,
+    /// Newest version matching version requirements of all dependants of the package.
+    pub compatible: Version,
+    /// Newest version available in the registry, possibly not matching version requirements.
+    pub latest: Version,
+    pub kind: OutdatedDependencyKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutdatedDependencyKind {
+    /// Dependency declared by a workspace member.
+    Direct,
+    /// Dependency pulled in by other dependencies only.
+    Transitive,
+}
+
+/// Compare locked versions of registry dependencies of the workspace against versions available
+/// in their registries.
+///
+/// Prerelease versions are only considered for packages locked at a prerelease version.
+/// Dependencies from other sources (paths, Git repositories) are not checked.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn outdated(ws: &Workspace<'_>) -> Result<Vec<OutdatedDependency>> {
+    let resolve = ops::resolve_workspace(ws)?;
+    let members: HashSet<PackageId> = ws.members().map(|member| member.id).collect();
+    let source_map = SourceMap::preloaded(ws.members(), ws.config());
+
+    ws.config().tokio_handle().block_on(async {
+        let mut outdated = Vec::new();
+        for package_id in resolve.resolve.package_ids().sorted() {
+            if !package_id.source_id.is_registry() {
+                continue;
+            }
+
+            let dependants = resolve
+                .resolve
+                .graph
+                .neighbors_directed(package_id, Direction::Incoming)
+                .collect_vec();
+            let requirements = dependants
+                .iter()
+                .flat_map(|dependant| {
+                    resolve.packages[dependant]
+                        .manifest
+                        .summary
+                        .dependencies
+                        .iter()
+                })
+                .filter(|dep| dep.name == package_id.name && dep.source_id == package_id.source_id)
+                .collect_vec();
+
+            let available = source_map
+                .query_versions(package_id.name.clone(), package_id.source_id)
+                .await
+                .with_context(|| format!("failed to query versions of package `{package_id}`"))?;
+            let candidates = available
+                .iter()
+                .map(|summary| &summary.package_id.version)
+                .filter(|version| version.pre.is_empty() || !package_id.version.pre.is_empty())
+                .collect_vec();
+
+            let latest = newest(&package_id.version, candidates.iter().copied());
+            let compatible = newest(
+                &package_id.version,
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|version| matches_all(&requirements, version)),
+            );
+            if latest == package_id.version && compatible == package_id.version {
+                continue;
+            }
+
+            let kind = if dependants.iter().any(|id| members.contains(id)) {
+                OutdatedDependencyKind::Direct
+            } else {
+                OutdatedDependencyKind::Transitive
+            };
+
+            outdated.push(OutdatedDependency {
+                name: package_id.name.clone(),
+                source: package_id.source_id,
+                current: package_id.version.clone(),
+                compatible,
+                latest,
+                kind,
+            });
+        }
+        Ok(outdated)
+    })
+}
+
+/// The newest of `versions`, or `current` if none of them is newer.
+fn newest<'a>(current: &Version, versions: impl Iterator<Item = &'a Version>) -> Version {
+    versions
+        .filter(|version| *version > current)
+        .max()
+        .unwrap_or(current)
+        .clone()
+}
+
+fn matches_all(requirements: &[&ManifestDependency], version: &Version) -> bool {
+    requirements
+        .iter()
+        .all(|dep| dep.version_req.matches(version))
+}
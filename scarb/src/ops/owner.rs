@@ -0,0 +1,76 @@
+use anyhow::{ensure, Context, Result};
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::client::RegistryClient;
+use crate::core::{Config, PackageName, SourceId};
+use crate::sources::RegistrySource;
+
+pub struct OwnersOpts {
+    pub index_url: Url,
+    pub package: PackageName,
+}
+
+/// Get logins of owners of the package in the registry.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn list_owners(opts: &OwnersOpts, config: &Config) -> Result<Vec<String>> {
+    let client = owners_client(opts, config)?;
+    config
+        .tokio_handle()
+        .block_on(client.list_owners(opts.package.clone()))
+        .with_context(|| format!("failed to list owners of package `{}`", opts.package))
+}
+
+/// Grant users with given logins ownership of the package in the registry.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn add_owners(logins: &[String], opts: &OwnersOpts, config: &Config) -> Result<()> {
+    let client = owners_client(opts, config)?;
+    for login in logins {
+        config.ui().print(Status::new(
+            "Adding",
+            &format!("`{login}` as owner of `{}`", opts.package),
+        ));
+    }
+    config
+        .tokio_handle()
+        .block_on(client.add_owners(opts.package.clone(), logins))
+        .with_context(|| format!("failed to add owners of package `{}`", opts.package))
+}
+
+/// Revoke ownership of the package in the registry from users with given logins.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn remove_owners(logins: &[String], opts: &OwnersOpts, config: &Config) -> Result<()> {
+    let client = owners_client(opts, config)?;
+    for login in logins {
+        config.ui().print(Status::new(
+            "Removing",
+            &format!("`{login}` from owners of `{}`", opts.package),
+        ));
+    }
+    config
+        .tokio_handle()
+        .block_on(client.remove_owners(opts.package.clone(), logins))
+        .with_context(|| format!("failed to remove owners of package `{}`", opts.package))
+}
+
+fn owners_client<'c>(
+    opts: &OwnersOpts,
+    config: &'c Config,
+) -> Result<Box<dyn RegistryClient + 'c>> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let client = RegistrySource::create_client(source_id, config)?;
+
+    let supports_publish = config
+        .tokio_handle()
+        .block_on(client.supports_publish())
+        .with_context(|| {
+            format!("failed to check if registry supports managing owners: {source_id}")
+        })?;
+    ensure!(
+        supports_publish,
+        "managing package owners is not supported by registry: {source_id}"
+    );
+
+    Ok(client)
+}
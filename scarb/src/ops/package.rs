@@ -1,5 +1,5 @@
-use std::collections::BTreeMap;
-use std::fs::File;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, Metadata};
 use std::io::{Seek, SeekFrom, Write};
 
 use anyhow::{bail, ensure, Context, Result};
@@ -11,7 +11,8 @@ use scarb_ui::{HumanBytes, HumanCount};
 
 use crate::core::publishing::manifest_normalization::prepare_manifest_for_publish;
 use crate::core::publishing::source::list_source_files;
-use crate::core::{Package, PackageId, PackageName, Workspace};
+use crate::core::publishing::vcs::{GitPackageRepo, VCS_INFO_FILE_NAME};
+use crate::core::{HookKind, Package, PackageId, PackageName, Workspace};
 use crate::flock::FileLockGuard;
 use crate::internal::restricted_names;
 use crate::{ops, MANIFEST_FILE_NAME};
@@ -20,9 +21,26 @@ const VERSION: u8 = 1;
 const VERSION_FILE_NAME: &str = "VERSION";
 const ORIGINAL_MANIFEST_FILE_NAME: &str = "Scarb.orig.toml";
 
-const RESERVED_FILES: &[&str] = &[VERSION_FILE_NAME, ORIGINAL_MANIFEST_FILE_NAME];
+const RESERVED_FILES: &[&str] = &[
+    VERSION_FILE_NAME,
+    ORIGINAL_MANIFEST_FILE_NAME,
+    VCS_INFO_FILE_NAME,
+];
 
-pub struct PackageOpts;
+/// Modification time set on all archive entries, so that archives are reproducible.
+///
+/// From `set_metadata_in_mode` implementation in `tar` crate:
+/// We could in theory set the mtime to zero here, but not all
+/// tools seem to behave well when ingesting files with a 0
+/// timestamp.
+const ARCHIVE_MTIME: u64 = 1;
+
+pub struct PackageOpts {
+    /// Run `pre-package` and `post-package` hooks of packaged packages.
+    ///
+    /// Disabled when packaging sources which are not trusted, like when verifying packages.
+    pub run_hooks: bool,
+}
 
 /// A listing of files to include in the archive, without actually building it yet.
 ///
@@ -88,10 +106,10 @@ fn before_package(ws: &Workspace<'_>) -> Result<()> {
     Ok(())
 }
 
-#[tracing::instrument(level = "trace", skip(_opts, ws))]
+#[tracing::instrument(level = "trace", skip(opts, ws))]
 fn package_one_impl(
     pkg_id: PackageId,
-    _opts: &PackageOpts,
+    opts: &PackageOpts,
     ws: &Workspace<'_>,
 ) -> Result<FileLockGuard> {
     let pkg = ws.fetch_package(&pkg_id)?;
@@ -104,6 +122,10 @@ fn package_one_impl(
 
     // TODO(#643): Check dirty in VCS (but do not do it when listing!).
 
+    if opts.run_hooks {
+        ops::run_hook(HookKind::PrePackage, pkg, &[], HashMap::new(), ws)?;
+    }
+
     let recipe = prepare_archive_recipe(pkg)?;
     let num_files = recipe.len();
 
@@ -142,6 +164,16 @@ fn package_one_impl(
         ),
     ));
 
+    if opts.run_hooks {
+        ops::run_hook(
+            HookKind::PostPackage,
+            pkg,
+            &[],
+            HashMap::from_iter([("SCARB_PACKAGE_ARCHIVE".into(), dst.path().into())]),
+            ws,
+        )?;
+    }
+
     Ok(dst)
 }
 
@@ -185,6 +217,18 @@ fn prepare_archive_recipe(pkg: &Package) -> Result<ArchiveRecipe> {
         contents: ArchiveFileContents::Generated(Box::new(|| Ok(VERSION.to_string().into_bytes()))),
     });
 
+    // Add VCS info file, if the package is located in a Git repository.
+    if let Some(repo) = GitPackageRepo::discover(pkg.root())? {
+        let files = recipe.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        recipe.push(ArchiveFile {
+            path: VCS_INFO_FILE_NAME.into(),
+            contents: ArchiveFileContents::Generated(Box::new(move || {
+                let vcs_info = repo.vcs_info(&files)?;
+                Ok(serde_json::to_string_pretty(&vcs_info)?.into_bytes())
+            })),
+        });
+    }
+
     // Put generated files in right order within the recipe.
     sort_recipe(&mut recipe);
 
@@ -290,6 +334,9 @@ fn normalize_manifest(pkg: Package) -> Result<Vec<u8>> {
 
 /// Compress and package the recipe, and write it into the given file.
 ///
+/// The archive only depends on paths and contents of packaged files, and on whether they are
+/// executable, so that identical sources always produce byte-identical archives.
+///
 /// Returns the uncompressed size of the contents of the archive.
 #[tracing::instrument(level = "trace", skip_all)]
 fn tar(
@@ -299,7 +346,12 @@ fn tar(
     ws: &Workspace<'_>,
 ) -> Result<u64> {
     const COMPRESSION_LEVEL: i32 = 22;
-    let encoder = zstd::stream::Encoder::new(dst, COMPRESSION_LEVEL)?;
+    let mut encoder = zstd::stream::Encoder::new(dst, COMPRESSION_LEVEL)?;
+    // Pin all frame parameters, instead of relying on library defaults.
+    encoder.include_checksum(false)?;
+    encoder.include_contentsize(false)?;
+    encoder.include_dictid(false)?;
+    encoder.long_distance_matching(false)?;
     let mut ar = tar::Builder::new(encoder);
 
     let base_path = Utf8PathBuf::from(pkg_id.tarball_basename());
@@ -311,7 +363,6 @@ fn tar(
             .verbose(Status::new("Archiving", path.as_str()));
 
         let archive_path = base_path.join(&path);
-        let mut header = tar::Header::new_gnu();
         match contents {
             ArchiveFileContents::OnDisk(disk_path) => {
                 let mut file = File::open(&disk_path)
@@ -321,9 +372,7 @@ fn tar(
                     .metadata()
                     .with_context(|| format!("failed to stat: {disk_path}"))?;
 
-                header.set_metadata_in_mode(&metadata, tar::HeaderMode::Deterministic);
-                header.set_cksum();
-
+                let mut header = archive_header(metadata.len(), is_executable(&metadata));
                 ar.append_data(&mut header, &archive_path, &mut file)
                     .with_context(|| format!("could not archive source file: {disk_path}"))?;
 
@@ -333,18 +382,7 @@ fn tar(
             ArchiveFileContents::Generated(generator) => {
                 let contents = generator()?;
 
-                header.set_entry_type(tar::EntryType::file());
-                header.set_mode(0o644);
-                header.set_size(contents.len() as u64);
-
-                // From `set_metadata_in_mode` implementation in `tar` crate:
-                // We could in theory set the mtime to zero here, but not all
-                // tools seem to behave well when ingesting files with a 0
-                // timestamp.
-                header.set_mtime(1);
-
-                header.set_cksum();
-
+                let mut header = archive_header(contents.len() as u64, false);
                 ar.append_data(&mut header, &archive_path, contents.as_slice())
                     .with_context(|| format!("could not archive source file: {path}"))?;
 
@@ -357,3 +395,27 @@ fn tar(
     encoder.finish()?;
     Ok(uncompressed_size)
 }
+
+/// Create a header of a regular file entry, with all metadata normalized.
+fn archive_header(size: u64, executable: bool) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::file());
+    header.set_mode(if executable { 0o755 } else { 0o644 });
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(ARCHIVE_MTIME);
+    header.set_size(size);
+    header.set_cksum();
+    header
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &Metadata) -> bool {
+    false
+}
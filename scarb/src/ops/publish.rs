@@ -1,21 +1,99 @@
-use anyhow::{ensure, Context, Result};
+use std::io;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use smol_str::SmolStr;
+use toml_edit::{Document, InlineTable, Item, Table};
 use url::Url;
 
 use scarb_ui::components::Status;
 
-use crate::core::{PackageId, SourceId, Workspace};
-use crate::ops;
+use crate::core::registry::client::{OtpRequired, RegistryClient};
+use crate::core::registry::provenance::{
+    attest, BuildEnvironment, ProvenanceSigningKey, SignedProvenance,
+};
+use crate::core::{
+    Config, ConfigFile, Digest, Package, PackageId, PackageName, SourceId, TargetKind, Workspace,
+};
+use crate::flock::FileLockGuard;
+use crate::internal::fsx;
 use crate::sources::RegistrySource;
+use crate::{ops, MANIFEST_FILE_NAME};
+
+/// Delay between checks whether a published package is available in the registry index.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct PublishOpts {
     pub index_url: Url,
+    /// Name of the configured registry `index_url` has been taken from, if it was selected by name.
+    pub registry_name: Option<SmolStr>,
+    /// Wait until each published version appears in registry index.
+    ///
+    /// Packages which other published packages depend on are always waited for.
+    pub wait: bool,
+    /// Maximum time to wait for a published version to appear in registry index.
+    pub wait_timeout: Duration,
+    /// Compile the packaged tarball as a standalone package before uploading it.
+    pub verify: bool,
+    /// Perform all checks and build the package, but do not upload it.
+    pub dry_run: bool,
+    /// Minisign secret key to sign provenance attestations of published packages with.
+    ///
+    /// Attestations are only generated if this is set.
+    pub provenance_key: Option<String>,
+    /// One-time password to send along with uploads, for registries enforcing two-factor
+    /// authentication.
+    ///
+    /// If the registry requires one and it is missing or rejected, it is asked for on standard
+    /// input.
+    pub otp: Option<String>,
 }
 
+/// Publish `packages` to the registry, in an order in which dependencies are published
+/// before their dependants.
 #[tracing::instrument(level = "debug", skip(opts, ws))]
-pub fn publish(package_id: PackageId, opts: &PublishOpts, ws: &Workspace<'_>) -> Result<()> {
-    let package = ws.fetch_package(&package_id)?.clone();
+pub fn publish(packages: &[PackageId], opts: &PublishOpts, ws: &Workspace<'_>) -> Result<()> {
+    let packages = packages
+        .iter()
+        .map(|package_id| Ok(ws.fetch_package(package_id)?.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    let packages = publish_order(packages)?;
 
     let source_id = SourceId::for_registry(&opts.index_url)?;
+    let registry_name = opts.registry_name.clone().or_else(|| {
+        ws.config()
+            .config_file()
+            .registry_serving(source_id)
+            .map(|(name, _)| name.clone())
+    });
+    for package in &packages {
+        check_publish_allowed(package, registry_name.as_ref(), source_id)?;
+    }
+
+    let provenance = opts
+        .provenance_key
+        .as_deref()
+        .map(|key| -> Result<_> {
+            let environment = BuildEnvironment::detect().context(
+                "provenance attestations can only be generated in GitHub Actions or GitLab CI",
+            )?;
+            Ok((environment, ProvenanceSigningKey::parse(key)?))
+        })
+        .transpose()?;
+
+    // Local registries are created on first publish, enabling fully offline workflows.
+    if source_id.url.scheme() == "file" && !opts.dry_run {
+        if let Ok(path) = source_id.url.to_file_path() {
+            if !path.exists() {
+                fsx::create_dir_all(&path)
+                    .with_context(|| format!("failed to create local registry: {source_id}"))?;
+            }
+        }
+    }
+
     let registry_client = RegistrySource::create_client(source_id, ws.config())?;
 
     let supports_publish = ws
@@ -28,20 +106,371 @@ pub fn publish(package_id: PackageId, opts: &PublishOpts, ws: &Workspace<'_>) ->
         "publishing packages is not supported by registry: {source_id}"
     );
 
-    let package_opts = ops::PackageOpts;
-    let tarball = ops::package_one(package_id, &package_opts, ws)?;
+    // Check, package and verify all packages before uploading any of them, so that a failure
+    // does not leave the workspace published only partially.
+    let mut verified = Vec::new();
+    let prepared = packages
+        .into_iter()
+        .map(|package| {
+            prepare_one(
+                package,
+                registry_client.as_ref(),
+                provenance.as_ref(),
+                &mut verified,
+                opts,
+                ws,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if opts.dry_run {
+        ws.config().ui().warn("aborting upload due to dry run");
+        return Ok(());
+    }
+
+    // Password entered once is reused for following packages, while it is accepted.
+    let mut otp = opts.otp.clone();
+    for (idx, package) in prepared.iter().enumerate() {
+        let has_dependants = prepared[idx + 1..]
+            .iter()
+            .any(|dependant| depends_on(&dependant.package, &package.package.id.name));
+        upload_one(
+            package,
+            registry_client.as_ref(),
+            has_dependants,
+            &mut otp,
+            opts,
+            ws,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// A package which passed all checks and is ready to be uploaded.
+struct PreparedPackage {
+    package: Package,
+    dest_package_id: PackageId,
+    tarball: FileLockGuard,
+    provenance: Option<SignedProvenance>,
+}
 
+/// Check that `package.publish` of `package` allows uploading it to the target registry.
+fn check_publish_allowed(
+    package: &Package,
+    registry_name: Option<&SmolStr>,
+    source_id: SourceId,
+) -> Result<()> {
+    let Some(allowed) = &package.manifest.metadata.publish else {
+        return Ok(());
+    };
+    let name = &package.id.name;
+    if allowed.is_empty() {
+        bail!("`{name}` cannot be published, `package.publish` is set to `false`");
+    }
+    if registry_name.is_some_and(|registry| allowed.contains(registry)) {
+        return Ok(());
+    }
+    let target = match registry_name {
+        Some(registry) => format!("registry `{registry}`"),
+        None => format!("`{source_id}`"),
+    };
+    bail!(
+        "`{name}` cannot be published to {target}, `package.publish` only allows: {}",
+        allowed.iter().map(|r| format!("`{r}`")).join(", ")
+    )
+}
+
+/// Run registry checks of `package`, then package it and verify the tarball.
+///
+/// Unpacked tarballs of verified packages are collected in `verified`, so that packages depending
+/// on them can be verified before any of them is available in the registry.
+#[tracing::instrument(
+    level = "trace",
+    skip(package, registry_client, provenance, verified, opts, ws)
+)]
+fn prepare_one(
+    package: Package,
+    registry_client: &dyn RegistryClient,
+    provenance: Option<&(BuildEnvironment, ProvenanceSigningKey)>,
+    verified: &mut Vec<(PackageName, Utf8PathBuf)>,
+    opts: &PublishOpts,
+    ws: &Workspace<'_>,
+) -> Result<PreparedPackage> {
+    let package_id = package.id;
+    let source_id = SourceId::for_registry(&opts.index_url)?;
     let dest_package_id = package_id.with_source_id(source_id);
 
+    ws.config()
+        .tokio_handle()
+        .block_on(registry_client.check_publish(dest_package_id, &package.manifest.metadata))
+        .with_context(|| format!("cannot publish `{dest_package_id}`"))?;
+
+    let package_opts = ops::PackageOpts { run_hooks: true };
+    let tarball = ops::package_one(package_id, &package_opts, ws)?;
+
+    if opts.verify {
+        let unpack_path = verify_tarball(package_id, &tarball, verified, &opts.index_url, ws)?;
+        verified.push((package_id.name.clone(), unpack_path));
+    }
+
+    let provenance = provenance
+        .map(|(environment, key)| {
+            ws.config()
+                .ui()
+                .print(Status::new("Attesting", &package_id.to_string()));
+            let checksum = Digest::recommended()
+                .update_read(fsx::open(tarball.path())?)?
+                .finish();
+            attest(dest_package_id, &checksum, environment, key)
+        })
+        .transpose()?;
+
+    Ok(PreparedPackage {
+        package,
+        dest_package_id,
+        tarball,
+        provenance,
+    })
+}
+
+#[tracing::instrument(level = "trace", skip_all)]
+fn upload_one(
+    prepared: &PreparedPackage,
+    registry_client: &dyn RegistryClient,
+    has_dependants: bool,
+    otp: &mut Option<String>,
+    opts: &PublishOpts,
+    ws: &Workspace<'_>,
+) -> Result<()> {
+    let dest_package_id = prepared.dest_package_id;
     ws.config()
         .ui()
         .print(Status::new("Uploading", &dest_package_id.to_string()));
 
-    ws.config().tokio_handle().block_on(async {
-        registry_client.publish(package, tarball).await
+    let mut prompted = false;
+    loop {
+        let result = ws.config().tokio_handle().block_on(registry_client.publish(
+            prepared.package.clone(),
+            &prepared.tarball,
+            otp.as_deref(),
+        ));
+        match result {
+            Err(err) if err.is::<OtpRequired>() && !prompted => {
+                *otp = Some(prompt_otp(otp.is_some(), ws.config())?);
+                prompted = true;
+            }
+            result => break result?,
+        }
+    }
 
-        // TODO(mkaput): Wait for publish here.
-    })?;
+    if let Some(provenance) = &prepared.provenance {
+        ws.config()
+            .tokio_handle()
+            .block_on(registry_client.publish_provenance(dest_package_id, provenance.clone()))?;
+    }
+
+    if opts.wait || has_dependants {
+        let source_id = dest_package_id.source_id;
+        // Missing packages are cached, which must not hide the freshly published one.
+        let registry_client = RegistrySource::create_uncached_client(source_id, ws.config())?;
+        let is_available = ws.config().tokio_handle().block_on(wait_for_publish(
+            registry_client.as_ref(),
+            dest_package_id,
+            opts.wait_timeout,
+            ws.config(),
+        ))?;
+        if !is_available {
+            if has_dependants {
+                bail!(
+                    "timed out waiting for `{dest_package_id}` to be available in registry, \
+                    packages depending on it cannot be published"
+                );
+            }
+            ws.config().ui().warn(format!(
+                "timed out waiting for `{dest_package_id}` to be available in registry, \
+                it may take a while before it can be used as a dependency"
+            ));
+        }
+    }
 
     Ok(())
 }
+
+/// Ask the user for a one-time password on standard input.
+fn prompt_otp(rejected: bool, config: &Config) -> Result<String> {
+    if rejected {
+        config
+            .ui()
+            .print("registry rejected the one-time password, please enter a new one below");
+    } else {
+        config
+            .ui()
+            .print("registry requires a one-time password, please enter it below");
+    }
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .context("failed to read one-time password from standard input")?;
+    let otp = line.trim().to_string();
+    ensure!(
+        !otp.is_empty(),
+        "no one-time password provided\n\
+        help: pass it with `--otp` when publishing non-interactively"
+    );
+    Ok(otp)
+}
+
+/// Unpack freshly built `tarball` and compile it as a standalone package.
+///
+/// This catches files missing from the package and dependencies which are only resolvable
+/// within the workspace, before the package is uploaded for others to use.
+///
+/// Dependencies on packages in `verified` are patched to use their unpacked tarballs, as they are
+/// not published yet. Returns the path of the unpacked package.
+fn verify_tarball(
+    package_id: PackageId,
+    tarball: &FileLockGuard,
+    verified: &[(PackageName, Utf8PathBuf)],
+    index_url: &Url,
+    ws: &Workspace<'_>,
+) -> Result<Utf8PathBuf> {
+    ws.config()
+        .ui()
+        .print(Status::new("Verifying", &package_id.to_string()));
+
+    let package_dir = ws.target_dir().child("package");
+    let unpack_path = package_dir
+        .path_existent()?
+        .join(package_id.tarball_basename());
+    if unpack_path.exists() {
+        fsx::remove_dir_all(&unpack_path)?;
+    }
+
+    // Read the tarball by path, so that the position of the locked file handle is not moved.
+    let mut archive = tar::Archive::new(zstd::Decoder::new(fsx::open(tarball.path())?)?);
+    archive
+        .unpack(package_dir.path_unchecked())
+        .with_context(|| format!("failed to unpack: {}", tarball.path()))?;
+
+    let manifest_path = unpack_path.join(MANIFEST_FILE_NAME);
+    patch_verified_dependencies(&manifest_path, verified, index_url)?;
+    let verify_ws = ops::read_standalone_package(&manifest_path, ws.config())?;
+    let packages = verify_ws.members().map(|package| package.id).collect();
+    let opts = ops::CompileOpts {
+        include_targets: Vec::new(),
+        exclude_targets: vec![TargetKind::TEST.clone()],
+        features: ops::FeaturesOpts::default(),
+        deny_warnings: false,
+    };
+    ops::compile(packages, opts, &verify_ws).context(
+        "failed to verify package tarball\n\
+        help: use `--no-verify` to skip this check",
+    )?;
+    Ok(unpack_path)
+}
+
+/// Add `[patch]` entries to the unpacked manifest at `manifest_path`, which replace packages
+/// in `verified` with their unpacked tarballs.
+///
+/// Dependencies on other workspace members are published either as default registry
+/// dependencies, or as dependencies from the registry at `index_url`, so both are patched.
+fn patch_verified_dependencies(
+    manifest_path: &Utf8Path,
+    verified: &[(PackageName, Utf8PathBuf)],
+    index_url: &Url,
+) -> Result<()> {
+    if verified.is_empty() {
+        return Ok(());
+    }
+    let mut doc = Document::from_str(&fsx::read_to_string(manifest_path)?)
+        .with_context(|| format!("failed to read manifest at: {manifest_path}"))?;
+    let patch = doc["patch"].or_insert(implicit_table());
+    for source in [ConfigFile::DEFAULT_SOURCE_NAME, index_url.as_str()] {
+        let source_patches = patch[source].or_insert(toml_edit::table());
+        for (name, path) in verified {
+            let mut dependency = InlineTable::new();
+            dependency.insert("path", path.as_str().into());
+            source_patches[name.as_str()] = toml_edit::value(dependency);
+        }
+    }
+    fsx::write(manifest_path, doc.to_string())
+}
+
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+/// Sort packages topologically, so that each package comes after packages it depends on.
+///
+/// Packages which do not depend on each other are kept in alphabetical order.
+fn publish_order(mut packages: Vec<Package>) -> Result<Vec<Package>> {
+    packages.sort_by(|a, b| a.id.name.cmp(&b.id.name));
+
+    let mut ordered: Vec<Package> = Vec::with_capacity(packages.len());
+    while !packages.is_empty() {
+        let Some(idx) = packages.iter().position(|package| {
+            packages
+                .iter()
+                .all(|other| !depends_on(package, &other.id.name))
+        }) else {
+            let names = packages.iter().map(|package| package.id.name.to_string());
+            bail!(
+                "cannot determine publishing order, packages depend on each other: {}",
+                names.collect::<Vec<_>>().join(", ")
+            );
+        };
+        ordered.push(packages.remove(idx));
+    }
+    Ok(ordered)
+}
+
+fn depends_on(package: &Package, name: &PackageName) -> bool {
+    package
+        .manifest
+        .summary
+        .publish_dependencies()
+        .any(|dep| dep.name == *name)
+}
+
+/// Poll the registry index until `package_id` can be resolved, or `timeout` elapses.
+///
+/// Registries may process uploads asynchronously, so that published packages are not
+/// available right away. Returns `false` if the package did not appear in time.
+async fn wait_for_publish(
+    registry_client: &dyn RegistryClient,
+    package_id: PackageId,
+    timeout: Duration,
+    config: &Config,
+) -> Result<bool> {
+    config.ui().print(Status::new(
+        "Waiting",
+        &format!("for {package_id} to be available"),
+    ));
+
+    let start = Instant::now();
+    loop {
+        let records = registry_client
+            .get_records(package_id.name.clone())
+            .await
+            .with_context(|| format!("failed to check if `{package_id}` is available"))?;
+        let is_available = records.map_or(false, |records| {
+            records
+                .iter()
+                .any(|record| record.version == package_id.version)
+        });
+        if is_available {
+            config
+                .ui()
+                .print(Status::new("Published", &package_id.to_string()));
+            return Ok(true);
+        }
+
+        if start.elapsed() >= timeout {
+            return Ok(false);
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+    }
+}
@@ -1,31 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Context, Result};
 use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
-use futures::TryFutureExt;
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
 use itertools::Itertools;
+use semver::Version;
+use tracing::trace;
+
+use scarb_ui::args::FeaturesSpec;
+use scarb_ui::components::Spinner;
 
 use crate::compiler::{CompilationUnit, CompilationUnitCairoPlugin, CompilationUnitComponent};
 use crate::core::lockfile::Lockfile;
 use crate::core::package::{Package, PackageClass, PackageId};
 use crate::core::registry::cache::RegistryCache;
-use crate::core::registry::patch_map::PatchMap;
 use crate::core::registry::patcher::RegistryPatcher;
 use crate::core::registry::source_map::SourceMap;
 use crate::core::registry::Registry;
 use crate::core::resolver::Resolve;
 use crate::core::workspace::Workspace;
 use crate::core::{
-    DepKind, DependencyVersionReq, ManifestDependency, PackageName, SourceId, Target, TargetKind,
-    TestTargetProps, TestTargetType,
+    Config, DepKind, DependencyVersionReq, FeatureName, FeatureUnification, ManifestDependency,
+    PackageName, SourceId, Summary, Target, TargetKind, TestTargetProps, TestTargetType,
+    TomlManifest,
 };
 use crate::internal::to_version::ToVersion;
 use crate::ops::lockfile::{read_lockfile, write_lockfile};
+use crate::resolver::features::{FeatureResolver, ResolvedFeatures};
 use crate::{resolver, DEFAULT_SOURCE_PATH};
 
 pub struct WorkspaceResolve {
     pub resolve: Resolve,
     pub packages: HashMap<PackageId, Package>,
+    /// Resolution of build dependencies of workspace members, kept apart from the package graph,
+    /// so that none of these packages is ever compiled into member's compilation units.
+    pub build_resolve: Option<Resolve>,
+    pub build_packages: HashMap<PackageId, Package>,
 }
 
 impl WorkspaceResolve {
@@ -45,12 +57,188 @@ impl WorkspaceResolve {
             .map(|id| self.packages[id].clone())
             .collect_vec()
     }
+
+    /// Collect all [`Package`]s made available by build dependencies of workspace `member`.
+    pub fn build_dependencies_of(&self, member: PackageId) -> Vec<Package> {
+        let Some(build_resolve) = &self.build_resolve else {
+            return Vec::new();
+        };
+        if !build_resolve.graph.contains_node(member) {
+            return Vec::new();
+        }
+        build_resolve
+            .solution_of(member, &TargetKind::LIB)
+            .iter()
+            .filter(|id| **id != member)
+            .map(|id| self.build_packages[id].clone())
+            .collect_vec()
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct ResolveOpts {
     /// Do not use lockfile when resolving.
     pub update: bool,
+    /// Update only these packages, keeping other versions locked.
+    pub update_packages: Vec<PackageName>,
+    /// Exact version to update the package from `update_packages` to.
+    pub precise: Option<Version>,
+    /// Resolve dependencies to the lowest versions matching their requirements.
+    pub minimal_versions: bool,
+    /// Let prerelease versions satisfy version requirements of all dependencies, in addition to
+    /// the `allow-prerelease` setting of the workspace.
+    pub allow_prerelease: bool,
+    /// Features of workspace members to enable, determining which optional dependencies to use.
+    pub features: FeaturesOpts,
+}
+
+/// Features requested for workspace members, typically with command line arguments.
+#[derive(Clone, Debug)]
+pub struct FeaturesOpts {
+    /// Features to enable, either in all selected members or in a concrete one.
+    pub features: Vec<RequestedFeature>,
+    /// Enable all features declared by members.
+    pub all_features: bool,
+    /// Enable the `default` feature of members.
+    pub default_features: bool,
+}
+
+/// A feature requested with `--features`, written as `<feature>` or `<package>/<feature>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestedFeature {
+    pub package: Option<PackageName>,
+    pub feature: FeatureName,
+}
+
+impl Default for FeaturesOpts {
+    fn default() -> Self {
+        Self {
+            features: Vec::new(),
+            all_features: false,
+            default_features: true,
+        }
+    }
+}
+
+impl TryFrom<FeaturesSpec> for FeaturesOpts {
+    type Error = anyhow::Error;
+
+    fn try_from(spec: FeaturesSpec) -> Result<Self> {
+        let features = spec
+            .features
+            .iter()
+            .map(|feature| feature.trim())
+            .filter(|feature| !feature.is_empty())
+            .map(RequestedFeature::from_str)
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            features,
+            all_features: spec.all_features,
+            default_features: !spec.no_default_features,
+        })
+    }
+}
+
+impl FromStr for RequestedFeature {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.split_once('/') {
+            Some((package, feature)) => Ok(Self {
+                package: Some(PackageName::try_new(package)?),
+                feature: FeatureName::try_new(feature)?,
+            }),
+            None => Ok(Self {
+                package: None,
+                feature: FeatureName::try_new(value)?,
+            }),
+        }
+    }
+}
+
+impl FeaturesOpts {
+    /// Check that requested features are declared by workspace members, so that typos do not
+    /// go unnoticed.
+    ///
+    /// Features without package name must be declared by at least one of `packages`.
+    pub fn validate(&self, packages: &[PackageId], ws: &Workspace<'_>) -> Result<()> {
+        let mut missing = Vec::new();
+        for requested in &self.features {
+            match &requested.package {
+                Some(name) => {
+                    let Some(member) = ws.members().find(|member| member.id.name == *name) else {
+                        bail!("package `{name}` not found in workspace");
+                    };
+                    ensure!(
+                        member
+                            .manifest
+                            .summary
+                            .features
+                            .contains_key(&requested.feature),
+                        "package `{}` does not have feature `{}`",
+                        member.id,
+                        requested.feature
+                    );
+                }
+                None => {
+                    let is_declared = packages.iter().any(|id| {
+                        ws.fetch_package(id).is_ok_and(|package| {
+                            package
+                                .manifest
+                                .summary
+                                .features
+                                .contains_key(&requested.feature)
+                        })
+                    });
+                    if !is_declared {
+                        missing.push(requested.feature.to_string());
+                    }
+                }
+            }
+        }
+        ensure!(
+            missing.is_empty(),
+            "none of the selected packages contains these features: {}",
+            missing.join(", ")
+        );
+        Ok(())
+    }
+
+    /// Enable features requested for workspace `member`, along with these requested for other
+    /// packages reachable from it.
+    ///
+    /// Features not declared by `member` are skipped, as they may be meant for other members.
+    pub(crate) fn enable<'a>(
+        &self,
+        resolver: &mut FeatureResolver<'a>,
+        member: &'a Summary,
+    ) -> Result<()> {
+        let features: Vec<FeatureName> = if self.all_features {
+            member.features.keys().cloned().collect()
+        } else {
+            self.features
+                .iter()
+                .filter(|requested| {
+                    requested
+                        .package
+                        .as_ref()
+                        .map_or(true, |name| *name == member.package_id.name)
+                })
+                .filter(|requested| member.features.contains_key(&requested.feature))
+                .map(|requested| requested.feature.clone())
+                .collect()
+        };
+        resolver.enable_package(member, &features, self.default_features)?;
+
+        for requested in &self.features {
+            if let Some(name) = &requested.package {
+                if *name != member.package_id.name {
+                    resolver.enable_reachable_feature(name, &requested.feature)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn resolve_workspace(ws: &Workspace<'_>) -> Result<WorkspaceResolve> {
@@ -58,6 +246,41 @@ pub fn resolve_workspace(ws: &Workspace<'_>) -> Result<WorkspaceResolve> {
     resolve_workspace_with_opts(ws, &opts)
 }
 
+/// Apply settings overridden for individual packages in the current profile to dependencies.
+///
+/// Overrides of workspace members are applied when reading their manifests.
+fn apply_profile_package_overrides(
+    packages: HashMap<PackageId, Package>,
+    ws: &Workspace<'_>,
+) -> Result<HashMap<PackageId, Package>> {
+    let overrides = TomlManifest::read_from_path(ws.manifest_path())?
+        .collect_profile_package_overrides(ws.config().profile())
+        .with_context(|| format!("failed to parse manifest at: {}", ws.manifest_path()))?;
+    for name in overrides.keys() {
+        if !packages.keys().any(|id| id.name == *name) {
+            ws.config().ui().warn(format!(
+                "profile `{}` overrides settings of package `{name}`, \
+                which is not used in this workspace",
+                ws.config().profile()
+            ));
+        }
+    }
+    packages
+        .into_iter()
+        .map(|(id, package)| {
+            let package = match overrides.get(&id.name) {
+                Some(overrides) if ws.package(&id).is_none() => {
+                    let mut manifest = package.manifest.clone();
+                    overrides.apply_to(&mut manifest)?;
+                    Package::new(id, package.manifest_path().to_path_buf(), manifest)
+                }
+                _ => package,
+            };
+            Ok((id, package))
+        })
+        .collect()
+}
+
 /// Resolves workspace dependencies and downloads missing packages.
 #[tracing::instrument(level = "debug", skip_all, fields(root = ws.root().to_string()))]
 pub fn resolve_workspace_with_opts(
@@ -66,7 +289,7 @@ pub fn resolve_workspace_with_opts(
 ) -> Result<WorkspaceResolve> {
     ws.config().tokio_handle().block_on(
         async {
-            let mut patch_map = PatchMap::new();
+            let mut patch_map = ws.patch_map().clone();
 
             let cairo_version = crate::version::get().cairo.version.parse().unwrap();
             let version_req = DependencyVersionReq::exact(&cairo_version);
@@ -110,52 +333,196 @@ pub fn resolve_workspace_with_opts(
             let lockfile: Lockfile = if opts.update {
                 Lockfile::new([])
             } else {
-                read_lockfile(ws)?
+                let mut lockfile = read_lockfile(ws)?;
+                unlock_packages(&mut lockfile, opts)?;
+                lockfile
             };
 
-            let resolve =
-                resolver::resolve(&members_summaries, &patched, lockfile, ws.config().ui()).await?;
-
-            write_lockfile(Lockfile::from_resolve(&resolve), ws)?;
+            let build_lockfile = lockfile.build_lockfile();
+            let resolve = resolver::resolve(
+                &members_summaries,
+                &patched,
+                ws.patch_map(),
+                lockfile,
+                &opts.features,
+                opts.minimal_versions,
+                opts.allow_prerelease || ws.allow_prerelease(),
+                ws.config().ui(),
+            )
+            .await?;
+
+            let build_resolve =
+                resolve_build_dependencies(ws, &patched, build_lockfile, opts).await?;
+
+            if let Some(precise) = &opts.precise {
+                let name = &opts.update_packages[0];
+                let updated = resolve
+                    .package_ids()
+                    .chain(build_resolve.iter().flat_map(Resolve::package_ids))
+                    .any(|id| id.name == *name && id.version == *precise);
+                ensure!(
+                    updated,
+                    "cannot update package `{name}` to version `{precise}`, \
+                    it does not match version requirements of its dependants"
+                );
+            }
 
-            let packages = collect_packages_from_resolve_graph(&resolve, &patched).await?;
+            let mut new_lockfile = Lockfile::from_resolve(&resolve);
+            if let Some(build_resolve) = &build_resolve {
+                new_lockfile =
+                    new_lockfile.with_build_lockfile(Lockfile::from_resolve(build_resolve));
+            }
+            write_lockfile(new_lockfile, ws)?;
+
+            let packages =
+                collect_packages_from_resolve_graph(&resolve, &patched, ws.config()).await?;
+            let packages = apply_profile_package_overrides(packages, ws)?;
+            let build_packages = match &build_resolve {
+                Some(build_resolve) => {
+                    collect_packages_from_resolve_graph(build_resolve, &patched, ws.config())
+                        .await?
+                }
+                None => HashMap::new(),
+            };
 
-            Ok(WorkspaceResolve { resolve, packages })
+            Ok(WorkspaceResolve {
+                resolve,
+                packages,
+                build_resolve,
+                build_packages,
+            })
         }
         .into_future(),
     )
 }
 
+/// Unlock packages requested to be updated, or lock them to the requested precise version.
+fn unlock_packages(lockfile: &mut Lockfile, opts: &ResolveOpts) -> Result<()> {
+    if let Some(precise) = &opts.precise {
+        ensure!(
+            opts.update_packages.len() == 1,
+            "`--precise` can only be used when updating a single package"
+        );
+        let name = &opts.update_packages[0];
+        ensure!(
+            lockfile.lock_precise(name, precise),
+            "package `{name}` not found in lockfile"
+        );
+        return Ok(());
+    }
+    for name in &opts.update_packages {
+        ensure!(
+            lockfile.unlock(name),
+            "package `{name}` not found in lockfile"
+        );
+    }
+    Ok(())
+}
+
+/// Resolve build dependencies of workspace members, independently of their normal dependencies.
+///
+/// Each member with build dependencies becomes a root of a separate graph, in which it depends on
+/// its build dependencies only. Returns `None` if no member declares build dependencies.
+async fn resolve_build_dependencies(
+    ws: &Workspace<'_>,
+    registry: &dyn Registry,
+    lockfile: Lockfile,
+    opts: &ResolveOpts,
+) -> Result<Option<Resolve>> {
+    let roots = ws
+        .members()
+        .filter(|member| !member.manifest.build_dependencies.is_empty())
+        .map(|member| {
+            Summary::builder()
+                .package_id(member.id)
+                .dependencies(member.manifest.build_dependencies.clone())
+                .target_kinds(HashSet::new())
+                .no_core(true)
+                .build()
+        })
+        .collect_vec();
+    if roots.is_empty() {
+        return Ok(None);
+    }
+
+    let resolve = resolver::resolve(
+        &roots,
+        registry,
+        ws.patch_map(),
+        lockfile,
+        &FeaturesOpts::default(),
+        opts.minimal_versions,
+        opts.allow_prerelease || ws.allow_prerelease(),
+        ws.config().ui(),
+    )
+    .await
+    .context("failed to resolve build dependencies")?;
+    Ok(Some(resolve))
+}
+
 /// Gather [`Package`] instances from this resolver result, by asking the [`RegistryCache`]
 /// to download resolved packages.
 ///
-/// Currently, it is expected that all packages are already downloaded during resolution,
-/// so the `download` calls in this method should be cheap, but this may change the future.
+/// Packages are downloaded concurrently, at most `downloads.concurrency` at a time.
 #[tracing::instrument(level = "trace", skip_all)]
 async fn collect_packages_from_resolve_graph(
     resolve: &Resolve,
     registry: &dyn Registry,
+    config: &Config,
 ) -> Result<HashMap<PackageId, Package>> {
-    let mut packages = HashMap::with_capacity(resolve.package_ids().size_hint().0);
-    // TODO(#6): Parallelize this loop.
-    for package_id in resolve.package_ids() {
-        let package = registry.download(package_id).await?;
-        packages.insert(package_id, package);
-    }
-    Ok(packages)
+    let concurrency = config.config_file().downloads.concurrency();
+    trace!("downloading packages with concurrency: {concurrency}");
+
+    let _spinner = config.ui().widget(Spinner::new("Downloading packages"));
+
+    futures::stream::iter(resolve.package_ids())
+        .map(|package_id| {
+            registry
+                .download(package_id)
+                .map_ok(move |package| (package_id, package))
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await
 }
 
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn generate_compilation_units(
     resolve: &WorkspaceResolve,
+    features: &FeaturesOpts,
     ws: &Workspace<'_>,
 ) -> Result<Vec<CompilationUnit>> {
+    let workspace_features = match ws.feature_unification() {
+        FeatureUnification::Unit => None,
+        FeatureUnification::Workspace => {
+            let mut resolver = FeatureResolver::new(resolve.packages.values().map(|package| {
+                (
+                    resolve.resolve.import_name(package.id),
+                    &package.manifest.summary,
+                )
+            }));
+            for member in ws.members() {
+                features.enable(
+                    &mut resolver,
+                    &resolve.packages[&member.id].manifest.summary,
+                )?;
+            }
+            Some(resolver.finish())
+        }
+    };
+
     let mut units = Vec::with_capacity(ws.members().size_hint().0);
     for member in ws.members() {
         units.extend(if member.is_cairo_plugin() {
             generate_cairo_plugin_compilation_units()?
         } else {
-            generate_cairo_compilation_units(&member, resolve, ws)?
+            generate_cairo_compilation_units(
+                &member,
+                resolve,
+                features,
+                workspace_features.as_ref(),
+                ws,
+            )?
         });
     }
 
@@ -164,16 +531,27 @@ pub fn generate_compilation_units(
         "All generated compilation units must have unique IDs."
     );
 
+    // Check each package once, even if it is a part of multiple compilation units.
+    let packages = units
+        .iter()
+        .flat_map(|unit| unit.components.iter().map(|component| &component.package))
+        .unique_by(|package| &package.id)
+        .cloned()
+        .collect::<Vec<_>>();
+    check_cairo_version_compatibility(&packages, ws)?;
+
     Ok(units)
 }
 
 fn generate_cairo_compilation_units(
     member: &Package,
     resolve: &WorkspaceResolve,
+    features: &FeaturesOpts,
+    workspace_features: Option<&ResolvedFeatures>,
     ws: &Workspace<'_>,
 ) -> Result<Vec<CompilationUnit>> {
     let profile = ws.current_profile()?;
-    let mut solution = PackageSolutionCollector::new(member, resolve, ws);
+    let mut solution = PackageSolutionCollector::new(member, resolve);
     member
         .manifest
         .targets
@@ -181,10 +559,16 @@ fn generate_cairo_compilation_units(
         .sorted_by_key(|target| target.kind.clone())
         .map(|member_target| {
             solution.collect(&member_target.kind)?;
-            let packages = solution.packages.as_ref().unwrap();
             let cairo_plugins = solution.cairo_plugins.as_ref().unwrap();
-
-            let cfg_set = build_cfg_set(member_target);
+            let (packages, enabled_features) = resolve_unit_features(
+                member,
+                solution.packages.as_ref().unwrap(),
+                resolve,
+                features,
+                workspace_features,
+            )?;
+            let cfg_set = build_cfg_set(member_target, enabled_features.iter());
+            check_unique_crates(member, &packages, resolve)?;
 
             let props: TestTargetProps = member_target.props()?;
             let is_integration_test = props.test_type == TestTargetType::Integration;
@@ -208,17 +592,23 @@ fn generate_cairo_compilation_units(
 
                     // For integration tests target, rewrite package with prefixed name.
                     // This allows integration test code to reference main package as dependency.
-                    let package = if package.id == member.id && is_integration_test {
-                        Package::new(
+                    let (package, crate_name) = if package.id == member.id && is_integration_test {
+                        let package = Package::new(
                             test_package_id,
                             package.manifest_path().to_path_buf(),
                             package.manifest.clone(),
-                        )
+                        );
+                        (package, test_package_id.name.to_smol_str())
                     } else {
-                        package
+                        let crate_name = resolve.resolve.import_name(package.id).to_smol_str();
+                        (package, crate_name)
                     };
 
-                    CompilationUnitComponent { package, target }
+                    CompilationUnitComponent {
+                        package,
+                        crate_name,
+                        target,
+                    }
                 })
                 .collect();
 
@@ -240,6 +630,7 @@ fn generate_cairo_compilation_units(
                 // Add `lib` target for tested package, to be available as dependency.
                 components.push(CompilationUnitComponent {
                     package: member.clone(),
+                    crate_name: resolve.resolve.import_name(member.id).to_smol_str(),
                     target,
                 });
 
@@ -261,21 +652,107 @@ fn generate_cairo_compilation_units(
         .collect::<Result<Vec<CompilationUnit>>>()
 }
 
+/// Ensure that the compilation unit of `member` contains at most one version of each package.
+///
+/// Cairo crates share a single namespace within a compilation unit, and crates are looked up by
+/// name only, so dependants of different semver-incompatible versions of a package cannot each
+/// be compiled against the version they require.
+/// Renaming some of these crates would not help, as the compiler has no way to alias a crate for
+/// only some of its dependants, and their `use` paths would no longer resolve.
+fn check_unique_crates(
+    member: &Package,
+    packages: &[Package],
+    resolve: &WorkspaceResolve,
+) -> Result<()> {
+    let by_name = packages
+        .iter()
+        .map(|package| package.id)
+        .into_group_map_by(|package_id| resolve.resolve.import_name(*package_id));
+    for (name, package_ids) in by_name.into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+        if package_ids.len() < 2 {
+            continue;
+        }
+        let versions = package_ids
+            .iter()
+            .map(|package_id| package_id.version.to_string())
+            .sorted()
+            .join(", ");
+        let dependants = packages
+            .iter()
+            .filter(|package| {
+                resolve
+                    .resolve
+                    .package_dependencies(package.id)
+                    .any(|dep| package_ids.contains(&dep))
+            })
+            .map(|package| package.id.to_string())
+            .sorted()
+            .join(", ");
+        bail!(
+            "compilation unit of `{}` depends on semver-incompatible versions of `{name}`: \
+            {versions}\n\
+            help: Cairo crates of a compilation unit share a single namespace, \
+            make these packages depend on compatible versions of `{name}`: {dependants}",
+            member.id.name
+        );
+    }
+    Ok(())
+}
+
+/// Compute features enabled in the compilation unit of `member`, made of `packages`.
+///
+/// Unless features are unified across the workspace, they are computed from `member` alone,
+/// and optional dependencies enabled only by other workspace members are left out of the unit.
+fn resolve_unit_features(
+    member: &Package,
+    packages: &[Package],
+    resolve: &WorkspaceResolve,
+    features: &FeaturesOpts,
+    workspace_features: Option<&ResolvedFeatures>,
+) -> Result<(Vec<Package>, BTreeSet<FeatureName>)> {
+    let collect_enabled = |resolved: &ResolvedFeatures, packages: &[Package]| -> BTreeSet<_> {
+        packages
+            .iter()
+            .filter_map(|package| resolved.features.get(&package.id))
+            .flatten()
+            .cloned()
+            .collect()
+    };
+
+    if let Some(resolved) = workspace_features {
+        return Ok((packages.to_vec(), collect_enabled(resolved, packages)));
+    }
+
+    let mut resolver = FeatureResolver::new(packages.iter().map(|package| {
+        (
+            resolve.resolve.import_name(package.id),
+            &package.manifest.summary,
+        )
+    }));
+    features.enable(&mut resolver, &member.manifest.summary)?;
+    let resolved = resolver.finish();
+    let packages = packages
+        .iter()
+        .filter(|package| package.id.is_core() || resolved.features.contains_key(&package.id))
+        .cloned()
+        .collect_vec();
+    let enabled = collect_enabled(&resolved, &packages);
+    Ok((packages, enabled))
+}
+
 pub struct PackageSolutionCollector<'a> {
     member: &'a Package,
     resolve: &'a WorkspaceResolve,
-    ws: &'a Workspace<'a>,
     packages: Option<Vec<Package>>,
     cairo_plugins: Option<Vec<CompilationUnitCairoPlugin>>,
     target_kind: Option<TargetKind>,
 }
 
 impl<'a> PackageSolutionCollector<'a> {
-    pub fn new(member: &'a Package, resolve: &'a WorkspaceResolve, ws: &'a Workspace<'a>) -> Self {
+    pub fn new(member: &'a Package, resolve: &'a WorkspaceResolve) -> Self {
         Self {
             member,
             resolve,
-            ws,
             packages: None,
             cairo_plugins: None,
             target_kind: None,
@@ -337,8 +814,6 @@ impl<'a> PackageSolutionCollector<'a> {
         assert!(!packages.is_empty());
         assert_eq!(packages[0].id, self.member.id);
 
-        check_cairo_version_compatibility(&packages, self.ws)?;
-
         let cairo_plugins = cairo_plugins
             .into_iter()
             .map(|package| CompilationUnitCairoPlugin { package })
@@ -349,30 +824,60 @@ impl<'a> PackageSolutionCollector<'a> {
 }
 
 /// Build a set of `cfg` items to enable while building the compilation unit.
-fn build_cfg_set(target: &Target) -> CfgSet {
+///
+/// The Cairo compiler evaluates `cfg` attributes against a single set for the whole unit,
+/// so features enabled in any of its packages are visible to all of them.
+fn build_cfg_set<'a>(target: &Target, features: impl Iterator<Item = &'a FeatureName>) -> CfgSet {
     let mut cfg = CfgSet::from_iter([Cfg::kv("target", target.kind.clone())]);
     if target.is_test() {
         cfg.insert(Cfg::name("test"));
     }
+    for feature in features {
+        cfg.insert(Cfg::kv("feature", feature.clone()));
+    }
     cfg
 }
 
 fn check_cairo_version_compatibility(packages: &[Package], ws: &Workspace<'_>) -> Result<()> {
     let current_version = crate::version::get().cairo.version.to_version().unwrap();
-    let matching_version = packages.iter().all(|pkg| {
-        match &pkg.manifest.metadata.cairo_version {
-            Some(package_version) if !package_version.matches(&current_version) => {
-                ws.config().ui().error(format!(
-                    "Package {}. Required Cairo version isn't compatible with current version. Should be: {} is: {}",
-                    pkg.id.name, package_version, current_version
-                ));
-                false
+    let current_scarb_version = crate::version::get().version.to_version().unwrap();
+    let mut matching_version = true;
+    for pkg in packages {
+        let Some(package_version) = &pkg.manifest.metadata.cairo_version else {
+            continue;
+        };
+        if package_version.matches(&current_version) {
+            continue;
+        }
+        let mut message = format!(
+            "Package {}. Required Cairo version isn't compatible with current version. Should be: {} is: {}",
+            pkg.id.name, package_version, current_version
+        );
+        if let Some((scarb, cairo)) = crate::version::scarb_release_for_cairo(package_version) {
+            if scarb > current_scarb_version {
+                write!(message, "\nhelp: upgrade Scarb to {scarb} or newer").unwrap();
+            } else {
+                write!(
+                    message,
+                    "\nhelp: Cairo {cairo} is bundled with Scarb {scarb}"
+                )
+                .unwrap();
             }
-            _ => true
         }
-    });
+        if ws.config().ignores_cairo_version(&pkg.id.name) {
+            ws.config().ui().warn(format!(
+                "{message}\nnote: continuing, because `--ignore-cairo-version` is set"
+            ));
+        } else {
+            ws.config().ui().error(message);
+            matching_version = false;
+        }
+    }
     if !matching_version {
-        bail!("For each package, the required Cairo version must match the current Cairo version.");
+        bail!(
+            "For each package, the required Cairo version must match the current Cairo version.\n\
+            help: use `--ignore-cairo-version` to compile these packages anyway"
+        );
     }
     Ok(())
 }
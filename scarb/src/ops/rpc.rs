@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use scarb_metadata as m;
+use scarb_ui::args::FeaturesSpec;
+
+use crate::core::{Config, Workspace};
+use crate::ops;
+
+/// Version of the JSON-RPC interface served by `scarb rpc`, bumped on incompatible changes.
+pub const RPC_INTERFACE_VERSION: u64 = 1;
+
+// Error codes defined by the JSON-RPC 2.0 specification.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Error code of failed Scarb operations, like reading or resolving the workspace.
+const SCARB_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Request {
+    jsonrpc: String,
+    /// Requests without an ID are notifications, which are not responded to.
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::new(SCARB_ERROR, format!("{err:#}"))
+    }
+}
+
+/// Features to enable when resolving the workspace, like `--features` arguments.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+struct FeaturesParams {
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    all_features: bool,
+    #[serde(default)]
+    no_default_features: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+struct MetadataParams {
+    #[serde(default)]
+    no_deps: bool,
+    #[serde(flatten)]
+    features: FeaturesParams,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompilationUnitsParams {
+    /// Only return compilation units of the workspace member with this name.
+    package: Option<String>,
+    #[serde(flatten)]
+    features: FeaturesParams,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigParams {
+    /// Return only the value of this key, instead of all values.
+    key: Option<String>,
+}
+
+/// State of the server, caching the workspace and its metadata between requests.
+struct RpcServer<'c> {
+    config: &'c Config,
+    workspace: Option<Workspace<'c>>,
+    metadata: HashMap<MetadataParams, m::Metadata>,
+    shutdown: bool,
+}
+
+/// Serve JSON-RPC 2.0 requests read from `input`, until it is closed or shutdown is requested.
+///
+/// Each request and response is a single line of JSON.
+/// The workspace is read and resolved once and then reused by subsequent requests, until the
+/// `reload` method is called.
+pub fn serve_rpc(input: impl BufRead, mut output: impl Write, config: &Config) -> Result<()> {
+    let mut server = RpcServer {
+        config,
+        workspace: None,
+        metadata: HashMap::new(),
+        shutdown: false,
+    };
+    for line in input.lines() {
+        let line = line.context("failed to read JSON-RPC request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle(&line) {
+            serde_json::to_writer(&mut output, &response)?;
+            output.write_all(b"\n")?;
+            output.flush()?;
+        }
+        if server.shutdown {
+            break;
+        }
+    }
+    Ok(())
+}
+
+impl<'c> RpcServer<'c> {
+    fn handle(&mut self, line: &str) -> Option<Response> {
+        let value = match serde_json::from_str::<Value>(line) {
+            Ok(value) => value,
+            Err(err) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, err.to_string()),
+                ))
+            }
+        };
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        let request = match serde_json::from_value::<Request>(value) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                return Some(error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, "only JSON-RPC 2.0 is supported"),
+                ))
+            }
+            Err(err) => {
+                return Some(error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, err.to_string()),
+                ))
+            }
+        };
+
+        let result = self.dispatch(&request.method, request.params);
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => Response {
+                jsonrpc: "2.0",
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn dispatch(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "version" => Ok(self.version()),
+            "metadata" => {
+                let params: MetadataParams = parse_params(params)?;
+                Ok(serde_json::to_value(self.metadata(params)?).map_err(anyhow::Error::from)?)
+            }
+            "compilationUnits" => self.compilation_units(parse_params(params)?),
+            "config" => self.config_values(parse_params(params)?),
+            "reload" => {
+                self.workspace = None;
+                self.metadata.clear();
+                Ok(Value::Null)
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    fn version(&self) -> Value {
+        let version = crate::version::get();
+        json!({
+            "interfaceVersion": RPC_INTERFACE_VERSION,
+            "scarb": version.version,
+            "cairo": version.cairo.version,
+        })
+    }
+
+    fn workspace(&mut self) -> Result<&Workspace<'c>> {
+        if self.workspace.is_none() {
+            self.workspace = Some(ops::read_workspace(
+                self.config.manifest_path(),
+                self.config,
+            )?);
+        }
+        Ok(self
+            .workspace
+            .as_ref()
+            .expect("workspace has just been read"))
+    }
+
+    fn metadata(&mut self, params: MetadataParams) -> Result<&m::Metadata> {
+        if !self.metadata.contains_key(&params) {
+            let features = params.features.clone();
+            let opts = ops::MetadataOptions {
+                version: m::VersionPin.numeric(),
+                no_deps: params.no_deps,
+                features: FeaturesSpec {
+                    features: features.features,
+                    all_features: features.all_features,
+                    no_default_features: features.no_default_features,
+                }
+                .try_into()?,
+            };
+            let metadata = ops::collect_metadata(&opts, self.workspace()?)?;
+            self.metadata.insert(params.clone(), metadata);
+        }
+        Ok(&self.metadata[&params])
+    }
+
+    fn compilation_units(&mut self, params: CompilationUnitsParams) -> Result<Value, RpcError> {
+        let metadata = self.metadata(MetadataParams {
+            no_deps: false,
+            features: params.features,
+        })?;
+        let units = metadata
+            .compilation_units
+            .iter()
+            .filter(|unit| match &params.package {
+                Some(name) => metadata
+                    .packages
+                    .iter()
+                    .any(|package| package.id == unit.package && &package.name == name),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_value(units).map_err(anyhow::Error::from)?)
+    }
+
+    fn config_values(&mut self, params: ConfigParams) -> Result<Value, RpcError> {
+        let config = self.config;
+        let target_dir = self.workspace()?.target_dir().path_unchecked().to_string();
+        let values = json!({
+            "manifestPath": config.manifest_path(),
+            "targetDir": target_dir,
+            "profile": config.profile().to_string(),
+            "offline": !config.network_allowed(),
+            "cacheDir": config.dirs().cache_dir.path_unchecked(),
+            "configDir": config.dirs().config_dir.path_unchecked(),
+        });
+        match params.key {
+            Some(key) => values
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("unknown config key: {key}"))),
+            None => Ok(values),
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Methods with only optional parameters can be called without any.
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn error_response(id: Value, error: RpcError) -> Response {
+    Response {
+        jsonrpc: "2.0",
+        id,
+        result: None,
+        error: Some(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{parse_params, ConfigParams, MetadataParams, INVALID_PARAMS};
+
+    #[test]
+    fn params_default_to_empty_object() {
+        let params: MetadataParams = parse_params(Value::Null).unwrap();
+        assert_eq!(params, MetadataParams::default());
+    }
+
+    #[test]
+    fn metadata_params_are_camel_case() {
+        let params: MetadataParams = parse_params(json!({
+            "noDeps": true,
+            "features": ["x"],
+            "noDefaultFeatures": true,
+        }))
+        .unwrap();
+        assert!(params.no_deps);
+        assert_eq!(params.features.features, vec!["x".to_string()]);
+        assert!(params.features.no_default_features);
+        assert!(!params.features.all_features);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let err = parse_params::<ConfigParams>(json!({"keys": []})).unwrap_err();
+        assert_eq!(err.code, INVALID_PARAMS);
+    }
+}
@@ -1,11 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::ffi::OsString;
 use std::rc::Rc;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use camino::Utf8Path;
 use deno_task_shell::{parser, ExecutableCommand, ShellCommand};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use itertools::Itertools;
+use smol_str::SmolStr;
+
+use scarb_ui::components::Status;
 
 use crate::core::errors::ScriptExecutionError;
 use crate::core::manifest::ScriptDefinition;
@@ -19,10 +25,126 @@ pub fn execute_script(
     ws: &Workspace<'_>,
     cwd: &Utf8Path,
     custom_env: Option<HashMap<OsString, OsString>>,
+) -> Result<()> {
+    ws.config().tokio_handle().block_on(execute_script_async(
+        script_definition,
+        args,
+        ws,
+        cwd,
+        custom_env,
+    ))
+}
+
+/// Execute user defined script named `name`, after running all scripts it needs.
+///
+/// Scripts are looked up by name with `lookup`.
+/// Each needed script is run once, without `args`, as soon as all scripts it needs itself have
+/// finished, so that independent scripts run concurrently.
+/// If any script fails, no more scripts are started, and the error is returned once scripts
+/// which are already running finish.
+pub fn execute_script_with_needs<'a>(
+    name: &SmolStr,
+    args: &[OsString],
+    lookup: impl Fn(&str) -> Option<&'a ScriptDefinition>,
+    ws: &Workspace<'_>,
+    cwd: &Utf8Path,
+    custom_env: Option<HashMap<OsString, OsString>>,
+) -> Result<()> {
+    let mut pending = Vec::new();
+    collect_needed_scripts(name, &lookup, &mut Vec::new(), &mut pending)?;
+
+    ws.config().tokio_handle().block_on(async {
+        let mut done = HashSet::new();
+        let mut running = FuturesUnordered::new();
+        loop {
+            let (ready, rest): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|(_, definition)| {
+                    definition.needs().iter().all(|need| done.contains(need))
+                });
+            pending = rest;
+            for (script_name, definition) in ready {
+                let is_needed = script_name != *name;
+                if is_needed {
+                    ws.config().ui().print(Status::new(
+                        "Running",
+                        &format!("{script_name} ({definition})"),
+                    ));
+                }
+                let args = if is_needed { &[] } else { args };
+                let custom_env = custom_env.clone();
+                running.push(async move {
+                    let result = execute_script_async(definition, args, ws, cwd, custom_env).await;
+                    let result = if is_needed {
+                        result.with_context(|| {
+                            format!("script `{script_name}` needed by `{name}` failed")
+                        })
+                    } else {
+                        result
+                    };
+                    (script_name, result)
+                });
+            }
+
+            let Some((script_name, result)) = running.next().await else {
+                break;
+            };
+            if let Err(err) = result {
+                while running.next().await.is_some() {}
+                return Err(err);
+            }
+            done.insert(script_name);
+        }
+        Ok(())
+    })
+}
+
+/// Collect script `name` along with all scripts it needs, transitively, each one only once and
+/// after all scripts it needs.
+///
+/// The `chain` holds scripts needing `name`, used to detect dependency cycles.
+fn collect_needed_scripts<'a>(
+    name: &SmolStr,
+    lookup: &impl Fn(&str) -> Option<&'a ScriptDefinition>,
+    chain: &mut Vec<SmolStr>,
+    collected: &mut Vec<(SmolStr, &'a ScriptDefinition)>,
+) -> Result<()> {
+    if collected.iter().any(|(collected, _)| collected == name) {
+        return Ok(());
+    }
+    if let Some(start) = chain.iter().position(|script| script == name) {
+        let cycle = chain[start..]
+            .iter()
+            .chain([name])
+            .map(|script| format!("`{script}`"))
+            .join(" -> ");
+        bail!("script dependency cycle detected: {cycle}");
+    }
+    let definition = lookup(name).ok_or_else(|| match chain.last() {
+        Some(parent) => anyhow!("script `{parent}` needs `{name}`, which is not defined"),
+        None => anyhow!("missing script `{name}`"),
+    })?;
+    chain.push(name.clone());
+    for need in definition.needs() {
+        collect_needed_scripts(need, lookup, chain, collected)?;
+    }
+    chain.pop();
+    collected.push((name.clone(), definition));
+    Ok(())
+}
+
+async fn execute_script_async(
+    script_definition: &ScriptDefinition,
+    args: &[OsString],
+    ws: &Workspace<'_>,
+    cwd: &Utf8Path,
+    custom_env: Option<HashMap<OsString, OsString>>,
 ) -> Result<()> {
     let target_dir = Some(ws.target_dir().path_unchecked().to_owned());
-    let mut env_vars: HashMap<String, String> = get_env_vars(ws.config(), target_dir)?
+    let mut env_vars: HashMap<String, String> = ws
+        .env()
+        .resolve()
         .into_iter()
+        .chain(get_env_vars(ws.config(), target_dir)?)
         .map(|(k, v)| {
             (
                 k.to_string_lossy().to_string(),
@@ -36,6 +158,17 @@ pub fn execute_script(
             )
         }))
         .collect();
+    if let Some((program, shell_args)) = script_definition.shell().program() {
+        return execute_in_system_shell(
+            program,
+            shell_args,
+            &script_definition.command_line(args)?,
+            env_vars,
+            cwd,
+        )
+        .await;
+    }
+
     let custom_commands = HashMap::from([
         // Used to ensure deno_task_shell scripts use the current scarb executable.
         (
@@ -62,13 +195,8 @@ pub fn execute_script(
         );
     }
 
-    let runtime = ws.config().tokio_handle();
-    let exit_code = runtime.block_on(deno_task_shell::execute(
-        list,
-        env_vars,
-        (&cwd).as_ref(),
-        custom_commands,
-    ));
+    let exit_code =
+        deno_task_shell::execute(list, env_vars, (&cwd).as_ref(), custom_commands).await;
 
     if exit_code != 0 {
         Err(ScriptExecutionError::new(exit_code).into())
@@ -77,6 +205,29 @@ pub fn execute_script(
     }
 }
 
+/// Run `command_line` with a shell installed in the system, like `bash` or `powershell`.
+async fn execute_in_system_shell(
+    program: &str,
+    shell_args: &[&str],
+    command_line: &str,
+    env_vars: HashMap<String, String>,
+    cwd: &Utf8Path,
+) -> Result<()> {
+    let status = tokio::process::Command::new(program)
+        .args(shell_args)
+        .arg(command_line)
+        .envs(env_vars)
+        .current_dir(cwd)
+        .status()
+        .await
+        .with_context(|| format!("failed to run script with `{program}` shell"))?;
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(exit_code) => Err(ScriptExecutionError::new(exit_code).into()),
+        None => bail!("script run with `{program}` shell was terminated by a signal"),
+    }
+}
+
 fn has_pipe(seq: &parser::Sequence) -> bool {
     match seq {
         parser::Sequence::ShellVar(_) => false,
@@ -92,3 +243,62 @@ fn has_pipe(seq: &parser::Sequence) -> bool {
         parser::Sequence::BooleanList(list) => has_pipe(&list.current) || has_pipe(&list.next),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use smol_str::SmolStr;
+
+    use super::collect_needed_scripts;
+    use crate::core::manifest::ScriptDefinition;
+
+    fn collect(scripts: &str, name: &str) -> anyhow::Result<Vec<SmolStr>> {
+        let scripts: BTreeMap<SmolStr, ScriptDefinition> = toml::from_str(scripts).unwrap();
+        let mut collected = Vec::new();
+        collect_needed_scripts(
+            &name.into(),
+            &|name: &str| scripts.get(name),
+            &mut Vec::new(),
+            &mut collected,
+        )?;
+        Ok(collected.into_iter().map(|(name, _)| name).collect())
+    }
+
+    #[test]
+    fn needed_scripts_are_collected_once_in_order() {
+        let scripts = r#"
+            build = "scarb build"
+            abi = { script = "echo abi", needs = ["build"] }
+            deploy = { script = "echo deploy", needs = ["build", "abi"] }
+        "#;
+        assert_eq!(
+            collect(scripts, "deploy").unwrap(),
+            vec!["build", "abi", "deploy"]
+        );
+    }
+
+    #[test]
+    fn dependency_cycle() {
+        let scripts = r#"
+            a = { script = "echo a", needs = ["b"] }
+            b = { script = "echo b", needs = ["c"] }
+            c = { script = "echo c", needs = ["b"] }
+        "#;
+        assert_eq!(
+            collect(scripts, "a").unwrap_err().to_string(),
+            "script dependency cycle detected: `b` -> `c` -> `b`"
+        );
+    }
+
+    #[test]
+    fn missing_needed_script() {
+        let scripts = r#"
+            deploy = { script = "echo deploy", needs = ["build"] }
+        "#;
+        assert_eq!(
+            collect(scripts, "deploy").unwrap_err().to_string(),
+            "script `deploy` needs `build`, which is not defined"
+        );
+    }
+}
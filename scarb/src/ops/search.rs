@@ -0,0 +1,24 @@
+use anyhow::{Context, Result};
+use url::Url;
+
+use crate::core::{Config, SearchResults, SourceId};
+use crate::sources::RegistrySource;
+
+pub struct SearchOpts {
+    pub index_url: Url,
+    pub query: String,
+    /// Maximum number of packages to return.
+    pub limit: usize,
+}
+
+/// Find packages in the registry matching the search query.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn search(opts: SearchOpts, config: &Config) -> Result<SearchResults> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let registry_client = RegistrySource::create_client(source_id, config)?;
+
+    config
+        .tokio_handle()
+        .block_on(registry_client.search(&opts.query, opts.limit))
+        .with_context(|| format!("failed to search registry: {source_id}"))
+}
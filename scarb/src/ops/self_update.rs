@@ -0,0 +1,115 @@
+use std::env;
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+use semver::Version;
+
+use scarb_ui::components::Status;
+
+use crate::core::Config;
+use crate::internal::fsx;
+use crate::ops::toolchain::{download_release, Release};
+
+pub struct SelfUpdateOpts {
+    /// Install this version instead of the newest one in the configured release channel.
+    pub version: Option<Version>,
+}
+
+/// Replace the running Scarb with another release, by default the newest one in the release
+/// channel configured in the `[self-update]` config table.
+///
+/// Executables of the release which are installed next to the running Scarb, like bundled
+/// extensions, are replaced as well.
+/// Each executable is replaced with a single rename, so that an interrupted update never
+/// leaves a partially written file behind.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn self_update(opts: &SelfUpdateOpts, config: &Config) -> Result<()> {
+    let current = current_release_tag();
+    if let Some(version) = &opts.version {
+        if Release::stable(version).tag == current {
+            config
+                .ui()
+                .print(format!("Scarb {current} is already installed"));
+            return Ok(());
+        }
+    }
+    ensure!(
+        config.network_allowed(),
+        "cannot update Scarb in offline mode"
+    );
+
+    let exe = env::current_exe().context("failed to determine path of current executable")?;
+    let bin_dir = exe
+        .parent()
+        .expect("executable path must have a parent directory")
+        .to_path_buf();
+
+    config.tokio_handle().block_on(async {
+        let release = match &opts.version {
+            Some(version) => Release::stable(version),
+            None => Release::latest(config.config_file().self_update.channel(), config).await?,
+        };
+        if release.tag == current {
+            config
+                .ui()
+                .print(format!("Scarb is up to date ({current})"));
+            return Ok(());
+        }
+
+        let _lock = config.package_cache_lock().acquire_async().await?;
+        let staging = config.dirs().cache_dir.child("self-update");
+        let staging_dir = staging.path_existent()?;
+        let unpacked = download_release(&release, staging_dir, config).await?;
+
+        config.ui().print(Status::new(
+            "Updating",
+            &format!("Scarb {current} -> {}", release.tag),
+        ));
+        let new_bin_dir = unpacked.join("bin");
+        for entry in fsx::read_dir(&new_bin_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let installed = bin_dir.join(&name);
+            if installed == exe || installed.exists() {
+                replace_executable(&entry.path(), &installed)?;
+            }
+        }
+        fsx::remove_dir_all(staging_dir)?;
+        Ok(())
+    })
+}
+
+/// Tag of the release the running Scarb has been built from.
+fn current_release_tag() -> String {
+    let version = Version::parse(crate::version::get().version)
+        .expect("Scarb version must be a valid semver version");
+    match version.build.as_str() {
+        build if build.starts_with("nightly-") => build.to_string(),
+        _ => format!("v{version}"),
+    }
+}
+
+/// Atomically replace the executable at `installed` with the one at `new`.
+///
+/// The new executable is first copied next to the installed one, so that the final rename
+/// happens within a single filesystem.
+fn replace_executable(new: &Path, installed: &Path) -> Result<()> {
+    let name = installed
+        .file_name()
+        .expect("executable path must have a file name")
+        .to_string_lossy();
+    let staged = installed.with_file_name(format!(".{name}.new"));
+    fsx::copy(new, &staged)?;
+
+    // Windows does not allow overwriting a running executable, but does allow renaming it.
+    if cfg!(windows) && installed.exists() {
+        let old = installed.with_file_name(format!(".{name}.old"));
+        if old.exists() {
+            fsx::remove_file(&old)?;
+        }
+        fsx::rename(installed, &old)?;
+    }
+
+    fsx::rename(&staged, installed)
+        .with_context(|| format!("failed to replace executable: {}", installed.display()))
+}
@@ -1,22 +1,29 @@
 use std::collections::HashMap;
-use std::env;
 use std::ffi::OsString;
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use std::{env, iter, thread};
 
-use anyhow::{bail, Result};
-use camino::Utf8PathBuf;
+use anyhow::{bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::Version;
 use tracing::debug;
 
+use scarb_ui::args::FeaturesSpec;
 use scarb_ui::components::Status;
 
-use crate::core::{Config, Package, ScriptDefinition, Workspace};
+use crate::core::{Config, Package, ScriptDefinition, TomlManifest, Workspace};
 use crate::ops;
 use crate::process::{exec_replace, is_executable};
 use crate::subcommands::{get_env_vars, EXTERNAL_CMD_PREFIX, SCARB_MANIFEST_PATH_ENV};
 
 /// Prepare environment and execute an external subcommand.
 ///
+/// Executables in `tools_dir`, holding tools pinned by the workspace, take precedence over
+/// other installed subcommands.
+///
 /// NOTE: This may replace the current process.
 #[tracing::instrument(level = "debug", skip(config))]
 pub fn execute_external_subcommand(
@@ -25,8 +32,9 @@ pub fn execute_external_subcommand(
     custom_env: Option<HashMap<OsString, OsString>>,
     config: &Config,
     target_dir: Option<Utf8PathBuf>,
+    tools_dir: Option<Utf8PathBuf>,
 ) -> Result<()> {
-    let Some(cmd) = find_external_subcommand(cmd, config) else {
+    let Some(cmd) = find_external_subcommand(cmd, config, tools_dir.as_deref()) else {
         // TODO(mkaput): Reuse clap's no such command message logic here.
         bail!("no such command: `{cmd}`");
     };
@@ -36,6 +44,12 @@ pub fn execute_external_subcommand(
     let mut cmd = Command::new(cmd);
     cmd.args(args);
     cmd.envs(get_env_vars(config, target_dir)?);
+    if let Some(tools_dir) = tools_dir {
+        // Let subcommands call pinned tools directly, too.
+        let path_dirs = iter::once(tools_dir.into_std_path_buf())
+            .chain(config.dirs().path_dirs.iter().cloned());
+        cmd.env("PATH", env::join_paths(path_dirs)?);
+    }
     if let Some(env) = custom_env {
         cmd.envs(env);
     }
@@ -47,13 +61,22 @@ pub fn execute_external_subcommand(
 pub fn execute_test_subcommand(
     package: &Package,
     args: &[OsString],
+    features: &FeaturesSpec,
     ws: &Workspace<'_>,
 ) -> Result<()> {
     let package_name = &package.id.name;
-    let env = Some(HashMap::from_iter([(
+    let mut env = HashMap::from_iter([(
         SCARB_MANIFEST_PATH_ENV.into(),
         package.manifest_path().into(),
-    )]));
+    )]);
+    // Pass requested features to Scarb invocations made by the test runner.
+    env.extend(
+        features
+            .to_env()
+            .into_iter()
+            .map(|(key, value)| (key.into(), value)),
+    );
+    let env = Some(env);
     if let Some(script_definition) = package.manifest.scripts.get("test") {
         debug!("using `test` script: {script_definition}");
         ws.config().ui().print(Status::new(
@@ -73,9 +96,141 @@ pub fn execute_test_subcommand(
     }
 }
 
-fn find_external_subcommand(cmd: &str, config: &Config) -> Option<PathBuf> {
+/// Verify that the installed external subcommand satisfies the version requirement declared for it
+/// in the `[required-tools]` table of the workspace manifest.
+///
+/// The version is read from the output of `scarb-<cmd> --version`, as the first word which is
+/// a valid semantic version, optionally prefixed with `v`.
+#[tracing::instrument(level = "debug", skip(ws))]
+pub fn check_required_tool_version(
+    cmd: &str,
+    ws: &Workspace<'_>,
+    tools_dir: Option<&Utf8Path>,
+) -> Result<()> {
+    let manifest = TomlManifest::read_from_path(ws.manifest_path())?;
+    let Some(req) = manifest
+        .required_tools
+        .as_ref()
+        .and_then(|tools| tools.get(cmd))
+    else {
+        return Ok(());
+    };
+
+    let help = format!(
+        "help: this workspace requires `scarb-{cmd}` in version matching `{req}`, \
+        as declared in the `[required-tools]` table of: {}",
+        ws.manifest_path()
+    );
+    let Some(path) = find_external_subcommand(cmd, ws.config(), tools_dir) else {
+        bail!("no such command: `{cmd}`\n{help}");
+    };
+    let output = Command::new(&path)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("failed to run `{}`", path.display()))?;
+    let Some(version) = parse_tool_version(&String::from_utf8_lossy(&output.stdout)) else {
+        bail!("could not determine version of `scarb-{cmd}`\n{help}");
+    };
+    debug!("found `scarb-{cmd}` in version {version}");
+    ensure!(
+        req.matches(&version),
+        "`scarb-{cmd}` version {version} is installed, which does not match `{req}`\n{help}"
+    );
+    Ok(())
+}
+
+/// Version and short description of an external subcommand, as reported by the subcommand itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExternalSubcommandInfo {
+    pub version: Option<Version>,
+    pub description: Option<String>,
+}
+
+/// Time given to an external subcommand to answer a single query about itself.
+const DESCRIBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Ask an external subcommand executable for its version and short description.
+///
+/// The version is read from `--version` output, the same way as for `[required-tools]` checks.
+/// The description is the first line of `--help` output, unless it is the usage line.
+/// Queries which fail, or are not answered within [`DESCRIBE_TIMEOUT`], are ignored.
+pub fn describe_external_subcommand(path: &Path) -> ExternalSubcommandInfo {
+    let version = query_subcommand(path, "--version")
+        .as_deref()
+        .and_then(parse_tool_version);
+    let description = query_subcommand(path, "--help")
+        .as_deref()
+        .and_then(parse_tool_description);
+    ExternalSubcommandInfo {
+        version,
+        description,
+    }
+}
+
+/// Run `path` with a single argument and return its standard output, if it succeeds in time.
+fn query_subcommand(path: &Path, arg: &str) -> Option<String> {
+    let mut child = match Command::new(path)
+        .arg(arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            debug!("failed to run `{}`: {err}", path.display());
+            return None;
+        }
+    };
+    // Read output in the background, so that the child does not block on a full pipe.
+    let mut stdout = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).ok().map(|_| output)
+    });
+
+    let deadline = Instant::now() + DESCRIBE_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                debug!("`{} {arg}` did not finish in time", path.display());
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    let output = reader.join().ok()??;
+    status.success().then_some(output)
+}
+
+fn parse_tool_description(output: &str) -> Option<String> {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    (!line.to_lowercase().starts_with("usage")).then(|| line.to_string())
+}
+
+fn parse_tool_version(output: &str) -> Option<Version> {
+    output
+        .split_whitespace()
+        .find_map(|word| Version::parse(word.strip_prefix('v').unwrap_or(word)).ok())
+}
+
+fn find_external_subcommand(
+    cmd: &str,
+    config: &Config,
+    tools_dir: Option<&Utf8Path>,
+) -> Option<PathBuf> {
     let command_exe = format!("{}{}{}", EXTERNAL_CMD_PREFIX, cmd, env::consts::EXE_SUFFIX);
-    let mut dirs = config.dirs().path_dirs.clone();
+    let mut dirs = tools_dir
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .into_iter()
+        .chain(config.dirs().path_dirs.iter().cloned())
+        .collect::<Vec<_>>();
 
     // Add directory containing the Scarb executable.
     if let Ok(path) = config.app_exe() {
@@ -89,3 +244,34 @@ fn find_external_subcommand(cmd: &str, config: &Config) -> Option<PathBuf> {
         .map(|dir| dir.join(&command_exe))
         .find(|file| is_executable(file))
 }
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+    use test_case::test_case;
+
+    use super::{parse_tool_description, parse_tool_version};
+
+    #[test_case("scarb-cairo-test 2.3.1\n", Some("2.3.1"))]
+    #[test_case("snforge v0.10.0-rc.1 (abc123)\n", Some("0.10.0-rc.1"))]
+    #[test_case("tool version 1.2\n", None)]
+    #[test_case("", None)]
+    fn tool_version(output: &str, expected: Option<&str>) {
+        assert_eq!(
+            parse_tool_version(output),
+            expected.map(|version| Version::parse(version).unwrap())
+        );
+    }
+
+    #[test_case(
+        "Format Cairo code\n\nUsage: scarb-fmt [OPTIONS]\n",
+        Some("Format Cairo code")
+    )]
+    #[test_case("\n  Runs tests  \n", Some("Runs tests"))]
+    #[test_case("Usage: scarb-hello [ARGS]\n", None)]
+    #[test_case("usage: scarb-hello\n", None)]
+    #[test_case("", None)]
+    fn tool_description(output: &str, expected: Option<&str>) {
+        assert_eq!(parse_tool_description(output).as_deref(), expected);
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::io;
+use std::io::IsTerminal;
+use std::process::Command;
+
+use anyhow::{bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use serde::Deserialize;
+use url::Url;
+use walkdir::WalkDir;
+
+use crate::core::{Config, GitReference, PackageName};
+use crate::internal::fsx;
+use crate::sources::canonical_url::CanonicalUrl;
+use crate::sources::git::{self, GitRemote};
+use crate::{DEFAULT_TARGET_DIR_NAME, MANIFEST_FILE_NAME};
+
+/// Name of the optional file in the root of a template, declaring its placeholders.
+///
+/// The file itself is not copied into generated projects.
+pub const TEMPLATE_MANIFEST_FILE_NAME: &str = "scarb-template.toml";
+
+/// Placeholders always available in templates.
+const BUILTIN_PLACEHOLDERS: [&str; 2] = ["name", "author"];
+
+/// Location of a template.
+#[derive(Clone, Debug)]
+pub enum TemplateSource {
+    /// URL of a Git repository, holding the template in its default branch.
+    Git(Url),
+    /// Local directory holding the template.
+    Path(Utf8PathBuf),
+}
+
+impl fmt::Display for TemplateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Git(url) => write!(f, "{url}"),
+            Self::Path(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TemplateOpts {
+    pub source: TemplateSource,
+    /// Values of placeholders, set upfront instead of being prompted for.
+    pub defines: BTreeMap<String, String>,
+}
+
+/// Contents of [`TEMPLATE_MANIFEST_FILE_NAME`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateManifest {
+    #[serde(default)]
+    placeholders: BTreeMap<String, TemplatePlaceholder>,
+    /// Destination paths of template files or directories, relative to the project root.
+    ///
+    /// Both keys and values use `/` as path separator, values may contain placeholders.
+    #[serde(default)]
+    renames: BTreeMap<String, String>,
+}
+
+/// A value which is asked for when the template is used.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplatePlaceholder {
+    /// Question shown to the user, defaults to the placeholder name.
+    prompt: Option<String>,
+    /// Value used when the user does not provide one, or cannot be asked.
+    default: Option<String>,
+}
+
+/// Copy files of a template into `path`, replacing `{{ placeholder }}` occurrences in file
+/// contents and paths.
+///
+/// Git templates are fetched into the Git cache of Scarb, so that they can be reused in offline
+/// mode.
+pub(crate) fn generate_from_template(
+    opts: &TemplateOpts,
+    path: &Utf8Path,
+    name: &PackageName,
+    config: &Config,
+) -> Result<()> {
+    let template = match &opts.source {
+        TemplateSource::Git(url) => fetch_template(url, config)
+            .with_context(|| format!("failed to fetch template from: {url}"))?,
+        TemplateSource::Path(template) => fsx::canonicalize_utf8(template)
+            .with_context(|| format!("failed to find template directory: {template}"))?,
+    };
+    ensure!(
+        template.join(MANIFEST_FILE_NAME).exists(),
+        "template does not contain `{MANIFEST_FILE_NAME}` in its root directory"
+    );
+
+    let manifest = read_template_manifest(&template)?;
+    let values = placeholder_values(&manifest, &opts.defines, name, config)?;
+
+    for entry in WalkDir::new(&template)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        // Skip build artifacts of local templates, which may be built packages themselves.
+        .filter_entry(|entry| {
+            entry.file_name() != ".git"
+                && !(entry.depth() == 1 && entry.file_name() == DEFAULT_TARGET_DIR_NAME)
+        })
+    {
+        let entry = entry?;
+        let source = Utf8Path::from_path(entry.path()).context("template path is not UTF-8")?;
+        let relative = source
+            .strip_prefix(&template)
+            .expect("walked path must be within template directory");
+        if relative == TEMPLATE_MANIFEST_FILE_NAME {
+            continue;
+        }
+        let relative = rename(relative, &manifest.renames);
+        let destination = path.join(substitute(&relative, &values));
+
+        if entry.file_type().is_dir() {
+            fsx::create_dir_all(&destination)?;
+            continue;
+        }
+        ensure!(
+            !destination.exists(),
+            "destination `{destination}` already exists"
+        );
+        fsx::copy(source, &destination)?;
+        // Files which are not UTF-8 text are copied as they are.
+        if let Ok(contents) = String::from_utf8(fsx::read(source)?) {
+            let substituted = substitute(&contents, &values);
+            if substituted != contents {
+                fsx::write(&destination, substituted)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn fetch_template(url: &Url, config: &Config) -> Result<Utf8PathBuf> {
+    let remote = GitRemote::new(CanonicalUrl::new(url)?);
+    config.tokio_handle().block_on(async {
+        let _lock = config.package_cache_lock().acquire_async().await?;
+        let (location, _) = git::checkout(&remote, &GitReference::DefaultBranch, None, config)?;
+        anyhow::Ok(location)
+    })
+}
+
+fn read_template_manifest(template: &Utf8Path) -> Result<TemplateManifest> {
+    let path = template.join(TEMPLATE_MANIFEST_FILE_NAME);
+    if !path.exists() {
+        return Ok(TemplateManifest::default());
+    }
+    let manifest: TemplateManifest = toml::from_str(&fsx::read_to_string(&path)?)
+        .with_context(|| format!("failed to parse template manifest at: {path}"))?;
+    for name in manifest.placeholders.keys() {
+        ensure!(
+            !BUILTIN_PLACEHOLDERS.contains(&name.as_str()),
+            "template manifest cannot declare built-in placeholder `{name}`"
+        );
+    }
+    for source in manifest.renames.keys() {
+        ensure!(
+            template.join(source).exists(),
+            "template manifest renames `{source}`, which does not exist in the template"
+        );
+    }
+    Ok(manifest)
+}
+
+/// Values of all placeholders available in a template.
+///
+/// Values of placeholders declared by the template are taken from `defines`, asked for if
+/// standard input is a terminal, or fall back to the declared default.
+fn placeholder_values(
+    manifest: &TemplateManifest,
+    defines: &BTreeMap<String, String>,
+    name: &PackageName,
+    config: &Config,
+) -> Result<BTreeMap<String, String>> {
+    ensure!(
+        !defines.contains_key("name"),
+        "the `name` placeholder cannot be defined\n\
+        help: use --name to override the package name"
+    );
+
+    let mut values = BTreeMap::from([
+        ("name".to_string(), name.to_string()),
+        ("author".to_string(), default_author().unwrap_or_default()),
+    ]);
+    values.extend(defines.clone());
+
+    for (key, placeholder) in &manifest.placeholders {
+        if values.contains_key(key) {
+            continue;
+        }
+        let value = if io::stdin().is_terminal() {
+            prompt(key, placeholder, config)?
+        } else {
+            None
+        };
+        let Some(value) = value.or_else(|| placeholder.default.clone()) else {
+            bail!(
+                "no value provided for template placeholder `{key}`\n\
+                help: use `--define {key}=<value>` to provide it"
+            );
+        };
+        values.insert(key.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Ask the user for a placeholder value, returns `None` if the answer is empty.
+fn prompt(key: &str, placeholder: &TemplatePlaceholder, config: &Config) -> Result<Option<String>> {
+    let question = placeholder.prompt.as_deref().unwrap_or(key);
+    match &placeholder.default {
+        Some(default) => config.ui().print(format!("{question} [{default}]:")),
+        None => config.ui().print(format!("{question}:")),
+    }
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .with_context(|| format!("failed to read value of `{key}` from standard input"))?;
+    let answer = line.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+/// Author of generated projects, in the `name <email>` form if the email is known.
+///
+/// Taken from Git author environment variables, or Git configuration.
+pub(crate) fn default_author() -> Option<String> {
+    let git_config = |key: &str| {
+        let output = Command::new("git")
+            .args(["config", "--get", key])
+            .output()
+            .ok()?;
+        let value = String::from_utf8(output.stdout).ok()?;
+        let value = value.trim();
+        (output.status.success() && !value.is_empty()).then(|| value.to_string())
+    };
+    let name = env::var("GIT_AUTHOR_NAME")
+        .ok()
+        .or_else(|| git_config("user.name"))?;
+    let email = env::var("GIT_AUTHOR_EMAIL")
+        .ok()
+        .or_else(|| git_config("user.email"));
+    Some(match email {
+        Some(email) => format!("{name} <{email}>"),
+        None => name,
+    })
+}
+
+/// Path of a template file in generated projects, relative to the project root.
+///
+/// The file is moved along with its closest ancestor directory present in `renames`, if any.
+fn rename(relative: &Utf8Path, renames: &BTreeMap<String, String>) -> String {
+    let components = relative
+        .components()
+        .map(|component| component.as_str())
+        .collect::<Vec<_>>();
+    for len in (1..=components.len()).rev() {
+        if let Some(destination) = renames.get(&components[..len].join("/")) {
+            return [destination.as_str()]
+                .into_iter()
+                .chain(components[len..].iter().copied())
+                .join("/");
+        }
+    }
+    components.join("/")
+}
+
+/// Replace `{{ placeholder }}` occurrences in `text` with their values.
+///
+/// Occurrences of unknown placeholders are left untouched.
+fn substitute(text: &str, values: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + len + 2;
+        result.push_str(&rest[..start]);
+        let key = rest[start + 2..end - 2].trim();
+        match values.get(key) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use test_case::test_case;
+
+    use camino::Utf8Path;
+
+    use super::{rename, substitute};
+
+    #[test_case("name = \"{{name}}\"", "name = \"hello\"")]
+    #[test_case("{{ author }} wrote {{name}}", "Jane <jane@example.com> wrote hello")]
+    #[test_case("{{ unknown }} stays", "{{ unknown }} stays")]
+    #[test_case("unclosed {{ name", "unclosed {{ name")]
+    #[test_case("src/{{name}}.cairo", "src/hello.cairo")]
+    fn substitute_placeholders(text: &str, expected: &str) {
+        let values = BTreeMap::from([
+            ("name".to_string(), "hello".to_string()),
+            ("author".to_string(), "Jane <jane@example.com>".to_string()),
+        ]);
+        assert_eq!(substitute(text, &values), expected);
+    }
+
+    #[test_case("gitignore", ".gitignore")]
+    #[test_case("src/lib.cairo", "src/{{name}}.cairo")]
+    #[test_case("contracts/token.cairo", "src/token.cairo")]
+    #[test_case("contracts/nested/lib.cairo", "src/nested/lib.cairo")]
+    #[test_case("tests/test.cairo", "tests/test.cairo")]
+    fn rename_paths(relative: &str, expected: &str) {
+        let renames = BTreeMap::from([
+            ("gitignore".to_string(), ".gitignore".to_string()),
+            (
+                "src/lib.cairo".to_string(),
+                "src/{{name}}.cairo".to_string(),
+            ),
+            ("contracts".to_string(), "src".to_string()),
+        ]);
+        assert_eq!(rename(Utf8Path::new(relative), &renames), expected);
+    }
+}
@@ -0,0 +1,343 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{bail, ensure, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use futures::TryStreamExt;
+use semver::Version;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tracing::debug;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::signature::{verify_signature, SIGNATURE_SUFFIX};
+use crate::core::{Checksum, Config, Digest, ReleaseChannel};
+use crate::internal::fsx;
+use crate::process::{exec_replace, is_executable};
+
+/// Name of the file pinning the Scarb version used by a project.
+pub const TOOLCHAIN_FILE_NAME: &str = ".scarb-version";
+
+/// Set in the environment of a pinned toolchain when Scarb switches to it, so that it never
+/// switches toolchains again.
+pub const SCARB_TOOLCHAIN_ENV: &str = "SCARB_TOOLCHAIN";
+
+const RELEASES_REPO: &str = "software-mansion/scarb";
+const NIGHTLIES_REPO: &str = "software-mansion/scarb-nightlies";
+
+/// Name of the file published with each release, listing SHA-256 checksums of its archives.
+const CHECKSUMS_FILE_NAME: &str = "checksums.sha256";
+
+/// Find the Scarb version pinned in a `.scarb-version` file, located in `dir` or any of
+/// its ancestors.
+///
+/// Returns the version along with the path of the file pinning it.
+pub fn find_pinned_version(dir: &Utf8Path) -> Result<Option<(Version, Utf8PathBuf)>> {
+    for dir in dir.ancestors() {
+        let path = dir.join(TOOLCHAIN_FILE_NAME);
+        if !path.is_file() {
+            continue;
+        }
+        let version = parse_pinned_version(&fsx::read_to_string(&path)?)
+            .with_context(|| format!("failed to parse Scarb version pinned in: {path}"))?;
+        return Ok(Some((version, path)));
+    }
+    Ok(None)
+}
+
+fn parse_pinned_version(contents: &str) -> Result<Version> {
+    let version = contents.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    Ok(Version::parse(version)?)
+}
+
+/// Replace the current process with the Scarb toolchain pinned by the current project,
+/// installing it if needed, and passing it all arguments of this invocation.
+///
+/// Does nothing if the project does not pin any toolchain, it pins the version of the running
+/// Scarb, or the running Scarb has already been selected as the pinned toolchain.
+///
+/// NOTE: This may replace the current process.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn switch_to_pinned_toolchain(config: &Config) -> Result<()> {
+    if env::var_os(SCARB_TOOLCHAIN_ENV).is_some() {
+        return Ok(());
+    }
+    let dir = config
+        .manifest_path()
+        .parent()
+        .expect("parent of manifest path must always exist");
+    let Some((version, path)) = find_pinned_version(dir)? else {
+        return Ok(());
+    };
+    if version.to_string() == crate::version::get().version {
+        return Ok(());
+    }
+    debug!("switching to Scarb {version} pinned in: {path}");
+    let exe = install_toolchain(&version, config)?;
+
+    let mut cmd = Command::new(exe);
+    cmd.args(env::args_os().skip(1));
+    cmd.env(SCARB_TOOLCHAIN_ENV, version.to_string());
+    exec_replace(&mut cmd)
+}
+
+/// Install a Scarb release in the toolchains directory, unless it is already installed.
+///
+/// Returns the path to the `scarb` executable of the release.
+pub fn install_toolchain(version: &Version, config: &Config) -> Result<PathBuf> {
+    let toolchains = config.dirs().toolchains_dir();
+    let toolchain = toolchains.child(version.to_string());
+    let exe = toolchain
+        .path_unchecked()
+        .join("bin")
+        .join(format!("scarb{}", env::consts::EXE_SUFFIX))
+        .into_std_path_buf();
+    if toolchain.is_ok() && is_executable(&exe) {
+        return Ok(exe);
+    }
+
+    ensure!(
+        config.network_allowed(),
+        "cannot download Scarb v{version} in offline mode"
+    );
+
+    config.tokio_handle().block_on(async {
+        let _lock = config.package_cache_lock().acquire_async().await?;
+        let unpacked = download_release(
+            &Release::stable(version),
+            toolchains.path_existent()?,
+            config,
+        )
+        .await?;
+        if toolchain.path_unchecked().exists() {
+            fsx::remove_dir_all(toolchain.path_unchecked())?;
+        }
+        fsx::rename(&unpacked, toolchain.path_unchecked())?;
+        ensure!(
+            is_executable(&exe),
+            "Scarb v{version} archive does not contain `scarb` executable"
+        );
+        toolchain.mark_ok()
+    })?;
+
+    Ok(exe)
+}
+
+/// A Scarb release published on GitHub.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Release {
+    /// GitHub repository publishing the release, in the `owner/name` form.
+    repo: &'static str,
+    pub tag: String,
+}
+
+impl Release {
+    pub fn stable(version: &Version) -> Self {
+        Self {
+            repo: RELEASES_REPO,
+            tag: format!("v{version}"),
+        }
+    }
+
+    /// Look up the newest release published in `channel`.
+    pub async fn latest(channel: ReleaseChannel, config: &Config) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct GitHubRelease {
+            tag_name: String,
+        }
+
+        let repo = match channel {
+            ReleaseChannel::Stable => RELEASES_REPO,
+            ReleaseChannel::Nightly => NIGHTLIES_REPO,
+        };
+        let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+        let release = async {
+            let response = config.http()?.get(&url).send().await?.error_for_status()?;
+            anyhow::Ok(response.json::<GitHubRelease>().await?)
+        }
+        .await
+        .with_context(|| format!("failed to look up latest Scarb release at: {url}"))?;
+        Ok(Self {
+            repo,
+            tag: release.tag_name,
+        })
+    }
+
+    fn download_url(&self, file_name: &str) -> String {
+        format!(
+            "https://github.com/{}/releases/download/{}/{file_name}",
+            self.repo, self.tag
+        )
+    }
+}
+
+/// Download the archive of `release` built for this machine into `dir`, verify its checksum and
+/// unpack it.
+///
+/// If trusted keys are configured in the `[self-update]` config table, the published checksums
+/// must be signed with one of them.
+/// Returns the path to the unpacked release directory.
+pub(crate) async fn download_release(
+    release: &Release,
+    dir: &Utf8Path,
+    config: &Config,
+) -> Result<Utf8PathBuf> {
+    let Some(target) = host_target() else {
+        bail!(
+            "Scarb releases are not available for this platform: {}-{}",
+            env::consts::ARCH,
+            env::consts::OS
+        );
+    };
+    let tag = &release.tag;
+    let archive_name = format!("scarb-{tag}-{target}");
+    let archive_ext = if cfg!(windows) { "zip" } else { "tar.gz" };
+    let archive_file_name = format!("{archive_name}.{archive_ext}");
+
+    config
+        .ui()
+        .print(Status::new("Downloading", &format!("Scarb {tag}")));
+
+    let checksums_url = release.download_url(CHECKSUMS_FILE_NAME);
+    let checksums = download_text(&checksums_url, config)
+        .await
+        .with_context(|| {
+            format!("failed to download Scarb {tag} checksums from: {checksums_url}")
+        })?;
+    let trusted_keys = &config.config_file().self_update.trusted_keys;
+    if !trusted_keys.is_empty() {
+        let signature_url = format!("{checksums_url}{SIGNATURE_SUFFIX}");
+        let signature = download_text(&signature_url, config)
+            .await
+            .with_context(|| format!("failed to download signature from: {signature_url}"))?;
+        verify_signature(checksums.as_bytes(), &signature, trusted_keys)
+            .with_context(|| format!("failed to verify signature of Scarb {tag} checksums"))?;
+    }
+    let expected = find_checksum(&checksums, &archive_file_name).with_context(|| {
+        format!("checksum of `{archive_file_name}` is missing in Scarb {tag} checksums")
+    })?;
+
+    let archive = dir.join(&archive_file_name);
+    let url = release.download_url(&archive_file_name);
+    download(&url, &archive, config)
+        .await
+        .with_context(|| format!("failed to download Scarb {tag} from: {url}"))?;
+    let actual = Digest::recommended()
+        .update_read(fsx::open(&archive)?)?
+        .finish();
+    if actual != expected {
+        fsx::remove_file(&archive)?;
+        bail!("checksum of Scarb {tag} archive does not match: expected {expected}, got {actual}");
+    }
+
+    let unpacked = dir.join(&archive_name);
+    if unpacked.exists() {
+        fsx::remove_dir_all(&unpacked)?;
+    }
+    unpack(&archive, dir).with_context(|| format!("failed to unpack Scarb {tag} archive"))?;
+    fsx::remove_file(&archive)?;
+    Ok(unpacked)
+}
+
+async fn download_text(url: &str, config: &Config) -> Result<String> {
+    let response = config.http()?.get(url).send().await?.error_for_status()?;
+    Ok(response.text().await?)
+}
+
+async fn download(url: &str, path: &Utf8Path, config: &Config) -> Result<()> {
+    let response = config.http()?.get(url).send().await?.error_for_status()?;
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .with_context(|| format!("failed to create file: {path}"))?;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.try_next().await? {
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+/// Find checksum of `file_name` in a `sha256sum` formatted checksums file.
+fn find_checksum(checksums: &str, file_name: &str) -> Option<Checksum> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start();
+        let name = name.strip_prefix('*').unwrap_or(name);
+        if name != file_name {
+            return None;
+        }
+        Checksum::parse(&format!("sha256:{hash}")).ok()
+    })
+}
+
+#[cfg(not(windows))]
+fn unpack(archive: &Utf8Path, into: &Utf8Path) -> Result<()> {
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(into)
+        .status()
+        .context("failed to run `tar`")?;
+    ensure!(status.success(), "`tar` exited with {status}");
+    Ok(())
+}
+
+#[cfg(windows)]
+fn unpack(archive: &Utf8Path, into: &Utf8Path) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(fsx::open(archive)?)?;
+    zip.extract(into)?;
+    Ok(())
+}
+
+/// Target triple of Scarb release archives which can run on this machine.
+fn host_target() -> Option<&'static str> {
+    match (env::consts::ARCH, env::consts::OS) {
+        ("x86_64", "linux") => Some("x86_64-unknown-linux-gnu"),
+        ("aarch64", "linux") => Some("aarch64-unknown-linux-gnu"),
+        ("x86_64", "macos") => Some("x86_64-apple-darwin"),
+        ("aarch64", "macos") => Some("aarch64-apple-darwin"),
+        ("x86_64", "windows") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+    use test_case::test_case;
+
+    use super::{find_checksum, parse_pinned_version};
+
+    #[test_case("2.3.1\n", "2.3.1")]
+    #[test_case("  v2.4.0-rc.1 ", "2.4.0-rc.1")]
+    fn parse_version(contents: &str, expected: &str) {
+        assert_eq!(
+            parse_pinned_version(contents).unwrap(),
+            Version::parse(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_version() {
+        assert!(parse_pinned_version("stable").is_err());
+    }
+
+    #[test]
+    fn find_checksum_of_archive() {
+        let checksums = "\
+            0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef  scarb-v2.3.1-x86_64-unknown-linux-gnu.tar.gz
+            fedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210 *scarb-v2.3.1-aarch64-apple-darwin.tar.gz
+        ";
+        assert_eq!(
+            find_checksum(checksums, "scarb-v2.3.1-aarch64-apple-darwin.tar.gz")
+                .unwrap()
+                .to_string(),
+            "sha256:fedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210"
+        );
+        assert!(find_checksum(checksums, "scarb-v2.3.1-x86_64-pc-windows-msvc.zip").is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::VersionReq;
+
+use crate::core::{
+    DetailedTomlToolPin, GitReference, PackageName, SourceId, TomlManifest, Workspace,
+};
+use crate::internal::fsx;
+use crate::ops::{install_into, installed_in, InstallOpts, InstallSource, InstalledPackage};
+
+/// Name of the directory in the target directory, where tools pinned in `[tools]` are installed.
+const TOOLS_DIR_NAME: &str = "tools";
+
+/// Install tools pinned in the `[tools]` table of the workspace manifest, unless they are already
+/// installed in their pinned versions.
+///
+/// Returns the directory containing executables of pinned tools, or `None` if the workspace does
+/// not pin any.
+#[tracing::instrument(level = "debug", skip(ws))]
+pub fn ensure_pinned_tools(ws: &Workspace<'_>) -> Result<Option<Utf8PathBuf>> {
+    let manifest_path = ws.manifest_path();
+    let manifest = TomlManifest::read_from_path(manifest_path)?;
+    let Some(tools) = manifest.tools.filter(|tools| !tools.is_empty()) else {
+        return Ok(None);
+    };
+
+    let tools_dir = ws.target_dir().child(TOOLS_DIR_NAME);
+    let installed = installed_in(tools_dir.path_unchecked())?;
+    for (name, pin) in &tools {
+        let opts = install_opts(name, &pin.to_detailed(), manifest_path)?;
+        if installed
+            .get(name.as_str())
+            .is_some_and(|installed| is_installed(installed, &opts))
+        {
+            continue;
+        }
+        install_into(&opts, &tools_dir, ws.config()).with_context(|| {
+            format!("failed to install tool `{name}` pinned in: {manifest_path}")
+        })?;
+    }
+
+    Ok(Some(tools_dir.path_unchecked().join("bin")))
+}
+
+fn install_opts(
+    name: &PackageName,
+    pin: &DetailedTomlToolPin,
+    manifest_path: &Utf8Path,
+) -> Result<InstallOpts> {
+    let source = match (&pin.git, &pin.path) {
+        (Some(_), Some(_)) => bail!(
+            "tool ({name}) specification is ambiguous, only one of `git` or `path` is allowed"
+        ),
+        (Some(_), _) | (_, Some(_)) if pin.version.is_some() || pin.registry.is_some() => bail!(
+            "tool ({name}) pinned to a Git repository or a local path cannot specify \
+            `version` or `registry`"
+        ),
+        (Some(_), None) if pin.branch.is_some() => {
+            bail!("tool ({name}) cannot be pinned to a Git branch, use a `tag` or a `rev`")
+        }
+        (Some(url), None) => {
+            let reference = match (&pin.tag, &pin.rev) {
+                (Some(tag), None) => GitReference::Tag(tag.into()),
+                (None, Some(rev)) => GitReference::Rev(rev.into()),
+                _ => bail!(
+                    "tool ({name}) must be pinned to either a `tag` or a `rev` \
+                    of its Git repository"
+                ),
+            };
+            InstallSource::Git {
+                url: url.clone(),
+                reference,
+            }
+        }
+        (None, Some(path)) => InstallSource::Path(path.relative_to_file(manifest_path)?),
+        (None, None) => {
+            if pin.version.is_none() {
+                bail!("tool ({name}) must be pinned to an exact version");
+            }
+            InstallSource::Registry(pin.registry.clone())
+        }
+    };
+    let version_req = pin
+        .version
+        .as_ref()
+        .map(|version| VersionReq::parse(&format!("={version}")))
+        .transpose()?;
+    Ok(InstallOpts {
+        name: Some(name.clone()),
+        version_req,
+        source,
+        // Pinned tools are installed into a directory managed by Scarb only.
+        force: true,
+    })
+}
+
+/// Whether `installed` comes from the source and version pinned in `opts`.
+fn is_installed(installed: &InstalledPackage, opts: &InstallOpts) -> bool {
+    let pinned = match &opts.source {
+        InstallSource::Registry(registry) => {
+            let source_id = match registry {
+                Some(url) => SourceId::for_registry(url).ok(),
+                None => Some(SourceId::default_registry()),
+            };
+            let version_matches = match (&opts.version_req, &installed.version) {
+                (Some(req), Some(version)) => req.matches(version),
+                _ => false,
+            };
+            return version_matches && source_id == Some(installed.source);
+        }
+        InstallSource::Git { url, reference } => SourceId::for_git(url, reference).ok(),
+        InstallSource::Path(path) => fsx::canonicalize_utf8(path)
+            .ok()
+            .and_then(|path| SourceId::for_path(&path).ok()),
+    };
+    pinned.is_some_and(|pinned| installed.source.can_lock_source_id(pinned))
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use semver::Version;
+
+    use super::install_opts;
+    use crate::core::{DetailedTomlToolPin, GitReference, PackageName};
+    use crate::ops::InstallSource;
+
+    fn opts(pin: DetailedTomlToolPin) -> anyhow::Result<super::InstallOpts> {
+        install_opts(
+            &PackageName::new("hello"),
+            &pin,
+            Utf8Path::new("/project/Scarb.toml"),
+        )
+    }
+
+    #[test]
+    fn registry_pin() {
+        let opts = opts(DetailedTomlToolPin {
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(opts.source, InstallSource::Registry(None)));
+        let req = opts.version_req.unwrap();
+        assert!(req.matches(&Version::new(1, 2, 3)));
+        assert!(!req.matches(&Version::new(1, 2, 4)));
+    }
+
+    #[test]
+    fn git_pin() {
+        let opts = opts(DetailedTomlToolPin {
+            git: Some("https://example.com/hello.git".parse().unwrap()),
+            tag: Some("v1.0.0".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            opts.source,
+            InstallSource::Git {
+                reference: GitReference::Tag(_),
+                ..
+            }
+        ));
+        assert!(opts.version_req.is_none());
+    }
+
+    #[test]
+    fn invalid_pins() {
+        let err = opts(DetailedTomlToolPin::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tool (hello) must be pinned to an exact version"
+        );
+
+        let err = opts(DetailedTomlToolPin {
+            git: Some("https://example.com/hello.git".parse().unwrap()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tool (hello) must be pinned to either a `tag` or a `rev` of its Git repository"
+        );
+
+        let err = opts(DetailedTomlToolPin {
+            git: Some("https://example.com/hello.git".parse().unwrap()),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tool (hello) pinned to a Git repository or a local path cannot specify \
+            `version` or `registry`"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use itertools::Itertools;
+use petgraph::visit::{Dfs, Walker};
+use petgraph::Direction;
+use semver::Version;
+use serde::Serialize;
+
+use crate::core::resolver::DependencyEdge;
+use crate::core::{PackageId, PackageName, Resolve, TargetKind, Workspace};
+use crate::ops;
+
+pub struct TreeOpts {
+    /// Workspace members to print dependency trees of.
+    pub packages: Vec<PackageId>,
+    /// Maximum depth of printed trees, unlimited if `None`.
+    pub depth: Option<usize>,
+    /// Print trees of dependants of packages matching this spec, instead of dependencies.
+    pub invert: Option<TreeInvertSpec>,
+    /// Print trees of dependants of packages present in the graph in multiple versions.
+    pub duplicates: bool,
+}
+
+/// Packages to print inverted trees of, with `scarb tree --invert`.
+#[derive(Clone, Debug)]
+pub struct TreeInvertSpec {
+    pub name: PackageName,
+    /// Only match this version of the package, if set.
+    pub version: Option<Version>,
+}
+
+/// A package in a dependency tree, along with its dependencies.
+///
+/// In inverted trees, [`DependencyTree::dependencies`] contains dependants of the package instead.
+#[derive(Clone, Debug, Serialize)]
+pub struct DependencyTree {
+    pub package: PackageId,
+    /// Kinds of targets using this dependency, empty if it is used by all targets.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub target_kinds: Vec<TargetKind>,
+    /// Whether dependencies of this package are omitted, because they are already listed
+    /// elsewhere in the tree.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deduplicated: bool,
+    pub dependencies: Vec<DependencyTree>,
+}
+
+/// Build trees of resolved dependencies of workspace members.
+///
+/// With [`TreeOpts::invert`] set, build trees of packages matching the spec instead, showing all
+/// paths leading to them from the workspace members.
+/// With [`TreeOpts::duplicates`] set, do the same for all packages present in the graph
+/// in multiple versions, or coming from multiple sources.
+/// Implicit dependencies on `core` and the test plugin are left out.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn tree(opts: &TreeOpts, ws: &Workspace<'_>) -> Result<Vec<DependencyTree>> {
+    let resolve = ops::resolve_workspace(ws)?;
+    let resolve = &resolve.resolve;
+
+    if opts.invert.is_none() && !opts.duplicates {
+        return Ok(opts
+            .packages
+            .iter()
+            .sorted()
+            .map(|&root| {
+                let walker = TreeWalker {
+                    resolve,
+                    direction: Direction::Outgoing,
+                    included: None,
+                    depth: opts.depth,
+                };
+                walker.build(root, Vec::new(), 0, &mut HashSet::new())
+            })
+            .collect());
+    }
+
+    // Only show paths leading from the selected workspace members.
+    let included: HashSet<PackageId> = opts
+        .packages
+        .iter()
+        .flat_map(|&member| Dfs::new(&resolve.graph, member).iter(&resolve.graph))
+        .collect();
+
+    let targets = if let Some(spec) = &opts.invert {
+        let targets = included
+            .iter()
+            .filter(|id| id.name == spec.name)
+            .filter(|id| spec.version.as_ref().map_or(true, |v| id.version == *v))
+            .copied()
+            .sorted()
+            .collect_vec();
+        if targets.is_empty() {
+            match &spec.version {
+                Some(version) => bail!(
+                    "package `{}@{version}` not found in the dependency graph",
+                    spec.name
+                ),
+                None => bail!("package `{}` not found in the dependency graph", spec.name),
+            }
+        }
+        targets
+    } else {
+        included
+            .iter()
+            .copied()
+            .into_group_map_by(|id| id.name.clone())
+            .into_values()
+            .filter(|ids| ids.len() > 1)
+            .flatten()
+            .sorted()
+            .collect_vec()
+    };
+
+    let walker = TreeWalker {
+        resolve,
+        direction: Direction::Incoming,
+        included: Some(&included),
+        depth: opts.depth,
+    };
+    Ok(targets
+        .into_iter()
+        .map(|target| walker.build(target, Vec::new(), 0, &mut HashSet::new()))
+        .collect())
+}
+
+struct TreeWalker<'a> {
+    resolve: &'a Resolve,
+    /// Whether to follow dependencies ([`Direction::Outgoing`]) or dependants
+    /// ([`Direction::Incoming`]) of packages.
+    direction: Direction,
+    /// Packages allowed to appear in the tree, all if `None`.
+    included: Option<&'a HashSet<PackageId>>,
+    depth: Option<usize>,
+}
+
+impl TreeWalker<'_> {
+    fn build(
+        &self,
+        package: PackageId,
+        target_kinds: Vec<TargetKind>,
+        level: usize,
+        visited: &mut HashSet<PackageId>,
+    ) -> DependencyTree {
+        let is_root = visited.is_empty();
+        let deduplicated =
+            !visited.insert(package) && self.neighbors(package, true).next().is_some();
+        let dependencies = if deduplicated || self.depth == Some(level) {
+            Vec::new()
+        } else {
+            self.neighbors(package, is_root)
+                .sorted_by_key(|(neighbor, _)| *neighbor)
+                .map(|(neighbor, edge)| {
+                    self.build(neighbor, edge.target_kinds().to_vec(), level + 1, visited)
+                })
+                .collect()
+        };
+        DependencyTree {
+            package,
+            target_kinds,
+            deduplicated,
+            dependencies,
+        }
+    }
+
+    /// Packages adjacent to `package` in the walked direction, along with edges connecting them.
+    fn neighbors(
+        &self,
+        package: PackageId,
+        is_root: bool,
+    ) -> impl Iterator<Item = (PackageId, &DependencyEdge)> + '_ {
+        let graph = &self.resolve.graph;
+        graph
+            .neighbors_directed(package, self.direction)
+            .filter(|neighbor| !is_implicit(*neighbor))
+            .filter(move |neighbor| {
+                self.included
+                    .map_or(true, |included| included.contains(neighbor))
+            })
+            .map(move |neighbor| {
+                let edge = match self.direction {
+                    Direction::Outgoing => &graph[(package, neighbor)],
+                    Direction::Incoming => &graph[(neighbor, package)],
+                };
+                (neighbor, edge)
+            })
+            // Dependencies specific to target kinds are only used by the root package.
+            .filter(move |(_, edge)| {
+                self.direction == Direction::Incoming || is_root || edge.is_normal()
+            })
+    }
+}
+
+fn is_implicit(package: PackageId) -> bool {
+    package.is_core() || package.name == PackageName::TEST_PLUGIN
+}
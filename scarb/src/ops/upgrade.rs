@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::{Op, Version, VersionReq};
+use toml_edit::Document;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::source_map::SourceMap;
+use crate::core::registry::Registry;
+use crate::core::{
+    DepKind, DependencyVersionReq, ManifestDependency, Package, PackageId, PackageName, Workspace,
+};
+use crate::internal::fsx;
+use crate::manifest_editor;
+use crate::manifest_editor::{EditManifestOptions, SetDependencyVersion};
+
+pub struct UpgradeOpts {
+    /// Workspace members to upgrade dependencies of.
+    pub packages: Vec<PackageId>,
+    /// Upgrade only dependencies on these packages, all if empty.
+    pub dependencies: Vec<PackageName>,
+    /// Upgrade to the newest versions, even if they do not match current version requirements.
+    pub incompatible: bool,
+    pub dry_run: bool,
+}
+
+/// A planned change of a version requirement in a manifest file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Upgrade {
+    manifest_path: Utf8PathBuf,
+    table: Vec<String>,
+    key: String,
+    name: PackageName,
+    from: VersionReq,
+    to: String,
+}
+
+/// Raise version requirements of registry dependencies in manifests of workspace members.
+///
+/// By default, requirements are raised to the newest versions matching them, so that only the
+/// lowest accepted versions change. With [`UpgradeOpts::incompatible`], they are raised to the
+/// newest versions available in registries. Only requirements consisting of a single caret or
+/// tilde comparator are upgraded; exact and range requirements are left as they are.
+/// Dependencies inherited from the workspace are upgraded in the workspace manifest.
+///
+/// Returns the number of upgraded requirements.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn upgrade(opts: &UpgradeOpts, ws: &Workspace<'_>) -> Result<usize> {
+    let source_map = SourceMap::preloaded(ws.members(), ws.config());
+    let upgrades = ws.config().tokio_handle().block_on(async {
+        let mut upgrades = Vec::new();
+        for package_id in &opts.packages {
+            let member = ws.fetch_package(package_id)?;
+            let doc = read_manifest(member.manifest_path())?;
+            for (table, dep) in dependency_tables(member) {
+                if !dep.source_id.is_registry()
+                    || !(opts.dependencies.is_empty() || opts.dependencies.contains(&dep.name))
+                {
+                    continue;
+                }
+                let DependencyVersionReq::Req(req) = &dep.version_req else {
+                    continue;
+                };
+                let available = source_map
+                    .query_versions(dep.name.clone(), dep.source_id)
+                    .await
+                    .with_context(|| {
+                        format!("failed to query versions of package `{}`", dep.name)
+                    })?;
+                let versions = available
+                    .iter()
+                    .map(|summary| &summary.package_id.version)
+                    .collect::<Vec<_>>();
+                let Some(to) = upgraded_requirement(req, &versions, opts.incompatible) else {
+                    continue;
+                };
+
+                let key = dep.import_name().to_string();
+                let (manifest_path, table) = if is_inherited(&doc, &table, &key) {
+                    let table = vec!["workspace".to_string(), "dependencies".to_string()];
+                    (ws.manifest_path().to_path_buf(), table)
+                } else {
+                    (member.manifest_path().to_path_buf(), table)
+                };
+                upgrades.push(Upgrade {
+                    manifest_path,
+                    table,
+                    key,
+                    name: dep.name.clone(),
+                    from: req.clone(),
+                    to,
+                });
+            }
+        }
+        anyhow::Ok(upgrades)
+    })?;
+
+    // Dependencies inherited by multiple members are upgraded only once.
+    let mut by_manifest: BTreeMap<Utf8PathBuf, Vec<Upgrade>> = BTreeMap::new();
+    for upgrade in upgrades {
+        let upgrades = by_manifest
+            .entry(upgrade.manifest_path.clone())
+            .or_default();
+        if !upgrades.contains(&upgrade) {
+            upgrades.push(upgrade);
+        }
+    }
+
+    let mut count = 0;
+    for (manifest_path, upgrades) in by_manifest {
+        let ops = upgrades
+            .into_iter()
+            .map(|upgrade| -> Box<dyn manifest_editor::Op> {
+                ws.config().ui().print(Status::new(
+                    "Upgrading",
+                    &format!("{} {} -> {}", upgrade.name, upgrade.from, upgrade.to),
+                ));
+                count += 1;
+                Box::new(SetDependencyVersion {
+                    table: upgrade.table,
+                    key: upgrade.key,
+                    version: upgrade.to,
+                })
+            })
+            .collect();
+        manifest_editor::edit(
+            &manifest_path,
+            ops,
+            EditManifestOptions {
+                config: ws.config(),
+                dry_run: opts.dry_run,
+            },
+        )?;
+    }
+    Ok(count)
+}
+
+/// Dependencies declared in the manifest of `member`, along with paths to tables declaring them.
+fn dependency_tables(member: &Package) -> Vec<(Vec<String>, &ManifestDependency)> {
+    let normal = member.manifest.summary.dependencies.iter().map(|dep| {
+        let table = match &dep.kind {
+            DepKind::Normal => vec!["dependencies".to_string()],
+            DepKind::Target(kind) => {
+                vec!["target-dependencies".to_string(), kind.to_string()]
+            }
+        };
+        (table, dep)
+    });
+    let build = member
+        .manifest
+        .build_dependencies
+        .iter()
+        .map(|dep| (vec!["build-dependencies".to_string()], dep));
+    normal.chain(build).collect()
+}
+
+fn read_manifest(path: &Utf8Path) -> Result<Document> {
+    Document::from_str(&fsx::read_to_string(path)?)
+        .with_context(|| format!("failed to read manifest at: {path}"))
+}
+
+/// Whether the dependency under `key` in `table` is declared with `workspace = true`.
+fn is_inherited(doc: &Document, table: &[String], key: &str) -> bool {
+    let mut item = doc.as_item();
+    for segment in table {
+        match item.get(segment) {
+            Some(next) => item = next,
+            None => return false,
+        }
+    }
+    item.get(key)
+        .and_then(|dep| dep.get("workspace"))
+        .and_then(|workspace| workspace.as_bool())
+        .unwrap_or(false)
+}
+
+/// New requirement raising `req` to the newest of `versions`, or `None` if it cannot be raised.
+///
+/// Without `incompatible`, only versions matching `req` are considered. Prerelease versions are
+/// only considered if `req` itself refers to a prerelease.
+fn upgraded_requirement(
+    req: &VersionReq,
+    versions: &[&Version],
+    incompatible: bool,
+) -> Option<String> {
+    let [comparator] = req.comparators.as_slice() else {
+        return None;
+    };
+    let prefix = match comparator.op {
+        Op::Caret => "",
+        Op::Tilde => "~",
+        _ => return None,
+    };
+    let lowest = Version {
+        major: comparator.major,
+        minor: comparator.minor.unwrap_or(0),
+        patch: comparator.patch.unwrap_or(0),
+        pre: comparator.pre.clone(),
+        build: Default::default(),
+    };
+
+    let newest = versions
+        .iter()
+        .copied()
+        .filter(|version| version.pre.is_empty() || !comparator.pre.is_empty())
+        .filter(|version| incompatible || req.matches(version))
+        .max()?;
+    (*newest > lowest).then(|| format!("{prefix}{newest}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::{Version, VersionReq};
+    use test_case::test_case;
+
+    use super::upgraded_requirement;
+
+    #[test_case("1", false, Some("1.2.0"))]
+    #[test_case("1", true, Some("2.1.0"))]
+    #[test_case("^1.0.5", false, Some("1.2.0"))]
+    #[test_case("~1.1", false, Some("~1.1.3"))]
+    #[test_case("~1.1", true, Some("~2.1.0"))]
+    #[test_case("2.1", false, None)]
+    #[test_case("3", true, None)]
+    #[test_case("=1.0.0", true, None)]
+    #[test_case(">=1.0.0, <2.0.0", true, None)]
+    #[test_case("2.2.0-rc.1", false, Some("2.2.0-rc.2"))]
+    fn upgrade_requirement(req: &str, incompatible: bool, expected: Option<&str>) {
+        let versions = [
+            "1.0.0",
+            "1.1.0",
+            "1.1.3",
+            "1.2.0",
+            "2.0.0",
+            "2.1.0",
+            "2.2.0-rc.1",
+            "2.2.0-rc.2",
+        ]
+        .map(|version| Version::parse(version).unwrap());
+        let versions = versions.iter().collect::<Vec<_>>();
+        let req = VersionReq::parse(req).unwrap();
+        assert_eq!(
+            upgraded_requirement(&req, &versions, incompatible).as_deref(),
+            expected
+        );
+    }
+}
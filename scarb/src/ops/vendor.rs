@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use walkdir::WalkDir;
+
+use scarb_ui::components::Status;
+
+use crate::core::registry::client::directory::VENDOR_RECORD_FILE;
+use crate::core::registry::index::IndexRecord;
+use crate::core::{ConfigFile, Package, SourceConfig, SourceId, Workspace};
+use crate::flock::OK_FILE;
+use crate::internal::fsx;
+use crate::internal::fsx::PathUtf8Ext;
+use crate::ops;
+
+/// Name of the source pointing at vendored packages in emitted configuration.
+const VENDORED_SOURCE_NAME: &str = "vendored-sources";
+
+pub struct VendorOpts {
+    /// Directory to vendor packages into, relative to the workspace root.
+    pub destination: Utf8PathBuf,
+}
+
+/// Copy sources of all registry dependencies of the workspace into the vendor directory.
+///
+/// Returns the contents of `.scarb/config.toml` which makes Scarb use vendored packages
+/// instead of registries.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn vendor(opts: &VendorOpts, ws: &Workspace<'_>) -> Result<String> {
+    let resolve = ops::resolve_workspace(ws)?;
+    let destination = ws.root().join(&opts.destination);
+
+    let mut packages = resolve
+        .packages
+        .values()
+        .chain(resolve.build_packages.values())
+        .filter(|package| package.id.source_id.is_registry())
+        .collect::<Vec<_>>();
+    packages.sort_by_key(|package| package.id);
+    packages.dedup_by_key(|package| package.id);
+
+    let mut vendored = BTreeSet::new();
+    for package in &packages {
+        let id = package.id;
+        if !vendored.insert(id.tarball_basename()) {
+            bail!(
+                "cannot vendor `{id}`, because the same package version is used \
+                from multiple registries"
+            );
+        }
+    }
+
+    remove_stale_packages(&destination, &vendored)?;
+    fsx::create_dir_all(&destination)?;
+
+    let mut registries = BTreeSet::new();
+    for package in packages {
+        let id = package.id;
+        registries.insert(id.source_id);
+
+        ws.config()
+            .ui()
+            .print(Status::new("Vendoring", &id.to_string()));
+
+        let checksum = resolve
+            .resolve
+            .summaries
+            .get(&id)
+            .and_then(|summary| summary.checksum.clone())
+            .with_context(|| format!("registry did not provide a checksum for `{id}`"))?;
+        let record = IndexRecord::from_summary(&resolve.resolve.summaries[&id], checksum);
+
+        // Packages may already be loaded from the vendor directory, if it is in use.
+        let package_dir = destination.join(id.tarball_basename());
+        let in_place = package_dir.exists()
+            && fsx::canonicalize(&package_dir)? == fsx::canonicalize(package.root())?;
+        if !in_place {
+            if package_dir.exists() {
+                fsx::remove_dir_all(&package_dir)?;
+            }
+            copy_package(package, &package_dir)
+                .with_context(|| format!("failed to vendor `{id}`"))?;
+        }
+        fsx::write(
+            package_dir.join(VENDOR_RECORD_FILE),
+            serde_json::to_string(&record)?,
+        )?;
+    }
+
+    replacement_config(&opts.destination, &registries)
+}
+
+/// Remove packages left from previous invocations which are not used anymore,
+/// keeping unrelated files intact.
+fn remove_stale_packages(destination: &Utf8Path, vendored: &BTreeSet<String>) -> Result<()> {
+    if !destination.is_dir() {
+        return Ok(());
+    }
+    for entry in fsx::read_dir(destination)? {
+        let entry = entry?;
+        let is_used = entry
+            .file_name()
+            .to_str()
+            .map_or(false, |name| vendored.contains(name));
+        let path = entry.path();
+        if !is_used && path.join(VENDOR_RECORD_FILE).is_file() {
+            fsx::remove_dir_all(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_package(package: &Package, package_dir: &Utf8Path) -> Result<()> {
+    let root = package.root();
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(root)?.try_as_utf8()?;
+        if relative == OK_FILE {
+            continue;
+        }
+        let target = package_dir.join(relative);
+        if entry.file_type().is_dir() {
+            fsx::create_dir_all(&target)?;
+        } else {
+            fsx::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn replacement_config(destination: &Utf8Path, registries: &BTreeSet<SourceId>) -> Result<String> {
+    let mut config = ConfigFile::default();
+    for (idx, source_id) in registries.iter().enumerate() {
+        let (name, registry) = if source_id.is_default_registry() {
+            (ConfigFile::DEFAULT_SOURCE_NAME.into(), None)
+        } else {
+            (
+                format!("registry-{}", idx + 1).into(),
+                Some(source_id.url.clone()),
+            )
+        };
+        config.source.insert(
+            name,
+            SourceConfig {
+                replace_with: Some(VENDORED_SOURCE_NAME.into()),
+                registry,
+                ..Default::default()
+            },
+        );
+    }
+    config.source.insert(
+        VENDORED_SOURCE_NAME.into(),
+        SourceConfig {
+            directory: Some(destination.to_path_buf()),
+            ..Default::default()
+        },
+    );
+    Ok(toml::to_string(&config)?)
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use camino::Utf8PathBuf;
+use semver::Version;
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::publishing::vcs::VCS_INFO_FILE_NAME;
+use crate::core::{
+    Config, DependencyVersionReq, GitReference, ManifestDependency, PackageId, PackageName,
+    SourceId,
+};
+use crate::internal::fsx;
+use crate::internal::fsx::PathBufUtf8Ext;
+use crate::internal::safe_unpack::LimitedReader;
+use crate::ops;
+use crate::sources::RegistrySource;
+
+pub struct VerifyOpts {
+    pub index_url: Url,
+    pub name: PackageName,
+    pub version: Version,
+    /// Git repository to take package sources from, instead of the current workspace.
+    pub git: Option<(Url, GitReference)>,
+}
+
+/// Contents of files in a package tarball, by path relative to the package root.
+type TarballContents = BTreeMap<Utf8PathBuf, Vec<u8>>;
+
+/// Check that a package published in the registry is identical to the package built
+/// from its sources.
+///
+/// Sources are taken from the current workspace, or from a git repository if requested.
+/// Only paths and contents of files are compared, so metadata like modification times
+/// do not affect the result.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn verify(opts: VerifyOpts, config: &Config) -> Result<()> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let package_id = PackageId::new(opts.name.clone(), opts.version.clone(), source_id);
+
+    config
+        .ui()
+        .print(Status::new("Verifying", &package_id.to_string()));
+
+    let registry = RegistrySource::new(source_id, config)?;
+    let published = config
+        .tokio_handle()
+        .block_on(registry.download_tarball(package_id))
+        .with_context(|| format!("failed to download `{package_id}`"))?;
+
+    let manifest_path = match &opts.git {
+        Some((url, reference)) => {
+            let git_source_id = SourceId::for_git(url, reference)?;
+            let dependency = ManifestDependency::builder()
+                .name(opts.name.clone())
+                .source_id(git_source_id)
+                .version_req(DependencyVersionReq::exact(&opts.version))
+                .build();
+            let package = config.tokio_handle().block_on(async {
+                let source = git_source_id.load(config)?;
+                let Some(summary) = source.query(&dependency).await?.into_iter().next() else {
+                    bail!(
+                        "cannot find `{} v{}` in git repository: {url}",
+                        opts.name,
+                        opts.version
+                    );
+                };
+                source.download(summary.package_id).await
+            })?;
+            package.manifest_path().to_path_buf()
+        }
+        None => config.manifest_path().to_path_buf(),
+    };
+
+    let ws = ops::read_workspace(&manifest_path, config)?;
+    let Some(package) = ws.members().find(|package| package.id.name == opts.name) else {
+        bail!(
+            "package `{}` not found in workspace: {}",
+            opts.name,
+            ws.root()
+        );
+    };
+    if package.id.version != opts.version {
+        bail!(
+            "package `{}` in workspace has version {}, but version {} is being verified",
+            opts.name,
+            package.id.version,
+            opts.version
+        );
+    }
+
+    // Sources being verified are not trusted, so their hooks must not be run.
+    let package_opts = ops::PackageOpts { run_hooks: false };
+    let tarball = ops::package_one(package.id, &package_opts, &ws)?;
+
+    let max_unpack_size = config.config_file().downloads.max_unpack_size();
+    let published = read_tarball(&published, max_unpack_size)
+        .with_context(|| format!("failed to read published package: {package_id}"))?;
+    let built = read_tarball(tarball.path().as_std_path(), max_unpack_size)
+        .with_context(|| format!("failed to read package built from sources: {package_id}"))?;
+
+    let differences = compare(&published, &built);
+    if !differences.is_empty() {
+        bail!(
+            "published package `{package_id}` does not match package built from sources:\n{}",
+            differences.join("\n")
+        );
+    }
+
+    config
+        .ui()
+        .print(Status::new("Verified", &package_id.to_string()));
+    Ok(())
+}
+
+fn read_tarball(path: &Path, max_unpack_size: u64) -> Result<TarballContents> {
+    let zst = zstd::Decoder::new(fsx::open(path)?)?;
+    let mut archive = tar::Archive::new(LimitedReader::new(zst, max_unpack_size));
+
+    let mut contents = TarballContents::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let path = entry.path()?.into_owned().try_into_utf8()?;
+        // Strip the top-level `{name}-{version}` directory.
+        let path = path.components().skip(1).collect::<Utf8PathBuf>();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        contents.insert(path, data);
+    }
+    Ok(contents)
+}
+
+/// List human-readable descriptions of differences between `published` and `built` packages.
+fn compare(published: &TarballContents, built: &TarballContents) -> Vec<String> {
+    let mut differences = Vec::new();
+    for (path, data) in published {
+        // VCS info describes the checkout the package was packaged from, not its contents.
+        if path == VCS_INFO_FILE_NAME {
+            continue;
+        }
+        match built.get(path) {
+            Some(built_data) if built_data == data => {}
+            Some(_) => differences.push(format!("    modified: {path}")),
+            None => differences.push(format!("    only in published package: {path}")),
+        }
+    }
+    for path in built.keys() {
+        if path != VCS_INFO_FILE_NAME && !published.contains_key(path) {
+            differences.push(format!("    missing in published package: {path}"));
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+
+    use super::{compare, TarballContents};
+
+    fn contents(files: &[(&str, &str)]) -> TarballContents {
+        files
+            .iter()
+            .map(|(path, data)| (Utf8PathBuf::from(path), data.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn compare_tarballs() {
+        let published = contents(&[
+            ("Scarb.toml", "[package]"),
+            ("src/lib.cairo", "fn f() {}"),
+            ("src/extra.cairo", ""),
+            ("VCS_INFO.json", "{}"),
+        ]);
+        let built = contents(&[
+            ("Scarb.toml", "[package]"),
+            ("src/lib.cairo", "fn g() {}"),
+            ("README.md", ""),
+        ]);
+
+        assert!(compare(&published, &published).is_empty());
+        assert_eq!(
+            compare(&published, &built),
+            vec![
+                "    only in published package: src/extra.cairo",
+                "    modified: src/lib.cairo",
+                "    missing in published package: README.md",
+            ]
+        );
+    }
+}
@@ -1,18 +1,19 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
-use glob::glob;
+use glob::{glob, Pattern};
 use indoc::formatdoc;
 use indoc::indoc;
 use tracing::trace;
 
 use crate::core::config::Config;
+use crate::core::manifest::schema;
 use crate::core::package::Package;
 use crate::core::source::SourceId;
 use crate::core::workspace::Workspace;
-use crate::core::TomlManifest;
+use crate::core::{insert_patches, PackageId, TomlManifest};
 use crate::internal::fsx;
 use crate::internal::fsx::PathBufUtf8Ext;
 use crate::ops::find_workspace_manifest_path;
@@ -22,7 +23,9 @@ use crate::MANIFEST_FILE_NAME;
 #[tracing::instrument(level = "debug", skip(config))]
 pub fn read_workspace<'c>(manifest_path: &Utf8Path, config: &'c Config) -> Result<Workspace<'c>> {
     let source_id = SourceId::for_path(manifest_path)?;
-    read_workspace_impl(manifest_path, source_id, config)
+    let ws = read_workspace_impl(manifest_path, source_id, config)?;
+    warn_unused_manifest_keys(&ws);
+    Ok(ws)
 }
 
 #[tracing::instrument(level = "debug", skip(config))]
@@ -34,6 +37,49 @@ pub fn read_workspace_with_source_id<'c>(
     read_workspace_impl(manifest_path, source_id, config)
 }
 
+/// Read the package at `manifest_path` as a workspace of its own, without looking for
+/// a workspace it might be a member of in parent directories.
+#[tracing::instrument(level = "debug", skip(config))]
+pub fn read_standalone_package<'c>(
+    manifest_path: &Utf8Path,
+    config: &'c Config,
+) -> Result<Workspace<'c>> {
+    let source_id = SourceId::for_path(manifest_path)?;
+    let ws = read_workspace_root(manifest_path, source_id, config)?;
+    warn_unused_manifest_keys(&ws);
+    Ok(ws)
+}
+
+/// Warn about keys in manifests of the workspace which are not used by Scarb, as they are
+/// most likely typos.
+fn warn_unused_manifest_keys(ws: &Workspace<'_>) {
+    let mut manifest_paths = ws
+        .members()
+        .map(|member| member.manifest_path().to_path_buf())
+        .collect::<Vec<_>>();
+    if !manifest_paths.iter().any(|path| path == ws.manifest_path()) {
+        manifest_paths.insert(0, ws.manifest_path().to_path_buf());
+    }
+    for path in manifest_paths {
+        // Manifests have already been parsed successfully, so they are not expected to fail here.
+        let Ok(table) =
+            fsx::read_to_string(&path).and_then(|contents| Ok(contents.parse::<toml::Table>()?))
+        else {
+            continue;
+        };
+        for key in schema::find_unused_keys(&table) {
+            let help = key
+                .suggestion
+                .map(|suggestion| format!("\nhelp: did you mean `{suggestion}`?"))
+                .unwrap_or_default();
+            ws.config().ui().warn(format!(
+                "unused manifest key `{}` in: {path}{help}",
+                key.path
+            ));
+        }
+    }
+}
+
 fn read_workspace_impl<'c>(
     package_manifest: &Utf8Path,
     source_id: SourceId,
@@ -78,6 +124,16 @@ fn read_workspace_root<'c>(
     let toml_manifest = TomlManifest::read_from_path(manifest_path)?;
     let toml_workspace = toml_manifest.get_workspace();
     let profiles = toml_manifest.collect_profiles()?;
+    let mut patch_map = toml_manifest
+        .collect_patches(manifest_path, config)
+        .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
+    insert_patches(
+        &mut patch_map,
+        &config.config_file().patch,
+        manifest_path,
+        config,
+    )
+    .context("failed to load patches from Scarb configuration")?;
 
     let root_package = if toml_manifest.is_package() {
         let manifest = toml_manifest
@@ -97,6 +153,10 @@ fn read_workspace_root<'c>(
         None
     };
 
+    // Collected after the root package, so that errors in profile definitions are reported
+    // along with the manifest path.
+    let env = toml_manifest.collect_env(manifest_path, config.profile())?;
+
     if let Some(workspace) = toml_workspace {
         let workspace_root = manifest_path
             .parent()
@@ -105,12 +165,21 @@ fn read_workspace_root<'c>(
         // Read workspace members.
         let mut packages = workspace
             .members
-            .map(|m| find_member_paths(workspace_root, m, config))
+            .map(|m| {
+                let exclude = workspace.exclude.as_deref().unwrap_or_default();
+                find_member_paths(workspace_root, m, exclude, config)
+            })
             .unwrap_or_else(|| Ok(Vec::new()))?
             .iter()
             .map(AsRef::as_ref)
             .map(|package_path| {
                 let package_manifest = TomlManifest::read_from_path(package_path)?;
+                if package_path != manifest_path && package_manifest.patch.is_some() {
+                    config.ui().warn(format!(
+                        "patch for the non root package will be ignored, \
+                        specify patch at the workspace root: {manifest_path}"
+                    ));
+                }
                 // Read the member package.
                 let manifest = package_manifest
                     .to_manifest(
@@ -132,25 +201,43 @@ fn read_workspace_root<'c>(
             packages.push(p.clone());
             p.id
         });
+        let default_members = workspace
+            .default_members
+            .map(|m| resolve_default_members(workspace_root, m, &packages, config))
+            .transpose()?;
         Workspace::new(
             manifest_path.into(),
             packages.as_ref(),
             root_package,
+            default_members,
+            workspace.scripts.unwrap_or_default(),
             config,
             profiles,
+            workspace.feature_unification.unwrap_or_default(),
+            workspace.allow_prerelease.unwrap_or(false),
+            patch_map,
+            env,
         )
     } else {
         // Read single package workspace
         let package = root_package.ok_or_else(|| anyhow!("the [package] section is missing"))?;
-        Workspace::from_single_package(package, config, profiles)
+        Workspace::from_single_package(package, config, profiles, patch_map, env)
     }
 }
 
 fn find_member_paths(
     root: &Utf8Path,
     globs: Vec<String>,
+    exclude: &[String],
     config: &Config,
 ) -> Result<Vec<Utf8PathBuf>> {
+    let exclude = exclude
+        .iter()
+        .map(|pattern| {
+            Pattern::new(root.join(pattern).as_str())
+                .with_context(|| format!("could not parse pattern: {pattern}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
     let mut paths = Vec::with_capacity(globs.len());
     for pattern in globs {
         for path in glob(root.join(&pattern).as_str())
@@ -162,6 +249,13 @@ fn find_member_paths(
             if is_hidden(path.clone()) {
                 continue;
             }
+            // Skip excluded directories, along with everything inside them.
+            if path
+                .ancestors()
+                .any(|dir| exclude.iter().any(|pattern| pattern.matches_path(dir)))
+            {
+                continue;
+            }
             // Look for manifest file, continuing if it does not exist.
             let path = path.join(MANIFEST_FILE_NAME);
             if path.is_file() {
@@ -179,6 +273,39 @@ fn find_member_paths(
     Ok(paths)
 }
 
+/// Find IDs of members whose directories are listed in the `default-members` field.
+fn resolve_default_members(
+    root: &Utf8Path,
+    globs: Vec<String>,
+    members: &[Package],
+    config: &Config,
+) -> Result<Vec<PackageId>> {
+    let mut default_members = Vec::new();
+    for pattern in globs {
+        let paths = find_member_paths(root, vec![pattern.clone()], &[], config)?;
+        ensure!(
+            !paths.is_empty(),
+            "workspace `default-members` entry `{pattern}` does not match any package"
+        );
+        for path in paths {
+            let member = members
+                .iter()
+                .find(|member| member.manifest_path() == path)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "package `{}` is listed in workspace `default-members` \
+                        but is not a workspace member",
+                        path.parent().unwrap_or(&path)
+                    )
+                })?;
+            if !default_members.contains(&member.id) {
+                default_members.push(member.id);
+            }
+        }
+    }
+    Ok(default_members)
+}
+
 #[tracing::instrument(level = "debug", skip(config))]
 pub fn find_all_workspaces_recursive_with_source_id<'c>(
     root: &Utf8Path,
@@ -0,0 +1,55 @@
+use anyhow::{ensure, Context, Result};
+use semver::Version;
+use url::Url;
+
+use scarb_ui::components::Status;
+
+use crate::core::{Config, PackageId, PackageName, SourceId};
+use crate::sources::RegistrySource;
+
+pub struct YankOpts {
+    pub index_url: Url,
+    pub name: PackageName,
+    pub version: Version,
+    /// Unyank the package version instead.
+    pub undo: bool,
+}
+
+/// Mark a published package version as yanked in the registry, or revert that with `undo`.
+///
+/// Yanked versions stay downloadable, so that packages which already have them pinned
+/// in their lockfiles keep working, but the resolver does not pick them for new dependants.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn yank(opts: YankOpts, config: &Config) -> Result<()> {
+    let source_id = SourceId::for_registry(&opts.index_url)?;
+    let registry_client = RegistrySource::create_client(source_id, config)?;
+
+    let supports_publish = config
+        .tokio_handle()
+        .block_on(registry_client.supports_publish())
+        .with_context(|| format!("failed to check if registry supports yanking: {source_id}"))?;
+    ensure!(
+        supports_publish,
+        "yanking packages is not supported by registry: {source_id}"
+    );
+
+    let package_id = PackageId::new(opts.name, opts.version, source_id);
+
+    if opts.undo {
+        config
+            .ui()
+            .print(Status::new("Unyank", &package_id.to_string()));
+        config
+            .tokio_handle()
+            .block_on(registry_client.unyank(package_id))
+            .with_context(|| format!("failed to unyank package: {package_id}"))
+    } else {
+        config
+            .ui()
+            .print(Status::new("Yank", &package_id.to_string()));
+        config
+            .tokio_handle()
+            .block_on(registry_client.yank(package_id))
+            .with_context(|| format!("failed to yank package: {package_id}"))
+    }
+}
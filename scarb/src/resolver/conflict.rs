@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::core::{ManifestDependency, PackageId};
+
+/// Records which requirement caused each package to be selected, so that conflicts can be
+/// explained in terms of requirements declared by workspace members and their dependencies.
+#[derive(Debug, Default)]
+pub struct Derivations {
+    introduced_by: HashMap<PackageId, Requirement>,
+}
+
+/// A version requirement of `dependant` on another package.
+#[derive(Clone, Debug)]
+pub struct Requirement {
+    pub dependant: PackageId,
+    pub dependency: ManifestDependency,
+}
+
+/// A requirement which is not satisfied by the package selected for it.
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    pub requirement: Requirement,
+    pub selected: PackageId,
+}
+
+impl Derivations {
+    /// Record that `package` has been selected to satisfy `requirement`.
+    ///
+    /// Only the first requirement is remembered for each package, as this is the one which
+    /// determined the selected version.
+    pub fn record(&mut self, package: PackageId, requirement: Requirement) {
+        self.introduced_by.entry(package).or_insert(requirement);
+    }
+
+    /// Chain of requirements which led to selecting `package`, starting from the one declaring
+    /// a dependency on `package` and ending at a workspace member.
+    pub fn chain(&self, package: PackageId) -> Vec<&Requirement> {
+        let mut chain = Vec::new();
+        let mut current = package;
+        while let Some(requirement) = self.introduced_by.get(&current) {
+            // Packages are recorded in order of selection, so chains cannot form cycles,
+            // but make sure this loop always terminates anyway.
+            if chain.len() == self.introduced_by.len() {
+                break;
+            }
+            chain.push(requirement);
+            current = requirement.dependant;
+        }
+        chain
+    }
+
+    /// Render a tree of requirements explaining why `package` has been selected.
+    ///
+    /// Returns an empty string for packages not introduced by any requirement, that is,
+    /// for workspace members.
+    fn explain_selection(&self, package: PackageId, indent: &str) -> String {
+        let chain = self.chain(package);
+        if chain.is_empty() {
+            return String::new();
+        }
+        let mut out = format!("{indent}{} was selected, because:\n", short(package));
+        for (depth, requirement) in chain.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{indent}{}└── {} requires {} {}",
+                "    ".repeat(depth),
+                short(requirement.dependant),
+                requirement.dependency.name,
+                requirement.dependency.version_req,
+            );
+        }
+        out
+    }
+}
+
+impl Conflict {
+    /// Render a derivation tree explaining this conflict: which requirements conflict,
+    /// and which packages introduced them.
+    pub fn explain(&self, derivations: &Derivations) -> String {
+        let Requirement {
+            dependant,
+            dependency,
+        } = &self.requirement;
+        let mut out = format!(
+            "- {dependant} cannot use {}, because {} requires {} {}\n",
+            self.selected, dependant.name, self.selected.name, dependency.version_req
+        );
+        out.push_str(&derivations.explain_selection(self.selected, "  "));
+        out.push_str(&derivations.explain_selection(*dependant, "  "));
+        out
+    }
+}
+
+/// Render package identifier without its source, to keep explanations short.
+fn short(package: PackageId) -> String {
+    format!("{} v{}", package.name, package.version)
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{bail, Result};
+
+use crate::core::{FeatureName, FeatureValue, ManifestDependency, PackageId, PackageName, Summary};
+
+/// Features and optional dependencies enabled in each package of a compilation unit.
+#[derive(Debug, Default)]
+pub struct ResolvedFeatures {
+    pub features: HashMap<PackageId, BTreeSet<FeatureName>>,
+    pub optional_dependencies: HashMap<PackageId, BTreeSet<PackageName>>,
+}
+
+impl ResolvedFeatures {
+    /// Check whether `dependency` of `package_id` is used, that is, either it is not optional,
+    /// or it has been enabled by a feature.
+    pub fn is_dependency_enabled(
+        &self,
+        package_id: PackageId,
+        dependency: &ManifestDependency,
+    ) -> bool {
+        !dependency.optional
+            || self
+                .optional_dependencies
+                .get(&package_id)
+                .is_some_and(|deps| deps.contains(dependency.import_name()))
+    }
+}
+
+/// Computes features enabled in each of a set of packages, possibly starting from multiple roots.
+///
+/// Features propagate to dependencies through `features` and `default-features` fields of
+/// dependency specifications, and through `<dependency>/<feature>` items of feature definitions.
+/// Packages are identified by names they are imported as by their dependants, and by versions
+/// when a package is imported in multiple semver-incompatible versions.
+/// Dependencies missing from the set are skipped.
+pub struct FeatureResolver<'a> {
+    summaries: HashMap<PackageName, Vec<&'a Summary>>,
+    visited: HashSet<PackageId>,
+    resolved: ResolvedFeatures,
+}
+
+impl<'a> FeatureResolver<'a> {
+    pub fn new(summaries: impl IntoIterator<Item = (PackageName, &'a Summary)>) -> Self {
+        let mut by_name: HashMap<PackageName, Vec<&'a Summary>> = HashMap::new();
+        for (name, summary) in summaries {
+            by_name.entry(name).or_default().push(summary);
+        }
+        Self {
+            summaries: by_name,
+            visited: HashSet::new(),
+            resolved: ResolvedFeatures::default(),
+        }
+    }
+
+    pub fn finish(self) -> ResolvedFeatures {
+        self.resolved
+    }
+
+    pub fn enable_package(
+        &mut self,
+        summary: &'a Summary,
+        features: &[FeatureName],
+        default_features: bool,
+    ) -> Result<()> {
+        if self.visited.insert(summary.package_id) {
+            self.resolved
+                .features
+                .entry(summary.package_id)
+                .or_default();
+            for dep in summary.dependencies.iter().filter(|dep| !dep.optional) {
+                self.enable_dependency(dep)?;
+            }
+        }
+
+        if default_features && summary.features.contains_key(&FeatureName::DEFAULT) {
+            self.enable_feature(summary, &FeatureName::DEFAULT)?;
+        }
+        for feature in features {
+            self.enable_feature(summary, feature)?;
+        }
+        Ok(())
+    }
+
+    /// Enable `feature` of the package named `name`, if it is used by already enabled packages.
+    pub fn enable_reachable_feature(
+        &mut self,
+        name: &PackageName,
+        feature: &FeatureName,
+    ) -> Result<()> {
+        let reachable = self
+            .summaries
+            .get(name)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|summary| self.visited.contains(&summary.package_id))
+            .collect::<Vec<_>>();
+        for summary in reachable {
+            self.enable_feature(summary, feature)?;
+        }
+        Ok(())
+    }
+
+    /// Summary of the package used to satisfy `dep`, if it is in the set.
+    fn dependency_summary(&self, dep: &ManifestDependency) -> Option<&'a Summary> {
+        let candidates = self.summaries.get(dep.import_name())?;
+        match candidates.as_slice() {
+            [summary] => Some(*summary),
+            candidates => candidates
+                .iter()
+                .copied()
+                .find(|summary| dep.matches_version(&summary.package_id.version)),
+        }
+    }
+
+    fn enable_dependency(&mut self, dep: &ManifestDependency) -> Result<()> {
+        if let Some(dep_summary) = self.dependency_summary(dep) {
+            self.enable_package(dep_summary, &dep.features, dep.default_features)?;
+        }
+        Ok(())
+    }
+
+    fn enable_optional_dependency(
+        &mut self,
+        summary: &'a Summary,
+        dep_name: &PackageName,
+    ) -> Result<()> {
+        let Some(dep) = summary
+            .dependencies
+            .iter()
+            .find(|dep| dep.optional && dep.import_name() == dep_name)
+        else {
+            return Ok(());
+        };
+
+        let is_new = self
+            .resolved
+            .optional_dependencies
+            .entry(summary.package_id)
+            .or_default()
+            .insert(dep_name.clone());
+        if is_new {
+            self.enable_dependency(dep)?;
+        }
+        Ok(())
+    }
+
+    fn enable_feature(&mut self, summary: &'a Summary, feature: &FeatureName) -> Result<()> {
+        let Some(values) = summary.features.get(feature) else {
+            bail!(
+                "package `{}` does not have feature `{feature}`",
+                summary.package_id
+            );
+        };
+
+        let is_new = self
+            .resolved
+            .features
+            .entry(summary.package_id)
+            .or_default()
+            .insert(feature.clone());
+        if !is_new {
+            return Ok(());
+        }
+
+        for value in values {
+            match value {
+                FeatureValue::Feature(name) => self.enable_feature(summary, name)?,
+                FeatureValue::Dep { dep_name } => {
+                    self.enable_optional_dependency(summary, dep_name)?
+                }
+                FeatureValue::DepFeature {
+                    dep_name,
+                    dep_feature,
+                } => {
+                    self.enable_optional_dependency(summary, dep_name)?;
+                    let dep_summary = summary
+                        .dependencies
+                        .iter()
+                        .find(|dep| dep.import_name() == dep_name)
+                        .and_then(|dep| self.dependency_summary(dep));
+                    if let Some(dep_summary) = dep_summary {
+                        self.enable_feature(dep_summary, dep_feature)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
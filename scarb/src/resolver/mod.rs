@@ -1,16 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use indoc::{formatdoc, indoc};
+use itertools::Itertools;
 use petgraph::graphmap::DiGraphMap;
 use scarb_ui::Ui;
+use semver::Version;
 
 use crate::core::lockfile::Lockfile;
+use crate::core::registry::patch_map::PatchMap;
 use crate::core::registry::Registry;
 use crate::core::resolver::{DependencyEdge, Resolve};
 use crate::core::{
-    DepKind, DependencyVersionReq, ManifestDependency, PackageId, Summary, TargetKind,
+    DepKind, DependencyVersionReq, ManifestDependency, PackageId, PackageName, Summary, TargetKind,
 };
+use crate::ops::FeaturesOpts;
+use crate::resolver::conflict::{Conflict, Derivations, Requirement};
+use crate::resolver::features::{FeatureResolver, ResolvedFeatures};
+
+pub mod conflict;
+pub mod features;
 
 /// Builds the list of all packages required to build the first argument.
 ///
@@ -25,28 +34,64 @@ use crate::core::{
 ///     our requests to it (aka returns the same results for the same query every time).
 ///     It is also advised to implement internal caching, as the resolver may frequently ask
 ///     repetitive queries.
+///
+/// * `patch_map` - dependency overrides declared by the user. These are applied before
+///     consulting the lockfile, so that patched packages are locked under their new sources.
+///
+/// * `features` - features requested for top-level packages, determining which optional
+///     dependencies are used.
+///
+/// * `minimal_versions` - resolve dependencies not pinned by the lockfile to the lowest
+///     versions matching their requirements, instead of the highest ones.
+///
+/// * `allow_prerelease` - let prerelease versions satisfy version requirements of all
+///     dependencies, as if each of them allowed prereleases in its specification.
 #[tracing::instrument(level = "trace", skip_all)]
 pub async fn resolve(
     summaries: &[Summary],
     registry: &dyn Registry,
+    patch_map: &PatchMap,
     lockfile: Lockfile,
+    features: &FeaturesOpts,
+    minimal_versions: bool,
+    allow_prerelease: bool,
     ui: Ui,
 ) -> Result<Resolve> {
     // TODO(#2): This is very bad, use PubGrub here.
     let mut graph = DiGraphMap::<PackageId, DependencyEdge>::new();
 
-    let mut packages: HashMap<_, _> = HashMap::from_iter(
-        summaries
-            .iter()
-            .map(|s| (s.package_id.name.clone(), s.package_id)),
-    );
+    // Packages are selected once per import name and semver-compatible version range, so that
+    // semver-incompatible versions of a package required by different dependants can coexist.
+    let mut packages: HashMap<(PackageName, SemverCompatibility), PackageId> =
+        HashMap::from_iter(summaries.iter().map(|s| {
+            let key = (
+                s.package_id.name.clone(),
+                SemverCompatibility::of(&s.package_id.version),
+            );
+            (key, s.package_id)
+        }));
+    // Package selected for each dependency of each package, by the dependency import name.
+    let mut selected: HashMap<(PackageId, PackageName), PackageId> = HashMap::new();
 
     let mut summaries: HashMap<_, _> = summaries
         .iter()
         .map(|s| (s.package_id, s.clone()))
         .collect();
 
-    let mut queue: Vec<PackageId> = summaries.keys().copied().collect();
+    let members: Vec<PackageId> = summaries.keys().copied().collect();
+    let mut import_names: HashMap<PackageId, PackageName> = members
+        .iter()
+        .map(|member| (*member, member.name.clone()))
+        .collect();
+    // Dependencies specific to target kinds are only used by workspace members.
+    let is_dependency_used = |package_id: PackageId, dep: &ManifestDependency| {
+        dep.kind == DepKind::Normal || members.contains(&package_id)
+    };
+    let mut resolved_features = ResolvedFeatures::default();
+    let mut derivations = Derivations::default();
+    let mut visited_deps: HashSet<(PackageId, ManifestDependency)> = HashSet::new();
+
+    let mut queue: Vec<PackageId> = members.clone();
     while !queue.is_empty() {
         let mut next_queue = Vec::new();
 
@@ -54,7 +99,21 @@ pub async fn resolve(
             graph.add_node(package_id);
 
             for dep in summaries[&package_id].clone().full_dependencies() {
-                let dep = rewrite_dependency_source_id(registry, &package_id, dep).await?;
+                if !is_dependency_used(package_id, dep)
+                    || !resolved_features.is_dependency_enabled(package_id, dep)
+                    || !visited_deps.insert((package_id, dep.clone()))
+                {
+                    continue;
+                }
+
+                let requirement = Requirement {
+                    dependant: package_id,
+                    dependency: dep.clone(),
+                };
+                let import_name = dep.import_name().clone();
+                let dep = apply_patch(patch_map, dep);
+                let dep = rewrite_dependency_source_id(registry, &package_id, &dep).await?;
+                let dep = apply_prerelease_policy(&dep, allow_prerelease, &lockfile);
 
                 let locked_package_id = lockfile.packages_matching(dep.clone());
                 let dep = if let Some(locked_package_id) = locked_package_id {
@@ -65,10 +124,21 @@ pub async fn resolve(
 
                 let results = registry.query(&dep).await?;
 
-                let Some(dep_summary) = results.first() else {
+                let dep_summary = if minimal_versions {
+                    results
+                        .iter()
+                        .min_by_key(|summary| &summary.package_id.version)
+                } else {
+                    results
+                        .iter()
+                        .max_by_key(|summary| &summary.package_id.version)
+                };
+                let Some(dep_summary) = dep_summary else {
                     bail!("cannot find package {}", dep.name)
                 };
 
+                verify_locked_checksum(&lockfile, dep_summary)?;
+
                 let dep_target_kind: Option<TargetKind> = match dep.kind.clone() {
                     DepKind::Normal => None,
                     DepKind::Target(target_kind) => Some(target_kind),
@@ -84,7 +154,11 @@ pub async fn resolve(
                     ));
                 }
 
-                if let Some(existing) = packages.get(dep.name.as_ref()) {
+                if let Some(existing) = packages
+                    .iter()
+                    .find(|((name, _), _)| *name == import_name)
+                    .map(|(_, package_id)| package_id)
+                {
                     if existing.source_id != dep.source_id {
                         bail!(
                             indoc! {"
@@ -93,62 +167,184 @@ pub async fn resolve(
                             source 1: {}
                             source 2: {}
                             "},
-                            dep.name,
+                            import_name,
                             existing.source_id,
                             dep.source_id
                         );
                     }
                 }
 
+                // Reuse the package already selected for a compatible requirement, if any.
+                let key = (import_name.clone(), SemverCompatibility::of(&dep.version));
+                let already_selected = packages.get(&key).copied();
+                let dep = already_selected.unwrap_or(dep);
+
                 let weight = graph
                     .edge_weight(package_id, dep)
                     .cloned()
                     .unwrap_or_default();
                 let weight = weight.extend(dep_target_kind);
                 graph.add_edge(package_id, dep, weight);
-                summaries.insert(dep, dep_summary.clone());
+                selected
+                    .entry((package_id, import_name.clone()))
+                    .or_insert(dep);
+
+                if let Some(existing) = import_names.insert(dep, import_name.clone()) {
+                    ensure!(
+                        existing == import_name,
+                        "package `{dep}` is imported under different names: \
+                        `{existing}` and `{import_name}`"
+                    );
+                }
 
-                if packages.contains_key(dep.name.as_ref()) {
+                if already_selected.is_some() {
                     continue;
                 }
 
-                packages.insert(dep.name.clone(), dep);
+                summaries.insert(dep, dep_summary.clone());
+                packages.insert(key, dep);
+                derivations.record(dep, requirement);
                 next_queue.push(dep);
             }
         }
 
         queue = next_queue;
+
+        // Optional dependencies are enabled by features, which in turn can be enabled by
+        // packages found in this pass. Revisit packages with newly enabled dependencies,
+        // until there are none left.
+        if queue.is_empty() {
+            resolved_features =
+                resolve_workspace_features(&members, &summaries, &import_names, features)?;
+            queue = summaries
+                .values()
+                .filter(|summary| {
+                    summary.dependencies.iter().any(|dep| {
+                        is_dependency_used(summary.package_id, dep)
+                            && resolved_features.is_dependency_enabled(summary.package_id, dep)
+                            && !visited_deps.contains(&(summary.package_id, dep.clone()))
+                    })
+                })
+                .map(|summary| summary.package_id)
+                .collect();
+        }
     }
 
     // Detect incompatibilities and bail in case ones are found.
-    let mut incompatibilities = Vec::new();
+    let mut conflicts = Vec::new();
     for from_package in graph.nodes() {
-        for manifest_dependency in summaries[&from_package].full_dependencies() {
-            let to_package = packages[&manifest_dependency.name];
-            if !manifest_dependency.matches_package_id(to_package) {
-                let message = format!(
-                    "- {from_package} cannot use {to_package}, because {} requires {} {}",
-                    from_package.name, to_package.name, manifest_dependency.version_req
-                );
-                incompatibilities.push(message);
+        for manifest_dependency in summaries[&from_package]
+            .full_dependencies()
+            .filter(|dep| is_dependency_used(from_package, dep))
+            .filter(|dep| resolved_features.is_dependency_enabled(from_package, dep))
+        {
+            let to_package = selected[&(from_package, manifest_dependency.import_name().clone())];
+            if !apply_prerelease_policy(manifest_dependency, allow_prerelease, &lockfile)
+                .matches_package_id(to_package)
+            {
+                conflicts.push(Conflict {
+                    requirement: Requirement {
+                        dependant: from_package,
+                        dependency: manifest_dependency.clone(),
+                    },
+                    selected: to_package,
+                });
             }
         }
     }
 
-    if !incompatibilities.is_empty() {
-        incompatibilities.sort();
-        let incompatibilities = incompatibilities.join("\n");
+    if !conflicts.is_empty() {
+        let incompatibilities = conflicts
+            .iter()
+            .map(|conflict| conflict.explain(&derivations))
+            .sorted()
+            .collect::<String>();
         bail!(formatdoc! {"
             Version solving failed:
             {incompatibilities}
-
             Scarb does not have real version solving algorithm yet.
             Perhaps in the future this conflict could be resolved, but currently,
             please upgrade your dependencies to use latest versions of their dependencies.
         "});
     }
 
-    Ok(Resolve { graph })
+    let renames = import_names
+        .into_iter()
+        .filter(|(package_id, name)| package_id.name != *name)
+        .collect();
+
+    Ok(Resolve {
+        graph,
+        summaries,
+        renames,
+    })
+}
+
+/// Range of versions considered compatible with each other according to semver rules:
+/// versions sharing the first non-zero component of `major.minor.patch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SemverCompatibility {
+    Major(u64),
+    Minor(u64),
+    Patch(u64),
+}
+
+impl SemverCompatibility {
+    fn of(version: &Version) -> Self {
+        match (version.major, version.minor) {
+            (0, 0) => Self::Patch(version.patch),
+            (0, minor) => Self::Minor(minor),
+            (major, _) => Self::Major(major),
+        }
+    }
+}
+
+/// Compute features enabled across the workspace, when `features` are requested for `members`.
+fn resolve_workspace_features(
+    members: &[PackageId],
+    summaries: &HashMap<PackageId, Summary>,
+    import_names: &HashMap<PackageId, PackageName>,
+    features: &FeaturesOpts,
+) -> Result<ResolvedFeatures> {
+    let mut resolver = FeatureResolver::new(
+        summaries
+            .values()
+            .map(|summary| (import_names[&summary.package_id].clone(), summary)),
+    );
+    for member in members {
+        features.enable(&mut resolver, &summaries[member])?;
+    }
+    Ok(resolver.finish())
+}
+
+/// Ensure that the package checksum recorded in the lockfile, if any, matches the one
+/// reported by the source now.
+fn verify_locked_checksum(lockfile: &Lockfile, summary: &Summary) -> Result<()> {
+    let package_id = summary.package_id;
+    let Some(locked) = lockfile
+        .package(package_id)
+        .and_then(|lock| lock.checksum.as_ref())
+    else {
+        return Ok(());
+    };
+    let Some(actual) = &summary.checksum else {
+        return Ok(());
+    };
+    ensure!(
+        locked == actual,
+        formatdoc! {"
+            checksum for `{package_id}` changed between lock files
+
+            this could be indicative of a few possible errors:
+                * the lock file is corrupt
+                * the registry has been tampered with
+                * the package has been republished with different contents
+
+            expected: {locked}
+            actual:   {actual}
+        "}
+    );
+    Ok(())
 }
 
 fn rewrite_locked_dependency(
@@ -158,6 +354,7 @@ fn rewrite_locked_dependency(
     ManifestDependency::builder()
         .kind(dependency.kind.clone())
         .name(dependency.name.clone())
+        .rename(dependency.rename.clone())
         .source_id(locked_package_id.source_id)
         .version_req(DependencyVersionReq::Locked {
             exact: locked_package_id.version.clone(),
@@ -166,6 +363,56 @@ fn rewrite_locked_dependency(
         .build()
 }
 
+/// Replace source and version requirement of `dependency` with ones of its patch, if any.
+///
+/// Other properties, like dependency kind or requested features, are kept as specified by
+/// the dependant.
+fn apply_patch(patch_map: &PatchMap, dependency: &ManifestDependency) -> ManifestDependency {
+    let patch = patch_map.lookup(dependency);
+    if patch == dependency {
+        return dependency.clone();
+    }
+
+    ManifestDependency::builder()
+        .kind(dependency.kind.clone())
+        .name(dependency.name.clone())
+        .rename(dependency.rename.clone())
+        .source_id(patch.source_id)
+        .version_req(patch.version_req.clone())
+        .features(dependency.features.clone())
+        .default_features(dependency.default_features)
+        .optional(dependency.optional)
+        .allow_prerelease(patch.allow_prerelease)
+        .build()
+}
+
+/// Let prerelease versions satisfy `dependency`, if requested for the whole resolution,
+/// or if the lockfile pins a prerelease version which would satisfy it.
+///
+/// The latter keeps prerelease versions selected once, for example by `scarb update --pre`,
+/// locked in subsequent resolutions.
+fn apply_prerelease_policy(
+    dependency: &ManifestDependency,
+    allow_prerelease: bool,
+    lockfile: &Lockfile,
+) -> ManifestDependency {
+    if dependency.allow_prerelease {
+        return dependency.clone();
+    }
+    let allowing = dependency.allowing_prerelease();
+    let is_prerelease_locked = || {
+        lockfile.packages().any(|package| {
+            !package.version.pre.is_empty()
+                && allowing.matches_name_and_version(&package.name, &package.version)
+        })
+    };
+    if allow_prerelease || is_prerelease_locked() {
+        allowing
+    } else {
+        dependency.clone()
+    }
+}
+
 async fn rewrite_dependency_source_id(
     registry: &dyn Registry,
     package_id: &PackageId,
@@ -176,8 +423,10 @@ async fn rewrite_dependency_source_id(
         let rewritten_dep = ManifestDependency::builder()
             .kind(dependency.kind.clone())
             .name(dependency.name.clone())
+            .rename(dependency.rename.clone())
             .source_id(package_id.source_id)
             .version_req(dependency.version_req.clone())
+            .allow_prerelease(dependency.allow_prerelease)
             .build();
         // Check if this dependency can be queried from git source.
         // E.g. packages below other package's manifest will not be accessible.
@@ -201,14 +450,16 @@ mod tests {
     use itertools::Itertools;
     use scarb_ui::Verbosity::Verbose;
     use scarb_ui::{OutputFormat, Ui};
-    use semver::Version;
+    use semver::{Version, VersionReq};
     use similar_asserts::assert_serde_eq;
     use tokio::runtime::Builder;
 
     use crate::core::lockfile::{Lockfile, PackageLock};
     use crate::core::package::PackageName;
     use crate::core::registry::mock::{deps, locks, pkgs, registry, MockRegistry};
+    use crate::core::registry::patch_map::PatchMap;
     use crate::core::{ManifestDependency, PackageId, Resolve, SourceId, TargetKind};
+    use crate::ops::FeaturesOpts;
 
     fn check(
         registry: MockRegistry,
@@ -223,6 +474,17 @@ mod tests {
         roots: &[&[ManifestDependency]],
         locks: &[PackageLock],
         expected: Result<&[PackageId], &str>,
+    ) {
+        check_with_opts(registry, roots, locks, false, false, expected)
+    }
+
+    fn check_with_opts(
+        registry: MockRegistry,
+        roots: &[&[ManifestDependency]],
+        locks: &[PackageLock],
+        minimal_versions: bool,
+        allow_prerelease: bool,
+        expected: Result<&[PackageId], &str>,
     ) {
         let root_ids = (1..).map(|n| package_id(format!("root_{n}")));
 
@@ -232,7 +494,7 @@ mod tests {
             .map(|(&deps, pid)| (deps, pid))
             .collect_vec();
 
-        let resolve = resolve_with_lock(registry, roots, locks);
+        let resolve = resolve_with_lock(registry, roots, locks, minimal_versions, allow_prerelease);
 
         let resolve = resolve
             .map(|r| {
@@ -260,13 +522,15 @@ mod tests {
         registry: MockRegistry,
         roots: Vec<(&[ManifestDependency], PackageId)>,
     ) -> Result<Resolve> {
-        resolve_with_lock(registry, roots, &[])
+        resolve_with_lock(registry, roots, &[], false, false)
     }
 
     fn resolve_with_lock(
         mut registry: MockRegistry,
         roots: Vec<(&[ManifestDependency], PackageId)>,
         locks: &[PackageLock],
+        minimal_versions: bool,
+        allow_prerelease: bool,
     ) -> Result<Resolve> {
         let runtime = Builder::new_multi_thread().build().unwrap();
 
@@ -285,7 +549,17 @@ mod tests {
 
         let lockfile = Lockfile::new(locks.iter().cloned());
         let ui = Ui::new(Verbose, OutputFormat::Text);
-        runtime.block_on(super::resolve(&summaries, &registry, lockfile, ui))
+        let features = FeaturesOpts::default();
+        runtime.block_on(super::resolve(
+            &summaries,
+            &registry,
+            &PatchMap::new(),
+            lockfile,
+            &features,
+            minimal_versions,
+            allow_prerelease,
+            ui,
+        ))
     }
 
     fn package_id<S: AsRef<str>>(name: S) -> PackageId {
@@ -418,14 +692,12 @@ mod tests {
             //     "baz v1.0.0",
             //     "foo v1.0.0"
             // ]),
-            Err(indoc! {"
-            Version solving failed:
-            - bar v2.0.0 cannot use baz v1.0.0, because bar requires baz ^2.0.0
-
-            Scarb does not have real version solving algorithm yet.
-            Perhaps in the future this conflict could be resolved, but currently,
-            please upgrade your dependencies to use latest versions of their dependencies.
-            "}),
+            Ok(pkgs![
+                "bar v2.0.0",
+                "baz v1.0.0",
+                "baz v2.0.0",
+                "foo v2.0.0"
+            ]),
         )
     }
 
@@ -450,14 +722,12 @@ mod tests {
             //     "baz v1.8.0",
             //     "foo v2.9.0"
             // ]),
-            Err(indoc! {"
-            Version solving failed:
-            - foo v2.7.0 cannot use baz v2.1.0, because foo requires baz ~1.7.1
-
-            Scarb does not have real version solving algorithm yet.
-            Perhaps in the future this conflict could be resolved, but currently,
-            please upgrade your dependencies to use latest versions of their dependencies.
-            "}),
+            Ok(pkgs![
+                "bar v1.1.1",
+                "baz v1.7.1",
+                "baz v2.1.0",
+                "foo v2.7.0"
+            ]),
         )
     }
 
@@ -497,9 +767,47 @@ mod tests {
                 ("top2 v1.0.0", [("foo", "2.0.0")]),
             ],
             &[deps![("top1", "1"), ("top2", "1")]],
+            Ok(pkgs![
+                "foo v1.0.0",
+                "foo v2.0.0",
+                "top1 v1.0.0",
+                "top2 v1.0.0"
+            ]),
+        )
+    }
+
+    #[test]
+    fn sub_dependencies_with_compatible_versions() {
+        check(
+            registry![
+                ("foo v1.0.0", []),
+                ("foo v1.1.0", []),
+                ("top1 v1.0.0", [("foo", "1.0.0")]),
+                ("top2 v1.0.0", [("foo", "1.1.0")]),
+            ],
+            &[deps![("top1", "1"), ("top2", "1")]],
+            Ok(pkgs!["foo v1.1.0", "top1 v1.0.0", "top2 v1.0.0"]),
+        )
+    }
+
+    #[test]
+    fn sub_dependencies_conflict() {
+        check(
+            registry![
+                ("foo v1.0.0", []),
+                ("foo v1.1.0", []),
+                ("top1 v1.0.0", [("foo", "1.1.0")]),
+                ("top2 v1.0.0", [("foo", "=1.0.0")]),
+            ],
+            &[deps![("top1", "1"), ("top2", "1")]],
             Err(indoc! {"
             Version solving failed:
-            - top2 v1.0.0 cannot use foo v1.0.0, because top2 requires foo ^2.0.0
+            - top2 v1.0.0 cannot use foo v1.1.0, because top2 requires foo =1.0.0
+              foo v1.1.0 was selected, because:
+              └── top1 v1.0.0 requires foo ^1.1.0
+                  └── root_1 v1.0.0 requires top1 ^1
+              top2 v1.0.0 was selected, because:
+              └── root_1 v1.0.0 requires top2 ^1
 
             Scarb does not have real version solving algorithm yet.
             Perhaps in the future this conflict could be resolved, but currently,
@@ -508,6 +816,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn sub_dependencies_with_incompatible_zero_versions() {
+        check(
+            registry![
+                ("foo v0.1.0", []),
+                ("foo v0.2.0", []),
+                ("foo v0.0.3", []),
+                ("top1 v1.0.0", [("foo", "0.1.0")]),
+                ("top2 v1.0.0", [("foo", "0.2.0")]),
+                ("top3 v1.0.0", [("foo", "0.0.3")]),
+            ],
+            &[deps![("top1", "1"), ("top2", "1"), ("top3", "1")]],
+            Ok(pkgs![
+                "foo v0.0.3",
+                "foo v0.1.0",
+                "foo v0.2.0",
+                "top1 v1.0.0",
+                "top2 v1.0.0",
+                "top3 v1.0.0"
+            ]),
+        )
+    }
+
     #[test]
     fn missing_dependency() {
         check(
@@ -646,6 +977,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn target_kind_deps_of_dependencies_are_not_resolved() {
+        check(
+            registry![
+                ("foo v1.0.0", [("boo", "1.0.0", (), "test")]),
+                ("boo v1.0.0", [])
+            ],
+            &[deps![("foo", "1.0.0")]],
+            Ok(pkgs!["foo v1.0.0"]),
+        );
+    }
+
+    #[test]
+    fn minimal_versions() {
+        check_with_opts(
+            registry![
+                ("foo v1.0.0", []),
+                ("foo v1.1.0", [("bar", "^1.1")]),
+                ("foo v1.2.0", []),
+                ("bar v1.0.0", []),
+                ("bar v1.1.0", []),
+                ("bar v1.1.5", []),
+            ],
+            &[deps![("foo", ">=1.1")]],
+            &[],
+            true,
+            false,
+            Ok(pkgs!["bar v1.1.0", "foo v1.1.0"]),
+        );
+    }
+
+    #[test]
+    fn minimal_versions_respect_lock() {
+        check_with_opts(
+            registry![("foo v1.0.0", []), ("foo v1.0.1", [])],
+            &[deps![("foo", "1")]],
+            locks![("foo v1.0.1", [])],
+            true,
+            false,
+            Ok(pkgs!["foo v1.0.1"]),
+        );
+    }
+
+    #[test]
+    fn allow_prerelease() {
+        check_with_opts(
+            registry![
+                ("foo v1.0.0", []),
+                ("foo v1.1.0-rc.1", []),
+                ("foo v2.0.0-rc.1", []),
+                ("bar v1.0.0", []),
+                ("bar v1.0.1-beta", []),
+            ],
+            &[deps![("foo", "1"), ("bar", "1.0")]],
+            &[],
+            false,
+            true,
+            Ok(pkgs!["bar v1.0.1-beta", "foo v1.1.0-rc.1"]),
+        );
+    }
+
+    #[test]
+    fn prerelease_allowed_by_dependency() {
+        let registry = registry![
+            ("foo v1.0.0", []),
+            ("foo v1.1.0-rc.1", []),
+            ("bar v1.0.0", []),
+            ("bar v1.1.0-rc.1", []),
+        ];
+        let foo = ManifestDependency::builder()
+            .name(PackageName::new("foo"))
+            .version_req(VersionReq::parse("1").unwrap().into())
+            .source_id(SourceId::default_registry())
+            .allow_prerelease(true)
+            .build();
+        let deps = [foo, deps![("bar", "1")][0].clone()];
+        check(
+            registry,
+            &[&deps],
+            Ok(pkgs!["bar v1.0.0", "foo v1.1.0-rc.1"]),
+        );
+    }
+
+    #[test]
+    fn locked_prerelease_is_kept() {
+        check_with_lock(
+            registry![("foo v1.0.0", []), ("foo v1.1.0-rc.1", [])],
+            &[deps![("foo", "1")]],
+            locks![("foo v1.1.0-rc.1", [])],
+            Ok(pkgs!["foo v1.1.0-rc.1"]),
+        );
+    }
+
     #[test]
     fn lock_dependency() {
         check_with_lock(
@@ -330,7 +330,7 @@ fn collect_refspecs(reference: &GitReference) -> (Vec<String>, bool) {
     }
 }
 
-fn git_command() -> Command {
+pub(crate) fn git_command() -> Command {
     let mut cmd = Command::new("git");
 
     // If Scarb is run by Git (for example, the `exec` command in `git rebase`),
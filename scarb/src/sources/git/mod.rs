@@ -2,12 +2,13 @@ use std::{fmt, mem};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use camino::Utf8PathBuf;
 use tokio::sync::OnceCell;
 use tokio::task::spawn_blocking;
 use url::Url;
 
 use canonical_url::CanonicalUrl;
-use client::{GitRemote, Rev};
+pub(crate) use client::{GitRemote, Rev};
 use scarb_ui::components::Status;
 
 use crate::core::source::Source;
@@ -19,7 +20,7 @@ use crate::sources::git::client::GitDatabase;
 use super::PathSource;
 
 pub mod canonical_url;
-mod client;
+pub(crate) mod client;
 
 pub struct GitSource<'c> {
     source_id: SourceId,
@@ -95,53 +96,11 @@ impl<'c> GitSource<'c> {
             locked_rev: Option<Rev>,
             config: &Config,
         ) -> Result<InnerState<'_>> {
-            let remote_ident = remote.ident();
-
-            let git_fs = config.dirs().registry_dir().into_child("git");
-
-            let db_fs = git_fs
-                .child("db")
-                .into_child(&format!("{remote_ident}.git"));
-
-            let db = GitDatabase::open(&remote, &db_fs).ok();
-            let (db, actual_rev) = match (db, locked_rev) {
-                // If we have a locked revision, and we have a preexisting database
-                // which has that revision, then no update needs to happen.
-                (Some(db), Some(rev)) if db.contains(rev) => (db, rev),
-
-                // If Scarb is in offline mode, source is not locked to particular revision,
-                // and there is a functional database, then try to resolve our reference
-                // with the preexisting repository.
-                (Some(db), None) if !config.network_allowed() => {
-                    let rev = db.resolve(&requested_reference).context(
-                        "failed to lookup reference in preexisting repository, and \
-                        cannot check for updates in offline mode (--offline)",
-                    )?;
-                    (db, rev)
-                }
-
-                // Now we can freely update the database.
-                (db, locked_rev) => {
-                    // The actual error will be produced by `checkout`.
-                    if config.network_allowed() {
-                        config
-                            .ui()
-                            .print(Status::new("Updating", &format!("git repository {remote}")));
-                    }
-
-                    remote.checkout(&db_fs, db, &requested_reference, locked_rev, config)?
-                }
-            };
-
-            let checkout_fs = git_fs
-                .child("checkouts")
-                .into_child(&remote_ident)
-                .into_child(db.short_id_of(actual_rev)?);
-
-            let checkout = db.copy_to(&checkout_fs, actual_rev, config)?;
+            let (location, actual_rev) =
+                checkout(&remote, &requested_reference, locked_rev, config)?;
             let source_id = source_id.with_precise(actual_rev.to_string())?;
 
-            let path_source = PathSource::recursive_at(&checkout.location, source_id, config);
+            let path_source = PathSource::recursive_at(&location, source_id, config);
 
             Ok(InnerState {
                 path_source,
@@ -151,6 +110,64 @@ impl<'c> GitSource<'c> {
     }
 }
 
+/// Fetch `reference` of `remote` into the Git database cache, and check it out.
+///
+/// Returns the location of the checkout and the revision it points to.
+/// In offline mode, the reference is resolved within a previously fetched database, if any.
+/// The package cache lock must be held by the caller.
+pub(crate) fn checkout(
+    remote: &GitRemote,
+    reference: &GitReference,
+    locked_rev: Option<Rev>,
+    config: &Config,
+) -> Result<(Utf8PathBuf, Rev)> {
+    let remote_ident = remote.ident();
+
+    let git_fs = config.dirs().registry_dir().into_child("git");
+
+    let db_fs = git_fs
+        .child("db")
+        .into_child(&format!("{remote_ident}.git"));
+
+    let db = GitDatabase::open(remote, &db_fs).ok();
+    let (db, actual_rev) = match (db, locked_rev) {
+        // If we have a locked revision, and we have a preexisting database
+        // which has that revision, then no update needs to happen.
+        (Some(db), Some(rev)) if db.contains(rev) => (db, rev),
+
+        // If Scarb is in offline mode, source is not locked to particular revision,
+        // and there is a functional database, then try to resolve our reference
+        // with the preexisting repository.
+        (Some(db), None) if !config.network_allowed() => {
+            let rev = db.resolve(reference).context(
+                "failed to lookup reference in preexisting repository, and \
+                cannot check for updates in offline mode (--offline)",
+            )?;
+            (db, rev)
+        }
+
+        // Now we can freely update the database.
+        (db, locked_rev) => {
+            // The actual error will be produced by `checkout`.
+            if config.network_allowed() {
+                config
+                    .ui()
+                    .print(Status::new("Updating", &format!("git repository {remote}")));
+            }
+
+            remote.checkout(&db_fs, db, reference, locked_rev, config)?
+        }
+    };
+
+    let checkout_fs = git_fs
+        .child("checkouts")
+        .into_child(&remote_ident)
+        .into_child(db.short_id_of(actual_rev)?);
+
+    let checkout = db.copy_to(&checkout_fs, actual_rev, config)?;
+    Ok((checkout.location, actual_rev))
+}
+
 #[async_trait]
 impl<'c> Source for GitSource<'c> {
     async fn query(&self, dependency: &ManifestDependency) -> Result<Vec<Summary>> {
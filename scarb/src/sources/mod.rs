@@ -3,7 +3,7 @@ pub use path::*;
 pub use registry::*;
 pub use standard_lib::*;
 
-mod git;
+pub(crate) mod git;
 mod path;
 mod registry;
 mod standard_lib;
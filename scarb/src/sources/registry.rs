@@ -1,37 +1,64 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::io;
+use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
 use async_trait::async_trait;
-use tracing::trace;
+use futures::StreamExt;
+use indoc::formatdoc;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use tracing::{debug, trace};
 
-use scarb_ui::components::Status;
+use scarb_ui::components::{ProgressBar, Status};
 
+use crate::core::registry::client::cache::CachingRegistryClient;
+use crate::core::registry::client::directory::{DirectoryRegistryClient, DIRECTORY_SCHEME_PREFIX};
+use crate::core::registry::client::git::{GitIndexRegistryClient, GIT_INDEX_SCHEME_PREFIX};
 use crate::core::registry::client::http::HttpRegistryClient;
 use crate::core::registry::client::local::LocalRegistryClient;
-use crate::core::registry::client::RegistryClient;
+use crate::core::registry::client::mirrored::MirroredRegistryClient;
+use crate::core::registry::client::object_storage::ObjectStorageRegistryClient;
+use crate::core::registry::client::oci::OciRegistryClient;
+use crate::core::registry::client::{DownloadProgress, NoProgress, RegistryClient, TarballStream};
 use crate::core::registry::index::IndexRecord;
 use crate::core::registry::package_source_store::PackageSourceStore;
+use crate::core::registry::provenance::verify_provenance;
+use crate::core::registry::signature::verify_signature;
 use crate::core::source::Source;
 use crate::core::{
-    Config, DependencyVersionReq, ManifestDependency, Package, PackageId, SourceId, Summary,
-    TargetKind,
+    Checksum, Config, DependencyVersionReq, Digest, ManifestDependency, Package, PackageId,
+    SourceId, Summary, TargetKind,
 };
+use crate::internal::channel_reader::ChannelReader;
+use crate::internal::fsx;
+use crate::internal::fsx::PathBufUtf8Ext;
 use crate::sources::PathSource;
 
+/// Number of downloaded chunks buffered in memory when unpacking packages during download.
+const STREAM_BUFFER_CHUNKS: usize = 16;
+
 pub struct RegistrySource<'c> {
     source_id: SourceId,
     config: &'c Config,
     client: Box<dyn RegistryClient + 'c>,
     package_sources: PackageSourceStore<'c>,
+    /// Index records of packages queried so far, used to verify downloads.
+    records: Mutex<HashMap<PackageId, IndexRecord>>,
 }
 
 impl<'c> RegistrySource<'c> {
     pub fn new(source_id: SourceId, config: &'c Config) -> Result<Self> {
-        let client = Self::create_client(source_id, config)?;
+        // Packages keep being identified by the original source, only the client is replaced.
+        let client_source_id = config
+            .config_file()
+            .replacement_for(source_id)?
+            .unwrap_or(source_id);
+        let client = Self::create_client(client_source_id, config)?;
 
-        // TODO(mkaput): Wrap remote clients in a disk caching layer.
         // TODO(mkaput): Wrap all clients in an in-memory caching layer.
 
         let package_sources = PackageSourceStore::new(source_id, config);
@@ -41,12 +68,52 @@ impl<'c> RegistrySource<'c> {
             config,
             client,
             package_sources,
+            records: Default::default(),
         })
     }
 
     pub fn create_client(
         source_id: SourceId,
         config: &'c Config,
+    ) -> Result<Box<dyn RegistryClient + 'c>> {
+        let client = Self::create_uncached_client(source_id, config)?;
+        if client.is_offline() {
+            return Ok(client);
+        }
+        Ok(Box::new(CachingRegistryClient::new(
+            source_id, config, client,
+        )))
+    }
+
+    /// Create a client which always asks the registry, bypassing cached missing packages.
+    pub fn create_uncached_client(
+        source_id: SourceId,
+        config: &'c Config,
+    ) -> Result<Box<dyn RegistryClient + 'c>> {
+        let client = Self::create_protocol_client(source_id, config)?;
+
+        let mirrors = config
+            .config_file()
+            .registry_for(source_id)
+            .map(|(_, registry)| registry.mirrors.as_slice())
+            .unwrap_or_default();
+        if mirrors.is_empty() {
+            return Ok(client);
+        }
+
+        let mut clients = vec![(source_id, client)];
+        for mirror in mirrors {
+            let mirror_source_id = SourceId::for_registry(mirror)?;
+            trace!("adding registry mirror for {source_id}: {mirror_source_id}");
+            let mirror_client = Self::create_protocol_client(mirror_source_id, config)?;
+            clients.push((mirror_source_id, mirror_client));
+        }
+        Ok(Box::new(MirroredRegistryClient::new(config, clients)))
+    }
+
+    fn create_protocol_client(
+        source_id: SourceId,
+        config: &'c Config,
     ) -> Result<Box<dyn RegistryClient + 'c>> {
         assert!(source_id.is_registry());
         match source_id.url.scheme() {
@@ -62,6 +129,24 @@ impl<'c> RegistrySource<'c> {
                 trace!("creating http registry client for: {source_id}");
                 Ok(Box::new(HttpRegistryClient::new(source_id, config)?))
             }
+            "s3" | "gs" | "gcs" => {
+                trace!("creating object storage registry client for: {source_id}");
+                Ok(Box::new(ObjectStorageRegistryClient::new(
+                    source_id, config,
+                )?))
+            }
+            scheme if scheme.starts_with(DIRECTORY_SCHEME_PREFIX) => {
+                trace!("creating directory registry client for: {source_id}");
+                Ok(Box::new(DirectoryRegistryClient::new(source_id)?))
+            }
+            scheme if scheme.starts_with(GIT_INDEX_SCHEME_PREFIX) => {
+                trace!("creating git index registry client for: {source_id}");
+                Ok(Box::new(GitIndexRegistryClient::new(source_id, config)?))
+            }
+            "oci" => {
+                trace!("creating oci registry client for: {source_id}");
+                Ok(Box::new(OciRegistryClient::new(source_id, config)?))
+            }
             _ => {
                 bail!("unsupported registry protocol: {source_id}")
             }
@@ -102,6 +187,10 @@ impl<'c> Source for RegistrySource<'c> {
                         .name(index_dep.name.clone())
                         .version_req(DependencyVersionReq::from(index_dep.req.clone()))
                         .source_id(self.source_id)
+                        .features(index_dep.features.clone())
+                        .default_features(index_dep.default_features)
+                        .optional(index_dep.optional)
+                        .rename(index_dep.rename.clone())
                         .build()
                 })
                 .collect();
@@ -111,54 +200,326 @@ impl<'c> Source for RegistrySource<'c> {
                 .dependencies(dependencies)
                 .target_kinds(HashSet::from_iter([TargetKind::LIB]))
                 .no_core(record.no_core)
+                .checksum(Some(record.checksum.clone()))
+                .features(record.features.clone())
                 .build()
         };
 
-        // TODO(mkaput): Save checksums out-of-band for later use.
-
-        Ok(records
+        let matching_records: Vec<&IndexRecord> = records
             .iter()
             // NOTE: We filter based on IndexRecords here, to avoid unnecessarily allocating
             //   PackageIds just to abandon them soon after.
-            .filter(|record| dependency.version_req.matches(&record.version))
-            .map(build_summary_from_index_record)
-            .collect())
+            .filter(|record| dependency.matches_version(&record.version))
+            // Yanked versions can only be used if they are already pinned in the lockfile.
+            .filter(|record| {
+                !record.yanked
+                    || matches!(dependency.version_req, DependencyVersionReq::Locked { .. })
+            })
+            .collect();
+        let summaries: Vec<Summary> = matching_records
+            .iter()
+            .map(|record| build_summary_from_index_record(record))
+            .collect();
+
+        // Remember records for verifying downloads later.
+        self.records.lock().unwrap().extend(
+            summaries
+                .iter()
+                .zip(matching_records)
+                .map(|(summary, record)| (summary.package_id, record.clone())),
+        );
+
+        Ok(summaries)
     }
 
     #[tracing::instrument(level = "trace", skip(self))]
     async fn download(&self, id: PackageId) -> Result<Package> {
+        if let Some(path) = self.client.unpacked_path(id) {
+            let path = path.try_into_utf8()?;
+            let path_source = PathSource::recursive_at(&path, self.source_id, self.config);
+            return path_source.download(id).await;
+        }
+
+        // In offline mode, packages unpacked by previous runs are used as they are, because
+        // remote registries are not asked for their tarballs, signatures or provenance.
+        if !self.config.network_allowed() && !self.client.is_offline() {
+            if let Some(path) = self.package_sources.unpacked_path(id) {
+                let path_source = PathSource::recursive_at(&path, self.source_id, self.config);
+                return path_source.download(id).await;
+            }
+        }
+
         let is_downloaded = self.client.is_downloaded(id).await;
 
         ensure!(
             self.config.network_allowed() || self.client.is_offline() || is_downloaded,
-            "cannot download from `{}` in offline mode",
+            "package `{id}` is missing from the cache, cannot download it from `{}` in offline mode",
             self.source_id
         );
 
-        if !is_downloaded && !self.client.is_offline() {
+        let is_remote_download = !is_downloaded && !self.client.is_offline();
+        let size_limit = if is_remote_download {
+            Some(self.check_download_size(id).await?)
+        } else {
+            None
+        };
+        self.verify_provenance(id).await?;
+
+        let progress_bar = if is_remote_download {
             self.config
                 .ui()
                 .print(Status::new("Downloading", &id.to_string()));
+            self.config.ui().widget(ProgressBar::new(id.to_string()))
+        } else {
+            None
+        };
+        let progress: &dyn DownloadProgress = match &progress_bar {
+            Some(progress_bar) => progress_bar,
+            None => &NoProgress,
+        };
+        let limited_progress = size_limit.map(|limit| SizeLimitedProgress {
+            inner: progress,
+            id,
+            limit,
+        });
+        let progress: &dyn DownloadProgress = match &limited_progress {
+            Some(limited_progress) => limited_progress,
+            None => progress,
+        };
+
+        // Packages are unpacked while being downloaded, unless the whole tarball is needed
+        // upfront to verify its signature. If streaming fails, the package is downloaded
+        // to disk and verified before unpacking, reporting any errors in full detail.
+        if !is_downloaded && self.trusted_keys().is_none() {
+            if let Some(stream) = self.client.download_stream(id).await? {
+                match self.stream_package(id, stream, progress).await {
+                    Ok(package) => return Ok(package),
+                    Err(err) => debug!("streaming {id} failed, downloading again: {err:#}"),
+                }
+            }
         }
 
-        let archive = self.client.download(id).await?;
+        let archive = self.client.download(id, progress).await?;
+        drop(progress_bar);
 
         self.verify_checksum(id, archive.clone()).await?;
+        self.verify_signature(id, archive.clone()).await?;
         self.load_package(id, archive).await
     }
 }
 
 impl<'c> RegistrySource<'c> {
-    async fn verify_checksum(&self, id: PackageId, _archive: PathBuf) -> Result<()> {
+    /// Download the package tarball without unpacking it, verifying its checksum
+    /// and signature, and return the path to it.
+    pub async fn download_tarball(&self, id: PackageId) -> Result<PathBuf> {
+        self.verify_provenance(id).await?;
+        let archive = self.client.download(id, &NoProgress).await?;
+        self.verify_checksum(id, archive.clone()).await?;
+        self.verify_signature(id, archive.clone()).await?;
+        Ok(archive)
+    }
+
+    /// Check that the downloaded tarball matches the checksum listed in registry index.
+    ///
+    /// The checksum in the index is, in turn, compared to the one recorded in the lockfile
+    /// during resolution.
+    async fn verify_checksum(&self, id: PackageId, archive: PathBuf) -> Result<()> {
         self.config
             .ui()
             .verbose(Status::new("Verifying", &id.to_string()));
 
-        // TODO(mkaput): Verify checksum.
+        let expected = self.expected_checksum(id).await?;
+
+        let actual = spawn_blocking(move || -> Result<Checksum> {
+            let file = fsx::open(&archive)?;
+            Ok(Digest::recommended().update_read(file)?.finish())
+        })
+        .await?
+        .with_context(|| format!("failed to compute checksum of `{id}`"))?;
+
+        ensure!(
+            actual == expected,
+            checksum_mismatch(id, &expected, &actual)
+        );
+
+        Ok(())
+    }
+
+    async fn expected_checksum(&self, id: PackageId) -> Result<Checksum> {
+        Ok(self.index_record(id).await?.checksum)
+    }
+
+    async fn index_record(&self, id: PackageId) -> Result<IndexRecord> {
+        if let Some(record) = self.records.lock().unwrap().get(&id) {
+            return Ok(record.clone());
+        }
+
+        let records = self
+            .client
+            .get_records(id.name.clone())
+            .await?
+            .ok_or_else(|| anyhow!("package not found in registry: {id}"))?;
+        let record = records
+            .iter()
+            .find(|record| record.version == id.version)
+            .ok_or_else(|| anyhow!("package not found in registry: {id}"))?;
+        Ok(record.clone())
+    }
+
+    /// Refuse to download packages exceeding `downloads.max-package-size`, or which would not
+    /// fit in the cache directory, based on tarball sizes advertised in the registry index.
+    ///
+    /// Returns the limit of bytes to receive while downloading the package, as advertised sizes
+    /// cannot be trusted.
+    async fn check_download_size(&self, id: PackageId) -> Result<SizeLimit> {
+        let max_size = self.config.config_file().downloads.max_package_size;
+        let Some(size) = self.index_record(id).await?.size else {
+            return Ok(SizeLimit {
+                size: None,
+                max_size,
+            });
+        };
+
+        if let Some(max_size) = max_size {
+            ensure!(
+                size <= max_size,
+                "package `{id}` has {size} bytes, which exceeds the maximum package size \
+                of {max_size} bytes\n\
+                help: the limit can be changed with `downloads.max-package-size` setting"
+            );
+        }
+
+        let registry_dir = self.config.dirs().registry_dir();
+        let cache_path = registry_dir.path_existent()?;
+        match fs4::available_space(cache_path) {
+            Ok(available) => ensure!(
+                size <= available,
+                "not enough disk space to download `{id}`: it needs {size} bytes, \
+                but only {available} bytes are available in: {cache_path}"
+            ),
+            Err(err) => debug!("failed to check available disk space in {cache_path}: {err}"),
+        }
+        Ok(SizeLimit {
+            size: Some(size),
+            max_size,
+        })
+    }
+
+    /// Check that the downloaded tarball is signed with one of the keys trusted for this registry.
+    ///
+    /// Signatures are only verified if the registry has any `trusted-keys` configured.
+    async fn verify_signature(&self, id: PackageId, archive: PathBuf) -> Result<()> {
+        let Some(trusted_keys) = self.trusted_keys() else {
+            return Ok(());
+        };
+
+        let signature = self.client.get_signature(id).await?.ok_or_else(|| {
+            anyhow!(
+                "registry `{}` does not provide a signature for `{id}`, \
+                but signature verification is enabled",
+                self.source_id
+            )
+        })?;
+
+        spawn_blocking(move || {
+            let tarball = fsx::read(&archive)?;
+            verify_signature(&tarball, &signature, &trusted_keys)
+        })
+        .await?
+        .with_context(|| format!("failed to verify signature of `{id}`"))
+    }
+
+    /// Keys trusted to sign packages of this registry, if signature verification is enabled.
+    fn trusted_keys(&self) -> Option<Vec<String>> {
+        let (_, registry) = self.config.config_file().registry_serving(self.source_id)?;
+        if registry.trusted_keys.is_empty() {
+            return None;
+        }
+        Some(registry.trusted_keys.clone())
+    }
 
+    /// Check that the package has a provenance attestation signed with one of the keys trusted
+    /// for this registry, which describes the tarball listed in registry index.
+    ///
+    /// Attestations are only verified if the registry has any `provenance-keys` configured.
+    async fn verify_provenance(&self, id: PackageId) -> Result<()> {
+        let Some((_, registry)) = self.config.config_file().registry_serving(self.source_id) else {
+            return Ok(());
+        };
+        if registry.provenance_keys.is_empty() {
+            return Ok(());
+        }
+
+        let verify = async {
+            let provenance = self.client.get_provenance(id).await?.ok_or_else(|| {
+                anyhow!(
+                    "registry `{}` does not provide a provenance attestation for `{id}`, \
+                    but provenance verification is enabled",
+                    self.source_id
+                )
+            })?;
+            let checksum = self.expected_checksum(id).await?;
+            verify_provenance(&provenance, id, &checksum, &registry.provenance_keys)
+        };
+        let statement = verify
+            .await
+            .with_context(|| format!("failed to verify provenance of `{id}`"))?;
+
+        let source = &statement
+            .predicate
+            .build_definition
+            .external_parameters
+            .source;
+        self.config.ui().verbose(Status::new(
+            "Attested",
+            &format!(
+                "{id} built from {}@{} by {}",
+                source.repository, source.commit, statement.predicate.run_details.builder.id
+            ),
+        ));
         Ok(())
     }
 
+    /// Unpack the package while its tarball is being downloaded, verifying its checksum
+    /// once all data has been received.
+    async fn stream_package(
+        &self,
+        id: PackageId,
+        stream: TarballStream,
+        progress: &dyn DownloadProgress,
+    ) -> Result<Package> {
+        let expected = self.expected_checksum(id).await?;
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_CHUNKS);
+        let TarballStream { total, mut chunks } = stream;
+        let receive = async move {
+            let mut downloaded = 0;
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk.and_then(|chunk| {
+                    downloaded += chunk.len() as u64;
+                    progress.update(downloaded, total)?;
+                    Ok(chunk)
+                });
+                let is_err = chunk.is_err();
+                // Stop if extraction has finished early, or the download has failed.
+                if tx.send(chunk).await.is_err() || is_err {
+                    break;
+                }
+            }
+        };
+
+        let reader = VerifyingReader {
+            inner: ChannelReader::new(rx),
+            digest: Some(Digest::recommended()),
+            id,
+            expected,
+        };
+        let ((), path) = futures::join!(receive, self.package_sources.extract_reader(id, reader));
+
+        let path_source = PathSource::recursive_at(&path?, self.source_id, self.config);
+        path_source.download(id).await
+    }
+
     /// Turn the downloaded `.tar.zst` tarball into a [`Package`].
     ///
     /// This method extracts the tarball into cache directory, and then loads it using
@@ -180,6 +541,86 @@ impl<'c> RegistrySource<'c> {
     }
 }
 
+fn checksum_mismatch(id: PackageId, expected: &Checksum, actual: &Checksum) -> String {
+    formatdoc! {"
+        checksum verification failed for `{id}`
+        expected: {expected}
+        actual:   {actual}
+
+        the downloaded tarball does not match the registry index, it may have been \
+        corrupted or tampered with
+    "}
+}
+
+/// Sizes which a package tarball must not exceed while being downloaded.
+#[derive(Clone, Copy)]
+struct SizeLimit {
+    /// Size advertised in the registry index.
+    size: Option<u64>,
+    /// The `downloads.max-package-size` setting.
+    max_size: Option<u64>,
+}
+
+/// A [`DownloadProgress`] which aborts downloads once more bytes than allowed by [`SizeLimit`]
+/// are received.
+struct SizeLimitedProgress<'a> {
+    inner: &'a dyn DownloadProgress,
+    id: PackageId,
+    limit: SizeLimit,
+}
+
+impl DownloadProgress for SizeLimitedProgress<'_> {
+    fn update(&self, downloaded: u64, total: Option<u64>) -> Result<()> {
+        let id = self.id;
+        let received = downloaded.max(total.unwrap_or_default());
+        if let Some(max_size) = self.limit.max_size {
+            ensure!(
+                received <= max_size,
+                "package `{id}` has more than {max_size} bytes, which exceeds the maximum \
+                package size\n\
+                help: the limit can be changed with `downloads.max-package-size` setting"
+            );
+        }
+        if let Some(size) = self.limit.size {
+            ensure!(
+                received <= size,
+                "package `{id}` has more than {size} bytes advertised in the registry index"
+            );
+        }
+        self.inner.update(downloaded, total)
+    }
+}
+
+/// A reader computing checksum of all data read through it, which fails at the end of data
+/// if the checksum does not match the expected one.
+struct VerifyingReader<R> {
+    inner: R,
+    /// Taken once the checksum has been verified.
+    digest: Option<Digest>,
+    id: PackageId,
+    expected: Checksum,
+}
+
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 || buf.is_empty() {
+            if let Some(digest) = &mut self.digest {
+                digest.update(&buf[..read]);
+            }
+        } else if let Some(mut digest) = self.digest.take() {
+            let actual = digest.finish();
+            if actual != self.expected {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    checksum_mismatch(self.id, &self.expected, &actual),
+                ));
+            }
+        }
+        Ok(read)
+    }
+}
+
 impl<'c> fmt::Debug for RegistrySource<'c> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RegistrySource")
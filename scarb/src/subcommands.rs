@@ -22,6 +22,10 @@ pub fn get_env_vars(
             "SCARB_CONFIG".into(),
             config.dirs().config_dir.path_unchecked().into(),
         ),
+        (
+            "SCARB_INSTALL_DIR".into(),
+            config.dirs().install_dir.path_unchecked().into(),
+        ),
         ("SCARB_LOG".into(), config.log_filter_directive().into()),
         (
             SCARB_MANIFEST_PATH_ENV.into(),
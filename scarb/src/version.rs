@@ -2,9 +2,11 @@
 
 use std::fmt;
 use std::fmt::Write;
+use std::iter;
 
 use indoc::formatdoc;
 use once_cell::sync::Lazy;
+use semver::{Op, Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
 use scarb_build_metadata::{
@@ -146,3 +148,86 @@ pub fn get() -> VersionInfo {
         sierra,
     }
 }
+
+/// Past Scarb releases along with versions of Cairo they bundle, newest first.
+///
+/// Since Scarb v2.3.0, Scarb versions follow versions of the bundled Cairo compiler.
+const SCARB_RELEASES: &[(&str, &str)] = &[
+    ("2.3.0", "2.3.0"),
+    ("0.7.0", "2.2.0"),
+    ("0.6.0", "2.1.0"),
+    ("0.5.2", "2.0.2"),
+    ("0.5.1", "2.0.1"),
+    ("0.5.0", "2.0.0"),
+];
+
+/// First Scarb release sharing its version with the bundled Cairo compiler.
+const FIRST_ALIGNED_RELEASE: Version = Version::new(2, 3, 0);
+
+/// Suggest a Scarb release bundling a Cairo compiler which satisfies `req`.
+///
+/// Known releases are checked first, newest first, including this one. Requirements which
+/// can only be satisfied by future Cairo versions point to the Scarb release of the same version.
+pub fn scarb_release_for_cairo(req: &VersionReq) -> Option<(Version, Version)> {
+    let current = get();
+    let current = (
+        Version::parse(current.version).ok()?,
+        Version::parse(current.cairo.version).ok()?,
+    );
+    let known = iter::once(current.clone()).chain(SCARB_RELEASES.iter().map(|(scarb, cairo)| {
+        (
+            Version::parse(scarb).unwrap(),
+            Version::parse(cairo).unwrap(),
+        )
+    }));
+    for (scarb, cairo) in known {
+        if req.matches(&cairo) {
+            return Some((scarb, cairo));
+        }
+    }
+
+    let [comparator] = req.comparators.as_slice() else {
+        return None;
+    };
+    if !matches!(
+        comparator.op,
+        Op::Caret | Op::Tilde | Op::Exact | Op::GreaterEq | Op::Wildcard
+    ) {
+        return None;
+    }
+    let lowest = Version {
+        major: comparator.major,
+        minor: comparator.minor.unwrap_or(0),
+        patch: comparator.patch.unwrap_or(0),
+        pre: comparator.pre.clone(),
+        build: Default::default(),
+    };
+    (lowest > current.1 && lowest >= FIRST_ALIGNED_RELEASE && req.matches(&lowest))
+        .then(|| (lowest.clone(), lowest))
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::{Version, VersionReq};
+    use test_case::test_case;
+
+    use super::scarb_release_for_cairo;
+
+    #[test_case("~2.2", Some(("0.7.0", "2.2.0")))]
+    #[test_case("=2.0.1", Some(("0.5.1", "2.0.1")))]
+    #[test_case(">=2.0.0, <2.1.0", Some(("0.5.2", "2.0.2")))]
+    #[test_case("^99.1", Some(("99.1.0", "99.1.0")))]
+    #[test_case(">=99.0.0", Some(("99.0.0", "99.0.0")))]
+    #[test_case("<1.0.0", None)]
+    #[test_case("=1.1.0", None)]
+    fn suggest_scarb_release(req: &str, expected: Option<(&str, &str)>) {
+        let req = VersionReq::parse(req).unwrap();
+        assert_eq!(
+            scarb_release_for_cairo(&req),
+            expected.map(|(scarb, cairo)| (
+                Version::parse(scarb).unwrap(),
+                Version::parse(cairo).unwrap()
+            ))
+        );
+    }
+}
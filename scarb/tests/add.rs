@@ -7,6 +7,7 @@ use test_case::test_case;
 
 use scarb_test_support::manifest_edit::ManifestEditHarness;
 use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::registry::local::LocalRegistry;
 
 #[test]
 fn registry_with_version() {
@@ -56,6 +57,30 @@ fn registry_with_caret_version_req() {
         .run();
 }
 
+#[test]
+fn registry_allowing_prerelease() {
+    ManifestEditHarness::offline()
+        .args(["add", "dep@1", "--pre"])
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = "1.0.0"
+        "#})
+        .output(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = "1.0.0"
+            dep = { version = "1", allow-prerelease = true }
+        "#})
+        .run();
+}
+
 #[test]
 fn registry_without_version() {
     ManifestEditHarness::offline()
@@ -464,3 +489,188 @@ fn should_not_sort_if_already_unsorted() {
         "#})
         .run();
 }
+
+#[test]
+fn multiple_packages() {
+    ManifestEditHarness::offline()
+        .args(["add", "foo@1.0.0", "bar@2", "--pre"])
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+        "#})
+        .output(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            foo = { version = "1.0.0", allow-prerelease = true }
+            bar = { version = "2", allow-prerelease = true }
+        "#})
+        .run();
+}
+
+#[test]
+fn dev() {
+    ManifestEditHarness::offline()
+        .args(["add", "dep@1.0.0", "--dev"])
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = "1.0.0"
+        "#})
+        .output(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = "1.0.0"
+
+            [target-dependencies.test]
+            dep = "1.0.0"
+        "#})
+        .run();
+}
+
+fn registry_config(registry: &LocalRegistry) -> TempDir {
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.local]
+            index = "{registry}"
+        "#})
+        .unwrap();
+    config_dir
+}
+
+fn local_registry() -> LocalRegistry {
+    let mut registry = LocalRegistry::create();
+    for version in ["1.0.0", "1.2.0", "2.0.0-rc.1"] {
+        registry.publish(|t| {
+            ProjectBuilder::start()
+                .name("dep")
+                .version(version)
+                .build(t);
+        });
+    }
+    registry
+}
+
+#[test]
+fn registry_latest_version() {
+    let registry = local_registry();
+    let config_dir = registry_config(&registry);
+    ManifestEditHarness::offline()
+        .args(["add", "dep", "--registry", "local"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+        "#})
+        .output(formatdoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            dep = {{ version = "1.2.0", registry = "{registry}" }}
+        "#})
+        .run();
+}
+
+#[test]
+fn registry_latest_prerelease_version() {
+    let registry = local_registry();
+    let config_dir = registry_config(&registry);
+    ManifestEditHarness::offline()
+        .args(["add", "dep", "--registry", "local", "--pre"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+        "#})
+        .output(formatdoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            dep = {{ version = "2.0.0-rc.1", registry = "{registry}", allow-prerelease = true }}
+        "#})
+        .run();
+}
+
+#[test]
+fn registry_missing_package() {
+    let registry = local_registry();
+    let config_dir = registry_config(&registry);
+    ManifestEditHarness::offline()
+        .args(["add", "missing", "--registry", "local"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+        "#})
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: cannot find package `missing` in registry: registry+file://[..]
+        "#})
+        .run();
+}
+
+#[test]
+fn undefined_registry() {
+    ManifestEditHarness::offline()
+        .args(["add", "dep@1.0.0", "--registry", "unknown"])
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+        "#})
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: registry `unknown` is not defined in Scarb configuration
+        "#})
+        .run();
+}
+
+#[test]
+fn overwrite_change_source_from_registry_to_path() {
+    let t = TempDir::new().unwrap();
+    let dep = t.child("dep");
+    ProjectBuilder::start()
+        .name("dep")
+        .version("1.0.0")
+        .build(&dep);
+
+    ManifestEditHarness::offline()
+        .path(t.child("hello"))
+        .args(["add", "dep", "--path"])
+        .arg(dep.path())
+        .input(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            dep = { version = "1.0.0", registry = "https://example.com/" }
+        "#})
+        .output(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "1.0.0"
+
+            [dependencies]
+            dep = { version = "1.0.0", path = "../dep" }
+        "#})
+        .run();
+}
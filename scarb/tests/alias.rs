@@ -0,0 +1,119 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+#[test]
+fn alias_of_builtin_command() {
+    let config = TempDir::new().unwrap();
+    config
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            mp = "manifest-path"
+        "#})
+        .unwrap();
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("mp")
+        .env("SCARB_CONFIG", config.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("[..]Scarb.toml\n");
+}
+
+#[test]
+fn alias_with_arguments() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            b = ["build", "--package"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["--offline", "b", "hello"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v[..] ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn builtin_commands_take_precedence() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            manifest-path = "build"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("manifest-path")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("[..]Scarb.toml\n");
+}
+
+#[test]
+fn recursive_alias() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            a = "b --release"
+            b = "a"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("a")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq("error: alias `a` expands recursively through `a`\n");
+}
+
+#[test]
+fn aliases_from_ancestor_directories() {
+    let t = TempDir::new().unwrap();
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            mp = "manifest-path"
+            b = "build"
+            c = "b"
+        "#})
+        .unwrap();
+    let package = t.child("hello");
+    ProjectBuilder::start().name("hello").build(&package);
+    package
+        .child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            b = "mp"
+        "#})
+        .unwrap();
+
+    // Aliases from the nearest directory take precedence, but can refer to ones defined further up.
+    Scarb::quick_snapbox()
+        .arg("c")
+        .current_dir(&package)
+        .assert()
+        .success()
+        .stdout_matches("[..]hello[..]Scarb.toml\n");
+}
@@ -0,0 +1,180 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use serde_json::Value;
+
+use scarb_test_support::command::{CommandExt, Scarb};
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+fn project(registry: &mut LocalRegistry, bar_version: &str, cache_dir: &TempDir) -> TempDir {
+    registry.publish_simple("bar", bar_version);
+    registry.publish_simple("qux", "1.0.0");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version(bar_version).registry(&*registry))
+        .dep("qux", Dep.version("1").registry(&*registry))
+        .build_locked(&t.child("foo"), cache_dir);
+
+    t.child("db/bar/SCARB-2024-0001.toml")
+        .write_str(indoc! {r#"
+            [advisory]
+            id = "SCARB-2024-0001"
+            package = "bar"
+            title = "Overflow in token transfers"
+            url = "https://example.com/SCARB-2024-0001"
+            severity = "medium"
+
+            [versions]
+            patched = [">=1.1.0"]
+        "#})
+        .unwrap();
+    t.child("db/qux/SCARB-2024-0002.toml")
+        .write_str(indoc! {r#"
+            [advisory]
+            id = "SCARB-2024-0002"
+            package = "qux"
+            title = "qux is unmaintained"
+            kind = "unmaintained"
+        "#})
+        .unwrap();
+    t
+}
+
+#[test]
+fn vulnerable_dependency_fails_audit() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, "1.0.0", &cache_dir);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../db"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        SCARB-2024-0001: Overflow in token transfers
+          package:  bar v1.0.0
+          severity: medium
+          patched:  >=1.1.0
+          url:      https://example.com/SCARB-2024-0001
+
+        SCARB-2024-0002: qux is unmaintained (allowed)
+          package:  qux v1.0.0
+          kind:     unmaintained
+          patched:  none
+        error: audit failed: 1 of 2 advisories affecting locked packages are denied
+        "#});
+}
+
+#[test]
+fn severity_threshold() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, "1.0.0", &cache_dir);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../db", "--severity-threshold", "high"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        SCARB-2024-0001: Overflow in token transfers (allowed)
+          package:  bar v1.0.0
+          severity: medium
+          patched:  >=1.1.0
+          url:      https://example.com/SCARB-2024-0001
+
+        SCARB-2024-0002: qux is unmaintained (allowed)
+          package:  qux v1.0.0
+          kind:     unmaintained
+          patched:  none
+        "#});
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../db", "--severity-threshold", "high"])
+        .arg("--deny-unmaintained")
+        .current_dir(t.child("foo"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        SCARB-2024-0001: Overflow in token transfers (allowed)
+        [..]
+        [..]
+        [..]
+        [..]
+
+        SCARB-2024-0002: qux is unmaintained
+        [..]
+        [..]
+        [..]
+        error: audit failed: 1 of 2 advisories affecting locked packages are denied
+        "#});
+}
+
+#[test]
+fn patched_dependency_passes_audit() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, "1.1.0", &cache_dir);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../db"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        SCARB-2024-0002: qux is unmaintained (allowed)
+          package:  qux v1.0.0
+          kind:     unmaintained
+          patched:  none
+        "#});
+}
+
+#[test]
+fn json_report() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, "1.0.0", &cache_dir);
+
+    let report = Scarb::quick_snapbox()
+        .arg("--json")
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../db", "--severity-threshold", "critical"])
+        .current_dir(t.child("foo"))
+        .stdout_json::<Value>();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0]["advisory"]["id"], "SCARB-2024-0001");
+    assert_eq!(findings[0]["advisory"]["severity"], "medium");
+    assert_eq!(findings[0]["denied"], false);
+    assert_eq!(findings[1]["advisory"]["kind"], "unmaintained");
+}
+
+#[test]
+fn missing_database() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, "1.0.0", &cache_dir);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["audit", "--db", "../nope"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to load advisory database
+
+        Caused by:
+            advisory database directory does not exist: ../nope
+        "#});
+}
@@ -283,10 +283,81 @@ fn compile_with_incompatible_cairo_version() {
         .code(1)
         .stdout_matches(indoc! {r#"
             error: Package hello. Required Cairo version isn't compatible with current version. Should be: ^3.0.0 is: [..]
+            help: upgrade Scarb to 3.0.0 or newer
             error: For each package, the required Cairo version must match the current Cairo version.
+            help: use `--ignore-cairo-version` to compile these packages anyway
         "#});
 }
 
+#[test]
+fn compile_with_older_cairo_version_suggests_scarb_release() {
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml")
+        .write_str(
+            r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            cairo-version = "~2.2"
+            "#,
+        )
+        .unwrap();
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .code(1)
+        .stdout_matches(indoc! {r#"
+            error: Package hello. Required Cairo version isn't compatible with current version. Should be: ~2.2 is: [..]
+            help: Cairo 2.2.0 is bundled with Scarb 0.7.0
+            error: For each package, the required Cairo version must match the current Cairo version.
+            help: use `--ignore-cairo-version` to compile these packages anyway
+        "#});
+}
+
+#[test]
+fn compile_ignoring_incompatible_cairo_version() {
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml")
+        .write_str(
+            r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            cairo-version = "3.0.0"
+            "#,
+        )
+        .unwrap();
+    t.child("src/lib.cairo")
+        .write_str("fn f() -> felt252 { 42 }")
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["--ignore-cairo-version=other", "build"])
+        .current_dir(&t)
+        .assert()
+        .code(1);
+
+    Scarb::quick_snapbox()
+        .args(["--ignore-cairo-version=hello", "build"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: Package hello. Required Cairo version isn't compatible with current version. Should be: ^3.0.0 is: [..]
+            help: upgrade Scarb to 3.0.0 or newer
+            note: continuing, because `--ignore-cairo-version` is set
+            [..] Compiling hello v0.1.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["--ignore-cairo-version", "build"])
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
 #[test]
 fn compile_with_compatible_cairo_version() {
     let t = TempDir::new().unwrap();
@@ -0,0 +1,153 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use itertools::Itertools;
+use scarb_metadata::Metadata;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+
+#[test]
+fn build_dependency_is_not_compiled() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("codegen")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 42 }"#)
+        .build(&t.child("codegen"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [build-dependencies]
+            codegen = { path = "../codegen" }
+        "#})
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(&foo)
+        .stdout_json::<Metadata>();
+    let packages = metadata
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(packages, vec!["codegen", "core", "foo"]);
+    let components = metadata.compilation_units[0]
+        .components
+        .iter()
+        .map(|component| component.name.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(components, vec!["core", "foo"]);
+
+    foo.child("Scarb.lock").assert(indoc! {r#"
+        # Code generated by scarb DO NOT EDIT.
+        version = 1
+
+        [[package]]
+        name = "foo"
+        version = "0.1.0"
+
+        [[build-package]]
+        name = "codegen"
+        version = "1.0.0"
+
+        [[build-package]]
+        name = "foo"
+        version = "0.1.0"
+        dependencies = [
+         "codegen",
+        ]
+    "#});
+}
+
+#[test]
+fn build_dependencies_are_resolved_separately() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("2.0.0")
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .manifest_extra(format!(
+            indoc! {r#"
+                [build-dependencies]
+                bar = {{ version = "2", registry = "{}" }}
+            "#},
+            registry
+        ))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let lockfile = t.child("Scarb.lock");
+    lockfile.assert(predicates::str::contains(indoc! {r#"
+        [[package]]
+        name = "bar"
+        version = "1.0.0"
+    "#}));
+    lockfile.assert(predicates::str::contains(indoc! {r#"
+        [[build-package]]
+        name = "bar"
+        version = "2.0.0"
+    "#}));
+}
+
+#[test]
+fn optional_build_dependency() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [build-dependencies]
+            codegen = { version = "1.0.0", optional = true }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to parse manifest at: [..]Scarb.toml
+
+        Caused by:
+            build dependency `codegen` cannot be optional
+        "#});
+}
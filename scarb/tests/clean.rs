@@ -4,6 +4,7 @@ use indoc::indoc;
 
 use scarb_test_support::command::Scarb;
 use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
 #[test]
 fn simple() {
@@ -47,3 +48,42 @@ fn requires_workspace() {
     t.child("target/dev/some.sierra.json")
         .assert(predicates::path::is_file());
 }
+
+#[test]
+fn selected_packages() {
+    let t = TempDir::new().unwrap();
+    for name in ["first", "second"] {
+        ProjectBuilder::start().name(name).build(&t.child(name));
+    }
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("target/dev/first.sierra.json")
+        .assert(predicates::path::is_file());
+    t.child("target/dev/second.sierra.json")
+        .assert(predicates::path::is_file());
+
+    Scarb::quick_snapbox()
+        .args(["clean", "-p", "first"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("target/dev/first.sierra.json")
+        .assert(predicates::path::missing());
+    t.child("target/dev/second.sierra.json")
+        .assert(predicates::path::is_file());
+
+    Scarb::quick_snapbox()
+        .args(["clean", "--workspace"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("target").assert(predicates::path::missing());
+}
@@ -0,0 +1,196 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+#[test]
+fn set_and_get() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["config", "set", "http.retry", "5"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("[..]Updated `http.retry` in [..]config.toml\n");
+
+    t.child(".scarb/config.toml").assert("[http]\nretry = 5\n");
+
+    Scarb::quick_snapbox()
+        .args(["config", "get", "http.retry"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("http.retry = 5\n");
+
+    Scarb::quick_snapbox()
+        .args(["config", "get", "http", "--show-origin"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("http.retry = 5 # [..]config.toml\n");
+}
+
+#[test]
+fn set_preserves_formatting() {
+    let config = TempDir::new().unwrap();
+    config
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            # Retry flaky downloads.
+            [http]
+            retry = 1
+
+            [alias]
+            mp = "manifest-path"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["config", "set", "--global", "alias.b", "build"])
+        .env("SCARB_CONFIG", config.path())
+        .assert()
+        .success();
+    Scarb::quick_snapbox()
+        .args(["config", "set", "--global", "http.retry", "3"])
+        .env("SCARB_CONFIG", config.path())
+        .assert()
+        .success();
+
+    config.child("config.toml").assert(indoc! {r#"
+        # Retry flaky downloads.
+        [http]
+        retry = 3
+
+        [alias]
+        mp = "manifest-path"
+        b = "build"
+    "#});
+}
+
+#[test]
+fn list_with_origins() {
+    let config = TempDir::new().unwrap();
+    config
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [http]
+            retry = 1
+
+            [network]
+            offline = true
+        "#})
+        .unwrap();
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [http]
+            retry = 2
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args([
+            "--config",
+            "downloads.concurrency=4",
+            "config",
+            "list",
+            "--show-origin",
+        ])
+        .env("SCARB_CONFIG", config.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            downloads.concurrency = 4 # --config
+            http.retry = 2 # [..].scarb[..]config.toml
+            network.offline = true # [..]config.toml
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["config", "list", "--global"])
+        .env("SCARB_CONFIG", config.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(indoc! {r#"
+            http.retry = 1
+            network.offline = true
+        "#});
+}
+
+#[test]
+fn show_origin_of_overridden_value() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [http]
+            retry = 2
+
+            [network]
+            offline = true
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args([
+            "--config",
+            "http.retry=7",
+            "config",
+            "list",
+            "--show-origin",
+        ])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            http.retry = 7 # --config
+            network.offline = true # [..].scarb[..]config.toml
+        "#});
+}
+
+#[test]
+fn get_unset_key() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["config", "get", "http.proxy"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq("error: configuration key `http.proxy` is not set\n");
+}
+
+#[test]
+fn set_invalid_values() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["config", "set", "http.timeout", "120"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq("error: unknown configuration key `http.timeout`\n");
+
+    Scarb::quick_snapbox()
+        .args(["config", "set", "http.retry", "many"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: invalid value of `http.retry`
+
+            Caused by:
+            ...
+        "#});
+
+    t.child(".scarb/config.toml")
+        .assert(predicates::path::missing());
+}
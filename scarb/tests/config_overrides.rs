@@ -0,0 +1,54 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+#[test]
+fn override_takes_precedence_over_project_config() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(indoc! {r#"
+            [alias]
+            mp = "build"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["--config", r#"alias.mp="manifest-path""#, "mp"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("[..]Scarb.toml\n");
+}
+
+#[test]
+fn unknown_key() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--config", "http.timeout=120", "build"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq("error: unknown configuration key `http.timeout`\n");
+}
+
+#[test]
+fn invalid_argument() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--config", "http.retry", "build"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: invalid `--config` argument `http.retry`
+            help: expected a dotted key and a TOML value, like `http.retry=5`
+        "#});
+}
@@ -0,0 +1,149 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use itertools::Itertools;
+use scarb_metadata::Metadata;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+
+#[test]
+fn renamed_dependency() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 42 }"#)
+        .build(&t.child("bar"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("baz", Dep.path("../bar").with("package", "bar"))
+        .lib_cairo(r#"fn f() -> felt252 { baz::f() }"#)
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn depend_on_two_forks() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 1 }"#)
+        .build(&t.child("fork_a"));
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.1.0")
+        .lib_cairo(r#"fn g() -> felt252 { 2 }"#)
+        .build(&t.child("fork_b"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar_a", Dep.path("../fork_a").with("package", "bar"))
+        .dep("bar_b", Dep.path("../fork_b").with("package", "bar"))
+        .lib_cairo(r#"fn f() -> felt252 { bar_a::f() + bar_b::g() }"#)
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(&foo)
+        .stdout_json::<Metadata>();
+    let components = metadata.compilation_units[0]
+        .components
+        .iter()
+        .map(|component| component.name.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(components, vec!["bar_a", "bar_b", "core", "foo"]);
+}
+
+#[test]
+fn renamed_optional_dependency() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 42 }"#)
+        .build(&t.child("bar"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep(
+            "baz",
+            Dep.path("../bar")
+                .with("package", "bar")
+                .with("optional", true),
+        )
+        .manifest_extra(indoc! {r#"
+            [features]
+            extra = ["dep:baz"]
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'extra')]
+            fn f() -> felt252 { baz::f() }
+        "#})
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--features")
+        .arg("extra")
+        .current_dir(&foo)
+        .assert()
+        .success();
+}
+
+#[test]
+fn package_imported_under_different_names() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .build(&t.child("bar"));
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .dep("bar", Dep.path("../bar"))
+        .build(&t.child("baz"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("baz", Dep.path("../baz"))
+        .dep("qux", Dep.path("../bar").with("package", "bar"))
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&foo)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: package `bar v1.0.0 ([..]Scarb.toml)` is imported under different names: `[..]` and `[..]`
+        "#});
+}
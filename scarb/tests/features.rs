@@ -0,0 +1,653 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+
+use scarb_metadata::{Cfg, Metadata};
+use scarb_test_support::command::{CommandExt, Scarb};
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
+
+#[test]
+fn default_features_are_enabled() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["x"]
+            x = []
+            y = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'x')]
+            fn f() -> felt252 { 42 }
+
+            #[cfg(feature: 'y')]
+            fn f() -> felt252 { missing() }
+
+            fn g() -> felt252 { f() }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
+#[test]
+fn features_are_enabled_by_dependants() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["std"]
+            std = []
+            extra = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'extra')]
+            fn extra() -> felt252 { 42 }
+        "#})
+        .build(&q);
+
+    let y = t.child("y");
+    ProjectBuilder::start()
+        .name("y")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").with("default-features", false))
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["q-extra"]
+            q-extra = ["q/extra"]
+        "#})
+        .lib_cairo("fn f() -> felt252 { q::extra() }")
+        .build(&y);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("y", Dep.path("../y"))
+        .lib_cairo("fn f() -> felt252 { y::f() }")
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .success();
+
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(&x)
+        .stdout_json::<Metadata>();
+    let cfg = &metadata.compilation_units[0].cfg;
+    assert!(cfg.contains(&Cfg::KV("feature".into(), "extra".into())));
+    assert!(cfg.contains(&Cfg::KV("feature".into(), "q-extra".into())));
+    assert!(!cfg.contains(&Cfg::KV("feature".into(), "std".into())));
+}
+
+#[test]
+fn dependency_features() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            extra = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'extra')]
+            fn extra() -> felt252 { 42 }
+        "#})
+        .build(&q);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").features(&["extra"]))
+        .lib_cairo("fn f() -> felt252 { q::extra() }")
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .success();
+}
+
+#[test]
+fn missing_dependency_feature() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start().name("q").version("1.0.0").build(&q);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").features(&["extra"]))
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: package `q v1.0.0 ([..])` does not have feature `extra`
+        "#});
+}
+
+#[test]
+fn undeclared_feature_in_definition() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            x = ["y"]
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                feature `x` includes `y`, but `y` is not a declared feature
+        "#});
+}
+
+#[test]
+fn feature_of_non_dependency() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            x = ["q/extra"]
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                feature `x` includes `q/extra`, but `q` is not a dependency
+        "#});
+}
+
+#[test]
+fn optional_dependency_is_not_resolved() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("q", Dep.path("../missing").with("optional", true))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let lockfile = std::fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert!(!lockfile.contains(r#"name = "q""#));
+}
+
+#[test]
+fn optional_dependency_enabled_by_implicit_feature() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 42 }")
+        .build(&q);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").with("optional", true))
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["q"]
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'q')]
+            fn f() -> felt252 { q::f() }
+        "#})
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .success();
+
+    let lockfile = std::fs::read_to_string(x.child("Scarb.lock")).unwrap();
+    assert!(lockfile.contains(r#"name = "q""#));
+}
+
+#[test]
+fn optional_dependency_enabled_by_dependants() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            extra = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'extra')]
+            fn extra() -> felt252 { 42 }
+        "#})
+        .build(&q);
+
+    let y = t.child("y");
+    ProjectBuilder::start()
+        .name("y")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").with("optional", true))
+        .manifest_extra(indoc! {r#"
+            [features]
+            q-extra = ["q/extra"]
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'q-extra')]
+            fn f() -> felt252 { q::extra() }
+        "#})
+        .build(&y);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("y", Dep.path("../y").features(&["q-extra"]))
+        .lib_cairo("fn f() -> felt252 { y::f() }")
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .success();
+}
+
+#[test]
+fn dep_feature_of_non_optional_dependency() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start().name("q").version("1.0.0").build(&q);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q"))
+        .manifest_extra(indoc! {r#"
+            [features]
+            with-q = ["dep:q"]
+        "#})
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&x)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                feature `with-q` includes `dep:q`, but `q` is not an optional dependency
+        "#});
+}
+
+fn features_project(t: &TempDir) {
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["x"]
+            x = []
+            y = []
+        "#})
+        .build(t);
+}
+
+fn enabled_features(t: &TempDir, args: &[&str]) -> Vec<String> {
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .args(args)
+        .current_dir(t)
+        .stdout_json::<Metadata>();
+    let mut features: Vec<String> = metadata.compilation_units[0]
+        .cfg
+        .iter()
+        .filter_map(|cfg| match cfg {
+            Cfg::KV(key, value) if key == "feature" => Some(value.clone()),
+            _ => None,
+        })
+        .collect();
+    features.sort();
+    features
+}
+
+#[test]
+fn features_flags() {
+    let t = TempDir::new().unwrap();
+    features_project(&t);
+
+    assert_eq!(enabled_features(&t, &[]), vec!["default", "x"]);
+    assert_eq!(
+        enabled_features(&t, &["--features", "y"]),
+        vec!["default", "x", "y"]
+    );
+    assert_eq!(
+        enabled_features(&t, &["--no-default-features", "-F", "hello/y"]),
+        vec!["y"]
+    );
+    assert_eq!(
+        enabled_features(&t, &["--all-features", "--no-default-features"]),
+        vec!["default", "x", "y"]
+    );
+}
+
+#[test]
+fn features_from_env() {
+    let t = TempDir::new().unwrap();
+    features_project(&t);
+
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .env("SCARB_FEATURES", "y")
+        .env("SCARB_NO_DEFAULT_FEATURES", "true")
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+    let cfg = &metadata.compilation_units[0].cfg;
+    assert!(cfg.contains(&Cfg::KV("feature".into(), "y".into())));
+    assert!(!cfg.contains(&Cfg::KV("feature".into(), "x".into())));
+}
+
+#[test]
+fn unknown_feature_flag() {
+    let t = TempDir::new().unwrap();
+    features_project(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--features")
+        .arg("y,nope")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: none of the selected packages contains these features: nope
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--features")
+        .arg("world/y")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: package `world` not found in workspace
+        "#});
+}
+
+#[test]
+fn optional_dependency_enabled_by_features_flag() {
+    let t = TempDir::new().unwrap();
+    let q = t.child("q");
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 42 }")
+        .build(&q);
+
+    let x = t.child("x");
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .dep("q", Dep.path("../q").with("optional", true))
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'q')]
+            fn f() -> felt252 { q::f() }
+        "#})
+        .build(&x);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--features")
+        .arg("q")
+        .current_dir(&x)
+        .assert()
+        .success();
+
+    let lockfile = std::fs::read_to_string(x.child("Scarb.lock")).unwrap();
+    assert!(lockfile.contains(r#"name = "q""#));
+}
+
+fn unification_workspace(t: &TempDir, feature_unification: &str) {
+    ProjectBuilder::start()
+        .name("contracts")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            testing = []
+        "#})
+        .build(&t.child("contracts"));
+    ProjectBuilder::start()
+        .name("tests")
+        .version("1.0.0")
+        .dep("contracts", Dep.path("../contracts").features(&["testing"]))
+        .build(&t.child("tests"));
+    t.child("Scarb.toml")
+        .write_str(&formatdoc! {r#"
+            [workspace]
+            members = ["contracts", "tests"]
+            feature-unification = "{feature_unification}"
+        "#})
+        .unwrap();
+}
+
+fn contracts_unit_cfg(t: &TempDir) -> Vec<Cfg> {
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(t)
+        .stdout_json::<Metadata>();
+    metadata
+        .compilation_units
+        .into_iter()
+        .find(|unit| unit.package.repr.starts_with("contracts "))
+        .unwrap()
+        .cfg
+}
+
+#[test]
+fn features_are_computed_per_unit() {
+    let t = TempDir::new().unwrap();
+    unification_workspace(&t, "unit");
+    assert!(!contracts_unit_cfg(&t).contains(&Cfg::KV("feature".into(), "testing".into())));
+}
+
+#[test]
+fn features_are_unified_across_workspace() {
+    let t = TempDir::new().unwrap();
+    unification_workspace(&t, "workspace");
+    assert!(contracts_unit_cfg(&t).contains(&Cfg::KV("feature".into(), "testing".into())));
+}
+
+#[test]
+fn feature_cfg_follows_selected_features() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            default = ["x"]
+            x = []
+            y = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'x')]
+            fn f() -> felt252 { 42 }
+
+            #[cfg(feature: 'y')]
+            fn f() -> felt252 { 24 }
+
+            fn g() -> felt252 { f() }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--no-default-features")
+        .arg("--features")
+        .arg("y")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    // Neither definition of `f` is compiled.
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--no-default-features")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            error: Function not found.
+             --> [..]lib.cairo:7:21
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+
+    // Both definitions of `f` are compiled.
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--all-features")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            error: The name `f` is defined multiple times.
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+}
+
+fn inherited_dependency_workspace(t: &TempDir, dep: &str) {
+    ProjectBuilder::start()
+        .name("q")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [features]
+            extra = []
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'extra')]
+            fn extra() -> felt252 { 42 }
+        "#})
+        .build(&t.child("q"));
+    ProjectBuilder::start()
+        .name("x")
+        .version("1.0.0")
+        .manifest_extra(formatdoc! {r#"
+            [dependencies]
+            q = {dep}
+        "#})
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'q')]
+            fn f() -> felt252 { q::extra() }
+        "#})
+        .build(&t.child("x"));
+    WorkspaceBuilder::start()
+        .add_member("x")
+        .dep("q", Dep.path("q"))
+        .build(t);
+}
+
+#[test]
+fn inherited_dependency_with_features_and_optional() {
+    let t = TempDir::new().unwrap();
+    inherited_dependency_workspace(
+        &t,
+        r#"{ workspace = true, features = ["extra"], optional = true }"#,
+    );
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+    let lockfile = std::fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert!(!lockfile.contains(r#"name = "q""#));
+
+    Scarb::quick_snapbox()
+        .args(["build", "--features", "q"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    let lockfile = std::fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert!(lockfile.contains(r#"name = "q""#));
+}
+
+#[test]
+fn inherited_dependency_cannot_override_source() {
+    let t = TempDir::new().unwrap();
+    inherited_dependency_workspace(&t, r#"{ workspace = true, version = "2" }"#);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]Scarb.toml
+
+            Caused by:
+                0: error inheriting `q` from workspace root manifest's `workspace.dependencies.q`
+                1: `version` cannot be specified for a dependency inherited from workspace, only `features` and `optional` can
+        "#});
+}
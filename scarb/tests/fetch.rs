@@ -2,7 +2,8 @@ use indoc::indoc;
 use scarb_test_support::gitx;
 
 use scarb_test_support::command::Scarb;
-use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
 
 #[test]
 fn simple() {
@@ -40,3 +41,61 @@ fn check_git_fetch_stdout() {
         [..]  Updating git repository file://[..]/dep1
         "#});
 }
+
+#[test]
+fn fetch_registry_dependencies() {
+    let mut registry = LocalRegistry::create();
+    for name in ["bar", "baz"] {
+        registry.publish(|t| {
+            ProjectBuilder::start().name(name).version("1.0.0").build(t);
+        });
+    }
+
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .dep(
+            "baz",
+            Dep.version("1").registry(&registry).with("optional", true),
+        )
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Unpacking bar v1.0.0 ([..])
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["fetch", "--all-features"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Unpacking bar v1.0.0 ([..])
+        [..] Unpacking baz v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn fetch_unknown_feature() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["fetch", "--features", "nope"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: none of the selected packages contains these features: nope
+        "#});
+}
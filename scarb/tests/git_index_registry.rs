@@ -0,0 +1,128 @@
+use std::fs;
+use std::time::Duration;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::git_index::GitIndexRegistry;
+
+#[test]
+fn usage() {
+    let mut registry = GitIndexRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Updating git index file://[..]
+        [..] Downloading bar v1.0.0 (registry+git+file://[..])
+        "#});
+}
+
+#[test]
+fn picks_up_new_versions() {
+    let mut registry = GitIndexRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    let cache_dir = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.1.0")
+            .lib_cairo(r#"fn f() -> felt252 { 1 }"#)
+            .build(t);
+    });
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .arg("update")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Updating git index file://[..]
+        [..] Downloading bar v1.1.0 (registry+git+file://[..])
+        "#});
+
+    // Checkout of the previous index revision is kept for a while, because other Scarb
+    // processes may still be reading from it.
+    let checkouts = fs::read_dir(cache_dir.child("registry/index/checkouts"))
+        .unwrap()
+        .map(|entry| fs::read_dir(entry.unwrap().path()).unwrap().count())
+        .collect::<Vec<_>>();
+    assert_eq!(checkouts, vec![2]);
+}
+
+#[test]
+fn not_found() {
+    let mut registry = GitIndexRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("baz", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Updating git index file://[..]
+        error: package not found in registry: baz ^1 (registry+git+file://[..])
+        "#});
+}
@@ -0,0 +1,168 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+
+#[test]
+fn build_hooks_run_around_compilation() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-build = "echo $SCARB_HOOK $SCARB_PACKAGE_NAME $SCARB_PACKAGE_VERSION $SCARB_TARGET_KINDS"
+            post-build = "echo $SCARB_HOOK $SCARB_TARGETS"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Running pre-build hello (echo $SCARB_HOOK [..])
+            pre-build hello 1.0.0 lib
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..] Running post-build hello (echo $SCARB_HOOK $SCARB_TARGETS)
+            post-build [{"kind":"lib","name":"hello"}]
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn pre_build_hook_can_generate_code() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+            mod generated;
+
+            fn answer() -> felt252 {
+                generated::value()
+            }
+        "#})
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-build = "echo 'fn value() -> felt252 { 42 }' > src/generated.cairo"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    t.child("src/generated.cairo")
+        .assert("fn value() -> felt252 { 42 }\n");
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::path::is_file());
+}
+
+#[test]
+fn failing_hook_aborts_build() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-build = "exit 3"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Running pre-build hello (exit 3)
+            error: `pre-build` hook of package `hello` failed with exit code: 3
+        "#});
+
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn hooks_of_dependencies_are_not_run() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("dep")
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-build = "echo 'dependency hook'"
+        "#})
+        .build(&t.child("dep"));
+    ProjectBuilder::start()
+        .name("hello")
+        .dep("dep", Dep.path("../dep"))
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn package_hooks() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-package = "echo $SCARB_HOOK"
+            post-package = "echo $SCARB_HOOK $SCARB_PACKAGE_ARCHIVE"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("package")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Packaging hello v1.0.0 [..]
+            [..] Running pre-package hello (echo $SCARB_HOOK)
+            pre-package
+            [..]  Packaged [..] files, [..] ([..] compressed)
+            [..] Running post-package hello (echo $SCARB_HOOK $SCARB_PACKAGE_ARCHIVE)
+            post-package [..]hello-1.0.0.tar.zst
+        "#});
+}
+
+#[test]
+fn unknown_hook() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [hooks]
+            pre-test = "echo"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                TOML parse error at line [..]
+                ...
+                unknown variant `pre-test`, expected one of `pre-build`, `post-build`, `pre-package`, `post-package`
+                ...
+        "#});
+}
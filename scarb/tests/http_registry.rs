@@ -1,12 +1,14 @@
 use assert_fs::prelude::*;
 use assert_fs::TempDir;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use std::fs;
 use std::time::Duration;
 
 use scarb_test_support::command::Scarb;
+use scarb_test_support::fsx::ChildPathEx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 use scarb_test_support::registry::http::HttpRegistry;
+use scarb_test_support::simple_http_server::etag;
 
 #[test]
 fn usage() {
@@ -97,6 +99,1056 @@ fn missing_config_json() {
         "#});
 }
 
+#[test]
+fn mirror_failover() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    // Nothing listens on port 1, so the primary registry is guaranteed to be unreachable.
+    let primary = "http://127.0.0.1:1/";
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.flaky]
+            index = "{primary}"
+            mirrors = ["{registry}"]
+
+            [http]
+            retry = 0
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(primary))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        warn: registry `registry+http://127.0.0.1:1/` failed, trying next mirror: [..]
+        [..] Downloading bar v1.0.0 (registry+http://127.0.0.1:1/)
+        "#});
+}
+
+#[test]
+fn concurrent_downloads() {
+    let mut registry = HttpRegistry::serve();
+    for name in ["bar", "baz", "qux"] {
+        registry.publish(|t| {
+            ProjectBuilder::start()
+                .name(name)
+                .version("1.0.0")
+                .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+                .build(t);
+        });
+    }
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [downloads]
+            concurrency = 2
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .dep("baz", Dep.version("1").registry(&registry))
+        .dep("qux", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    // Download order is not deterministic.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading [..] v1.0.0 ([..])
+        [..] Downloading [..] v1.0.0 ([..])
+        [..] Downloading [..] v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn retry_transient_errors() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    registry.fail_next_requests(2);
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        warn: spurious network error (3 tries remaining): HTTP status 503 Service Unavailable
+        warn: spurious network error (2 tries remaining): HTTP status 503 Service Unavailable
+        [..] Downloading bar v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn retries_exhausted() {
+    let registry = HttpRegistry::serve();
+    registry.fail_next_requests(u32::MAX);
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [http]
+            retry = 1
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        warn: spurious network error (1 tries remaining): HTTP status 503 Service Unavailable
+        error: failed to lookup for `bar ^1 (registry+http://[..])` in registry: registry+http://[..]
+
+        Caused by:
+            0: failed to fetch registry config
+            1: HTTP status 503 Service Unavailable
+        "#});
+}
+
+#[test]
+fn proxy_from_config() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    // The registry server also works as a forward proxy for plain HTTP requests,
+    // because it only looks at request paths. The registry host does not exist,
+    // so the request can only succeed if it goes through the proxy.
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [http]
+            proxy = "{registry}"
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry("http://registry.invalid/"))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .env_remove("NO_PROXY")
+        .env_remove("no_proxy")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 (registry+http://registry.invalid/)
+        "#});
+}
+
+#[test]
+fn missing_ca_cert() {
+    let registry = HttpRegistry::serve();
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.internal]
+            index = "{registry}"
+            ca-cert = "missing-ca.pem"
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to lookup for `bar ^1 (registry+http://[..])` in registry: registry+http://[..]
+
+        Caused by:
+            0: failed to fetch registry config
+            1: failed to create HTTP client for registry `internal`
+            2: failed to read CA certificate
+            3: failed to read `[..]missing-ca.pem`
+            4: [..]
+        "#});
+}
+
+#[test]
+fn custom_layout() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    // Move files around, as if the registry was laid out differently.
+    registry.child("files/bar/1.0.0").create_dir_all().unwrap();
+    fs::rename(
+        registry.child("bar-1.0.0.tar.zst"),
+        registry.child("files/bar/1.0.0/download"),
+    )
+    .unwrap();
+    registry.child("meta").create_dir_all().unwrap();
+    fs::rename(
+        registry.child("index/3/b/bar.json"),
+        registry.child("meta/bar"),
+    )
+    .unwrap();
+
+    let url = registry.to_string();
+    registry.configure(|config| {
+        config["dl"] = format!("{url}files/{{package}}/{{version}}/download").into();
+        config["index"] = format!("{url}meta/{{package}}").into();
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn auth_required_without_token() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    registry.configure(|config| config["auth-required"] = true.into());
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to lookup for `bar ^1 (registry+http://[..])` in registry: registry+http://[..]
+
+        Caused by:
+            0: registry requires authentication
+            1: no token found for registry: registry+http://[..]
+               help: run `scarb login --index http://[..]` first
+        "#});
+}
+
+#[test]
+fn index_records_are_revalidated() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let cache_dir = TempDir::new().unwrap();
+    let update = || {
+        Scarb::quick_snapbox()
+            .arg("update")
+            .env("SCARB_CACHE", cache_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .success();
+    };
+
+    update();
+    assert_eq!(registry.not_modified_responses(), 0);
+
+    // Warm cache, records are not transferred again.
+    update();
+    assert_eq!(registry.not_modified_responses(), 1);
+
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.1.0")
+            .lib_cairo(r#"fn f() -> felt252 { 1 }"#)
+            .build(t);
+    });
+
+    update();
+    assert_eq!(registry.not_modified_responses(), 1);
+    let lockfile = fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert!(lockfile.contains(r#"version = "1.1.0""#));
+}
+
+#[test]
+fn not_found_is_cached() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("baz", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let cache_dir = TempDir::new().unwrap();
+    let fetch = || {
+        Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CACHE", cache_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .failure()
+            .stdout_matches(indoc! {r#"
+            error: package not found in registry: baz ^1 (registry+http://[..])
+            "#});
+    };
+
+    fetch();
+
+    // The registry must not be asked again, so it is fine if it is unavailable.
+    registry.fail_next_requests(100);
+    fetch();
+}
+
+#[test]
+fn download_is_unpacked_while_streaming() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let cache_dir = TempDir::new().unwrap();
+    let fetch = || {
+        Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CACHE", cache_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .success();
+    };
+
+    fetch();
+    let registry_dir = cache_dir.child("registry");
+    let tree = registry_dir.tree();
+    assert!(tree.contains("bar-1.0.0/\n"), "{tree}");
+    assert!(!tree.contains("bar-1.0.0.tar.zst"), "{tree}");
+
+    // Partially downloaded tarballs are left to the regular, resumable download path.
+    let dl_dir = fs::read_dir(registry_dir.child("dl"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let tarball = fs::read(registry.child("bar-1.0.0.tar.zst")).unwrap();
+    fs::write(dl_dir.join("bar-1.0.0.tar.zst.part"), &tarball[..16]).unwrap();
+
+    fetch();
+    assert_eq!(fs::read(dl_dir.join("bar-1.0.0.tar.zst")).unwrap(), tarball);
+}
+
+#[test]
+fn interrupted_download_is_resumed() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let cache_dir = TempDir::new().unwrap();
+    let fetch = || {
+        Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CACHE", cache_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .success();
+    };
+
+    fetch();
+    let dl_dir = fs::read_dir(cache_dir.child("registry").child("dl"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let tarball = fs::read(registry.child("bar-1.0.0.tar.zst")).unwrap();
+    let scratch_path = dl_dir.join("bar-1.0.0.tar.zst.part");
+    let validator_path = dl_dir.join("bar-1.0.0.tar.zst.part.validator");
+
+    // The tarball has not changed since the download was interrupted, only the rest is fetched.
+    fs::write(&scratch_path, &tarball[..16]).unwrap();
+    fs::write(&validator_path, etag(&tarball)).unwrap();
+    fetch();
+    assert_eq!(registry.partial_responses(), 1);
+    assert_eq!(fs::read(dl_dir.join("bar-1.0.0.tar.zst")).unwrap(), tarball);
+    assert!(!validator_path.exists());
+
+    // The tarball has changed, so the partial download is discarded and fetched from scratch.
+    fs::remove_file(dl_dir.join("bar-1.0.0.tar.zst")).unwrap();
+    fs::write(&scratch_path, b"stale tarball contents").unwrap();
+    fs::write(&validator_path, etag(b"stale tarball contents")).unwrap();
+    fetch();
+    assert_eq!(registry.partial_responses(), 1);
+    assert_eq!(fs::read(dl_dir.join("bar-1.0.0.tar.zst")).unwrap(), tarball);
+    assert!(!validator_path.exists());
+}
+
+#[test]
+fn max_package_size() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [downloads]
+            max-package-size = 10
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: package `bar v1.0.0 ([..])` has [..] bytes, which exceeds the maximum package size of 10 bytes
+        help: the limit can be changed with `downloads.max-package-size` setting
+        "#});
+}
+
+#[test]
+fn download_size_limits_apply_to_received_bytes() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let edit_records = |f: &dyn Fn(&mut serde_json::Value)| {
+        let path = registry.child("index/3/b/bar.json");
+        let mut records: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        for record in records.as_array_mut().unwrap() {
+            f(record);
+        }
+        fs::write(&path, serde_json::to_string(&records).unwrap()).unwrap();
+    };
+    let fetch = |config_dir: &TempDir| {
+        let output = Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CONFIG", config_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // The registry advertises a smaller tarball than it actually serves.
+    edit_records(&|record| record["size"] = 10.into());
+    let config_dir = TempDir::new().unwrap();
+    let stdout = fetch(&config_dir);
+    assert!(
+        stdout.contains("has more than 10 bytes advertised in the registry index"),
+        "{stdout}"
+    );
+
+    // The maximum package size is enforced even if the registry does not advertise the size.
+    edit_records(&|record| {
+        record.as_object_mut().unwrap().remove("size");
+    });
+    config_dir
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [downloads]
+            max-package-size = 10
+        "#})
+        .unwrap();
+    let stdout = fetch(&config_dir);
+    assert!(
+        stdout.contains("has more than 10 bytes, which exceeds the maximum package size"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn publish_respects_rate_limits() {
+    let registry = HttpRegistry::serve();
+    registry.enable_api();
+    registry.rate_limit_next_uploads(2);
+
+    let config_dir = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .description("Foo.")
+        .license("MIT")
+        .lib_cairo("fn main() -> felt252 { 0 }")
+        .build(&t);
+
+    // The test server does not accept uploads, so the request fails after rate limiting ends.
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(registry.to_string())
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging foo v0.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying foo v0.1.0 ([..])
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        [..] Uploading foo v0.1.0 (registry+http://[..])
+        warn: registry is rate limiting requests, retrying in 0s (3 tries remaining)
+        warn: registry is rate limiting requests, retrying in 0s (2 tries remaining)
+        error: registry rejected the package
+
+        Caused by:
+            [..]405 Method Not Allowed[..]
+        "#});
+}
+
+#[test]
+fn publish_dry_run() {
+    let mut registry = HttpRegistry::serve();
+    registry.enable_api();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    let config_dir = TempDir::new().unwrap();
+    let publish = |version: &str| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version(version)
+            .description("Bar.")
+            .license("MIT")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(&t);
+        Scarb::quick_snapbox()
+            .arg("publish")
+            .arg("--dry-run")
+            .arg("--index")
+            .arg(registry.to_string())
+            .env("SCARB_CONFIG", config_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+    };
+
+    publish("1.1.0").failure().stdout_matches(indoc! {r#"
+        error: cannot publish `bar v1.1.0 (registry+http://[..])`
+
+        Caused by:
+            no token found for registry: registry+http://[..]
+            help: run `scarb login --index http://[..]` first
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success();
+
+    publish("1.0.0").failure().stdout_matches(indoc! {r#"
+        error: cannot publish `bar v1.0.0 (registry+http://[..])`
+
+        Caused by:
+            package `bar v1.0.0 (registry+http://[..])` is already published in registry
+        "#});
+
+    publish("1.1.0").success().stdout_matches(indoc! {r#"
+        [..] Packaging bar v1.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying bar v1.1.0 ([..])
+        [..] Compiling bar v1.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        warn: aborting upload due to dry run
+        "#});
+    // Registry requirements on package metadata are checked before anything is built.
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.1.0")
+        .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+        .build(&t);
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--dry-run")
+        .arg("--index")
+        .arg(registry.to_string())
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: cannot publish `bar v1.1.0 (registry+http://[..])`
+
+        Caused by:
+            manifest is missing fields required by registry: `description`, `license` or `license-file`
+            help: set them in the `[package]` section of Scarb.toml
+        "#});
+}
+
 // TODO(mkaput): Test errors properly when package is in index, but tarball is missing.
 // TODO(mkaput): Test interdependencies.
-// TODO(mkaput): Test offline mode.
+
+#[test]
+fn publish_with_otp() {
+    let registry = HttpRegistry::serve();
+    registry.enable_api();
+    registry.require_otp("123456");
+
+    let config_dir = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .description("Foo.")
+        .license("MIT")
+        .lib_cairo("fn main() -> felt252 { 0 }")
+        .build(&t);
+
+    let publish = || {
+        Scarb::quick_snapbox()
+            .arg("publish")
+            .arg("--no-verify")
+            .arg("--index")
+            .arg(registry.to_string())
+            .env("SCARB_CONFIG", config_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+    };
+
+    publish().assert().failure().stdout_matches(indoc! {r#"
+        [..] Packaging foo v0.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading foo v0.1.0 (registry+http://[..])
+        registry requires a one-time password, please enter it below
+        error: no one-time password provided
+        help: pass it with `--otp` when publishing non-interactively
+        "#});
+
+    // The test server does not accept uploads, so the request fails once the password is accepted.
+    publish()
+        .stdin("123456\n")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging foo v0.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading foo v0.1.0 (registry+http://[..])
+        registry requires a one-time password, please enter it below
+        error: registry rejected the package
+
+        Caused by:
+            [..]405 Method Not Allowed[..]
+        "#});
+
+    publish()
+        .args(["--otp", "123456"])
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging foo v0.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading foo v0.1.0 (registry+http://[..])
+        error: registry rejected the package
+
+        Caused by:
+            [..]405 Method Not Allowed[..]
+        "#});
+
+    publish()
+        .args(["--otp", "000000"])
+        .stdin("111111\n")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging foo v0.1.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading foo v0.1.0 (registry+http://[..])
+        registry rejected the one-time password, please enter a new one below
+        error: registry requires a one-time password to publish packages
+        "#});
+}
+
+fn offline_project(registry: &HttpRegistry) -> TempDir {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+    t
+}
+
+#[test]
+fn offline_uses_cache() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    let cache_dir = TempDir::new().unwrap();
+    let t = offline_project(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+
+    // Resolve anew, using only cached index records and packages.
+    fs::remove_file(t.child("Scarb.lock")).unwrap();
+    Scarb::quick_snapbox()
+        .args(["--offline", "build"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    t.child(".scarb/config.toml")
+        .write_str("[network]\noffline = true\n")
+        .unwrap();
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+}
+
+#[test]
+fn offline_index_records_missing_from_cache() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    let t = offline_project(&registry);
+
+    Scarb::quick_snapbox()
+        .args(["--offline", "fetch"])
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to lookup for `bar ^1 (registry+http://[..])` in registry: registry+http://[..]
+
+        Caused by:
+            index records of package `bar` are missing from the cache, cannot look them up in offline mode
+        "#});
+}
+
+#[test]
+fn offline_package_missing_from_cache() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    let cache_dir = TempDir::new().unwrap();
+    let t = offline_project(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+
+    // Drop unpacked packages, keeping cached index records.
+    fs::remove_dir_all(cache_dir.child("registry/src")).unwrap();
+    Scarb::quick_snapbox()
+        .args(["--offline", "fetch"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: package `bar v1.0.0 (registry+http://[..])` is missing from the cache, cannot download it from `registry+http://[..]` in offline mode
+        "#});
+}
+
+#[test]
+fn offline_publish() {
+    let registry = HttpRegistry::serve();
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--offline", "publish", "--index", &registry.to_string()])
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to check if registry supports publishing: registry+http://[..]
+
+        Caused by:
+            cannot check registry capabilities in offline mode (--offline)
+        "#});
+}
+
+#[test]
+fn offline_search() {
+    let registry = HttpRegistry::serve();
+    registry.enable_api();
+
+    Scarb::quick_snapbox()
+        .args([
+            "--offline",
+            "search",
+            "bar",
+            "--index",
+            &registry.to_string(),
+        ])
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to search registry: registry+http://[..]
+
+        Caused by:
+            cannot search packages in offline mode (--offline)
+        "#});
+}
+
+#[test]
+fn offline_yank() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+
+    Scarb::quick_snapbox()
+        .args([
+            "--offline",
+            "yank",
+            "bar@1.0.0",
+            "--index",
+            &registry.to_string(),
+        ])
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to check if registry supports yanking: registry+http://[..]
+
+        Caused by:
+            cannot check registry capabilities in offline mode (--offline)
+        "#});
+}
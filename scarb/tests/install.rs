@@ -0,0 +1,203 @@
+use std::env;
+
+use assert_fs::fixture::ChildPath;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb::process::make_executable;
+use scarb_test_support::command::Scarb;
+use scarb_test_support::gitx;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+/// Build a package shipping a prebuilt `scarb-hello` extension in its `bin` directory.
+fn extension_package(t: &ChildPath, greeting: &str) {
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .build(t);
+    let script = t.child(format!("bin/scarb-hello{}", env::consts::EXE_SUFFIX));
+    script
+        .write_str(&format!("#!/usr/bin/env sh\necho \"{greeting} $@\"\n"))
+        .unwrap();
+    make_executable(script.path());
+}
+
+#[test]
+#[cfg_attr(
+    not(target_family = "unix"),
+    ignore = "This test writes a shell script, so it only works on Unix."
+)]
+fn install_list_and_uninstall() {
+    let t = TempDir::new().unwrap();
+    let package = t.child("hello");
+    extension_package(&package, "Hello");
+    let install_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["install", "--path", "hello"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Installing hello v1.0.0 ([..]hello[..])
+            [..]Installed scarb-hello to: [..]bin
+        "#});
+
+    install_dir
+        .child("bin/scarb-hello")
+        .assert(predicates::path::is_file());
+
+    Scarb::quick_snapbox()
+        .args(["hello", "world"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("Hello world\n");
+
+    Scarb::quick_snapbox()
+        .args(["install", "--list"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            hello v1.0.0 ([..]hello[..]):
+                scarb-hello
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["uninstall", "hello"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .success()
+        .stdout_matches("[..]Removing [..]scarb-hello\n");
+
+    install_dir
+        .child("bin/scarb-hello")
+        .assert(predicates::path::missing());
+
+    Scarb::quick_snapbox()
+        .args(["install", "--list"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .success()
+        .stdout_eq("no packages installed\n");
+}
+
+#[test]
+#[cfg_attr(
+    not(target_family = "unix"),
+    ignore = "This test writes a shell script, so it only works on Unix."
+)]
+fn install_from_git() {
+    let git = gitx::new("hello", |t| extension_package(&t, "Hi"));
+    let install_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["install", "--git"])
+        .arg(git.url())
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Updating git repository file://[..]/hello
+            [..]Installing hello v1.0.0 (git+file://[..]/hello#[..])
+            [..]Installed scarb-hello to: [..]bin
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["hello", "there"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .success()
+        .stdout_eq("Hi there\n");
+}
+
+#[test]
+#[cfg_attr(
+    not(target_family = "unix"),
+    ignore = "This test writes a shell script, so it only works on Unix."
+)]
+fn install_does_not_overwrite_foreign_executables() {
+    let t = TempDir::new().unwrap();
+    let package = t.child("hello");
+    extension_package(&package, "Hello");
+    let install_dir = TempDir::new().unwrap();
+    install_dir
+        .child("bin/scarb-hello")
+        .write_str("#!/usr/bin/env sh\necho \"Howdy $@\"\n")
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["install", "--path", "hello"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..]Installing hello v1.0.0 ([..]hello[..])
+            error: executable `scarb-hello` already exists in: [..]bin
+            help: use --force to overwrite it
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["install", "--path", "hello", "--force"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    install_dir
+        .child("bin/scarb-hello")
+        .assert(predicates::str::contains("Hello"));
+}
+
+#[test]
+fn install_package_without_executables() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .build(&t.child("hello"));
+    let install_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["install", "--path", "hello"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..]Installing hello v1.0.0 ([..]hello[..])
+            error: failed to build `hello`
+
+            Caused by:
+                package contains neither `scarb-*` executables in its `bin` directory, nor a Cargo project building them
+        "#});
+}
+
+#[test]
+fn install_path_with_version_requirement() {
+    let t = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["install", "hello@1.0.0", "--path", "hello"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq("error: version requirement can only be used when installing from a registry\n");
+}
+
+#[test]
+fn uninstall_not_installed() {
+    let install_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["uninstall", "hello"])
+        .env("SCARB_INSTALL_DIR", install_dir.path())
+        .assert()
+        .failure()
+        .stdout_eq("error: package `hello` is not installed\n");
+}
@@ -0,0 +1,279 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
+
+const UNUSED_VARIABLE: &str = indoc! {r#"
+    fn f() -> felt252 {
+        let a = 1;
+        42
+    }
+"#};
+
+#[test]
+fn warnings_are_reported_by_default() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            warning: Unused variable. [..]
+            ...
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn allow_lint() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [lints]
+            unused_variables = "allow"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn deny_warnings() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [lints]
+            warnings = "deny"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            error: Unused variable. [..]
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn named_lint_overrides_warnings_group() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [lints]
+            warnings = "deny"
+            unused_variables = "warn"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            warning: Unused variable. [..]
+            ...
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn inherit_workspace_lints() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [lints]
+            workspace = true
+        "#})
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .lib_cairo(UNUSED_VARIABLE)
+        .build(&t.child("second"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .manifest_extra(indoc! {r#"
+            [workspace.lints]
+            unused_variables = "deny"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["build", "--package", "second"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args(["build", "--package", "first"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling first v1.0.0 ([..]Scarb.toml)
+            error: Unused variable. [..]
+            ...
+            error: could not compile `first` due to previous error
+        "#});
+}
+
+#[test]
+fn unknown_lint() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [lints]
+            unused_things = "allow"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                unknown lint `unused_things`, expected one of: warnings, unused_variables, unused_imports, unhandled_must_use
+        "#});
+}
+
+#[test]
+fn invalid_lint_level() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [lints]
+            warnings = "forbid"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                TOML parse error at line [..]
+                ...
+        "#});
+}
+
+#[test]
+fn deny_warnings_flag() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["build", "--deny-warnings"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            error: Unused variable. [..]
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn deny_warnings_keeps_allowed_lints() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [lints]
+            unused_variables = "allow"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["build", "--deny-warnings"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn deny_warnings_in_profile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(UNUSED_VARIABLE)
+        .manifest_extra(indoc! {r#"
+            [profile.release.cairo]
+            deny-warnings = true
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args(["--release", "build"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            error: Unused variable. [..]
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+}
@@ -8,6 +8,7 @@ use scarb_test_support::command::Scarb;
 use scarb_test_support::fsx::ChildPathEx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
 #[test]
 fn usage() {
@@ -39,6 +40,181 @@ fn usage() {
         "#});
 }
 
+#[test]
+fn replace_default_registry() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(&formatdoc! {r#"
+            [source.default]
+            replace-with = "vendored"
+
+            [source.vendored]
+            local-registry = "{}"
+        "#, registry.t.path().display()})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Unpacking bar v1.0.0 ([..])
+        "#});
+
+    // Lockfile refers to the original source, not to the replacement.
+    t.child("Scarb.lock").assert(predicates::str::contains(
+        "registry+https://there-is-no-default-registry-yet.com/",
+    ));
+}
+
+#[test]
+fn lockfile_records_checksum() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let records = registry
+        .t
+        .child("index/3/b/bar.json")
+        .assert_is_json::<serde_json::Value>();
+    let checksum = records[0]["cksum"].as_str().unwrap();
+
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(formatdoc! {r#"
+        [[package]]
+        name = "bar"
+        version = "1.0.0"
+        source = "registry+{registry}"
+        checksum = "{checksum}"
+    "#}));
+}
+
+#[test]
+fn lockfile_checksum_mismatch() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    // Republish different contents under the same version.
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 1 }"#)
+            .build(t);
+    });
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: checksum for `bar v1.0.0 (registry+file://[..])` changed between lock files
+
+        this could be indicative of a few possible errors:
+            * the lock file is corrupt
+            * the registry has been tampered with
+            * the package has been republished with different contents
+
+        expected: sha256:[..]
+        actual:   sha256:[..]
+        "#});
+}
+
+#[test]
+fn tarball_checksum_mismatch() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    registry
+        .t
+        .child("bar-1.0.0.tar.zst")
+        .write_binary(b"corrupted")
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: checksum verification failed for `bar v1.0.0 (registry+file://[..])`
+        expected: sha256:[..]
+        actual:   sha256:[..]
+
+        the downloaded tarball does not match the registry index, it may have been corrupted or tampered with
+        "#});
+}
+
 #[test]
 fn not_found() {
     let mut registry = LocalRegistry::create();
@@ -147,6 +323,9 @@ fn publish() {
             .stdout_matches(formatdoc! {r#"
             [..] Packaging {name} v{version} ([..])
             [..]  Packaged [..]
+            [..] Verifying {name} v{version} ([..])
+            [..] Compiling {name} v{version} ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
             [..] Uploading {name} v{version} (registry+file://[..]/index/)
             "#});
     };
@@ -171,6 +350,8 @@ fn publish() {
         "#}
     );
 
+    let size = |tarball: &str| index.child(tarball).metadata().unwrap().len();
+
     assert_eq!(
         index
             .child("index/3/b/bar.json")
@@ -180,6 +361,7 @@ fn publish() {
                 "v": "1.0.0",
                 "deps": [],
                 "cksum": "sha256:13973a8c7a6d86430ad569fd2c2d5cad282ba67ee587820a4b597f7b0a66a8dd",
+                "size": size("bar-1.0.0.tar.zst"),
             }
         ])
     );
@@ -193,16 +375,288 @@ fn publish() {
                 "v": "1.0.0",
                 "deps": [],
                 "cksum": "sha256:032b626571a86bb18d93d6e67376d5c9b5a14efd76871bb5e3de4b1ded3c6c64",
+                "size": size("hello-1.0.0.tar.zst"),
             },
             {
                 "v": "1.1.0",
                 "deps": [],
                 "cksum": "sha256:0b9c792212d383b00b3b059461caa1bea64b1528890d54f95ea678d2956ec613",
+                "size": size("hello-1.1.0.tar.zst"),
             }
         ])
     );
 }
 
+#[test]
+fn publish_and_wait() {
+    let t = TempDir::new().unwrap();
+    let index = t.child("index");
+
+    let package = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&package);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--wait")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&package)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying bar v1.0.0 ([..])
+        [..] Compiling bar v1.0.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        [..] Uploading bar v1.0.0 (registry+file://[..]/index/)
+        [..]   Waiting for bar v1.0.0 (registry+file://[..]/index/) to be available
+        [..] Published bar v1.0.0 (registry+file://[..]/index/)
+        "#});
+}
+
+#[test]
+fn publish_dry_run() {
+    let t = TempDir::new().unwrap();
+    let index = t.child("index");
+
+    let package = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&package);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--dry-run")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&package)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying bar v1.0.0 ([..])
+        [..] Compiling bar v1.0.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        warn: aborting upload due to dry run
+        "#});
+
+    index.assert(predicates::path::missing());
+}
+
+#[test]
+fn publish_workspace_in_dependency_order() {
+    let index = TempDir::new().unwrap();
+
+    let t = TempDir::new().unwrap();
+    let lib = t.child("lib");
+    ProjectBuilder::start()
+        .name("lib")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&lib);
+    ProjectBuilder::start()
+        .name("app")
+        .version("1.0.0")
+        .dep("lib", lib.version("1.0.0"))
+        .lib_cairo("fn f() -> felt252 { lib::f() }")
+        .build(&t.child("app"));
+    WorkspaceBuilder::start()
+        .add_member("app")
+        .add_member("lib")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--workspace")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging lib v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying lib v1.0.0 ([..])
+        [..] Compiling lib v1.0.0 ([..])
+        [..]  Finished [..]
+        [..] Packaging app v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Verifying app v1.0.0 ([..])
+        [..] Compiling app v1.0.0 ([..])
+        [..]  Finished [..]
+        [..] Uploading lib v1.0.0 (registry+file://[..])
+        [..]   Waiting for lib v1.0.0 (registry+file://[..]) to be available
+        [..] Published lib v1.0.0 (registry+file://[..])
+        [..] Uploading app v1.0.0 (registry+file://[..])
+        "#});
+
+    index
+        .child("app-1.0.0.tar.zst")
+        .assert(predicates::path::is_file());
+    index
+        .child("lib-1.0.0.tar.zst")
+        .assert(predicates::path::is_file());
+}
+
+#[test]
+fn publish_workspace_verifies_all_before_upload() {
+    let index = TempDir::new().unwrap();
+
+    let t = TempDir::new().unwrap();
+    let lib = t.child("lib");
+    ProjectBuilder::start()
+        .name("lib")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&lib);
+    let app = t.child("app");
+    ProjectBuilder::start()
+        .name("app")
+        .version("1.0.0")
+        .dep("lib", lib.version("1.0.0"))
+        .lib_cairo("mod missing;\nfn f() -> felt252 { lib::f() + missing::f() }")
+        .src("src/missing.cairo", "fn f() -> felt252 { 0 }")
+        .build(&app);
+    app.child(".scarbignore")
+        .write_str("src/missing.cairo")
+        .unwrap();
+    WorkspaceBuilder::start()
+        .add_member("app")
+        .add_member("lib")
+        .build(&t);
+
+    let output = Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--workspace")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&t)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Verifying lib v1.0.0"), "{stdout}");
+    assert!(
+        stdout.contains("error: failed to verify package tarball"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("Uploading"), "{stdout}");
+
+    index
+        .child("lib-1.0.0.tar.zst")
+        .assert(predicates::path::missing());
+    index
+        .child("app-1.0.0.tar.zst")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn publish_verifies_package() {
+    let t = TempDir::new().unwrap();
+    let index = t.child("index");
+
+    let package = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo("mod missing;\nfn f() -> felt252 { missing::f() }")
+        .src("src/missing.cairo", "fn f() -> felt252 { 0 }")
+        .build(&package);
+    package
+        .child(".scarbignore")
+        .write_str("src/missing.cairo")
+        .unwrap();
+
+    let output = Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&package)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("error: failed to verify package tarball"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("Uploading"), "{stdout}");
+
+    // The check can be skipped.
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&package)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading bar v1.0.0 (registry+file://[..]/index/)
+        "#});
+}
+
+#[test]
+fn publish_creates_registry() {
+    let t = TempDir::new().unwrap();
+    let index = t.child("registry/nested");
+
+    let package = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&package);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(Url::from_directory_path(&index).unwrap().to_string())
+        .current_dir(&package)
+        .assert()
+        .success();
+
+    assert_eq!(
+        index.tree(),
+        indoc! {r#"
+            bar-1.0.0.tar.zst
+            index/
+            . 3/
+            . . b/
+            . . . bar.json
+        "#}
+    );
+
+    // The package can be consumed from the new registry right away.
+    let consumer = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep(
+            "bar",
+            Dep.version("1")
+                .registry(Url::from_directory_path(&index).unwrap()),
+        )
+        .lib_cairo("fn f() -> felt252 { bar::f() }")
+        .build(&consumer);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&consumer)
+        .assert()
+        .success();
+}
+
 #[test]
 fn publish_overwrites_existing() {
     let index = TempDir::new().unwrap();
@@ -264,6 +718,190 @@ fn publish_overwrites_existing() {
     );
 }
 
+fn restricted_package(publish: &str) -> TempDir {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("1.0.0")
+        .lib_cairo("fn f() -> felt252 { 0 }")
+        .build(&t);
+    t.child("Scarb.toml")
+        .write_str(&formatdoc! {r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            publish = {publish}
+        "#})
+        .unwrap();
+    t
+}
+
+fn registries_config(registries: &[(&str, &LocalRegistry)]) -> TempDir {
+    let config_dir = TempDir::new().unwrap();
+    let config = registries
+        .iter()
+        .map(|(name, registry)| format!("[registries.{name}]\nindex = \"{registry}\"\n"))
+        .collect::<String>();
+    config_dir.child("config.toml").write_str(&config).unwrap();
+    config_dir
+}
+
+#[test]
+fn publish_to_named_registry() {
+    let internal = LocalRegistry::create();
+    let config_dir = registries_config(&[("internal", &internal)]);
+    let t = restricted_package(r#"["internal"]"#);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--registry")
+        .arg("internal")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging foo v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Uploading foo v1.0.0 (registry+file://[..])
+        "#});
+
+    internal
+        .t
+        .child("foo-1.0.0.tar.zst")
+        .assert(predicates::path::is_file());
+}
+
+#[test]
+fn publish_to_allowed_registry_by_index() {
+    let internal = LocalRegistry::create();
+    let config_dir = registries_config(&[("internal", &internal)]);
+    let t = restricted_package(r#"["internal"]"#);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--index")
+        .arg(&internal.url)
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
+#[test]
+fn publish_to_disallowed_registry() {
+    let internal = LocalRegistry::create();
+    let public = LocalRegistry::create();
+    let config_dir = registries_config(&[("internal", &internal), ("public", &public)]);
+    let t = restricted_package(r#"["internal"]"#);
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--registry")
+        .arg("public")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: `foo` cannot be published to registry `public`, `package.publish` only allows: `internal`
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--index")
+        .arg(LocalRegistry::create().url)
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: `foo` cannot be published to `registry+file://[..]`, `package.publish` only allows: `internal`
+        "#});
+
+    public
+        .t
+        .child("foo-1.0.0.tar.zst")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn publish_disabled() {
+    let t = restricted_package("false");
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(LocalRegistry::create().url)
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: `foo` cannot be published, `package.publish` is set to `false`
+        "#});
+}
+
+#[test]
+fn publish_to_unknown_registry() {
+    let t = restricted_package("true");
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--registry")
+        .arg("unknown")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: registry `unknown` is not defined in Scarb configuration
+        "#});
+}
+
 // TODO(mkaput): Test errors properly when package is in index, but tarball is missing.
 // TODO(mkaput): Test publishing with target-specific dependencies.
 // TODO(mkaput): Test offline mode.
+
+#[test]
+fn max_unpack_size() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(indoc! {r#"
+            [downloads]
+            max-unpack-size = 100
+        "#})
+        .unwrap();
+
+    let output = Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("maximum unpack size of 100 bytes exceeded"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,143 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+fn project(registry: &mut LocalRegistry, cache_dir: &TempDir) -> TempDir {
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("baz", "1.0.0");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&*registry))
+        .build_locked(&t, cache_dir);
+    t
+}
+
+#[test]
+fn locked_unchanged() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+    registry.publish_simple("bar", "1.1.0");
+
+    Scarb::quick_snapbox()
+        .args(["--locked", "fetch"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("");
+}
+
+#[test]
+fn locked_fails_on_changes() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+    let lockfile = std::fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .dep("baz", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--locked", "fetch"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: the lockfile needs to be updated, but `--locked` was passed to prevent this
+          Adding baz v1.0.0
+        "#});
+
+    t.child("Scarb.lock").assert(lockfile);
+}
+
+#[test]
+fn locked_update() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+    registry.publish_simple("bar", "1.1.0");
+
+    Scarb::quick_snapbox()
+        .args(["--locked", "update"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: the lockfile needs to be updated, but `--locked` was passed to prevent this
+          Updating bar v1.0.0 -> v1.1.0
+        "#});
+}
+
+#[test]
+fn locked_without_lockfile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--locked", "fetch"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: the lockfile does not exist, but `--locked` was passed to prevent creating it
+        "#});
+
+    t.child("Scarb.lock").assert(predicates::path::missing());
+}
+
+#[test]
+fn frozen() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("");
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "update"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .dep("baz", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: the lockfile needs to be updated, but `--frozen` was passed to prevent this
+          Adding baz v1.0.0
+        "#});
+}
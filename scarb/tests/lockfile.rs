@@ -8,6 +8,9 @@ use snapbox::cmd::Command;
 
 use fs_extra::dir::{copy, CopyOptions};
 use scarb_test_support::cargo::cargo_bin;
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
 use test_for_each_example::test_for_each_example;
 
 #[test_for_each_example]
@@ -45,3 +48,58 @@ fn create_lockfile_simple(example: &Path) {
         [[package]]
     "#}));
 }
+
+#[test]
+fn resolve_merge_conflicts() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    let cache_dir = TempDir::new().unwrap();
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+    let ours = fs::read_to_string(t.child("Scarb.lock")).unwrap();
+
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.1.0")
+            .build(t);
+    });
+    Scarb::quick_snapbox()
+        .args(["update", "bar"])
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+    let theirs = fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert_ne!(ours, theirs);
+
+    t.child("Scarb.lock")
+        .write_str(&format!(
+            "<<<<<<< HEAD\n{ours}=======\n{theirs}>>>>>>> feature\n"
+        ))
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("Scarb.lock").assert(theirs);
+}
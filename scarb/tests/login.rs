@@ -0,0 +1,337 @@
+use std::fs;
+use std::time::Duration;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+
+use scarb::process::make_executable;
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::http::HttpRegistry;
+
+#[test]
+fn login_and_logout() {
+    let config_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["login", "--index", "https://example.com/", "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Login token for `registry+https://example.com/` saved
+        "#});
+
+    config_dir.child("credentials.toml").assert(indoc! {r#"
+            [registries."https://example.com/"]
+            token = "secret"
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["logout", "--index", "https://example.com/"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Logout token for `registry+https://example.com/` has been removed
+        "#});
+
+    config_dir.child("credentials.toml").assert("");
+
+    Scarb::quick_snapbox()
+        .args(["logout", "--index", "https://example.com/"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        warn: no token found for `registry+https://example.com/`, nothing to do
+        "#});
+}
+
+#[test]
+fn login_reads_token_from_stdin() {
+    let config_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["login", "--index", "https://example.com/"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .stdin("secret\n")
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        please paste the token below
+        [..] Login token for `registry+https://example.com/` saved
+        "#});
+
+    config_dir
+        .child("credentials.toml")
+        .assert(predicates::str::contains(r#"token = "secret""#));
+}
+
+#[test]
+fn private_registry() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    registry.require_token("secret");
+
+    let config_dir = TempDir::new().unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to lookup for `bar ^1 (registry+http://[..])` in registry: registry+http://[..]
+
+        Caused by:
+            0: failed to fetch registry config
+            1: HTTP status client error (401 Unauthorized) for url (http://[..]/config.json)
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn token_is_not_sent_to_other_hosts() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    registry.require_token("secret");
+    registry.serve_downloads_separately();
+
+    let config_dir = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let fetch = || {
+        Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CONFIG", config_dir.path())
+            .current_dir(&t)
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .success()
+    };
+
+    fetch();
+    assert_eq!(registry.authorized_download_requests(), 0);
+
+    // Registries requiring authentication get the token with all requests.
+    registry.configure(|config| config["auth-required"] = true.into());
+    fetch();
+    assert!(registry.authorized_download_requests() > 0);
+}
+
+#[test]
+fn publish_requires_token() {
+    let registry = HttpRegistry::serve();
+    let config_json = registry.child("config.json");
+    let mut index_config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_json).unwrap()).unwrap();
+    index_config["api"] = format!("{registry}api/").into();
+    config_json
+        .write_str(&serde_json::to_string(&index_config).unwrap())
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .lib_cairo("fn main() -> felt252 { 0 }")
+        .build(&t);
+
+    let config_dir = TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(registry.to_string())
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(formatdoc! {r#"
+        error: cannot publish `foo v0.1.0 (registry+{registry})`
+
+        Caused by:
+            no token found for registry: registry+{registry}
+            help: run `scarb login --index {registry}` first
+        "#});
+}
+
+#[test]
+#[cfg(unix)]
+fn credential_process_provider() {
+    let mut registry = HttpRegistry::serve();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    registry.require_token("secret");
+
+    // Keeps the token in a file next to the script, and checks that the registry is identified.
+    let provider_dir = TempDir::new().unwrap();
+    let provider = provider_dir.child("provider");
+    provider
+        .write_str(&formatdoc! {r#"
+            #!/usr/bin/env bash
+            test "$SCARB_REGISTRY_NAME" = private || exit 1
+            test "$SCARB_REGISTRY_INDEX_URL" = "{registry}" || exit 1
+            token="$(dirname "$0")/token"
+            case "$1" in
+                get) cat "$token" 2>/dev/null || true ;;
+                store) cat > "$token" ;;
+                erase) rm "$token" ;;
+                *) echo "unknown action: $1" >&2; exit 1 ;;
+            esac
+        "#})
+        .unwrap();
+    make_executable(provider.path());
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.private]
+            index = "{registry}"
+            credential-provider = ["{provider}"]
+        "#, provider = provider.path().display()})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["login", "--index", &registry.to_string(), "secret"])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(formatdoc! {r#"
+        [..] Login token for `registry+{registry}` saved
+        "#});
+
+    provider_dir.child("token").assert("secret");
+    config_dir
+        .child("credentials.toml")
+        .assert(predicates::path::missing());
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["logout", "--index", &registry.to_string()])
+        .env("SCARB_CONFIG", config_dir.path())
+        .assert()
+        .success()
+        .stdout_matches(formatdoc! {r#"
+        [..] Logout token for `registry+{registry}` has been removed
+        "#});
+
+    provider_dir
+        .child("token")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn failing_credential_provider() {
+    let registry = HttpRegistry::serve();
+
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.private]
+            index = "{registry}"
+            credential-provider = "scarb-test-no-such-credential-provider"
+        "#})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(formatdoc! {r#"
+        error: failed to lookup for `bar ^1 (registry+{registry})` in registry: registry+{registry}
+
+        Caused by:
+            0: failed to fetch registry config
+            1: failed to get token for registry: registry+{registry}
+            2: failed to spawn credential provider: scarb-test-no-such-credential-provider
+            3: [..]
+        "#});
+}
@@ -0,0 +1,120 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use serde_json::{json, Value};
+
+use scarb_test_support::command::{CommandExt, Scarb};
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
+
+#[test]
+fn print_schema() {
+    let t = TempDir::new().unwrap();
+    let schema = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("manifest-schema")
+        .current_dir(&t)
+        .stdout_json::<Value>();
+
+    assert_eq!(schema["title"], "Scarb.toml");
+    assert_eq!(
+        schema["properties"]["package"]["properties"]["name"],
+        json!({ "type": "string" })
+    );
+    assert_eq!(
+        schema["properties"]["dependencies"]["additionalProperties"]["anyOf"][0],
+        json!({ "type": "string" })
+    );
+}
+
+#[test]
+fn warn_about_unused_keys() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            sierra-txt = true
+
+            [build]
+            jobs = 2
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: unused manifest key `build` in: [..]/Scarb.toml
+            warn: unused manifest key `lib.sierra-txt` in: [..]/Scarb.toml
+            help: did you mean `sierra-text`?
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn warn_about_unused_keys_in_members() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .manifest_extra(indoc! {r#"
+            [dependencies]
+            second = { path = "../second", optinal = true }
+        "#})
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .build(&t.child("second"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .manifest_extra(indoc! {r#"
+            [workspace.package]
+            verison = "1.0.0"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: unused manifest key `workspace.package.verison` in: [..]/Scarb.toml
+            help: did you mean `version`?
+            warn: unused manifest key `dependencies.second.optinal` in: [..]/first/Scarb.toml
+            help: did you mean `optional`?
+        "#});
+}
+
+#[test]
+fn type_errors_point_into_manifest() {
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            keywords = "cairo"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                TOML parse error at line 4, column 12
+                  |
+                4 | keywords = "cairo"
+                  |            ^^^^^^^
+                ...
+        "#});
+}
@@ -7,7 +7,9 @@ use serde_json::json;
 use scarb_metadata::{Cfg, ManifestMetadataBuilder, Metadata, PackageMetadata};
 use scarb_test_support::command::{CommandExt, Scarb};
 use scarb_test_support::fsx;
+use scarb_test_support::gitx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
 use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
 fn packages_by_name(meta: Metadata) -> BTreeMap<String, PackageMetadata> {
@@ -326,11 +328,65 @@ fn manifest_targets_and_metadata() {
                 ("numeric".to_string(), json!(1231)),
                 ("table".to_string(), json!({ "key": "value" }))
             ])))
+            .metadata(Some(BTreeMap::from_iter([
+                ("key".to_string(), json!("value")),
+                ("meta".to_string(), json!("data")),
+                ("numeric".to_string(), json!("1231")),
+            ])))
             .build()
             .unwrap()
     );
 }
 
+#[test]
+fn package_metadata_is_passed_verbatim() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [package.metadata.deploy]
+            network = "goerli"
+            accounts = ["0x1", "0x2"]
+
+            [package.metadata.deploy.contracts]
+            balance = { class-hash = "0x123", salt = 7 }
+        "#})
+        .build(&t);
+
+    let meta = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version")
+        .arg("1")
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+
+    assert_eq!(
+        packages_by_name(meta)
+            .into_iter()
+            .map(|(k, p)| (k, p.manifest_metadata.metadata))
+            .collect::<BTreeMap<_, _>>(),
+        BTreeMap::from_iter([
+            ("core".to_string(), None),
+            ("test_plugin".to_string(), None),
+            (
+                "hello".to_string(),
+                Some(BTreeMap::from_iter([(
+                    "deploy".to_string(),
+                    json!({
+                        "network": "goerli",
+                        "accounts": ["0x1", "0x2"],
+                        "contracts": {
+                            "balance": { "class-hash": "0x123", "salt": 7 }
+                        }
+                    })
+                )]))
+            ),
+        ])
+    );
+}
+
 #[test]
 fn tool_metadata_is_packaged_contained() {
     let t = assert_fs::TempDir::new().unwrap();
@@ -668,6 +724,61 @@ fn workspace_package_key_inheritance() {
     )
 }
 
+#[test]
+fn workspace_package_urls_and_contents_inheritance() {
+    let t = assert_fs::TempDir::new().unwrap();
+    t.child("first/Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "first"
+            version.workspace = true
+            urls.workspace = true
+            include.workspace = true
+            publish.workspace = true
+        "#})
+        .unwrap();
+    t.child("first/src/lib.cairo")
+        .write_str("fn f() -> felt252 { 42 }")
+        .unwrap();
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["first"]
+
+            [workspace.package]
+            version = "0.2.0"
+            urls = { chat = "https://example.com/chat" }
+            include = ["assets/*"]
+            publish = false
+        "#})
+        .unwrap();
+
+    let meta = Scarb::quick_snapbox()
+        .args(["--json", "metadata", "--format-version", "1"])
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+    let first = packages_by_name(meta).remove("first").unwrap();
+    assert_eq!(first.version.to_string(), "0.2.0");
+    assert_eq!(
+        first.manifest_metadata.urls,
+        Some(BTreeMap::from_iter([(
+            "chat".to_string(),
+            "https://example.com/chat".to_string()
+        )]))
+    );
+
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--index")
+        .arg(LocalRegistry::create().url)
+        .current_dir(t.child("first"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: `first` cannot be published, `package.publish` is set to `false`
+        "#});
+}
+
 #[test]
 fn infer_readme_simple() {
     let t = assert_fs::TempDir::new().unwrap();
@@ -1112,3 +1223,40 @@ fn infer_readme_workspace() {
     assert_eq!(packages.get("t5").unwrap().manifest_metadata.readme, None);
     assert_eq!(packages.get("t6").unwrap().manifest_metadata.readme, None);
 }
+
+#[test]
+fn vcs_info_of_registry_packages() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+        gitx::init(t.path());
+        gitx::commit(t.path());
+    });
+
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    let packages = packages_by_name(
+        Scarb::quick_snapbox()
+            .arg("--json")
+            .arg("metadata")
+            .arg("--format-version")
+            .arg("1")
+            .current_dir(&t)
+            .stdout_json::<Metadata>(),
+    );
+
+    let vcs = packages["bar"].vcs.clone().unwrap();
+    assert_eq!(vcs.commit_hash.len(), 40);
+    assert!(!vcs.dirty);
+    assert_eq!(vcs.path_in_vcs, "");
+    assert_eq!(vcs.repository, None);
+    assert_eq!(packages["foo"].vcs, None);
+}
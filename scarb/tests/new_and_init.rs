@@ -1,13 +1,14 @@
 use std::fs;
 
 use assert_fs::prelude::*;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use predicates::prelude::*;
 use toml::{Table, Value};
 
 use scarb::core::TomlManifest;
 use scarb_test_support::command::Scarb;
 use scarb_test_support::fsx::AssertFsUtf8Ext;
+use scarb_test_support::gitx;
 
 #[test]
 fn new_simple() {
@@ -40,6 +41,22 @@ fn new_simple() {
         .assert(predicates::str::is_empty().not());
 }
 
+#[test]
+fn new_simple_with_vcs_none() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--vcs", "none"])
+        .current_dir(&pt)
+        .assert()
+        .success();
+
+    let t = pt.child("hello");
+    assert!(t.child("Scarb.toml").is_file());
+    assert!(!t.child(".gitignore").exists());
+    assert!(!t.child(".git").exists());
+}
+
 #[test]
 fn new_simple_without_vcs() {
     let pt = assert_fs::TempDir::new().unwrap();
@@ -382,3 +399,506 @@ fn init_core_name() {
         .assert()
         .success();
 }
+
+#[test]
+fn new_workspace() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--workspace"])
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches("Created `hello` workspace.\n");
+
+    let t = pt.child("hello");
+    assert!(t.child(".gitignore").is_file());
+    assert!(t.child(".git").is_dir());
+    assert!(t.child("Scarb.lock").is_file());
+    assert!(t.child("hello/src/lib.cairo").is_file());
+
+    let toml_manifest = TomlManifest::read_from_path(t.child("Scarb.toml").utf8_path()).unwrap();
+    assert!(toml_manifest.package.is_none());
+    let workspace = toml_manifest.workspace.unwrap();
+    assert_eq!(workspace.members.unwrap(), vec!["hello".to_string()]);
+    assert!(workspace.dependencies.unwrap().is_empty());
+
+    let toml_manifest =
+        TomlManifest::read_from_path(t.child("hello/Scarb.toml").utf8_path()).unwrap();
+    assert_eq!(toml_manifest.package.unwrap().name.as_str(), "hello");
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::str::is_empty().not());
+}
+
+#[test]
+fn init_workspace() {
+    let t = assert_fs::TempDir::new().unwrap().child("hello");
+    t.create_dir_all().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["init", "--workspace", "--no-vcs"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("Created workspace.\n");
+
+    assert!(!t.child(".git").exists());
+    assert!(t.child("Scarb.lock").is_file());
+    assert!(t.child("hello/Scarb.toml").is_file());
+
+    Scarb::quick_snapbox()
+        .args(["build", "-p", "hello"])
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
+fn template_repo() -> gitx::GitProject {
+    gitx::new("template", |t| {
+        t.child("Scarb.toml")
+            .write_str(indoc! {r#"
+                [package]
+                name = "{{name}}"
+                version = "0.1.0"
+                description = "{{ description }}"
+                authors = ["{{ author }}"]
+            "#})
+            .unwrap();
+        t.child("src/lib.cairo")
+            .write_str("fn main() -> felt252 { 42 }\n")
+            .unwrap();
+        t.child("scarb-template.toml")
+            .write_str(indoc! {r#"
+                [placeholders.description]
+                prompt = "Description of the package"
+                default = "Generated from a template"
+            "#})
+            .unwrap();
+    })
+}
+
+#[test]
+fn new_from_template() {
+    let template = template_repo();
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--template"])
+        .arg(template.url())
+        .args(["--define", "author=Jane Doe"])
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Updating git repository file://[..]/template
+            Created `hello` package.
+        "#});
+
+    let t = pt.child("hello");
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        indoc! {r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            description = "Generated from a template"
+            authors = ["Jane Doe"]
+        "#}
+    );
+    assert!(t.child("src/lib.cairo").is_file());
+    assert!(t.child(".gitignore").is_file());
+    assert!(t.child(".git").is_dir());
+    assert!(!t.child("scarb-template.toml").exists());
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
+#[test]
+fn new_from_template_missing_placeholder_value() {
+    let template = gitx::new("template", |t| {
+        t.child("Scarb.toml")
+            .write_str(indoc! {r#"
+                [package]
+                name = "{{name}}"
+                version = "{{ version }}"
+            "#})
+            .unwrap();
+        t.child("scarb-template.toml")
+            .write_str(indoc! {r#"
+                [placeholders.version]
+                prompt = "Initial version"
+            "#})
+            .unwrap();
+    });
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--template"])
+        .arg(template.url())
+        .current_dir(&pt)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Updating git repository file://[..]/template
+            error: failed to create package `hello` at: hello
+
+            Caused by:
+                no value provided for template placeholder `version`
+                help: use `--define version=<value>` to provide it
+        "#});
+}
+
+#[test]
+fn new_from_cached_template_offline() {
+    let template = template_repo();
+    let cache = assert_fs::TempDir::new().unwrap();
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "first", "--template"])
+        .arg(template.url())
+        .env("SCARB_CACHE", cache.path())
+        .current_dir(&pt)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args(["--offline", "new", "second", "--template"])
+        .arg(template.url())
+        .env("SCARB_CACHE", cache.path())
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches("Created `second` package.\n");
+
+    assert!(fs::read_to_string(pt.child("second/Scarb.toml"))
+        .unwrap()
+        .contains(r#"name = "second""#));
+}
+
+#[test]
+fn new_from_local_template() {
+    let pt = assert_fs::TempDir::new().unwrap();
+    let template = pt.child("blueprints/contract");
+    template
+        .child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "{{name}}"
+            version = "0.1.0"
+            license = "{{ license }}"
+        "#})
+        .unwrap();
+    template
+        .child("src/lib.cairo")
+        .write_str("mod {{name}}_token;\n")
+        .unwrap();
+    template
+        .child("src/token.cairo")
+        .write_str("fn supply() -> felt252 { 42 }\n")
+        .unwrap();
+    template.child("gitignore").write_str("target\n").unwrap();
+    template
+        .child("target/dev/stale.json")
+        .write_str("{}")
+        .unwrap();
+    template
+        .child("scarb-template.toml")
+        .write_str(indoc! {r#"
+            [placeholders.license]
+            default = "MIT"
+
+            [renames]
+            gitignore = ".gitignore"
+            "src/token.cairo" = "src/{{name}}_token.cairo"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--template", "blueprints/contract"])
+        .args(["--define", "license=Apache-2.0"])
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches("Created `hello` package.\n");
+
+    let t = pt.child("hello");
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        indoc! {r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            license = "Apache-2.0"
+        "#}
+    );
+    t.child("src/lib.cairo").assert("mod hello_token;\n");
+    assert!(t.child("src/hello_token.cairo").is_file());
+    assert!(!t.child("src/token.cairo").exists());
+    t.child(".gitignore").assert("target\n");
+    assert!(!t.child("gitignore").exists());
+    assert!(!t.child("target").exists());
+    assert!(!t.child("scarb-template.toml").exists());
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+}
+
+#[test]
+fn new_from_local_template_renaming_missing_path() {
+    let pt = assert_fs::TempDir::new().unwrap();
+    let template = pt.child("template");
+    template
+        .child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "{{name}}"
+            version = "0.1.0"
+        "#})
+        .unwrap();
+    template
+        .child("scarb-template.toml")
+        .write_str(indoc! {r#"
+            [renames]
+            "src/main.cairo" = "src/lib.cairo"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--template", "template"])
+        .current_dir(&pt)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to create package `hello` at: hello
+
+            Caused by:
+                template manifest renames `src/main.cairo`, which does not exist in the template
+        "#});
+}
+
+#[test]
+fn new_from_missing_local_template() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--template", "missing"])
+        .current_dir(&pt)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to create package `hello` at: hello
+
+            Caused by:
+                0: failed to find template directory: missing
+                1: failed to get absolute path of `missing`
+                2: [..]
+        "#});
+}
+
+#[test]
+fn new_interactive() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--interactive"])
+        .stdin("contract\nstarknet-contract\nstarknet-foundry\nMIT\nnone\n")
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            Package kind (lib, starknet-contract, executable) [lib]:
+            warn: invalid answer `contract`, expected one of: lib, starknet-contract, executable
+            Package kind (lib, starknet-contract, executable) [lib]:
+            Test runner (cairo-test, starknet-foundry) [cairo-test]:
+            License (SPDX expression) [none]:
+            Version control (git, none) [git]:
+            Created `hello` package.
+        "#});
+
+    let t = pt.child("hello");
+    let cairo_version = scarb::version::get().cairo.version;
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        formatdoc! {r#"
+            [package]
+            name = "hello"
+            version = "0.1.0"
+            license = "MIT"
+
+            # See more keys and their definitions at https://docs.swmansion.com/scarb/docs/reference/manifest.html
+
+            [dependencies]
+            starknet = ">={cairo_version}"
+            snforge_std = {{ git = "https://github.com/foundry-rs/starknet-foundry.git", tag = "v0.11.0" }}
+
+            [[target.starknet-contract]]
+
+            [scripts]
+            test = "snforge test"
+        "#}
+    );
+    assert!(fs::read_to_string(t.child("src/lib.cairo"))
+        .unwrap()
+        .contains("mod HelloStarknet"));
+    assert!(!t.child(".git").exists());
+}
+
+#[test]
+fn new_interactive_defaults() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "-i", "--kind", "executable", "--no-vcs"])
+        .stdin("\n\n\n\n")
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            Package kind (lib, starknet-contract, executable) [executable]:
+            Test runner (cairo-test, starknet-foundry) [cairo-test]:
+            License (SPDX expression) [none]:
+            Version control (git, none) [none]:
+            Created `hello` package.
+        "#});
+
+    let t = pt.child("hello");
+    assert!(fs::read_to_string(t.child("Scarb.toml"))
+        .unwrap()
+        .ends_with(indoc! {r#"
+            [scripts]
+            run = "scarb build && scarb cairo-run"
+        "#}));
+    assert!(!t.child(".git").exists());
+}
+
+#[test]
+fn new_interactive_closed_input() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--interactive"])
+        .stdin("lib\n")
+        .current_dir(&pt)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            Package kind (lib, starknet-contract, executable) [lib]:
+            Test runner (cairo-test, starknet-foundry) [cairo-test]:
+            error: standard input closed while waiting for an answer
+        "#});
+}
+
+#[test]
+fn new_contract_with_license() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args([
+            "new",
+            "hello",
+            "--kind",
+            "starknet-contract",
+            "--license",
+            "Apache-2.0",
+        ])
+        .current_dir(&pt)
+        .assert()
+        .success();
+
+    let t = pt.child("hello");
+    let manifest = fs::read_to_string(t.child("Scarb.toml")).unwrap();
+    assert!(manifest.contains(r#"license = "Apache-2.0""#));
+    assert!(manifest.contains("[[target.starknet-contract]]"));
+    t.child("LICENSE")
+        .assert(predicates::str::contains("Apache License\n"));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    t.child("target/dev/hello_HelloStarknet.contract_class.json")
+        .assert(predicates::str::is_empty().not());
+}
+
+#[test]
+fn new_with_dual_license() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--license", "MIT OR Apache-2.0"])
+        .env("GIT_AUTHOR_NAME", "Jane Doe")
+        .env("GIT_AUTHOR_EMAIL", "jane@example.com")
+        .current_dir(&pt)
+        .assert()
+        .success();
+
+    let t = pt.child("hello");
+    let manifest = fs::read_to_string(t.child("Scarb.toml")).unwrap();
+    assert!(manifest.contains(r#"license = "MIT OR Apache-2.0""#));
+    t.child("LICENSE-MIT")
+        .assert(predicates::str::starts_with("MIT License\n"))
+        .assert(predicates::str::contains(" Jane Doe <jane@example.com>\n"));
+    t.child("LICENSE-APACHE")
+        .assert(predicates::str::contains("Apache License\n"));
+    assert!(!t.child("LICENSE").exists());
+}
+
+#[test]
+fn new_with_unknown_license() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["new", "hello", "--license", "GPL-3.0-only"])
+        .current_dir(&pt)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: Scarb does not know the text of `GPL-3.0-only` license
+            help: add the license file to the package manually
+
+            Created `hello` package.
+        "#});
+
+    let t = pt.child("hello");
+    let manifest = fs::read_to_string(t.child("Scarb.toml")).unwrap();
+    assert!(manifest.contains(r#"license = "GPL-3.0-only""#));
+    assert!(!t.child("LICENSE").exists());
+}
+
+#[test]
+fn new_gitignore_patterns() {
+    let pt = assert_fs::TempDir::new().unwrap();
+
+    Scarb::quick_snapbox()
+        .args([
+            "new",
+            "hello",
+            "--vcs",
+            "git",
+            "--test-runner",
+            "starknet-foundry",
+        ])
+        .current_dir(&pt)
+        .assert()
+        .success();
+
+    pt.child("hello/.gitignore")
+        .assert("target\n.env\n.snfoundry_cache/\n");
+}
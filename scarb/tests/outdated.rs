@@ -0,0 +1,91 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+fn project(registry: &mut LocalRegistry, cache_dir: &TempDir) -> TempDir {
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("qux", "1.0.0");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .dep("qux", Dep.version("1").registry(&*registry))
+        .build(&t.child("baz"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&*registry))
+        .dep("baz", Dep.path("../baz"))
+        .build_locked(&t.child("foo"), cache_dir);
+    t
+}
+
+#[test]
+fn outdated() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+    registry.publish_simple("bar", "1.1.0");
+    registry.publish_simple("bar", "2.0.0");
+    registry.publish_simple("bar", "3.0.0-rc.1");
+    registry.publish_simple("qux", "1.0.1");
+
+    Scarb::quick_snapbox()
+        .arg("outdated")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        Name  Current  Compatible  Latest  Kind
+        bar   1.0.0    1.1.0       2.0.0   direct
+        qux   1.0.0    1.0.1       1.0.1   transitive
+        "#});
+
+    // Checking for newer versions does not update the lockfile.
+    t.child("foo/Scarb.lock").assert(predicates::str::contains(
+        "name = \"bar\"\nversion = \"1.0.0\"\n",
+    ));
+}
+
+#[test]
+fn only_incompatible_versions_available() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+    registry.publish_simple("bar", "2.0.0");
+
+    Scarb::quick_snapbox()
+        .arg("outdated")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        Name  Current  Compatible  Latest  Kind
+        bar   1.0.0    -           2.0.0   direct
+        "#});
+}
+
+#[test]
+fn up_to_date() {
+    let mut registry = LocalRegistry::create();
+    let cache_dir = TempDir::new().unwrap();
+    let t = project(&mut registry, &cache_dir);
+
+    Scarb::quick_snapbox()
+        .arg("outdated")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        all dependencies are up to date
+        "#});
+}
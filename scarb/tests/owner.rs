@@ -0,0 +1,117 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+#[test]
+fn add_list_remove() {
+    let registry = LocalRegistry::with_bar();
+
+    Scarb::quick_snapbox()
+        .args([
+            "owner",
+            "bar",
+            "--add",
+            "alice,bob",
+            "--index",
+            &registry.url,
+        ])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Adding `alice` as owner of `bar`
+        [..] Adding `bob` as owner of `bar`
+        "#});
+
+    registry
+        .t
+        .child("owners/bar.json")
+        .assert(r#"["alice","bob"]"#);
+
+    Scarb::quick_snapbox()
+        .args([
+            "owner",
+            "bar",
+            "--remove",
+            "alice",
+            "--index",
+            &registry.url,
+        ])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Removing `alice` from owners of `bar`
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["owner", "bar", "--list", "--index", &registry.url])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        bob
+        "#});
+}
+
+#[test]
+fn defaults_to_current_package() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["owner", "--add", "alice", "--index", &registry.url])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Adding `alice` as owner of `bar`
+        "#});
+}
+
+#[test]
+fn remove_not_an_owner() {
+    let registry = LocalRegistry::with_bar();
+
+    Scarb::quick_snapbox()
+        .args([
+            "owner",
+            "bar",
+            "--remove",
+            "carol",
+            "--index",
+            &registry.url,
+        ])
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Removing `carol` from owners of `bar`
+        error: failed to remove owners of package `bar`
+
+        Caused by:
+            `carol` is not an owner of package `bar`
+        "#});
+}
+
+#[test]
+fn package_not_published() {
+    let registry = LocalRegistry::with_bar();
+
+    Scarb::quick_snapbox()
+        .args(["owner", "baz", "--list", "--index", &registry.url])
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to list owners of package `baz`
+
+        Caused by:
+            package `baz` is not published in this registry
+        "#});
+}
@@ -235,6 +235,23 @@ fn list_simple() {
         "#}));
 }
 
+#[test]
+fn list_json() {
+    let t = TempDir::new().unwrap();
+    simple_project().build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("package")
+        .arg("--list")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(indoc! {r#"
+            {"foo":["VERSION","Scarb.orig.toml","Scarb.toml","src/foo.cairo","src/lib.cairo"]}
+        "#});
+}
+
 #[test]
 fn list_workspace() {
     let t = TempDir::new().unwrap();
@@ -279,6 +296,7 @@ fn reserved_files_collision() {
         .version("1.0.0")
         .src("VERSION", "oops")
         .src("Scarb.orig.toml", "oops")
+        .src("VCS_INFO.json", "oops")
         .build(&t);
 
     Scarb::quick_snapbox()
@@ -288,7 +306,7 @@ fn reserved_files_collision() {
         .failure()
         .stdout_matches(formatdoc! {r#"
         [..] Packaging foo v1.0.0 [..]
-        error: invalid inclusion of reserved files in package: VERSION, Scarb.orig.toml
+        error: invalid inclusion of reserved files in package: VERSION, Scarb.orig.toml, VCS_INFO.json
         "#});
 }
 
@@ -753,6 +771,126 @@ fn clean_tar_headers() {
     }
 }
 
+#[test]
+fn reproducible_archives() {
+    let t = TempDir::new().unwrap();
+    simple_project().build(&t);
+
+    let package = || {
+        Scarb::quick_snapbox()
+            .arg("package")
+            .current_dir(&t)
+            .assert()
+            .success();
+        std::fs::read(t.child("target/package/foo-1.0.0.tar.zst")).unwrap()
+    };
+
+    let first = package();
+
+    // Rewriting files changes their modification times, which must not affect the archive.
+    let lib = t.child("src/lib.cairo");
+    let contents = std::fs::read_to_string(&lib).unwrap();
+    std::fs::remove_file(&lib).unwrap();
+    lib.write_str(&contents).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&lib, std::fs::Permissions::from_mode(0o600)).unwrap();
+    }
+
+    assert_eq!(package(), first);
+}
+
+#[cfg(unix)]
+#[test]
+fn executable_files_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let t = TempDir::new().unwrap();
+    simple_project().src("run.sh", "").build(&t);
+    std::fs::set_permissions(t.child("run.sh"), std::fs::Permissions::from_mode(0o700)).unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("package")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let mut archive = PackageChecker::open(&t.child("target/package/foo-1.0.0.tar.zst"));
+    for entry in archive.entries().expect("failed to get archive entries") {
+        let entry = entry.expect("failed to get archive entry");
+        let path = entry.path().unwrap().into_owned();
+        let expected_mode = if path.ends_with("run.sh") {
+            0o755
+        } else {
+            0o644
+        };
+        assert_eq!(entry.header().mode().unwrap(), expected_mode, "{path:?}");
+        assert_eq!(entry.header().mtime().unwrap(), 1, "{path:?}");
+    }
+}
+
+#[test]
+fn vcs_info() {
+    let g = gitx::new("repo", |t| {
+        simple_project().build(&t.child("foo"));
+    });
+    g.git([
+        "remote",
+        "add",
+        "origin",
+        "https://github.com/software-mansion/repo.git",
+    ]);
+    // Changes outside the package do not make it dirty.
+    g.child("README.md").write_str("").unwrap();
+
+    let package = || {
+        Scarb::quick_snapbox()
+            .arg("package")
+            .current_dir(g.child("foo"))
+            .assert()
+            .success();
+        PackageChecker::assert(&g.child("foo/target/package/foo-1.0.0.tar.zst"))
+    };
+
+    let commit_hash = gitx::git_command()
+        .args(["rev-parse", "HEAD"])
+        .current_dir(g.p.path())
+        .output()
+        .unwrap()
+        .stdout;
+    let commit_hash = String::from_utf8(commit_hash).unwrap();
+    let vcs_info = |dirty: bool| {
+        formatdoc! {r#"
+            {{
+              "git": {{
+                "commit_hash": "{commit_hash}",
+                "dirty": {dirty}
+              }},
+              "path_in_vcs": "foo",
+              "repository": "https://github.com/software-mansion/repo.git"
+            }}"#,
+            commit_hash = commit_hash.trim(),
+        }
+    };
+
+    package()
+        .contents(&[
+            "VERSION",
+            "VCS_INFO.json",
+            "Scarb.orig.toml",
+            "Scarb.toml",
+            "src/lib.cairo",
+            "src/foo.cairo",
+        ])
+        .file_eq("VCS_INFO.json", &vcs_info(false));
+
+    g.child("foo/src/foo.cairo")
+        .write_str("fn foo() -> felt252 { 42 }")
+        .unwrap();
+    package().file_eq("VCS_INFO.json", &vcs_info(true));
+}
+
 #[test_case("../.gitignore", false, false; "gitignore outside")]
 #[test_case("../.gitignore", true, false; "gitignore outside with git")]
 #[test_case("../.ignore", false, false; "ignore outside")]
@@ -782,6 +920,9 @@ fn ignore_file(ignore_path: &str, setup_git: bool, expect_ignore_to_work: bool)
     expected.push("VERSION");
     expected.push("Scarb.orig.toml");
     expected.push("Scarb.toml");
+    if setup_git {
+        expected.push("VCS_INFO.json");
+    }
     if !expect_ignore_to_work {
         expected.push("ignore.txt");
     }
@@ -800,6 +941,77 @@ fn ignore_file(ignore_path: &str, setup_git: bool, expect_ignore_to_work: bool)
         .stdout_eq(expected);
 }
 
+#[test]
+fn include_overrides_ignore_files() {
+    let g = gitx::new("package", |t| {
+        ProjectBuilder::start()
+            .name("foo")
+            .version("1.0.0")
+            .src("README.md", "")
+            .src("generated/lib.cairo", "")
+            .src("generated/tmp.txt", "")
+            .build(&t);
+        t.child("Scarb.toml")
+            .write_str(indoc! {r#"
+                [package]
+                name = "foo"
+                version = "1.0.0"
+                include = ["src/", "generated/*.cairo", "generated/tmp.txt"]
+                exclude = ["*.txt"]
+            "#})
+            .unwrap();
+        t.child(".gitignore").write_str("generated/\n").unwrap();
+    });
+
+    Scarb::quick_snapbox()
+        .arg("package")
+        .arg("--list")
+        .current_dir(g.p)
+        .assert()
+        .success()
+        .stdout_eq(unix_paths_to_os_lossy(indoc! {r#"
+            VERSION
+            Scarb.orig.toml
+            Scarb.toml
+            VCS_INFO.json
+            generated/lib.cairo
+            src/lib.cairo
+        "#}));
+}
+
+#[test]
+fn exclude() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("1.0.0")
+        .src("tests/fixtures/big.json", "")
+        .src("tests/test.cairo", "")
+        .build(&t);
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            exclude = ["tests/fixtures/"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("package")
+        .arg("--list")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(unix_paths_to_os_lossy(indoc! {r#"
+            VERSION
+            Scarb.orig.toml
+            Scarb.toml
+            src/lib.cairo
+            tests/test.cairo
+        "#}));
+}
+
 #[test]
 fn ignore_whitelist_pattern() {
     let t = TempDir::new().unwrap();
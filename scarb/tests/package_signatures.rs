@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::http::HttpRegistry;
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+use scarb_test_support::signing::SigningKey;
+
+fn project_depending_on(registry: &impl std::fmt::Display) -> TempDir {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+    t
+}
+
+fn trust_keys(registry: &impl std::fmt::Display, keys: &[&SigningKey]) -> TempDir {
+    let keys = keys
+        .iter()
+        .map(|key| format!("\"{}\"", key.public_key()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.signed]
+            index = "{registry}"
+            trusted-keys = [{keys}]
+        "#})
+        .unwrap();
+    config_dir
+}
+
+#[test]
+fn signed_package() {
+    let registry = HttpRegistry::with_bar();
+    let key = SigningKey::from_seed(1);
+    key.sign_file(registry.child("bar-1.0.0.tar.zst"));
+
+    let other_key = SigningKey::from_seed(2);
+    let config_dir = trust_keys(&registry, &[&other_key, &key]);
+    let t = project_depending_on(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn signature_verification_is_opt_in() {
+    let registry = HttpRegistry::with_bar();
+    let t = project_depending_on(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+}
+
+#[test]
+fn missing_signature() {
+    let registry = HttpRegistry::with_bar();
+    let key = SigningKey::from_seed(1);
+    let config_dir = trust_keys(&registry, &[&key]);
+    let t = project_depending_on(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        error: registry `registry+http://[..]` does not provide a signature for `bar v1.0.0 ([..])`, but signature verification is enabled
+        "#});
+}
+
+#[test]
+fn untrusted_signature() {
+    let registry = HttpRegistry::with_bar();
+    SigningKey::from_seed(1).sign_file(registry.child("bar-1.0.0.tar.zst"));
+
+    let key = SigningKey::from_seed(2);
+    let config_dir = trust_keys(&registry, &[&key]);
+    let t = project_depending_on(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Downloading bar v1.0.0 ([..])
+        error: failed to verify signature of `bar v1.0.0 ([..])`
+
+        Caused by:
+            package signature is invalid or has not been made with any of the trusted keys
+        "#});
+}
+
+#[test]
+fn local_registry_signature() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+    let key = SigningKey::from_seed(1);
+    key.sign_file(registry.t.child("bar-1.0.0.tar.zst"));
+
+    let config_dir = trust_keys(&registry, &[&key]);
+    let t = project_depending_on(&registry);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Unpacking bar v1.0.0 ([..])
+        "#});
+
+    // Tamper with the signature.
+    registry
+        .t
+        .child("bar-1.0.0.tar.zst.minisig")
+        .write_str(&SigningKey::from_seed(2).sign(b"something else"))
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to verify signature of `bar v1.0.0 ([..])`
+
+        Caused by:
+            package signature is invalid or has not been made with any of the trusted keys
+        "#});
+}
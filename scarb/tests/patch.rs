@@ -0,0 +1,271 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+use predicates::prelude::*;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::gitx;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+#[test]
+fn patch_registry_dependency_with_path() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    let patched = t.child("patched");
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.1")
+        .lib_cairo(r#"fn patched() -> felt252 { 42 }"#)
+        .build(&patched);
+
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::patched() }"#)
+        .manifest_extra(formatdoc! {r#"
+            [patch."{registry}"]
+            bar = {{ path = "../patched" }}
+        "#})
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    let lockfile = foo.child("Scarb.lock");
+    lockfile.assert(predicates::str::contains(indoc! {r#"
+        [[package]]
+        name = "bar"
+        version = "1.0.1"
+    "#}));
+    lockfile.assert(predicates::str::contains("registry+").not());
+}
+
+#[test]
+fn patch_applies_to_transitive_dependencies() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.1")
+        .lib_cairo(r#"fn patched() -> felt252 { 42 }"#)
+        .build(&t.child("patched"));
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn g() -> felt252 { bar::patched() }"#)
+        .build(&t.child("baz"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("baz", Dep.path("../baz"))
+        .lib_cairo(r#"fn f() -> felt252 { baz::g() }"#)
+        .build(&t.child("foo"));
+    t.child("Scarb.toml")
+        .write_str(&formatdoc! {r#"
+            [workspace]
+            members = ["foo"]
+
+            [patch."{registry}"]
+            bar = {{ path = "patched" }}
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn patch_in_member_is_ignored() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.1")
+        .lib_cairo(r#"fn patched() -> felt252 { 42 }"#)
+        .build(&t.child("patched"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .manifest_extra(formatdoc! {r#"
+            [patch."{registry}"]
+            bar = {{ path = "../patched" }}
+        "#})
+        .build(&t.child("foo"));
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["foo"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        warn: patch for the non root package will be ignored, specify patch at the workspace root: [..]Scarb.toml
+        [..] Unpacking bar v1.0.0 ([..])
+        "#});
+}
+
+#[test]
+fn patch_with_git_is_locked() {
+    let registry = LocalRegistry::with_bar();
+
+    let bar = gitx::new("bar", |t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.1")
+            .lib_cairo("fn f() -> felt252 { 11111111111101 }")
+            .build(&t)
+    });
+
+    // Use the same cache dir to prevent downloading git dep second time for the locked rev.
+    let cache_dir = TempDir::new().unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .manifest_extra(formatdoc! {r#"
+            [patch."{registry}"]
+            bar = {{ git = "{git}" }}
+        "#, git = bar.url()})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]  Updating git repository file://[..]/bar
+        [..] Compiling foo v0.1.0 ([..])
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(r#"source = "git+file://"#));
+    t.child("target/dev/foo.sierra.json")
+        .assert(predicates::str::contains("11111111111101"));
+
+    bar.change_file("src/lib.cairo", "fn f() -> felt252 { 11111111111102 }");
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..])
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    t.child("target/dev/foo.sierra.json")
+        .assert(predicates::str::contains("11111111111101"));
+}
+
+#[test]
+fn patch_unknown_source() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [patch.unknown]
+            bar = { path = "bar" }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to parse manifest at: [..]Scarb.toml
+
+        Caused by:
+            `[patch.unknown]` must refer to a URL or a registry defined in Scarb configuration
+        "#});
+}
+
+#[test]
+fn patch_from_config() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.1")
+        .lib_cairo(r#"fn from_manifest() -> felt252 { 42 }"#)
+        .build(&t.child("manifest_patch"));
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.2")
+        .lib_cairo(r#"fn from_config() -> felt252 { 42 }"#)
+        .build(&t.child("config_patch"));
+
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::from_config() }"#)
+        .manifest_extra(formatdoc! {r#"
+            [patch."{registry}"]
+            bar = {{ path = "../manifest_patch" }}
+        "#})
+        .build(&foo);
+
+    // Config patches take precedence over manifest ones,
+    // and relative paths are resolved against the project root.
+    foo.child(".scarb/config.toml")
+        .write_str(&formatdoc! {r#"
+            [patch."{registry}"]
+            bar = {{ path = "../config_patch" }}
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    foo.child("Scarb.lock")
+        .assert(predicates::str::contains(r#"version = "1.0.2""#));
+}
@@ -5,7 +5,8 @@ use indoc::indoc;
 use scarb_metadata::Metadata;
 use scarb_test_support::command::{CommandExt, Scarb};
 use scarb_test_support::fsx::ChildPathEx;
-use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
 #[test]
 fn build_defaults_to_dev() {
@@ -575,13 +576,57 @@ fn custom_profiles_can_inherit_by_name() {
 }
 
 #[test]
-fn custom_profiles_can_inherit_dev_and_release_only() {
+fn custom_profiles_can_inherit_custom_profiles() {
     let t = TempDir::new().unwrap();
     ProjectBuilder::start()
         .name("hello")
         .manifest_extra(indoc! {r#"
             [profile.some-profile]
+            inherits = "release"
+
+            [profile.custom]
+            inherits = "some-profile"
+        "#})
+        .build(&t);
+
+    let metadata = Scarb::quick_snapbox()
+        .args([
+            "--json",
+            "--profile",
+            "custom",
+            "metadata",
+            "--format-version",
+            "1",
+        ])
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+
+    assert_eq!(metadata.current_profile, "custom".to_string());
+    assert!(!metadata.compilation_units.is_empty());
+    for cu in metadata.compilation_units {
+        let compiler_config = cu.compiler_config;
+        assert!(!compiler_config
+            .get("sierra_replace_ids")
+            .unwrap()
+            .as_bool()
+            .unwrap());
+    }
+
+    Scarb::quick_snapbox()
+        .args(["--profile", "custom", "build"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("target/custom/hello.sierra.json")
+        .assert(predicates::path::is_file());
+}
 
+#[test]
+fn custom_profiles_cannot_inherit_undefined_profiles() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
             [profile.custom]
             inherits = "some-profile"
         "#})
@@ -596,7 +641,34 @@ fn custom_profiles_can_inherit_dev_and_release_only() {
             error: failed to parse manifest at: [..]
 
             Caused by:
-                profile can inherit from `dev` or `release` only, found `some-profile`
+                profile `custom` inherits from `some-profile`, which is not defined
+        "#});
+}
+
+#[test]
+fn profile_inheritance_cycle() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [profile.first]
+            inherits = "second"
+
+            [profile.second]
+            inherits = "first"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--profile", "first", "metadata", "--format-version", "1"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]
+
+            Caused by:
+                profile inheritance cycle detected: `first` -> `second` -> `first`
         "#});
 }
 
@@ -659,3 +731,116 @@ fn profile_overrides_tool() {
         "some-other-value"
     );
 }
+
+#[test]
+fn profile_overrides_member_package_settings() {
+    let t = TempDir::new().unwrap();
+    for name in ["first", "second"] {
+        ProjectBuilder::start().name(name).build(&t.child(name));
+    }
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .manifest_extra(indoc! {r#"
+            [profile.dev.package.second.cairo]
+            sierra-replace-ids = false
+
+            [profile.release.package.first.cairo]
+            sierra-replace-ids = true
+        "#})
+        .build(&t);
+
+    let replace_ids = |args: &[&str]| {
+        let metadata = Scarb::quick_snapbox()
+            .args(["--json"])
+            .args(args)
+            .args(["metadata", "--format-version", "1"])
+            .current_dir(&t)
+            .stdout_json::<Metadata>();
+        let mut units = metadata
+            .compilation_units
+            .into_iter()
+            .filter(|cu| cu.target.kind == "lib")
+            .map(|cu| {
+                let name = cu.package.to_string();
+                let name = name.split_whitespace().next().unwrap().to_string();
+                let replace_ids = cu.compiler_config["sierra_replace_ids"].as_bool().unwrap();
+                (name, replace_ids)
+            })
+            .collect::<Vec<_>>();
+        units.sort();
+        units
+    };
+
+    assert_eq!(
+        replace_ids(&[]),
+        vec![("first".to_string(), true), ("second".to_string(), false)]
+    );
+    assert_eq!(
+        replace_ids(&["--release"]),
+        vec![("first".to_string(), true), ("second".to_string(), false)]
+    );
+}
+
+#[test]
+fn warn_on_profile_override_of_unknown_package() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [profile.dev.package.unknown.cairo]
+            sierra-replace-ids = false
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: profile `dev` overrides settings of package `unknown`, which is not used in this workspace
+        "#});
+}
+
+#[test]
+fn profile_overrides_dependency_package_settings() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("dep")
+        .manifest_extra(indoc! {r#"
+            [tool.some-tool]
+            some-key = "some-value"
+            other-key = "other-value"
+        "#})
+        .build(&t.child("dep"));
+    ProjectBuilder::start()
+        .name("hello")
+        .dep("dep", Dep.path("../dep"))
+        .manifest_extra(indoc! {r#"
+            [profile.release.package.dep.tool.some-tool]
+            some-key = "overridden-value"
+        "#})
+        .build(&t.child("hello"));
+
+    let some_key = |args: &[&str]| {
+        let metadata = Scarb::quick_snapbox()
+            .arg("--json")
+            .args(args)
+            .args(["metadata", "--format-version", "1"])
+            .current_dir(t.child("hello"))
+            .stdout_json::<Metadata>();
+        let dep = metadata
+            .packages
+            .into_iter()
+            .find(|package| package.name == "dep")
+            .unwrap();
+        dep.manifest_metadata.tool.unwrap()["some-tool"]["some-key"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(some_key(&[]), "some-value");
+    assert_eq!(some_key(&["--release"]), "overridden-value");
+}
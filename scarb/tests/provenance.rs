@@ -0,0 +1,183 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+use snapbox::cmd::OutputAssert;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::signing::SigningKey;
+
+const GITHUB_ACTIONS_ENV: &[(&str, &str)] = &[
+    ("GITHUB_ACTIONS", "true"),
+    ("GITHUB_SERVER_URL", "https://github.com"),
+    ("GITHUB_REPOSITORY", "foo/bar"),
+    ("GITHUB_RUN_ID", "42"),
+    ("GITHUB_RUN_ATTEMPT", "1"),
+    (
+        "GITHUB_WORKFLOW_REF",
+        "foo/bar/.github/workflows/release.yml@refs/tags/v1.0.0",
+    ),
+    ("GITHUB_SHA", "0123456789abcdef0123456789abcdef01234567"),
+];
+
+fn bar() -> TempDir {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+        .build(&t);
+    t
+}
+
+fn publish_with_provenance(registry: &LocalRegistry, key: &SigningKey) {
+    let t = bar();
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--no-verify")
+        .arg("--provenance")
+        .arg("--index")
+        .arg(&registry.url)
+        .envs(GITHUB_ACTIONS_ENV.iter().copied())
+        .env("SCARB_PROVENANCE_KEY", key.secret_key())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..] Attesting bar v1.0.0 ([..])
+        [..] Uploading bar v1.0.0 (registry+file://[..])
+        "#});
+}
+
+fn fetch_with_provenance_keys(registry: &LocalRegistry, key: &SigningKey) -> OutputAssert {
+    let config_dir = TempDir::new().unwrap();
+    config_dir
+        .child("config.toml")
+        .write_str(&formatdoc! {r#"
+            [registries.attested]
+            index = "{registry}"
+            provenance-keys = ["{key}"]
+        "#, key = key.public_key()})
+        .unwrap();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CONFIG", config_dir.path())
+        .current_dir(&t)
+        .assert()
+}
+
+#[test]
+fn publish_and_verify_provenance() {
+    let mut registry = LocalRegistry::create();
+    let key = SigningKey::from_seed(1);
+    publish_with_provenance(&registry, &key);
+
+    let attestation = registry.t.child("bar-1.0.0.tar.zst.provenance.json");
+    attestation.assert(predicates::path::is_file());
+    let attestation: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(attestation.path()).unwrap()).unwrap();
+    assert_eq!(attestation["subject"][0]["name"], "bar-1.0.0.tar.zst");
+    assert_eq!(
+        attestation["predicate"]["buildDefinition"]["externalParameters"]["source"],
+        serde_json::json!({
+            "repository": "https://github.com/foo/bar",
+            "commit": "0123456789abcdef0123456789abcdef01234567",
+        })
+    );
+    assert_eq!(
+        attestation["predicate"]["runDetails"]["builder"]["id"],
+        "https://github.com/foo/bar/.github/workflows/release.yml@refs/tags/v1.0.0"
+    );
+    registry
+        .t
+        .child("bar-1.0.0.tar.zst.provenance.json.minisig")
+        .assert(predicates::path::is_file());
+
+    fetch_with_provenance_keys(&registry, &key).success();
+
+    // Packages published without attestation are rejected.
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.1")
+            .build(t);
+    });
+    fetch_with_provenance_keys(&registry, &key)
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to verify provenance of `bar v1.0.1 (registry+file://[..])`
+
+        Caused by:
+            registry `registry+file://[..]` does not provide a provenance attestation for `bar v1.0.1 (registry+file://[..])`, but provenance verification is enabled
+        "#});
+}
+
+#[test]
+fn provenance_signed_with_untrusted_key() {
+    let registry = LocalRegistry::create();
+    publish_with_provenance(&registry, &SigningKey::from_seed(1));
+
+    fetch_with_provenance_keys(&registry, &SigningKey::from_seed(2))
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to verify provenance of `bar v1.0.0 (registry+file://[..])`
+
+        Caused by:
+            0: provenance attestation signature is invalid
+            1: package signature is invalid or has not been made with any of the trusted keys
+        "#});
+}
+
+#[test]
+fn provenance_requires_ci() {
+    let t = bar();
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--dry-run")
+        .arg("--provenance")
+        .arg("--index")
+        .arg(LocalRegistry::create().url)
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("GITLAB_CI")
+        .env(
+            "SCARB_PROVENANCE_KEY",
+            SigningKey::from_seed(1).secret_key(),
+        )
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: provenance attestations can only be generated in GitHub Actions or GitLab CI
+        "#});
+}
+
+#[test]
+fn provenance_requires_key() {
+    let t = bar();
+    Scarb::quick_snapbox()
+        .arg("publish")
+        .arg("--provenance")
+        .arg("--index")
+        .arg(LocalRegistry::create().url)
+        .env_remove("SCARB_PROVENANCE_KEY")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: `--provenance` requires a minisign secret key in `SCARB_PROVENANCE_KEY`
+
+        Caused by:
+            environment variable not found
+        "#});
+}
@@ -1,5 +1,10 @@
+use std::fs;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
 use indoc::indoc;
 
+use scarb_test_support::command::Scarb;
 use scarb_test_support::manifest_edit::ManifestEditHarness;
 
 #[test]
@@ -116,3 +121,117 @@ fn dry_run() {
         "#})
         .run();
 }
+
+fn workspace_inheriting_dependencies() -> TempDir {
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+
+            [workspace.dependencies]
+            foo = "1.0.0"
+            bar = "1.0.0"
+        "#})
+        .unwrap();
+    t.child("first/Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "first"
+            version = "1.0.0"
+
+            [dependencies]
+            foo = { workspace = true }
+            bar = { workspace = true }
+        "#})
+        .unwrap();
+    t.child("second/Scarb.toml")
+        .write_str(indoc! {r#"
+            [package]
+            name = "second"
+            version = "1.0.0"
+
+            [target-dependencies.test]
+            bar = { workspace = true }
+        "#})
+        .unwrap();
+    for member in ["first", "second"] {
+        t.child(format!("{member}/src/lib.cairo"))
+            .write_str("fn foo() -> felt252 { 42 }")
+            .unwrap();
+    }
+    t
+}
+
+#[test]
+fn prune_unused_workspace_dependency() {
+    let t = workspace_inheriting_dependencies();
+
+    Scarb::quick_snapbox()
+        .args([
+            "--offline",
+            "remove",
+            "--package",
+            "first",
+            "foo",
+            "bar",
+            "--prune",
+        ])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Removing foo from dependencies
+            [..]Removing bar from dependencies
+            [..]Removing foo from workspace.dependencies
+        "#});
+
+    assert_eq!(
+        fs::read_to_string(t.child("first/Scarb.toml")).unwrap(),
+        indoc! {r#"
+            [package]
+            name = "first"
+            version = "1.0.0"
+
+            [dependencies]
+        "#}
+    );
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        indoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+
+            [workspace.dependencies]
+            bar = "1.0.0"
+        "#}
+    );
+}
+
+#[test]
+fn warn_about_unused_workspace_dependency() {
+    let t = workspace_inheriting_dependencies();
+
+    Scarb::quick_snapbox()
+        .args(["--offline", "remove", "--package", "first", "foo"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Removing foo from dependencies
+            warn: dependency `foo` in `[workspace.dependencies]` is no longer used by any workspace member
+            help: pass `--prune` to remove such dependencies from the workspace manifest
+        "#});
+
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        indoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+
+            [workspace.dependencies]
+            foo = "1.0.0"
+            bar = "1.0.0"
+        "#}
+    );
+}
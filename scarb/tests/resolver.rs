@@ -1,10 +1,13 @@
+use std::fs;
+
 use assert_fs::prelude::*;
 use assert_fs::TempDir;
 use indoc::indoc;
 
 use scarb_test_support::command::Scarb;
 use scarb_test_support::gitx;
-use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
 
 /// https://github.com/software-mansion/scarb/issues/600
 #[test]
@@ -62,3 +65,61 @@ fn issue_600_git() {
             [..] Updating git repository [..]
         "#});
 }
+
+#[test]
+fn semver_incompatible_versions() {
+    let mut registry = LocalRegistry::create();
+    let url = registry.url.clone();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo("fn f() -> felt252 { 1 }")
+            .build(t);
+    });
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("2.0.0")
+            .lib_cairo("fn g() -> felt252 { 2 }")
+            .build(t);
+    });
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("baz")
+            .version("1.0.0")
+            .dep("bar", Dep.version("1").registry(&url))
+            .lib_cairo("fn f() -> felt252 { bar::f() }")
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("2").registry(&url))
+        .dep("baz", Dep.version("1").registry(&url))
+        .lib_cairo("fn f() -> felt252 { bar::g() + baz::f() }")
+        .build(&t);
+
+    // Both versions are resolved and locked.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+    let lockfile = fs::read_to_string(t.child("Scarb.lock")).unwrap();
+    assert!(lockfile.contains("name = \"bar\"\nversion = \"1.0.0\""));
+    assert!(lockfile.contains("name = \"bar\"\nversion = \"2.0.0\""));
+
+    // But they cannot be compiled together, as `baz` would see `bar` 2.0.0 instead of 1.0.0.
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: compilation unit of `foo` depends on semver-incompatible versions of `bar`: 1.0.0, 2.0.0
+            help: Cairo crates of a compilation unit share a single namespace, make these packages depend on compatible versions of `bar`: baz v1.0.0 ([..]), foo v0.1.0 ([..])
+        "#});
+}
@@ -0,0 +1,176 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use serde_json::{json, Value};
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+/// Send newline-delimited `requests` to `scarb rpc` run in `t`, and collect its responses.
+fn rpc(t: &TempDir, requests: &[Value]) -> Vec<Value> {
+    let input = requests
+        .iter()
+        .map(|request| format!("{request}\n"))
+        .collect::<String>();
+    let cmd = Scarb::quick_snapbox()
+        .arg("rpc")
+        .current_dir(t)
+        .stdin(input)
+        .assert()
+        .success();
+    let output = cmd.get_output().stdout.clone();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+#[test]
+fn version_and_config() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    let responses = rpc(
+        &t,
+        &[
+            request(1, "version", Value::Null),
+            request(2, "config", json!({"key": "profile"})),
+            request(3, "config", Value::Null),
+        ],
+    );
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["interfaceVersion"], 1);
+    assert_eq!(
+        responses[1],
+        json!({"jsonrpc": "2.0", "id": 2, "result": "dev"})
+    );
+    let config = &responses[2]["result"];
+    assert!(config["manifestPath"]
+        .as_str()
+        .unwrap()
+        .ends_with("Scarb.toml"));
+    assert!(config["targetDir"].as_str().unwrap().ends_with("target"));
+    assert_eq!(config["offline"], false);
+}
+
+#[test]
+fn metadata_and_compilation_units() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    let responses = rpc(
+        &t,
+        &[
+            request(1, "metadata", json!({"noDeps": true})),
+            request(2, "compilationUnits", json!({"package": "hello"})),
+            request(3, "compilationUnits", json!({"package": "missing"})),
+        ],
+    );
+
+    let packages = responses[0]["result"]["packages"].as_array().unwrap();
+    assert!(packages.iter().any(|package| package["name"] == "hello"));
+    let units = responses[1]["result"].as_array().unwrap();
+    assert!(!units.is_empty());
+    assert!(units
+        .iter()
+        .all(|unit| unit["package"].as_str().unwrap().starts_with("hello ")));
+    assert_eq!(responses[2]["result"], json!([]));
+}
+
+#[test]
+fn reload() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    let responses = rpc(
+        &t,
+        &[
+            request(1, "metadata", json!({"noDeps": true})),
+            request(2, "reload", Value::Null),
+            request(3, "metadata", json!({"noDeps": true})),
+        ],
+    );
+
+    assert_eq!(
+        responses[1],
+        json!({"jsonrpc": "2.0", "id": 2, "result": null})
+    );
+    assert_eq!(responses[0]["result"], responses[2]["result"]);
+}
+
+#[test]
+fn errors() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    let responses = rpc(
+        &t,
+        &[
+            json!("not a request"),
+            request(1, "frobnicate", Value::Null),
+            request(2, "config", json!({"key": "color"})),
+            json!({"jsonrpc": "2.0", "method": "version"}),
+            json!({"jsonrpc": "1.0", "id": 3, "method": "version"}),
+        ],
+    );
+
+    // Notifications are not responded to.
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["error"]["code"], -32600);
+    assert_eq!(
+        responses[1]["error"],
+        json!({"code": -32601, "message": "unknown method: frobnicate"})
+    );
+    assert_eq!(
+        responses[2]["error"],
+        json!({"code": -32602, "message": "unknown config key: color"})
+    );
+    assert_eq!(responses[3]["id"], 3);
+    assert_eq!(responses[3]["error"]["code"], -32600);
+}
+
+#[test]
+fn workspace_errors() {
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml").write_str("not toml").unwrap();
+
+    let responses = rpc(
+        &t,
+        &[
+            request(1, "version", Value::Null),
+            request(2, "metadata", Value::Null),
+        ],
+    );
+
+    assert!(responses[0]["result"].is_object());
+    assert_eq!(responses[1]["error"]["code"], -32000);
+    assert!(responses[1]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("failed to parse manifest at:"));
+}
+
+#[test]
+fn shutdown() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    let responses = rpc(
+        &t,
+        &[
+            request(1, "shutdown", Value::Null),
+            request(2, "version", Value::Null),
+        ],
+    );
+
+    assert_eq!(
+        responses,
+        vec![json!({"jsonrpc": "2.0", "id": 1, "result": null})]
+    );
+}
@@ -365,3 +365,393 @@ fn additional_args_not_parsed_as_package_filter() {
         .success()
         .stdout_eq("Hello -p world\n");
 }
+
+#[test]
+fn run_workspace_script() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        hello = "echo 'Hello from first!'"
+        "#})
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .build(&t.child("second"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .manifest_extra(indoc! {r#"
+        [workspace.scripts]
+        hello = "echo 'Hello from workspace!'"
+        touch = "echo 'touched' > touched.txt"
+        "#})
+        .build(&t);
+
+    // Workspace scripts are run once, in the workspace root.
+    Scarb::quick_snapbox()
+        .args(["run", "hello"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("Hello from workspace!\n");
+    Scarb::quick_snapbox()
+        .args(["run", "touch"])
+        .current_dir(t.child("second"))
+        .assert()
+        .success();
+    t.child("touched.txt").assert("touched\n");
+    t.child("second/touched.txt")
+        .assert(predicates::path::missing());
+
+    // Package scripts take precedence in package directories.
+    Scarb::quick_snapbox()
+        .args(["run", "hello"])
+        .current_dir(t.child("first"))
+        .assert()
+        .success()
+        .stdout_eq("Hello from first!\n");
+
+    // Scripts can be fanned out across all members.
+    Scarb::quick_snapbox()
+        .args(["run", "--workspace", "hello"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(indoc! {r#"
+            Hello from first!
+            Hello from workspace!
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("run")
+        .current_dir(t.child("second"))
+        .assert()
+        .success()
+        .stdout_eq(indoc! {r#"
+            Scripts available via `scarb run` for package `second`:
+
+            Scripts available via `scarb run` for workspace:
+            hello                 : echo 'Hello from workspace!'
+            touch                 : echo 'touched' > touched.txt
+
+        "#});
+}
+
+#[test]
+fn env_section_is_passed_to_scripts() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        show = "echo $GREETING $TARGET $ASSETS"
+
+        [env]
+        GREETING = "hello"
+        TARGET = { value = "manifest", force = true }
+        ASSETS = { value = "assets", relative = true }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches("hello manifest [..]/assets\n");
+
+    // Variables set in the environment take precedence, unless forced.
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(&t)
+        .env("GREETING", "hi")
+        .env("TARGET", "process")
+        .assert()
+        .success()
+        .stdout_matches("hi manifest [..]/assets\n");
+}
+
+#[test]
+fn env_section_is_read_from_workspace_root() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        show = "echo $GREETING"
+        "#})
+        .build(&t.child("first"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .manifest_extra(indoc! {r#"
+        [env]
+        GREETING = "hello from workspace"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(t.child("first"))
+        .assert()
+        .success()
+        .stdout_eq("hello from workspace\n");
+}
+
+#[test]
+fn env_file_is_loaded() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        show = "echo $RPC_URL $ACCOUNT"
+
+        [env]
+        ACCOUNT = "alice"
+        "#})
+        .build(&t);
+    t.child(".env")
+        .write_str(indoc! {r#"
+            # Local settings.
+            RPC_URL=https://example.com/rpc
+            ACCOUNT=bob
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("https://example.com/rpc alice\n");
+}
+
+#[test]
+fn env_file_is_configurable_per_profile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        show = "echo $RPC_URL"
+
+        [profile.release]
+        env-file = ".env.mainnet"
+        "#})
+        .build(&t);
+    t.child(".env")
+        .write_str("RPC_URL=http://localhost:5050\n")
+        .unwrap();
+    t.child(".env.mainnet")
+        .write_str("RPC_URL=https://mainnet.example.com\n")
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("http://localhost:5050\n");
+
+    Scarb::quick_snapbox()
+        .args(["--release", "run", "show"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("https://mainnet.example.com\n");
+}
+
+#[test]
+fn missing_env_file_of_profile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        show = "echo $RPC_URL"
+
+        [profile.dev]
+        env-file = ".env.local"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "show"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to read `[..].env.local`
+            ...
+        "#});
+}
+
+#[test]
+fn needed_scripts_are_run_first() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        build = "echo 'building'"
+        generate-abi = { script = "echo 'generating ABI'", needs = ["build"] }
+        deploy = { script = "echo 'deploying'", needs = ["build", "generate-abi"] }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "deploy", "--", "--network", "sepolia"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Running build (echo 'building')
+            building
+            [..] Running generate-abi (echo 'generating ABI')
+            generating ABI
+            deploying --network sepolia
+        "#});
+}
+
+#[test]
+fn independent_needed_scripts_run_concurrently() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        slow = "sleep 1 && echo 'slow'"
+        fast = "echo 'fast'"
+        all = { script = "echo 'all'", needs = ["slow", "fast"] }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "all"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Running slow (sleep 1 && echo 'slow')
+            [..] Running fast (echo 'fast')
+            fast
+            slow
+            all
+        "#});
+}
+
+#[test]
+fn failing_needed_script_stops_run() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        build = "exit 7"
+        deploy = { script = "echo 'deploying'", needs = ["build"] }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "deploy"])
+        .current_dir(&t)
+        .assert()
+        .code(7)
+        .stdout_matches(indoc! {r#"
+            [..] Running build (exit 7)
+            error: script `build` needed by `deploy` failed
+        "#});
+}
+
+#[test]
+fn script_dependency_cycle() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        a = { script = "echo 'a'", needs = ["b"] }
+        b = { script = "echo 'b'", needs = ["a"] }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "a"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: script dependency cycle detected: `a` -> `b` -> `a`
+        "#});
+}
+
+#[test]
+fn platform_specific_script() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        platform = { unix = "echo 'unix'", windows = "echo 'windows'" }
+        fallback = { script = "echo 'anywhere'", windows = "echo 'windows'" }
+        "#})
+        .build(&t);
+
+    let expected = if cfg!(windows) { "windows\n" } else { "unix\n" };
+    Scarb::quick_snapbox()
+        .args(["run", "platform"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(expected);
+
+    let expected = if cfg!(windows) {
+        "windows\n"
+    } else {
+        "anywhere\n"
+    };
+    Scarb::quick_snapbox()
+        .args(["run", "fallback"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(expected);
+}
+
+#[test]
+#[cfg(unix)]
+fn script_without_command_for_platform() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        clean = { windows = "rmdir /s /q out" }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "clean"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: script does not define a command for this platform, nor a platform-independent `script` command
+        "#});
+}
+
+#[test]
+#[cfg(unix)]
+fn script_in_system_shell() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+        [scripts]
+        greet = { script = 'printf "%s\n" hello $NAME', shell = "sh" }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["run", "greet", "--", "beautiful world"])
+        .current_dir(&t)
+        .env("NAME", "scarb")
+        .assert()
+        .success()
+        .stdout_eq(indoc! {r#"
+            hello
+            scarb
+            beautiful world
+        "#});
+}
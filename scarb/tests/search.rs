@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use assert_fs::prelude::*;
+use indoc::indoc;
+use serde_json::json;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::registry::http::HttpRegistry;
+use scarb_test_support::registry::local::LocalRegistry;
+
+#[test]
+fn local_registry() {
+    let mut registry = LocalRegistry::create();
+    for (name, version) in [
+        ("alexandria_math", "0.1.0"),
+        ("alexandria_math", "0.2.0"),
+        ("alexandria_ascii", "0.1.0"),
+        ("bar", "1.0.0"),
+    ] {
+        registry.publish(|t| {
+            ProjectBuilder::start()
+                .name(name)
+                .version(version)
+                .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+                .build(t);
+        });
+    }
+
+    Scarb::quick_snapbox()
+        .args(["search", "alexandria", "--index", &registry.url])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        alexandria_ascii = "0.1.0"
+        alexandria_math = "0.2.0"
+        "#});
+
+    Scarb::quick_snapbox()
+        .args([
+            "search",
+            "alexandria",
+            "--limit",
+            "1",
+            "--index",
+            &registry.url,
+        ])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        alexandria_ascii = "0.1.0"
+        ... and 1 more packages
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["search", "baz", "--index", &registry.url])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        no packages found
+        "#});
+}
+
+#[test]
+fn http_registry() {
+    let registry = HttpRegistry::serve();
+    registry.enable_api();
+    registry
+        .child("api/v1/packages")
+        .write_str(
+            &json!({
+                "packages": [
+                    {"name": "bar", "version": "1.0.0", "description": "Bar library"},
+                    {"name": "foobar", "version": "0.10.0", "description": "Everything"},
+                    {"name": "barbaz", "version": "2.0.0"}
+                ],
+                "total": 3
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["search", "bar", "--index", &registry.to_string()])
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        bar = "1.0.0"        # Bar library
+        foobar = "0.10.0"    # Everything
+        barbaz = "2.0.0"
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["--json", "search", "bar", "--index", &registry.to_string()])
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        {"packages":[{"name":"bar","version":"1.0.0","description":"Bar library"},{"name":"foobar","version":"0.10.0","description":"Everything"},{"name":"barbaz","version":"2.0.0"}],"total":3}
+        "#});
+}
+
+#[test]
+fn http_registry_without_api() {
+    let registry = HttpRegistry::serve();
+
+    Scarb::quick_snapbox()
+        .args(["search", "bar", "--index", &registry.to_string()])
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to search registry: registry+http://[..]
+
+        Caused by:
+            registry does not provide api endpoint: registry+http://[..]
+        "#});
+}
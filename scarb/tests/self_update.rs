@@ -0,0 +1,44 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_build_metadata::SCARB_VERSION;
+use scarb_test_support::command::Scarb;
+
+#[test]
+fn update_to_installed_version() {
+    let t = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .args(["self", "update", "--version", SCARB_VERSION])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq(format!("Scarb v{SCARB_VERSION} is already installed\n"));
+}
+
+#[test]
+fn update_offline() {
+    let t = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .args(["--offline", "self", "update"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq(indoc! {r#"
+            error: cannot update Scarb in offline mode
+        "#});
+}
+
+#[test]
+fn update_ignores_pinned_toolchain() {
+    let t = TempDir::new().unwrap();
+    t.child(".scarb-version").write_str("9.9.9").unwrap();
+    Scarb::quick_snapbox()
+        .args(["--offline", "self", "update"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_eq(indoc! {r#"
+            error: cannot update Scarb in offline mode
+        "#});
+}
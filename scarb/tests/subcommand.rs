@@ -3,9 +3,13 @@ use std::net::TcpListener;
 use std::process::{Child, Command};
 use std::{env, io};
 
+#[cfg(unix)]
+use assert_fs::prelude::*;
 use assert_fs::TempDir;
 #[cfg(unix)]
 use indoc::indoc;
+#[cfg(unix)]
+use scarb::process::make_executable;
 use scarb_test_support::cargo::cargo_bin;
 
 use scarb_test_support::command::Scarb;
@@ -49,7 +53,49 @@ fn list_commands_e2e() {
         .success();
     let output = cmd.get_output().stdout.clone();
     let stdout = String::from_utf8(output).unwrap();
-    assert!(stdout.starts_with("Installed Commands:\n"))
+    assert!(stdout.starts_with("Built-in commands:\n"));
+    assert!(stdout.contains("\nExternal commands:\n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn list_commands_with_descriptions() {
+    let t = TempDir::new().unwrap();
+    write_script(
+        "hello",
+        indoc! {r#"
+            #!/usr/bin/env sh
+            case "$1" in
+                --version) echo "scarb-hello 1.2.0" ;;
+                --help) printf "Say hello to the world\n\nUsage: scarb hello [NAME]\n" ;;
+                *) echo "Hello $@" ;;
+            esac
+        "#},
+        &t,
+    );
+    write_script("bye", "#!/usr/bin/env sh\nexit 1\n", &t);
+
+    let cmd = Scarb::quick_snapbox()
+        .args(["--list"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .success();
+    let output = cmd.get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let (builtin, external) = stdout.split_once("\nExternal commands:\n").unwrap();
+    assert!(builtin.contains("\n    build "));
+    let lines = external.lines().collect::<Vec<_>>();
+    let hello = lines
+        .iter()
+        .find(|line| line.trim_start().starts_with("hello "))
+        .unwrap();
+    assert!(hello.contains("v1.2.0  Say hello to the world"), "{hello}");
+    // Subcommands which cannot describe themselves are listed with their path.
+    let bye = lines
+        .iter()
+        .find(|line| line.trim_start().starts_with("bye "))
+        .unwrap();
+    assert!(bye.ends_with("scarb-bye"), "{bye}");
 }
 
 #[test]
@@ -130,6 +176,38 @@ fn env_scarb_log_is_passed_verbatim() {
         .success();
 }
 
+#[test]
+#[cfg(unix)]
+fn env_section_is_passed() {
+    let t = TempDir::new().unwrap();
+    write_script(
+        "env",
+        indoc! {
+            r#"
+            #!/usr/bin/env bash
+            echo "$GREETING"
+            "#
+        },
+        &t,
+    );
+
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+            [env]
+            GREETING = "hello"
+        "#})
+        .build(&p);
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .arg("env")
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .success()
+        .stdout_eq("hello\n");
+}
+
 #[test]
 fn ctrl_c_kills_everyone() {
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
@@ -215,3 +293,159 @@ fn can_list_scarb_directory_scripts() {
     let stdout = String::from_utf8(output).unwrap();
     assert!(stdout.contains("hello"))
 }
+
+#[cfg(unix)]
+fn write_versioned_hello_script(version: &str, t: &TempDir) {
+    write_script(
+        "hello",
+        &format!(
+            indoc! {r#"
+                #!/usr/bin/env sh
+                if [ "$1" = "--version" ]; then
+                    echo "scarb-hello {}"
+                else
+                    echo "Hello $@"
+                fi
+            "#},
+            version
+        ),
+        t,
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn required_tool_version_matches() {
+    let t = TempDir::new().unwrap();
+    write_versioned_hello_script("1.2.0", &t);
+
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+            [required-tools]
+            hello = "^1.1"
+        "#})
+        .build(&p);
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello", "world"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .success()
+        .stdout_eq("Hello world\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn required_tool_version_mismatch() {
+    let t = TempDir::new().unwrap();
+    write_versioned_hello_script("1.2.0", &t);
+
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+            [required-tools]
+            hello = ">=2.0.0"
+        "#})
+        .build(&p);
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello", "world"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: `scarb-hello` version 1.2.0 is installed, which does not match `>=2.0.0`
+            help: this workspace requires `scarb-hello` in version matching `>=2.0.0`, as declared in the `[required-tools]` table of: [..]Scarb.toml
+        "#});
+}
+
+#[test]
+#[cfg(unix)]
+fn required_tool_missing() {
+    let t = TempDir::new().unwrap();
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+            [required-tools]
+            hello = "1"
+        "#})
+        .build(&p);
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello", "world"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: no such command: `hello`
+            help: this workspace requires `scarb-hello` in version matching `^1`, as declared in the `[required-tools]` table of: [..]Scarb.toml
+        "#});
+}
+
+#[test]
+#[cfg(unix)]
+fn pinned_tool_takes_precedence() {
+    let t = TempDir::new().unwrap();
+    write_simple_hello_script("hello", &t);
+
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra(indoc! {r#"
+            [tools]
+            hello = { path = "tools/hello" }
+        "#})
+        .build(&p);
+    let tool = p.child("tools/hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .build(&tool);
+    let script = tool.child("bin/scarb-hello");
+    script
+        .write_str("#!/usr/bin/env sh\necho \"Pinned $@\"\n")
+        .unwrap();
+    make_executable(script.path());
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello", "world"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Installing hello v1.0.0 ([..]hello[..])
+            [..]Installed scarb-hello to: [..]target/tools/bin
+            Pinned world
+        "#});
+
+    p.child("target/tools/bin/scarb-hello")
+        .assert(predicates::path::is_file());
+
+    // Tools already installed in their pinned versions are not installed again.
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello", "again"])
+        .env("PATH", path_with_temp_dir(&t))
+        .assert()
+        .success()
+        .stdout_eq("Pinned again\n");
+}
+
+#[test]
+fn pinned_tool_without_version() {
+    let p = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .manifest_extra("[tools]\nhello = { registry = \"https://example.com\" }\n")
+        .build(&p);
+
+    Scarb::quick_snapbox()
+        .current_dir(&p)
+        .args(["hello"])
+        .assert()
+        .failure()
+        .stdout_eq("error: tool (hello) must be pinned to an exact version\n");
+}
@@ -0,0 +1,95 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use itertools::Itertools;
+use scarb_metadata::Metadata;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+
+fn component_names(metadata: &Metadata, kind: &str) -> Vec<String> {
+    metadata
+        .compilation_units
+        .iter()
+        .find(|cu| cu.target.kind == kind)
+        .unwrap()
+        .components
+        .iter()
+        .map(|component| component.name.clone())
+        .sorted()
+        .collect()
+}
+
+#[test]
+fn dependency_used_only_in_tests() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(r#"fn f() -> felt252 { 42 }"#)
+        .build(&t.child("bar"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [target-dependencies.test]
+            bar = { path = "../bar" }
+        "#})
+        .lib_cairo(indoc! {r#"
+            fn f() -> felt252 { 1 }
+
+            #[cfg(test)]
+            mod tests {
+                fn g() -> felt252 { bar::f() }
+            }
+        "#})
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&foo)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(&foo)
+        .stdout_json::<Metadata>();
+    assert_eq!(component_names(&metadata, "lib"), vec!["core", "foo"]);
+    assert_eq!(
+        component_names(&metadata, "test"),
+        vec!["bar", "core", "foo"]
+    );
+}
+
+#[test]
+fn target_dependencies_of_dependencies_are_not_used() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [target-dependencies.starknet-contract]
+            missing = "1.0.0"
+        "#})
+        .build(&t.child("bar"));
+    let foo = t.child("foo");
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.path("../bar"))
+        .build(&foo);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&foo)
+        .assert()
+        .success();
+}
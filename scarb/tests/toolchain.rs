@@ -0,0 +1,100 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+#[cfg(unix)]
+use scarb::process::make_executable;
+use scarb_build_metadata::SCARB_VERSION;
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+/// Install a fake Scarb toolchain in the cache, which prints its arguments.
+#[cfg(unix)]
+fn install_fake_toolchain(cache_dir: &TempDir, version: &str) {
+    let toolchain = cache_dir.child(format!("toolchains/{version}"));
+    let exe = toolchain.child("bin/scarb");
+    exe.write_str(indoc! {r#"
+        #!/usr/bin/env sh
+        echo "scarb $SCARB_TOOLCHAIN: $@"
+    "#})
+        .unwrap();
+    make_executable(exe.path());
+    toolchain.child(".scarb-ok").touch().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn switch_to_pinned_toolchain() {
+    let cache_dir = TempDir::new().unwrap();
+    install_fake_toolchain(&cache_dir, "9.9.9");
+
+    let t = TempDir::new().unwrap();
+    t.child(".scarb-version").write_str("9.9.9\n").unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .args(["build", "--test"])
+        .current_dir(t.child("hello"))
+        .assert()
+        .success()
+        .stdout_eq("scarb 9.9.9: build --test\n");
+}
+
+#[test]
+fn pinned_current_version() {
+    let t = TempDir::new().unwrap();
+    t.child(".scarb-version")
+        .write_str(&format!("v{SCARB_VERSION}"))
+        .unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn pinned_toolchain_is_not_switched_twice() {
+    let t = TempDir::new().unwrap();
+    t.child(".scarb-version").write_str("9.9.9").unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_TOOLCHAIN", "9.9.9")
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn invalid_pinned_version() {
+    let t = TempDir::new().unwrap();
+    t.child(".scarb-version").write_str("latest").unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse Scarb version pinned in: [..]/.scarb-version
+
+            Caused by:
+                unexpected character 'l' while parsing major version number
+        "#});
+}
@@ -0,0 +1,246 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+
+fn project_with_dependencies(t: &TempDir) {
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .build(&t.child("baz"));
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .dep("baz", Dep.path("../baz"))
+        .build(&t.child("bar"));
+    ProjectBuilder::start()
+        .name("qux")
+        .version("1.0.0")
+        .build(&t.child("qux"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.path("../bar"))
+        .dep("baz", Dep.path("../baz"))
+        .manifest_extra(indoc! {r#"
+            [target-dependencies.test]
+            qux = { path = "../qux" }
+        "#})
+        .build(&t.child("foo"));
+}
+
+#[test]
+fn tree() {
+    let t = TempDir::new().unwrap();
+    project_with_dependencies(&t);
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        foo v0.1.0 ([..]Scarb.toml)
+        ├── bar v1.0.0 ([..]Scarb.toml)
+        │   └── baz v1.0.0 ([..]Scarb.toml)
+        ├── baz v1.0.0 ([..]Scarb.toml)
+        └── qux v1.0.0 ([..]Scarb.toml) [test]
+        "#});
+}
+
+#[test]
+fn tree_with_depth() {
+    let t = TempDir::new().unwrap();
+    project_with_dependencies(&t);
+
+    Scarb::quick_snapbox()
+        .args(["tree", "--depth", "1"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        foo v0.1.0 ([..]Scarb.toml)
+        ├── bar v1.0.0 ([..]Scarb.toml)
+        ├── baz v1.0.0 ([..]Scarb.toml)
+        └── qux v1.0.0 ([..]Scarb.toml) [test]
+        "#});
+}
+
+#[test]
+fn repeated_subtrees_are_deduplicated() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .build(&t.child("baz"));
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .dep("baz", Dep.path("../baz"))
+        .build(&t.child("bar"));
+    ProjectBuilder::start()
+        .name("qux")
+        .version("1.0.0")
+        .dep("bar", Dep.path("../bar"))
+        .build(&t.child("qux"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.path("../bar"))
+        .dep("qux", Dep.path("../qux"))
+        .build(&t.child("foo"));
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        foo v0.1.0 ([..]Scarb.toml)
+        ├── bar v1.0.0 ([..]Scarb.toml)
+        │   └── baz v1.0.0 ([..]Scarb.toml)
+        └── qux v1.0.0 ([..]Scarb.toml)
+            └── bar v1.0.0 ([..]Scarb.toml) (*)
+        "#});
+}
+
+#[test]
+fn tree_of_workspace_members() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .version("1.0.0")
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .version("1.0.0")
+        .dep("first", Dep.path("../first"))
+        .build(&t.child("second"));
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .arg("--workspace")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        first v1.0.0 ([..]Scarb.toml)
+
+        second v1.0.0 ([..]Scarb.toml)
+        └── first v1.0.0 ([..]Scarb.toml)
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["tree", "-p", "second"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        second v1.0.0 ([..]Scarb.toml)
+        └── first v1.0.0 ([..]Scarb.toml)
+        "#});
+}
+
+#[test]
+fn inverted_tree() {
+    let t = TempDir::new().unwrap();
+    project_with_dependencies(&t);
+
+    Scarb::quick_snapbox()
+        .args(["tree", "--invert", "baz"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        baz v1.0.0 ([..]Scarb.toml)
+        ├── bar v1.0.0 ([..]Scarb.toml)
+        │   └── foo v0.1.0 ([..]Scarb.toml)
+        └── foo v0.1.0 ([..]Scarb.toml)
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["tree", "-i", "qux@1.0.0"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        qux v1.0.0 ([..]Scarb.toml)
+        └── foo v0.1.0 ([..]Scarb.toml) [test]
+        "#});
+}
+
+#[test]
+fn inverted_tree_of_missing_package() {
+    let t = TempDir::new().unwrap();
+    project_with_dependencies(&t);
+
+    Scarb::quick_snapbox()
+        .args(["tree", "--invert", "baz@2.0.0"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: package `baz@2.0.0` not found in the dependency graph
+        "#});
+}
+
+#[test]
+fn duplicates() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .build(&t.child("fork_a"));
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.1.0")
+        .build(&t.child("fork_b"));
+    ProjectBuilder::start()
+        .name("baz")
+        .version("1.0.0")
+        .dep("bar_b", Dep.path("../fork_b").with("package", "bar"))
+        .build(&t.child("baz"));
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar_a", Dep.path("../fork_a").with("package", "bar"))
+        .dep("baz", Dep.path("../baz"))
+        .build(&t.child("foo"));
+
+    Scarb::quick_snapbox()
+        .args(["tree", "--duplicates"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        bar v1.0.0 ([..]Scarb.toml)
+        └── foo v0.1.0 ([..]Scarb.toml)
+
+        bar v1.1.0 ([..]Scarb.toml)
+        └── baz v1.0.0 ([..]Scarb.toml)
+            └── foo v0.1.0 ([..]Scarb.toml)
+        "#});
+}
+
+#[test]
+fn no_duplicates() {
+    let t = TempDir::new().unwrap();
+    project_with_dependencies(&t);
+
+    Scarb::quick_snapbox()
+        .args(["tree", "-d"])
+        .current_dir(t.child("foo"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        no duplicate packages found
+        "#});
+}
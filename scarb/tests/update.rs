@@ -0,0 +1,240 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+fn project(registry: &mut LocalRegistry) -> TempDir {
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("baz", "1.0.0");
+
+    let t = TempDir::new().unwrap();
+    let cache_dir = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&*registry))
+        .dep("baz", Dep.version("1").registry(&*registry))
+        .build_locked(&t, &cache_dir);
+
+    registry
+        .publish_simple("bar", "1.0.1")
+        .publish_simple("bar", "1.1.0")
+        .publish_simple("baz", "1.1.0");
+    t
+}
+
+fn assert_locked(t: &TempDir, name: &str, version: &str) {
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(format!(
+            "name = \"{name}\"\nversion = \"{version}\"\n"
+        )));
+}
+
+#[test]
+fn update_all() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+
+    Scarb::quick_snapbox()
+        .arg("update")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.1.0");
+    assert_locked(&t, "baz", "1.1.0");
+}
+
+#[test]
+fn update_single_package() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+
+    Scarb::quick_snapbox()
+        .args(["update", "-p", "bar"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.1.0");
+    assert_locked(&t, "baz", "1.0.0");
+}
+
+#[test]
+fn update_precise() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+
+    Scarb::quick_snapbox()
+        .args(["update", "-p", "bar", "--precise", "1.0.1"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.0.1");
+    assert_locked(&t, "baz", "1.0.0");
+}
+
+#[test]
+fn update_precise_not_matching_requirements() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+
+    Scarb::quick_snapbox()
+        .args(["update", "-p", "bar", "--precise", "2.0.0"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: cannot update package `bar` to version `2.0.0`, it does not match version requirements of its dependants
+        "#});
+
+    assert_locked(&t, "bar", "1.0.0");
+}
+
+#[test]
+fn update_package_not_locked() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+
+    Scarb::quick_snapbox()
+        .args(["update", "-p", "qux"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: package `qux` not found in lockfile
+        "#});
+}
+
+#[test]
+fn update_minimal_versions() {
+    let mut registry = LocalRegistry::create();
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("bar", "1.1.0");
+    registry.publish_simple("baz", "1.0.0");
+    registry.publish_simple("baz", "1.2.0");
+    registry.publish_simple("baz", "1.3.0");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .dep("baz", Dep.version("1.2").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["update", "--minimal-versions"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.0.0");
+    assert_locked(&t, "baz", "1.2.0");
+
+    // Locked versions are kept by subsequent resolutions.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.0.0");
+    assert_locked(&t, "baz", "1.2.0");
+}
+
+#[test]
+fn update_pre() {
+    let mut registry = LocalRegistry::create();
+    let t = project(&mut registry);
+    registry.publish_simple("bar", "1.2.0-rc.1");
+    registry.publish_simple("baz", "2.0.0-rc.1");
+
+    Scarb::quick_snapbox()
+        .arg("update")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.1.0");
+
+    Scarb::quick_snapbox()
+        .args(["update", "--pre"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.2.0-rc.1");
+    assert_locked(&t, "baz", "1.1.0");
+
+    // Locked prerelease versions are kept by subsequent resolutions.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.2.0-rc.1");
+}
+
+#[test]
+fn workspace_allows_prerelease() {
+    let mut registry = LocalRegistry::create();
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("bar", "1.1.0-beta.1");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .manifest_extra(indoc! {r#"
+            [workspace]
+            allow-prerelease = true
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.1.0-beta.1");
+}
+
+#[test]
+fn dependency_allows_prerelease() {
+    let mut registry = LocalRegistry::create();
+    registry.publish_simple("bar", "1.0.0");
+    registry.publish_simple("bar", "1.1.0-beta.1");
+    registry.publish_simple("baz", "1.0.0");
+    registry.publish_simple("baz", "1.1.0-beta.1");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep(
+            "bar",
+            Dep.version("1")
+                .registry(&registry)
+                .with("allow-prerelease", true),
+        )
+        .dep("baz", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_locked(&t, "bar", "1.1.0-beta.1");
+    assert_locked(&t, "baz", "1.0.0");
+}
@@ -0,0 +1,210 @@
+use std::fs;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::{formatdoc, indoc};
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::registry::local::LocalRegistry;
+
+fn registry() -> LocalRegistry {
+    let mut registry = LocalRegistry::create();
+    for (name, version) in [
+        ("bar", "1.0.0"),
+        ("bar", "1.2.0"),
+        ("bar", "2.0.0"),
+        ("baz", "1.0.0"),
+        ("baz", "1.1.0"),
+    ] {
+        registry.publish(|t| {
+            ProjectBuilder::start().name(name).version(version).build(t);
+        });
+    }
+    registry
+}
+
+fn write_package(t: &impl PathChild, manifest: &str) {
+    t.child("Scarb.toml").write_str(manifest).unwrap();
+    t.child("src/lib.cairo")
+        .write_str("fn foo() -> felt252 { 42 }")
+        .unwrap();
+}
+
+#[test]
+fn upgrade_compatible() {
+    let registry = registry();
+    let t = TempDir::new().unwrap();
+    write_package(
+        &t,
+        &formatdoc! {r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = {{ version = "1.0.0", registry = "{registry}" }} # keep me
+        "#},
+    );
+
+    Scarb::quick_snapbox()
+        .arg("upgrade")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Upgrading bar ^1.0.0 -> 1.2.0
+        [..] Unpacking bar v1.2.0 ([..])
+        "#});
+
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        formatdoc! {r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = {{ version = "1.2.0", registry = "{registry}" }} # keep me
+        "#}
+    );
+    t.child("Scarb.lock").assert(predicates::str::contains(
+        "name = \"bar\"\nversion = \"1.2.0\"\n",
+    ));
+}
+
+#[test]
+fn upgrade_incompatible_dry_run() {
+    let registry = registry();
+    let t = TempDir::new().unwrap();
+    let manifest = formatdoc! {r#"
+        [package]
+        name = "foo"
+        version = "0.1.0"
+
+        [dependencies]
+        bar = {{ version = "1.0.0", registry = "{registry}" }}
+        baz = {{ version = "~1.0", registry = "{registry}" }}
+    "#};
+    write_package(&t, &manifest);
+
+    Scarb::quick_snapbox()
+        .args(["upgrade", "--incompatible", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Upgrading bar ^1.0.0 -> 2.0.0
+        [..] Upgrading baz ~1.0 -> ~1.1.0
+        warn: aborting due to dry run
+        "#});
+
+    assert_eq!(fs::read_to_string(t.child("Scarb.toml")).unwrap(), manifest);
+}
+
+#[test]
+fn upgrade_selected_dependencies() {
+    let registry = registry();
+    let t = TempDir::new().unwrap();
+    write_package(
+        &t,
+        &formatdoc! {r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = {{ version = "1.0.0", registry = "{registry}" }}
+            baz = {{ version = "1.0.0", registry = "{registry}" }}
+        "#},
+    );
+
+    Scarb::quick_snapbox()
+        .args(["upgrade", "baz", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Upgrading baz ^1.0.0 -> 1.1.0
+        warn: aborting due to dry run
+        "#});
+}
+
+#[test]
+fn upgrade_workspace_dependency() {
+    let registry = registry();
+    let t = TempDir::new().unwrap();
+    t.child("Scarb.toml")
+        .write_str(&formatdoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+
+            [workspace.dependencies]
+            bar = {{ version = "1", registry = "{registry}" }}
+        "#})
+        .unwrap();
+    for name in ["first", "second"] {
+        write_package(
+            &t.child(name),
+            &formatdoc! {r#"
+                [package]
+                name = "{name}"
+                version = "0.1.0"
+
+                [dependencies]
+                bar.workspace = true
+            "#},
+        );
+    }
+
+    Scarb::quick_snapbox()
+        .args(["upgrade", "--workspace"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Upgrading bar ^1 -> 1.2.0
+        [..] Unpacking bar v1.2.0 ([..])
+        "#});
+
+    assert_eq!(
+        fs::read_to_string(t.child("Scarb.toml")).unwrap(),
+        formatdoc! {r#"
+            [workspace]
+            members = ["first", "second"]
+
+            [workspace.dependencies]
+            bar = {{ version = "1.2.0", registry = "{registry}" }}
+        "#}
+    );
+    assert!(fs::read_to_string(t.child("first/Scarb.toml"))
+        .unwrap()
+        .contains("bar.workspace = true"));
+}
+
+#[test]
+fn nothing_to_upgrade() {
+    let registry = registry();
+    let t = TempDir::new().unwrap();
+    write_package(
+        &t,
+        &formatdoc! {r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = {{ version = "2", registry = "{registry}" }}
+            baz = {{ version = "=1.0.0", registry = "{registry}" }}
+        "#},
+    );
+
+    Scarb::quick_snapbox()
+        .args(["upgrade", "--incompatible"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        all version requirements are up to date
+        "#});
+}
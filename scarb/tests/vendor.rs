@@ -0,0 +1,169 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::fsx::ChildPathEx;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+#[test]
+fn vendor_and_build_offline() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("vendor")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Vendoring bar v1.0.0 ([..])
+        To use vendored sources, add this to your .scarb/config.toml for this project:
+
+        [source.registry-1]
+        replace-with = "vendored-sources"
+        registry = "file://[..]"
+
+        [source.vendored-sources]
+        directory = "vendor"
+
+        "#});
+
+    t.child("vendor/bar-1.0.0/Scarb.toml")
+        .assert(predicates::path::is_file());
+    t.child("vendor/bar-1.0.0/src/lib.cairo")
+        .assert(predicates::path::is_file());
+    t.child("vendor/bar-1.0.0/.scarb-vendor.json")
+        .assert_is_json::<serde_json::Value>();
+
+    let output = Scarb::quick_snapbox()
+        .arg("vendor")
+        .current_dir(&t)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (_, config) = stdout.split_once("\n\n").unwrap();
+    t.child(".scarb/config.toml").write_str(config).unwrap();
+
+    // Vendored sources must be enough to build the project.
+    drop(registry);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling foo v0.1.0 ([..]Scarb.toml)
+        [..]  Finished release target(s) in [..]
+        "#});
+}
+
+#[test]
+fn vendor_default_registry_replacement() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+    t.child(".scarb/config.toml")
+        .write_str(&format!(
+            "[source.default]\nreplace-with = \"local\"\n\n[source.local]\nregistry = \"{registry}\"\n"
+        ))
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("vendor")
+        .arg("deps")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Vendoring bar v1.0.0 ([..])
+        To use vendored sources, add this to your .scarb/config.toml for this project:
+
+        [source.default]
+        replace-with = "vendored-sources"
+
+        [source.vendored-sources]
+        directory = "deps"
+
+        "#});
+
+    t.child("deps/bar-1.0.0/Scarb.toml")
+        .assert(predicates::path::is_file());
+}
+
+#[test]
+fn vendor_removes_stale_packages() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .build(&t);
+
+    t.child("vendor/baz-0.1.0/Scarb.toml")
+        .write_str("")
+        .unwrap();
+    t.child("vendor/baz-0.1.0/.scarb-vendor.json")
+        .write_str("{}")
+        .unwrap();
+    t.child("vendor/README.md").write_str("Hello").unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("vendor")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("vendor").tree(),
+        indoc! {r#"
+        README.md
+        bar-1.0.0/
+        . .scarb-vendor.json
+        . Scarb.orig.toml
+        . Scarb.toml
+        . VERSION
+        . src/
+        . . lib.cairo
+        "#}
+    );
+}
+
+#[test]
+fn vendor_without_registry_dependencies() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("foo").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("vendor")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        To use vendored sources, add this to your .scarb/config.toml for this project:
+
+        [source.vendored-sources]
+        directory = "vendor"
+
+        "#});
+
+    t.child("vendor").assert(predicates::path::is_dir());
+}
@@ -0,0 +1,151 @@
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::gitx;
+use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::registry::TestRegistry;
+
+fn bar(lib_cairo: &str) -> ProjectBuilder {
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.0.0")
+        .lib_cairo(lib_cairo)
+}
+
+#[test]
+fn matching_sources() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    bar("fn f() -> felt252 { 0 }").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("verify")
+        .arg("bar@1.0.0")
+        .arg("--index")
+        .arg(registry.to_string())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Verifying bar v1.0.0 (registry+file://[..])
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..]  Verified bar v1.0.0 (registry+file://[..])
+        "#});
+}
+
+#[test]
+fn modified_sources() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    bar("fn f() -> felt252 { 1 }")
+        .src("README.md", "Hello")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("verify")
+        .arg("bar@1.0.0")
+        .arg("--index")
+        .arg(registry.to_string())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Verifying bar v1.0.0 (registry+file://[..])
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        error: published package `bar v1.0.0 (registry+file://[..])` does not match package built from sources:
+            modified: src/lib.cairo
+            missing in published package: README.md
+        "#});
+}
+
+#[test]
+fn version_mismatch() {
+    let registry = LocalRegistry::with_bar();
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("verify")
+        .arg("bar@1.0.0")
+        .arg("--index")
+        .arg(registry.to_string())
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Verifying bar v1.0.0 (registry+file://[..])
+        error: package `bar` in workspace has version 1.1.0, but version 1.0.0 is being verified
+        "#});
+}
+
+#[test]
+fn sources_from_git_tag() {
+    let registry = LocalRegistry::with_bar();
+
+    let git = gitx::new("bar", |t| bar("fn f() -> felt252 { 0 }").build(&t));
+    git.tag("v1.0.0");
+    git.change_file("src/lib.cairo", "fn f() -> felt252 { 1 }");
+
+    let t = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .arg("verify")
+        .arg("bar@1.0.0")
+        .arg("--index")
+        .arg(registry.to_string())
+        .arg("--git")
+        .arg(git.url())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Verifying bar v1.0.0 (registry+file://[..])
+        [..]  Updating git repository file://[..]/bar
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..]  Verified bar v1.0.0 (registry+file://[..])
+        "#});
+}
+
+#[test]
+fn hooks_of_verified_sources_are_not_run() {
+    let project = bar("fn f() -> felt252 { 0 }").manifest_extra(indoc! {r#"
+        [hooks]
+        pre-package = "echo pwned"
+        post-package = "echo pwned"
+    "#});
+
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| project.build(t));
+
+    let git = gitx::new("bar", |t| project.build(&t));
+    git.tag("v1.0.0");
+
+    let t = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .arg("verify")
+        .arg("bar@1.0.0")
+        .arg("--index")
+        .arg(registry.to_string())
+        .arg("--git")
+        .arg(git.url())
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Verifying bar v1.0.0 (registry+file://[..])
+        [..]  Updating git repository file://[..]/bar
+        [..] Packaging bar v1.0.0 ([..])
+        [..]  Packaged [..]
+        [..]  Verified bar v1.0.0 (registry+file://[..])
+        "#});
+}
@@ -1,4 +1,4 @@
-use assert_fs::fixture::{PathChild, PathCreateDir};
+use assert_fs::prelude::*;
 use assert_fs::TempDir;
 use indoc::indoc;
 
@@ -91,3 +91,174 @@ fn unify_target_dir() {
         fsx::canonicalize(t.child("target")).unwrap()
     );
 }
+
+#[test]
+fn default_members() {
+    let t = TempDir::new().unwrap().child("test_workspace");
+    for name in ["first", "second", "examples"] {
+        ProjectBuilder::start().name(name).build(&t.child(name));
+    }
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["first", "second", "examples"]
+            default-members = ["first", "second"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling first v1.0.0 ([..]Scarb.toml)
+            [..] Compiling second v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(t.child("examples"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling examples v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["build", "--workspace"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling examples v1.0.0 ([..]Scarb.toml)
+            [..] Compiling first v1.0.0 ([..]Scarb.toml)
+            [..] Compiling second v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    let metadata = Scarb::quick_snapbox()
+        .args(["--json", "metadata", "--format-version", "1"])
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+    let default_members = metadata
+        .workspace
+        .default_members
+        .unwrap()
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(default_members.len(), 2);
+    assert!(default_members[0].starts_with("first 1.0.0"));
+    assert!(default_members[1].starts_with("second 1.0.0"));
+}
+
+#[test]
+fn default_members_must_be_members() {
+    let t = TempDir::new().unwrap().child("test_workspace");
+    for name in ["first", "second"] {
+        ProjectBuilder::start().name(name).build(&t.child(name));
+    }
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["first"]
+            default-members = ["second"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: package `[..]second` is listed in workspace `default-members` but is not a workspace member
+        "#});
+}
+
+#[test]
+fn glob_members_with_exclude() {
+    let t = TempDir::new().unwrap().child("test_workspace");
+    for name in ["first", "second", "legacy"] {
+        ProjectBuilder::start()
+            .name(name)
+            .build(&t.child("crates").child(name));
+    }
+    t.child("Scarb.toml")
+        .write_str(indoc! {r#"
+            [workspace]
+            members = ["crates/*"]
+            exclude = ["crates/legacy"]
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling first v1.0.0 ([..]Scarb.toml)
+            [..] Compiling second v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    // Excluded package is a workspace of its own.
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(t.child("crates/legacy"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling legacy v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+    t.child("crates/legacy/target/dev/legacy.sierra.json")
+        .assert(predicates::path::exists());
+}
+
+#[test]
+fn exclude_packages_from_selection() {
+    let t = TempDir::new().unwrap().child("test_workspace");
+    for name in ["first", "second", "third"] {
+        ProjectBuilder::start().name(name).build(&t.child(name));
+    }
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .add_member("third")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["build", "--exclude", "second"])
+        .current_dir(t.child("first"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling first v1.0.0 ([..]Scarb.toml)
+            [..] Compiling third v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["build", "--workspace", "--exclude", "first,third"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling second v1.0.0 ([..]Scarb.toml)
+            [..]  Finished release target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["build", "--exclude", "*"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: all selected packages are excluded by `--exclude`
+        "#});
+}
@@ -0,0 +1,144 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use indoc::indoc;
+use serde_json::json;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::fsx::ChildPathEx;
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::local::LocalRegistry;
+
+fn publish_bar(registry: &mut LocalRegistry, version: &str) {
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version(version)
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+}
+
+fn project_depending_on_bar(registry: &LocalRegistry) -> TempDir {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+    t
+}
+
+#[test]
+fn yank_and_unyank() {
+    let mut registry = LocalRegistry::create();
+    publish_bar(&mut registry, "1.0.0");
+    publish_bar(&mut registry, "1.1.0");
+
+    Scarb::quick_snapbox()
+        .args(["yank", "bar@1.0.0", "--index", &registry.url])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Yank bar v1.0.0 (registry+file://[..])
+        "#});
+
+    let records = registry
+        .t
+        .child("index/3/b/bar.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(records[0]["yanked"], json!(true));
+    assert_eq!(records[1].get("yanked"), None);
+
+    let t = project_depending_on_bar(&registry);
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(r#"version = "1.1.0""#));
+
+    Scarb::quick_snapbox()
+        .args(["yank", "bar@1.0.0", "--undo", "--index", &registry.url])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Unyank bar v1.0.0 (registry+file://[..])
+        "#});
+
+    let records = registry
+        .t
+        .child("index/3/b/bar.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(records[0].get("yanked"), None);
+}
+
+#[test]
+fn yanked_version_pinned_in_lockfile() {
+    let mut registry = LocalRegistry::create();
+    publish_bar(&mut registry, "1.0.0");
+
+    let t = project_depending_on_bar(&registry);
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args(["yank", "bar@1.0.0", "--index", &registry.url])
+        .assert()
+        .success();
+
+    // Packages which already depend on the yanked version keep working.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(r#"version = "1.0.0""#));
+
+    // But it is not selected for new dependants.
+    let t = project_depending_on_bar(&registry);
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: cannot find package bar
+        "#});
+}
+
+#[test]
+fn yank_not_published() {
+    let mut registry = LocalRegistry::create();
+    publish_bar(&mut registry, "1.0.0");
+
+    Scarb::quick_snapbox()
+        .args(["yank", "bar@2.0.0", "--index", &registry.url])
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Yank bar v2.0.0 (registry+file://[..])
+        error: failed to yank package: bar v2.0.0 (registry+file://[..])
+
+        Caused by:
+            package version is not published in this registry
+        "#});
+}
+
+#[test]
+fn invalid_spec() {
+    Scarb::quick_snapbox()
+        .args(["yank", "bar", "--index", "https://example.com/"])
+        .assert()
+        .failure()
+        .stderr_matches(indoc! {r#"
+        error: invalid value 'bar' for '<SPEC>': package version must be specified as `<NAME>@<VERSION>`, got: bar
+
+        For more information, try '--help'.
+        "#});
+}
@@ -7,5 +7,6 @@ pub mod gitx;
 pub mod manifest_edit;
 pub mod project_builder;
 pub mod registry;
+pub mod signing;
 pub mod simple_http_server;
 pub mod workspace_builder;
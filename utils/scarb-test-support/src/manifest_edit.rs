@@ -71,6 +71,11 @@ impl ManifestEditHarness {
         self
     }
 
+    pub fn env(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> Self {
+        self.cmd = self.cmd.env(key, value);
+        self
+    }
+
     pub fn path(mut self, t: ChildPath) -> Self {
         self.path = Some(t);
         self
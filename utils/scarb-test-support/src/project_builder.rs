@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use assert_fs::fixture::ChildPath;
@@ -10,6 +11,7 @@ use toml_edit::{Document, Item, Value};
 use scarb_build_metadata::CAIRO_VERSION;
 use to_version::ToVersion;
 
+use crate::command::Scarb;
 use crate::fsx::PathUtf8Ext;
 use crate::gitx::GitProject;
 
@@ -20,6 +22,8 @@ pub struct ProjectBuilder {
     name: String,
     version: Version,
     cairo_version: Option<Version>,
+    description: Option<String>,
+    license: Option<String>,
     src: HashMap<Utf8PathBuf, String>,
     deps: Vec<(String, Value)>,
     manifest_extra: String,
@@ -35,6 +39,8 @@ impl ProjectBuilder {
             name: format!("pkg{n}"),
             version: Version::new(1, n, 0),
             cairo_version: None,
+            description: None,
+            license: None,
             src: HashMap::from_iter([(
                 Utf8PathBuf::from("src/lib.cairo"),
                 format!(r#"fn f{n}() -> felt252 {{ {n} }}"#),
@@ -59,6 +65,16 @@ impl ProjectBuilder {
         self
     }
 
+    pub fn description(mut self, description: impl ToString) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn license(mut self, license: impl ToString) -> Self {
+        self.license = Some(license.to_string());
+        self
+    }
+
     pub fn src(mut self, path: impl Into<Utf8PathBuf>, source: impl ToString) -> Self {
         self.src.insert(path.into(), source.to_string());
         self
@@ -94,6 +110,12 @@ impl ProjectBuilder {
         if let Some(cairo_version) = self.cairo_version.as_ref() {
             doc["package"]["cairo-version"] = Item::Value(Value::from(cairo_version.to_string()));
         }
+        if let Some(description) = self.description.as_ref() {
+            doc["package"]["description"] = Item::Value(Value::from(description.clone()));
+        }
+        if let Some(license) = self.license.as_ref() {
+            doc["package"]["license"] = Item::Value(Value::from(license.clone()));
+        }
         doc["dependencies"] = toml_edit::table();
         for (name, dep) in &self.deps {
             doc["dependencies"][name.clone()] = Item::Value(dep.clone());
@@ -122,6 +144,18 @@ impl ProjectBuilder {
         self.just_manifest(t);
         self.just_code(t);
     }
+
+    /// Build the project, then lock its dependencies and download them to `cache_dir`, so that
+    /// later commands using the same cache print nothing about unpacking packages.
+    pub fn build_locked(&self, t: &(impl PathChild + AsRef<Path>), cache_dir: &impl AsRef<Path>) {
+        self.build(t);
+        Scarb::quick_snapbox()
+            .arg("fetch")
+            .env("SCARB_CACHE", cache_dir.as_ref())
+            .current_dir(t)
+            .assert()
+            .success();
+    }
 }
 
 pub trait DepBuilder {
@@ -150,6 +184,10 @@ pub trait DepBuilder {
     fn registry(&self, registry: impl ToString) -> DepWith<'_, Self> {
         self.with("registry", registry.to_string())
     }
+
+    fn features(&self, features: &[&str]) -> DepWith<'_, Self> {
+        self.with("features", Value::from_iter(features.iter().copied()))
+    }
 }
 
 pub struct Dep;
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use url::Url;
+
+use crate::gitx;
+use crate::registry::http::HttpRegistry;
+
+/// Registry with an index stored in a Git repository, and tarballs served over HTTP.
+///
+/// The backing [`HttpRegistry`] directory is turned into a Git repository, and each publish
+/// creates a new commit.
+pub struct GitIndexRegistry {
+    http: HttpRegistry,
+}
+
+impl GitIndexRegistry {
+    pub fn serve() -> Self {
+        let http = HttpRegistry::serve();
+        let root = http.child(".");
+        gitx::init(root.path());
+        gitx::commit(root.path());
+        Self { http }
+    }
+
+    pub fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
+        self.http.publish(f);
+        gitx::commit(self.http.child(".").path());
+        self
+    }
+
+    /// Enable this when writing tests to see what requests are being made in the test.
+    pub fn debug_log_requests(&self) {
+        self.http.debug_log_requests();
+    }
+}
+
+impl fmt::Display for GitIndexRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let url = Url::from_directory_path(self.http.child(".").path()).unwrap();
+        write!(f, "git+{url}")
+    }
+}
@@ -9,6 +9,7 @@ use serde_json::json;
 use tokio::runtime;
 
 use crate::registry::local::LocalRegistry;
+use crate::registry::TestRegistry;
 use crate::simple_http_server::SimpleHttpServer;
 
 pub struct HttpRegistry {
@@ -17,20 +18,21 @@ pub struct HttpRegistry {
 
     // This needs to be stored here so that it's dropped properly.
     server: SimpleHttpServer,
+    dl_server: Option<SimpleHttpServer>,
 }
 
+// Keep a global multi-threading runtime to contain all running servers in one shared
+// thread pool, while maintaining synchronous nature of tests.
+static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
+    runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap()
+});
+
 impl HttpRegistry {
     pub fn serve() -> Self {
-        // Keep a global multi-threading runtime to contain all running servers in one shared
-        // thread pool, while maintaining synchronous nature of tests.
-        static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
-            runtime::Builder::new_multi_thread()
-                .worker_threads(1)
-                .enable_all()
-                .build()
-                .unwrap()
-        });
-
         let local = LocalRegistry::create();
         let server = {
             let _guard = RUNTIME.enter();
@@ -38,18 +40,53 @@ impl HttpRegistry {
         };
         let url = server.url();
 
-        let config = json!({
+        let registry = Self {
+            local,
+            url,
+            server,
+            dl_server: None,
+        };
+        registry.configure(|_| {});
+        registry
+    }
+
+    /// Serve package downloads from a separate server, as if they were hosted on another domain.
+    pub fn serve_downloads_separately(&mut self) {
+        let dl_server = {
+            let _guard = RUNTIME.enter();
+            SimpleHttpServer::serve(self.local.t.path().to_owned())
+        };
+        self.dl_server = Some(dl_server);
+        self.configure(|_| {});
+    }
+
+    /// Write `config.json` of this registry, with default contents modified by `f`.
+    pub fn configure(&self, f: impl FnOnce(&mut serde_json::Value)) {
+        let url = &self.url;
+        let dl_url = self
+            .dl_server
+            .as_ref()
+            .map_or_else(|| url.clone(), SimpleHttpServer::url);
+        let mut config = json!({
             "version": 1,
-            "dl": format!("{url}{{package}}-{{version}}.tar.zst"),
+            "dl": format!("{dl_url}{{package}}-{{version}}.tar.zst"),
             "index": format!("{url}index/{{prefix}}/{{package}}.json")
         });
-        local
+        f(&mut config);
+        self.local
             .t
             .child("config.json")
             .write_str(&serde_json::to_string(&config).unwrap())
             .unwrap();
+    }
 
-        Self { local, url, server }
+    /// Advertise the `api/` directory of this registry as the API endpoint.
+    ///
+    /// The server only serves static files, so tests need to put API responses in place
+    /// themselves, for example `registry.child("api/v1/packages")`.
+    pub fn enable_api(&self) {
+        let api = format!("{}api/", self.url);
+        self.configure(|config| config["api"] = json!(api));
     }
 
     pub fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
@@ -61,6 +98,48 @@ impl HttpRegistry {
     pub fn debug_log_requests(&self) {
         self.server.log_requests(true);
     }
+
+    /// Make the registry respond to next `count` requests with a transient server error.
+    pub fn fail_next_requests(&self, count: u32) {
+        self.server.fail_next_requests(count);
+    }
+
+    /// Make the registry respond to next `count` uploads with `429 Too Many Requests`.
+    pub fn rate_limit_next_uploads(&self, count: u32) {
+        self.server.rate_limit_next_uploads(count);
+    }
+
+    /// Make the registry require authentication with given token.
+    pub fn require_token(&self, token: &str) {
+        self.server.require_token(token);
+    }
+
+    /// Make the registry require given one-time password in uploads.
+    pub fn require_otp(&self, otp: &str) {
+        self.server.require_otp(otp);
+    }
+
+    /// Number of `304 Not Modified` responses sent to conditional requests so far.
+    pub fn not_modified_responses(&self) -> u32 {
+        self.server.not_modified_responses()
+    }
+
+    /// Number of `206 Partial Content` responses sent to resumed downloads so far.
+    pub fn partial_responses(&self) -> u32 {
+        self.dl_server
+            .as_ref()
+            .unwrap_or(&self.server)
+            .partial_responses()
+    }
+
+    /// Number of download requests with `Authorization` header received by the server set up
+    /// with [`HttpRegistry::serve_downloads_separately`].
+    pub fn authorized_download_requests(&self) -> u32 {
+        self.dl_server
+            .as_ref()
+            .expect("downloads are not served separately")
+            .authorized_requests()
+    }
 }
 
 impl PathChild for HttpRegistry {
@@ -77,3 +156,13 @@ impl fmt::Display for HttpRegistry {
         fmt::Display::fmt(&self.url, f)
     }
 }
+
+impl TestRegistry for HttpRegistry {
+    fn create() -> Self {
+        HttpRegistry::serve()
+    }
+
+    fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
+        HttpRegistry::publish(self, f)
+    }
+}
@@ -4,6 +4,7 @@ use assert_fs::TempDir;
 use url::Url;
 
 use crate::command::Scarb;
+use crate::registry::TestRegistry;
 
 pub struct LocalRegistry {
     pub t: TempDir,
@@ -22,6 +23,7 @@ impl LocalRegistry {
         f(&t);
         Scarb::quick_snapbox()
             .arg("publish")
+            .arg("--no-verify")
             .arg("--index")
             .arg(&self.url)
             .current_dir(&t)
@@ -36,3 +38,13 @@ impl fmt::Display for LocalRegistry {
         fmt::Display::fmt(&self.url, f)
     }
 }
+
+impl TestRegistry for LocalRegistry {
+    fn create() -> Self {
+        LocalRegistry::create()
+    }
+
+    fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
+        LocalRegistry::publish(self, f)
+    }
+}
@@ -1,2 +1,38 @@
+use assert_fs::TempDir;
+
+use crate::project_builder::ProjectBuilder;
+
+pub mod git_index;
 pub mod http;
 pub mod local;
+pub mod object_storage;
+pub mod oci;
+
+/// Fixtures shared by registries, which tests publish packages to.
+pub trait TestRegistry: Sized {
+    /// Create an empty registry.
+    fn create() -> Self;
+
+    /// Publish the package built by `f`.
+    fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self;
+
+    /// Publish an empty package `name` in `version`.
+    fn publish_simple(&mut self, name: &str, version: &str) -> &mut Self {
+        self.publish(|t| {
+            ProjectBuilder::start().name(name).version(version).build(t);
+        })
+    }
+
+    /// Create a registry with `bar v1.0.0` published, which defines `fn f() -> felt252 { 0 }`.
+    fn with_bar() -> Self {
+        let mut registry = Self::create();
+        registry.publish(|t| {
+            ProjectBuilder::start()
+                .name("bar")
+                .version("1.0.0")
+                .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+                .build(t);
+        });
+        registry
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use once_cell::sync::Lazy;
+use tokio::runtime;
+use url::Url;
+
+use crate::command::Scarb;
+use crate::simple_http_server::SimpleHttpServer;
+
+/// Emulates an S3-compatible bucket by serving a local registry over HTTP using path-style
+/// bucket addressing.
+pub struct ObjectStorageRegistry {
+    pub t: TempDir,
+    bucket: String,
+    prefix: String,
+
+    // This needs to be stored here so that it's dropped properly.
+    server: SimpleHttpServer,
+}
+
+impl ObjectStorageRegistry {
+    pub fn serve() -> Self {
+        static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
+            runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .unwrap()
+        });
+
+        let t = TempDir::new().unwrap();
+        let bucket = "registry-bucket".to_string();
+        let prefix = "cairo/packages".to_string();
+        t.child(&bucket).child(&prefix).create_dir_all().unwrap();
+
+        let server = {
+            let _guard = RUNTIME.enter();
+            SimpleHttpServer::serve(t.path().to_owned())
+        };
+
+        Self {
+            t,
+            bucket,
+            prefix,
+            server,
+        }
+    }
+
+    pub fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
+        let t = TempDir::new().unwrap();
+        f(&t);
+        let local_url =
+            Url::from_directory_path(self.t.child(&self.bucket).child(&self.prefix)).unwrap();
+        Scarb::quick_snapbox()
+            .arg("publish")
+            .arg("--no-verify")
+            .arg("--index")
+            .arg(local_url.as_str())
+            .current_dir(&t)
+            .assert()
+            .success();
+        self
+    }
+
+    /// Value for the `AWS_ENDPOINT_URL` environment variable.
+    pub fn endpoint(&self) -> String {
+        self.server.url()
+    }
+
+    /// URL of the same bucket as if it was hosted in Google Cloud Storage, to be used along with
+    /// the `STORAGE_EMULATOR_HOST` environment variable set to [`ObjectStorageRegistry::endpoint`].
+    pub fn gcs_url(&self) -> String {
+        format!("gs://{}/{}", self.bucket, self.prefix)
+    }
+
+    /// Make the bucket require authentication with given bearer token.
+    pub fn require_token(&self, token: &str) {
+        self.server.require_token(token);
+    }
+
+    /// Number of requests with `Authorization` header received so far.
+    pub fn authorized_requests(&self) -> u32 {
+        self.server.authorized_requests()
+    }
+
+    /// Enable this when writing tests to see what requests are being made in the test.
+    pub fn debug_log_requests(&self) {
+        self.server.log_requests(true);
+    }
+}
+
+impl fmt::Display for ObjectStorageRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.prefix)
+    }
+}
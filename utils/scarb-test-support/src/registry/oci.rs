@@ -0,0 +1,165 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use data_encoding::HEXLOWER;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use sha2::digest::FixedOutput;
+use sha2::Digest;
+use tokio::runtime;
+
+use crate::registry::local::LocalRegistry;
+use crate::simple_http_server::SimpleHttpServer;
+
+/// Emulates a read-only OCI container registry by laying out artifacts of a local registry
+/// as static files matching the OCI distribution API paths.
+pub struct OciRegistry {
+    local: LocalRegistry,
+    t: TempDir,
+    namespace: String,
+
+    // This needs to be stored here so that it's dropped properly.
+    server: SimpleHttpServer,
+}
+
+impl OciRegistry {
+    pub fn serve() -> Self {
+        static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
+            runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .unwrap()
+        });
+
+        let local = LocalRegistry::create();
+        let t = TempDir::new().unwrap();
+        let namespace = "cairo/packages".to_string();
+        t.child("v2").child(&namespace).create_dir_all().unwrap();
+
+        let server = {
+            let _guard = RUNTIME.enter();
+            SimpleHttpServer::serve(t.path().to_owned())
+        };
+
+        Self {
+            local,
+            t,
+            namespace,
+            server,
+        }
+    }
+
+    pub fn publish(&mut self, f: impl FnOnce(&TempDir)) -> &mut Self {
+        self.local.publish(f);
+        self.sync();
+        self
+    }
+
+    /// Enable this when writing tests to see what requests are being made in the test.
+    pub fn debug_log_requests(&self) {
+        self.server.log_requests(true);
+    }
+
+    /// Convert all packages from the backing local registry into OCI artifacts.
+    fn sync(&self) {
+        let mut records_files = Vec::new();
+        collect_files(&self.local.t.path().join("index"), &mut records_files);
+
+        for records_file in records_files {
+            let package = records_file.file_stem().unwrap().to_str().unwrap();
+            let records = fs::read(&records_file).unwrap();
+            self.push(
+                package,
+                "index",
+                "application/vnd.scarb.index.records.v1+json",
+                &records,
+            );
+
+            let records: serde_json::Value = serde_json::from_slice(&records).unwrap();
+            for record in records.as_array().unwrap() {
+                let version = record["v"].as_str().unwrap();
+                let tarball = fs::read(
+                    self.local
+                        .t
+                        .path()
+                        .join(format!("{package}-{version}.tar.zst")),
+                )
+                .unwrap();
+                self.push(
+                    package,
+                    &version.replace('+', "_"),
+                    "application/vnd.scarb.package.tarball.v1.tar+zstd",
+                    &tarball,
+                );
+            }
+        }
+    }
+
+    fn push(&self, package: &str, tag: &str, media_type: &str, blob: &[u8]) {
+        let repository = self.t.child("v2").child(&self.namespace).child(package);
+
+        let digest = sha256(blob);
+        repository
+            .child("blobs")
+            .child(&digest)
+            .write_binary(blob)
+            .unwrap();
+
+        let manifest = json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "artifactType": "application/vnd.scarb.package.v1",
+            "config": {
+                "mediaType": "application/vnd.oci.empty.v1+json",
+                "digest": sha256(b"{}"),
+                "size": 2
+            },
+            "layers": [{
+                "mediaType": media_type,
+                "digest": digest,
+                "size": blob.len()
+            }]
+        });
+        repository
+            .child("manifests")
+            .child(tag)
+            .write_str(&serde_json::to_string(&manifest).unwrap())
+            .unwrap();
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    let mut digest = sha2::Sha256::new();
+    digest.update(bytes);
+    let digest: [u8; 32] = digest.finalize_fixed().into();
+    format!("sha256:{}", HEXLOWER.encode(&digest))
+}
+
+impl fmt::Display for OciRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let url = self.server.url();
+        let host = url
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_string();
+        write!(f, "oci://{host}/{}", self.namespace)
+    }
+}
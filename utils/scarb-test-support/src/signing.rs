@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+
+use data_encoding::BASE64;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+/// Minisign key pair for signing package tarballs in tests.
+///
+/// Produces legacy (non-prehashed) minisign signatures, which Scarb accepts as well.
+pub struct SigningKey {
+    seed: [u8; 32],
+    key_pair: Ed25519KeyPair,
+    key_id: [u8; 8],
+}
+
+impl SigningKey {
+    /// Create a deterministic key pair, different for each `seed`.
+    pub fn from_seed(seed: u8) -> Self {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+        Self {
+            seed: [seed; 32],
+            key_pair,
+            key_id: [seed; 8],
+        }
+    }
+
+    /// Base64 encoded public key, as used in `trusted-keys` registry configuration.
+    pub fn public_key(&self) -> String {
+        let mut bytes = b"Ed".to_vec();
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.key_pair.public_key().as_ref());
+        BASE64.encode(&bytes)
+    }
+
+    /// Contents of an unencrypted minisign secret key file, as created by `minisign -G -W`.
+    ///
+    /// Scarb does not validate the key checksum, so it is left zeroed.
+    pub fn secret_key(&self) -> String {
+        let mut bytes = b"Ed".to_vec();
+        // Unencrypted key (no KDF), Blake2b checksum.
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(b"B2");
+        // KDF salt, operations limit and memory limit.
+        bytes.extend_from_slice(&[0; 48]);
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(&self.seed);
+        bytes.extend_from_slice(self.key_pair.public_key().as_ref());
+        bytes.extend_from_slice(&[0; 32]);
+        format!(
+            "untrusted comment: minisign encrypted secret key\n{}\n",
+            BASE64.encode(&bytes)
+        )
+    }
+
+    /// Produce contents of a `.minisig` file with the signature of `contents`.
+    pub fn sign(&self, contents: &[u8]) -> String {
+        let signature = self.key_pair.sign(contents);
+
+        let mut signature_bytes = b"Ed".to_vec();
+        signature_bytes.extend_from_slice(&self.key_id);
+        signature_bytes.extend_from_slice(signature.as_ref());
+
+        let trusted_comment = "timestamp:0";
+        let mut global_message = signature.as_ref().to_vec();
+        global_message.extend_from_slice(trusted_comment.as_bytes());
+        let global_signature = self.key_pair.sign(&global_message);
+
+        format!(
+            "untrusted comment: signature from scarb test key\n{}\ntrusted comment: {trusted_comment}\n{}\n",
+            BASE64.encode(&signature_bytes),
+            BASE64.encode(global_signature.as_ref()),
+        )
+    }
+
+    /// Write detached signature of the file at `path` next to it.
+    pub fn sign_file(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let contents = fs::read(path).unwrap();
+        let mut signature_path = path.as_os_str().to_owned();
+        signature_path.push(".minisig");
+        fs::write(signature_path, self.sign(&contents)).unwrap();
+    }
+}
@@ -1,14 +1,14 @@
 use std::net::{SocketAddr, TcpListener};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use axum::body::Body;
 use axum::extract::State;
-use axum::http::header::ETAG;
-use axum::http::Request;
+use axum::http::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH, IF_RANGE, RANGE, RETRY_AFTER};
+use axum::http::{Method, Request, StatusCode};
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
 use axum::{middleware, Router};
 use data_encoding::HEXLOWER;
 use itertools::Itertools;
@@ -19,6 +19,13 @@ use tower_http::services::ServeDir;
 pub struct SimpleHttpServer {
     addr: SocketAddr,
     log_requests: Arc<AtomicBool>,
+    fail_requests: Arc<AtomicU32>,
+    rate_limited_uploads: Arc<AtomicU32>,
+    required_token: Arc<Mutex<Option<String>>>,
+    required_otp: Arc<Mutex<Option<String>>>,
+    not_modified: Arc<AtomicU32>,
+    authorized: Arc<AtomicU32>,
+    partial: Arc<AtomicU32>,
     ct: Option<tokio::sync::oneshot::Sender<()>>,
 }
 
@@ -27,11 +34,42 @@ impl SimpleHttpServer {
         let (ct, ctrx) = tokio::sync::oneshot::channel::<()>();
 
         let log_requests = Arc::new(AtomicBool::new(false));
+        let fail_requests = Arc::new(AtomicU32::new(0));
+        let rate_limited_uploads = Arc::new(AtomicU32::new(0));
+        let required_token = Arc::new(Mutex::new(None));
+        let required_otp = Arc::new(Mutex::new(None));
+        let not_modified = Arc::new(AtomicU32::new(0));
+        let authorized = Arc::new(AtomicU32::new(0));
+        let partial = Arc::new(AtomicU32::new(0));
 
         let app = Router::new()
-            .fallback_service(ServeDir::new(dir))
+            .fallback_service(ServeDir::new(&dir))
+            .layer(middleware::from_fn_with_state(
+                (dir, partial.clone()),
+                check_if_range,
+            ))
+            .layer(middleware::from_fn_with_state(fail_requests.clone(), fail))
+            .layer(middleware::from_fn_with_state(
+                rate_limited_uploads.clone(),
+                rate_limit_uploads,
+            ))
+            .layer(middleware::from_fn_with_state(
+                required_token.clone(),
+                authorize,
+            ))
+            .layer(middleware::from_fn_with_state(
+                authorized.clone(),
+                count_authorized,
+            ))
+            .layer(middleware::from_fn_with_state(
+                required_otp.clone(),
+                check_otp,
+            ))
             .layer(middleware::from_fn_with_state(log_requests.clone(), logger))
-            .layer(middleware::map_response(set_etag));
+            .layer(middleware::from_fn_with_state(
+                not_modified.clone(),
+                set_etag,
+            ));
 
         let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
         let addr = tcp.local_addr().unwrap();
@@ -50,6 +88,13 @@ impl SimpleHttpServer {
         Self {
             addr,
             log_requests,
+            fail_requests,
+            rate_limited_uploads,
+            required_token,
+            required_otp,
+            not_modified,
+            authorized,
+            partial,
             ct: Some(ct),
         }
     }
@@ -62,6 +107,50 @@ impl SimpleHttpServer {
     pub fn log_requests(&self, enable: bool) {
         self.log_requests.store(enable, Ordering::Relaxed);
     }
+
+    /// Respond to next `count` requests with `503 Service Unavailable`, simulating server blips.
+    pub fn fail_next_requests(&self, count: u32) {
+        self.fail_requests.store(count, Ordering::Relaxed);
+    }
+
+    /// Respond to next `count` `PUT` requests with `429 Too Many Requests`.
+    pub fn rate_limit_next_uploads(&self, count: u32) {
+        self.rate_limited_uploads.store(count, Ordering::Relaxed);
+    }
+
+    /// Respond with `401 Unauthorized` to requests without `Authorization: Bearer <token>` header.
+    pub fn require_token(&self, token: impl Into<String>) {
+        *self.required_token.lock().unwrap() = Some(token.into());
+    }
+
+    /// Respond with `401 Unauthorized` and `X-Scarb-OTP: required` header to `PUT` requests
+    /// without `X-Scarb-OTP: <otp>` header.
+    pub fn require_otp(&self, otp: impl Into<String>) {
+        *self.required_otp.lock().unwrap() = Some(otp.into());
+    }
+
+    /// Number of `304 Not Modified` responses sent to conditional requests so far.
+    pub fn not_modified_responses(&self) -> u32 {
+        self.not_modified.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests with `Authorization` header received so far.
+    pub fn authorized_requests(&self) -> u32 {
+        self.authorized.load(Ordering::Relaxed)
+    }
+
+    /// Number of `206 Partial Content` responses sent to range requests so far.
+    pub fn partial_responses(&self) -> u32 {
+        self.partial.load(Ordering::Relaxed)
+    }
+}
+
+/// Entity tag which the server sends along with `contents`.
+pub fn etag(contents: &[u8]) -> String {
+    let mut digest = sha2::Sha256::new();
+    digest.update(contents);
+    let digest: [u8; 32] = digest.finalize_fixed().into();
+    HEXLOWER.encode(&digest)
 }
 
 impl Drop for SimpleHttpServer {
@@ -107,16 +196,125 @@ async fn logger<B>(
     response
 }
 
-async fn set_etag(res: Response) -> Response<Body> {
+async fn fail<B>(
+    State(remaining): State<Arc<AtomicU32>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let should_fail = remaining
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+        .is_ok();
+    if should_fail {
+        (StatusCode::SERVICE_UNAVAILABLE, [(RETRY_AFTER, "0")]).into_response()
+    } else {
+        next.run(request).await
+    }
+}
+
+async fn rate_limit_uploads<B>(
+    State(remaining): State<Arc<AtomicU32>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let should_limit = request.method() == Method::PUT
+        && remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok();
+    if should_limit {
+        (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "0")]).into_response()
+    } else {
+        next.run(request).await
+    }
+}
+
+async fn authorize<B>(
+    State(required_token): State<Arc<Mutex<Option<String>>>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let required_token = required_token.lock().unwrap().clone();
+    if let Some(token) = required_token {
+        let authorization = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        if authorization != Some(&format!("Bearer {token}")) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn count_authorized<B>(
+    State(authorized): State<Arc<AtomicU32>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if request.headers().contains_key(AUTHORIZATION) {
+        authorized.fetch_add(1, Ordering::Relaxed);
+    }
+    next.run(request).await
+}
+
+/// Serve the whole file instead of the requested range, if `If-Range` does not match
+/// the entity tag of the file.
+async fn check_if_range<B>(
+    State((dir, partial)): State<(PathBuf, Arc<AtomicU32>)>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if let Some(if_range) = request.headers_mut().remove(IF_RANGE) {
+        let path = dir.join(request.uri().path().trim_start_matches('/'));
+        let is_fresh = std::fs::read(path).map_or(false, |contents| if_range == etag(&contents));
+        if !is_fresh {
+            request.headers_mut().remove(RANGE);
+        }
+    }
+    let response = next.run(request).await;
+    if response.status() == StatusCode::PARTIAL_CONTENT {
+        partial.fetch_add(1, Ordering::Relaxed);
+    }
+    response
+}
+
+async fn check_otp<B>(
+    State(required_otp): State<Arc<Mutex<Option<String>>>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let required_otp = required_otp.lock().unwrap().clone();
+    if let Some(otp) = required_otp {
+        let provided = request
+            .headers()
+            .get("x-scarb-otp")
+            .and_then(|value| value.to_str().ok());
+        if request.method() == Method::PUT && provided != Some(otp.as_str()) {
+            return (StatusCode::UNAUTHORIZED, [("x-scarb-otp", "required")]).into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn set_etag<B>(
+    State(not_modified): State<Arc<AtomicU32>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+    let res = next.run(request).await;
+
     let (mut parts, body) = res.into_parts();
     let bytes = hyper::body::to_bytes(body).await.unwrap();
 
-    let mut digest = sha2::Sha256::new();
-    digest.update(&bytes);
-    let digest: [u8; 32] = digest.finalize_fixed().into();
-    let digest = HEXLOWER.encode(&digest);
+    let digest = etag(&bytes);
+
+    let is_fresh = if_none_match.map_or(false, |etag| etag == digest.as_str());
+    if parts.status == StatusCode::OK && is_fresh {
+        not_modified.fetch_add(1, Ordering::Relaxed);
+        return (StatusCode::NOT_MODIFIED, [(ETAG, digest)]).into_response();
+    }
 
     parts.headers.insert(ETAG, digest.parse().unwrap());
 
-    Response::from_parts(parts, Body::from(bytes))
+    Response::from_parts(parts, Body::from(bytes)).into_response()
 }
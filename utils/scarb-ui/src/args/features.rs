@@ -0,0 +1,58 @@
+use std::ffi::OsString;
+
+const FEATURES_DELIMITER: char = ',';
+
+/// [`clap`] structured arguments that provide feature selection.
+///
+/// ## Usage
+///
+/// ```no_run
+/// # use scarb_ui::args::FeaturesSpec;
+/// #[derive(clap::Parser)]
+/// struct Args {
+///     #[command(flatten)]
+///     features: FeaturesSpec,
+/// }
+/// ```
+#[derive(clap::Parser, Clone, Debug, Default)]
+pub struct FeaturesSpec {
+    /// Comma separated list of features to activate, can be a feature of selected packages
+    /// (`foo`) or of a concrete workspace member (`package/foo`).
+    #[arg(
+        short = 'F',
+        long,
+        value_delimiter = FEATURES_DELIMITER,
+        value_name = "FEATURES",
+        env = "SCARB_FEATURES"
+    )]
+    pub features: Vec<String>,
+
+    /// Activate all available features of selected packages.
+    #[arg(long, conflicts_with = "features", env = "SCARB_ALL_FEATURES")]
+    pub all_features: bool,
+
+    /// Do not activate the `default` feature of selected packages.
+    #[arg(long, env = "SCARB_NO_DEFAULT_FEATURES")]
+    pub no_default_features: bool,
+}
+
+impl FeaturesSpec {
+    /// Get the feature selection as environment variables.
+    ///
+    /// These values can be passed to child processes, so that Scarb invocations made by them
+    /// use the same features.
+    pub fn to_env(&self) -> Vec<(&'static str, OsString)> {
+        let mut env = Vec::new();
+        if !self.features.is_empty() {
+            let features = self.features.join(FEATURES_DELIMITER.to_string().as_str());
+            env.push(("SCARB_FEATURES", features.into()));
+        }
+        if self.all_features {
+            env.push(("SCARB_ALL_FEATURES", "true".into()));
+        }
+        if self.no_default_features {
+            env.push(("SCARB_NO_DEFAULT_FEATURES", "true".into()));
+        }
+        env
+    }
+}
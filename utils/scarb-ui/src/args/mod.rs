@@ -1,5 +1,7 @@
 //! This module provides reusable [`clap`] arguments for common tasks in Scarb ecosystem.
 
+pub use features::*;
 pub use packages_filter::*;
 
+mod features;
 mod packages_filter;
@@ -37,6 +37,10 @@ pub struct PackagesFilter {
     /// Run for all packages in the workspace.
     #[arg(short, long, conflicts_with = "package")]
     workspace: bool,
+    /// Exclude packages from the selection, can be a concrete package name (`foobar`) or
+    /// a prefix glob (`foo*`). Selects all workspace members if no other packages are specified.
+    #[arg(long, value_delimiter = PACKAGES_FILTER_DELIMITER, value_name = "SPEC")]
+    exclude: Vec<String>,
 }
 
 impl PackagesFilter {
@@ -46,15 +50,18 @@ impl PackagesFilter {
     pub fn match_one<S: PackagesSource>(&self, source: &S) -> Result<S::Package> {
         let specs = self.package_specs()?;
 
-        // Check for current package.
+        // Check for default members or current package.
         // If none (in case of virtual workspace), run for all members.
-        if self.current_selected(&specs) {
-            if let Some(pkg) = self.current_package(source)? {
-                return Ok(pkg);
-            }
-        }
-
-        let members = source.members();
+        let default_selection = if self.current_selected(&specs) {
+            self.default_selection(source)?
+        } else {
+            None
+        };
+        let members = match default_selection {
+            Some(mut packages) if packages.len() == 1 => return Ok(packages.remove(0)),
+            Some(packages) => packages,
+            None => self.without_excluded::<S>(source.members())?,
+        };
 
         if (self.workspace || specs.iter().any(|spec| matches!(spec, Spec::All)))
             && members.len() > 1
@@ -87,16 +94,27 @@ impl PackagesFilter {
     pub fn match_many<S: PackagesSource>(&self, source: &S) -> Result<Vec<S::Package>> {
         let specs = self.package_specs()?;
 
-        // Check for current package.
+        // Check for default members or current package.
         // If none (in case of virtual workspace), run for all members.
         if self.current_selected(&specs) {
-            if let Some(pkg) = self.current_package(source)? {
-                return Ok(vec![pkg]);
+            if let Some(packages) = self.default_selection(source)? {
+                return Ok(packages);
             }
         }
 
         let members = source.members();
-        Self::do_match_all::<S>(specs, self.workspace, members)
+        let packages = Self::do_match_all::<S>(specs, self.workspace, members)?;
+        self.without_excluded::<S>(packages)
+    }
+
+    /// Whether neither specific packages nor the whole workspace were requested.
+    ///
+    /// In this case, [`PackagesFilter::match_many`] selects the current package, workspace default
+    /// members, or all members of a virtual workspace.
+    pub fn is_default(&self) -> bool {
+        self.package_specs()
+            .map(|specs| self.current_selected(&specs))
+            .unwrap_or(false)
     }
 
     /// Generate a new [`PackagesFilter`] for the given slice  of packages.
@@ -115,6 +133,7 @@ impl PackagesFilter {
         Self {
             package: names,
             workspace: false,
+            exclude: Vec::new(),
         }
     }
 
@@ -140,6 +159,20 @@ impl PackagesFilter {
         }
     }
 
+    /// Packages to run on when no package selection flags are given.
+    ///
+    /// When run from the workspace root, these are workspace default members, if declared.
+    /// Otherwise, this is the current package, if any.
+    fn default_selection<S: PackagesSource>(&self, source: &S) -> Result<Option<Vec<S::Package>>> {
+        if source.runtime_manifest() == source.workspace_manifest() {
+            if let Some(packages) = source.default_members() {
+                ensure!(!packages.is_empty(), "workspace has no default members");
+                return Ok(Some(packages));
+            }
+        }
+        Ok(self.current_package(source)?.map(|pkg| vec![pkg]))
+    }
+
     fn current_package<S: PackagesSource>(&self, source: &S) -> Result<Option<S::Package>> {
         Ok(source
             .members()
@@ -149,7 +182,36 @@ impl PackagesFilter {
     }
 
     fn current_selected(&self, specs: &[Spec<'_>]) -> bool {
-        !self.workspace && specs.iter().any(|spec| matches!(spec, Spec::All))
+        !self.workspace
+            && self.exclude.is_empty()
+            && specs.iter().any(|spec| matches!(spec, Spec::All))
+    }
+
+    fn without_excluded<S: PackagesSource>(
+        &self,
+        packages: Vec<S::Package>,
+    ) -> Result<Vec<S::Package>> {
+        if self.exclude.is_empty() {
+            return Ok(packages);
+        }
+        let excluded = self
+            .exclude
+            .iter()
+            .map(|s| Spec::parse(s))
+            .collect::<Result<Vec<_>>>()?;
+        let packages = packages
+            .into_iter()
+            .filter(|pkg| {
+                !excluded
+                    .iter()
+                    .any(|spec| spec.matches(S::package_name_of(pkg)))
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            !packages.is_empty(),
+            "all selected packages are excluded by `--exclude`"
+        );
+        Ok(packages)
     }
 
     fn do_match_all<S: PackagesSource>(
@@ -278,8 +340,14 @@ pub trait PackagesSource {
     #[doc(hidden)]
     fn members(&self) -> Vec<Self::Package>;
 
+    #[doc(hidden)]
+    fn default_members(&self) -> Option<Vec<Self::Package>>;
+
     #[doc(hidden)]
     fn runtime_manifest(&self) -> Utf8PathBuf;
+
+    #[doc(hidden)]
+    fn workspace_manifest(&self) -> Utf8PathBuf;
 }
 
 impl PackagesSource for Metadata {
@@ -299,6 +367,17 @@ impl PackagesSource for Metadata {
             .collect()
     }
 
+    fn default_members(&self) -> Option<Vec<Self::Package>> {
+        let default_members = self.workspace.default_members.as_ref()?;
+        Some(
+            self.packages
+                .iter()
+                .filter(|pkg| default_members.contains(&pkg.id))
+                .cloned()
+                .collect(),
+        )
+    }
+
     fn runtime_manifest(&self) -> Utf8PathBuf {
         let path = self.runtime_manifest.clone();
         if !path.as_str().is_empty() {
@@ -307,6 +386,10 @@ impl PackagesSource for Metadata {
             self.workspace.manifest_path.clone()
         }
     }
+
+    fn workspace_manifest(&self) -> Utf8PathBuf {
+        self.workspace.manifest_path.clone()
+    }
 }
 
 #[cfg(test)]
@@ -331,6 +414,7 @@ mod tests {
 
     struct MockSource {
         pub members: Vec<MockPackage>,
+        pub default_members: Option<Vec<MockPackage>>,
         pub runtime_manifest: Utf8PathBuf,
     }
 
@@ -338,10 +422,16 @@ mod tests {
         fn new(members: Vec<MockPackage>) -> Self {
             Self {
                 members,
+                default_members: None,
                 runtime_manifest: Utf8PathBuf::from("runtime/manifest"),
             }
         }
 
+        fn with_default_members(mut self, default_members: Vec<MockPackage>) -> Self {
+            self.default_members = Some(default_members);
+            self
+        }
+
         fn with_runtime_manifest(mut self, path: Utf8PathBuf) -> Self {
             self.runtime_manifest = path;
             self
@@ -359,9 +449,17 @@ mod tests {
             self.members.clone()
         }
 
+        fn default_members(&self) -> Option<Vec<Self::Package>> {
+            self.default_members.clone()
+        }
+
         fn runtime_manifest(&self) -> Utf8PathBuf {
             self.runtime_manifest.clone()
         }
+
+        fn workspace_manifest(&self) -> Utf8PathBuf {
+            Utf8PathBuf::from("runtime/manifest")
+        }
     }
 
     fn mock_package(name: &str) -> MockPackage {
@@ -389,6 +487,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["first".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         let filter = PackagesFilter::generate_for::<MockSource>(packages.iter());
@@ -398,6 +497,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["*".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         let filter = PackagesFilter::generate_for::<MockSource>(packages.iter());
@@ -412,6 +512,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["second".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         assert_eq!(packages.len(), 1);
@@ -428,6 +529,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["first".into(), "second".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         assert_eq!(packages.len(), 2);
@@ -444,6 +546,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["pack*".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         assert_eq!(packages.len(), 2);
@@ -460,6 +563,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["pack*".into(), "second".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         assert_eq!(packages.len(), 3);
@@ -475,6 +579,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["pack*".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let package = filter.match_one(&mock);
         assert!(package.is_err());
@@ -488,6 +593,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["*".into()],
             workspace: false,
+            exclude: Vec::new(),
         };
         let package = filter.match_one(&mock).unwrap();
         assert_eq!(package.name, "package_1");
@@ -502,6 +608,7 @@ mod tests {
         let filter = PackagesFilter {
             package: vec!["*".into()],
             workspace: true,
+            exclude: Vec::new(),
         };
         let packages = filter.match_many(&mock).unwrap();
         assert_eq!(packages.len(), 2);
@@ -510,4 +617,71 @@ mod tests {
             packages.into_iter().map(|p| p.name).collect(),
         );
     }
+
+    #[test]
+    fn can_select_default_members() {
+        let packages = mock_packages(vec!["package_1", "package_2", "package_3"]);
+        let mock = MockSource::new(packages.clone())
+            .with_default_members(vec![packages[0].clone(), packages[2].clone()]);
+        let filter = PackagesFilter {
+            package: vec!["*".into()],
+            workspace: false,
+            exclude: Vec::new(),
+        };
+        let found = filter.match_many(&mock).unwrap();
+        cmp_no_order(
+            vec!["package_1", "package_3"],
+            found.into_iter().map(|p| p.name).collect(),
+        );
+        assert!(filter.match_one(&mock).is_err());
+
+        let filter = PackagesFilter {
+            package: vec!["*".into()],
+            workspace: true,
+            exclude: Vec::new(),
+        };
+        assert_eq!(filter.match_many(&mock).unwrap().len(), 3);
+
+        let mock = mock.with_runtime_manifest(packages[1].manifest_path.clone());
+        let filter = PackagesFilter {
+            package: vec!["*".into()],
+            workspace: false,
+            exclude: Vec::new(),
+        };
+        let package = filter.match_one(&mock).unwrap();
+        assert_eq!(package.name, "package_2");
+    }
+
+    #[test]
+    fn can_exclude_packages() {
+        let packages = mock_packages(vec!["package_1", "package_2", "other"]);
+        let mock = MockSource::new(packages.clone())
+            .with_runtime_manifest(packages[0].manifest_path.clone());
+
+        let filter = PackagesFilter {
+            package: vec!["*".into()],
+            workspace: false,
+            exclude: vec!["package_1".into()],
+        };
+        let found = filter.match_many(&mock).unwrap();
+        cmp_no_order(
+            vec!["package_2", "other"],
+            found.into_iter().map(|p| p.name).collect(),
+        );
+
+        let filter = PackagesFilter {
+            package: vec!["*".into()],
+            workspace: true,
+            exclude: vec!["pack*".into()],
+        };
+        let package = filter.match_one(&mock).unwrap();
+        assert_eq!(package.name, "other");
+
+        let filter = PackagesFilter {
+            package: vec!["package_2".into()],
+            workspace: false,
+            exclude: vec!["pack*".into()],
+        };
+        assert!(filter.match_many(&mock).is_err());
+    }
 }
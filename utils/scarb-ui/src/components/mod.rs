@@ -2,12 +2,14 @@
 //! a [`Ui`][crate::Ui].
 
 pub use machine::*;
+pub use progress_bar::*;
 pub use spinner::*;
 pub use status::*;
 pub use typed::*;
 pub use value::*;
 
 mod machine;
+mod progress_bar;
 mod spinner;
 mod status;
 mod typed;
@@ -0,0 +1,161 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use indicatif::{ProgressDrawTarget, ProgressStyle};
+
+use crate::widget::widgets;
+use crate::Widget;
+
+/// Progress bar widget informing about an ongoing transfer of bytes, for example a download.
+///
+/// Multiple progress bars displayed at the same time are stacked one below another,
+/// followed by a summary of all of them, showing the aggregate transfer rate.
+pub struct ProgressBar {
+    message: String,
+}
+
+impl ProgressBar {
+    /// Create a new [`ProgressBar`] with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    fn default_style() -> ProgressStyle {
+        ProgressStyle::with_template(
+            "{spinner:.cyan} {wide_msg} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+        )
+        .unwrap()
+    }
+
+    fn total_style() -> ProgressStyle {
+        ProgressStyle::with_template(
+            "{spinner:.cyan} {wide_msg} {bytes}/{total_bytes} ({bytes_per_sec})",
+        )
+        .unwrap()
+    }
+}
+
+/// Summary of all progress bars displayed at the same time.
+///
+/// It is only drawn while more than one progress bar is displayed, but counts bytes of all
+/// of them, so that the transfer rate does not jump when it appears.
+struct Total {
+    pb: indicatif::ProgressBar,
+    active: usize,
+    shown: bool,
+}
+
+static TOTAL: Mutex<Option<Total>> = Mutex::new(None);
+
+/// Allows updating the state of associated [`ProgressBar`], and finishes it when dropped.
+pub struct ProgressBarHandle {
+    pb: indicatif::ProgressBar,
+    total: indicatif::ProgressBar,
+    length: AtomicU64,
+    position: AtomicU64,
+}
+
+impl ProgressBarHandle {
+    /// Set the total number of bytes to be transferred.
+    pub fn set_length(&self, length: u64) {
+        let previous = self.length.swap(length, Ordering::Relaxed);
+        self.pb.set_length(length);
+        self.total.inc_length(length.saturating_sub(previous));
+    }
+
+    /// Set the number of bytes transferred so far.
+    pub fn set_position(&self, position: u64) {
+        let previous = self.position.swap(position, Ordering::Relaxed);
+        self.pb.set_position(position);
+        self.total.inc(position.saturating_sub(previous));
+    }
+}
+
+impl Drop for ProgressBarHandle {
+    fn drop(&mut self) {
+        self.pb.finish_and_clear();
+        widgets().remove(&self.pb);
+
+        let mut total = TOTAL.lock().unwrap();
+        if let Some(state) = total.as_mut() {
+            state.active -= 1;
+            if state.active == 0 {
+                state.pb.finish_and_clear();
+                if state.shown {
+                    widgets().remove(&state.pb);
+                }
+                *total = None;
+            }
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    type Handle = ProgressBarHandle;
+
+    fn text(self) -> Self::Handle {
+        let pb = indicatif::ProgressBar::new(0)
+            .with_style(ProgressBar::default_style())
+            .with_message(self.message);
+
+        let mut total = TOTAL.lock().unwrap();
+        let state = total.get_or_insert_with(|| Total {
+            pb: indicatif::ProgressBar::with_draw_target(Some(0), ProgressDrawTarget::hidden())
+                .with_style(ProgressBar::total_style())
+                .with_message("Total"),
+            active: 0,
+            shown: false,
+        });
+        state.active += 1;
+        if state.active > 1 && !state.shown {
+            widgets().add(state.pb.clone());
+            state.shown = true;
+        }
+        let pb = if state.shown {
+            widgets().insert_before(&state.pb, pb)
+        } else {
+            widgets().add(pb)
+        };
+
+        ProgressBarHandle {
+            pb,
+            total: state.pb.clone(),
+            length: AtomicU64::new(0),
+            position: AtomicU64::new(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProgressBar, TOTAL};
+    use crate::Widget;
+
+    #[test]
+    fn total_aggregates_all_progress_bars() {
+        let total = || {
+            let total = TOTAL.lock().unwrap();
+            total
+                .as_ref()
+                .map(|total| (total.pb.position(), total.pb.length(), total.shown))
+        };
+
+        let first = ProgressBar::new("first").text();
+        first.set_length(100);
+        first.set_position(40);
+        assert_eq!(total(), Some((40, Some(100), false)));
+
+        let second = ProgressBar::new("second").text();
+        second.set_length(50);
+        second.set_position(10);
+        second.set_position(30);
+        assert_eq!(total(), Some((70, Some(150), true)));
+
+        drop(first);
+        assert_eq!(total(), Some((70, Some(150), true)));
+        drop(second);
+        assert_eq!(total(), None);
+    }
+}
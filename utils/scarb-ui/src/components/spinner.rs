@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::widget::widgets;
 use crate::Widget;
 
 /// Spinner widget informing about an ongoing process.
@@ -29,7 +30,8 @@ pub struct SpinnerHandle {
 
 impl Drop for SpinnerHandle {
     fn drop(&mut self) {
-        self.pb.finish_and_clear()
+        self.pb.finish_and_clear();
+        widgets().remove(&self.pb);
     }
 }
 
@@ -40,6 +42,7 @@ impl Widget for Spinner {
         let pb = ProgressBar::new_spinner()
             .with_style(Spinner::default_style())
             .with_message(self.message);
+        let pb = widgets().add(pb);
         pb.enable_steady_tick(Duration::from_millis(120));
         SpinnerHandle { pb }
     }
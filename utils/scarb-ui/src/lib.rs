@@ -32,6 +32,7 @@ pub use verbosity::*;
 pub use widget::*;
 
 use crate::components::TypedMessage;
+use crate::widget::suspend_widgets;
 
 pub mod args;
 pub mod components;
@@ -124,7 +125,7 @@ impl Ui {
 
     fn do_print<T: Message>(&self, message: T) {
         match self.output_format {
-            OutputFormat::Text => message.print_text(),
+            OutputFormat::Text => suspend_widgets(|| message.print_text()),
             OutputFormat::Json => message.print_json(),
         }
     }
@@ -1,3 +1,7 @@
+use std::sync::OnceLock;
+
+use indicatif::MultiProgress;
+
 /// A persistent message that is only usable for humans, for example a spinner.
 pub trait Widget {
     /// Allows for live interaction with the widget, and its drop is called when the widget should
@@ -7,3 +11,17 @@ pub trait Widget {
     /// Display the widget on the standard output, and return a handle for further interaction.
     fn text(self) -> Self::Handle;
 }
+
+/// All widgets displayed at the same time are drawn together, stacked one below another.
+///
+/// Messages are printed while widgets are hidden, see [`suspend_widgets`], so that they do not
+/// interleave with widgets being redrawn.
+pub(crate) fn widgets() -> &'static MultiProgress {
+    static WIDGETS: OnceLock<MultiProgress> = OnceLock::new();
+    WIDGETS.get_or_init(MultiProgress::new)
+}
+
+/// Hide displayed widgets for the duration of `f`, and draw them again afterwards.
+pub(crate) fn suspend_widgets<R>(f: impl FnOnce() -> R) -> R {
+    widgets().suspend(f)
+}
@@ -47,14 +47,18 @@ const sidebar = {
       items: [
         p("Compilation model", "/docs/reference/compilation-model"),
         p("Conditional compilation", "/docs/reference/conditional-compilation"),
+        p("Configuration", "/docs/reference/configuration"),
+        p("Features", "/docs/reference/features"),
         p("Global directories", "/docs/reference/global-directories"),
         p("Manifest", "/docs/reference/manifest"),
         p("Lockfile", "/docs/reference/lockfile"),
         p("Workspaces", "/docs/reference/workspaces"),
         p("Profiles", "/docs/reference/profiles"),
         p("Scripts", "/docs/reference/scripts"),
+        p("Security advisories", "/docs/reference/advisories"),
         p("Specifying dependencies", "/docs/reference/specifying-dependencies"),
         p("Targets", "/docs/reference/targets"),
+        p("Toolchains", "/docs/reference/toolchains"),
       ],
     },
     {
@@ -62,6 +66,7 @@ const sidebar = {
       items: [
         p("JSON output", "/docs/writing-extensions/json-output"),
         p("Scarb metadata", "/docs/writing-extensions/scarb-metadata"),
+        p("JSON-RPC interface", "/docs/writing-extensions/rpc"),
         p("Subcommands", "/docs/writing-extensions/subcommands"),
         p("Scarb crate", "/docs/writing-extensions/scarb-crate"),
       ],